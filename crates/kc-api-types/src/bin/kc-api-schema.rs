@@ -0,0 +1,134 @@
+//! Writes a JSON Schema (draft 2020-12) bundle of every wire type, for
+//! consumers outside Rust to generate clients from and validate payloads
+//! against.
+//!
+//! ```text
+//! cargo run -p kc-api-types --features schema --bin kc-api-schema [OUT]
+//! ```
+//!
+//! Each type is a definition under `$defs`, named after the Rust type. The
+//! v2 types that share a name with a v1 type carry a `V2` suffix. Without
+//! `OUT` the bundle goes to stdout.
+
+use kc_api_types::{v1, v2, webhook};
+use schemars::JsonSchema;
+use schemars::generate::{SchemaGenerator, SchemaSettings};
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+
+fn add<T: JsonSchema>(generator: &mut SchemaGenerator) {
+    generator.subschema_for::<T>();
+}
+
+fn main() -> io::Result<()> {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+
+    add::<kc_api_types::SignPurpose>(&mut generator);
+    add::<kc_api_types::WalletAddress>(&mut generator);
+    add::<kc_api_types::ChainId>(&mut generator);
+    add::<kc_api_types::AssetSymbol>(&mut generator);
+    add::<kc_api_types::ErrorCode>(&mut generator);
+    add::<kc_api_types::ApiError>(&mut generator);
+    add::<kc_api_types::PageQuery>(&mut generator);
+
+    add::<v1::WalletCreateRequest>(&mut generator);
+    add::<v1::WalletCreateResponse>(&mut generator);
+    add::<v1::WalletSummary>(&mut generator);
+    add::<v1::WalletListResponse>(&mut generator);
+    add::<v1::WalletSignRequest>(&mut generator);
+    add::<v1::WalletSignResponse>(&mut generator);
+    add::<v1::WalletBalanceResponse>(&mut generator);
+    add::<v1::WalletBatchBalanceRequest>(&mut generator);
+    add::<v1::WalletBatchBalanceResponse>(&mut generator);
+    add::<v1::WalletPortfolioResponse>(&mut generator);
+    add::<v1::WalletActivityRequest>(&mut generator);
+    add::<v1::WalletActivityResponse>(&mut generator);
+    add::<v1::WalletChainTxsResponse>(&mut generator);
+    add::<v1::WalletTxHistoryResponse>(&mut generator);
+    add::<v1::WalletSubmitRequest>(&mut generator);
+    add::<v1::WalletSubmitSignedRequest>(&mut generator);
+    add::<v1::WalletSubmitResponse>(&mut generator);
+    add::<v1::TransactionEnvelope>(&mut generator);
+    add::<v1::WalletDecryptMemoRequest>(&mut generator);
+    add::<v1::WalletDecryptMemoResponse>(&mut generator);
+    add::<v1::WalletFeeEstimateRequest>(&mut generator);
+    add::<v1::WalletFeeEstimateResponse>(&mut generator);
+    add::<v1::WalletNonceResponse>(&mut generator);
+    add::<v1::WalletNonceReservationResponse>(&mut generator);
+    add::<v1::WalletTxStatusResponse>(&mut generator);
+    add::<v1::AuthChallengeRequest>(&mut generator);
+    add::<v1::AuthChallengeResponse>(&mut generator);
+    add::<v1::AuthVerifyRequest>(&mut generator);
+    add::<v1::AuthVerifyResponse>(&mut generator);
+    add::<v1::WalletSessionToken>(&mut generator);
+    add::<v1::AuthBindRequest>(&mut generator);
+    add::<v1::AuthBindResponse>(&mut generator);
+    add::<v1::AuthSessionListResponse>(&mut generator);
+    add::<v1::AuthSessionRevokeResponse>(&mut generator);
+    add::<v1::ConditionalTransferCreateRequest>(&mut generator);
+    add::<v1::ConditionalTransferApproveRequest>(&mut generator);
+    add::<v1::ConditionalTransferCancelRequest>(&mut generator);
+    add::<v1::ConditionalTransferResponse>(&mut generator);
+    add::<v1::ConditionalTransferListResponse>(&mut generator);
+    add::<v1::TransferApprovalDecideRequest>(&mut generator);
+    add::<v1::TransferApprovalResponse>(&mut generator);
+    add::<v1::TransferApprovalListResponse>(&mut generator);
+    add::<v1::WalletBridgeRequest>(&mut generator);
+    add::<v1::WalletBridgeResponse>(&mut generator);
+    add::<v1::SigningGrantCreateRequest>(&mut generator);
+    add::<v1::SigningGrantRevokeRequest>(&mut generator);
+    add::<v1::SigningGrantResponse>(&mut generator);
+    add::<v1::WalletSessionKeyRequest>(&mut generator);
+    add::<v1::WalletSessionKeyResponse>(&mut generator);
+    add::<v1::WalletRotateKeyRequest>(&mut generator);
+    add::<v1::WalletRotateKeyResponse>(&mut generator);
+    add::<v1::DeviceRegisterRequest>(&mut generator);
+    add::<v1::DeviceApproveRequest>(&mut generator);
+    add::<v1::OpsDeviceApproveRequest>(&mut generator);
+    add::<v1::DeviceListResponse>(&mut generator);
+    add::<v1::ProofCortexCommitmentRequest>(&mut generator);
+    add::<v1::ProofCortexCommitmentResponse>(&mut generator);
+    add::<v1::ProofVerifyRequest>(&mut generator);
+    add::<v1::ProofVerifyResponse>(&mut generator);
+    add::<v1::FortressDigitalWalletStatusRequest>(&mut generator);
+    add::<v1::FortressDigitalWalletStatusResponse>(&mut generator);
+    add::<v1::ChainConfigResponse>(&mut generator);
+    add::<v1::WalletRestoreRequest>(&mut generator);
+    add::<v1::WalletRestoreResponse>(&mut generator);
+    add::<v1::WalletWatchRequest>(&mut generator);
+    add::<v1::WalletWatchBatchRequest>(&mut generator);
+    add::<v1::WalletWatchBatchResponse>(&mut generator);
+    add::<v1::WalletImportPublicRequest>(&mut generator);
+    add::<v1::WalletRenameRequest>(&mut generator);
+    add::<v1::WalletRenameResponse>(&mut generator);
+    add::<v1::WalletDeleteRequest>(&mut generator);
+    add::<v1::WalletDeleteResponse>(&mut generator);
+    add::<v1::DeviceLinkRequest>(&mut generator);
+    add::<v1::DeviceLinkResponse>(&mut generator);
+    add::<v1::DeviceUnlinkRequest>(&mut generator);
+    add::<v1::DeviceUnlinkResponse>(&mut generator);
+    add::<v1::WalletLookupRequest>(&mut generator);
+    add::<v1::WalletLookupResponse>(&mut generator);
+
+    add::<v2::WalletBalanceResponse>(&mut generator);
+    add::<v2::ApiError>(&mut generator);
+    add::<v2::WalletListResponse>(&mut generator);
+    add::<v2::WalletTxHistoryResponse>(&mut generator);
+
+    add::<webhook::WebhookEnvelope>(&mut generator);
+    add::<webhook::WebhookEvent>(&mut generator);
+
+    let bundle = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "KeyCortex API types",
+        "$defs": generator.take_definitions(true),
+    });
+    let mut text = serde_json::to_string_pretty(&bundle)?;
+    text.push('\n');
+    match env::args_os().nth(1) {
+        Some(path) => fs::write(path, text),
+        None => io::stdout().write_all(text.as_bytes()),
+    }
+}
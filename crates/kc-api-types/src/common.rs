@@ -0,0 +1,512 @@
+//! Types every API version shares: identifiers, amounts, signing purposes
+//! and the error body.
+
+use serde::{Deserialize, Serialize};
+
+/// What a signature is for; selects the domain tag it is made under.
+///
+/// On the wire this is a plain string. Anything other than the built-in
+/// names parses as [`SignPurpose::Custom`], which signers only accept when
+/// the chain config registers it (`ChainDomainTags::custom_purposes`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(from = "String", into = "String")]
+pub enum SignPurpose {
+    Transaction,
+    Auth,
+    Proof,
+    /// Integrator-defined purpose such as `delegation` or `session`.
+    Custom(String),
+}
+
+impl SignPurpose {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Transaction => "transaction",
+            Self::Auth => "auth",
+            Self::Proof => "proof",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl From<String> for SignPurpose {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "transaction" => Self::Transaction,
+            "auth" => Self::Auth,
+            "proof" => Self::Proof,
+            _ => Self::Custom(value),
+        }
+    }
+}
+
+impl From<SignPurpose> for String {
+    fn from(value: SignPurpose) -> Self {
+        match value {
+            SignPurpose::Custom(name) => name,
+            builtin => builtin.as_str().to_owned(),
+        }
+    }
+}
+
+/// A wallet as chains address it: KeyCortex and EVM `0x` addresses of 20
+/// bytes, or bech32 for Cosmos and Bitcoin chains.
+///
+/// Parsing checks the shape only; whether a bech32 address is for the right
+/// chain, and its checksum, is left to that chain's adapter.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletAddress(pub String);
+
+/// A chain slug such as `flowcortex-l1` or `ethereum-sepolia`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChainId(pub String);
+
+/// An asset ticker such as `PROOF`, `FloweR` or `USDC.e`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AssetSymbol(pub String);
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn is_hex_address(value: &str) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|digits| digits.len() == 40 && digits.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// `hrp1data` with a lowercase prefix and at least the six checksum
+/// characters, in one case throughout.
+fn is_bech32_address(value: &str) -> bool {
+    let mixed_case = value.bytes().any(|b| b.is_ascii_lowercase())
+        && value.bytes().any(|b| b.is_ascii_uppercase());
+    if value.len() > 90 || mixed_case {
+        return false;
+    }
+    let value = value.to_ascii_lowercase();
+    let Some((hrp, data)) = value.rsplit_once('1') else {
+        return false;
+    };
+    !hrp.is_empty()
+        && hrp.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        && data.len() >= 6
+        && data.chars().all(|c| BECH32_CHARSET.contains(c))
+}
+
+fn is_chain_slug(value: &str) -> bool {
+    value.len() <= 64
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+fn is_asset_symbol(value: &str) -> bool {
+    value.len() <= 32
+        && value.starts_with(|c: char| c.is_ascii_alphabetic())
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"._-".contains(&b))
+}
+
+/// Check `value` is present before its format, so blank fields read as
+/// missing rather than malformed.
+fn validate_identifier(
+    value: &str,
+    valid: impl Fn(&str) -> bool,
+    malformed: IdentifierError,
+) -> Result<(), IdentifierError> {
+    if value.trim().is_empty() {
+        return Err(IdentifierError::Empty);
+    }
+    if !valid(value) {
+        return Err(malformed);
+    }
+    Ok(())
+}
+
+impl TryFrom<String> for WalletAddress {
+    type Error = IdentifierError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        validate_identifier(
+            &value,
+            |value| is_hex_address(value) || is_bech32_address(value),
+            IdentifierError::WalletAddress,
+        )?;
+        Ok(Self(value))
+    }
+}
+
+impl TryFrom<String> for ChainId {
+    type Error = IdentifierError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        validate_identifier(&value, is_chain_slug, IdentifierError::ChainId)?;
+        Ok(Self(value))
+    }
+}
+
+impl TryFrom<String> for AssetSymbol {
+    type Error = IdentifierError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        validate_identifier(&value, is_asset_symbol, IdentifierError::AssetSymbol)?;
+        Ok(Self(value))
+    }
+}
+
+impl std::str::FromStr for WalletAddress {
+    type Err = IdentifierError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value.to_owned())
+    }
+}
+
+impl std::fmt::Display for WalletAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for ChainId {
+    type Err = IdentifierError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value.to_owned())
+    }
+}
+
+impl std::fmt::Display for ChainId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for AssetSymbol {
+    type Err = IdentifierError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value.to_owned())
+    }
+}
+
+impl std::fmt::Display for AssetSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Why a [`WalletAddress`], [`ChainId`] or [`AssetSymbol`] did not parse.
+///
+/// Displays as a predicate for the field name to lead, e.g. `to is
+/// required`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierError {
+    Empty,
+    WalletAddress,
+    ChainId,
+    AssetSymbol,
+}
+
+impl std::fmt::Display for IdentifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Empty => "is required",
+            Self::WalletAddress => "must be a 0x address of 40 hex digits or a bech32 address",
+            Self::ChainId => "must be 1-64 characters of a-z, 0-9 and '-'",
+            Self::AssetSymbol => {
+                "must be 1-32 letters, digits, '.', '-' or '_', starting with a letter"
+            }
+        })
+    }
+}
+
+impl std::error::Error for IdentifierError {}
+
+/// An amount of an asset in base units, with the decimals that place the
+/// point (18 for PROOF: `1500000000000000000` is 1.5 PROOF).
+///
+/// The JSON API keeps amounts as decimal strings of base units; handlers
+/// turn them into an `Amount` with [`Amount::parse`] before anything reaches
+/// a chain adapter, so a bad amount is a 400 rather than a zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount {
+    base_units: u128,
+    decimals: u8,
+}
+
+impl Amount {
+    pub const fn new(base_units: u128, decimals: u8) -> Self {
+        Self {
+            base_units,
+            decimals,
+        }
+    }
+
+    /// Parse a base-unit string for an asset with `decimals`, rejecting
+    /// anything above [`Amount::max_base_units`].
+    pub fn parse(amount: &str, decimals: u8) -> Result<Self, AmountError> {
+        let base_units = Self::parse_base_units(amount)?;
+        if base_units > Self::max_base_units(decimals) {
+            return Err(AmountError::AboveMaximum { decimals });
+        }
+        Ok(Self::new(base_units, decimals))
+    }
+
+    /// Parse a non-negative integer of base units, without a per-asset
+    /// limit.
+    pub fn parse_base_units(amount: &str) -> Result<u128, AmountError> {
+        let digits = amount.trim();
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AmountError::NotAnInteger);
+        }
+        digits.parse().map_err(|_| AmountError::Overflow)
+    }
+
+    /// Largest amount accepted for an asset with `decimals`: `u64::MAX` whole
+    /// tokens plus any fraction, saturating at `u128::MAX`.
+    pub fn max_base_units(decimals: u8) -> u128 {
+        10u128
+            .checked_pow(u32::from(decimals))
+            .and_then(|scale| scale.checked_mul(u128::from(u64::MAX) + 1))
+            .map_or(u128::MAX, |limit| limit - 1)
+    }
+
+    pub const fn base_units(self) -> u128 {
+        self.base_units
+    }
+
+    pub const fn decimals(self) -> u8 {
+        self.decimals
+    }
+}
+
+/// Base units, as the JSON API carries them.
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.base_units)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountError {
+    NotAnInteger,
+    Overflow,
+    AboveMaximum { decimals: u8 },
+}
+
+impl std::fmt::Display for AmountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAnInteger => f.write_str("amount must be a non-negative integer"),
+            Self::Overflow => f.write_str("amount overflows 128 bits"),
+            Self::AboveMaximum { decimals } => {
+                write!(f, "amount exceeds the maximum for an asset with {decimals} decimals")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AmountError {}
+
+/// Machine-readable reason a request failed, for clients to branch on
+/// instead of matching messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request is malformed or fails validation; fix it before resending.
+    InvalidRequest,
+    /// The nonce is already used. Fetch `GET /wallet/nonce` and sign again.
+    NonceConflict,
+    /// Missing or invalid credentials or signature.
+    Unauthorized,
+    /// The caller may not do this, e.g. to a watch-only wallet.
+    Forbidden,
+    NotFound,
+    /// The request's deadline passed before the chain or storage answered.
+    Timeout,
+    /// A chain node or other dependency failed.
+    Upstream,
+    /// A tenant quota is used up, e.g. its wallet limit.
+    QuotaExceeded,
+    /// A request with the same `Idempotency-Key` is still in flight.
+    IdempotencyConflict,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the same request may succeed if sent again unchanged.
+    pub const fn retryable(self) -> bool {
+        matches!(self, Self::Timeout | Self::Upstream | Self::IdempotencyConflict)
+    }
+}
+
+/// Body of every error response.
+///
+/// The message goes out as `error`, the field clients read before codes
+/// existed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ApiError {
+    pub code: ErrorCode,
+    #[serde(rename = "error")]
+    pub message: String,
+    pub retryable: bool,
+    /// Machine-readable context, e.g. `field` naming the request field at
+    /// fault.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub details: std::collections::BTreeMap<String, String>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retryable: code.retryable(),
+            details: Default::default(),
+        }
+    }
+
+    pub fn with_detail(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.details.insert(key.to_owned(), value.into());
+        self
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// One page of a listing, in the listing's order.
+///
+/// Pass `next_cursor` back as `cursor` for the page after; it is absent on
+/// the last page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "{T}Page"))]
+#[non_exhaustive]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// About how many items the whole listing holds, where that is cheap to
+    /// know; absent otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_estimate: Option<u64>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<String>) -> Self {
+        Self {
+            items,
+            next_cursor,
+            total_estimate: None,
+        }
+    }
+
+    pub fn with_total_estimate(mut self, total: u64) -> Self {
+        self.total_estimate = Some(total);
+        self
+    }
+}
+
+/// `cursor` and `limit` query parameters of a paginated listing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PageQuery {
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl PageQuery {
+    /// The cursor without surrounding whitespace; `None` when blank.
+    pub fn start(&self) -> Option<&str> {
+        self.cursor
+            .as_deref()
+            .map(str::trim)
+            .filter(|cursor| !cursor.is_empty())
+    }
+
+    /// `limit`, or `default` without one, kept within `1..=max`.
+    pub fn limit_or(&self, default: usize, max: usize) -> usize {
+        self.limit.unwrap_or(default).clamp(1, max)
+    }
+}
+
+/// Request metadata a client sends as headers on any call.
+///
+/// Build it with [`RequestMeta::from_headers`] from whatever header map the
+/// server or client uses; blank headers count as absent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestMeta {
+    /// `Idempotency-Key`: a retried mutating request with the same key gets
+    /// the first response back instead of running again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// `X-Client-Request-Id`: the client's own correlation id, echoed on the
+    /// response to every mutating request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_request_id: Option<String>,
+    /// `Origin`, as the browser sent it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+impl RequestMeta {
+    pub const IDEMPOTENCY_KEY_HEADER: &'static str = "idempotency-key";
+    pub const CLIENT_REQUEST_ID_HEADER: &'static str = "x-client-request-id";
+    pub const ORIGIN_HEADER: &'static str = "origin";
+    /// Longest idempotency key or client request id accepted.
+    pub const MAX_ID_LEN: usize = 128;
+
+    /// Read the metadata through `header`, which returns the value of a
+    /// header by its lowercase name, or `None` when it is missing or not
+    /// text.
+    pub fn from_headers<'a>(
+        header: impl Fn(&'static str) -> Option<&'a str>,
+    ) -> Result<Self, RequestMetaError> {
+        let value = |name| header(name).map(str::trim).filter(|value| !value.is_empty());
+        let id = |name| match value(name) {
+            Some(id) if id.len() > Self::MAX_ID_LEN || !id.bytes().all(|b| b.is_ascii_graphic()) => {
+                Err(RequestMetaError { header: name })
+            }
+            id => Ok(id.map(ToOwned::to_owned)),
+        };
+        Ok(Self {
+            idempotency_key: id(Self::IDEMPOTENCY_KEY_HEADER)?,
+            client_request_id: id(Self::CLIENT_REQUEST_ID_HEADER)?,
+            origin: value(Self::ORIGIN_HEADER).map(ToOwned::to_owned),
+        })
+    }
+}
+
+/// An idempotency key or client request id that is too long or not plain
+/// ASCII.
+///
+/// Displays as a predicate for the header name to lead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestMetaError {
+    pub header: &'static str,
+}
+
+impl std::fmt::Display for RequestMetaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "must be at most {} visible ASCII characters without spaces",
+            RequestMeta::MAX_ID_LEN
+        )
+    }
+}
+
+impl std::error::Error for RequestMetaError {}
@@ -0,0 +1,41 @@
+//! Conversions between API versions.
+//!
+//! Only downgrades that lose nothing a v1 client reads are offered, plus
+//! the error body, which is the same in both versions apart from its
+//! message key.
+
+use crate::{v1, v2};
+
+/// v1 carries only the base units; its clients know each asset's decimals.
+impl From<v2::WalletBalanceResponse> for v1::WalletBalanceResponse {
+    fn from(response: v2::WalletBalanceResponse) -> Self {
+        Self {
+            wallet_address: response.wallet_address,
+            chain: response.chain,
+            asset: response.asset,
+            amount: response.amount.base_units,
+        }
+    }
+}
+
+impl From<crate::ApiError> for v2::ApiError {
+    fn from(error: crate::ApiError) -> Self {
+        Self {
+            code: error.code,
+            message: error.message,
+            retryable: error.retryable,
+            details: error.details,
+        }
+    }
+}
+
+impl From<v2::ApiError> for crate::ApiError {
+    fn from(error: v2::ApiError) -> Self {
+        Self {
+            code: error.code,
+            message: error.message,
+            retryable: error.retryable,
+            details: error.details,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Lowercase hex, shared by every crate that puts bytes on the wire.
+//!
+//! Encode and decode are branch-free over the input bytes so they are safe
+//! to use on key material and MACs.
+
+/// Lowercase hex encoding.
+pub fn to_hex(input: &[u8]) -> String {
+    let mut output = Vec::with_capacity(input.len() * 2);
+    for byte in input {
+        output.push(encode_nibble(byte >> 4));
+        output.push(encode_nibble(byte & 0x0f));
+    }
+    // Every byte pushed above is ASCII.
+    String::from_utf8(output).expect("hex output is ascii")
+}
+
+/// Decode hex (either case). Timing depends only on the input length.
+pub fn from_hex(input: &str) -> Result<Vec<u8>, HexError> {
+    let bytes = input.as_bytes();
+    if bytes.len() % 2 != 0 {
+        return Err(HexError::OddLength);
+    }
+
+    let mut output = Vec::with_capacity(bytes.len() / 2);
+    let mut invalid = 0i16;
+    for pair in bytes.chunks_exact(2) {
+        let high = decode_nibble(pair[0]);
+        let low = decode_nibble(pair[1]);
+        invalid |= high | low;
+        output.push(((high << 4) | low) as u8);
+    }
+    if invalid < 0 {
+        return Err(HexError::InvalidCharacter);
+    }
+    Ok(output)
+}
+
+/// Why [`from_hex`] rejected its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexError {
+    OddLength,
+    InvalidCharacter,
+}
+
+impl std::fmt::Display for HexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::OddLength => "hex input length must be even",
+            Self::InvalidCharacter => "invalid hex character",
+        })
+    }
+}
+
+impl std::error::Error for HexError {}
+
+fn encode_nibble(nibble: u8) -> u8 {
+    let n = i16::from(nibble);
+    // Adds the '0'..'9' offset, plus the gap to 'a' when n > 9.
+    (n + 0x30 + (((9 - n) >> 8) & 0x27)) as u8
+}
+
+/// Returns the nibble value, or -1 for a non-hex character.
+fn decode_nibble(c: u8) -> i16 {
+    let c = i16::from(c);
+    // ((lo - 1 - c) & (c - hi - 1)) >> 8 is -1 when lo <= c <= hi, else 0.
+    let digit = ((0x2f - c) & (c - 0x3a)) >> 8;
+    let lower = ((0x60 - c) & (c - 0x67)) >> 8;
+    let upper = ((0x40 - c) & (c - 0x47)) >> 8;
+    -1 + (digit & (c - 0x30 + 1)) + (lower & (c - 0x61 + 11)) + (upper & (c - 0x41 + 11))
+}
@@ -0,0 +1,1366 @@
+//! The v1 wire types, as frozen in the v0.1 contract and served on the
+//! unprefixed routes. The WASM UI and external SDKs are pinned to these, so
+//! fields are only ever added here, never renamed or retyped.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Amount, AssetSymbol, ChainId, IdentifierError, SignPurpose, WalletAddress};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletCreateRequest {
+    pub label: Option<String>,
+    pub passphrase: Option<String>,
+    /// Unique device fingerprint (generated by the frontend, stored in localStorage).
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Contact info (email or phone) associated with this device.
+    #[serde(default)]
+    pub contact_info: Option<String>,
+    /// Owner email for identity-based wallet lookup.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Owner phone for identity-based wallet lookup.
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Bank identifier for institutional wallet lookup.
+    #[serde(default)]
+    pub bank_id: Option<String>,
+}
+
+/// A public key in a form two people can read to each other to confirm
+/// they mean the same key; see `kc_crypto::fingerprint`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct KeyFingerprint {
+    /// Eight words, space-separated, e.g. `"amber otter quill ..."`.
+    pub words: String,
+    /// The same 64 bits as four groups of five digits, e.g.
+    /// `"04213-88107-31960-55202"`.
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletCreateResponse {
+    pub wallet_address: String,
+    pub public_key: String,
+    pub chain: String,
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_fingerprint: Option<KeyFingerprint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSummary {
+    pub wallet_address: String,
+    pub chain: String,
+    pub bound_user_id: Option<String>,
+    pub public_key: Option<String>,
+    /// Fingerprint of `public_key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_fingerprint: Option<KeyFingerprint>,
+    pub label: Option<String>,
+    /// Device that created or owns this wallet (populated when device_id filter is used).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    /// Linked email identity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Linked phone identity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    /// Linked bank identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bank_id: Option<String>,
+    /// `false` for watch-only entries, which cannot sign or submit.
+    #[serde(default = "default_custodied")]
+    pub custodied: bool,
+    /// `true` when the key is held outside KeyCortex; submit needs `signed_payload`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub external_key: bool,
+    /// When the wallet was created or registered; absent for wallets older
+    /// than wallet metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at_epoch_ms: Option<u128>,
+    /// `ed25519`, `watch-only`, or an imported key's type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_scheme: Option<String>,
+    /// Signatures KeyCortex has made with the wallet's key.
+    #[serde(default)]
+    pub signature_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_signed_at_epoch_ms: Option<u128>,
+    /// Last transfer submitted from the wallet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_submitted_at_epoch_ms: Option<u128>,
+}
+
+fn default_custodied() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletListResponse {
+    pub wallets: Vec<WalletSummary>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSignRequest {
+    pub wallet_address: String,
+    pub payload: String,
+    pub purpose: SignPurpose,
+    /// Sign with this session key of the wallet instead of the wallet key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_key_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSignResponse {
+    pub signature: String,
+    /// Set when a session key signed: verify `signature` against its public
+    /// key, and the attestation against the wallet key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_key: Option<SessionKeyAttestation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBalanceResponse {
+    pub wallet_address: String,
+    pub chain: String,
+    pub asset: String,
+    pub amount: String,
+}
+
+/// `POST /wallet/balances` — balances for several wallets in one call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBatchBalanceRequest {
+    pub wallet_addresses: Vec<String>,
+    /// Defaults to every enabled asset.
+    #[serde(default)]
+    pub assets: Vec<String>,
+    #[serde(default)]
+    pub chain: Option<String>,
+}
+
+/// One wallet/asset pair; `error` is set instead of `amount` when the chain
+/// lookup failed, so one bad wallet does not fail the batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBalanceEntry {
+    pub wallet_address: String,
+    pub asset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AssetTotal {
+    pub asset: String,
+    pub amount: String,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBatchBalanceResponse {
+    pub chain: String,
+    pub balances: Vec<WalletBalanceEntry>,
+    /// Sum of the successful entries per asset, in base units.
+    pub totals: Vec<AssetTotal>,
+}
+
+/// One asset a wallet holds on one chain, in base units.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AssetHolding {
+    pub asset: String,
+    pub chain: String,
+    pub amount: String,
+    pub decimals: u8,
+}
+
+impl AssetHolding {
+    pub fn new(asset: String, chain: String, amount: Amount) -> Self {
+        Self {
+            asset,
+            chain,
+            amount: amount.base_units().to_string(),
+            decimals: amount.decimals(),
+        }
+    }
+}
+
+/// Everything a wallet holds across chains, as of one moment: the balance
+/// tab and the summary read this instead of one balance call per asset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletPortfolioResponse {
+    pub wallet_address: String,
+    pub holdings: Vec<AssetHolding>,
+    pub as_of_epoch_ms: u128,
+    /// Assets left out of `holdings` because their chain failed or timed out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<HoldingError>,
+}
+
+/// An asset whose balance could not be fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HoldingError {
+    pub asset: String,
+    pub chain: String,
+    pub error: String,
+}
+
+/// `POST /wallet/activity` — recent transfers sent from any of the wallets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletActivityRequest {
+    pub wallet_addresses: Vec<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletActivityEntry {
+    pub tx_hash: String,
+    pub status: String,
+    pub chain: String,
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    pub amount: String,
+    pub submitted_at_epoch_ms: u128,
+    /// Hex of the note sealed to `to`, if the sender attached one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_memo: Option<String>,
+}
+
+/// A transfer read from the chain by `GET /wallet/txs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletChainTxEntry {
+    pub tx_hash: String,
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    pub amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    /// Whether KeyCortex has its own record of submitting this transfer.
+    pub submitted_by_keycortex: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletChainTxsResponse {
+    pub wallet_address: String,
+    pub chain: String,
+    pub transactions: Vec<WalletChainTxEntry>,
+    /// Pass as `cursor` for the next page; absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletActivityResponse {
+    pub activity: Vec<WalletActivityEntry>,
+}
+
+/// One page of `GET /wallet/{address}/transactions`, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletTxHistoryResponse {
+    pub wallet_address: String,
+    pub transactions: Vec<WalletActivityEntry>,
+    /// Pass as `cursor` for the next page; absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSubmitRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+    pub nonce: u64,
+    /// Hex signature over the canonical transfer payload, produced outside
+    /// KeyCortex. Required for external-key wallets, rejected otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_payload: Option<String>,
+    /// Expiry bound into a v2 envelope signature (see [`TransactionEnvelope`]).
+    /// Server-signed transfers default to five minutes from now; external
+    /// signatures without it are verified as v1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_epoch_ms: Option<u128>,
+    /// Note for the recipient, sealed to its wallet key before it is stored.
+    /// Not part of the signed payload and never sent to the chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Validate and sign, then ask the chain what it would do instead of
+    /// broadcasting. Nothing is recorded and the nonce stays free.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+/// `POST /wallet/{address}/decrypt` — open a memo sealed to the wallet,
+/// either by the transfer it was attached to or as raw hex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletDecryptMemoRequest {
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    #[serde(default)]
+    pub ciphertext: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletDecryptMemoResponse {
+    pub wallet_address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    pub memo: String,
+}
+
+/// `POST /wallet/fee-estimate` — the fee a transfer would cost, before it
+/// is signed or submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletFeeEstimateRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletFeeEstimateResponse {
+    pub chain: String,
+    pub asset: String,
+    pub amount: String,
+    /// Base units of `fee_asset`.
+    pub fee: String,
+    pub fee_asset: String,
+}
+
+/// A transfer as signed with purpose `transaction`.
+///
+/// [`TxEnvelopeV1::canonical`] is the exact text signers sign and verifiers
+/// check:
+///
+/// `from={from};to={to};amount={amount};asset={asset};chain={chain};nonce={nonce}`
+///
+/// Fields always come in that order with nothing between them. `amount` (in
+/// base units) and `nonce` are plain decimal integers: no sign, no leading
+/// zeros, no whitespace. The identifiers are validated, so none of them can
+/// contain `;` or `=`. [`TxEnvelopeV1::parse`] accepts only text that
+/// re-serializes to itself, so every signable transfer has one encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxEnvelopeV1 {
+    pub from: WalletAddress,
+    pub to: WalletAddress,
+    pub amount: u128,
+    pub asset: AssetSymbol,
+    pub chain: ChainId,
+    pub nonce: u64,
+}
+
+impl TxEnvelopeV1 {
+    /// The envelope for a submit request. `amount` may carry leading zeros
+    /// or surrounding whitespace; it is signed in canonical form.
+    pub fn from_request(request: &WalletSubmitRequest) -> Result<Self, TxEnvelopeError> {
+        Ok(Self {
+            from: parse_identifier("from", &request.from)?,
+            to: parse_identifier("to", &request.to)?,
+            amount: Amount::parse_base_units(&request.amount)
+                .map_err(|_| TxEnvelopeError::Integer { field: "amount" })?,
+            asset: parse_identifier("asset", &request.asset)?,
+            chain: parse_identifier("chain", &request.chain)?,
+            nonce: request.nonce,
+        })
+    }
+
+    pub fn canonical(&self) -> String {
+        format!(
+            "from={};to={};amount={};asset={};chain={};nonce={}",
+            self.from, self.to, self.amount, self.asset, self.chain, self.nonce
+        )
+    }
+
+    pub fn parse(payload: &str) -> Result<Self, TxEnvelopeError> {
+        let mut parts = payload.split(';');
+        let mut field = |name: &'static str| {
+            parts
+                .next()
+                .and_then(|part| part.strip_prefix(name))
+                .and_then(|part| part.strip_prefix('='))
+                .ok_or(TxEnvelopeError::Malformed)
+        };
+        let envelope = Self {
+            from: parse_identifier("from", field("from")?)?,
+            to: parse_identifier("to", field("to")?)?,
+            amount: canonical_integer("amount", field("amount")?)?,
+            asset: parse_identifier("asset", field("asset")?)?,
+            chain: parse_identifier("chain", field("chain")?)?,
+            nonce: canonical_integer("nonce", field("nonce")?)?,
+        };
+        if parts.next().is_some() || envelope.canonical() != payload {
+            return Err(TxEnvelopeError::Malformed);
+        }
+        Ok(envelope)
+    }
+}
+
+fn parse_identifier<T>(field: &'static str, value: &str) -> Result<T, TxEnvelopeError>
+where
+    T: TryFrom<String, Error = IdentifierError>,
+{
+    T::try_from(value.to_owned()).map_err(|error| TxEnvelopeError::Identifier { field, error })
+}
+
+fn canonical_integer<T: std::str::FromStr>(field: &'static str, value: &str) -> Result<T, TxEnvelopeError> {
+    let canonical = !value.is_empty()
+        && value.bytes().all(|b| b.is_ascii_digit())
+        && (value == "0" || !value.starts_with('0'));
+    canonical
+        .then(|| value.parse().ok())
+        .flatten()
+        .ok_or(TxEnvelopeError::Integer { field })
+}
+
+/// Why a [`TxEnvelopeV1`] could not be built or parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxEnvelopeError {
+    /// Fields missing, out of order or followed by more text.
+    Malformed,
+    Identifier {
+        field: &'static str,
+        error: IdentifierError,
+    },
+    /// Not a decimal integer without leading zeros, or out of range.
+    Integer { field: &'static str },
+}
+
+impl std::fmt::Display for TxEnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed => {
+                f.write_str("payload must be from=..;to=..;amount=..;asset=..;chain=..;nonce=..")
+            }
+            Self::Identifier { field, error } => write!(f, "{field} {error}"),
+            Self::Integer { field } => {
+                write!(f, "{field} must be a decimal integer without leading zeros")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TxEnvelopeError {}
+
+/// Replay context bound into a v2 transaction signature.
+///
+/// The signed bytes are
+/// `{domain_tag}#v2;chain={chain_id};nonce={nonce};expires_at={expires_at_epoch_ms}:{payload}`,
+/// so a signature is only valid on one chain, for one nonce, until it expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransactionEnvelope {
+    pub chain_id: String,
+    pub nonce: u64,
+    pub expires_at_epoch_ms: u128,
+}
+
+/// A transfer signed outside KeyCortex, submitted via `/wallet/submit-signed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSubmitSignedRequest {
+    /// Canonical payload: [`TxEnvelopeV1::canonical`].
+    pub payload: String,
+    /// Hex Ed25519 signature over `payload` with purpose `transaction`.
+    pub signature: String,
+    /// Hex public key of the signing key.
+    pub public_key: String,
+    /// Set when `signature` is a v2 envelope signature; chain and nonce come
+    /// from `payload`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_epoch_ms: Option<u128>,
+    /// See [`WalletSubmitRequest::memo`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSubmitResponse {
+    pub accepted: bool,
+    pub tx_hash: String,
+    pub signature: String,
+    /// Envelope expiry when `signature` is a v2 envelope signature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_epoch_ms: Option<u128>,
+    /// Set for a `dry_run` submit, which broadcasts nothing: `accepted` is
+    /// then false and `tx_hash` empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SubmitSimulation>,
+    /// Set when the source wallet's signing policy holds the transfer for
+    /// approval (see `GET /wallet/approvals`): nothing was signed or
+    /// broadcast, so `accepted` is false and `tx_hash` and `signature` are
+    /// empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_id: Option<String>,
+}
+
+/// What the chain would do with a `dry_run` submit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SubmitSimulation {
+    pub would_accept: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Fee in base units of `fee_asset`; absent when the chain cannot
+    /// estimate fees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_asset: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletNonceResponse {
+    pub wallet_address: String,
+    pub last_nonce: u64,
+    pub next_nonce: u64,
+    /// `none`, `sequential`, `chain_queried` or `recent_blockhash`.
+    #[serde(default)]
+    pub nonce_strategy: String,
+    /// Block hash to reference, for `recent_blockhash` chains.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recent_blockhash: Option<String>,
+}
+
+/// A nonce held for an external signer until `expires_at_epoch_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletNonceReservationResponse {
+    pub wallet_address: String,
+    pub nonce: u64,
+    pub reservation_id: String,
+    pub expires_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletTxStatusResponse {
+    pub tx_hash: String,
+    pub status: String,
+    pub accepted: bool,
+    pub chain: String,
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    pub amount: String,
+    pub submitted_at_epoch_ms: u128,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u64>,
+    /// Statuses seen so far, oldest first.
+    #[serde(default)]
+    pub status_history: Vec<WalletTxStatusChange>,
+    /// Explorer page for this transaction, when the chain has a template configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletTxStatusChange {
+    pub status: String,
+    pub at_epoch_ms: u128,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthChallengeRequest {
+    /// Wallet that will sign; the response then carries a sign-in message for it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthChallengeResponse {
+    pub challenge: String,
+    pub expires_in: u64,
+    /// Sign-in message to sign instead of the bare challenge, when the
+    /// request named a wallet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthVerifyRequest {
+    pub wallet_address: String,
+    pub signature: String,
+    pub challenge: String,
+    /// The sign-in message `signature` is over, exactly as issued; without
+    /// it the signature is over `challenge`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthVerifyResponse {
+    pub valid: bool,
+    pub wallet_address: String,
+    pub verified_at_epoch_ms: u128,
+    /// Wallet session, when the signature was valid.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<WalletSessionToken>,
+}
+
+/// A wallet session token, sent back in `X-Wallet-Session`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSessionToken {
+    pub token: String,
+    /// The token's `jti`; revoke it with `POST /auth/sessions/{session_id}/revoke`.
+    pub session_id: String,
+    pub wallet_address: String,
+    pub verified_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthBindRequest {
+    pub wallet_address: String,
+    pub chain: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthBindResponse {
+    pub bound: bool,
+    pub user_id: String,
+    pub wallet_address: String,
+    pub chain: String,
+    pub bound_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthSessionSummary {
+    pub session_id: String,
+    pub user_id: String,
+    pub issued_at_epoch_ms: Option<u128>,
+    pub expires_at_epoch_ms: Option<u128>,
+    pub last_seen_epoch_ms: u128,
+    pub revoked: bool,
+    pub revoked_at_epoch_ms: Option<u128>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthSessionListResponse {
+    pub user_id: String,
+    pub sessions: Vec<AuthSessionSummary>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuthSessionRevokeResponse {
+    pub session_id: String,
+    pub revoked: bool,
+    pub revoked_at_epoch_ms: Option<u128>,
+}
+
+// --- Conditional (escrow) transfer types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConditionalTransferCreateRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+    /// Wallet whose signature over the transfer digest releases the funds.
+    pub approver_wallet: String,
+    pub expires_in_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConditionalTransferApproveRequest {
+    /// Approver signature (hex, purpose `transaction`) over the ASCII `digest`.
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConditionalTransferCancelRequest {
+    /// Sender or approver wallet.
+    pub wallet_address: String,
+    /// Signature (hex, purpose `transaction`) over `cancel:{digest}`.
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConditionalTransferResponse {
+    pub transfer_id: String,
+    /// `pending`, `releasing`, `released`, `cancelled`, `expired` or
+    /// `failed`.
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+    pub approver_wallet: String,
+    pub digest: String,
+    pub created_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+    pub resolved_at_epoch_ms: Option<u128>,
+    /// On-chain tx hash once released.
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConditionalTransferListResponse {
+    pub transfers: Vec<ConditionalTransferResponse>,
+    pub total: usize,
+}
+
+// --- Transfer approval (two-person rule) types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransferApprovalDecideRequest {
+    /// `true` signs and submits the transfer, `false` drops it.
+    pub approve: bool,
+}
+
+/// A transfer held because it is over its wallet's `approval_above` limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransferApprovalResponse {
+    pub approval_id: String,
+    /// `pending`, `rejected`, `submitted` or `failed`; `approved` while an
+    /// approved transfer is being submitted.
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+    pub reason: String,
+    /// Principal that submitted the transfer, when the submit carried a
+    /// bearer token. That principal may not decide it.
+    pub requested_by: Option<String>,
+    pub requested_at_epoch_ms: u128,
+    pub decided_by: Option<String>,
+    pub decided_at_epoch_ms: Option<u128>,
+    /// On-chain tx hash once submitted.
+    pub tx_hash: Option<String>,
+    /// Why an approved transfer could not be submitted.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransferApprovalListResponse {
+    /// Newest first.
+    pub approvals: Vec<TransferApprovalResponse>,
+}
+
+// --- Bridge (cross-chain) transfer types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBridgeRequest {
+    /// Custodied wallet on `source_chain` that is debited.
+    pub from: String,
+    /// Wallet on `target_chain` that is credited.
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub source_chain: String,
+    pub target_chain: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBridgeResponse {
+    pub bridge_id: String,
+    /// `pending`, `source_submitted`, `source_confirmed`, `target_submitted`,
+    /// `completed` or `failed`.
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub source_chain: String,
+    pub target_chain: String,
+    pub source_tx_hash: Option<String>,
+    pub target_tx_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at_epoch_ms: u128,
+    pub updated_at_epoch_ms: u128,
+    pub status_history: Vec<WalletTxStatusChange>,
+}
+
+// --- Delegated signing grant types ---
+
+/// Authority a grantor wallet gives another subject to sign on its behalf,
+/// within the listed purposes, an amount cap and an expiry.
+///
+/// The grantor signs [`SigningGrant::canonical_payload`] with the custom
+/// purpose `delegation`; `signature` is that signature in hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SigningGrant {
+    pub grantor_wallet: String,
+    /// AuthBuddy user id, wallet address or service principal that may sign.
+    pub grantee_subject: String,
+    /// At least one; `transaction`, `auth`, `proof` or a custom purpose.
+    pub purposes: Vec<SignPurpose>,
+    /// Most a single transaction signed under the grant may move, in base
+    /// units. `None` leaves transactions uncapped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<String>,
+    pub expires_at_epoch_ms: u128,
+    pub signature: String,
+}
+
+impl SigningGrant {
+    /// Purpose the grantor signs a grant with.
+    pub const SIGN_PURPOSE: &'static str = "delegation";
+
+    /// The text the grantor signs:
+    /// `grant:grantor={grantor_wallet};grantee={grantee_subject};purposes={p1,p2};max_amount={max_amount};expires_at={expires_at_epoch_ms}`,
+    /// with purposes in the order given and `max_amount` empty when uncapped.
+    pub fn canonical_payload(&self) -> String {
+        let purposes: Vec<&str> = self.purposes.iter().map(SignPurpose::as_str).collect();
+        format!(
+            "grant:grantor={};grantee={};purposes={};max_amount={};expires_at={}",
+            self.grantor_wallet,
+            self.grantee_subject,
+            purposes.join(","),
+            self.max_amount.as_deref().unwrap_or_default(),
+            self.expires_at_epoch_ms
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SigningGrantCreateRequest {
+    pub grant: SigningGrant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SigningGrantRevokeRequest {
+    /// The grantor wallet.
+    pub wallet_address: String,
+    /// Signature (hex, purpose `delegation`) over `revoke:{grant_id}`.
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SigningGrantResponse {
+    pub grant_id: String,
+    /// `active`, `revoked` or `expired`.
+    pub status: String,
+    pub grant: SigningGrant,
+    pub created_at_epoch_ms: u128,
+    pub revoked_at_epoch_ms: Option<u128>,
+}
+
+// --- Session key types ---
+
+/// A short-lived key a wallet delegates some of its signing to, so frequent
+/// low-value signatures such as auth challenges never use the wallet key.
+///
+/// The wallet key signs [`SessionKeyAttestation::canonical_payload`] with
+/// the custom purpose `delegation`; `signature` is that signature in hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SessionKeyAttestation {
+    pub wallet_address: String,
+    /// Hex Ed25519 public key of the session key.
+    pub session_public_key: String,
+    /// What the session key may sign for; never `transaction`.
+    pub purposes: Vec<SignPurpose>,
+    pub issued_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+    pub signature: String,
+}
+
+impl SessionKeyAttestation {
+    /// Purpose the wallet key signs an attestation with.
+    pub const SIGN_PURPOSE: &'static str = "delegation";
+
+    /// The text the wallet key signs:
+    /// `session-key:wallet={wallet_address};key={session_public_key};purposes={p1,p2};issued_at={issued_at_epoch_ms};expires_at={expires_at_epoch_ms}`,
+    /// with purposes in the order given.
+    pub fn canonical_payload(&self) -> String {
+        let purposes: Vec<&str> = self.purposes.iter().map(SignPurpose::as_str).collect();
+        format!(
+            "session-key:wallet={};key={};purposes={};issued_at={};expires_at={}",
+            self.wallet_address,
+            self.session_public_key,
+            purposes.join(","),
+            self.issued_at_epoch_ms,
+            self.expires_at_epoch_ms
+        )
+    }
+}
+
+/// `POST /wallet/session-key` — issue a session key for a custodied wallet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSessionKeyRequest {
+    pub wallet_address: String,
+    /// At least one; `transaction` is refused.
+    pub purposes: Vec<SignPurpose>,
+    /// Defaults to one hour; at most 24 hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletSessionKeyResponse {
+    /// Pass as `session_key_id` to `POST /wallet/sign`.
+    pub session_key_id: String,
+    pub attestation: SessionKeyAttestation,
+}
+
+// --- Key rotation types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletRotateKeyRequest {
+    pub wallet_address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletRotateKeyResponse {
+    pub wallet_address: String,
+    pub key_version: u32,
+    pub previous_public_key: String,
+    pub public_key: String,
+    /// Fingerprint of the new `public_key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_fingerprint: Option<KeyFingerprint>,
+    /// Hex signature by the previous key over
+    /// `key-rotation:{wallet_address}:{key_version}:{public_key}` (purpose `proof`).
+    pub linkage_signature: String,
+    pub rotated_at_epoch_ms: u128,
+}
+
+// --- Device trust types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceRegisterRequest {
+    /// Fingerprint generated by the frontend (see `WalletCreateRequest::device_id`).
+    pub device_id: String,
+    pub label: Option<String>,
+    /// Hex Ed25519 key the device signs trusted-device proofs with.
+    /// Registering a different key for a device drops its trust.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceApproveRequest {
+    /// Wallet bound to the caller that signs `{challenge}:device-approve:{device_id}`.
+    pub wallet_address: String,
+    pub challenge: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpsDeviceApproveRequest {
+    pub user_id: String,
+    pub device_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceSummary {
+    pub device_id: String,
+    pub user_id: String,
+    pub label: Option<String>,
+    pub first_seen_epoch_ms: u128,
+    pub last_seen_epoch_ms: u128,
+    pub trusted: bool,
+    pub approved_at_epoch_ms: Option<u128>,
+    pub approved_via: Option<String>,
+    pub revoked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceListResponse {
+    pub user_id: String,
+    pub devices: Vec<DeviceSummary>,
+    pub total: usize,
+}
+
+// --- ProofCortex types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProofCortexCommitmentRequest {
+    pub wallet_address: String,
+    pub challenge: String,
+    pub verification_result: bool,
+    pub chain: String,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProofCortexCommitmentResponse {
+    pub commitment: String,
+    pub wallet_address: String,
+    pub chain: String,
+    pub verification_result: bool,
+    pub domain_separator: String,
+    pub proof_input_schema_version: String,
+    pub generated_at_epoch_ms: u128,
+}
+
+/// A proof receipt from ProofCortex for a commitment this service issued.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProofVerifyRequest {
+    /// `commitment` from a [`ProofCortexCommitmentResponse`].
+    pub commitment: String,
+    /// Hex-encoded STARK proof receipt.
+    pub proof_blob: String,
+    /// ProofCortex circuit the proof was generated for.
+    pub circuit_id: String,
+}
+
+/// Outcome of checking a [`ProofVerifyRequest`], as recorded in the audit
+/// trail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProofVerifyResponse {
+    pub commitment: String,
+    pub circuit_id: String,
+    pub verified: bool,
+    /// Why the proof was rejected; unset when `verified`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    pub verified_at_epoch_ms: u128,
+}
+
+// --- FortressDigital enhanced types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FortressDigitalWalletStatusRequest {
+    pub wallet_address: String,
+    pub chain: String,
+    pub user_id: Option<String>,
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FortressDigitalWalletStatusResponse {
+    pub wallet_address: String,
+    pub chain: String,
+    pub wallet_exists: bool,
+    pub binding_status: WalletBindingStatus,
+    pub key_type: String,
+    pub last_verification_epoch_ms: Option<u128>,
+    pub signature_frequency_hint: String,
+    pub risk_signals: Vec<String>,
+    /// Signatures KeyCortex has made with the wallet's key.
+    #[serde(default)]
+    pub signature_count: u64,
+    #[serde(default)]
+    pub last_signed_at_epoch_ms: Option<u128>,
+    #[serde(default)]
+    pub last_submitted_at_epoch_ms: Option<u128>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletBindingStatus {
+    pub bound: bool,
+    pub user_id: Option<String>,
+    pub last_verified_epoch_ms: Option<u128>,
+}
+
+// --- Chain config types ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChainConfigResponse {
+    pub chain_slug: String,
+    pub chain_id_numeric: Option<u64>,
+    pub signature_scheme: String,
+    pub address_scheme: String,
+    pub domains: ChainDomainTags,
+    pub assets: Vec<ChainAssetInfo>,
+    pub finality_rule: String,
+    pub environment: String,
+    pub explorer: ChainExplorerTemplates,
+}
+
+/// Block-explorer deep-link templates for a chain. Each is unset when the
+/// deployment has no explorer for that kind of page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChainExplorerTemplates {
+    /// Transaction page, with a `{tx_hash}` placeholder.
+    pub tx_url: Option<String>,
+    /// Account page, with an `{address}` placeholder.
+    pub address_url: Option<String>,
+    /// Block page, with a `{height}` placeholder.
+    pub block_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChainDomainTags {
+    pub tx_domain_tag: String,
+    pub auth_domain_tag: String,
+    pub proof_domain_tag: String,
+    /// Registered [`SignPurpose::Custom`] names, each signed under
+    /// `{namespace}:{version}:custom.{name}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_purposes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChainAssetInfo {
+    pub symbol: String,
+    pub asset_type: String,
+    pub decimals: u8,
+    pub fee_payment_support: bool,
+    /// Token contract address; absent for the chain's own assets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletRestoreRequest {
+    pub passphrase: String,
+    pub label: Option<String>,
+    /// Unique device fingerprint.
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Contact info (email or phone) associated with this device.
+    #[serde(default)]
+    pub contact_info: Option<String>,
+    /// Owner email for identity-based wallet lookup.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Owner phone for identity-based wallet lookup.
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Bank identifier for institutional wallet lookup.
+    #[serde(default)]
+    pub bank_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletRestoreResponse {
+    pub wallet_address: String,
+    pub public_key: String,
+    pub chain: String,
+    pub label: Option<String>,
+    pub already_existed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_fingerprint: Option<KeyFingerprint>,
+}
+
+/// Track an external address for balance/history without holding its key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletWatchRequest {
+    pub wallet_address: String,
+    /// Defaults to `flowcortex-l1`.
+    #[serde(default)]
+    pub chain: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletWatchBatchRequest {
+    pub wallets: Vec<WalletWatchRequest>,
+    /// Applied to rows that do not set their own `device_id`.
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletWatchBatchResult {
+    /// Zero-based index into the request's `wallets`.
+    pub row: usize,
+    pub wallet_address: String,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletWatchBatchResponse {
+    pub registered: usize,
+    pub failed: usize,
+    pub results: Vec<WalletWatchBatchResult>,
+}
+
+/// Register a wallet by public key only; its secret key stays with the caller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletImportPublicRequest {
+    /// Hex-encoded public key.
+    pub public_key: String,
+    /// Defaults to `ed25519`, currently the only supported type.
+    #[serde(default)]
+    pub key_type: Option<String>,
+    /// Defaults to `flowcortex-l1`.
+    #[serde(default)]
+    pub chain: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletRenameRequest {
+    pub wallet_address: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletRenameResponse {
+    pub wallet_address: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletDeleteRequest {
+    pub wallet_address: String,
+    /// Recorded on the tombstone and the `wallet_delete` audit event.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletDeleteResponse {
+    pub wallet_address: String,
+    pub deleted: bool,
+    /// `/ops/wallets/undelete` can restore the wallet until this time.
+    pub purge_after_epoch_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceLinkRequest {
+    pub device_id: String,
+    pub wallet_address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceLinkResponse {
+    pub device_id: String,
+    pub wallet_address: String,
+    pub linked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceUnlinkRequest {
+    pub device_id: String,
+    pub wallet_address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceUnlinkResponse {
+    pub device_id: String,
+    pub wallet_address: String,
+    pub unlinked: bool,
+}
+
+// ─── Wallet identity lookup types ───
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletLookupRequest {
+    /// Find wallets by owner email.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Find wallets by owner phone.
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Find wallets by bank identifier.
+    #[serde(default)]
+    pub bank_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletLookupResponse {
+    pub wallets: Vec<WalletSummary>,
+    pub total: usize,
+    pub matched_by: String,
+}
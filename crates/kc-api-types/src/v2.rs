@@ -0,0 +1,100 @@
+//! The v2 wire types, served under `/v2`.
+//!
+//! v2 carries amounts with their decimals and puts error messages under
+//! `message`. Response structs are `#[non_exhaustive]` so fields can be
+//! added without breaking SDKs; build them with their constructors.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{Amount, AmountError, ErrorCode, Page, WalletActivityEntry, WalletSummary};
+
+/// `GET /v2/wallet/list`, in address order.
+pub type WalletListResponse = Page<WalletSummary>;
+
+/// `GET /v2/wallet/{address}/transactions`, newest first.
+pub type WalletTxHistoryResponse = Page<WalletActivityEntry>;
+
+/// An amount with the decimals that place its point. Base units stay a
+/// decimal string, as JSON numbers lose precision past 2^53.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TypedAmount {
+    pub base_units: String,
+    pub decimals: u8,
+}
+
+impl From<Amount> for TypedAmount {
+    fn from(amount: Amount) -> Self {
+        Self {
+            base_units: amount.base_units().to_string(),
+            decimals: amount.decimals(),
+        }
+    }
+}
+
+impl TryFrom<&TypedAmount> for Amount {
+    type Error = AmountError;
+
+    fn try_from(amount: &TypedAmount) -> Result<Self, Self::Error> {
+        Amount::parse(&amount.base_units, amount.decimals)
+    }
+}
+
+/// `GET /v2/wallet/balance`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "WalletBalanceResponseV2"))]
+#[non_exhaustive]
+pub struct WalletBalanceResponse {
+    pub wallet_address: String,
+    pub chain: String,
+    pub asset: String,
+    pub amount: TypedAmount,
+}
+
+impl WalletBalanceResponse {
+    pub fn new(wallet_address: String, chain: String, asset: String, amount: Amount) -> Self {
+        Self {
+            wallet_address,
+            chain,
+            asset,
+            amount: amount.into(),
+        }
+    }
+}
+
+/// Body of every v2 error response: [`crate::ApiError`] with the message
+/// under `message`. `error` is still read, so a v2 client can parse v1
+/// bodies too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "ApiErrorV2"))]
+#[non_exhaustive]
+pub struct ApiError {
+    pub code: ErrorCode,
+    #[serde(alias = "error")]
+    pub message: String,
+    pub retryable: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retryable: code.retryable(),
+            details: BTreeMap::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
@@ -0,0 +1,164 @@
+//! Outbound webhook events.
+//!
+//! Each [`WebhookEvent`] is posted inside a [`WebhookEnvelope`]: the event
+//! as the exact JSON string that was hashed, its SHA-256, the time it was
+//! signed and an HMAC-SHA256 over those under a secret shared with the
+//! consumer. The payload travels as a string so a consumer in any language
+//! checks the bytes that were signed rather than a re-serialization.
+//!
+//! A consumer calls [`WebhookEnvelope::verify`] with the shared secret and
+//! gets the event back only when the hash, the signature and the timestamp
+//! all check out. Consumers dedupe on `event_id`; a delivery may repeat.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::hex::{from_hex, to_hex};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How far a consumer should let an envelope's timestamp drift from its own
+/// clock, in either direction, before treating it as a replay.
+pub const DEFAULT_TOLERANCE_MS: u128 = 5 * 60 * 1_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum WebhookEvent {
+    /// A submitted transaction was recorded, or its status moved.
+    /// `previous_status` is `None` for a newly recorded transaction.
+    TxStatusChanged {
+        tx_hash: String,
+        from: String,
+        previous_status: Option<String>,
+        status: String,
+    },
+    /// A wallet was bound to a user, or its binding replaced.
+    WalletBound {
+        wallet_address: String,
+        user_id: String,
+        chain: String,
+    },
+    /// A custodial wallet was created.
+    WalletCreated {
+        wallet_address: String,
+        public_key: String,
+        chain: String,
+    },
+    /// A wallet signed an auth challenge and the signature verified.
+    ChallengeVerified { wallet_address: String },
+}
+
+impl WebhookEvent {
+    /// The `type` tag, e.g. `tx_status_changed`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TxStatusChanged { .. } => "tx_status_changed",
+            Self::WalletBound { .. } => "wallet_bound",
+            Self::WalletCreated { .. } => "wallet_created",
+            Self::ChallengeVerified { .. } => "challenge_verified",
+        }
+    }
+}
+
+/// The body of every webhook post.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WebhookEnvelope {
+    pub event_id: String,
+    /// The event's `type`, repeated so consumers can route before parsing
+    /// `payload`.
+    pub event_type: String,
+    pub timestamp_epoch_ms: u128,
+    /// The [`WebhookEvent`] as JSON.
+    pub payload: String,
+    /// Hex SHA-256 of `payload`.
+    pub payload_sha256: String,
+    /// Hex HMAC-SHA256 of
+    /// `keycortex:webhook:v1:{event_id}:{timestamp_epoch_ms}:{payload_sha256}`.
+    pub signature: String,
+}
+
+impl WebhookEnvelope {
+    pub fn sign(
+        event_id: String,
+        event: &WebhookEvent,
+        timestamp_epoch_ms: u128,
+        secret: &[u8],
+    ) -> Self {
+        // Every field of every event is a string or an option of one.
+        let payload = serde_json::to_string(event).expect("webhook events serialize to JSON");
+        let payload_sha256 = to_hex(&Sha256::digest(payload.as_bytes()));
+        let signature = to_hex(
+            &mac(secret, &event_id, timestamp_epoch_ms, &payload_sha256)
+                .finalize()
+                .into_bytes(),
+        );
+        Self {
+            event_id,
+            event_type: event.kind().to_owned(),
+            timestamp_epoch_ms,
+            payload,
+            payload_sha256,
+            signature,
+        }
+    }
+
+    /// The event, once the payload matches its hash, the signature matches
+    /// `secret` and the timestamp is within `tolerance_ms` of `now_epoch_ms`.
+    pub fn verify(
+        &self,
+        secret: &[u8],
+        now_epoch_ms: u128,
+        tolerance_ms: u128,
+    ) -> Result<WebhookEvent, WebhookError> {
+        if to_hex(&Sha256::digest(self.payload.as_bytes())) != self.payload_sha256 {
+            return Err(WebhookError::PayloadHash);
+        }
+        let signature = from_hex(&self.signature).map_err(|_| WebhookError::Signature)?;
+        mac(secret, &self.event_id, self.timestamp_epoch_ms, &self.payload_sha256)
+            .verify_slice(&signature)
+            .map_err(|_| WebhookError::Signature)?;
+        if now_epoch_ms.abs_diff(self.timestamp_epoch_ms) > tolerance_ms {
+            return Err(WebhookError::Stale);
+        }
+        let event: WebhookEvent =
+            serde_json::from_str(&self.payload).map_err(|_| WebhookError::Payload)?;
+        if event.kind() != self.event_type {
+            return Err(WebhookError::Payload);
+        }
+        Ok(event)
+    }
+}
+
+/// Why [`WebhookEnvelope::verify`] rejected an envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookError {
+    PayloadHash,
+    Signature,
+    Stale,
+    Payload,
+}
+
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::PayloadHash => "payload does not match payload_sha256",
+            Self::Signature => "signature does not match",
+            Self::Stale => "timestamp is outside the allowed tolerance",
+            Self::Payload => "payload is not a webhook event of event_type",
+        })
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+fn mac(secret: &[u8], event_id: &str, timestamp_epoch_ms: u128, payload_sha256: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(
+        format!("keycortex:webhook:v1:{event_id}:{timestamp_epoch_ms}:{payload_sha256}").as_bytes(),
+    );
+    mac
+}
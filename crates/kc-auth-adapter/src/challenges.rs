@@ -0,0 +1,233 @@
+//! The auth challenge lifecycle: issued with a time to live, consumed at
+//! most once, and removed by [`Challenges::sweep`] once long expired.
+//!
+//! [`Challenges`] runs the lifecycle over any [`ChallengeStore`]. The
+//! in-memory store suits tests; a single replica keeps challenges in its
+//! [`RocksDbKeystore`], and replicas behind a load balancer share them
+//! through Postgres or Redis, so a challenge issued by one replica can be
+//! verified by another.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use kc_storage::{ChallengeRecord, RocksDbKeystore};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// How long a challenge can be verified for unless
+/// [`Challenges::with_ttl`] says otherwise.
+pub const DEFAULT_CHALLENGE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a challenge stays after expiring, so late verifies are told it
+/// expired or was used rather than that it never existed.
+pub const CHALLENGE_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Result of [`ChallengeStore::consume`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeOutcome {
+    /// The challenge was unused and unexpired; it is now used.
+    Consumed,
+    NotFound,
+    AlreadyUsed,
+    /// The challenge had expired; it is now used, so it cannot be retried.
+    Expired,
+}
+
+#[async_trait]
+pub trait ChallengeStore: Send + Sync {
+    async fn insert(&self, challenge: &str, record: ChallengeRecord) -> Result<()>;
+    /// Mark `challenge` used, reporting whether it was usable at `now`.
+    async fn consume(&self, challenge: &str, now_epoch_ms: u128) -> Result<ChallengeOutcome>;
+    /// Remove challenges that expired before `cutoff_epoch_ms`, used or
+    /// not, returning how many were removed.
+    async fn sweep(&self, cutoff_epoch_ms: u128) -> Result<u64>;
+}
+
+/// A challenge just issued, as stored.
+#[derive(Debug, Clone)]
+pub struct PendingChallenge {
+    pub challenge: String,
+    pub expires_in_seconds: u64,
+    pub record: ChallengeRecord,
+}
+
+/// Issues, consumes and sweeps challenges in one [`ChallengeStore`].
+pub struct Challenges {
+    store: Arc<dyn ChallengeStore>,
+    ttl: Duration,
+}
+
+impl Challenges {
+    pub fn new(store: Arc<dyn ChallengeStore>) -> Self {
+        Self {
+            store,
+            ttl: DEFAULT_CHALLENGE_TTL,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Store a fresh random challenge, verifiable for the TTL from `now`.
+    pub async fn issue(&self, now_epoch_ms: u128) -> Result<PendingChallenge> {
+        let challenge = Uuid::new_v4().to_string();
+        let record = ChallengeRecord {
+            issued_at_epoch_ms: now_epoch_ms,
+            expires_at_epoch_ms: now_epoch_ms + self.ttl.as_millis(),
+            used: false,
+            used_at_epoch_ms: None,
+        };
+        self.store.insert(&challenge, record.clone()).await?;
+        Ok(PendingChallenge {
+            challenge,
+            expires_in_seconds: self.ttl.as_secs(),
+            record,
+        })
+    }
+
+    pub async fn consume(&self, challenge: &str, now_epoch_ms: u128) -> Result<ChallengeOutcome> {
+        self.store.consume(challenge, now_epoch_ms).await
+    }
+
+    /// Remove challenges expired for longer than [`CHALLENGE_RETENTION`] at
+    /// `now`, returning how many were removed.
+    pub async fn sweep(&self, now_epoch_ms: u128) -> Result<u64> {
+        let cutoff = now_epoch_ms.saturating_sub(CHALLENGE_RETENTION.as_millis());
+        self.store.sweep(cutoff).await
+    }
+}
+
+/// What consuming `record` at `now` gives, for a record that was unused.
+fn unused_outcome(record: &ChallengeRecord, now_epoch_ms: u128) -> ChallengeOutcome {
+    if now_epoch_ms > record.expires_at_epoch_ms {
+        ChallengeOutcome::Expired
+    } else {
+        ChallengeOutcome::Consumed
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryChallengeStore {
+    challenges: RwLock<HashMap<String, ChallengeRecord>>,
+}
+
+#[async_trait]
+impl ChallengeStore for InMemoryChallengeStore {
+    async fn insert(&self, challenge: &str, record: ChallengeRecord) -> Result<()> {
+        self.challenges.write().await.insert(challenge.to_owned(), record);
+        Ok(())
+    }
+
+    async fn consume(&self, challenge: &str, now_epoch_ms: u128) -> Result<ChallengeOutcome> {
+        let mut challenges = self.challenges.write().await;
+        let Some(record) = challenges.get_mut(challenge) else {
+            return Ok(ChallengeOutcome::NotFound);
+        };
+        if record.used {
+            return Ok(ChallengeOutcome::AlreadyUsed);
+        }
+        record.used = true;
+        record.used_at_epoch_ms = Some(now_epoch_ms);
+        Ok(unused_outcome(record, now_epoch_ms))
+    }
+
+    async fn sweep(&self, cutoff_epoch_ms: u128) -> Result<u64> {
+        let mut challenges = self.challenges.write().await;
+        let before = challenges.len();
+        challenges.retain(|_, record| record.expires_at_epoch_ms >= cutoff_epoch_ms);
+        Ok((before - challenges.len()) as u64)
+    }
+}
+
+#[async_trait]
+impl ChallengeStore for RocksDbKeystore {
+    async fn insert(&self, challenge: &str, record: ChallengeRecord) -> Result<()> {
+        self.save_challenge(challenge, &record)
+    }
+
+    async fn consume(&self, challenge: &str, now_epoch_ms: u128) -> Result<ChallengeOutcome> {
+        Ok(match self.consume_challenge(challenge, now_epoch_ms)? {
+            None => ChallengeOutcome::NotFound,
+            Some(record) if record.used => ChallengeOutcome::AlreadyUsed,
+            Some(record) => unused_outcome(&record, now_epoch_ms),
+        })
+    }
+
+    async fn sweep(&self, cutoff_epoch_ms: u128) -> Result<u64> {
+        self.delete_challenges_expired_before(cutoff_epoch_ms)
+    }
+}
+
+/// Behaviour every challenge store must share; run by each backend's tests
+/// against an empty store.
+#[doc(hidden)]
+pub mod conformance {
+    use super::*;
+
+    pub async fn check_challenge_store(store: &dyn ChallengeStore) {
+        let record = |expires_at_epoch_ms| ChallengeRecord {
+            issued_at_epoch_ms: 1_000,
+            expires_at_epoch_ms,
+            used: false,
+            used_at_epoch_ms: None,
+        };
+        store.insert("fresh", record(5_000)).await.unwrap();
+        store.insert("stale", record(2_000)).await.unwrap();
+        store.insert("unused", record(2_500)).await.unwrap();
+
+        assert_eq!(store.consume("missing", 3_000).await.unwrap(), ChallengeOutcome::NotFound);
+        assert_eq!(store.consume("fresh", 3_000).await.unwrap(), ChallengeOutcome::Consumed);
+        assert_eq!(store.consume("fresh", 3_000).await.unwrap(), ChallengeOutcome::AlreadyUsed);
+        assert_eq!(store.consume("stale", 3_000).await.unwrap(), ChallengeOutcome::Expired);
+        assert_eq!(store.consume("stale", 3_000).await.unwrap(), ChallengeOutcome::AlreadyUsed);
+
+        store.sweep(3_000).await.unwrap();
+        assert_eq!(store.consume("stale", 3_000).await.unwrap(), ChallengeOutcome::NotFound);
+        assert_eq!(store.consume("unused", 3_000).await.unwrap(), ChallengeOutcome::NotFound);
+        assert_eq!(store.consume("fresh", 3_000).await.unwrap(), ChallengeOutcome::AlreadyUsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stores_conform() {
+        conformance::check_challenge_store(&InMemoryChallengeStore::default()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+        conformance::check_challenge_store(&keystore).await;
+    }
+
+    #[tokio::test]
+    async fn challenges_expire_after_their_ttl_and_are_swept_after_retention() {
+        let store = Arc::new(InMemoryChallengeStore::default());
+        let challenges = Challenges::new(store.clone()).with_ttl(Duration::from_secs(60));
+        let issued = challenges.issue(1_000).await.unwrap();
+        assert_eq!(issued.expires_in_seconds, 60);
+        assert_eq!(issued.record.expires_at_epoch_ms, 61_000);
+        let late = challenges.issue(1_000).await.unwrap();
+
+        assert_eq!(
+            challenges.consume(&issued.challenge, 61_000).await.unwrap(),
+            ChallengeOutcome::Consumed
+        );
+        assert_eq!(
+            challenges.consume(&late.challenge, 61_001).await.unwrap(),
+            ChallengeOutcome::Expired
+        );
+
+        let retained_until = 61_000 + CHALLENGE_RETENTION.as_millis();
+        assert_eq!(challenges.sweep(retained_until).await.unwrap(), 0);
+        assert_eq!(challenges.sweep(retained_until + 1).await.unwrap(), 2);
+        assert_eq!(
+            challenges.consume(&late.challenge, retained_until + 1).await.unwrap(),
+            ChallengeOutcome::NotFound
+        );
+    }
+}
@@ -1,25 +1,22 @@
 use anyhow::{Result, anyhow};
 use kc_api_types::{AuthChallengeResponse, AuthVerifyResponse};
 use std::time::{SystemTime, UNIX_EPOCH};
-use uuid::Uuid;
 
-#[derive(Debug, Clone)]
-pub struct PendingChallenge {
-    pub challenge: String,
-    pub expires_in_seconds: u64,
-}
+mod challenges;
+mod sign_in;
 
-pub fn issue_challenge(expires_in_seconds: u64) -> PendingChallenge {
-    PendingChallenge {
-        challenge: Uuid::new_v4().to_string(),
-        expires_in_seconds,
-    }
-}
+pub use challenges::{
+    CHALLENGE_RETENTION, ChallengeOutcome, ChallengeStore, Challenges, DEFAULT_CHALLENGE_TTL,
+    InMemoryChallengeStore, PendingChallenge, conformance,
+};
+pub use kc_storage::ChallengeRecord;
+pub use sign_in::{MAX_CLOCK_SKEW, SignInError, SignInMessage};
 
 pub fn challenge_response(challenge: &PendingChallenge) -> AuthChallengeResponse {
     AuthChallengeResponse {
         challenge: challenge.challenge.clone(),
         expires_in: challenge.expires_in_seconds,
+        message: None,
     }
 }
 
@@ -41,5 +38,6 @@ pub fn verify_signature_placeholder(
         valid: true,
         wallet_address: wallet_address.to_owned(),
         verified_at_epoch_ms,
+        session: None,
     })
 }
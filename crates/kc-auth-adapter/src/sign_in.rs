@@ -0,0 +1,336 @@
+//! Human-readable sign-in messages for auth challenges, after EIP-4361
+//! (Sign-In with Ethereum).
+//!
+//! A [`SignInMessage`] binds a challenge to the service's origin and the
+//! wallet signing it, so someone signing in an external wallet sees what
+//! they are authorizing and where, and a signature collected by one site
+//! cannot be replayed against another:
+//!
+//! ```text
+//! wallet.example.com wants you to sign in with your wallet:
+//! 0x1234...
+//!
+//! Sign in to KeyCortex.
+//!
+//! URI: https://wallet.example.com
+//! Version: 1
+//! Nonce: 5f0c...
+//! Issued At: 2026-01-01T00:00:00.000Z
+//! Expiration Time: 2026-01-01T00:05:00.000Z
+//! ```
+//!
+//! The statement is optional; its line and the blank line after it are
+//! left out when there is none.
+
+use std::fmt;
+use std::time::Duration;
+
+/// How far ahead of the verifier's clock a message may say it was issued,
+/// for replicas whose clocks disagree.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your wallet:";
+const VERSION: &str = "1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignInMessage {
+    /// Host, and port if any, of `uri`.
+    pub domain: String,
+    /// Origin of the service asking for the signature.
+    pub uri: String,
+    pub wallet_address: String,
+    pub statement: Option<String>,
+    /// The challenge being signed for.
+    pub nonce: String,
+    /// Timestamps are written to the millisecond.
+    pub issued_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+}
+
+/// Why a [`SignInMessage`] could not be parsed or was not valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignInError {
+    /// The message is not laid out as [`SignInMessage`] writes it; names
+    /// the part that is wrong.
+    Malformed(&'static str),
+    DomainMismatch,
+    WalletMismatch,
+    NonceMismatch,
+    NotYetValid,
+    Expired,
+}
+
+impl fmt::Display for SignInError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(part) => write!(f, "malformed {part}"),
+            Self::DomainMismatch => write!(f, "message is for another domain"),
+            Self::WalletMismatch => write!(f, "message is for another wallet"),
+            Self::NonceMismatch => write!(f, "message nonce does not match the challenge"),
+            Self::NotYetValid => write!(f, "message is not valid yet"),
+            Self::Expired => write!(f, "message has expired"),
+        }
+    }
+}
+
+impl std::error::Error for SignInError {}
+
+impl SignInMessage {
+    /// A message for `wallet_address` to sign `nonce` for the service at
+    /// `origin`, e.g. `https://wallet.example.com`.
+    pub fn new(
+        origin: &str,
+        wallet_address: &str,
+        nonce: &str,
+        issued_at_epoch_ms: u128,
+        expires_at_epoch_ms: u128,
+    ) -> Self {
+        Self {
+            domain: domain_of(origin).to_owned(),
+            uri: origin.to_owned(),
+            wallet_address: wallet_address.to_owned(),
+            statement: None,
+            nonce: nonce.to_owned(),
+            issued_at_epoch_ms,
+            expires_at_epoch_ms,
+        }
+    }
+
+    /// A line telling the signer what they are agreeing to.
+    pub fn with_statement(mut self, statement: &str) -> Self {
+        self.statement = Some(statement.to_owned());
+        self
+    }
+
+    pub fn parse(text: &str) -> Result<Self, SignInError> {
+        let mut lines = text.split('\n');
+        let mut next = |part| lines.next().ok_or(SignInError::Malformed(part));
+
+        let domain = next("header")?
+            .strip_suffix(HEADER_SUFFIX)
+            .filter(|domain| !domain.is_empty())
+            .ok_or(SignInError::Malformed("header"))?
+            .to_owned();
+        let wallet_address = next("wallet address")?.to_owned();
+        if wallet_address.is_empty() || !next("wallet address")?.is_empty() {
+            return Err(SignInError::Malformed("wallet address"));
+        }
+
+        let mut line = next("URI")?;
+        let mut statement = None;
+        if !line.starts_with("URI: ") {
+            if line.is_empty() || !next("statement")?.is_empty() {
+                return Err(SignInError::Malformed("statement"));
+            }
+            statement = Some(line.to_owned());
+            line = next("URI")?;
+        }
+        let uri = field(line, "URI")?.to_owned();
+        if field(next("Version")?, "Version")? != VERSION {
+            return Err(SignInError::Malformed("Version"));
+        }
+        let nonce = field(next("Nonce")?, "Nonce")?.to_owned();
+        let issued_at_epoch_ms = parse_timestamp(field(next("Issued At")?, "Issued At")?)
+            .ok_or(SignInError::Malformed("Issued At"))?;
+        let expires_at_epoch_ms = parse_timestamp(field(next("Expiration Time")?, "Expiration Time")?)
+            .ok_or(SignInError::Malformed("Expiration Time"))?;
+        if lines.next().is_some() {
+            return Err(SignInError::Malformed("trailing lines"));
+        }
+
+        Ok(Self {
+            domain,
+            uri,
+            wallet_address,
+            statement,
+            nonce,
+            issued_at_epoch_ms,
+            expires_at_epoch_ms,
+        })
+    }
+
+    /// Check the message was written by the service at `origin` for
+    /// `wallet_address` to sign `nonce`, and is valid at `now`.
+    pub fn check(
+        &self,
+        origin: &str,
+        wallet_address: &str,
+        nonce: &str,
+        now_epoch_ms: u128,
+    ) -> Result<(), SignInError> {
+        if self.uri != origin || self.domain != domain_of(origin) {
+            return Err(SignInError::DomainMismatch);
+        }
+        if self.wallet_address != wallet_address {
+            return Err(SignInError::WalletMismatch);
+        }
+        if self.nonce != nonce {
+            return Err(SignInError::NonceMismatch);
+        }
+        if self.issued_at_epoch_ms > now_epoch_ms + MAX_CLOCK_SKEW.as_millis() {
+            return Err(SignInError::NotYetValid);
+        }
+        if now_epoch_ms > self.expires_at_epoch_ms {
+            return Err(SignInError::Expired);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SignInMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}{HEADER_SUFFIX}", self.domain)?;
+        writeln!(f, "{}", self.wallet_address)?;
+        writeln!(f)?;
+        if let Some(statement) = &self.statement {
+            writeln!(f, "{statement}")?;
+            writeln!(f)?;
+        }
+        writeln!(f, "URI: {}", self.uri)?;
+        writeln!(f, "Version: {VERSION}")?;
+        writeln!(f, "Nonce: {}", self.nonce)?;
+        writeln!(f, "Issued At: {}", format_timestamp(self.issued_at_epoch_ms))?;
+        write!(f, "Expiration Time: {}", format_timestamp(self.expires_at_epoch_ms))
+    }
+}
+
+/// The value of the `{name}: {value}` line `line`.
+fn field<'a>(line: &'a str, name: &'static str) -> Result<&'a str, SignInError> {
+    line.strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix(": "))
+        .ok_or(SignInError::Malformed(name))
+}
+
+/// `wallet.example.com:8443` for `https://wallet.example.com:8443/app`.
+fn domain_of(origin: &str) -> &str {
+    let rest = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.sssZ` for a Unix timestamp in milliseconds.
+fn format_timestamp(epoch_ms: u128) -> String {
+    let seconds = epoch_ms / 1_000;
+    let days = (seconds / 86_400) as i64;
+    let seconds_of_day = seconds % 86_400;
+
+    // Civil-from-days (proleptic Gregorian), days since 1970-01-01.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        seconds_of_day / 3_600,
+        (seconds_of_day / 60) % 60,
+        seconds_of_day % 60,
+        epoch_ms % 1_000
+    )
+}
+
+/// Inverse of [`format_timestamp`]; the milliseconds may be left out.
+fn parse_timestamp(text: &str) -> Option<u128> {
+    let text = text.strip_suffix('Z')?;
+    let (date, time) = text.split_once('T')?;
+    let (time, millis) = match time.split_once('.') {
+        Some((time, millis)) if millis.len() == 3 => (time, number(millis)?),
+        Some(_) => return None,
+        None => (time, 0),
+    };
+    let mut date = date.split('-');
+    let (year, month, day) = (number(date.next()?)?, number(date.next()?)?, number(date.next()?)?);
+    let mut time = time.split(':');
+    let (hour, minute, second) = (number(time.next()?)?, number(time.next()?)?, number(time.next()?)?);
+    if date.next().is_some()
+        || time.next().is_some()
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+        || year < 1970
+    {
+        return None;
+    }
+
+    // Days-from-civil, the inverse of the conversion in `format_timestamp`.
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(u128::try_from(seconds).ok()? * 1_000 + u128::try_from(millis).ok()?)
+}
+
+fn number(digits: &str) -> Option<i64> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: &str = "https://wallet.example.com:8443";
+
+    #[test]
+    fn message_round_trips_through_its_text() {
+        let message = SignInMessage::new(ORIGIN, "0xabc", "nonce-1", 1_767_225_600_123, 1_767_225_900_123)
+            .with_statement("Sign in to KeyCortex.");
+        let text = message.to_string();
+        assert_eq!(
+            text,
+            "wallet.example.com:8443 wants you to sign in with your wallet:\n\
+             0xabc\n\
+             \n\
+             Sign in to KeyCortex.\n\
+             \n\
+             URI: https://wallet.example.com:8443\n\
+             Version: 1\n\
+             Nonce: nonce-1\n\
+             Issued At: 2026-01-01T00:00:00.123Z\n\
+             Expiration Time: 2026-01-01T00:05:00.123Z"
+        );
+        assert_eq!(SignInMessage::parse(&text), Ok(message.clone()));
+
+        let bare = SignInMessage {
+            statement: None,
+            ..message
+        };
+        assert_eq!(SignInMessage::parse(&bare.to_string()), Ok(bare));
+        assert_eq!(parse_timestamp("2024-02-29T23:59:59Z"), Some(1_709_251_199_000));
+    }
+
+    #[test]
+    fn check_rejects_messages_for_anything_else() {
+        let message = SignInMessage::new(ORIGIN, "0xabc", "nonce-1", 100_000, 200_000);
+        assert_eq!(message.check(ORIGIN, "0xabc", "nonce-1", 150_000), Ok(()));
+        assert_eq!(
+            message.check("https://evil.example", "0xabc", "nonce-1", 150_000),
+            Err(SignInError::DomainMismatch)
+        );
+        assert_eq!(message.check(ORIGIN, "0xdef", "nonce-1", 150_000), Err(SignInError::WalletMismatch));
+        assert_eq!(message.check(ORIGIN, "0xabc", "nonce-2", 150_000), Err(SignInError::NonceMismatch));
+        assert_eq!(message.check(ORIGIN, "0xabc", "nonce-1", 200_001), Err(SignInError::Expired));
+        let early = 100_000 - MAX_CLOCK_SKEW.as_millis() - 1;
+        assert_eq!(message.check(ORIGIN, "0xabc", "nonce-1", early), Err(SignInError::NotYetValid));
+
+        let tampered = message.to_string().replace("Version: 1", "Version: 2");
+        assert_eq!(SignInMessage::parse(&tampered), Err(SignInError::Malformed("Version")));
+        assert_eq!(
+            SignInMessage::parse(&format!("{message}\nResources: none")),
+            Err(SignInError::Malformed("trailing lines"))
+        );
+    }
+}
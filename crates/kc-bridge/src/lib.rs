@@ -0,0 +1,286 @@
+//! Cross-chain transfer orchestration.
+//!
+//! A bridge transfer moves an asset from a wallet on one chain to a wallet on
+//! another in two legs: the source wallet pays the bridge wallet on the
+//! source chain, and once that confirms, the bridge wallet on the target
+//! chain pays the recipient. Both legs are tracked in one
+//! [`BridgeTransferRecord`].
+//!
+//! This crate only decides what the transfer does next and folds chain
+//! results into the record. Signing, submitting and persisting are left to
+//! the caller, which loops on [`next_step`] until it returns [`Step::Wait`]
+//! or [`Step::Done`].
+
+use kc_chain_client::TxStatusResult;
+use kc_storage::{BridgeTransferRecord, TxStatusChange};
+use std::fmt;
+
+pub const PENDING: &str = "pending";
+pub const SOURCE_SUBMITTED: &str = "source_submitted";
+pub const SOURCE_CONFIRMED: &str = "source_confirmed";
+pub const TARGET_SUBMITTED: &str = "target_submitted";
+pub const COMPLETED: &str = "completed";
+pub const FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leg {
+    /// Source wallet to the source chain's bridge wallet.
+    Source,
+    /// Target chain's bridge wallet to the recipient.
+    Target,
+}
+
+impl fmt::Display for Leg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Leg::Source => "source",
+            Leg::Target => "target",
+        })
+    }
+}
+
+/// A leg's transfer, as the caller should sign and submit it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegTransfer {
+    pub chain: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Sign and submit the leg, then report it with [`record_submitted`].
+    Submit(Leg),
+    /// Look up the leg's transaction and report it with [`record_status`].
+    Poll { leg: Leg, tx_hash: String },
+    /// Nothing to do until the transfer is polled again.
+    Wait,
+    Done,
+}
+
+/// A requested transfer of `amount` of `asset` from `from` on
+/// `source_chain` to `to` on `target_chain`.
+#[derive(Debug, Clone)]
+pub struct BridgeIntent {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub source_chain: String,
+    pub target_chain: String,
+}
+
+/// A new transfer, before its source leg is submitted.
+pub fn new_transfer(
+    bridge_id: String,
+    intent: BridgeIntent,
+    source_bridge_wallet: String,
+    target_bridge_wallet: String,
+    now: u128,
+) -> BridgeTransferRecord {
+    BridgeTransferRecord {
+        bridge_id,
+        status: PENDING.to_owned(),
+        from: intent.from,
+        to: intent.to,
+        amount: intent.amount,
+        asset: intent.asset,
+        source_chain: intent.source_chain,
+        target_chain: intent.target_chain,
+        source_bridge_wallet,
+        target_bridge_wallet,
+        source_tx_hash: None,
+        target_tx_hash: None,
+        error: None,
+        created_at_epoch_ms: now,
+        updated_at_epoch_ms: now,
+        status_history: vec![TxStatusChange {
+            status: PENDING.to_owned(),
+            at_epoch_ms: now,
+        }],
+    }
+}
+
+pub fn next_step(record: &BridgeTransferRecord) -> Step {
+    let poll = |leg, tx_hash: &Option<String>| match tx_hash {
+        Some(tx_hash) => Step::Poll {
+            leg,
+            tx_hash: tx_hash.clone(),
+        },
+        None => Step::Wait,
+    };
+    match record.status.as_str() {
+        PENDING => Step::Submit(Leg::Source),
+        SOURCE_SUBMITTED => poll(Leg::Source, &record.source_tx_hash),
+        SOURCE_CONFIRMED => Step::Submit(Leg::Target),
+        TARGET_SUBMITTED => poll(Leg::Target, &record.target_tx_hash),
+        _ => Step::Done,
+    }
+}
+
+/// The transfer `leg` makes.
+pub fn leg_transfer(record: &BridgeTransferRecord, leg: Leg) -> LegTransfer {
+    match leg {
+        Leg::Source => LegTransfer {
+            chain: record.source_chain.clone(),
+            from: record.from.clone(),
+            to: record.source_bridge_wallet.clone(),
+        },
+        Leg::Target => LegTransfer {
+            chain: record.target_chain.clone(),
+            from: record.target_bridge_wallet.clone(),
+            to: record.to.clone(),
+        },
+    }
+}
+
+/// Record the chain's answer to submitting `leg`. A rejected leg fails the
+/// transfer.
+pub fn record_submitted(
+    record: &mut BridgeTransferRecord,
+    leg: Leg,
+    tx_hash: String,
+    accepted: bool,
+    now: u128,
+) {
+    let chain = leg_transfer(record, leg).chain;
+    match leg {
+        Leg::Source => record.source_tx_hash = Some(tx_hash),
+        Leg::Target => record.target_tx_hash = Some(tx_hash),
+    }
+    if !accepted {
+        fail(record, format!("{leg} leg rejected by {chain}"), now);
+        return;
+    }
+    record.error = None;
+    let status = match leg {
+        Leg::Source => SOURCE_SUBMITTED,
+        Leg::Target => TARGET_SUBMITTED,
+    };
+    transition(record, status, now);
+}
+
+/// Fold the chain status of `leg`'s transaction into the record. Returns
+/// whether the transfer moved on; pending legs leave it where it is.
+pub fn record_status(
+    record: &mut BridgeTransferRecord,
+    leg: Leg,
+    status: &TxStatusResult,
+    now: u128,
+) -> bool {
+    match status.status.as_str() {
+        "confirmed" => {
+            let next = match leg {
+                Leg::Source => SOURCE_CONFIRMED,
+                Leg::Target => COMPLETED,
+            };
+            transition(record, next, now);
+            true
+        }
+        "failed" => {
+            let chain = leg_transfer(record, leg).chain;
+            fail(record, format!("{leg} leg failed on {chain}"), now);
+            true
+        }
+        _ => false,
+    }
+}
+
+pub fn fail(record: &mut BridgeTransferRecord, error: String, now: u128) {
+    record.error = Some(error);
+    transition(record, FAILED, now);
+}
+
+fn transition(record: &mut BridgeTransferRecord, status: &str, now: u128) {
+    record.status = status.to_owned();
+    record.updated_at_epoch_ms = now;
+    record.status_history.push(TxStatusChange {
+        status: status.to_owned(),
+        at_epoch_ms: now,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: &str) -> TxStatusResult {
+        TxStatusResult {
+            tx_hash: "0x1".to_owned(),
+            status: status.to_owned(),
+            accepted: status != "failed",
+            block_height: None,
+            confirmations: None,
+        }
+    }
+
+    fn transfer() -> BridgeTransferRecord {
+        new_transfer(
+            "bridge-1".to_owned(),
+            BridgeIntent {
+                from: "0xa".to_owned(),
+                to: "0xb".to_owned(),
+                amount: "10".to_owned(),
+                asset: "PROOF".to_owned(),
+                source_chain: "flowcortex-l1".to_owned(),
+                target_chain: "flowcortex-l2".to_owned(),
+            },
+            "0xbridge-l1".to_owned(),
+            "0xbridge-l2".to_owned(),
+            1,
+        )
+    }
+
+    #[test]
+    fn walks_both_legs_to_completion() {
+        let mut record = transfer();
+        assert_eq!(next_step(&record), Step::Submit(Leg::Source));
+        assert_eq!(
+            leg_transfer(&record, Leg::Source),
+            LegTransfer {
+                chain: "flowcortex-l1".to_owned(),
+                from: "0xa".to_owned(),
+                to: "0xbridge-l1".to_owned(),
+            }
+        );
+        record_submitted(&mut record, Leg::Source, "0x1".to_owned(), true, 2);
+        let poll_source = Step::Poll {
+            leg: Leg::Source,
+            tx_hash: "0x1".to_owned(),
+        };
+        assert_eq!(next_step(&record), poll_source);
+        assert!(!record_status(&mut record, Leg::Source, &status("pending"), 3));
+        assert_eq!(next_step(&record), poll_source);
+
+        assert!(record_status(&mut record, Leg::Source, &status("confirmed"), 4));
+        assert_eq!(next_step(&record), Step::Submit(Leg::Target));
+        assert_eq!(leg_transfer(&record, Leg::Target).from, "0xbridge-l2");
+        record_submitted(&mut record, Leg::Target, "0x2".to_owned(), true, 5);
+        assert!(record_status(&mut record, Leg::Target, &status("confirmed"), 6));
+        assert_eq!(next_step(&record), Step::Done);
+
+        let history: Vec<_> = record.status_history.iter().map(|change| change.status.as_str()).collect();
+        assert_eq!(
+            history,
+            [PENDING, SOURCE_SUBMITTED, SOURCE_CONFIRMED, TARGET_SUBMITTED, COMPLETED]
+        );
+        assert_eq!(record.updated_at_epoch_ms, 6);
+    }
+
+    #[test]
+    fn a_rejected_or_failed_leg_fails_the_transfer() {
+        let mut rejected = transfer();
+        record_submitted(&mut rejected, Leg::Source, "0x1".to_owned(), false, 2);
+        assert_eq!(rejected.status, FAILED);
+        assert_eq!(rejected.error.as_deref(), Some("source leg rejected by flowcortex-l1"));
+        assert_eq!(next_step(&rejected), Step::Done);
+
+        let mut failed = transfer();
+        record_submitted(&mut failed, Leg::Source, "0x1".to_owned(), true, 2);
+        record_status(&mut failed, Leg::Source, &status("confirmed"), 3);
+        record_submitted(&mut failed, Leg::Target, "0x2".to_owned(), true, 4);
+        assert!(record_status(&mut failed, Leg::Target, &status("failed"), 5));
+        assert_eq!(failed.status, FAILED);
+        assert_eq!(failed.error.as_deref(), Some("target leg failed on flowcortex-l2"));
+    }
+}
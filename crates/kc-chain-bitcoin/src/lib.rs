@@ -0,0 +1,424 @@
+//! [`ChainAdapter`] for Bitcoin over an Esplora REST endpoint.
+//!
+//! Esplora is served by Blockstream's `electrs` alongside its Electrum RPC,
+//! and by mempool.space, so the same index backs both. The adapter covers:
+//!
+//! - balances as the sum of a wallet's confirmed UTXOs, in sats;
+//! - transfers built as PSBTs with [`BitcoinAdapter::build_transfer`] and
+//!   signed with a secp256k1 key via [`psbt::Psbt::sign`];
+//! - `submit_transaction` broadcasts [`ChainPayload::Psbt`] (finalised here)
+//!   or [`ChainPayload::RawTransaction`];
+//! - status from `/tx/{txid}/status`, `pending` until mined.
+//!
+//! Addresses are native segwit under the network's bech32 prefix (`bc`,
+//! `tb` or `bcrt`). Bitcoin has no account nonce; inputs are UTXOs.
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+use kc_chain_client::{
+    AdapterHealth, BalanceResult, ChainAdapter, ChainPayload, NonceStrategy, SubmitTxRequest, SubmitTxResult,
+    TxStatusRequest, TxStatusResult,
+};
+use kc_crypto::encoding::{from_segwit_address, to_hex};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+pub mod psbt;
+
+use psbt::{Psbt, Utxo};
+
+pub const BTC: &str = "BTC";
+
+pub struct BitcoinAdapter {
+    chain_id: String,
+    endpoint: String,
+    bech32_prefix: String,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraUtxo {
+    txid: String,
+    vout: u32,
+    value: u64,
+    status: EsploraTxStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraTxStatus {
+    confirmed: bool,
+    block_height: Option<u64>,
+}
+
+impl BitcoinAdapter {
+    /// `bech32_prefix` is the network's segwit prefix: `bc`, `tb` or `bcrt`.
+    pub fn new(chain_id: &str, endpoint: &str, bech32_prefix: &str) -> Self {
+        Self {
+            chain_id: chain_id.to_owned(),
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            bech32_prefix: bech32_prefix.to_owned(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Send requests through `http`, e.g. a registry's shared pool.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn bech32_prefix(&self) -> &str {
+        &self.bech32_prefix
+    }
+
+    /// Output script paying `address`, a segwit address on this network.
+    pub fn script_pubkey(&self, address: &WalletAddress) -> Result<Vec<u8>> {
+        let (version, program) = from_segwit_address(&self.bech32_prefix, &address.0)
+            .with_context(|| format!("'{}' is not a {} address", address.0, self.chain_id))?;
+        Ok(psbt::witness_script(version, &program))
+    }
+
+    /// Confirmed UTXOs of `address`.
+    pub async fn utxos(&self, address: &WalletAddress) -> Result<Vec<Utxo>> {
+        self.script_pubkey(address)?;
+        let utxos: Vec<EsploraUtxo> = self.get_json(&format!("/address/{}/utxo", address.0)).await?;
+        Ok(utxos
+            .into_iter()
+            .filter(|utxo| utxo.status.confirmed)
+            .map(|utxo| Utxo {
+                txid: utxo.txid,
+                vout: utxo.vout,
+                value: utxo.value,
+            })
+            .collect())
+    }
+
+    /// Fee rate in sat/vB for confirmation within `target_blocks`, from the
+    /// node's estimates for the nearest target at or above it.
+    pub async fn fee_rate(&self, target_blocks: u32) -> Result<u64> {
+        let estimates: HashMap<String, f64> = self.get_json("/fee-estimates").await?;
+        let mut targets: Vec<(u32, f64)> = estimates
+            .into_iter()
+            .filter_map(|(target, rate)| Some((target.parse().ok()?, rate)))
+            .collect();
+        targets.sort_by_key(|(target, _)| *target);
+        let (_, rate) = targets
+            .iter()
+            .find(|(target, _)| *target >= target_blocks)
+            .or(targets.last())
+            .ok_or_else(|| anyhow!("{} returned no fee estimates", self.chain_id))?;
+        Ok((rate.ceil() as u64).max(1))
+    }
+
+    /// An unsigned PSBT sending `amount_sats` from the P2WPKH wallet `from`
+    /// to `to`, funded from confirmed UTXOs with change back to `from`.
+    pub async fn build_transfer(
+        &self,
+        from: &WalletAddress,
+        to: &WalletAddress,
+        amount_sats: u64,
+        fee_rate: u64,
+    ) -> Result<Psbt> {
+        let from_script = self.script_pubkey(from)?;
+        let to_script = self.script_pubkey(to)?;
+        let utxos = self.utxos(from).await?;
+        psbt::build_transfer(&utxos, &from_script, &to_script, amount_sats, fee_rate)
+    }
+
+    async fn get_text(&self, path: &str) -> Result<Option<String>> {
+        let response = self
+            .http
+            .get(format!("{}{path}", self.endpoint))
+            .send()
+            .await
+            .with_context(|| format!("GET {path} transport"))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!("GET {path} HTTP {status}: {text}");
+        }
+        Ok(Some(text))
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let text = self
+            .get_text(path)
+            .await?
+            .ok_or_else(|| anyhow!("GET {path} HTTP 404"))?;
+        serde_json::from_str(&text).with_context(|| format!("GET {path} response"))
+    }
+
+    async fn tip_height(&self) -> Result<u64> {
+        let height = self
+            .get_text("/blocks/tip/height")
+            .await?
+            .ok_or_else(|| anyhow!("{} returned no tip height", self.chain_id))?;
+        height
+            .trim()
+            .parse()
+            .map_err(|err| anyhow!("invalid tip height '{height}': {err}"))
+    }
+}
+
+#[async_trait]
+impl ChainAdapter for BitcoinAdapter {
+    fn chain_id(&self) -> &str {
+        &self.chain_id
+    }
+
+    fn nonce_strategy(&self) -> NonceStrategy {
+        NonceStrategy::None
+    }
+
+    /// Reachable when Esplora reports the tip height.
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async { self.tip_height().await.map(Some) }).await
+    }
+
+    async fn get_balance(
+        &self,
+        wallet_address: &WalletAddress,
+        asset: &AssetSymbol,
+    ) -> Result<BalanceResult> {
+        if !asset.0.eq_ignore_ascii_case(BTC) {
+            bail!("{} only holds {BTC}", self.chain_id);
+        }
+        let sats: u64 = self
+            .utxos(wallet_address)
+            .await?
+            .iter()
+            .map(|utxo| utxo.value)
+            .sum();
+
+        Ok(BalanceResult {
+            wallet_address: wallet_address.clone(),
+            chain: ChainId(self.chain_id.clone()),
+            asset: asset.clone(),
+            amount: Amount::new(u128::from(sats), 0),
+        })
+    }
+
+    async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+        let raw = match req.chain_payload {
+            Some(ChainPayload::Psbt(bytes)) => {
+                let mut psbt = Psbt::deserialize(&bytes)?;
+                psbt.finalize()?;
+                psbt.extract_transaction()?
+            }
+            Some(ChainPayload::RawTransaction(bytes)) => bytes,
+            None => bail!(
+                "{} submissions need a PSBT or raw transaction chain payload",
+                self.chain_id
+            ),
+        };
+        let response = self
+            .http
+            .post(format!("{}/tx", self.endpoint))
+            .body(to_hex(&raw))
+            .send()
+            .await
+            .context("broadcast transport")?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            // Refused by the node's mempool policy, e.g. a spent input or too low a fee.
+            return Ok(SubmitTxResult {
+                tx_hash: format!("failed:{}", text.trim()),
+                accepted: false,
+            });
+        }
+        if !status.is_success() {
+            bail!("broadcast HTTP {status}: {text}");
+        }
+        Ok(SubmitTxResult {
+            tx_hash: text.trim().to_owned(),
+            accepted: true,
+        })
+    }
+
+    async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+        let status = match self.get_text(&format!("/tx/{}/status", req.tx_hash)).await? {
+            Some(text) => Some(
+                serde_json::from_str::<EsploraTxStatus>(&text).context("tx status response")?,
+            ),
+            None => None,
+        };
+        let Some(height) = status
+            .filter(|status| status.confirmed)
+            .and_then(|status| status.block_height)
+        else {
+            return Ok(TxStatusResult {
+                tx_hash: req.tx_hash,
+                status: "pending".to_owned(),
+                accepted: true,
+                block_height: None,
+                confirmations: None,
+            });
+        };
+
+        let confirmations = self.tip_height().await?.saturating_sub(height) + 1;
+        Ok(TxStatusResult {
+            tx_hash: req.tx_hash,
+            status: "confirmed".to_owned(),
+            accepted: true,
+            block_height: Some(height),
+            confirmations: Some(confirmations),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use kc_crypto::Secp256k1Signer;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    const RECIPIENT: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+    #[derive(Default)]
+    struct MockNode {
+        broadcasts: Mutex<Vec<String>>,
+    }
+
+    fn wallet() -> (Secp256k1Signer, WalletAddress) {
+        let signer = Secp256k1Signer::from_secret_key_bytes([7u8; 32]).unwrap();
+        let address = WalletAddress(signer.p2wpkh_address("bcrt").unwrap());
+        (signer, address)
+    }
+
+    async fn start_mock(node: Arc<MockNode>) -> String {
+        let app = Router::new()
+            .route(
+                "/address/{address}/utxo",
+                get(|Path(address): Path<String>| async move {
+                    if address != wallet().1.0 {
+                        return Json(json!([]));
+                    }
+                    Json(json!([
+                        { "txid": "aa".repeat(32), "vout": 1, "value": 60_000, "status": { "confirmed": true, "block_height": 90 } },
+                        { "txid": "bb".repeat(32), "vout": 0, "value": 40_000, "status": { "confirmed": true, "block_height": 95 } },
+                        { "txid": "cc".repeat(32), "vout": 2, "value": 5_000, "status": { "confirmed": false } }
+                    ]))
+                }),
+            )
+            .route(
+                "/fee-estimates",
+                get(|| async { Json(json!({ "1": 20.4, "3": 12.1, "6": 8.9, "144": 1.0 })) }),
+            )
+            .route(
+                "/tx",
+                post(|State(node): State<Arc<MockNode>>, body: String| async move {
+                    node.broadcasts.lock().unwrap().push(body.clone());
+                    if body.starts_with("02000000") {
+                        Ok("f00d".repeat(16))
+                    } else {
+                        Err((StatusCode::BAD_REQUEST, "sendrawtransaction RPC error: TX decode failed".to_owned()))
+                    }
+                }),
+            )
+            .route(
+                "/tx/{txid}/status",
+                get(|Path(txid): Path<String>| async move {
+                    match txid.as_str() {
+                        "mined" => Ok(Json(json!({ "confirmed": true, "block_height": 98 }))),
+                        "mempool" => Ok(Json(json!({ "confirmed": false }))),
+                        _ => Err((StatusCode::NOT_FOUND, "Transaction not found")),
+                    }
+                }),
+            )
+            .route("/blocks/tip/height", get(|| async { "100" }))
+            .with_state(node);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://{address}/")
+    }
+
+    #[tokio::test]
+    async fn builds_signs_and_broadcasts_a_psbt() {
+        let node = Arc::new(MockNode::default());
+        let adapter = BitcoinAdapter::new("bitcoin-regtest", &start_mock(Arc::clone(&node)).await, "bcrt");
+        let (signer, from) = wallet();
+        let to = WalletAddress(RECIPIENT.to_owned());
+
+        assert_eq!(adapter.nonce_strategy(), NonceStrategy::None);
+        let balance = adapter.get_balance(&from, &AssetSymbol("btc".to_owned())).await.unwrap();
+        assert_eq!(balance.amount.base_units(), 100_000);
+        assert!(adapter.get_balance(&from, &AssetSymbol("ETH".to_owned())).await.is_err());
+        let mainnet = WalletAddress(signer.p2wpkh_address("bc").unwrap());
+        assert!(adapter.get_balance(&mainnet, &AssetSymbol(BTC.to_owned())).await.is_err());
+
+        assert_eq!(adapter.fee_rate(2).await.unwrap(), 13);
+        assert_eq!(adapter.fee_rate(1000).await.unwrap(), 1);
+
+        let mut psbt = adapter.build_transfer(&from, &to, 70_000, 13).await.unwrap();
+        assert_eq!(psbt.unsigned_tx.inputs.len(), 2);
+        assert_eq!(psbt.sign(&signer).unwrap(), 2);
+        let txid = psbt.unsigned_tx.txid();
+
+        let submit = |chain_payload: Option<ChainPayload>| SubmitTxRequest {
+            from: from.clone(),
+            to: to.clone(),
+            amount: Amount::new(70_000, 8),
+            asset: AssetSymbol(BTC.to_owned()),
+            chain: ChainId("bitcoin-regtest".to_owned()),
+            signed_payload: String::new(),
+            chain_payload,
+        };
+        let accepted = adapter
+            .submit_transaction(submit(Some(ChainPayload::Psbt(psbt.serialize()))))
+            .await
+            .unwrap();
+        assert!(accepted.accepted);
+        let broadcast = node.broadcasts.lock().unwrap()[0].clone();
+        assert!(broadcast.starts_with("020000000001"), "{broadcast}");
+        assert_ne!(txid, accepted.tx_hash, "mock node answers with a fixed txid");
+
+        let refused = adapter
+            .submit_transaction(submit(Some(ChainPayload::RawTransaction(vec![0xde, 0xad]))))
+            .await
+            .unwrap();
+        assert!(!refused.accepted);
+        assert_eq!(refused.tx_hash, "failed:sendrawtransaction RPC error: TX decode failed");
+        assert!(adapter.submit_transaction(submit(None)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_tx_status_with_confirmations_and_tip_health() {
+        let adapter = BitcoinAdapter::new("bitcoin-regtest", &start_mock(Arc::default()).await, "bcrt");
+        let status = |tx_hash: &str| TxStatusRequest {
+            tx_hash: tx_hash.to_owned(),
+            chain: ChainId("bitcoin-regtest".to_owned()),
+        };
+        let mined = adapter.get_transaction_status(status("mined")).await.unwrap();
+        assert_eq!((mined.status.as_str(), mined.accepted), ("confirmed", true));
+        assert_eq!((mined.block_height, mined.confirmations), (Some(98), Some(3)));
+        for txid in ["mempool", "unknown"] {
+            let pending = adapter.get_transaction_status(status(txid)).await.unwrap();
+            assert_eq!(pending.status, "pending");
+            assert_eq!(pending.confirmations, None);
+        }
+        assert_eq!(adapter.health().await.block_height, Some(100));
+
+        let offline = BitcoinAdapter::new("bitcoin-regtest", "http://127.0.0.1:9", "bcrt");
+        let health = offline.health().await;
+        assert!(!health.reachable);
+        assert!(health.detail.unwrap().contains("transport"));
+    }
+}
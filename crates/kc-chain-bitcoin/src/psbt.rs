@@ -0,0 +1,701 @@
+//! Partially signed Bitcoin transactions (BIP-174) for native segwit
+//! (P2WPKH) spends.
+//!
+//! Only what KeyCortex needs to build, sign and broadcast a transfer is
+//! modelled: the unsigned transaction, each input's `witness_utxo`, partial
+//! signatures and final witness. Other key-value pairs are kept as they were
+//! read so a PSBT from another signer round-trips unchanged.
+
+use anyhow::{Result, anyhow, bail};
+use kc_crypto::Secp256k1Signer;
+use kc_crypto::encoding::{from_hex, to_hex};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+const MAGIC: &[u8] = b"psbt\xff";
+const GLOBAL_UNSIGNED_TX: u8 = 0x00;
+const IN_WITNESS_UTXO: u8 = 0x01;
+const IN_PARTIAL_SIG: u8 = 0x02;
+const IN_SIGHASH_TYPE: u8 = 0x03;
+const IN_FINAL_SCRIPTWITNESS: u8 = 0x08;
+const SIGHASH_ALL: u32 = 1;
+/// Opts every input into replace-by-fee (BIP-125).
+const SEQUENCE_RBF: u32 = 0xffff_fffd;
+/// Smallest P2WPKH output relayed under the default dust limit.
+pub const DUST_LIMIT_SATS: u64 = 294;
+
+/// An unordered key-value pair this module does not interpret.
+type RawPair = (Vec<u8>, Vec<u8>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxIn {
+    /// Previous txid in internal byte order (reversed from its hex form).
+    pub previous_txid: [u8; 32],
+    pub vout: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxOut {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub version: i32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub lock_time: u32,
+}
+
+impl Transaction {
+    /// Serialise without witnesses, the form a txid is computed over.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.version.to_le_bytes());
+        self.write_body(&mut out);
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        out
+    }
+
+    /// Serialise with one witness stack per input (BIP-144).
+    pub fn serialize_with_witnesses(&self, witnesses: &[Vec<Vec<u8>>]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&[0x00, 0x01]);
+        self.write_body(&mut out);
+        for stack in witnesses {
+            write_witness(&mut out, stack);
+        }
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        out
+    }
+
+    /// Parse a transaction without witnesses, as carried in a PSBT.
+    pub fn parse_unsigned(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let version = i32::from_le_bytes(reader.array()?);
+        let input_count = reader.compact_size()?;
+        if input_count == 0 {
+            bail!("unsigned transaction has no inputs or carries witnesses");
+        }
+        let mut inputs = Vec::new();
+        for _ in 0..input_count {
+            inputs.push(TxIn {
+                previous_txid: reader.array()?,
+                vout: u32::from_le_bytes(reader.array()?),
+                script_sig: reader.var_bytes()?.to_vec(),
+                sequence: u32::from_le_bytes(reader.array()?),
+            });
+        }
+        let mut outputs = Vec::new();
+        for _ in 0..reader.compact_size()? {
+            outputs.push(TxOut {
+                value: u64::from_le_bytes(reader.array()?),
+                script_pubkey: reader.var_bytes()?.to_vec(),
+            });
+        }
+        let lock_time = u32::from_le_bytes(reader.array()?);
+        reader.finish()?;
+        Ok(Self {
+            version,
+            inputs,
+            outputs,
+            lock_time,
+        })
+    }
+
+    /// Transaction id in its usual (byte-reversed) hex form.
+    pub fn txid(&self) -> String {
+        let mut hash = double_sha256(&self.serialize());
+        hash.reverse();
+        to_hex(&hash)
+    }
+
+    fn write_body(&self, out: &mut Vec<u8>) {
+        write_compact_size(out, self.inputs.len() as u64);
+        for input in &self.inputs {
+            write_outpoint(out, input);
+            write_var_bytes(out, &input.script_sig);
+            out.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        write_compact_size(out, self.outputs.len() as u64);
+        for output in &self.outputs {
+            write_txout(out, output);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PsbtInput {
+    /// The output this input spends; required to sign a segwit input.
+    pub witness_utxo: Option<TxOut>,
+    /// Compressed public key → DER signature with the sighash byte.
+    pub partial_sigs: Vec<(Vec<u8>, Vec<u8>)>,
+    pub sighash_type: Option<u32>,
+    pub final_script_witness: Option<Vec<Vec<u8>>>,
+    unknown: Vec<RawPair>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Psbt {
+    pub unsigned_tx: Transaction,
+    pub inputs: Vec<PsbtInput>,
+    global_unknown: Vec<RawPair>,
+    outputs: Vec<Vec<RawPair>>,
+}
+
+impl Psbt {
+    /// A PSBT with empty input and output maps. Script sigs must be empty.
+    pub fn from_unsigned_tx(unsigned_tx: Transaction) -> Result<Self> {
+        if unsigned_tx.inputs.iter().any(|input| !input.script_sig.is_empty()) {
+            bail!("unsigned transaction inputs must have empty script sigs");
+        }
+        Ok(Self {
+            inputs: vec![PsbtInput::default(); unsigned_tx.inputs.len()],
+            outputs: vec![Vec::new(); unsigned_tx.outputs.len()],
+            global_unknown: Vec::new(),
+            unsigned_tx,
+        })
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            bail!("not a PSBT: bad magic bytes");
+        }
+
+        let mut unsigned_tx = None;
+        let mut global_unknown = Vec::new();
+        for (key, value) in reader.map()? {
+            if key == [GLOBAL_UNSIGNED_TX] {
+                unsigned_tx = Some(Transaction::parse_unsigned(&value)?);
+            } else {
+                global_unknown.push((key, value));
+            }
+        }
+        let unsigned_tx = unsigned_tx.ok_or_else(|| anyhow!("PSBT has no unsigned transaction"))?;
+
+        let mut inputs = Vec::new();
+        for _ in &unsigned_tx.inputs {
+            let mut input = PsbtInput::default();
+            for (key, value) in reader.map()? {
+                match (key[0], key.len()) {
+                    (IN_WITNESS_UTXO, 1) => {
+                        let mut utxo = Reader::new(&value);
+                        input.witness_utxo = Some(TxOut {
+                            value: u64::from_le_bytes(utxo.array()?),
+                            script_pubkey: utxo.var_bytes()?.to_vec(),
+                        });
+                        utxo.finish()?;
+                    }
+                    (IN_PARTIAL_SIG, _) => input.partial_sigs.push((key[1..].to_vec(), value)),
+                    (IN_SIGHASH_TYPE, 1) => {
+                        let mut sighash = Reader::new(&value);
+                        input.sighash_type = Some(u32::from_le_bytes(sighash.array()?));
+                        sighash.finish()?;
+                    }
+                    (IN_FINAL_SCRIPTWITNESS, 1) => {
+                        let mut witness = Reader::new(&value);
+                        let mut stack = Vec::new();
+                        for _ in 0..witness.compact_size()? {
+                            stack.push(witness.var_bytes()?.to_vec());
+                        }
+                        witness.finish()?;
+                        input.final_script_witness = Some(stack);
+                    }
+                    _ => input.unknown.push((key, value)),
+                }
+            }
+            inputs.push(input);
+        }
+        let mut outputs = Vec::new();
+        for _ in &unsigned_tx.outputs {
+            outputs.push(reader.map()?);
+        }
+        reader.finish()?;
+
+        Ok(Self {
+            unsigned_tx,
+            inputs,
+            global_unknown,
+            outputs,
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        write_pair(&mut out, &[GLOBAL_UNSIGNED_TX], &self.unsigned_tx.serialize());
+        for (key, value) in &self.global_unknown {
+            write_pair(&mut out, key, value);
+        }
+        out.push(0x00);
+
+        for input in &self.inputs {
+            if let Some(utxo) = &input.witness_utxo {
+                let mut value = Vec::new();
+                write_txout(&mut value, utxo);
+                write_pair(&mut out, &[IN_WITNESS_UTXO], &value);
+            }
+            for (public_key, signature) in &input.partial_sigs {
+                let mut key = vec![IN_PARTIAL_SIG];
+                key.extend_from_slice(public_key);
+                write_pair(&mut out, &key, signature);
+            }
+            if let Some(sighash_type) = input.sighash_type {
+                write_pair(&mut out, &[IN_SIGHASH_TYPE], &sighash_type.to_le_bytes());
+            }
+            if let Some(stack) = &input.final_script_witness {
+                let mut value = Vec::new();
+                write_witness(&mut value, stack);
+                write_pair(&mut out, &[IN_FINAL_SCRIPTWITNESS], &value);
+            }
+            for (key, value) in &input.unknown {
+                write_pair(&mut out, key, value);
+            }
+            out.push(0x00);
+        }
+        for output in &self.outputs {
+            for (key, value) in output {
+                write_pair(&mut out, key, value);
+            }
+            out.push(0x00);
+        }
+        out
+    }
+
+    /// BIP-143 `SIGHASH_ALL` digest for spending P2WPKH input `index`.
+    pub fn p2wpkh_sighash(&self, index: usize) -> Result<[u8; 32]> {
+        let tx = &self.unsigned_tx;
+        let input = tx
+            .inputs
+            .get(index)
+            .ok_or_else(|| anyhow!("PSBT has no input {index}"))?;
+        let utxo = self.inputs[index]
+            .witness_utxo
+            .as_ref()
+            .ok_or_else(|| anyhow!("input {index} has no witness_utxo"))?;
+        let key_hash = p2wpkh_key_hash(&utxo.script_pubkey)
+            .ok_or_else(|| anyhow!("input {index} does not spend a P2WPKH output"))?;
+
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &tx.inputs {
+            write_outpoint(&mut prevouts, input);
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        let mut outputs = Vec::new();
+        for output in &tx.outputs {
+            write_txout(&mut outputs, output);
+        }
+
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&tx.version.to_le_bytes());
+        preimage.extend_from_slice(&double_sha256(&prevouts));
+        preimage.extend_from_slice(&double_sha256(&sequences));
+        write_outpoint(&mut preimage, input);
+        // scriptCode is the P2PKH script for the key hash.
+        preimage.extend_from_slice(&[0x19, 0x76, 0xa9, 0x14]);
+        preimage.extend_from_slice(key_hash);
+        preimage.extend_from_slice(&[0x88, 0xac]);
+        preimage.extend_from_slice(&utxo.value.to_le_bytes());
+        preimage.extend_from_slice(&input.sequence.to_le_bytes());
+        preimage.extend_from_slice(&double_sha256(&outputs));
+        preimage.extend_from_slice(&tx.lock_time.to_le_bytes());
+        preimage.extend_from_slice(&SIGHASH_ALL.to_le_bytes());
+        Ok(double_sha256(&preimage))
+    }
+
+    /// Add `signer`'s signature to every unfinalised input spending its
+    /// P2WPKH output, returning how many inputs were signed. Inputs that
+    /// ask for a sighash type other than `SIGHASH_ALL` are refused.
+    pub fn sign(&mut self, signer: &Secp256k1Signer) -> Result<usize> {
+        let script = p2wpkh_script(&signer.public_key_hash());
+        let public_key = signer.public_key_bytes();
+        let mut signed = 0;
+        for index in 0..self.inputs.len() {
+            let input = &self.inputs[index];
+            let ours = input
+                .witness_utxo
+                .as_ref()
+                .is_some_and(|utxo| utxo.script_pubkey == script);
+            if !ours || input.final_script_witness.is_some() {
+                continue;
+            }
+            if input.sighash_type.is_some_and(|sighash| sighash != SIGHASH_ALL) {
+                bail!("input {index} requests an unsupported sighash type");
+            }
+            let mut signature = signer.sign_digest_der(&self.p2wpkh_sighash(index)?)?;
+            signature.push(SIGHASH_ALL as u8);
+            let input = &mut self.inputs[index];
+            input.partial_sigs.retain(|(key, _)| *key != public_key);
+            input.partial_sigs.push((public_key.clone(), signature));
+            signed += 1;
+        }
+        Ok(signed)
+    }
+
+    /// Move each signed P2WPKH input's signature into its final witness.
+    pub fn finalize(&mut self) -> Result<()> {
+        for (index, input) in self.inputs.iter_mut().enumerate() {
+            if input.final_script_witness.is_some() {
+                continue;
+            }
+            let Some(key_hash) = input
+                .witness_utxo
+                .as_ref()
+                .and_then(|utxo| p2wpkh_key_hash(&utxo.script_pubkey))
+            else {
+                bail!("input {index} is not a P2WPKH spend this PSBT can finalise");
+            };
+            let (public_key, signature) = input
+                .partial_sigs
+                .iter()
+                .find(|(public_key, _)| hash160(public_key) == key_hash)
+                .cloned()
+                .ok_or_else(|| anyhow!("input {index} is not signed by its key"))?;
+            input.final_script_witness = Some(vec![signature, public_key]);
+            input.partial_sigs.clear();
+            input.sighash_type = None;
+        }
+        Ok(())
+    }
+
+    /// The network serialisation of a fully finalised PSBT.
+    pub fn extract_transaction(&self) -> Result<Vec<u8>> {
+        let witnesses = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                input
+                    .final_script_witness
+                    .clone()
+                    .ok_or_else(|| anyhow!("input {index} is not finalised"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.unsigned_tx.serialize_with_witnesses(&witnesses))
+    }
+}
+
+/// An unspent output, as listed by Esplora.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utxo {
+    pub txid: String,
+    pub vout: u32,
+    pub value: u64,
+}
+
+/// A PSBT paying `amount` sats to `to_script` from P2WPKH `from_script`
+/// outputs at `fee_rate` sat/vB.
+///
+/// The largest UTXOs are spent first and change above the dust limit goes
+/// back to `from_script`; smaller change is left to the fee.
+pub fn build_transfer(
+    utxos: &[Utxo],
+    from_script: &[u8],
+    to_script: &[u8],
+    amount: u64,
+    fee_rate: u64,
+) -> Result<Psbt> {
+    if amount < DUST_LIMIT_SATS {
+        bail!("amount must be at least {DUST_LIMIT_SATS} sats");
+    }
+    if p2wpkh_key_hash(from_script).is_none() {
+        bail!("transfers can only spend P2WPKH outputs");
+    }
+    let mut candidates = utxos.to_vec();
+    candidates.sort_by_key(|utxo| std::cmp::Reverse(utxo.value));
+
+    // Virtual sizes of a P2WPKH spend: 10.5 vB of overhead, 68 vB per
+    // input and 9 vB plus the script per output.
+    let fee = |inputs: u64, outputs: &[&[u8]]| {
+        let output_vbytes: u64 = outputs.iter().map(|script| 9 + script.len() as u64).sum();
+        (11 + 68 * inputs + output_vbytes) * fee_rate
+    };
+
+    let mut selected = Vec::new();
+    let mut total = 0u64;
+    for utxo in candidates {
+        total += utxo.value;
+        selected.push(utxo);
+        let needed = amount + fee(selected.len() as u64, &[to_script]);
+        if total >= needed {
+            break;
+        }
+    }
+    let fee_without_change = fee(selected.len() as u64, &[to_script]);
+    if total < amount + fee_without_change {
+        bail!(
+            "insufficient funds: {total} sats available, {} needed",
+            amount + fee_without_change
+        );
+    }
+
+    let mut outputs = vec![TxOut {
+        value: amount,
+        script_pubkey: to_script.to_vec(),
+    }];
+    let fee_with_change = fee(selected.len() as u64, &[to_script, from_script]);
+    let change = total.saturating_sub(amount + fee_with_change);
+    if change >= DUST_LIMIT_SATS {
+        outputs.push(TxOut {
+            value: change,
+            script_pubkey: from_script.to_vec(),
+        });
+    }
+
+    let inputs = selected
+        .iter()
+        .map(|utxo| {
+            let mut previous_txid: [u8; 32] = from_hex(&utxo.txid)?
+                .try_into()
+                .map_err(|_| anyhow!("txid '{}' is not 32 bytes", utxo.txid))?;
+            previous_txid.reverse();
+            Ok(TxIn {
+                previous_txid,
+                vout: utxo.vout,
+                script_sig: Vec::new(),
+                sequence: SEQUENCE_RBF,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut psbt = Psbt::from_unsigned_tx(Transaction {
+        version: 2,
+        inputs,
+        outputs,
+        lock_time: 0,
+    })?;
+    for (input, utxo) in psbt.inputs.iter_mut().zip(&selected) {
+        input.witness_utxo = Some(TxOut {
+            value: utxo.value,
+            script_pubkey: from_script.to_vec(),
+        });
+    }
+    Ok(psbt)
+}
+
+/// `OP_0 <20-byte key hash>`.
+pub fn p2wpkh_script(key_hash: &[u8; 20]) -> Vec<u8> {
+    let mut script = vec![0x00, 0x14];
+    script.extend_from_slice(key_hash);
+    script
+}
+
+/// Output script for a segwit witness version and program.
+pub fn witness_script(version: u8, program: &[u8]) -> Vec<u8> {
+    // OP_0, or OP_1..OP_16.
+    let opcode = if version == 0 { 0x00 } else { 0x50 + version };
+    let mut script = vec![opcode, program.len() as u8];
+    script.extend_from_slice(program);
+    script
+}
+
+fn p2wpkh_key_hash(script: &[u8]) -> Option<&[u8]> {
+    match script {
+        [0x00, 0x14, key_hash @ ..] if key_hash.len() == 20 => Some(key_hash),
+        _ => None,
+    }
+}
+
+fn hash160(bytes: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(bytes)).into()
+}
+
+fn double_sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(bytes)).into()
+}
+
+fn write_compact_size(out: &mut Vec<u8>, value: u64) {
+    match value {
+        0..=0xfc => out.push(value as u8),
+        0xfd..=0xffff => {
+            out.push(0xfd);
+            out.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(0xfe);
+            out.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        _ => {
+            out.push(0xff);
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+fn write_var_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_compact_size(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn write_pair(out: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    write_var_bytes(out, key);
+    write_var_bytes(out, value);
+}
+
+fn write_outpoint(out: &mut Vec<u8>, input: &TxIn) {
+    out.extend_from_slice(&input.previous_txid);
+    out.extend_from_slice(&input.vout.to_le_bytes());
+}
+
+fn write_txout(out: &mut Vec<u8>, output: &TxOut) {
+    out.extend_from_slice(&output.value.to_le_bytes());
+    write_var_bytes(out, &output.script_pubkey);
+}
+
+fn write_witness(out: &mut Vec<u8>, stack: &[Vec<u8>]) {
+    write_compact_size(out, stack.len() as u64);
+    for item in stack {
+        write_var_bytes(out, item);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            bail!("unexpected end of data");
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    fn compact_size(&mut self) -> Result<u64> {
+        Ok(match self.array::<1>()?[0] {
+            0xfd => u64::from(u16::from_le_bytes(self.array()?)),
+            0xfe => u64::from(u32::from_le_bytes(self.array()?)),
+            0xff => u64::from_le_bytes(self.array()?),
+            small => u64::from(small),
+        })
+    }
+
+    fn var_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = usize::try_from(self.compact_size()?)?;
+        self.take(len)
+    }
+
+    /// Key-value pairs up to the `0x00` separator.
+    fn map(&mut self) -> Result<Vec<RawPair>> {
+        let mut pairs: Vec<RawPair> = Vec::new();
+        loop {
+            let key = self.var_bytes()?;
+            if key.is_empty() {
+                return Ok(pairs);
+            }
+            if pairs.iter().any(|(existing, _)| existing == key) {
+                bail!("duplicate PSBT key {}", to_hex(key));
+            }
+            let value = self.var_bytes()?;
+            pairs.push((key.to_vec(), value.to_vec()));
+        }
+    }
+
+    fn finish(&self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            bail!("{} trailing bytes", self.bytes.len());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BIP-143 "native P2WPKH" example: input 1 spends 6 BTC from a P2WPKH
+    /// output; input 0 is a P2PK spend this module does not sign.
+    const BIP143_UNSIGNED_TX: &str = "0100000002fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f0000000000eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac11000000";
+    const BIP143_SECRET_KEY: &str = "619c335025c7f4012e556c2a58b2506e30b8511b53ade95ea316fd8c3286feb9";
+
+    fn signer(secret_key_hex: &str) -> Secp256k1Signer {
+        let secret_key = from_hex(secret_key_hex).unwrap().try_into().unwrap();
+        Secp256k1Signer::from_secret_key_bytes(secret_key).unwrap()
+    }
+
+    #[test]
+    fn signs_the_bip143_p2wpkh_example() {
+        let tx = Transaction::parse_unsigned(&from_hex(BIP143_UNSIGNED_TX).unwrap()).unwrap();
+        assert_eq!(to_hex(&tx.serialize()), BIP143_UNSIGNED_TX);
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        let signer = signer(BIP143_SECRET_KEY);
+        psbt.inputs[1].witness_utxo = Some(TxOut {
+            value: 600_000_000,
+            script_pubkey: p2wpkh_script(&signer.public_key_hash()),
+        });
+        assert_eq!(
+            to_hex(&psbt.p2wpkh_sighash(1).unwrap()),
+            "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670"
+        );
+
+        assert_eq!(psbt.sign(&signer).unwrap(), 1);
+        assert!(psbt.inputs[0].partial_sigs.is_empty());
+        assert_eq!(
+            to_hex(&psbt.inputs[1].partial_sigs[0].1),
+            "304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee01"
+        );
+
+        let decoded = Psbt::deserialize(&psbt.serialize()).unwrap();
+        assert_eq!(decoded, psbt);
+        // Input 0 has no witness_utxo, so the PSBT cannot be completed here.
+        assert!(psbt.clone().finalize().is_err());
+        assert!(psbt.extract_transaction().is_err());
+    }
+
+    #[test]
+    fn builds_signs_and_extracts_a_transfer() {
+        let signer = signer(BIP143_SECRET_KEY);
+        let from = p2wpkh_script(&signer.public_key_hash());
+        let to = p2wpkh_script(&[0x11; 20]);
+        let utxos = [
+            Utxo { txid: "aa".repeat(32), vout: 0, value: 5_000 },
+            Utxo { txid: "bb".repeat(32), vout: 3, value: 80_000 },
+            Utxo { txid: "cc".repeat(32), vout: 1, value: 30_000 },
+        ];
+
+        let mut psbt = build_transfer(&utxos, &from, &to, 100_000, 2).unwrap();
+        let tx = &psbt.unsigned_tx;
+        assert_eq!(tx.inputs.len(), 2, "largest UTXOs first");
+        assert_eq!(tx.inputs[0].previous_txid, [0xbb; 32]);
+        assert_eq!(tx.outputs[0], TxOut { value: 100_000, script_pubkey: to.clone() });
+        // 11 + 2 * 68 + 31 + 31 = 209 vB at 2 sat/vB.
+        assert_eq!(tx.outputs[1], TxOut { value: 110_000 - 100_000 - 418, script_pubkey: from.clone() });
+        assert!(build_transfer(&utxos, &from, &to, 115_000, 2).is_err());
+
+        assert_eq!(psbt.sign(&signer).unwrap(), 2);
+        psbt.finalize().unwrap();
+        let raw = psbt.extract_transaction().unwrap();
+        assert_eq!(&raw[4..6], &[0x00, 0x01], "segwit marker");
+        let witness = psbt.inputs[0].final_script_witness.as_ref().unwrap();
+        assert_eq!(witness[1], signer.public_key_bytes());
+        assert_eq!(psbt.unsigned_tx.txid().len(), 64);
+
+        // Change below the dust limit is left to the fee.
+        let psbt = build_transfer(&utxos[1..2], &from, &to, 79_600, 1).unwrap();
+        assert_eq!(psbt.unsigned_tx.outputs.len(), 1);
+    }
+
+    #[test]
+    fn rejects_malformed_psbts() {
+        assert!(Psbt::deserialize(b"psbt").is_err());
+        assert!(Psbt::deserialize(b"psbu\xff\x00").is_err());
+        let tx = Transaction::parse_unsigned(&from_hex(BIP143_UNSIGNED_TX).unwrap()).unwrap();
+        let mut bytes = Psbt::from_unsigned_tx(tx).unwrap().serialize();
+        bytes.push(0x00);
+        assert!(Psbt::deserialize(&bytes).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+//! Transfer amounts in an asset's base units.
+//!
+//! Amounts travel as decimal strings so that 18-decimal assets are not
+//! squeezed through `u64`. These wrap [`Amount`]'s parsing for code that
+//! works in `anyhow` and fail on anything that does not fit rather than
+//! truncating.
+
+use anyhow::Result;
+use kc_api_types::Amount;
+
+/// Parse a non-negative integer amount of base units.
+pub fn parse_amount(amount: &str) -> Result<u128> {
+    Ok(Amount::parse_base_units(amount)?)
+}
+
+/// Largest amount accepted for an asset with `decimals`; see
+/// [`Amount::max_base_units`].
+pub fn max_amount(decimals: u8) -> u128 {
+    Amount::max_base_units(decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kc_api_types::AmountError;
+    use proptest::prelude::*;
+
+    #[test]
+    fn rejects_non_integers_and_overflow() {
+        assert_eq!(parse_amount(" 0042 ").expect("padded integer"), 42);
+        assert_eq!(parse_amount(&u128::MAX.to_string()).expect("u128::MAX"), u128::MAX);
+        for bad in ["", " ", "-1", "+1", "1.5", "1e18", "0x10", "１"] {
+            let err = parse_amount(bad).expect_err(bad).to_string();
+            assert_eq!(err, "amount must be a non-negative integer", "{bad:?}");
+        }
+        let err = parse_amount("340282366920938463463374607431768211456").expect_err("u128::MAX + 1");
+        assert_eq!(err.to_string(), "amount overflows 128 bits");
+
+        assert_eq!(max_amount(0), u128::from(u64::MAX));
+        assert_eq!(max_amount(6), (u128::from(u64::MAX) + 1) * 1_000_000 - 1);
+        assert_eq!(max_amount(20), u128::MAX);
+        assert_eq!(max_amount(u8::MAX), u128::MAX);
+
+        let flower = Amount::parse("18446744073709551615999999", 6).expect("u64::MAX FloweR");
+        assert_eq!((flower.base_units(), flower.decimals()), (max_amount(6), 6));
+        let err = Amount::parse("18446744073709551616000000", 6).expect_err("past the FloweR cap");
+        assert_eq!(err, AmountError::AboveMaximum { decimals: 6 });
+        assert_eq!(Amount::parse("1.5", 18), Err(AmountError::NotAnInteger));
+    }
+
+    proptest! {
+        #[test]
+        fn amounts_roundtrip_through_strings(value in any::<u128>()) {
+            prop_assert_eq!(parse_amount(&value.to_string()).unwrap(), value);
+            prop_assert_eq!(parse_amount(&format!("000{value}")).unwrap(), value);
+        }
+
+        #[test]
+        fn amounts_past_u128_overflow(value in (u128::MAX / 10 + 1).., extra in 0u8..10) {
+            let err = parse_amount(&format!("{value}{extra}")).unwrap_err();
+            prop_assert_eq!(err.to_string(), "amount overflows 128 bits");
+        }
+
+        #[test]
+        fn max_amount_keeps_whole_tokens_within_u64(decimals in 0u8..=19) {
+            let scale = 10u128.pow(u32::from(decimals));
+            let limit = max_amount(decimals);
+            prop_assert_eq!(limit / scale, u128::from(u64::MAX));
+            prop_assert_eq!(limit % scale, scale - 1);
+        }
+    }
+}
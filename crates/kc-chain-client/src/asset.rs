@@ -0,0 +1,63 @@
+//! What an asset symbol stands for on a chain.
+//!
+//! A symbol is either the chain's native coin or a token held by a contract,
+//! such as an ERC-20. Adapters that hold tokens keep a descriptor per symbol
+//! so they know which contract to query and what decimals its amounts use.
+
+use kc_api_types::AssetSymbol;
+
+use crate::config::AssetConfig;
+
+/// ERC-20's suggested decimals, for tokens registered without any.
+pub const DEFAULT_TOKEN_DECIMALS: u8 = 18;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetKind {
+    /// The chain's own coin, e.g. ETH on Ethereum.
+    Native,
+    /// A token held by the contract at this address.
+    Contract(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetDescriptor {
+    pub symbol: AssetSymbol,
+    pub kind: AssetKind,
+    pub decimals: u8,
+}
+
+impl AssetDescriptor {
+    pub fn native(symbol: &str, decimals: u8) -> Self {
+        Self {
+            symbol: AssetSymbol(symbol.to_owned()),
+            kind: AssetKind::Native,
+            decimals,
+        }
+    }
+
+    pub fn contract(symbol: &str, address: &str, decimals: u8) -> Self {
+        Self {
+            symbol: AssetSymbol(symbol.to_owned()),
+            kind: AssetKind::Contract(address.to_owned()),
+            decimals,
+        }
+    }
+
+    /// The token contract's address; `None` for the native coin.
+    pub fn contract_address(&self) -> Option<&str> {
+        match &self.kind {
+            AssetKind::Native => None,
+            AssetKind::Contract(address) => Some(address),
+        }
+    }
+}
+
+impl AssetConfig {
+    /// `symbol` as configured: a token when it names a contract, else native.
+    pub fn descriptor(&self, symbol: &str) -> AssetDescriptor {
+        match &self.contract {
+            Some(address) => AssetDescriptor::contract(symbol, address, self.decimals),
+            None => AssetDescriptor::native(symbol, self.decimals),
+        }
+    }
+}
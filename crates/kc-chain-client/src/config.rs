@@ -0,0 +1,275 @@
+//! Chains described in a `chains.toml` file.
+//!
+//! ```toml
+//! [[chain]]
+//! slug = "sepolia"
+//! adapter = "evm"
+//! endpoints = ["https://rpc.sepolia.example"]
+//! evm_chain_id = 11155111
+//!
+//! [chain.assets.ETH]
+//! decimals = 18
+//! fee_payment = true
+//!
+//! [chain.assets.USDC]
+//! decimals = 6
+//! asset_type = "erc20"
+//! contract = "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238"
+//!
+//! [signing]
+//! namespace = "acme"
+//! version = "v2"
+//! transaction_tag = "tx"
+//! custom_purposes = ["delegation"]
+//! ```
+//!
+//! This crate knows no concrete adapters: [`ChainRegistryConfig::load`]
+//! checks the file's shape, and the service builds and registers an adapter
+//! for each entry the way it does for chains added at runtime.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Most decimals an asset may have; `10^38` still fits in `u128`.
+pub const MAX_DECIMALS: u8 = 38;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainRegistryConfig {
+    #[serde(default, rename = "chain")]
+    pub chains: Vec<ChainConfig>,
+    /// The signing domain payloads are signed under; unset keeps the
+    /// service's.
+    pub signing: Option<SigningConfig>,
+}
+
+/// Parts of the `{namespace}:{version}:{purpose_tag}` signing domain.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningConfig {
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default = "default_transaction_tag")]
+    pub transaction_tag: String,
+    #[serde(default = "default_auth_tag")]
+    pub auth_tag: String,
+    #[serde(default = "default_proof_tag")]
+    pub proof_tag: String,
+    #[serde(default)]
+    pub custom_purposes: Vec<String>,
+}
+
+fn default_namespace() -> String {
+    "keycortex".to_owned()
+}
+
+fn default_version() -> String {
+    "v1".to_owned()
+}
+
+fn default_transaction_tag() -> String {
+    "transaction".to_owned()
+}
+
+fn default_auth_tag() -> String {
+    "auth".to_owned()
+}
+
+fn default_proof_tag() -> String {
+    "proof".to_owned()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainConfig {
+    /// Chain id clients send, e.g. `flowcortex-l1`.
+    pub slug: String,
+    /// Adapter kind, e.g. `flowcortex` or `evm`.
+    pub adapter: String,
+    /// Node URLs, in order of preference.
+    pub endpoints: Vec<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Assets by symbol. Empty leaves the service's defaults in place.
+    #[serde(default)]
+    pub assets: BTreeMap<String, AssetConfig>,
+    pub evm_chain_id: Option<u64>,
+    pub bech32_prefix: Option<String>,
+    pub explorer_tx_url: Option<String>,
+    pub explorer_address_url: Option<String>,
+    pub explorer_block_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AssetConfig {
+    pub decimals: u8,
+    #[serde(default = "native_asset_type")]
+    pub asset_type: String,
+    /// Whether fees can be paid in this asset.
+    #[serde(default)]
+    pub fee_payment: bool,
+    /// ERC-20 contract address (EVM only).
+    pub contract: Option<String>,
+    /// Bank denom, e.g. `uatom` (Cosmos only).
+    pub denom: Option<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+fn native_asset_type() -> String {
+    "native".to_owned()
+}
+
+impl ChainRegistryConfig {
+    /// Read and check the file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("chain config {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that need no knowledge of the adapter kinds.
+    fn validate(&self) -> Result<()> {
+        let mut slugs = HashSet::new();
+        for chain in &self.chains {
+            if chain.slug.trim().is_empty() {
+                bail!("chain slug must not be empty");
+            }
+            if !slugs.insert(chain.slug.as_str()) {
+                bail!("chain '{}' is listed more than once", chain.slug);
+            }
+            if chain.endpoints.is_empty() {
+                bail!("chain '{}' needs at least one endpoint", chain.slug);
+            }
+            for (symbol, asset) in &chain.assets {
+                if symbol.trim().is_empty() {
+                    bail!("chain '{}' has an asset with an empty symbol", chain.slug);
+                }
+                if asset.decimals > MAX_DECIMALS {
+                    bail!(
+                        "chain '{}' asset {symbol}: decimals must be at most {MAX_DECIMALS}",
+                        chain.slug
+                    );
+                }
+                match (asset.asset_type.as_str(), &asset.contract) {
+                    ("erc20", None) => {
+                        bail!("chain '{}' asset {symbol}: erc20 assets need a contract", chain.slug)
+                    }
+                    ("native", Some(_)) => bail!(
+                        "chain '{}' asset {symbol}: a contract asset cannot be native",
+                        chain.slug
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        if let Some(signing) = &self.signing {
+            for (field, value) in [
+                ("namespace", &signing.namespace),
+                ("version", &signing.version),
+                ("transaction_tag", &signing.transaction_tag),
+                ("auth_tag", &signing.auth_tag),
+                ("proof_tag", &signing.proof_tag),
+            ] {
+                if value.trim().is_empty() || value.contains(':') {
+                    bail!("signing {field} must be non-empty and contain no ':'");
+                }
+            }
+            let tags = [&signing.transaction_tag, &signing.auth_tag, &signing.proof_tag];
+            if tags.iter().enumerate().any(|(i, tag)| tags[..i].contains(tag)) {
+                bail!("signing purpose tags must differ from each other");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AssetDescriptor;
+
+    #[test]
+    fn parses_chains_and_rejects_inconsistent_entries() {
+        let config = ChainRegistryConfig::parse(
+            r#"
+            [[chain]]
+            slug = "flowcortex-l1"
+            adapter = "flowcortex"
+            endpoints = ["http://node-a:8082", "http://node-b:8082"]
+
+            [chain.assets.PROOF]
+            decimals = 18
+            fee_payment = true
+
+            [[chain]]
+            slug = "cosmoshub"
+            adapter = "cosmos"
+            endpoints = ["https://lcd.example"]
+            enabled = false
+            bech32_prefix = "cosmos"
+            assets = { ATOM = { decimals = 6, denom = "uatom" } }
+            "#,
+        )
+        .expect("valid config");
+        assert_eq!(config.chains.len(), 2);
+        let flowcortex = &config.chains[0];
+        assert!(flowcortex.enabled);
+        assert_eq!(flowcortex.endpoints.len(), 2);
+        assert_eq!(flowcortex.assets["PROOF"].asset_type, "native");
+        assert!(flowcortex.assets["PROOF"].fee_payment);
+        let cosmos = &config.chains[1];
+        assert!(!cosmos.enabled);
+        assert_eq!(cosmos.assets["ATOM"].denom.as_deref(), Some("uatom"));
+        assert_eq!(cosmos.assets["ATOM"].descriptor("ATOM"), AssetDescriptor::native("ATOM", 6));
+
+        assert_eq!(ChainRegistryConfig::parse("").expect("empty config"), ChainRegistryConfig::default());
+        let signing = ChainRegistryConfig::parse("[signing]\nnamespace = \"acme\"\nauth_tag = \"login\"")
+            .expect("signing table")
+            .signing
+            .expect("signing table should parse");
+        assert_eq!(
+            (signing.namespace.as_str(), signing.version.as_str(), signing.auth_tag.as_str()),
+            ("acme", "v1", "login")
+        );
+        for (bad, expected) in [
+            (
+                "[[chain]]\nslug = \"a\"\nadapter = \"evm\"\nendpoints = []",
+                "needs at least one endpoint",
+            ),
+            (
+                "[[chain]]\nslug = \"a\"\nadapter = \"evm\"\nendpoints = [\"http://x\"]\n[[chain]]\nslug = \"a\"\nadapter = \"evm\"\nendpoints = [\"http://y\"]",
+                "listed more than once",
+            ),
+            (
+                "[[chain]]\nslug = \"a\"\nadapter = \"evm\"\nendpoints = [\"http://x\"]\nassets = { ETH = { decimals = 40 } }",
+                "decimals must be at most 38",
+            ),
+            (
+                "[[chain]]\nslug = \"a\"\nadapter = \"evm\"\nendpoints = [\"http://x\"]\nassets = { USDC = { decimals = 6, asset_type = \"erc20\" } }",
+                "erc20 assets need a contract",
+            ),
+            (
+                "[[chain]]\nslug = \"a\"\nadapter = \"evm\"\nendpoint = \"http://x\"",
+                "unknown field",
+            ),
+            ("[signing]\nauth_tag = \"a:b\"", "contain no ':'"),
+            ("[signing]\nproof_tag = \"auth\"", "must differ"),
+        ] {
+            let err = format!("{:#}", ChainRegistryConfig::parse(bad).expect_err(expected));
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+}
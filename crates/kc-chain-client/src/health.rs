@@ -0,0 +1,177 @@
+//! Chain reachability, as reported by [`ChainAdapter::health`].
+//!
+//! [`probe_all`] checks a set of adapters concurrently, each bounded by a
+//! timeout, and [`ChainRegistry::record_health`] keeps the latest result per
+//! chain so readiness checks can tell an unreachable node apart from a
+//! failing wallet service without calling out themselves.
+
+use anyhow::Result;
+use futures_util::future::join_all;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::ChainAdapter;
+
+/// Result of one [`ChainAdapter::health`] check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterHealth {
+    pub reachable: bool,
+    pub latency_ms: u64,
+    /// Chain tip height, if the check reads one.
+    pub block_height: Option<u64>,
+    /// Why the chain is unreachable.
+    pub detail: Option<String>,
+}
+
+impl AdapterHealth {
+    /// Time `check`, which resolves to the chain tip height when it knows one.
+    pub async fn measure(check: impl Future<Output = Result<Option<u64>>>) -> Self {
+        let started = Instant::now();
+        let outcome = check.await;
+        let latency_ms = elapsed_ms(started);
+        match outcome {
+            Ok(block_height) => Self {
+                reachable: true,
+                latency_ms,
+                block_height,
+                detail: None,
+            },
+            Err(err) => Self::unreachable(latency_ms, format!("{err:#}")),
+        }
+    }
+
+    pub fn unreachable(latency_ms: u64, detail: impl Into<String>) -> Self {
+        Self {
+            reachable: false,
+            latency_ms,
+            block_height: None,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// The latest [`AdapterHealth`] of a registered chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHealthStatus {
+    pub health: AdapterHealth,
+    pub checked_at_epoch_ms: u128,
+}
+
+impl ChainHealthStatus {
+    pub fn now(health: AdapterHealth) -> Self {
+        Self {
+            health,
+            checked_at_epoch_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Check every adapter concurrently. A check still running after `timeout`
+/// counts as unreachable.
+pub async fn probe_all(adapters: Vec<Arc<dyn ChainAdapter>>, timeout: Duration) -> Vec<(String, AdapterHealth)> {
+    join_all(adapters.into_iter().map(|adapter| async move {
+        let started = Instant::now();
+        let health = match tokio::time::timeout(timeout, adapter.health()).await {
+            Ok(health) => health,
+            Err(_) => AdapterHealth::unreachable(
+                elapsed_ms(started),
+                format!("health check timed out after {}ms", timeout.as_millis()),
+            ),
+        };
+        (adapter.chain_id().to_owned(), health)
+    }))
+    .await
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BalanceResult, ChainRegistry, SubmitTxRequest, SubmitTxResult, TxStatusRequest,
+        TxStatusResult,
+    };
+    use anyhow::bail;
+    use async_trait::async_trait;
+    use kc_api_types::{AssetSymbol, WalletAddress};
+
+    struct Node {
+        chain_id: &'static str,
+        delay: Duration,
+        height: Option<u64>,
+    }
+
+    #[async_trait]
+    impl ChainAdapter for Node {
+        fn chain_id(&self) -> &str {
+            self.chain_id
+        }
+
+        async fn health(&self) -> AdapterHealth {
+            AdapterHealth::measure(async {
+                tokio::time::sleep(self.delay).await;
+                match self.height {
+                    Some(height) => Ok(Some(height)),
+                    None => bail!("connection refused"),
+                }
+            })
+            .await
+        }
+
+        async fn get_balance(&self, _: &WalletAddress, _: &AssetSymbol) -> Result<BalanceResult> {
+            bail!("unused")
+        }
+
+        async fn submit_transaction(&self, _: SubmitTxRequest) -> Result<SubmitTxResult> {
+            bail!("unused")
+        }
+
+        async fn get_transaction_status(&self, _: TxStatusRequest) -> Result<TxStatusResult> {
+            bail!("unused")
+        }
+    }
+
+    fn node(chain_id: &'static str, delay_ms: u64, height: Option<u64>) -> Arc<dyn ChainAdapter> {
+        Arc::new(Node {
+            chain_id,
+            delay: Duration::from_millis(delay_ms),
+            height,
+        })
+    }
+
+    #[tokio::test]
+    async fn probes_concurrently_and_times_out_slow_chains() {
+        let mut registry = ChainRegistry::default();
+        registry.register(node("up", 0, Some(42)));
+        registry.register(node("down", 0, None));
+        registry.register(node("slow", 5_000, Some(1)));
+
+        let started = Instant::now();
+        for (chain_id, health) in probe_all(registry.adapters(), Duration::from_millis(200)).await {
+            registry.record_health(&chain_id, health);
+        }
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let up = &registry.health("up").unwrap().health;
+        assert!(up.reachable);
+        assert_eq!(up.block_height, Some(42));
+        let down = &registry.health("down").unwrap().health;
+        assert!(!down.reachable);
+        assert_eq!(down.detail.as_deref(), Some("connection refused"));
+        let slow = &registry.health("slow").unwrap().health;
+        assert!(!slow.reachable);
+        assert!(slow.detail.as_deref().unwrap().contains("timed out"));
+
+        registry.record_health("unregistered", up.clone());
+        assert!(registry.health("unregistered").is_none());
+        registry.register(node("up", 0, Some(43)));
+        assert!(registry.health("up").is_none(), "re-registering drops stale health");
+    }
+}
@@ -0,0 +1,65 @@
+//! HTTP clients shared by every adapter in a [`ChainRegistry`].
+//!
+//! reqwest pools connections per client, so adapters handed the same
+//! [`SharedHttp`] reuse connections to a node instead of each keeping a pool
+//! of its own. [`HttpClientConfig`] sizes the pool and bounds every request.
+//!
+//! [`ChainRegistry`]: crate::ChainRegistry
+
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    /// Idle connections kept open per node.
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection is kept before it is closed.
+    pub pool_idle_timeout: Duration,
+    pub connect_timeout: Duration,
+    /// Whole-request limit, from connect to the end of the body.
+    pub request_timeout: Duration,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 16,
+            pool_idle_timeout: Duration::from_secs(90),
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// One pooled client per TLS policy.
+#[derive(Debug, Clone)]
+pub struct SharedHttp {
+    pub verified: reqwest::Client,
+    /// Accepts self-signed certificates, as the FlowCortex demo nodes use.
+    pub self_signed: reqwest::Client,
+}
+
+impl SharedHttp {
+    pub fn new(config: &HttpClientConfig) -> Result<Self> {
+        let build = |accept_invalid_certs: bool| {
+            reqwest::Client::builder()
+                .pool_max_idle_per_host(config.pool_max_idle_per_host)
+                .pool_idle_timeout(config.pool_idle_timeout)
+                .connect_timeout(config.connect_timeout)
+                .timeout(config.request_timeout)
+                .danger_accept_invalid_certs(accept_invalid_certs)
+                .build()
+                .context("build chain http client")
+        };
+        Ok(Self {
+            verified: build(false)?,
+            self_signed: build(true)?,
+        })
+    }
+}
+
+impl Default for SharedHttp {
+    fn default() -> Self {
+        Self::new(&HttpClientConfig::default()).expect("failed to build reqwest client")
+    }
+}
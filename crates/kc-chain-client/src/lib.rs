@@ -1,25 +1,52 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use async_trait::async_trait;
-use kc_api_types::{AssetSymbol, ChainId, WalletAddress};
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+pub mod amount;
+pub mod asset;
+pub mod config;
+pub mod health;
+pub mod http;
+
+pub use asset::{AssetDescriptor, AssetKind};
+pub use config::{AssetConfig, ChainConfig, ChainRegistryConfig, SigningConfig};
+pub use health::{AdapterHealth, ChainHealthStatus, probe_all};
+pub use http::{HttpClientConfig, SharedHttp};
+
 #[derive(Debug, Clone)]
 pub struct BalanceResult {
     pub wallet_address: WalletAddress,
     pub chain: ChainId,
     pub asset: AssetSymbol,
-    pub amount: String,
+    /// Base units. Adapters don't know asset decimals and report 0; they
+    /// live in chain config.
+    pub amount: Amount,
 }
 
 #[derive(Debug, Clone)]
 pub struct SubmitTxRequest {
     pub from: WalletAddress,
     pub to: WalletAddress,
-    pub amount: String,
+    /// Parsed and bounded for the asset's decimals before it gets here.
+    pub amount: Amount,
     pub asset: AssetSymbol,
     pub chain: ChainId,
     pub signed_payload: String,
+    /// Chain-specific transaction data for adapters that broadcast more
+    /// than the KeyCortex signature.
+    pub chain_payload: Option<ChainPayload>,
+}
+
+/// Transaction data in a chain's own format, carried by [`SubmitTxRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainPayload {
+    /// A serialised, fully signed transaction ready to broadcast.
+    RawTransaction(Vec<u8>),
+    /// A BIP-174 partially signed Bitcoin transaction, finalised by the
+    /// adapter before broadcast.
+    Psbt(Vec<u8>),
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +55,34 @@ pub struct SubmitTxResult {
     pub accepted: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct FeeEstimateRequest {
+    pub from: WalletAddress,
+    pub to: WalletAddress,
+    pub amount: String,
+    pub asset: AssetSymbol,
+    pub chain: ChainId,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeEstimate {
+    /// Fee in base units of `fee_asset`.
+    pub fee: String,
+    /// Asset the fee is charged in, which need not be the transferred asset.
+    pub fee_asset: AssetSymbol,
+}
+
+/// What submitting a transfer would do, from
+/// [`ChainAdapter::simulate_transaction`].
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    pub would_accept: bool,
+    /// Why the chain would refuse the transfer.
+    pub reason: Option<String>,
+    /// `None` when the adapter cannot estimate fees.
+    pub fee: Option<FeeEstimate>,
+}
+
 #[derive(Debug, Clone)]
 pub struct TxStatusRequest {
     pub tx_hash: String,
@@ -39,11 +94,133 @@ pub struct TxStatusResult {
     pub tx_hash: String,
     pub status: String,
     pub accepted: bool,
+    /// Height of the block that included the transaction, if the chain reports it.
+    pub block_height: Option<u64>,
+    /// Blocks on top of (and including) `block_height`.
+    pub confirmations: Option<u64>,
+}
+
+/// A transfer as recorded on chain, from [`ChainAdapter::list_transactions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainTransaction {
+    pub tx_hash: String,
+    pub from: WalletAddress,
+    pub to: WalletAddress,
+    pub asset: AssetSymbol,
+    /// Integer amount in the asset's base units.
+    pub amount: String,
+    pub block_height: Option<u64>,
+}
+
+/// One page of a wallet's on-chain transfers, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct ChainTransactionPage {
+    pub transactions: Vec<ChainTransaction>,
+    /// Opaque position to pass back as `cursor`; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// How a chain orders and de-duplicates transactions from one account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceStrategy {
+    /// No replay counter; the request `nonce` is not checked.
+    None,
+    /// KeyCortex tracks a strictly increasing counter per wallet.
+    Sequential,
+    /// The chain's account nonce (EVM-style) from [`ChainAdapter::get_account_nonce`].
+    ChainQueried,
+    /// Transactions reference a recent block hash instead of a counter
+    /// (Solana-style), from [`ChainAdapter::get_recent_blockhash`].
+    RecentBlockhash,
+}
+
+impl NonceStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Sequential => "sequential",
+            Self::ChainQueried => "chain_queried",
+            Self::RecentBlockhash => "recent_blockhash",
+        }
+    }
+
+    /// Whether KeyCortex keeps a per-wallet nonce for this chain.
+    pub fn tracks_nonce(self) -> bool {
+        matches!(self, Self::Sequential | Self::ChainQueried)
+    }
 }
 
 #[async_trait]
 pub trait ChainAdapter: Send + Sync {
     fn chain_id(&self) -> &str;
+
+    fn nonce_strategy(&self) -> NonceStrategy {
+        NonceStrategy::Sequential
+    }
+
+    /// Next nonce the chain expects from `wallet_address`, for
+    /// [`NonceStrategy::ChainQueried`] chains.
+    async fn get_account_nonce(&self, _wallet_address: &WalletAddress) -> Result<u64> {
+        bail!("{} does not expose account nonces", self.chain_id())
+    }
+
+    /// A block hash new transactions may reference, for
+    /// [`NonceStrategy::RecentBlockhash`] chains.
+    async fn get_recent_blockhash(&self) -> Result<String> {
+        bail!("{} does not use recent block hashes", self.chain_id())
+    }
+
+    /// Network fee the transfer in `req` is expected to cost, before it is signed.
+    async fn estimate_fee(&self, _req: FeeEstimateRequest) -> Result<FeeEstimate> {
+        bail!("{} does not estimate fees", self.chain_id())
+    }
+
+    /// What submitting `req` would do, without broadcasting it. By default
+    /// the transfer is accepted when the sender's balance covers the amount,
+    /// plus the fee when it is charged in the same asset.
+    async fn simulate_transaction(&self, req: SubmitTxRequest) -> Result<SimulationResult> {
+        let balance = self.get_balance(&req.from, &req.asset).await?.amount.base_units();
+        let fee = self
+            .estimate_fee(FeeEstimateRequest {
+                from: req.from.clone(),
+                to: req.to.clone(),
+                amount: req.amount.to_string(),
+                asset: req.asset.clone(),
+                chain: req.chain.clone(),
+            })
+            .await
+            .ok();
+        let fee_in_asset = match &fee {
+            Some(fee) if fee.fee_asset == req.asset => amount::parse_amount(&fee.fee)?,
+            _ => 0,
+        };
+        let needed = req.amount.base_units().saturating_add(fee_in_asset);
+        let reason = (balance < needed).then(|| {
+            format!("insufficient balance: {balance} {} available, {needed} needed", req.asset.0)
+        });
+        Ok(SimulationResult {
+            would_accept: reason.is_none(),
+            reason,
+            fee,
+        })
+    }
+
+    /// Transfers sent or received by `wallet_address` as the chain records
+    /// them, newest first, starting after `cursor`.
+    async fn list_transactions(
+        &self,
+        _wallet_address: &WalletAddress,
+        _cursor: Option<&str>,
+        _limit: usize,
+    ) -> Result<ChainTransactionPage> {
+        bail!("{} does not list transactions", self.chain_id())
+    }
+
+    /// Whether the chain's node answers, and how quickly.
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async { bail!("{} does not report health", self.chain_id()) }).await
+    }
+
     async fn get_balance(&self, wallet_address: &WalletAddress, asset: &AssetSymbol) -> Result<BalanceResult>;
     async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult>;
     async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult>;
@@ -52,14 +229,58 @@ pub trait ChainAdapter: Send + Sync {
 #[derive(Default)]
 pub struct ChainRegistry {
     adapters: HashMap<String, Arc<dyn ChainAdapter>>,
+    /// Latest probe of each registered adapter, from [`Self::record_health`].
+    health: HashMap<String, ChainHealthStatus>,
+    http: SharedHttp,
 }
 
 impl ChainRegistry {
+    /// Hand `http` to adapters built for this registry.
+    pub fn with_http(mut self, http: SharedHttp) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// The clients adapters built for this registry should send through.
+    pub fn http(&self) -> &SharedHttp {
+        &self.http
+    }
+
     pub fn register(&mut self, adapter: Arc<dyn ChainAdapter>) {
+        self.health.remove(adapter.chain_id());
         self.adapters.insert(adapter.chain_id().to_owned(), adapter);
     }
 
     pub fn adapter(&self, chain_id: &str) -> Option<Arc<dyn ChainAdapter>> {
         self.adapters.get(chain_id).cloned()
     }
+
+    pub fn remove(&mut self, chain_id: &str) -> Option<Arc<dyn ChainAdapter>> {
+        self.health.remove(chain_id);
+        self.adapters.remove(chain_id)
+    }
+
+    /// Every registered adapter, for [`probe_all`].
+    pub fn adapters(&self) -> Vec<Arc<dyn ChainAdapter>> {
+        self.adapters.values().cloned().collect()
+    }
+
+    /// Keep `health` as the latest status of `chain_id`. Ignored if the
+    /// chain was removed while it was being probed.
+    pub fn record_health(&mut self, chain_id: &str, health: AdapterHealth) {
+        if self.adapters.contains_key(chain_id) {
+            self.health.insert(chain_id.to_owned(), ChainHealthStatus::now(health));
+        }
+    }
+
+    /// Latest recorded status of `chain_id`; `None` until it is first probed.
+    pub fn health(&self, chain_id: &str) -> Option<&ChainHealthStatus> {
+        self.health.get(chain_id)
+    }
+
+    pub fn chain_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.adapters.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
 }
@@ -0,0 +1,480 @@
+//! [`ChainAdapter`] for Cosmos SDK chains over the REST (LCD) gateway.
+//!
+//! The LCD serves the SDK's gRPC query and tx services as JSON, so this
+//! covers the same calls as gRPC without a protobuf toolchain:
+//!
+//! - balances from `x/bank` `by_denom`, per asset symbol mapped to a denom
+//!   with [`CosmosAdapter::with_denom`] or given as the denom itself;
+//! - nonces from the `x/auth` account sequence;
+//! - `submit_transaction` broadcasts `signed_payload`, a signed `TxRaw` as
+//!   base64 (or `0x`-prefixed hex), with `BROADCAST_MODE_SYNC`;
+//! - status from the tx service, `pending` until the tx is in a block.
+//!
+//! Wallet addresses may be bech32 under the chain's prefix or KeyCortex
+//! `0x` addresses, which carry the same 20 bytes as an Ed25519 key's Cosmos
+//! address (see [`kc_crypto::Ed25519PublicKey::bech32_address`]).
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+use kc_chain_client::amount::parse_amount;
+use kc_chain_client::{
+    AdapterHealth, BalanceResult, ChainAdapter, NonceStrategy, SubmitTxRequest, SubmitTxResult, TxStatusRequest,
+    TxStatusResult,
+};
+use kc_crypto::encoding::{from_bech32, from_hex, to_bech32};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// gRPC `NotFound`, which the gateway can return with a non-404 status.
+const GRPC_NOT_FOUND: i64 = 5;
+
+pub struct CosmosAdapter {
+    chain_id: String,
+    endpoint: String,
+    bech32_prefix: String,
+    /// Upper-cased symbol → bank denom.
+    denoms: HashMap<String, String>,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct BalanceResponse {
+    balance: Option<Coin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Coin {
+    amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountResponse {
+    account: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxEnvelope {
+    tx_response: TxResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxResponse {
+    txhash: String,
+    /// Non-zero when the tx failed `CheckTx` or execution.
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    raw_log: String,
+    /// `"0"` for a broadcast that is not yet in a block.
+    #[serde(default)]
+    height: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestBlock {
+    block: BlockBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockBody {
+    header: BlockHeader,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockHeader {
+    height: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GatewayError {
+    code: Option<i64>,
+}
+
+impl CosmosAdapter {
+    /// `bech32_prefix` is the chain's account prefix, e.g. `cosmos` or `osmo`.
+    pub fn new(chain_id: &str, endpoint: &str, bech32_prefix: &str) -> Self {
+        Self {
+            chain_id: chain_id.to_owned(),
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            bech32_prefix: bech32_prefix.to_owned(),
+            denoms: HashMap::new(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Send requests through `http`, e.g. a registry's shared pool.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Serve `symbol` balances from the bank denom `denom`, e.g. `ATOM` → `uatom`.
+    pub fn with_denom(mut self, symbol: &str, denom: &str) -> Self {
+        self.denoms
+            .insert(symbol.to_ascii_uppercase(), denom.to_owned());
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn bech32_prefix(&self) -> &str {
+        &self.bech32_prefix
+    }
+
+    /// `wallet_address` as bech32 under this chain's prefix.
+    pub fn account_address(&self, wallet_address: &WalletAddress) -> Result<String> {
+        let address = wallet_address.0.as_str();
+        if let Some(digits) = address.strip_prefix("0x") {
+            let bytes = from_hex(digits)?;
+            if bytes.len() != 20 {
+                bail!("'{address}' is not a 20-byte address");
+            }
+            return to_bech32(&self.bech32_prefix, &bytes);
+        }
+        let (hrp, _) = from_bech32(address)?;
+        if hrp != self.bech32_prefix {
+            bail!(
+                "'{address}' is not a {} address (expected prefix '{}')",
+                self.chain_id,
+                self.bech32_prefix
+            );
+        }
+        Ok(address.to_ascii_lowercase())
+    }
+
+    fn denom<'a>(&'a self, asset: &'a str) -> &'a str {
+        self.denoms
+            .get(&asset.to_ascii_uppercase())
+            .map(String::as_str)
+            .unwrap_or(asset)
+    }
+
+    /// GET `path`; `None` if the gateway reports it not found.
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<Option<T>> {
+        let response = self
+            .http
+            .get(format!("{}{path}", self.endpoint))
+            .query(query)
+            .send()
+            .await
+            .with_context(|| format!("GET {path} transport"))?;
+        let status = response.status();
+        if status.is_success() {
+            return response
+                .json()
+                .await
+                .map(Some)
+                .with_context(|| format!("GET {path} response"));
+        }
+        let text = response.text().await.unwrap_or_default();
+        let not_found = status == reqwest::StatusCode::NOT_FOUND
+            || serde_json::from_str::<GatewayError>(&text)
+                .is_ok_and(|err| err.code == Some(GRPC_NOT_FOUND));
+        if not_found {
+            return Ok(None);
+        }
+        Err(anyhow!("GET {path} HTTP {status}: {text}"))
+    }
+
+    async fn latest_height(&self) -> Result<u64> {
+        let latest: LatestBlock = self
+            .get("/cosmos/base/tendermint/v1beta1/blocks/latest", &[])
+            .await?
+            .ok_or_else(|| anyhow!("{} returned no latest block", self.chain_id))?;
+        parse_height(&latest.block.header.height)
+    }
+}
+
+fn parse_height(height: &str) -> Result<u64> {
+    height
+        .parse()
+        .map_err(|err| anyhow!("invalid block height '{height}': {err}"))
+}
+
+/// Sequence of a `BaseAccount`, or of the one inside a vesting or module account.
+fn account_sequence(account: &Value) -> Option<u64> {
+    if let Some(sequence) = account.get("sequence") {
+        return sequence.as_str()?.parse().ok();
+    }
+    ["base_account", "base_vesting_account"]
+        .iter()
+        .find_map(|field| account.get(*field).and_then(account_sequence))
+}
+
+#[async_trait]
+impl ChainAdapter for CosmosAdapter {
+    fn chain_id(&self) -> &str {
+        &self.chain_id
+    }
+
+    fn nonce_strategy(&self) -> NonceStrategy {
+        NonceStrategy::ChainQueried
+    }
+
+    /// The account sequence; `0` for an account the chain has not seen yet.
+    async fn get_account_nonce(&self, wallet_address: &WalletAddress) -> Result<u64> {
+        let address = self.account_address(wallet_address)?;
+        let Some(response) = self
+            .get::<AccountResponse>(&format!("/cosmos/auth/v1beta1/accounts/{address}"), &[])
+            .await?
+        else {
+            return Ok(0);
+        };
+        account_sequence(&response.account)
+            .ok_or_else(|| anyhow!("account {address} has no sequence: {}", response.account))
+    }
+
+    /// Reachable when the gateway serves the latest block.
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async { self.latest_height().await.map(Some) }).await
+    }
+
+    async fn get_balance(
+        &self,
+        wallet_address: &WalletAddress,
+        asset: &AssetSymbol,
+    ) -> Result<BalanceResult> {
+        let address = self.account_address(wallet_address)?;
+        let response: Option<BalanceResponse> = self
+            .get(
+                &format!("/cosmos/bank/v1beta1/balances/{address}/by_denom"),
+                &[("denom", self.denom(&asset.0))],
+            )
+            .await?;
+
+        let amount = match response.and_then(|response| response.balance) {
+            Some(coin) => parse_amount(&coin.amount)
+                .with_context(|| format!("{} balance '{}'", self.chain_id, coin.amount))?,
+            None => 0,
+        };
+        Ok(BalanceResult {
+            wallet_address: wallet_address.clone(),
+            chain: ChainId(self.chain_id.clone()),
+            asset: asset.clone(),
+            amount: Amount::new(amount, 0),
+        })
+    }
+
+    async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+        let tx_bytes = match req.signed_payload.strip_prefix("0x") {
+            Some(hex) => BASE64.encode(from_hex(hex)?),
+            None => req.signed_payload,
+        };
+        let response = self
+            .http
+            .post(format!("{}/cosmos/tx/v1beta1/txs", self.endpoint))
+            .json(&json!({ "tx_bytes": tx_bytes, "mode": "BROADCAST_MODE_SYNC" }))
+            .send()
+            .await
+            .context("broadcast transport")?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!("broadcast HTTP {status}: {text}");
+        }
+        let envelope: TxEnvelope = response.json().await.context("broadcast response")?;
+        let tx = envelope.tx_response;
+        if tx.code != 0 {
+            // Rejected by CheckTx, e.g. a wrong sequence or too little fee.
+            return Ok(SubmitTxResult {
+                tx_hash: format!("failed:{}", tx.raw_log),
+                accepted: false,
+            });
+        }
+        Ok(SubmitTxResult {
+            tx_hash: tx.txhash,
+            accepted: true,
+        })
+    }
+
+    async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+        let envelope: Option<TxEnvelope> = self
+            .get(&format!("/cosmos/tx/v1beta1/txs/{}", req.tx_hash), &[])
+            .await?;
+        let block_height = match &envelope {
+            Some(envelope) => Some(parse_height(&envelope.tx_response.height)?).filter(|h| *h > 0),
+            None => None,
+        };
+        let (Some(envelope), Some(height)) = (envelope, block_height) else {
+            return Ok(TxStatusResult {
+                tx_hash: req.tx_hash,
+                status: "pending".to_owned(),
+                accepted: true,
+                block_height: None,
+                confirmations: None,
+            });
+        };
+
+        let succeeded = envelope.tx_response.code == 0;
+        let confirmations = self.latest_height().await?.saturating_sub(height) + 1;
+        Ok(TxStatusResult {
+            tx_hash: req.tx_hash,
+            status: if succeeded { "confirmed" } else { "failed" }.to_owned(),
+            accepted: succeeded,
+            block_height: Some(height),
+            confirmations: Some(confirmations),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, Query, State};
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use std::sync::{Arc, Mutex};
+
+    const HEX_WALLET: &str = "0x00000000000000000000000000000000000000aa";
+    /// A wallet the mock node has never seen.
+    const HEX_UNSEEN: &str = "0x00000000000000000000000000000000000000bb";
+
+    #[derive(Default)]
+    struct MockNode {
+        broadcasts: Mutex<Vec<Value>>,
+    }
+
+    fn unseen_account() -> String {
+        to_bech32("cosmos", &from_hex(&HEX_UNSEEN[2..]).unwrap()).unwrap()
+    }
+
+    async fn start_mock(node: Arc<MockNode>) -> String {
+        let app = Router::new()
+            .route(
+                "/cosmos/bank/v1beta1/balances/{address}/by_denom",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    let amount = match query.get("denom").map(String::as_str) {
+                        Some("uatom") => "1500000",
+                        Some("ibc/27394FB0") => "42",
+                        _ => "0",
+                    };
+                    Json(json!({ "balance": { "denom": query.get("denom"), "amount": amount } }))
+                }),
+            )
+            .route(
+                "/cosmos/auth/v1beta1/accounts/{address}",
+                get(|Path(address): Path<String>| async move {
+                    if address == unseen_account() {
+                        return Err((
+                            StatusCode::NOT_FOUND,
+                            Json(json!({ "code": 5, "message": "account not found" })),
+                        ));
+                    }
+                    Ok(Json(json!({
+                        "account": {
+                            "@type": "/cosmos.vesting.v1beta1.ContinuousVestingAccount",
+                            "base_vesting_account": {
+                                "base_account": { "address": address, "account_number": "12", "sequence": "9" }
+                            }
+                        }
+                    })))
+                }),
+            )
+            .route(
+                "/cosmos/tx/v1beta1/txs",
+                post(|State(node): State<Arc<MockNode>>, Json(body): Json<Value>| async move {
+                    node.broadcasts.lock().unwrap().push(body.clone());
+                    Json(if body["tx_bytes"] == "CgQKAggB" {
+                        json!({ "tx_response": { "txhash": "ABCD", "code": 0, "raw_log": "", "height": "0" } })
+                    } else {
+                        json!({ "tx_response": { "txhash": "EF01", "code": 32, "raw_log": "account sequence mismatch", "height": "0" } })
+                    })
+                }),
+            )
+            .route(
+                "/cosmos/tx/v1beta1/txs/{hash}",
+                get(|Path(hash): Path<String>| async move {
+                    match hash.as_str() {
+                        "MINED" => Ok(Json(json!({ "tx_response": { "txhash": hash, "code": 0, "height": "100" } }))),
+                        "FAILED" => Ok(Json(json!({ "tx_response": { "txhash": hash, "code": 11, "height": "104" } }))),
+                        _ => Err((
+                            StatusCode::BAD_REQUEST,
+                            Json(json!({ "code": 5, "message": "tx not found" })),
+                        )),
+                    }
+                }),
+            )
+            .route(
+                "/cosmos/base/tendermint/v1beta1/blocks/latest",
+                get(|| async { Json(json!({ "block": { "header": { "height": "104" } } })) }),
+            )
+            .with_state(node);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://{address}/")
+    }
+
+    #[tokio::test]
+    async fn reads_balances_and_sequences_for_hex_and_bech32_addresses() {
+        let adapter = CosmosAdapter::new("cosmoshub-4", &start_mock(Arc::default()).await, "cosmos")
+            .with_denom("atom", "uatom")
+            .with_denom("USDC", "ibc/27394FB0");
+        let hex = WalletAddress(HEX_WALLET.to_owned());
+        let bech32 = adapter.account_address(&hex).unwrap();
+        assert!(bech32.starts_with("cosmos1"), "{bech32}");
+        assert_eq!(adapter.account_address(&WalletAddress(bech32.clone())).unwrap(), bech32);
+        let foreign = to_bech32("osmo", &[0xaa; 20]).unwrap();
+        assert!(adapter.account_address(&WalletAddress(foreign)).is_err());
+
+        let atom = adapter.get_balance(&hex, &AssetSymbol("ATOM".to_owned())).await.unwrap();
+        assert_eq!(atom.amount.base_units(), 1_500_000);
+        assert_eq!(atom.chain.0, "cosmoshub-4");
+        let usdc = adapter
+            .get_balance(&WalletAddress(bech32), &AssetSymbol("usdc".to_owned()))
+            .await
+            .unwrap();
+        assert_eq!(usdc.amount.base_units(), 42);
+
+        assert_eq!(adapter.nonce_strategy(), NonceStrategy::ChainQueried);
+        assert_eq!(adapter.get_account_nonce(&hex).await.unwrap(), 9);
+        let unseen = WalletAddress(HEX_UNSEEN.to_owned());
+        assert_eq!(adapter.get_account_nonce(&unseen).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn broadcasts_sync_and_reads_tx_status() {
+        let node = Arc::new(MockNode::default());
+        let adapter = CosmosAdapter::new("cosmoshub-4", &start_mock(Arc::clone(&node)).await, "cosmos");
+
+        let submit = |signed_payload: &str| SubmitTxRequest {
+            from: WalletAddress(HEX_WALLET.to_owned()),
+            to: WalletAddress(HEX_WALLET.to_owned()),
+            amount: Amount::new(1, 6),
+            asset: AssetSymbol("ATOM".to_owned()),
+            chain: ChainId("cosmoshub-4".to_owned()),
+            signed_payload: signed_payload.to_owned(),
+            chain_payload: None,
+        };
+        let accepted = adapter.submit_transaction(submit("0x0a040a020801")).await.unwrap();
+        assert!(accepted.accepted);
+        assert_eq!(accepted.tx_hash, "ABCD");
+        let refused = adapter.submit_transaction(submit("CgQKAggC")).await.unwrap();
+        assert!(!refused.accepted);
+        assert_eq!(refused.tx_hash, "failed:account sequence mismatch");
+        assert_eq!(node.broadcasts.lock().unwrap()[0]["mode"], "BROADCAST_MODE_SYNC");
+
+        let status = |tx_hash: &str| TxStatusRequest {
+            tx_hash: tx_hash.to_owned(),
+            chain: ChainId("cosmoshub-4".to_owned()),
+        };
+        let mined = adapter.get_transaction_status(status("MINED")).await.unwrap();
+        assert_eq!((mined.status.as_str(), mined.accepted), ("confirmed", true));
+        assert_eq!((mined.block_height, mined.confirmations), (Some(100), Some(5)));
+        let failed = adapter.get_transaction_status(status("FAILED")).await.unwrap();
+        assert_eq!((failed.status.as_str(), failed.accepted), ("failed", false));
+        assert_eq!(failed.confirmations, Some(1));
+        let pending = adapter.get_transaction_status(status("UNKNOWN")).await.unwrap();
+        assert_eq!(pending.status, "pending");
+        assert_eq!(pending.block_height, None);
+    }
+}
@@ -0,0 +1,499 @@
+//! [`ChainAdapter`] for Ethereum and other EVM networks over JSON-RPC.
+//!
+//! Balances come from `eth_getBalance` for the native asset and from an
+//! ERC-20 `balanceOf` `eth_call` for tokens, named either by a symbol
+//! registered with [`EthereumAdapter::with_asset`] or by contract address.
+//! [`EthereumAdapter::transfer_call`] gives the call a wallet signs to move
+//! either.
+//! `submit_transaction` broadcasts `signed_payload` with
+//! `eth_sendRawTransaction`, so it must be a complete signed transaction,
+//! RLP-encoded as hex; status comes from `eth_getTransactionReceipt`.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+use kc_chain_client::amount::parse_amount;
+use kc_chain_client::asset::DEFAULT_TOKEN_DECIMALS;
+use kc_chain_client::{
+    AdapterHealth, AssetDescriptor, AssetKind, BalanceResult, ChainAdapter, NonceStrategy, SubmitTxRequest,
+    SubmitTxResult, TxStatusRequest, TxStatusResult,
+};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+mod quantity;
+
+use quantity::{address_word, hex_to_decimal, parse_u64, parse_u128};
+
+/// Symbol `get_balance` reads with `eth_getBalance`.
+pub const NATIVE_ASSET: &str = "ETH";
+
+/// ERC-20 `balanceOf(address)` selector.
+const BALANCE_OF_SELECTOR: &str = "0x70a08231";
+
+/// ERC-20 `transfer(address,uint256)` selector.
+const TRANSFER_SELECTOR: &str = "0xa9059cbb";
+
+pub struct EthereumAdapter {
+    chain_id: String,
+    endpoint: String,
+    /// Upper-cased symbol → asset, the native one included.
+    assets: HashMap<String, AssetDescriptor>,
+    http: reqwest::Client,
+    next_request_id: AtomicU64,
+}
+
+/// The `to`, `value` and `data` of a transaction moving an asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmCall {
+    pub to: String,
+    /// Wei sent along; 0 for token transfers.
+    pub value: u128,
+    /// `0x`-prefixed calldata; `0x` for native transfers.
+    pub data: String,
+}
+
+/// EIP-1559 fee fields for a type-2 transaction, in wei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eip1559Fees {
+    /// Base fee of the latest block.
+    pub base_fee_per_gas: u128,
+    /// The node's `eth_maxPriorityFeePerGas` suggestion.
+    pub max_priority_fee_per_gas: u128,
+    /// Twice the base fee plus the tip, which stays above the base fee
+    /// through about six consecutive full blocks.
+    pub max_fee_per_gas: u128,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Receipt {
+    /// `0x1` on success, `0x0` if the transaction reverted.
+    status: Option<String>,
+    block_number: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Block {
+    /// Absent before the London fork.
+    base_fee_per_gas: Option<String>,
+}
+
+impl EthereumAdapter {
+    pub fn new(chain_id: &str, endpoint: &str) -> Self {
+        Self {
+            chain_id: chain_id.to_owned(),
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            assets: HashMap::from([(
+                NATIVE_ASSET.to_owned(),
+                AssetDescriptor::native(NATIVE_ASSET, 18),
+            )]),
+            http: reqwest::Client::new(),
+            next_request_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Send requests through `http`, e.g. a registry's shared pool.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Register `asset` under its symbol, e.g. an ERC-20 token.
+    pub fn with_asset(mut self, asset: AssetDescriptor) -> Self {
+        self.assets.insert(asset.symbol.0.to_ascii_uppercase(), asset);
+        self
+    }
+
+    /// Serve `symbol` from the ERC-20 contract at `contract`, taken to use
+    /// [`DEFAULT_TOKEN_DECIMALS`].
+    pub fn with_erc20_token(self, symbol: &str, contract: &str) -> Self {
+        self.with_asset(AssetDescriptor::contract(symbol, contract, DEFAULT_TOKEN_DECIMALS))
+    }
+
+    /// The call that moves `amount` of `asset` to `to`, for the sender to
+    /// sign: a plain value transfer for the native asset, an ERC-20
+    /// `transfer` to the token contract otherwise.
+    pub fn transfer_call(&self, asset: &AssetSymbol, to: &WalletAddress, amount: Amount) -> Result<EvmCall> {
+        let asset = self.asset(&asset.0)?;
+        let recipient = address_word(&to.0)?;
+        Ok(match asset.kind {
+            AssetKind::Native => EvmCall {
+                to: to.0.clone(),
+                value: amount.base_units(),
+                data: "0x".to_owned(),
+            },
+            AssetKind::Contract(contract) => EvmCall {
+                to: contract,
+                value: 0,
+                data: format!("{TRANSFER_SELECTOR}{recipient}{:064x}", amount.base_units()),
+            },
+        })
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Fee fields for a transaction to be included in the next few blocks.
+    pub async fn suggest_fees(&self) -> Result<Eip1559Fees> {
+        let block: Block = self
+            .call("eth_getBlockByNumber", json!(["latest", false]))
+            .await?;
+        let base_fee_per_gas = parse_u128(
+            block
+                .base_fee_per_gas
+                .as_deref()
+                .ok_or_else(|| anyhow!("{} does not support EIP-1559", self.chain_id))?,
+        )?;
+        let tip: String = self.call("eth_maxPriorityFeePerGas", json!([])).await?;
+        let max_priority_fee_per_gas = parse_u128(&tip)?;
+        Ok(Eip1559Fees {
+            base_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_gas: base_fee_per_gas
+                .saturating_mul(2)
+                .saturating_add(max_priority_fee_per_gas),
+        })
+    }
+
+    /// A registered symbol, or a contract address of unknown decimals
+    /// (reported as 0).
+    fn asset(&self, asset: &str) -> Result<AssetDescriptor> {
+        if let Some(descriptor) = self.assets.get(&asset.to_ascii_uppercase()) {
+            return Ok(descriptor.clone());
+        }
+        if address_word(asset).is_ok() {
+            return Ok(AssetDescriptor::contract(asset, asset, 0));
+        }
+        Err(anyhow!("{} has no ERC-20 token '{asset}'", self.chain_id))
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        self.try_call(method, params)
+            .await?
+            .map_err(|err| anyhow!("{method} failed: {} (code {})", err.message, err.code))?
+            .ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    /// Like [`Self::call`], keeping a JSON-RPC error apart from a transport
+    /// failure. A `null` result is `Ok(Ok(None))`.
+    async fn try_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<std::result::Result<Option<T>, RpcError>> {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let response = self
+            .http
+            .post(&self.endpoint)
+            .json(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .send()
+            .await
+            .with_context(|| format!("{method} transport"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("{method} HTTP {status}: {text}");
+        }
+        let body: RpcResponse<T> = response
+            .json()
+            .await
+            .with_context(|| format!("{method} response"))?;
+        Ok(match body.error {
+            Some(err) => Err(err),
+            None => Ok(body.result),
+        })
+    }
+}
+
+#[async_trait]
+impl ChainAdapter for EthereumAdapter {
+    fn chain_id(&self) -> &str {
+        &self.chain_id
+    }
+
+    fn nonce_strategy(&self) -> NonceStrategy {
+        NonceStrategy::ChainQueried
+    }
+
+    /// Counts pending transactions, so back-to-back submits get distinct nonces.
+    async fn get_account_nonce(&self, wallet_address: &WalletAddress) -> Result<u64> {
+        let count: String = self
+            .call("eth_getTransactionCount", json!([wallet_address.0, "pending"]))
+            .await?;
+        parse_u64(&count)
+    }
+
+    /// Reachable when `eth_blockNumber` answers.
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async {
+            let tip: String = self.call("eth_blockNumber", json!([])).await?;
+            parse_u64(&tip).map(Some)
+        })
+        .await
+    }
+
+    async fn get_balance(
+        &self,
+        wallet_address: &WalletAddress,
+        asset: &AssetSymbol,
+    ) -> Result<BalanceResult> {
+        let descriptor = self.asset(&asset.0)?;
+        let balance: String = match &descriptor.kind {
+            AssetKind::Native => {
+                self.call("eth_getBalance", json!([wallet_address.0, "latest"]))
+                    .await?
+            }
+            AssetKind::Contract(contract) => {
+                let data = format!("{BALANCE_OF_SELECTOR}{}", address_word(&wallet_address.0)?);
+                self.call("eth_call", json!([{ "to": contract, "data": data }, "latest"]))
+                    .await?
+            }
+        };
+
+        // A uint256 balance past u128 is an error, not a truncated amount.
+        let amount = parse_amount(&hex_to_decimal(&balance)?)
+            .with_context(|| format!("{} balance of {}", self.chain_id, asset.0))?;
+        Ok(BalanceResult {
+            wallet_address: wallet_address.clone(),
+            chain: ChainId(self.chain_id.clone()),
+            asset: asset.clone(),
+            amount: Amount::new(amount, descriptor.decimals),
+        })
+    }
+
+    /// Broadcasts the signed transaction for a native or registered token
+    /// asset; unknown tokens fail before reaching the node.
+    async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+        self.asset(&req.asset.0)?;
+        let raw = if req.signed_payload.starts_with("0x") {
+            req.signed_payload
+        } else {
+            format!("0x{}", req.signed_payload)
+        };
+        match self
+            .try_call::<String>("eth_sendRawTransaction", json!([raw]))
+            .await?
+        {
+            Ok(Some(tx_hash)) => Ok(SubmitTxResult {
+                tx_hash,
+                accepted: true,
+            }),
+            Ok(None) => Err(anyhow!("eth_sendRawTransaction returned no result")),
+            // The node refused it, e.g. nonce too low or underpriced.
+            Err(err) => Ok(SubmitTxResult {
+                tx_hash: format!("failed:{}", err.message),
+                accepted: false,
+            }),
+        }
+    }
+
+    async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+        let receipt: Option<Receipt> = self
+            .try_call("eth_getTransactionReceipt", json!([req.tx_hash]))
+            .await?
+            .map_err(|err| anyhow!("eth_getTransactionReceipt failed: {}", err.message))?;
+        let Some(receipt) = receipt else {
+            return Ok(TxStatusResult {
+                tx_hash: req.tx_hash,
+                status: "pending".to_owned(),
+                accepted: true,
+                block_height: None,
+                confirmations: None,
+            });
+        };
+
+        let succeeded = receipt.status.as_deref().map(parse_u64).transpose()? != Some(0);
+        let block_height = receipt.block_number.as_deref().map(parse_u64).transpose()?;
+        let confirmations = match block_height {
+            Some(height) => {
+                let tip: String = self.call("eth_blockNumber", json!([])).await?;
+                Some(parse_u64(&tip)?.saturating_sub(height) + 1)
+            }
+            None => None,
+        };
+        Ok(TxStatusResult {
+            tx_hash: req.tx_hash,
+            status: if succeeded { "confirmed" } else { "failed" }.to_owned(),
+            accepted: succeeded,
+            block_height,
+            confirmations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::State, routing::post};
+    use std::sync::{Arc, Mutex};
+
+    const WALLET: &str = "0x00000000000000000000000000000000000000aa";
+    const USDC: &str = "0x00000000000000000000000000000000000000cc";
+
+    /// Canned JSON-RPC node that records the calls it receives.
+    #[derive(Default)]
+    struct MockNode {
+        calls: Mutex<Vec<Value>>,
+    }
+
+    async fn mock_rpc(State(node): State<Arc<MockNode>>, Json(request): Json<Value>) -> Json<Value> {
+        node.calls.lock().unwrap().push(request.clone());
+        let params = &request["params"];
+        let outcome = match request["method"].as_str().unwrap_or_default() {
+            "eth_getBalance" => Ok(json!("0xde0b6b3a7640000")),
+            "eth_call" if params[0]["to"] == USDC => Ok(json!(format!("0x{:064x}", 2_500_000))),
+            "eth_getTransactionCount" => Ok(json!("0x7")),
+            "eth_sendRawTransaction" if params[0] == "0x02f8aa" => Ok(json!("0xhash")),
+            "eth_sendRawTransaction" => Err("nonce too low"),
+            "eth_getTransactionReceipt" => Ok(match params[0].as_str() {
+                Some("0xmined") => json!({ "status": "0x1", "blockNumber": "0x10" }),
+                Some("0xreverted") => json!({ "status": "0x0", "blockNumber": "0x12" }),
+                _ => Value::Null,
+            }),
+            "eth_blockNumber" => Ok(json!("0x12")),
+            "eth_getBlockByNumber" => Ok(json!({ "baseFeePerGas": "0x3b9aca00" })),
+            "eth_maxPriorityFeePerGas" => Ok(json!("0x59682f00")),
+            _ => Err("method not found"),
+        };
+        Json(match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err(message) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32000, "message": message }
+            }),
+        })
+    }
+
+    async fn start_mock(node: Arc<MockNode>) -> String {
+        let app = Router::new().route("/", post(mock_rpc)).with_state(node);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://{address}/")
+    }
+
+    #[tokio::test]
+    async fn reads_native_and_erc20_balances() {
+        let node = Arc::new(MockNode::default());
+        let adapter =
+            EthereumAdapter::new("sepolia", &start_mock(Arc::clone(&node)).await)
+                .with_asset(AssetDescriptor::contract("usdc", USDC, 6));
+        let wallet = WalletAddress(WALLET.to_owned());
+
+        let native = adapter.get_balance(&wallet, &AssetSymbol("ETH".to_owned())).await.unwrap();
+        assert_eq!(native.amount, Amount::new(1_000_000_000_000_000_000, 18));
+        assert_eq!(native.chain.0, "sepolia");
+        let token = adapter.get_balance(&wallet, &AssetSymbol("USDC".to_owned())).await.unwrap();
+        assert_eq!(token.amount, Amount::new(2_500_000, 6));
+        let by_address = adapter.get_balance(&wallet, &AssetSymbol(USDC.to_owned())).await.unwrap();
+        assert_eq!(by_address.amount, Amount::new(2_500_000, 0));
+        assert!(adapter.get_balance(&wallet, &AssetSymbol("DAI".to_owned())).await.is_err());
+
+        let calls = node.calls.lock().unwrap();
+        assert_eq!(
+            calls[1]["params"][0]["data"],
+            format!("0x70a08231{}aa", "0".repeat(62))
+        );
+    }
+
+    #[tokio::test]
+    async fn submits_raw_transactions_and_reads_receipts() {
+        let adapter = EthereumAdapter::new("sepolia", &start_mock(Arc::default()).await);
+        assert_eq!(adapter.nonce_strategy(), NonceStrategy::ChainQueried);
+        let health = adapter.health().await;
+        assert!(health.reachable);
+        assert_eq!(health.block_height, Some(0x12));
+        assert_eq!(adapter.get_account_nonce(&WalletAddress(WALLET.to_owned())).await.unwrap(), 7);
+
+        let submit = |signed_payload: &str| SubmitTxRequest {
+            from: WalletAddress(WALLET.to_owned()),
+            to: WalletAddress(USDC.to_owned()),
+            amount: Amount::new(1, 18),
+            asset: AssetSymbol("ETH".to_owned()),
+            chain: ChainId("sepolia".to_owned()),
+            signed_payload: signed_payload.to_owned(),
+            chain_payload: None,
+        };
+        let accepted = adapter.submit_transaction(submit("02f8aa")).await.unwrap();
+        assert!(accepted.accepted);
+        assert_eq!(accepted.tx_hash, "0xhash");
+        let refused = adapter.submit_transaction(submit("0x02f8bb")).await.unwrap();
+        assert!(!refused.accepted);
+        assert_eq!(refused.tx_hash, "failed:nonce too low");
+        let unknown_token = SubmitTxRequest {
+            asset: AssetSymbol("DAI".to_owned()),
+            ..submit("02f8aa")
+        };
+        assert!(adapter.submit_transaction(unknown_token).await.is_err());
+
+        let status = |tx_hash: &str| TxStatusRequest {
+            tx_hash: tx_hash.to_owned(),
+            chain: ChainId("sepolia".to_owned()),
+        };
+        let mined = adapter.get_transaction_status(status("0xmined")).await.unwrap();
+        assert_eq!((mined.status.as_str(), mined.accepted), ("confirmed", true));
+        assert_eq!((mined.block_height, mined.confirmations), (Some(16), Some(3)));
+        let reverted = adapter.get_transaction_status(status("0xreverted")).await.unwrap();
+        assert_eq!((reverted.status.as_str(), reverted.accepted), ("failed", false));
+        assert_eq!(reverted.confirmations, Some(1));
+        let pending = adapter.get_transaction_status(status("0xunknown")).await.unwrap();
+        assert_eq!(pending.status, "pending");
+        assert_eq!(pending.block_height, None);
+    }
+
+    #[test]
+    fn builds_native_and_token_transfer_calls() {
+        let adapter = EthereumAdapter::new("sepolia", "http://127.0.0.1:9").with_erc20_token("USDC", USDC);
+        let to = WalletAddress(WALLET.to_owned());
+
+        let native = adapter
+            .transfer_call(&AssetSymbol("eth".to_owned()), &to, Amount::new(5, 18))
+            .unwrap();
+        assert_eq!(native, EvmCall { to: WALLET.to_owned(), value: 5, data: "0x".to_owned() });
+        let token = adapter
+            .transfer_call(&AssetSymbol("usdc".to_owned()), &to, Amount::new(2_500_000, 6))
+            .unwrap();
+        assert_eq!(token.to, USDC);
+        assert_eq!(token.value, 0);
+        assert_eq!(
+            token.data,
+            format!("0xa9059cbb{}aa{:064x}", "0".repeat(62), 2_500_000)
+        );
+        assert!(adapter.transfer_call(&AssetSymbol("DAI".to_owned()), &to, Amount::new(1, 18)).is_err());
+        assert!(adapter.transfer_call(&AssetSymbol("USDC".to_owned()), &WalletAddress("0xabc".to_owned()), Amount::new(1, 6)).is_err());
+    }
+
+    #[tokio::test]
+    async fn suggests_eip1559_fees() {
+        let adapter = EthereumAdapter::new("sepolia", &start_mock(Arc::default()).await);
+        assert_eq!(
+            adapter.suggest_fees().await.unwrap(),
+            Eip1559Fees {
+                base_fee_per_gas: 1_000_000_000,
+                max_priority_fee_per_gas: 1_500_000_000,
+                max_fee_per_gas: 3_500_000_000,
+            }
+        );
+    }
+}
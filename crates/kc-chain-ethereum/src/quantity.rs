@@ -0,0 +1,88 @@
+//! JSON-RPC hex quantities and ABI words.
+
+use anyhow::{Result, anyhow, bail};
+
+/// A `0x`-prefixed quantity that fits in a `u128`, such as a block number,
+/// nonce or gas price.
+pub(crate) fn parse_u128(hex: &str) -> Result<u128> {
+    let digits = strip_prefix(hex)?;
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u128::from_str_radix(digits, 16).map_err(|err| anyhow!("invalid quantity '{hex}': {err}"))
+}
+
+pub(crate) fn parse_u64(hex: &str) -> Result<u64> {
+    u64::try_from(parse_u128(hex)?).map_err(|_| anyhow!("quantity '{hex}' exceeds u64"))
+}
+
+/// Decimal string for a `0x`-prefixed quantity of any width. ERC-20
+/// balances are `uint256` and can overflow `u128`.
+pub(crate) fn hex_to_decimal(hex: &str) -> Result<String> {
+    const LIMB: u64 = 1_000_000_000;
+    // Base-1e9 digits, least significant first.
+    let mut limbs: Vec<u64> = vec![0];
+    for digit in strip_prefix(hex)?.chars() {
+        let mut carry = u64::from(
+            digit
+                .to_digit(16)
+                .ok_or_else(|| anyhow!("invalid quantity '{hex}'"))?,
+        );
+        for limb in &mut limbs {
+            let value = *limb * 16 + carry;
+            *limb = value % LIMB;
+            carry = value / LIMB;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+    let mut decimal = limbs.last().copied().unwrap_or_default().to_string();
+    for limb in limbs.iter().rev().skip(1) {
+        decimal.push_str(&format!("{limb:09}"));
+    }
+    Ok(decimal)
+}
+
+/// `address` left-padded to a 32-byte ABI word.
+pub(crate) fn address_word(address: &str) -> Result<String> {
+    let digits = strip_prefix(address)?;
+    if digits.len() != 40 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("'{address}' is not a 20-byte hex address");
+    }
+    Ok(format!("{:0>64}", digits.to_ascii_lowercase()))
+}
+
+fn strip_prefix(hex: &str) -> Result<&str> {
+    hex.strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .ok_or_else(|| anyhow!("quantity '{hex}' has no 0x prefix"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_to_decimal_handles_uint256() {
+        assert_eq!(hex_to_decimal("0x").unwrap(), "0");
+        assert_eq!(hex_to_decimal("0x0").unwrap(), "0");
+        assert_eq!(hex_to_decimal("0xde0b6b3a7640000").unwrap(), "1000000000000000000");
+        assert_eq!(
+            hex_to_decimal(&format!("0x{}", "f".repeat(64))).unwrap(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert!(hex_to_decimal("12").is_err());
+        assert!(hex_to_decimal("0xzz").is_err());
+    }
+
+    #[test]
+    fn quantities_and_address_words() {
+        assert_eq!(parse_u64("0x1b4").unwrap(), 436);
+        assert_eq!(
+            address_word("0x00000000000000000000000000000000000000Ab").unwrap(),
+            format!("{}ab", "0".repeat(62))
+        );
+        assert!(address_word("0xabc").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Failover across several FlowCortex nodes.
+//!
+//! Requests rotate round-robin over the nodes in good standing. A node that
+//! fails (transport error or 5xx) is benched for a cooldown that doubles
+//! with each consecutive failure, up to [`MAX_COOLDOWN`], and the request
+//! moves on to the next node. Benched nodes are still tried last, soonest
+//! to recover first, so a request only fails when every node does. A
+//! successful response clears the node's record.
+
+use anyhow::{Context, Result, anyhow};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const BASE_COOLDOWN: Duration = Duration::from_secs(1);
+const MAX_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Health of one node, as a [`EndpointPool`] request last saw it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointStatus {
+    pub url: String,
+    /// Failed requests since the last success.
+    pub consecutive_failures: u32,
+    /// Whether the node is benched and only tried after the others.
+    pub benched: bool,
+}
+
+struct Endpoint {
+    url: String,
+    standing: Mutex<Standing>,
+}
+
+#[derive(Default)]
+struct Standing {
+    consecutive_failures: u32,
+    benched_until: Option<Instant>,
+}
+
+impl Standing {
+    fn benched(&self, now: Instant) -> bool {
+        self.benched_until.is_some_and(|until| until > now)
+    }
+}
+
+pub(crate) struct EndpointPool {
+    endpoints: Vec<Endpoint>,
+    next: AtomicUsize,
+}
+
+impl EndpointPool {
+    /// `urls` must not be empty; trailing slashes are dropped.
+    pub(crate) fn new(urls: &[String]) -> Self {
+        assert!(!urls.is_empty(), "flowcortex needs at least one endpoint");
+        Self {
+            endpoints: urls
+                .iter()
+                .map(|url| Endpoint {
+                    url: url.trim_end_matches('/').to_owned(),
+                    standing: Mutex::default(),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn primary(&self) -> &str {
+        &self.endpoints[0].url
+    }
+
+    pub(crate) fn urls(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.iter().map(|endpoint| endpoint.url.as_str())
+    }
+
+    pub(crate) fn status(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let standing = endpoint.standing.lock().expect("endpoint standing lock");
+                EndpointStatus {
+                    url: endpoint.url.clone(),
+                    consecutive_failures: standing.consecutive_failures,
+                    benched: standing.benched(now),
+                }
+            })
+            .collect()
+    }
+
+    /// Nodes to try for one request: those in good standing in round-robin
+    /// order, then benched ones by when their cooldown ends.
+    fn order(&self) -> Vec<&Endpoint> {
+        let now = Instant::now();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let count = self.endpoints.len();
+        let (mut ready, mut benched): (Vec<_>, Vec<_>) = (0..count)
+            .map(|offset| &self.endpoints[(start + offset) % count])
+            .partition(|endpoint| !endpoint.standing.lock().expect("endpoint standing lock").benched(now));
+        benched.sort_by_key(|endpoint| endpoint.standing.lock().expect("endpoint standing lock").benched_until);
+        ready.append(&mut benched);
+        ready
+    }
+
+    /// Send the request `build` makes for a node's base URL, failing over
+    /// to the next node on a transport error or 5xx. Only connection
+    /// failures fail over unless `idempotent`, since any other error may
+    /// come after the node acted on the request. The last node's 5xx is
+    /// returned as is.
+    pub(crate) async fn send(
+        &self,
+        op: &str,
+        idempotent: bool,
+        build: impl Fn(&str) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        let order = self.order();
+        let mut last_error = None;
+        for (attempt, endpoint) in order.iter().enumerate() {
+            let last = attempt + 1 == order.len();
+            match build(&endpoint.url).send().await {
+                Ok(response) if response.status().is_server_error() => {
+                    endpoint.record_failure();
+                    if last || !idempotent {
+                        return Ok(response);
+                    }
+                    warn!("{op}: {} returned HTTP {}; trying next endpoint", endpoint.url, response.status());
+                }
+                Ok(response) => {
+                    endpoint.record_success();
+                    return Ok(response);
+                }
+                Err(err) => {
+                    endpoint.record_failure();
+                    let retry = idempotent || err.is_connect();
+                    let err = anyhow!(err).context(format!("{op} transport ({})", endpoint.url));
+                    if !retry {
+                        return Err(err);
+                    }
+                    if !last {
+                        warn!("{err:#}; trying next endpoint");
+                    }
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("no endpoints")))
+            .with_context(|| format!("{op}: all {} endpoints failed", order.len()))
+    }
+}
+
+impl Endpoint {
+    fn record_success(&self) {
+        *self.standing.lock().expect("endpoint standing lock") = Standing::default();
+    }
+
+    fn record_failure(&self) {
+        let mut standing = self.standing.lock().expect("endpoint standing lock");
+        standing.consecutive_failures = standing.consecutive_failures.saturating_add(1);
+        let cooldown = BASE_COOLDOWN
+            .saturating_mul(1 << (standing.consecutive_failures - 1).min(6))
+            .min(MAX_COOLDOWN);
+        standing.benched_until = Some(Instant::now() + cooldown);
+    }
+}
@@ -1,36 +1,140 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use kc_api_types::{AssetSymbol, ChainId, WalletAddress};
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+use kc_chain_client::amount::parse_amount;
 use kc_chain_client::{
-    BalanceResult, ChainAdapter, SubmitTxRequest, SubmitTxResult, TxStatusRequest, TxStatusResult,
+    AdapterHealth, BalanceResult, ChainAdapter, ChainTransaction, ChainTransactionPage, FeeEstimate,
+    FeeEstimateRequest, NonceStrategy, SubmitTxRequest, SubmitTxResult, TxStatusRequest,
+    TxStatusResult,
 };
+use kc_crypto::encoding::to_hex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing::warn;
 
+mod endpoints;
+mod status;
+mod subscribe;
+
+use endpoints::EndpointPool;
+use status::StatusTracker;
+pub use endpoints::EndpointStatus;
+pub use subscribe::{BLOCKS_WS_PATH, BlockEvent};
+
 pub const FLOWCORTEX_L1: &str = "flowcortex-l1";
+/// Asset FlowCortex charges fees in, whatever asset is transferred.
+pub const FLOWCORTEX_FEE_ASSET: &str = "PROOF";
+/// Marks a tx hash KeyCortex made up because the node did not report one.
+pub const LOCAL_TX_HASH_PREFIX: &str = "local_";
+/// How long a `/blocks` scan serves [`ChainAdapter::list_transactions`]
+/// before the node is asked again.
+const BLOCK_CACHE_TTL: Duration = Duration::from_secs(5);
+
+const DEFAULT_ENDPOINT: &str = "http://192.168.29.78:8082";
 
-/// Real HTTP adapter for FlowCortex L1 node.
+/// Real HTTP adapter for FlowCortex L1 nodes.
 ///
-/// Reads `FLOWCORTEX_L1_URL` from environment at construction time
-/// (default: `http://192.168.29.78:8082`).
+/// Reads `FLOWCORTEX_L1_URLS` (comma-separated) or `FLOWCORTEX_L1_URL`
+/// (default: `http://192.168.29.78:8082`), `FLOWCORTEX_FLAT_FEE`
+/// (default: `0`) and `FLOWCORTEX_L1_WS_URL` from environment at
+/// construction time. With several
+/// nodes, requests rotate between them, and a node that errors is skipped
+/// for a growing cooldown while the request moves on to the next one.
 pub struct FlowCortexAdapter {
-    endpoint: String,
+    chain_id: String,
+    endpoints: EndpointPool,
+    /// Per-transfer fee in PROOF base units; `/transfer` has no fee market.
+    flat_fee: u128,
+    block_cache: Mutex<BlockCache>,
+    tx_status: StatusTracker,
+    /// Block feed URL, when not derived from the nodes.
+    ws_url: Option<String>,
     http: reqwest::Client,
 }
 
+/// Transfers from every block seen so far, by height. Blocks are final once
+/// produced, so they stay cached even after the node stops listing them.
+#[derive(Default)]
+struct BlockCache {
+    fetched_at: Option<Instant>,
+    blocks: BTreeMap<u64, Vec<ChainTransaction>>,
+}
+
 impl Default for FlowCortexAdapter {
     fn default() -> Self {
-        Self::new(None)
+        Self::new(Vec::new())
     }
 }
 
 impl FlowCortexAdapter {
-    pub fn new(endpoint: Option<String>) -> Self {
-        let endpoint = endpoint
-            .or_else(|| std::env::var("FLOWCORTEX_L1_URL").ok())
-            .unwrap_or_else(|| "http://192.168.29.78:8082".to_string());
+    /// An adapter for FlowCortex L1 at `endpoints`, or the nodes configured
+    /// in the environment when the list is empty.
+    pub fn new(endpoints: Vec<String>) -> Self {
+        let endpoints = if endpoints.is_empty() {
+            endpoints_from_env()
+        } else {
+            endpoints
+        };
+        let flat_fee = std::env::var("FLOWCORTEX_FLAT_FEE")
+            .ok()
+            .and_then(|fee| fee.trim().parse().ok())
+            .unwrap_or(0);
+        let adapter = Self::for_chain_endpoints(FLOWCORTEX_L1, &endpoints).with_flat_fee(flat_fee);
+        match std::env::var("FLOWCORTEX_L1_WS_URL") {
+            Ok(url) if !url.trim().is_empty() => adapter.with_ws_url(url.trim()),
+            _ => adapter,
+        }
+    }
+
+    /// The first configured node.
+    pub fn endpoint(&self) -> &str {
+        self.endpoints.primary()
+    }
+
+    /// Every configured node and how its recent requests went.
+    pub fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        self.endpoints.status()
+    }
+
+    /// Quote `fee` PROOF base units for every transfer.
+    pub fn with_flat_fee(mut self, fee: u128) -> Self {
+        self.flat_fee = fee;
+        self
+    }
+
+    /// Send requests through `http`, e.g. a registry's shared pool.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Take blocks for [`Self::subscribe_blocks`] from `url` rather than
+    /// [`BLOCKS_WS_PATH`] on the configured nodes.
+    pub fn with_ws_url(mut self, url: &str) -> Self {
+        self.ws_url = Some(url.to_owned());
+        self
+    }
+
+    /// An adapter for another FlowCortex network (e.g. a testnet) at `endpoint`.
+    pub fn for_chain(chain_id: &str, endpoint: &str) -> Self {
+        Self::for_chain_endpoints(chain_id, &[endpoint.to_owned()])
+    }
+
+    /// Like [`Self::for_chain`], failing over between several nodes.
+    ///
+    /// # Panics
+    ///
+    /// If `endpoints` is empty.
+    pub fn for_chain_endpoints(chain_id: &str, endpoints: &[String]) -> Self {
         Self {
-            endpoint: endpoint.trim_end_matches('/').to_string(),
+            chain_id: chain_id.to_owned(),
+            endpoints: EndpointPool::new(endpoints),
+            flat_fee: 0,
+            block_cache: Mutex::default(),
+            tx_status: StatusTracker::default(),
+            ws_url: None,
             // Accept self-signed TLS certificates (local demo uses self-signed certs)
             http: reqwest::Client::builder()
                 .danger_accept_invalid_certs(true)
@@ -40,6 +144,23 @@ impl FlowCortexAdapter {
     }
 }
 
+/// `FLOWCORTEX_L1_URLS`, else `FLOWCORTEX_L1_URL`, else the default node.
+fn endpoints_from_env() -> Vec<String> {
+    let parse = |name: &str| -> Vec<String> {
+        std::env::var(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_owned)
+            .collect()
+    };
+    [parse("FLOWCORTEX_L1_URLS"), parse("FLOWCORTEX_L1_URL")]
+        .into_iter()
+        .find(|urls| !urls.is_empty())
+        .unwrap_or_else(|| vec![DEFAULT_ENDPOINT.to_owned()])
+}
+
 // ── FlowCortex L1 REST API types ─────────────────────────────────────
 
 #[derive(Debug, Serialize)]
@@ -47,33 +168,24 @@ struct TransferRequest {
     from: String,
     to: String,
     token: String,
-    amount: u64,
+    amount: u128,
     rw_set: RwSet,
     proof: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct RwSet {
     reads: Vec<String>,
     writes: Vec<String>,
 }
 
-impl Default for RwSet {
-    fn default() -> Self {
-        Self {
-            reads: Vec::new(),
-            writes: Vec::new(),
-        }
-    }
-}
-
 #[derive(Debug, Deserialize)]
 struct BalanceResponse {
     #[allow(dead_code)]
     account: String,
     #[allow(dead_code)]
     token: String,
-    balance: u64,
+    balance: u128,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,16 +194,243 @@ struct L1ErrorResponse {
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 struct BlockResponse {
     height: u64,
     transactions: Vec<serde_json::Value>,
 }
 
+/// The transfer fields of a block transaction; other transaction kinds
+/// lack some of them and are skipped.
+#[derive(Debug, Deserialize)]
+struct BlockTransaction {
+    #[serde(default, alias = "tx_hash")]
+    hash: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    #[serde(alias = "asset")]
+    token: Option<String>,
+    amount: Option<serde_json::Value>,
+}
+
+impl BlockResponse {
+    /// Hashes of the block's transactions that list one.
+    fn tx_hashes(&self) -> impl Iterator<Item = &str> {
+        self.transactions.iter().filter_map(|tx| {
+            ["hash", "tx_hash"]
+                .iter()
+                .find_map(|key| tx.get(key).and_then(|v| v.as_str()))
+        })
+    }
+
+    fn contains_tx(&self, tx_hash: &str) -> bool {
+        self.tx_hashes().any(|hash| hash == tx_hash)
+    }
+}
+
+/// Hash for a block transfer the node lists without one.
+fn transfer_hash(from: &str, to: &str, asset: &str, amount: &str, chain: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let payload = format!("{from}:{to}:{asset}:{amount}:{chain}");
+    format!("txn_{}", to_hex(&Sha256::digest(payload.as_bytes())))
+}
+
+/// Stand-in hash for an accepted transfer the node gave no hash for.
+/// The signature covers the nonce, so identical transfers still differ.
+fn local_tx_hash(req: &SubmitTxRequest) -> String {
+    use sha2::{Digest, Sha256};
+    let payload = format!(
+        "{}:{}:{}:{}:{}:{}",
+        req.from.0, req.to.0, req.asset.0, req.amount, req.chain.0, req.signed_payload
+    );
+    format!("{LOCAL_TX_HASH_PREFIX}{}", to_hex(&Sha256::digest(payload.as_bytes())))
+}
+
+/// The hash in a `/transfer` response, at the top level or under
+/// `transaction`.
+fn response_tx_hash(body: &str) -> Option<String> {
+    let body: serde_json::Value = serde_json::from_str(body).ok()?;
+    [&body, &body["transaction"]].into_iter().find_map(|object| {
+        ["tx_hash", "hash", "txHash", "id"]
+            .iter()
+            .filter_map(|key| object.get(key)?.as_str())
+            .map(str::trim)
+            .find(|hash| !hash.is_empty())
+            .map(str::to_owned)
+    })
+}
+
+/// A block transaction amount as a decimal string.
+fn amount_string(amount: serde_json::Value) -> Option<String> {
+    match amount {
+        serde_json::Value::String(amount) => Some(amount),
+        serde_json::Value::Number(amount) => Some(amount.to_string()),
+        _ => None,
+    }
+}
+
+/// `{height}:{index}` of a transaction within the scanned blocks.
+fn parse_cursor(cursor: &str) -> Result<(u64, usize)> {
+    cursor
+        .split_once(':')
+        .and_then(|(height, index)| Some((height.parse().ok()?, index.parse().ok()?)))
+        .with_context(|| format!("invalid cursor '{cursor}'"))
+}
+
+impl FlowCortexAdapter {
+    fn block_transfers(&self, block: &BlockResponse) -> Vec<ChainTransaction> {
+        block
+            .transactions
+            .iter()
+            .filter_map(|tx| serde_json::from_value::<BlockTransaction>(tx.clone()).ok())
+            .filter_map(|tx| {
+                let amount = amount_string(tx.amount?)?;
+                let (from, to, token) = (tx.from?, tx.to?, tx.token?);
+                Some(ChainTransaction {
+                    tx_hash: tx
+                        .hash
+                        .unwrap_or_else(|| transfer_hash(&from, &to, &token, &amount, &self.chain_id)),
+                    from: WalletAddress(from),
+                    to: WalletAddress(to),
+                    asset: AssetSymbol(token),
+                    amount,
+                    block_height: Some(block.height),
+                })
+            })
+            .collect()
+    }
+
+    /// GET `/blocks`, noting the tip for [`StatusTracker`].
+    async fn fetch_blocks(&self, op: &str) -> Result<Vec<BlockResponse>> {
+        let response = self
+            .endpoints
+            .send(op, true, |base| self.http.get(format!("{base}/blocks")))
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("{op} HTTP {status}: {text}");
+        }
+        let blocks: Vec<BlockResponse> = response.json().await.with_context(|| format!("{op} parse"))?;
+        if let Some(tip) = blocks.iter().map(|block| block.height).max() {
+            self.tx_status.observe_tip(tip);
+        }
+        Ok(blocks)
+    }
+
+    /// Hash of the newest transfer matching `req` in the latest block, if
+    /// the node lists it there with one.
+    async fn latest_block_tx_hash(&self, req: &SubmitTxRequest, amount: u128) -> Result<Option<String>> {
+        let blocks = self.fetch_blocks("flowcortex latest block").await?;
+        let Some(latest) = blocks.into_iter().max_by_key(|block| block.height) else {
+            return Ok(None);
+        };
+        let amount = amount.to_string();
+        Ok(latest
+            .transactions
+            .into_iter()
+            .rev()
+            .filter_map(|tx| serde_json::from_value::<BlockTransaction>(tx).ok())
+            .find(|tx| {
+                tx.from.as_deref() == Some(req.from.0.as_str())
+                    && tx.to.as_deref() == Some(req.to.0.as_str())
+                    && tx.token.as_deref() == Some(req.asset.0.as_str())
+                    && tx.amount.clone().and_then(amount_string).as_deref() == Some(amount.as_str())
+            })
+            .and_then(|tx| tx.hash))
+    }
+
+    /// Rescan `/blocks` into the block cache unless it was scanned within
+    /// [`BLOCK_CACHE_TTL`].
+    async fn refresh_block_cache(&self) -> Result<()> {
+        let fresh = self
+            .block_cache
+            .lock()
+            .expect("block cache lock")
+            .fetched_at
+            .is_some_and(|at| at.elapsed() < BLOCK_CACHE_TTL);
+        if fresh {
+            return Ok(());
+        }
+
+        let blocks = self.fetch_blocks("flowcortex list_transactions").await?;
+
+        let mut cache = self.block_cache.lock().expect("block cache lock");
+        for block in &blocks {
+            let transfers = self.block_transfers(block);
+            if !transfers.is_empty() {
+                cache.blocks.insert(block.height, transfers);
+            }
+        }
+        cache.fetched_at = Some(Instant::now());
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl ChainAdapter for FlowCortexAdapter {
     fn chain_id(&self) -> &str {
-        FLOWCORTEX_L1
+        &self.chain_id
+    }
+
+    /// `/transfer` carries no nonce, so KeyCortex orders transfers itself.
+    fn nonce_strategy(&self) -> NonceStrategy {
+        NonceStrategy::Sequential
+    }
+
+    /// Reachable when `/blocks` answers; the height is the newest block's.
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async {
+            let blocks = self.fetch_blocks("flowcortex health").await?;
+            Ok(blocks.iter().map(|block| block.height).max())
+        })
+        .await
+    }
+
+    /// The flat fee, the same for every asset and amount.
+    async fn estimate_fee(&self, req: FeeEstimateRequest) -> Result<FeeEstimate> {
+        parse_amount(&req.amount)
+            .with_context(|| format!("flowcortex estimate_fee amount '{}'", req.amount))?;
+        Ok(FeeEstimate {
+            fee: self.flat_fee.to_string(),
+            fee_asset: AssetSymbol(FLOWCORTEX_FEE_ASSET.to_owned()),
+        })
+    }
+
+    /// Transfers found by scanning `/blocks`, cached for [`BLOCK_CACHE_TTL`].
+    /// Cursors are `{height}:{index}` of the last transfer returned.
+    async fn list_transactions(
+        &self,
+        wallet_address: &WalletAddress,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<ChainTransactionPage> {
+        let after = cursor.map(parse_cursor).transpose()?;
+        self.refresh_block_cache().await?;
+
+        let cache = self.block_cache.lock().expect("block cache lock");
+        let mut matches = cache
+            .blocks
+            .iter()
+            .rev()
+            .flat_map(|(height, transfers)| {
+                transfers
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(move |(index, tx)| ((*height, index), tx))
+            })
+            .filter(|(position, _)| after.is_none_or(|after| *position < after))
+            .filter(|(_, tx)| tx.from == *wallet_address || tx.to == *wallet_address);
+        let page: Vec<_> = matches.by_ref().take(limit).collect();
+        let next_cursor = match (page.last(), matches.next()) {
+            (Some(((height, index), _)), Some(_)) => Some(format!("{height}:{index}")),
+            _ => None,
+        };
+
+        Ok(ChainTransactionPage {
+            transactions: page.into_iter().map(|(_, tx)| tx.clone()).collect(),
+            next_cursor,
+        })
     }
 
     async fn get_balance(
@@ -99,26 +438,22 @@ impl ChainAdapter for FlowCortexAdapter {
         wallet_address: &WalletAddress,
         asset: &AssetSymbol,
     ) -> Result<BalanceResult> {
-        let url = format!(
-            "{}/balance/{}/{}",
-            self.endpoint, wallet_address.0, asset.0
-        );
-
         let response = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .context("flowcortex get_balance transport")?;
+            .endpoints
+            .send("flowcortex get_balance", true, |base| {
+                self.http
+                    .get(format!("{base}/balance/{}/{}", wallet_address.0, asset.0))
+            })
+            .await?;
 
         let status = response.status();
         if status == reqwest::StatusCode::NOT_FOUND {
             // Account or token not found — return zero balance
             return Ok(BalanceResult {
                 wallet_address: wallet_address.clone(),
-                chain: ChainId(FLOWCORTEX_L1.to_owned()),
+                chain: ChainId(self.chain_id.clone()),
                 asset: asset.clone(),
-                amount: "0".to_owned(),
+                amount: Amount::new(0, 0),
             });
         }
 
@@ -134,20 +469,14 @@ impl ChainAdapter for FlowCortexAdapter {
 
         Ok(BalanceResult {
             wallet_address: wallet_address.clone(),
-            chain: ChainId(FLOWCORTEX_L1.to_owned()),
+            chain: ChainId(self.chain_id.clone()),
             asset: asset.clone(),
-            amount: body.balance.to_string(),
+            amount: Amount::new(body.balance, 0),
         })
     }
 
     async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
-        let amount: u64 = req
-            .amount
-            .parse()
-            .unwrap_or_else(|_| {
-                warn!("non-numeric amount '{}', defaulting to 0", req.amount);
-                0
-            });
+        let amount = req.amount.base_units();
 
         let body = TransferRequest {
             from: req.from.0.clone(),
@@ -158,14 +487,13 @@ impl ChainAdapter for FlowCortexAdapter {
             proof: None,
         };
 
-        let url = format!("{}/transfer", self.endpoint);
+        // Not idempotent: only a node that refused the connection is skipped.
         let response = self
-            .http
-            .post(&url)
-            .json(&body)
-            .send()
-            .await
-            .context("flowcortex submit_transaction transport")?;
+            .endpoints
+            .send("flowcortex submit_transaction", false, |base| {
+                self.http.post(format!("{base}/transfer")).json(&body)
+            })
+            .await?;
 
         let status = response.status();
         let text = response.text().await.unwrap_or_default();
@@ -181,18 +509,27 @@ impl ChainAdapter for FlowCortexAdapter {
             anyhow::bail!("flowcortex submit_transaction HTTP {status}: {text}");
         }
 
-        // FlowCortex L1 returns 201 on success. The tx_hash is derived from
-        // the transfer parameters. We use the latest block to find it.
-        // For now, generate a deterministic hash from the request.
-        let tx_hash = {
-            use sha2::{Digest, Sha256};
-            let payload = format!(
-                "{}:{}:{}:{}:{}",
-                req.from.0, req.to.0, req.asset.0, req.amount, req.chain.0
-            );
-            let hash = Sha256::digest(payload.as_bytes());
-            format!("txn_{}", hex_lower(&hash))
+        // FlowCortex L1 returns 201 on success. Take the chain's hash from
+        // the response, else from the transfer in the latest block, else
+        // make up a local one and say so.
+        let tx_hash = match response_tx_hash(&text) {
+            Some(tx_hash) => tx_hash,
+            None => match self.latest_block_tx_hash(&req, amount).await {
+                Ok(Some(tx_hash)) => tx_hash,
+                outcome => {
+                    let tx_hash = local_tx_hash(&req);
+                    let reason = match outcome {
+                        Err(err) => format!("{err:#}"),
+                        _ => "not in the latest block yet".to_owned(),
+                    };
+                    warn!(
+                        "flowcortex accepted a transfer without a tx hash ({reason}); using {tx_hash}"
+                    );
+                    tx_hash
+                }
+            },
         };
+        self.tx_status.record_submitted(&tx_hash);
 
         Ok(SubmitTxResult {
             tx_hash,
@@ -200,53 +537,415 @@ impl ChainAdapter for FlowCortexAdapter {
         })
     }
 
+    /// `confirmed` once the hash is in one of the newest
+    /// [`status::SCAN_WINDOW`] blocks, `pending` while a transfer this
+    /// adapter submitted may still land, `not_found` otherwise.
     async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
-        // FlowCortex L1 doesn't have a per-tx status endpoint.
-        // Check if the tx appears in any block by scanning recent blocks.
-        let url = format!("{}/blocks", self.endpoint);
-        let response = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .context("flowcortex get_transaction_status transport")?;
-
-        if !response.status().is_success() {
-            // Fall back to optimistic status
-            return Ok(TxStatusResult {
-                tx_hash: req.tx_hash,
-                status: "unknown".to_owned(),
-                accepted: true,
-            });
+        let confirmed = |tx_hash: String, block_height: u64, tip: Option<u64>| TxStatusResult {
+            tx_hash,
+            status: "confirmed".to_owned(),
+            accepted: true,
+            block_height: Some(block_height),
+            confirmations: tip.map(|tip| tip.saturating_sub(block_height) + 1),
+        };
+        if let Some(block_height) = self.tx_status.confirmed_height(&req.tx_hash) {
+            return Ok(confirmed(req.tx_hash, block_height, self.tx_status.tip()));
         }
 
-        let blocks: Vec<BlockResponse> = response
-            .json()
-            .await
-            .unwrap_or_default();
-
-        // Check if any block contains a transaction referencing our tx_hash
-        // or if blocks exist (meaning chain is producing blocks)
-        let status = if blocks.is_empty() {
-            "pending"
-        } else {
-            // If we have blocks and the transfer was accepted, it's confirmed
-            // (FlowCortex L1 finalizes immediately on block creation)
-            "confirmed"
-        };
+        let blocks = self.fetch_blocks("flowcortex get_transaction_status").await?;
+        let tip = blocks.iter().map(|block| block.height).max();
+        let window_start = tip.unwrap_or(0).saturating_sub(status::SCAN_WINDOW - 1);
+        let block_height = blocks
+            .iter()
+            .filter(|block| block.height >= window_start)
+            .find(|block| block.contains_tx(&req.tx_hash))
+            .map(|block| block.height);
+        if let Some(block_height) = block_height {
+            self.tx_status.record_confirmed(&req.tx_hash, block_height);
+            return Ok(confirmed(req.tx_hash, block_height, tip));
+        }
 
+        let pending = self.tx_status.recently_submitted(&req.tx_hash);
         Ok(TxStatusResult {
             tx_hash: req.tx_hash,
-            status: status.to_owned(),
-            accepted: true,
+            status: if pending { "pending" } else { "not_found" }.to_owned(),
+            accepted: pending,
+            block_height: None,
+            confirmations: None,
         })
     }
 }
 
-fn hex_lower(bytes: &[u8]) -> String {
-    let mut output = String::with_capacity(bytes.len() * 2);
-    for byte in bytes {
-        output.push_str(&format!("{byte:02x}"));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serves three blocks on the first scan, then only the newest one, as a
+    /// node that prunes its block list would.
+    async fn start_node(scans: Arc<AtomicUsize>) -> String {
+        let app = Router::new()
+            .route(
+                "/blocks",
+                get(|State(scans): State<Arc<AtomicUsize>>| async move {
+                    let first = scans.fetch_add(1, Ordering::SeqCst) == 0;
+                    let mut blocks = vec![json!({ "height": 3, "transactions": [
+                        { "hash": "tx-c", "from": "0xa", "to": "0xb", "token": "PROOF", "amount": "30" },
+                        { "hash": "tx-d", "from": "0xb", "to": "0xa", "token": "FloweR", "amount": 4 }
+                    ] })];
+                    if first {
+                        blocks.push(json!({ "height": 2, "transactions": [
+                            { "from": "0xa", "to": "0xc", "token": "PROOF", "amount": "20" },
+                            { "kind": "stake", "validator": "0xa" }
+                        ] }));
+                        blocks.push(json!({ "height": 1, "transactions": [
+                            { "tx_hash": "tx-a", "from": "0xc", "to": "0xa", "asset": "PROOF", "amount": "10" }
+                        ] }));
+                    }
+                    Json(blocks)
+                }),
+            )
+            .with_state(scans);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn lists_wallet_transfers_from_cached_blocks() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let adapter = FlowCortexAdapter::for_chain(FLOWCORTEX_L1, &start_node(Arc::clone(&scans)).await);
+        let wallet = WalletAddress("0xa".to_owned());
+
+        let first = adapter.list_transactions(&wallet, None, 2).await.unwrap();
+        let hashes: Vec<_> = first.transactions.iter().map(|tx| tx.tx_hash.as_str()).collect();
+        assert_eq!(hashes, ["tx-d", "tx-c"]);
+        assert_eq!(first.transactions[0].amount, "4");
+        assert_eq!(first.transactions[0].block_height, Some(3));
+        assert_eq!(first.next_cursor.as_deref(), Some("3:0"));
+
+        let rest = adapter
+            .list_transactions(&wallet, first.next_cursor.as_deref(), 10)
+            .await
+            .unwrap();
+        assert_eq!(rest.transactions.len(), 2);
+        assert_eq!(
+            rest.transactions[0].tx_hash,
+            transfer_hash("0xa", "0xc", "PROOF", "20", FLOWCORTEX_L1),
+            "transfers listed without a hash get a stable derived one"
+        );
+        assert_eq!(rest.transactions[1].tx_hash, "tx-a");
+        assert_eq!(rest.next_cursor, None);
+        assert_eq!(scans.load(Ordering::SeqCst), 1, "second page is served from the cache");
+
+        // Blocks the node no longer lists stay in the cache.
+        adapter.block_cache.lock().unwrap().fetched_at = None;
+        let rescanned = adapter.list_transactions(&wallet, None, 10).await.unwrap();
+        assert_eq!(scans.load(Ordering::SeqCst), 2);
+        assert_eq!(rescanned.transactions.len(), 4);
+
+        assert!(adapter.list_transactions(&wallet, Some("3"), 10).await.is_err());
+    }
+
+    /// A node answering `/balance`, `/blocks` and `/transfer`, or only 503s
+    /// when `up` is false. Counts balance and transfer requests.
+    async fn start_counting_node(up: bool) -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let app = if up {
+            Router::new()
+                .route(
+                    "/balance/{account}/{token}",
+                    get(|State(hits): State<Arc<AtomicUsize>>| async move {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        Json(json!({ "account": "0xa", "token": "PROOF", "balance": 5 }))
+                    }),
+                )
+                .route("/blocks", get(|| async { Json(json!([{ "height": 9, "transactions": [] }])) }))
+                .route(
+                    "/transfer",
+                    axum::routing::post(|State(hits): State<Arc<AtomicUsize>>| async move {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        (axum::http::StatusCode::CREATED, "{}")
+                    }),
+                )
+        } else {
+            Router::new().fallback(|State(hits): State<Arc<AtomicUsize>>| async move {
+                hits.fetch_add(1, Ordering::SeqCst);
+                (axum::http::StatusCode::SERVICE_UNAVAILABLE, "overloaded")
+            })
+        }
+        .with_state(Arc::clone(&hits));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        (format!("http://{address}"), hits)
+    }
+
+    #[tokio::test]
+    async fn fails_over_between_endpoints_and_benches_failing_ones() {
+        let (flaky, flaky_hits) = start_counting_node(false).await;
+        let (healthy, healthy_hits) = start_counting_node(true).await;
+        let dead = "http://127.0.0.1:9".to_owned();
+        let adapter = FlowCortexAdapter::for_chain_endpoints(FLOWCORTEX_L1, &[dead, flaky.clone(), healthy.clone()]);
+        let wallet = WalletAddress("0xa".to_owned());
+        let proof = AssetSymbol("PROOF".to_owned());
+
+        let balance = adapter.get_balance(&wallet, &proof).await.unwrap();
+        assert_eq!(balance.amount.base_units(), 5);
+        assert_eq!(flaky_hits.load(Ordering::SeqCst), 1);
+        let status = adapter.endpoint_status();
+        assert_eq!(
+            status.iter().map(|endpoint| endpoint.benched).collect::<Vec<_>>(),
+            [true, true, false]
+        );
+        assert_eq!(status[1].consecutive_failures, 1);
+
+        // Benched nodes are skipped while a healthy one is left.
+        let submit = SubmitTxRequest {
+            from: wallet.clone(),
+            to: WalletAddress("0xb".to_owned()),
+            amount: Amount::new(1, 18),
+            asset: proof.clone(),
+            chain: ChainId(FLOWCORTEX_L1.to_owned()),
+            signed_payload: String::new(),
+            chain_payload: None,
+        };
+        assert!(adapter.submit_transaction(submit.clone()).await.unwrap().accepted);
+        assert_eq!(flaky_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(adapter.health().await.block_height, Some(9));
+
+        // A transfer is not retried elsewhere once a node has answered it.
+        let adapter = FlowCortexAdapter::for_chain_endpoints(FLOWCORTEX_L1, &[flaky, healthy.clone()]);
+        let transfers_before = healthy_hits.load(Ordering::SeqCst);
+        assert!(adapter.submit_transaction(submit).await.is_err());
+        assert_eq!(healthy_hits.load(Ordering::SeqCst), transfers_before);
+
+        // Requests rotate between healthy nodes.
+        let (other, other_hits) = start_counting_node(true).await;
+        let adapter = FlowCortexAdapter::for_chain_endpoints(FLOWCORTEX_L1, &[healthy, other]);
+        for _ in 0..4 {
+            adapter.get_balance(&wallet, &proof).await.unwrap();
+        }
+        assert_eq!(other_hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn submit_reports_the_chain_assigned_tx_hash() {
+        /// `/transfer` answers with `receipt`; `/blocks` lists one block.
+        async fn node(receipt: serde_json::Value, latest: serde_json::Value) -> FlowCortexAdapter {
+            let app = Router::new()
+                .route(
+                    "/transfer",
+                    axum::routing::post(move || async move { (axum::http::StatusCode::CREATED, Json(receipt)) }),
+                )
+                .route(
+                    "/blocks",
+                    get(move || async move {
+                        Json(json!([
+                            { "height": 4, "transactions": latest },
+                            { "height": 3, "transactions": [
+                                { "hash": "0xolder", "from": "0xa", "to": "0xb", "token": "PROOF", "amount": "7" }
+                            ] }
+                        ]))
+                    }),
+                );
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                axum::serve(listener, app).await.ok();
+            });
+            FlowCortexAdapter::for_chain(FLOWCORTEX_L1, &format!("http://{address}"))
+        }
+        let submit = |signed_payload: &str| SubmitTxRequest {
+            from: WalletAddress("0xa".to_owned()),
+            to: WalletAddress("0xb".to_owned()),
+            amount: Amount::new(7, 18),
+            asset: AssetSymbol("PROOF".to_owned()),
+            chain: ChainId(FLOWCORTEX_L1.to_owned()),
+            signed_payload: signed_payload.to_owned(),
+            chain_payload: None,
+        };
+
+        let from_response = node(json!({ "transaction": { "hash": "0xreceipt" } }), json!([])).await;
+        let result = from_response.submit_transaction(submit("sig-1")).await.unwrap();
+        assert_eq!(result.tx_hash, "0xreceipt");
+
+        let from_block = node(
+            json!({ "status": "ok" }),
+            json!([
+                { "hash": "0xsomeone-else", "from": "0xc", "to": "0xb", "token": "PROOF", "amount": "7" },
+                { "tx_hash": "0xin-block", "from": "0xa", "to": "0xb", "asset": "PROOF", "amount": 7 }
+            ]),
+        )
+        .await;
+        let result = from_block.submit_transaction(submit("sig-1")).await.unwrap();
+        assert_eq!(result.tx_hash, "0xin-block");
+
+        // Only the latest block counts, so the older identical transfer is not reused.
+        let unknown = node(json!({}), json!([])).await;
+        let first = unknown.submit_transaction(submit("sig-1")).await.unwrap();
+        let second = unknown.submit_transaction(submit("sig-2")).await.unwrap();
+        assert!(first.accepted);
+        assert!(first.tx_hash.starts_with(LOCAL_TX_HASH_PREFIX));
+        assert_ne!(first.tx_hash, second.tx_hash, "identical transfers get distinct hashes");
+        assert_eq!(unknown.submit_transaction(submit("sig-1")).await.unwrap().tx_hash, first.tx_hash);
+    }
+
+    #[tokio::test]
+    async fn looks_up_tx_status_in_recent_blocks() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .route(
+                "/transfer",
+                axum::routing::post(|| async { Json(json!({ "hash": "0xpending" })) }),
+            )
+            .route(
+                "/blocks",
+                get(|State(scans): State<Arc<AtomicUsize>>| async move {
+                    scans.fetch_add(1, Ordering::SeqCst);
+                    let blocks: Vec<_> = (1..=300u64)
+                        .rev()
+                        .map(|height| {
+                            let transactions = match height {
+                                298 => json!([{ "hash": "0xnew", "from": "0xa", "to": "0xb", "token": "PROOF", "amount": "1" }]),
+                                10 => json!([{ "tx_hash": "0xold", "from": "0xa", "to": "0xb", "token": "PROOF", "amount": "1" }]),
+                                _ => json!([]),
+                            };
+                            json!({ "height": height, "transactions": transactions })
+                        })
+                        .collect();
+                    Json(blocks)
+                }),
+            )
+            .with_state(Arc::clone(&scans));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        let adapter = FlowCortexAdapter::for_chain(FLOWCORTEX_L1, &format!("http://{address}"));
+        let status = |tx_hash: &str| {
+            adapter.get_transaction_status(TxStatusRequest {
+                chain: ChainId(FLOWCORTEX_L1.to_owned()),
+                tx_hash: tx_hash.to_owned(),
+            })
+        };
+
+        let confirmed = status("0xnew").await.unwrap();
+        assert_eq!(confirmed.status, "confirmed");
+        assert_eq!(confirmed.block_height, Some(298));
+        assert_eq!(confirmed.confirmations, Some(3));
+        let cached = status("0xnew").await.unwrap();
+        assert_eq!(cached.status, "confirmed");
+        assert_eq!(scans.load(Ordering::SeqCst), 1, "confirmed hashes are answered from the cache");
+
+        // Block 10 is older than the scan window.
+        let old = status("0xold").await.unwrap();
+        assert_eq!(old.status, "not_found");
+        assert!(!old.accepted);
+
+        let submitted = adapter
+            .submit_transaction(SubmitTxRequest {
+                from: WalletAddress("0xa".to_owned()),
+                to: WalletAddress("0xb".to_owned()),
+                amount: Amount::new(1, 18),
+                asset: AssetSymbol("PROOF".to_owned()),
+                chain: ChainId(FLOWCORTEX_L1.to_owned()),
+                signed_payload: "sig".to_owned(),
+                chain_payload: None,
+            })
+            .await
+            .unwrap();
+        let pending = status(&submitted.tx_hash).await.unwrap();
+        assert_eq!(pending.status, "pending");
+        assert!(pending.accepted);
+        assert_eq!(status("0xmissing").await.unwrap().status, "not_found");
+    }
+
+    #[tokio::test]
+    async fn subscribed_blocks_confirm_submitted_transfers() {
+        use axum::extract::ws::{Message, WebSocketUpgrade};
+        use futures_util::StreamExt;
+
+        // No `/blocks` route: confirmations can only come from the feed.
+        let app = Router::new()
+            .route(
+                "/transfer",
+                axum::routing::post(|| async { Json(json!({ "hash": "0xmine" })) }),
+            )
+            .route(
+                BLOCKS_WS_PATH,
+                get(|ws: WebSocketUpgrade| async move {
+                    ws.on_upgrade(|mut socket| async move {
+                        let messages = [
+                            json!({ "type": "subscribed" }),
+                            json!({ "height": 5, "transactions": [
+                                { "hash": "0xmine", "from": "0xa", "to": "0xb", "token": "PROOF", "amount": "1" },
+                                { "kind": "stake", "tx_hash": "0xstake" }
+                            ] }),
+                            json!({ "block": { "height": 6, "transactions": [] } }),
+                        ];
+                        for message in messages {
+                            socket.send(Message::text(message.to_string())).await.ok();
+                        }
+                        socket.send(Message::Close(None)).await.ok();
+                    })
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        let adapter = FlowCortexAdapter::for_chain_endpoints(
+            FLOWCORTEX_L1,
+            &["http://127.0.0.1:9".to_owned(), format!("http://{address}")],
+        );
+        let submitted = adapter
+            .submit_transaction(SubmitTxRequest {
+                from: WalletAddress("0xa".to_owned()),
+                to: WalletAddress("0xb".to_owned()),
+                amount: Amount::new(1, 18),
+                asset: AssetSymbol("PROOF".to_owned()),
+                chain: ChainId(FLOWCORTEX_L1.to_owned()),
+                signed_payload: "sig".to_owned(),
+                chain_payload: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(submitted.tx_hash, "0xmine");
+
+        let events: Vec<BlockEvent> = adapter
+            .subscribe_blocks()
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(events.iter().map(|event| event.height).collect::<Vec<_>>(), vec![5, 6]);
+        assert_eq!(events[0].tx_hashes, vec!["0xmine", "0xstake"]);
+        assert_eq!(events[0].transfers.len(), 1);
+        assert_eq!(events[0].transfers[0].tx_hash, "0xmine");
+
+        let status = adapter
+            .get_transaction_status(TxStatusRequest {
+                chain: ChainId(FLOWCORTEX_L1.to_owned()),
+                tx_hash: "0xmine".to_owned(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(status.status, "confirmed");
+        assert_eq!(status.block_height, Some(5));
+        assert_eq!(status.confirmations, Some(2));
     }
-    output
 }
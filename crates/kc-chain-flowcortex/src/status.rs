@@ -0,0 +1,129 @@
+//! What [`FlowCortexAdapter`](crate::FlowCortexAdapter) remembers between
+//! tx status lookups.
+//!
+//! FlowCortex has no per-transaction endpoint, so a lookup scans the newest
+//! [`SCAN_WINDOW`] blocks for the hash. Hashes found there are kept in a
+//! small LRU, since a block never changes once produced, and answered
+//! without asking the node again. A hash that is not in the window is
+//! `pending` for [`PENDING_GRACE`] after this adapter submitted it and
+//! `not_found` otherwise.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Newest blocks searched for a tx hash.
+pub(crate) const SCAN_WINDOW: u64 = 256;
+/// How long a submitted transfer may take to show up in a block.
+pub(crate) const PENDING_GRACE: Duration = Duration::from_secs(10 * 60);
+const CONFIRMED_CAPACITY: usize = 1024;
+
+#[derive(Default)]
+pub(crate) struct StatusTracker {
+    confirmed: Mutex<ConfirmedCache>,
+    submitted: Mutex<HashMap<String, Instant>>,
+    /// Highest block height seen in any `/blocks` response.
+    tip: AtomicU64,
+}
+
+impl StatusTracker {
+    pub(crate) fn observe_tip(&self, height: u64) {
+        self.tip.fetch_max(height, Ordering::Relaxed);
+    }
+
+    pub(crate) fn tip(&self) -> Option<u64> {
+        Some(self.tip.load(Ordering::Relaxed)).filter(|tip| *tip > 0)
+    }
+
+    pub(crate) fn record_submitted(&self, tx_hash: &str) {
+        let mut submitted = self.submitted.lock().expect("submitted lock");
+        submitted.retain(|_, at| at.elapsed() < PENDING_GRACE);
+        submitted.insert(tx_hash.to_owned(), Instant::now());
+    }
+
+    /// Whether this adapter submitted `tx_hash` within [`PENDING_GRACE`].
+    pub(crate) fn recently_submitted(&self, tx_hash: &str) -> bool {
+        self.submitted
+            .lock()
+            .expect("submitted lock")
+            .get(tx_hash)
+            .is_some_and(|at| at.elapsed() < PENDING_GRACE)
+    }
+
+    pub(crate) fn record_confirmed(&self, tx_hash: &str, block_height: u64) {
+        self.submitted.lock().expect("submitted lock").remove(tx_hash);
+        self.confirmed.lock().expect("confirmed lock").insert(tx_hash, block_height);
+    }
+
+    /// Block height of a hash found earlier.
+    pub(crate) fn confirmed_height(&self, tx_hash: &str) -> Option<u64> {
+        self.confirmed.lock().expect("confirmed lock").get(tx_hash)
+    }
+}
+
+/// Least recently used hashes are dropped first.
+struct ConfirmedCache {
+    capacity: usize,
+    heights: HashMap<String, u64>,
+    /// Oldest use first.
+    order: VecDeque<String>,
+}
+
+impl Default for ConfirmedCache {
+    fn default() -> Self {
+        Self {
+            capacity: CONFIRMED_CAPACITY,
+            heights: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+}
+
+impl ConfirmedCache {
+    fn get(&mut self, tx_hash: &str) -> Option<u64> {
+        let height = *self.heights.get(tx_hash)?;
+        self.touch(tx_hash);
+        Some(height)
+    }
+
+    fn insert(&mut self, tx_hash: &str, height: u64) {
+        if self.heights.insert(tx_hash.to_owned(), height).is_some() {
+            self.touch(tx_hash);
+            return;
+        }
+        self.order.push_back(tx_hash.to_owned());
+        if self.order.len() > self.capacity
+            && let Some(evicted) = self.order.pop_front()
+        {
+            self.heights.remove(&evicted);
+        }
+    }
+
+    fn touch(&mut self, tx_hash: &str) {
+        if let Some(position) = self.order.iter().position(|hash| hash == tx_hash) {
+            let hash = self.order.remove(position).expect("position is in range");
+            self.order.push_back(hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmed_cache_evicts_the_least_recently_used_hash() {
+        let mut cache = ConfirmedCache {
+            capacity: 2,
+            ..ConfirmedCache::default()
+        };
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.get("a"), Some(1));
+        cache.insert("c", 3);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("c"), Some(3));
+    }
+}
@@ -0,0 +1,131 @@
+//! Push-style block updates from a FlowCortex node's WebSocket feed.
+//!
+//! [`FlowCortexAdapter::subscribe_blocks`] yields a [`BlockEvent`] for every
+//! block the node announces on [`BLOCKS_WS_PATH`]. Each block also feeds the
+//! adapter's block cache and tx status lookups, so a transfer confirmed on
+//! the feed is answered without another `/blocks` scan. The stream ends when
+//! the node closes the connection; subscribe again to resume; blocks
+//! produced in between still turn up in `/blocks`.
+
+use anyhow::{Context, Result, anyhow};
+use futures_util::{Stream, StreamExt};
+use kc_chain_client::ChainTransaction;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+use crate::{BlockResponse, FlowCortexAdapter};
+
+/// Block feed path, relative to a node's HTTP base URL.
+pub const BLOCKS_WS_PATH: &str = "/ws/blocks";
+
+/// A block announced on the feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockEvent {
+    pub height: u64,
+    /// Every hash the block lists, transfers or not.
+    pub tx_hashes: Vec<String>,
+    /// The block's transfers, as `list_transactions` reports them.
+    pub transfers: Vec<ChainTransaction>,
+}
+
+/// The block feed URL of the node at `http_url`.
+pub(crate) fn blocks_ws_url(http_url: &str) -> String {
+    let base = if let Some(rest) = http_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = http_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        http_url.to_owned()
+    };
+    format!("{}{BLOCKS_WS_PATH}", base.trim_end_matches('/'))
+}
+
+/// A feed message carries a block either as is or under `block`; anything
+/// else (subscription acks, heartbeats) is not a block.
+fn parse_block(text: &str) -> Option<BlockResponse> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let block = match value.get("block") {
+        Some(block) => block.clone(),
+        None => value,
+    };
+    serde_json::from_value(block).ok()
+}
+
+impl FlowCortexAdapter {
+    /// Connect to the block feed: `FLOWCORTEX_L1_WS_URL` or
+    /// [`Self::with_ws_url`] when set, else [`BLOCKS_WS_PATH`] on each
+    /// configured node in turn until one accepts.
+    pub async fn subscribe_blocks(&self) -> Result<impl Stream<Item = Result<BlockEvent>> + Send + '_> {
+        let urls = match &self.ws_url {
+            Some(url) => vec![url.clone()],
+            None => self.endpoints.urls().map(blocks_ws_url).collect(),
+        };
+        let mut last_error = None;
+        for url in &urls {
+            match tokio_tungstenite::connect_async(url.as_str()).await {
+                Ok((socket, _)) => {
+                    return Ok(socket.filter_map(move |message| async move { self.block_event(message) }));
+                }
+                Err(err) => {
+                    let err = anyhow!(err).context(format!("flowcortex block subscription ({url})"));
+                    warn!("{err:#}");
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("no endpoints")))
+            .with_context(|| format!("flowcortex block subscription: all {} endpoints failed", urls.len()))
+    }
+
+    fn block_event(
+        &self,
+        message: Result<Message, tokio_tungstenite::tungstenite::Error>,
+    ) -> Option<Result<BlockEvent>> {
+        let text = match message {
+            Ok(Message::Text(text)) => text.to_string(),
+            Ok(Message::Binary(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+            Ok(_) => return None,
+            Err(err) => return Some(Err(anyhow!(err).context("flowcortex block subscription"))),
+        };
+        let Some(block) = parse_block(&text) else {
+            debug!("flowcortex block subscription: ignoring non-block message");
+            return None;
+        };
+        Some(Ok(self.record_block(&block)))
+    }
+
+    /// Note a pushed block everywhere a `/blocks` scan would have.
+    fn record_block(&self, block: &BlockResponse) -> BlockEvent {
+        self.tx_status.observe_tip(block.height);
+        let tx_hashes: Vec<String> = block.tx_hashes().map(str::to_owned).collect();
+        for tx_hash in &tx_hashes {
+            // Other hashes are still found by a scan; only ours are likely
+            // to be asked about.
+            if self.tx_status.recently_submitted(tx_hash) {
+                self.tx_status.record_confirmed(tx_hash, block.height);
+            }
+        }
+        let transfers = self.block_transfers(block);
+        self.block_cache
+            .lock()
+            .expect("block cache lock")
+            .blocks
+            .insert(block.height, transfers.clone());
+        BlockEvent {
+            height: block.height,
+            tx_hashes,
+            transfers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_the_feed_url_from_the_node_url() {
+        assert_eq!(blocks_ws_url("http://node:8082/"), "ws://node:8082/ws/blocks");
+        assert_eq!(blocks_ws_url("https://node"), "wss://node/ws/blocks");
+    }
+}
@@ -0,0 +1,45 @@
+//! Stand-alone FlowCortex L1 simulator for local development and the UI demo.
+//!
+//! Listens on `FLOWCORTEX_SIM_ADDR` (default `127.0.0.1:8082`). Accounts in
+//! `FLOWCORTEX_SIM_FUND`, a comma-separated list of `account:token:amount`,
+//! are funded at startup. Point wallet-service at it with
+//! `FLOWCORTEX_L1_URL=http://127.0.0.1:8082`.
+
+use anyhow::{Context, Result, bail};
+use kc_chain_mock::simulator::Simulator;
+use std::env;
+use std::sync::Arc;
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .init();
+
+    let sim = Arc::new(Simulator::default());
+    for entry in env::var("FLOWCORTEX_SIM_FUND")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let [account, token, amount] = entry.split(':').collect::<Vec<_>>()[..] else {
+            bail!("FLOWCORTEX_SIM_FUND entry '{entry}' must be account:token:amount");
+        };
+        let amount = amount
+            .parse()
+            .with_context(|| format!("FLOWCORTEX_SIM_FUND entry '{entry}' amount"))?;
+        sim.fund(account, token, amount);
+    }
+
+    let addr = env::var("FLOWCORTEX_SIM_ADDR").unwrap_or_else(|_| "127.0.0.1:8082".to_owned());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("bind {addr}"))?;
+    info!("flowcortex simulator listening on {}", listener.local_addr()?);
+    axum::serve(listener, sim.router()).await?;
+    Ok(())
+}
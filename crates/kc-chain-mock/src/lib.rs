@@ -0,0 +1,385 @@
+//! Chains without a node, for tests and demos.
+//!
+//! [`MockAdapter`] is a [`ChainAdapter`] whose balances, tx statuses,
+//! failures and latency are scripted by the test. [`simulator`] serves the
+//! FlowCortex L1 REST API from memory, so the real `FlowCortexAdapter` and
+//! the wallet UI can run against it; the `flowcortex-sim` binary wraps it.
+
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+use kc_chain_client::{
+    AdapterHealth, BalanceResult, ChainAdapter, ChainTransaction, ChainTransactionPage, FeeEstimate,
+    FeeEstimateRequest, NonceStrategy, SubmitTxRequest, SubmitTxResult, TxStatusRequest, TxStatusResult,
+};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+pub mod simulator;
+
+/// A [`MockAdapter`] call that failures and latency can be scripted for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Health,
+    Balance,
+    Submit,
+    TxStatus,
+    EstimateFee,
+    ListTransactions,
+}
+
+struct Failure {
+    message: String,
+    /// Calls left to fail; `None` fails until cleared.
+    remaining: Option<usize>,
+}
+
+#[derive(Default)]
+struct MockState {
+    /// Base units keyed by (wallet, asset).
+    balances: HashMap<(String, String), u128>,
+    /// Statuses set with [`MockAdapter::set_tx_status`], ahead of settled ones.
+    scripted: HashMap<String, TxStatusResult>,
+    /// Block height each accepted transfer settled in.
+    settled: HashMap<String, u64>,
+    transfers: Vec<ChainTransaction>,
+    submitted: Vec<SubmitTxRequest>,
+    failures: HashMap<Operation, Failure>,
+    calls: HashMap<Operation, usize>,
+    height: u64,
+}
+
+/// Scripted [`ChainAdapter`].
+///
+/// A submission the sender's balance covers is accepted and settles in a
+/// new block straight away; one it does not cover comes back
+/// `accepted: false`. Every call first waits out the latency and then fails
+/// if a failure is scripted for its [`Operation`].
+pub struct MockAdapter {
+    chain_id: String,
+    nonce_strategy: NonceStrategy,
+    fee: Option<(u128, String)>,
+    latency: Mutex<Duration>,
+    state: Mutex<MockState>,
+}
+
+impl MockAdapter {
+    pub fn new(chain_id: &str) -> Self {
+        Self {
+            chain_id: chain_id.to_owned(),
+            nonce_strategy: NonceStrategy::Sequential,
+            fee: None,
+            latency: Mutex::new(Duration::ZERO),
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn with_balance(self, wallet_address: &str, asset: &str, amount: u128) -> Self {
+        self.set_balance(wallet_address, asset, amount);
+        self
+    }
+
+    pub fn with_nonce_strategy(mut self, nonce_strategy: NonceStrategy) -> Self {
+        self.nonce_strategy = nonce_strategy;
+        self
+    }
+
+    /// Quote `fee` base units of `fee_asset` for every transfer. Without
+    /// one, fee estimates fail as for a chain that does not estimate fees.
+    pub fn with_fee(mut self, fee: u128, fee_asset: &str) -> Self {
+        self.fee = Some((fee, fee_asset.to_owned()));
+        self
+    }
+
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.set_latency(latency);
+        self
+    }
+
+    pub fn set_balance(&self, wallet_address: &str, asset: &str, amount: u128) {
+        self.lock()
+            .balances
+            .insert((wallet_address.to_owned(), asset.to_owned()), amount);
+    }
+
+    pub fn balance(&self, wallet_address: &str, asset: &str) -> u128 {
+        self.lock()
+            .balances
+            .get(&(wallet_address.to_owned(), asset.to_owned()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Delay every call by `latency`.
+    pub fn set_latency(&self, latency: Duration) {
+        *self.latency.lock().unwrap_or_else(|err| err.into_inner()) = latency;
+    }
+
+    /// Fail every `operation` call with `message` until cleared.
+    pub fn fail(&self, operation: Operation, message: &str) {
+        self.script_failure(operation, message, None);
+    }
+
+    /// Fail the next `times` `operation` calls with `message`.
+    pub fn fail_times(&self, operation: Operation, times: usize, message: &str) {
+        self.script_failure(operation, message, Some(times));
+    }
+
+    pub fn clear_failures(&self) {
+        self.lock().failures.clear();
+    }
+
+    /// Answer status lookups for `tx_hash` with `status`, e.g. `pending` or
+    /// `failed`, whatever the mock settled.
+    pub fn set_tx_status(&self, tx_hash: &str, status: &str, block_height: Option<u64>) {
+        let mut state = self.lock();
+        let confirmations = block_height.map(|height| state.height.max(height) - height + 1);
+        state.scripted.insert(
+            tx_hash.to_owned(),
+            TxStatusResult {
+                tx_hash: tx_hash.to_owned(),
+                status: status.to_owned(),
+                accepted: status != "failed" && status != "not_found",
+                block_height,
+                confirmations,
+            },
+        );
+    }
+
+    /// Every submission received, accepted or not, oldest first.
+    pub fn submitted(&self) -> Vec<SubmitTxRequest> {
+        self.lock().submitted.clone()
+    }
+
+    /// How many times `operation` was called, failed calls included.
+    pub fn calls(&self, operation: Operation) -> usize {
+        self.lock().calls.get(&operation).copied().unwrap_or(0)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn script_failure(&self, operation: Operation, message: &str, remaining: Option<usize>) {
+        self.lock().failures.insert(
+            operation,
+            Failure {
+                message: message.to_owned(),
+                remaining,
+            },
+        );
+    }
+
+    /// Count the call, wait out the latency and apply any scripted failure.
+    async fn enter(&self, operation: Operation) -> Result<()> {
+        *self.lock().calls.entry(operation).or_default() += 1;
+        let latency = *self.latency.lock().unwrap_or_else(|err| err.into_inner());
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        let mut state = self.lock();
+        let Some(failure) = state.failures.get_mut(&operation) else {
+            return Ok(());
+        };
+        let message = failure.message.clone();
+        match &mut failure.remaining {
+            None => {}
+            Some(1) => {
+                state.failures.remove(&operation);
+            }
+            Some(remaining) => *remaining -= 1,
+        }
+        Err(anyhow!("{}: {message}", self.chain_id))
+    }
+}
+
+#[async_trait]
+impl ChainAdapter for MockAdapter {
+    fn chain_id(&self) -> &str {
+        &self.chain_id
+    }
+
+    fn nonce_strategy(&self) -> NonceStrategy {
+        self.nonce_strategy
+    }
+
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async {
+            self.enter(Operation::Health).await?;
+            Ok(Some(self.lock().height))
+        })
+        .await
+    }
+
+    async fn estimate_fee(&self, _req: FeeEstimateRequest) -> Result<FeeEstimate> {
+        self.enter(Operation::EstimateFee).await?;
+        let Some((fee, fee_asset)) = &self.fee else {
+            bail!("{} does not estimate fees", self.chain_id);
+        };
+        Ok(FeeEstimate {
+            fee: fee.to_string(),
+            fee_asset: AssetSymbol(fee_asset.clone()),
+        })
+    }
+
+    async fn list_transactions(
+        &self,
+        wallet_address: &WalletAddress,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<ChainTransactionPage> {
+        self.enter(Operation::ListTransactions).await?;
+        let state = self.lock();
+        // The cursor is the index of the last transfer returned.
+        let before = match cursor {
+            Some(cursor) => cursor.parse::<usize>().map_err(|_| anyhow!("invalid cursor '{cursor}'"))?,
+            None => state.transfers.len(),
+        };
+        let mut matches = state.transfers[..before.min(state.transfers.len())]
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, tx)| tx.from == *wallet_address || tx.to == *wallet_address);
+        let page: Vec<_> = matches.by_ref().take(limit).collect();
+        let next_cursor = match (page.last(), matches.next()) {
+            (Some((index, _)), Some(_)) => Some(index.to_string()),
+            _ => None,
+        };
+        Ok(ChainTransactionPage {
+            transactions: page.into_iter().map(|(_, tx)| tx.clone()).collect(),
+            next_cursor,
+        })
+    }
+
+    async fn get_balance(&self, wallet_address: &WalletAddress, asset: &AssetSymbol) -> Result<BalanceResult> {
+        self.enter(Operation::Balance).await?;
+        Ok(BalanceResult {
+            wallet_address: wallet_address.clone(),
+            chain: ChainId(self.chain_id.clone()),
+            asset: asset.clone(),
+            amount: Amount::new(self.balance(&wallet_address.0, &asset.0), 0),
+        })
+    }
+
+    async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+        self.enter(Operation::Submit).await?;
+        let amount = req.amount.base_units();
+        let mut state = self.lock();
+        state.submitted.push(req.clone());
+        let tx_hash = format!("0xmock{:016x}", state.submitted.len());
+
+        let from_key = (req.from.0.clone(), req.asset.0.clone());
+        let available = state.balances.get(&from_key).copied().unwrap_or(0);
+        if available < amount {
+            return Ok(SubmitTxResult {
+                tx_hash,
+                accepted: false,
+            });
+        }
+        state.balances.insert(from_key, available - amount);
+        *state
+            .balances
+            .entry((req.to.0.clone(), req.asset.0.clone()))
+            .or_default() += amount;
+        state.height += 1;
+        let height = state.height;
+        state.settled.insert(tx_hash.clone(), height);
+        state.transfers.push(ChainTransaction {
+            tx_hash: tx_hash.clone(),
+            from: req.from,
+            to: req.to,
+            asset: req.asset,
+            amount: amount.to_string(),
+            block_height: Some(height),
+        });
+        Ok(SubmitTxResult {
+            tx_hash,
+            accepted: true,
+        })
+    }
+
+    async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+        self.enter(Operation::TxStatus).await?;
+        let state = self.lock();
+        if let Some(scripted) = state.scripted.get(&req.tx_hash) {
+            return Ok(scripted.clone());
+        }
+        Ok(match state.settled.get(&req.tx_hash) {
+            Some(&block_height) => TxStatusResult {
+                tx_hash: req.tx_hash,
+                status: "confirmed".to_owned(),
+                accepted: true,
+                block_height: Some(block_height),
+                confirmations: Some(state.height - block_height + 1),
+            },
+            None => TxStatusResult {
+                tx_hash: req.tx_hash,
+                status: "not_found".to_owned(),
+                accepted: false,
+                block_height: None,
+                confirmations: None,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(amount: u128) -> SubmitTxRequest {
+        SubmitTxRequest {
+            from: WalletAddress("0xa".to_owned()),
+            to: WalletAddress("0xb".to_owned()),
+            amount: Amount::new(amount, 18),
+            asset: AssetSymbol("PROOF".to_owned()),
+            chain: ChainId("mock".to_owned()),
+            signed_payload: "sig".to_owned(),
+            chain_payload: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn settles_covered_transfers_and_injects_failures() {
+        let adapter = MockAdapter::new("mock").with_balance("0xa", "PROOF", 10);
+
+        let accepted = adapter.submit_transaction(transfer(7)).await.unwrap();
+        assert!(accepted.accepted);
+        let rejected = adapter.submit_transaction(transfer(7)).await.unwrap();
+        assert!(!rejected.accepted, "only 3 left");
+        assert_eq!(adapter.balance("0xa", "PROOF"), 3);
+        assert_eq!(adapter.balance("0xb", "PROOF"), 7);
+        assert_eq!(adapter.submitted().len(), 2);
+
+        let status = |tx_hash: &str| {
+            adapter.get_transaction_status(TxStatusRequest {
+                tx_hash: tx_hash.to_owned(),
+                chain: ChainId("mock".to_owned()),
+            })
+        };
+        assert_eq!(status(&accepted.tx_hash).await.unwrap().status, "confirmed");
+        assert_eq!(status(&rejected.tx_hash).await.unwrap().status, "not_found");
+        adapter.set_tx_status(&accepted.tx_hash, "failed", Some(1));
+        assert!(!status(&accepted.tx_hash).await.unwrap().accepted);
+
+        adapter.fail_times(Operation::Balance, 2, "node unavailable");
+        let wallet = WalletAddress("0xa".to_owned());
+        let asset = AssetSymbol("PROOF".to_owned());
+        for _ in 0..2 {
+            let err = adapter.get_balance(&wallet, &asset).await.unwrap_err();
+            assert_eq!(err.to_string(), "mock: node unavailable");
+        }
+        assert_eq!(adapter.get_balance(&wallet, &asset).await.unwrap().amount.base_units(), 3);
+        assert_eq!(adapter.calls(Operation::Balance), 3);
+
+        adapter.fail(Operation::Health, "down");
+        assert!(!adapter.health().await.reachable);
+        adapter.clear_failures();
+        assert_eq!(adapter.health().await.block_height, Some(1));
+
+        let page = adapter.list_transactions(&wallet, None, 10).await.unwrap();
+        assert_eq!(page.transactions.len(), 1);
+        assert_eq!(page.transactions[0].tx_hash, accepted.tx_hash);
+    }
+}
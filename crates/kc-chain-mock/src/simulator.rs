@@ -0,0 +1,281 @@
+//! In-process FlowCortex L1 node.
+//!
+//! Serves the part of the node's REST API that `FlowCortexAdapter` uses, from
+//! memory:
+//!
+//! - `GET /balance/{account}/{token}`, 404 for an account it has not seen
+//! - `POST /transfer`, settling each transfer in a new block
+//! - `GET /blocks`, newest first
+//! - `GET /ws/blocks`, pushing every new block
+//!
+//! plus `POST /faucet` (`{account, token, amount}`) to fund accounts. Nothing
+//! is persisted.
+
+use anyhow::{Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use kc_crypto::encoding::to_hex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::broadcast;
+
+/// Blocks `GET /blocks` keeps listing; older ones are pruned as a node would.
+const MAX_BLOCKS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+struct Block {
+    height: u64,
+    transactions: Vec<Transfer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Transfer {
+    hash: String,
+    from: String,
+    to: String,
+    token: String,
+    amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransferRequest {
+    from: String,
+    to: String,
+    token: String,
+    amount: u128,
+}
+
+#[derive(Debug, Deserialize)]
+struct FaucetRequest {
+    account: String,
+    token: String,
+    amount: u128,
+}
+
+#[derive(Default)]
+struct Ledger {
+    /// Base units keyed by (account, token).
+    balances: HashMap<(String, String), u128>,
+    /// Newest first.
+    blocks: VecDeque<Block>,
+    height: u64,
+}
+
+pub struct Simulator {
+    ledger: Mutex<Ledger>,
+    /// New blocks, as JSON, for `/ws/blocks` subscribers.
+    blocks: broadcast::Sender<String>,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self {
+            ledger: Mutex::default(),
+            blocks: broadcast::channel(64).0,
+        }
+    }
+}
+
+impl Simulator {
+    /// Add `amount` to an account's balance.
+    pub fn fund(&self, account: &str, token: &str, amount: u128) -> u128 {
+        let mut ledger = self.lock();
+        let balance = ledger
+            .balances
+            .entry((account.to_owned(), token.to_owned()))
+            .or_default();
+        *balance = balance.saturating_add(amount);
+        *balance
+    }
+
+    pub fn balance(&self, account: &str, token: &str) -> Option<u128> {
+        self.lock()
+            .balances
+            .get(&(account.to_owned(), token.to_owned()))
+            .copied()
+    }
+
+    pub fn height(&self) -> u64 {
+        self.lock().height
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/balance/{account}/{token}", get(balance))
+            .route("/transfer", post(transfer))
+            .route("/blocks", get(blocks))
+            .route("/ws/blocks", get(subscribe))
+            .route("/faucet", post(faucet))
+            .with_state(self)
+    }
+
+    /// Serve on `addr` in the background and return the bound address, so
+    /// tests can bind port 0.
+    pub async fn spawn(self: Arc<Self>, addr: &str) -> Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("bind {addr}"))?;
+        let bound = listener.local_addr()?;
+        let app = self.router();
+        tokio::spawn(async move {
+            if let Err(err) = axum::serve(listener, app).await {
+                tracing::error!("flowcortex simulator stopped: {err}");
+            }
+        });
+        Ok(bound)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Ledger> {
+        self.ledger.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn settle(&self, request: TransferRequest) -> Result<String, String> {
+        let mut ledger = self.lock();
+        let from_key = (request.from.clone(), request.token.clone());
+        let available = ledger.balances.get(&from_key).copied().unwrap_or(0);
+        if available < request.amount {
+            return Err(format!("insufficient balance: {available} < {}", request.amount));
+        }
+        ledger.balances.insert(from_key, available - request.amount);
+        let credit = ledger
+            .balances
+            .entry((request.to.clone(), request.token.clone()))
+            .or_default();
+        *credit = credit.saturating_add(request.amount);
+
+        ledger.height += 1;
+        let height = ledger.height;
+        let hash = format!(
+            "0x{}",
+            to_hex(&Sha256::digest(format!(
+                "flowcortex-sim:{height}:{}:{}:{}:{}",
+                request.from, request.to, request.token, request.amount
+            )))
+        );
+        let block = Block {
+            height,
+            transactions: vec![Transfer {
+                hash: hash.clone(),
+                from: request.from,
+                to: request.to,
+                token: request.token,
+                amount: request.amount.to_string(),
+            }],
+        };
+        // No subscribers is fine.
+        let _ = self.blocks.send(json!(block).to_string());
+        ledger.blocks.push_front(block);
+        ledger.blocks.truncate(MAX_BLOCKS);
+        Ok(hash)
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+async fn balance(State(sim): State<Arc<Simulator>>, Path((account, token)): Path<(String, String)>) -> Response {
+    match sim.balance(&account, &token) {
+        Some(balance) => Json(json!({ "account": account, "token": token, "balance": balance })).into_response(),
+        None => error(StatusCode::NOT_FOUND, "account not found"),
+    }
+}
+
+async fn transfer(State(sim): State<Arc<Simulator>>, Json(request): Json<TransferRequest>) -> Response {
+    match sim.settle(request) {
+        Ok(hash) => (StatusCode::CREATED, Json(json!({ "status": "ok", "hash": hash }))).into_response(),
+        Err(message) => error(StatusCode::BAD_REQUEST, &message),
+    }
+}
+
+async fn blocks(State(sim): State<Arc<Simulator>>) -> Json<Vec<Block>> {
+    Json(sim.lock().blocks.iter().cloned().collect())
+}
+
+async fn faucet(State(sim): State<Arc<Simulator>>, Json(request): Json<FaucetRequest>) -> Response {
+    let balance = sim.fund(&request.account, &request.token, request.amount);
+    Json(json!({ "account": request.account, "token": request.token, "balance": balance })).into_response()
+}
+
+async fn subscribe(State(sim): State<Arc<Simulator>>, ws: WebSocketUpgrade) -> Response {
+    let blocks = sim.blocks.subscribe();
+    ws.on_upgrade(move |socket| forward_blocks(socket, blocks))
+}
+
+async fn forward_blocks(mut socket: WebSocket, mut blocks: broadcast::Receiver<String>) {
+    loop {
+        match blocks.recv().await {
+            Ok(block) => {
+                if socket.send(Message::text(block)).await.is_err() {
+                    return;
+                }
+            }
+            // A slow subscriber misses blocks; `/blocks` still lists them.
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+    use kc_chain_client::{ChainAdapter, SubmitTxRequest, TxStatusRequest};
+    use kc_chain_flowcortex::{FLOWCORTEX_L1, FlowCortexAdapter};
+
+    #[tokio::test]
+    async fn serves_the_flowcortex_adapter() {
+        let sim = Arc::new(Simulator::default());
+        sim.fund("0xa", "PROOF", 100);
+        let address = Arc::clone(&sim).spawn("127.0.0.1:0").await.unwrap();
+        let adapter = FlowCortexAdapter::for_chain(FLOWCORTEX_L1, &format!("http://{address}"));
+        let mut feed = Box::pin(adapter.subscribe_blocks().await.unwrap());
+
+        let submit = |amount: u128| SubmitTxRequest {
+            from: WalletAddress("0xa".to_owned()),
+            to: WalletAddress("0xb".to_owned()),
+            amount: Amount::new(amount, 18),
+            asset: AssetSymbol("PROOF".to_owned()),
+            chain: ChainId(FLOWCORTEX_L1.to_owned()),
+            signed_payload: "sig".to_owned(),
+            chain_payload: None,
+        };
+        let result = adapter.submit_transaction(submit(40)).await.unwrap();
+        assert!(result.accepted);
+        assert!(result.tx_hash.starts_with("0x"));
+        let rejected = adapter.submit_transaction(submit(1000)).await.unwrap();
+        assert!(!rejected.accepted);
+
+        let pushed = feed.next().await.unwrap().unwrap();
+        assert_eq!(pushed.height, 1);
+        assert_eq!(pushed.tx_hashes, vec![result.tx_hash.clone()]);
+
+        let status = adapter
+            .get_transaction_status(TxStatusRequest {
+                tx_hash: result.tx_hash,
+                chain: ChainId(FLOWCORTEX_L1.to_owned()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(status.status, "confirmed");
+        let proof = AssetSymbol("PROOF".to_owned());
+        for (wallet, expected) in [("0xa", 60), ("0xb", 40), ("0xc", 0)] {
+            let balance = adapter
+                .get_balance(&WalletAddress(wallet.to_owned()), &proof)
+                .await
+                .unwrap();
+            assert_eq!(balance.amount.base_units(), expected, "{wallet}");
+        }
+        assert_eq!(adapter.health().await.block_height, Some(1));
+    }
+}
@@ -0,0 +1,88 @@
+//! AWS KMS `Sign` over the JSON 1.1 protocol, signed with [`kc_crypto::sigv4`].
+
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use kc_crypto::sigv4::{AwsCredentials, SigV4Request, amz_date, authorization_header};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignResponse {
+    signature: String,
+}
+
+pub(crate) async fn sign(
+    http: &reqwest::Client,
+    credentials: &AwsCredentials,
+    region: &str,
+    key_id: &str,
+    signing_algorithm: &str,
+    endpoint: Option<&str>,
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let url = match endpoint {
+        Some(endpoint) => endpoint.trim_end_matches('/').to_owned(),
+        None => format!("https://kms.{region}.amazonaws.com"),
+    };
+    let parsed = reqwest::Url::parse(&url).context("invalid AWS KMS endpoint")?;
+    let host = match (parsed.host_str(), parsed.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_owned(),
+        (None, _) => return Err(anyhow!("AWS KMS endpoint has no host")),
+    };
+
+    let body = serde_json::json!({
+        "KeyId": key_id,
+        "Message": STANDARD.encode(message),
+        "MessageType": "RAW",
+        "SigningAlgorithm": signing_algorithm,
+    })
+    .to_string();
+
+    let amz_date = amz_date(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs());
+    let mut headers = vec![
+        ("content-type", "application/x-amz-json-1.1".to_owned()),
+        ("host", host),
+        ("x-amz-date", amz_date.clone()),
+        ("x-amz-target", "TrentService.Sign".to_owned()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    let authorization = authorization_header(
+        credentials,
+        &SigV4Request {
+            method: "POST",
+            path: "/",
+            query: "",
+            headers: &headers,
+            payload: body.as_bytes(),
+        },
+        &amz_date,
+        region,
+        "kms",
+    );
+
+    let mut request = http.post(&url).header("authorization", authorization);
+    for (name, value) in &headers {
+        if *name != "host" {
+            request = request.header(*name, value);
+        }
+    }
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .context("aws kms sign transport")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow!("aws kms sign HTTP {status}: {text}"));
+    }
+    let parsed: SignResponse = response.json().await.context("aws kms sign response")?;
+    STANDARD
+        .decode(parsed.signature)
+        .context("aws kms signature is not base64")
+}
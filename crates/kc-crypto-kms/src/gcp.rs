@@ -0,0 +1,198 @@
+//! GCP Cloud KMS `asymmetricSign` over REST.
+
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+const DEFAULT_ENDPOINT: &str = "https://cloudkms.googleapis.com";
+const DEFAULT_METADATA_HOST: &str = "metadata.google.internal";
+const METADATA_TOKEN_PATH: &str = "/computeMetadata/v1/instance/service-accounts/default/token";
+const CLOUDKMS_SCOPE: &str = "https://www.googleapis.com/auth/cloudkms";
+const JWT_BEARER_GRANT: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+/// Lifetime asked for in a service-account assertion; Google's maximum.
+const ASSERTION_LIFETIME_SECS: u64 = 3600;
+/// A cached token is replaced once it has less than this left.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// The fields of a service account key file that token exchange needs.
+#[derive(Clone, Deserialize)]
+pub(crate) struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    private_key_id: Option<String>,
+    token_uri: String,
+}
+
+#[derive(Serialize)]
+struct AssertionClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+enum Credentials {
+    /// The service account attached to the GCE/GKE instance.
+    Metadata { host: String },
+    /// A key file, exchanged for tokens with a self-signed assertion.
+    ServiceAccount {
+        key: ServiceAccountKey,
+        signing_key: EncodingKey,
+    },
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// OAuth access tokens for Cloud KMS, cached until shortly before they expire.
+pub(crate) struct TokenSource {
+    credentials: Credentials,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl TokenSource {
+    /// The key file named by `GOOGLE_APPLICATION_CREDENTIALS` if set, else
+    /// the metadata server at `GCE_METADATA_HOST` (default
+    /// `metadata.google.internal`).
+    pub(crate) fn from_env() -> Result<Self> {
+        match std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
+            Ok(path) if !path.trim().is_empty() => {
+                let json = std::fs::read_to_string(path.trim())
+                    .with_context(|| format!("failed to read gcp credentials {path}"))?;
+                let key: ServiceAccountKey =
+                    serde_json::from_str(&json).context("invalid gcp service account key file")?;
+                Self::service_account(key)
+            }
+            _ => Ok(Self::metadata(
+                std::env::var("GCE_METADATA_HOST")
+                    .ok()
+                    .filter(|host| !host.trim().is_empty())
+                    .unwrap_or_else(|| DEFAULT_METADATA_HOST.to_owned()),
+            )),
+        }
+    }
+
+    pub(crate) fn metadata(host: String) -> Self {
+        Self::new(Credentials::Metadata { host })
+    }
+
+    pub(crate) fn service_account(key: ServiceAccountKey) -> Result<Self> {
+        let signing_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .context("gcp service account private_key is not an RSA PEM key")?;
+        Ok(Self::new(Credentials::ServiceAccount { key, signing_key }))
+    }
+
+    fn new(credentials: Credentials) -> Self {
+        Self {
+            credentials,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// A token valid for at least [`TOKEN_REFRESH_MARGIN`], fetching a new
+    /// one when the cached token is missing or about to expire.
+    pub(crate) async fn access_token(&self, http: &reqwest::Client) -> Result<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref()
+            && token.expires_at > Instant::now() + TOKEN_REFRESH_MARGIN
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let fetched_at = Instant::now();
+        let response = self.fetch(http).await?;
+        let access_token = response.access_token.clone();
+        *cached = Some(CachedToken {
+            access_token: response.access_token,
+            expires_at: fetched_at + Duration::from_secs(response.expires_in),
+        });
+        Ok(access_token)
+    }
+
+    async fn fetch(&self, http: &reqwest::Client) -> Result<TokenResponse> {
+        let request = match &self.credentials {
+            Credentials::Metadata { host } => http
+                .get(format!("http://{host}{METADATA_TOKEN_PATH}"))
+                .header("Metadata-Flavor", "Google"),
+            Credentials::ServiceAccount { key, signing_key } => {
+                let iat = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                let claims = AssertionClaims {
+                    iss: &key.client_email,
+                    scope: CLOUDKMS_SCOPE,
+                    aud: &key.token_uri,
+                    iat,
+                    exp: iat + ASSERTION_LIFETIME_SECS,
+                };
+                let mut header = Header::new(Algorithm::RS256);
+                header.kid = key.private_key_id.clone();
+                let assertion = jsonwebtoken::encode(&header, &claims, signing_key)
+                    .context("failed to sign gcp token assertion")?;
+                http.post(&key.token_uri)
+                    .form(&[("grant_type", JWT_BEARER_GRANT), ("assertion", &assertion)])
+            }
+        };
+
+        let response = request.send().await.context("gcp token transport")?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("gcp token HTTP {status}: {text}"));
+        }
+        response.json().await.context("gcp token response")
+    }
+}
+
+/// Sign with an `EC_SIGN_ED25519` key version (PureEdDSA: raw `data`, no digest).
+pub(crate) async fn sign(
+    http: &reqwest::Client,
+    tokens: &TokenSource,
+    key_version_name: &str,
+    endpoint: Option<&str>,
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let url = format!(
+        "{}/v1/{}:asymmetricSign",
+        endpoint.unwrap_or(DEFAULT_ENDPOINT).trim_end_matches('/'),
+        key_version_name
+    );
+    let token = tokens.access_token(http).await?;
+
+    let response = http
+        .post(&url)
+        .bearer_auth(token)
+        .json(&serde_json::json!({ "data": STANDARD.encode(message) }))
+        .send()
+        .await
+        .context("gcp kms asymmetricSign transport")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow!("gcp kms asymmetricSign HTTP {status}: {text}"));
+    }
+    let parsed: AsymmetricSignResponse = response
+        .json()
+        .await
+        .context("gcp kms asymmetricSign response")?;
+    STANDARD
+        .decode(parsed.signature)
+        .context("gcp kms signature is not base64")
+}
@@ -0,0 +1,508 @@
+//! Cloud KMS and HSM signer backends.
+//!
+//! [`KmsSigner`] implements [`kc_crypto::Signer`] against Ed25519 keys held in
+//! AWS KMS, GCP Cloud KMS or a PKCS#11 HSM (feature `pkcs11`), so the secret
+//! key never exists in KeyCortex memory or RocksDB. Keys are mapped to wallets by a JSON registry, loaded from
+//! `KEYCORTEX_KMS_KEYS_FILE`:
+//!
+//! ```json
+//! [
+//!   { "provider": "aws", "public_key": "<hex>", "region": "eu-west-1", "key_id": "alias/treasury" },
+//!   { "provider": "gcp", "public_key": "<hex>",
+//!     "key_version_name": "projects/p/locations/l/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1" },
+//!   { "provider": "pkcs11", "public_key": "<hex>", "label": "treasury" }
+//! ]
+//! ```
+//!
+//! The wallet address is derived from `public_key` exactly as for custodied
+//! keys, and every KMS signature is verified against it before use.
+//!
+//! GCP access tokens come from the key file named by
+//! `GOOGLE_APPLICATION_CREDENTIALS`, or else the metadata server, and are
+//! refreshed shortly before they expire. Async callers should use
+//! [`KmsSigner::sign_in_domain_async`]; the [`Signer`] impl blocks the calling
+//! thread for the round trip.
+
+mod aws;
+mod gcp;
+#[cfg(feature = "pkcs11")]
+mod pkcs11;
+
+use anyhow::{Context, Result, anyhow};
+use kc_api_types::SignPurpose;
+use kc_crypto::{Ed25519PublicKey, Signer, SigningDomain};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use kc_crypto::sigv4::AwsCredentials;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum KmsProvider {
+    Aws {
+        region: String,
+        /// Key ID, ARN or alias.
+        key_id: String,
+        #[serde(default = "default_aws_signing_algorithm")]
+        signing_algorithm: String,
+        /// Override for VPC endpoints or testing.
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    Gcp {
+        /// Full `projects/.../cryptoKeyVersions/N` resource name.
+        key_version_name: String,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    /// Ed25519 key pair on a PKCS#11 token; slot and PIN come from the environment.
+    Pkcs11 {
+        /// `CKA_LABEL` shared by the private and public key objects.
+        label: String,
+    },
+}
+
+fn default_aws_signing_algorithm() -> String {
+    "ED25519_SHA_512".to_owned()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KmsKeyConfig {
+    /// Hex Ed25519 public key of the KMS key.
+    pub public_key: String,
+    #[serde(flatten)]
+    pub provider: KmsProvider,
+}
+
+pub struct KmsSigner {
+    public_key: Ed25519PublicKey,
+    provider: KmsProvider,
+    http: reqwest::Client,
+    aws_credentials: Option<AwsCredentials>,
+    gcp_tokens: Option<gcp::TokenSource>,
+    #[cfg(feature = "pkcs11")]
+    hsm: Option<pkcs11::HsmKey>,
+}
+
+impl KmsSigner {
+    pub const SIGNATURE_SCHEME: &'static str = "ed25519";
+
+    /// Build a signer; AWS and GCP credentials come from the environment.
+    pub fn new(config: KmsKeyConfig) -> Result<Self> {
+        let public_key = Ed25519PublicKey::from_hex(&config.public_key)?;
+        let aws_credentials = match config.provider {
+            KmsProvider::Aws { .. } => Some(AwsCredentials::from_env()?),
+            KmsProvider::Gcp { .. } | KmsProvider::Pkcs11 { .. } => None,
+        };
+        #[cfg(feature = "pkcs11")]
+        let hsm = match &config.provider {
+            KmsProvider::Pkcs11 { label } => Some(pkcs11::HsmKey::open(label, &public_key)?),
+            _ => None,
+        };
+        #[cfg(not(feature = "pkcs11"))]
+        if matches!(config.provider, KmsProvider::Pkcs11 { .. }) {
+            return Err(anyhow!(
+                "pkcs11 keys require kc-crypto-kms built with the `pkcs11` feature"
+            ));
+        }
+        let gcp_tokens = match config.provider {
+            KmsProvider::Gcp { .. } => Some(gcp::TokenSource::from_env()?),
+            KmsProvider::Aws { .. } | KmsProvider::Pkcs11 { .. } => None,
+        };
+        Ok(Self {
+            public_key,
+            provider: config.provider,
+            http: reqwest::Client::new(),
+            aws_credentials,
+            gcp_tokens,
+            #[cfg(feature = "pkcs11")]
+            hsm,
+        })
+    }
+
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        self.public_key
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        self.public_key.to_hex()
+    }
+
+    pub fn wallet_address(&self) -> String {
+        self.public_key.wallet_address()
+    }
+
+    pub fn provider_name(&self) -> &'static str {
+        match self.provider {
+            KmsProvider::Aws { .. } => "aws-kms",
+            KmsProvider::Gcp { .. } => "gcp-kms",
+            KmsProvider::Pkcs11 { .. } => "pkcs11-hsm",
+        }
+    }
+
+    /// Async form of [`Signer::sign_in_domain`]; prefer this from async code.
+    pub async fn sign_in_domain_async(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>> {
+        self.sign_with(&self.http, domain, payload, purpose).await
+    }
+
+    async fn sign_with(
+        &self,
+        http: &reqwest::Client,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>> {
+        if payload.is_empty() {
+            return Err(anyhow!("payload cannot be empty"));
+        }
+        let message = domain.signing_input(payload, &purpose);
+
+        let signature = match &self.provider {
+            KmsProvider::Aws {
+                region,
+                key_id,
+                signing_algorithm,
+                endpoint,
+            } => {
+                let credentials = self
+                    .aws_credentials
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("aws credentials not configured"))?;
+                aws::sign(
+                    http,
+                    credentials,
+                    region,
+                    key_id,
+                    signing_algorithm,
+                    endpoint.as_deref(),
+                    &message,
+                )
+                .await?
+            }
+            KmsProvider::Gcp {
+                key_version_name,
+                endpoint,
+            } => {
+                let tokens = self
+                    .gcp_tokens
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("gcp credentials not configured"))?;
+                gcp::sign(http, tokens, key_version_name, endpoint.as_deref(), &message).await?
+            }
+            KmsProvider::Pkcs11 { .. } => self.sign_hsm(&message)?,
+        };
+        self.checked(domain, payload, purpose, signature)
+    }
+
+    #[cfg(feature = "pkcs11")]
+    fn sign_hsm(&self, message: &[u8]) -> Result<Vec<u8>> {
+        self.hsm
+            .as_ref()
+            .ok_or_else(|| anyhow!("pkcs11 key not opened"))?
+            .sign(message)
+    }
+
+    #[cfg(not(feature = "pkcs11"))]
+    fn sign_hsm(&self, _message: &[u8]) -> Result<Vec<u8>> {
+        Err(anyhow!("kc-crypto-kms built without the `pkcs11` feature"))
+    }
+
+    fn checked(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+        signature: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        // A misconfigured key ID would otherwise yield valid-looking signatures
+        // for the wrong wallet.
+        if !self
+            .public_key
+            .verify_in_domain(domain, payload, purpose, &signature)?
+        {
+            return Err(anyhow!(
+                "{} signature does not verify against the configured public key",
+                self.provider_name()
+            ));
+        }
+        Ok(signature)
+    }
+}
+
+/// Blocks the calling thread; async code should call
+/// [`KmsSigner::sign_in_domain_async`] instead.
+impl Signer for KmsSigner {
+    fn sign_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>> {
+        // HSM calls are already blocking; no runtime juggling needed.
+        if matches!(self.provider, KmsProvider::Pkcs11 { .. }) {
+            if payload.is_empty() {
+                return Err(anyhow!("payload cannot be empty"));
+            }
+            let signature = self.sign_hsm(&domain.signing_input(payload, &purpose))?;
+            return self.checked(domain, payload, purpose, signature);
+        }
+        // The caller may be inside a runtime that must not be blocked on, so
+        // sign on a helper thread with its own runtime and HTTP client.
+        run_on_helper_thread(async {
+            let http = reqwest::Client::new();
+            self.sign_with(&http, domain, payload, purpose).await
+        })
+    }
+}
+
+fn run_on_helper_thread<F>(future: F) -> Result<Vec<u8>>
+where
+    F: Future<Output = Result<Vec<u8>>> + Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .context("failed to build kms signing runtime")?
+                    .block_on(future)
+            })
+            .join()
+            .map_err(|_| anyhow!("kms signing thread panicked"))?
+    })
+}
+
+/// Wallet address → KMS signer.
+#[derive(Default)]
+pub struct KmsKeyRegistry {
+    signers: HashMap<String, Arc<KmsSigner>>,
+}
+
+impl KmsKeyRegistry {
+    pub fn from_configs(configs: Vec<KmsKeyConfig>) -> Result<Self> {
+        let mut signers = HashMap::with_capacity(configs.len());
+        for config in configs {
+            let signer = KmsSigner::new(config)?;
+            let wallet_address = signer.wallet_address();
+            if signers.insert(wallet_address.clone(), Arc::new(signer)).is_some() {
+                return Err(anyhow!("duplicate kms key for wallet {wallet_address}"));
+            }
+        }
+        Ok(Self { signers })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let configs: Vec<KmsKeyConfig> =
+            serde_json::from_str(json).context("invalid kms key registry json")?;
+        Self::from_configs(configs)
+    }
+
+    /// Load from `KEYCORTEX_KMS_KEYS_FILE`; empty when unset.
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("KEYCORTEX_KMS_KEYS_FILE") {
+            Ok(path) if !path.trim().is_empty() => {
+                let json = std::fs::read_to_string(path.trim())
+                    .with_context(|| format!("failed to read kms key registry {path}"))?;
+                Self::from_json(&json)
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn get(&self, wallet_address: &str) -> Option<Arc<KmsSigner>> {
+        self.signers.get(wallet_address).cloned()
+    }
+
+    pub fn wallet_addresses(&self) -> Vec<String> {
+        let mut addresses: Vec<String> = self.signers.keys().cloned().collect();
+        addresses.sort();
+        addresses
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, routing::{get, post}};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+    use ed25519_dalek::{Signer as _, SigningKey};
+
+    async fn mock_gcp_kms(signing_key: SigningKey) -> String {
+        let app = Router::new().route(
+            "/v1/{*name}",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let signing_key = signing_key.clone();
+                async move {
+                    let data = STANDARD
+                        .decode(body["data"].as_str().unwrap_or_default())
+                        .unwrap_or_default();
+                    let signature = signing_key.sign(&data);
+                    Json(serde_json::json!({
+                        "signature": STANDARD.encode(signature.to_bytes()),
+                    }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://{addr}")
+    }
+
+    fn gcp_signer(public_key: &[u8; 32], endpoint: String, metadata_host: String) -> KmsSigner {
+        let mut signer = KmsSigner::new(KmsKeyConfig {
+            public_key: kc_crypto::encoding::to_hex(public_key),
+            provider: KmsProvider::Gcp {
+                key_version_name: "projects/p/locations/l/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1"
+                    .to_owned(),
+                endpoint: Some(endpoint),
+            },
+        })
+        .expect("signer should build");
+        signer.gcp_tokens = Some(gcp::TokenSource::metadata(metadata_host));
+        signer
+    }
+
+    /// Metadata server handing out tokens that live `expires_in` seconds;
+    /// returns its host and a count of tokens issued.
+    async fn mock_metadata(expires_in: u64) -> (String, Arc<AtomicUsize>) {
+        let issued = Arc::new(AtomicUsize::new(0));
+        let counter = issued.clone();
+        let app = Router::new().route(
+            "/computeMetadata/v1/instance/service-accounts/default/token",
+            get(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    Json(serde_json::json!({
+                        "access_token": format!("token-{n}"),
+                        "expires_in": expires_in,
+                        "token_type": "Bearer",
+                    }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        (addr.to_string(), issued)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn gcp_signer_produces_verifiable_domain_signatures() {
+        let kms_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = kms_key.verifying_key().to_bytes();
+        let endpoint = mock_gcp_kms(kms_key).await;
+        let (metadata_host, _) = mock_metadata(3600).await;
+        let signer = gcp_signer(&public_key, endpoint.clone(), metadata_host.clone());
+
+        let domain = SigningDomain::default();
+        let signature = signer
+            .sign_in_domain_async(&domain, b"transfer", SignPurpose::Transaction)
+            .await
+            .expect("kms sign should succeed");
+        assert!(
+            signer
+                .public_key()
+                .verify_in_domain(&domain, b"transfer", SignPurpose::Transaction, &signature)
+                .expect("verify should run")
+        );
+
+        // The blocking `Signer` impl works from a thread inside the runtime.
+        let signer = Arc::new(signer);
+        let blocking_signer = signer.clone();
+        let blocking = tokio::task::spawn_blocking(move || {
+            blocking_signer.sign(b"transfer", SignPurpose::Transaction)
+        })
+        .await
+        .expect("blocking task should finish")
+        .expect("blocking sign should succeed");
+        assert_eq!(blocking, signature);
+
+        // A registry entry whose public key does not match the KMS key is caught.
+        let wrong = gcp_signer(
+            &SigningKey::from_bytes(&[8u8; 32]).verifying_key().to_bytes(),
+            endpoint,
+            metadata_host,
+        );
+        assert!(
+            wrong
+                .sign_in_domain_async(&domain, b"transfer", SignPurpose::Transaction)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn gcp_tokens_are_cached_until_close_to_expiry() {
+        let http = reqwest::Client::new();
+
+        let (host, issued) = mock_metadata(3600).await;
+        let tokens = gcp::TokenSource::metadata(host);
+        let first = tokens.access_token(&http).await.expect("token should be fetched");
+        let second = tokens.access_token(&http).await.expect("token should be cached");
+        assert_eq!(first, second);
+        assert_eq!(issued.load(Ordering::SeqCst), 1);
+
+        // A token inside the refresh margin is replaced on every use.
+        let (host, issued) = mock_metadata(30).await;
+        let tokens = gcp::TokenSource::metadata(host);
+        let first = tokens.access_token(&http).await.expect("token should be fetched");
+        let second = tokens.access_token(&http).await.expect("token should be refreshed");
+        assert_ne!(first, second);
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(not(feature = "pkcs11"))]
+    #[test]
+    fn pkcs11_keys_need_the_feature() {
+        let public_key = SigningKey::from_bytes(&[5u8; 32]).verifying_key().to_bytes();
+        let json = format!(
+            r#"[{{"provider":"pkcs11","public_key":"{}","label":"treasury"}}]"#,
+            kc_crypto::encoding::to_hex(&public_key)
+        );
+        let err = KmsKeyRegistry::from_json(&json).err().expect("should be rejected");
+        assert!(err.to_string().contains("pkcs11"));
+    }
+
+    #[test]
+    fn registry_maps_wallet_addresses_from_public_keys() {
+        let public_key = SigningKey::from_bytes(&[9u8; 32]).verifying_key().to_bytes();
+        let json = format!(
+            r#"[{{"provider":"gcp","public_key":"{}","key_version_name":"projects/p/k/1"}}]"#,
+            kc_crypto::encoding::to_hex(&public_key)
+        );
+        let registry = KmsKeyRegistry::from_json(&json).expect("registry should parse");
+        let expected = Ed25519PublicKey::from_bytes(&public_key)
+            .expect("valid key")
+            .wallet_address();
+        assert_eq!(registry.wallet_addresses(), vec![expected.clone()]);
+        assert_eq!(
+            registry.get(&expected).map(|s| s.provider_name()),
+            Some("gcp-kms")
+        );
+
+        let duplicated = format!("[{0},{0}]", &json[1..json.len() - 1]);
+        assert!(KmsKeyRegistry::from_json(&duplicated).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! PKCS#11 HSM signing (feature `pkcs11`).
+//!
+//! One logged-in session is shared by every HSM key in the process. It is
+//! opened on first use from:
+//!
+//! - `KEYCORTEX_PKCS11_MODULE`: path to the vendor PKCS#11 library
+//! - `KEYCORTEX_PKCS11_SLOT`: slot id; defaults to the first slot with a token
+//! - `KEYCORTEX_PKCS11_PIN` or `KEYCORTEX_PKCS11_PIN_FILE`: user PIN
+
+use anyhow::{Context, Result, anyhow};
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::mechanism::eddsa::{EddsaParams, EddsaSignatureScheme};
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+use kc_crypto::Ed25519PublicKey;
+use std::sync::{Arc, Mutex, OnceLock};
+
+struct HsmSession {
+    session: Mutex<Session>,
+}
+
+static SHARED_SESSION: OnceLock<Mutex<Option<Arc<HsmSession>>>> = OnceLock::new();
+
+fn shared_session() -> Result<Arc<HsmSession>> {
+    let mut guard = SHARED_SESSION
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .map_err(|_| anyhow!("pkcs11 session lock poisoned"))?;
+    if let Some(session) = guard.as_ref() {
+        return Ok(Arc::clone(session));
+    }
+    let session = Arc::new(open_session()?);
+    *guard = Some(Arc::clone(&session));
+    Ok(session)
+}
+
+fn open_session() -> Result<HsmSession> {
+    let module = std::env::var("KEYCORTEX_PKCS11_MODULE")
+        .map_err(|_| anyhow!("KEYCORTEX_PKCS11_MODULE is not set"))?;
+    let pkcs11 = Pkcs11::new(module.trim())
+        .with_context(|| format!("failed to load pkcs11 module {module}"))?;
+    pkcs11
+        .initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK))
+        .context("pkcs11 C_Initialize failed")?;
+
+    let slot = match std::env::var("KEYCORTEX_PKCS11_SLOT") {
+        Ok(id) if !id.trim().is_empty() => {
+            let id: u64 = id
+                .trim()
+                .parse()
+                .context("KEYCORTEX_PKCS11_SLOT must be a numeric slot id")?;
+            Slot::try_from(id).context("invalid pkcs11 slot id")?
+        }
+        _ => *pkcs11
+            .get_slots_with_token()
+            .context("pkcs11 C_GetSlotList failed")?
+            .first()
+            .ok_or_else(|| anyhow!("no pkcs11 slot with a token present"))?,
+    };
+
+    let pin = match std::env::var("KEYCORTEX_PKCS11_PIN_FILE") {
+        Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+            .with_context(|| format!("failed to read pkcs11 pin file {path}"))?,
+        _ => std::env::var("KEYCORTEX_PKCS11_PIN")
+            .map_err(|_| anyhow!("KEYCORTEX_PKCS11_PIN or KEYCORTEX_PKCS11_PIN_FILE is required"))?,
+    };
+    let pin = AuthPin::from(pin.trim().to_owned());
+
+    let session = pkcs11
+        .open_ro_session(slot)
+        .with_context(|| format!("failed to open pkcs11 session on slot {}", slot.id()))?;
+    session
+        .login(UserType::User, Some(&pin))
+        .context("pkcs11 C_Login failed")?;
+
+    Ok(HsmSession {
+        session: Mutex::new(session),
+    })
+}
+
+/// An Ed25519 key pair on the HSM, addressed by its `CKA_LABEL`.
+pub(crate) struct HsmKey {
+    session: Arc<HsmSession>,
+    private_key: ObjectHandle,
+}
+
+impl HsmKey {
+    /// Find the key pair and check the token's public key matches `expected`.
+    pub fn open(label: &str, expected: &Ed25519PublicKey) -> Result<Self> {
+        let session = shared_session()?;
+        let (private_key, public_key) = {
+            let guard = session
+                .session
+                .lock()
+                .map_err(|_| anyhow!("pkcs11 session lock poisoned"))?;
+            let private_key = find_one(&guard, ObjectClass::PRIVATE_KEY, label)?;
+            let public_handle = find_one(&guard, ObjectClass::PUBLIC_KEY, label)?;
+            let public_key = read_public_key(&guard, public_handle)?;
+            (private_key, public_key)
+        };
+
+        if public_key != *expected {
+            return Err(anyhow!(
+                "pkcs11 key '{label}' does not match the configured public key"
+            ));
+        }
+        Ok(Self {
+            session,
+            private_key,
+        })
+    }
+
+    /// PureEdDSA over `message`; the token applies SHA-512 itself.
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let guard = self
+            .session
+            .session
+            .lock()
+            .map_err(|_| anyhow!("pkcs11 session lock poisoned"))?;
+        guard
+            .sign(
+                &Mechanism::Eddsa(EddsaParams::new(EddsaSignatureScheme::Pure)),
+                self.private_key,
+                message,
+            )
+            .context("pkcs11 C_Sign failed")
+    }
+}
+
+fn find_one(session: &Session, class: ObjectClass, label: &str) -> Result<ObjectHandle> {
+    let handles = session
+        .find_objects(&[
+            Attribute::Class(class),
+            Attribute::KeyType(KeyType::EC_EDWARDS),
+            Attribute::Label(label.as_bytes().to_vec()),
+        ])
+        .context("pkcs11 C_FindObjects failed")?;
+    match handles.as_slice() {
+        [handle] => Ok(*handle),
+        [] => Err(anyhow!("no pkcs11 {class} labelled '{label}'")),
+        _ => Err(anyhow!("several pkcs11 {class} objects labelled '{label}'")),
+    }
+}
+
+fn read_public_key(session: &Session, handle: ObjectHandle) -> Result<Ed25519PublicKey> {
+    let attributes = session
+        .get_attributes(handle, &[AttributeType::EcPoint])
+        .context("pkcs11 C_GetAttributeValue failed")?;
+    let Some(Attribute::EcPoint(point)) = attributes.into_iter().next() else {
+        return Err(anyhow!("pkcs11 public key has no CKA_EC_POINT"));
+    };
+    let point = decode_ec_point(&point)?;
+    Ed25519PublicKey::from_bytes(&point)
+}
+
+/// `CKA_EC_POINT` is a DER OCTET STRING per the spec, though some tokens
+/// return the raw 32 bytes.
+fn decode_ec_point(point: &[u8]) -> Result<[u8; 32]> {
+    let raw = match point {
+        [0x04, 0x20, rest @ ..] if rest.len() == 32 => rest,
+        _ if point.len() == 32 => point,
+        _ => return Err(anyhow!("unexpected CKA_EC_POINT encoding ({} bytes)", point.len())),
+    };
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(raw);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ec_point_accepts_der_and_raw_encodings() {
+        let raw = [9u8; 32];
+        let mut der = vec![0x04, 0x20];
+        der.extend_from_slice(&raw);
+        assert_eq!(decode_ec_point(&der).expect("der decodes"), raw);
+        assert_eq!(decode_ec_point(&raw).expect("raw decodes"), raw);
+        assert!(decode_ec_point(&der[..33]).is_err());
+    }
+}
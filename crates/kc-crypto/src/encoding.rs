@@ -0,0 +1,335 @@
+//! Byte encodings shared across KeyCortex crates.
+//!
+//! Hex lives in [`kc_api_types::hex`] so wire types can use it too; it is
+//! branch-free and safe to use on key material. Base58(check) and bech32 are
+//! intended for public data such as addresses and are not constant-time.
+
+use anyhow::{Result, anyhow};
+use sha2::{Digest, Sha256};
+
+pub use kc_api_types::hex::to_hex;
+
+/// Decode hex (either case). Timing depends only on the input length.
+pub fn from_hex(input: &str) -> Result<Vec<u8>> {
+    Ok(kc_api_types::hex::from_hex(input)?)
+}
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Bitcoin-alphabet base58.
+pub fn to_base58(input: &[u8]) -> String {
+    let zeros = input.iter().take_while(|&&b| b == 0).count();
+    // log(256) / log(58) ≈ 1.37
+    let mut digits: Vec<u8> = Vec::with_capacity(input.len() * 138 / 100 + 1);
+    for &byte in &input[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut output = String::with_capacity(zeros + digits.len());
+    output.extend(std::iter::repeat_n('1', zeros));
+    output.extend(digits.iter().rev().map(|&d| BASE58_ALPHABET[d as usize] as char));
+    output
+}
+
+pub fn from_base58(input: &str) -> Result<Vec<u8>> {
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+    for (offset, c) in input.bytes().enumerate().skip(zeros) {
+        let value = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| anyhow!("invalid base58 character at offset {offset}"))?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut output = vec![0u8; zeros];
+    output.extend(bytes.iter().rev());
+    Ok(output)
+}
+
+fn base58_checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Base58 with a 4-byte double-SHA-256 checksum suffix.
+pub fn to_base58check(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&base58_checksum(payload));
+    to_base58(&data)
+}
+
+pub fn from_base58check(input: &str) -> Result<Vec<u8>> {
+    let mut data = from_base58(input)?;
+    if data.len() < 4 {
+        return Err(anyhow!("base58check input too short"));
+    }
+    let checksum = data.split_off(data.len() - 4);
+    if checksum != base58_checksum(&data) {
+        return Err(anyhow!("base58check checksum mismatch"));
+    }
+    Ok(data)
+}
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+const BECH32_MAX_LENGTH: usize = 90;
+const BECH32_CONSTANT: u32 = 1;
+const BECH32M_CONSTANT: u32 = 0x2bc8_30a3;
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x01ff_ffff) << 5) ^ u32::from(value);
+        for (bit, generator) in BECH32_GENERATOR.iter().enumerate() {
+            if (top >> bit) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn bech32_hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|c| c >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|c| c & 0x1f))
+}
+
+/// Regroup bits, e.g. 8-bit bytes to 5-bit bech32 words and back.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let max = (1u32 << to) - 1;
+    let mut output = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        acc = (acc << from) | u32::from(value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            output.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            output.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(anyhow!("invalid bech32 padding"));
+    }
+    Ok(output)
+}
+
+/// BIP-173 bech32 over 8-bit `data`, with a lowercase `hrp`.
+pub fn to_bech32(hrp: &str, data: &[u8]) -> Result<String> {
+    encode_bech32_words(hrp, &convert_bits(data, 8, 5, true)?, BECH32_CONSTANT)
+}
+
+/// Decode BIP-173 bech32 into `(hrp, data)`; mixed case is rejected.
+pub fn from_bech32(input: &str) -> Result<(String, Vec<u8>)> {
+    let (hrp, words, constant) = decode_bech32_words(input)?;
+    if constant != BECH32_CONSTANT {
+        return Err(anyhow!("bech32 checksum mismatch"));
+    }
+    Ok((hrp, convert_bits(&words, 5, 8, false)?))
+}
+
+/// Segregated witness address for `program`: bech32 for witness version 0
+/// (BIP-173) and bech32m for later versions (BIP-350).
+pub fn to_segwit_address(hrp: &str, version: u8, program: &[u8]) -> Result<String> {
+    check_witness_program(version, program)?;
+    let mut words = vec![version];
+    words.extend(convert_bits(program, 8, 5, true)?);
+    encode_bech32_words(hrp, &words, segwit_constant(version))
+}
+
+/// Decode a segregated witness address under `hrp` into its witness
+/// version and program.
+pub fn from_segwit_address(hrp: &str, address: &str) -> Result<(u8, Vec<u8>)> {
+    let (decoded_hrp, words, constant) = decode_bech32_words(address)?;
+    if decoded_hrp != hrp {
+        return Err(anyhow!("address is for '{decoded_hrp}', expected '{hrp}'"));
+    }
+    let (&version, program) = words
+        .split_first()
+        .ok_or_else(|| anyhow!("segwit address has no witness version"))?;
+    if constant != segwit_constant(version) {
+        return Err(anyhow!("segwit address checksum mismatch"));
+    }
+    let program = convert_bits(program, 5, 8, false)?;
+    check_witness_program(version, &program)?;
+    Ok((version, program))
+}
+
+fn segwit_constant(version: u8) -> u32 {
+    if version == 0 {
+        BECH32_CONSTANT
+    } else {
+        BECH32M_CONSTANT
+    }
+}
+
+fn check_witness_program(version: u8, program: &[u8]) -> Result<()> {
+    if version > 16 {
+        return Err(anyhow!("invalid witness version {version}"));
+    }
+    if !(2..=40).contains(&program.len()) || (version == 0 && ![20, 32].contains(&program.len())) {
+        return Err(anyhow!(
+            "invalid {}-byte witness program for version {version}",
+            program.len()
+        ));
+    }
+    Ok(())
+}
+
+fn encode_bech32_words(hrp: &str, words: &[u8], constant: u32) -> Result<String> {
+    if hrp.is_empty() || !hrp.bytes().all(|c| (33..=126).contains(&c) && !c.is_ascii_uppercase()) {
+        return Err(anyhow!("invalid bech32 human-readable part"));
+    }
+    if hrp.len() + 1 + words.len() + 6 > BECH32_MAX_LENGTH {
+        return Err(anyhow!("bech32 output exceeds {BECH32_MAX_LENGTH} characters"));
+    }
+
+    let polymod = bech32_polymod(
+        bech32_hrp_expand(hrp)
+            .chain(words.iter().copied())
+            .chain([0u8; 6]),
+    ) ^ constant;
+
+    let mut output = String::with_capacity(hrp.len() + 1 + words.len() + 6);
+    output.push_str(hrp);
+    output.push('1');
+    output.extend(words.iter().map(|&w| BECH32_CHARSET[w as usize] as char));
+    output.extend((0..6).map(|i| BECH32_CHARSET[((polymod >> (5 * (5 - i))) & 0x1f) as usize] as char));
+    Ok(output)
+}
+
+/// `(hrp, 5-bit words without the checksum, checksum constant)`. The
+/// constant tells bech32 from bech32m; anything else is a bad checksum.
+fn decode_bech32_words(input: &str) -> Result<(String, Vec<u8>, u32)> {
+    if input.len() > BECH32_MAX_LENGTH {
+        return Err(anyhow!("bech32 input exceeds {BECH32_MAX_LENGTH} characters"));
+    }
+    let has_lower = input.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = input.bytes().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(anyhow!("bech32 input has mixed case"));
+    }
+    let input = input.to_ascii_lowercase();
+
+    let separator = input
+        .rfind('1')
+        .ok_or_else(|| anyhow!("bech32 separator missing"))?;
+    let (hrp, rest) = (&input[..separator], &input[separator + 1..]);
+    if hrp.is_empty() || !hrp.bytes().all(|c| (33..=126).contains(&c)) {
+        return Err(anyhow!("invalid bech32 human-readable part"));
+    }
+    if rest.len() < 6 {
+        return Err(anyhow!("bech32 checksum too short"));
+    }
+
+    let mut words = rest
+        .bytes()
+        .map(|c| {
+            BECH32_CHARSET
+                .iter()
+                .position(|&a| a == c)
+                .map(|p| p as u8)
+                .ok_or_else(|| anyhow!("invalid bech32 character '{}'", c as char))
+        })
+        .collect::<Result<Vec<u8>>>()?;
+    let constant = bech32_polymod(bech32_hrp_expand(hrp).chain(words.iter().copied()));
+    if constant != BECH32_CONSTANT && constant != BECH32M_CONSTANT {
+        return Err(anyhow!("bech32 checksum mismatch"));
+    }
+
+    words.truncate(words.len() - 6);
+    Ok((hrp.to_owned(), words, constant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_roundtrips_and_rejects_invalid_input() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let encoded = to_hex(&bytes);
+        assert_eq!(&encoded[..8], "00010203");
+        assert_eq!(&encoded[encoded.len() - 4..], "feff");
+        assert_eq!(from_hex(&encoded).expect("lowercase decodes"), bytes);
+        assert_eq!(from_hex("DEADbeef").expect("mixed case decodes"), [0xde, 0xad, 0xbe, 0xef]);
+
+        assert!(from_hex("abc").is_err());
+        for bad in ["0g", "g0", "/0", ":0", "@0", "G0", "`0", "0 ", "é0"] {
+            assert!(from_hex(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn base58_matches_known_vectors() {
+        assert_eq!(to_base58(b"Hello World"), "JxF12TrwUP45BMd");
+        assert_eq!(to_base58(&[0, 0, 1]), "112");
+        assert_eq!(from_base58("JxF12TrwUP45BMd").expect("decodes"), b"Hello World");
+        assert!(from_base58("0OIl").is_err());
+
+        let address = to_base58check(&[0u8; 21]);
+        assert_eq!(address, "1111111111111111111114oLvT2");
+        assert_eq!(from_base58check(&address).expect("checksum valid"), [0u8; 21]);
+        assert!(from_base58check("1111111111111111111114oLvT3").is_err());
+    }
+
+    #[test]
+    fn bech32_matches_bip173_and_roundtrips() {
+        assert_eq!(from_bech32("A12UEL5L").expect("valid").0, "a");
+        assert_eq!(to_bech32("a", &[]).expect("encodes"), "a12uel5l");
+
+        let encoded = to_bech32("kc", b"keycortex").expect("encodes");
+        let (hrp, data) = from_bech32(&encoded).expect("decodes");
+        assert_eq!(hrp, "kc");
+        assert_eq!(data, b"keycortex");
+
+        let mut corrupted = encoded.into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+        assert!(from_bech32(std::str::from_utf8(&corrupted).unwrap()).is_err());
+        assert!(from_bech32("A12uEL5L").is_err());
+    }
+
+    #[test]
+    fn segwit_addresses_match_bip173_and_bip350() {
+        let program = from_hex("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert_eq!(to_segwit_address("bc", 0, &program).unwrap(), address);
+        assert_eq!(from_segwit_address("bc", &address.to_uppercase()).unwrap(), (0, program));
+        assert!(from_segwit_address("tb", address).is_err());
+
+        let taproot = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+        let (version, program) = from_segwit_address("bc", taproot).unwrap();
+        assert_eq!((version, program.len()), (1, 32));
+        assert_eq!(to_segwit_address("bc", 1, &program).unwrap(), taproot);
+        // Version 1 with a bech32 (not bech32m) checksum.
+        assert!(from_segwit_address("bc", "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7k7grplx").is_err());
+        assert!(to_segwit_address("bc", 0, &[0u8; 21]).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Key fingerprints two people can compare by reading them aloud.
+//!
+//! The fingerprint is SHA-256 over `keycortex:fingerprint:v1:` and the raw
+//! public key. Its first 64 bits are shown as eight words from [`WORDS`],
+//! one per byte, and as four groups of five decimal digits. Both forms carry
+//! the same bits, so either can be checked against the other. 64 bits is
+//! enough to catch a mistaken or swapped key in conversation, but is not a
+//! collision-resistant identifier; compare full public keys for that.
+
+use anyhow::Result;
+use kc_api_types::KeyFingerprint;
+use sha2::{Digest, Sha256};
+
+use crate::encoding::from_hex;
+
+const DOMAIN: &[u8] = b"keycortex:fingerprint:v1:";
+
+/// One word per byte value. Short, common and distinct when spoken; never
+/// reorder, since that changes every fingerprint.
+pub const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "agent", "alarm", "album", "alpha", "amber",
+    "anchor", "angle", "apple", "april", "arrow", "atlas", "atom", "aunt",
+    "badge", "bagel", "baker", "bamboo", "banjo", "barn", "basil", "beach",
+    "beard", "berry", "bison", "blade", "blaze", "bloom", "bonus", "boxer",
+    "brain", "brass", "bread", "brick", "bridge", "brook", "brush", "bucket",
+    "bugle", "cabin", "cable", "cactus", "camel", "candy", "canoe", "canyon",
+    "cargo", "carpet", "castle", "cedar", "chalk", "cherry", "chess", "chief",
+    "cider", "cinema", "circus", "citrus", "clock", "cloud", "clover", "cobra",
+    "cocoa", "comet", "copper", "coral", "cotton", "cougar", "crane", "crayon",
+    "cricket", "crown", "cube", "daisy", "dancer", "delta", "denim", "desert",
+    "diesel", "dingo", "doctor", "dolphin", "donkey", "dragon", "drum", "eagle",
+    "easel", "echo", "elbow", "ember", "emerald", "engine", "falcon", "fender",
+    "ferry", "fiddle", "fig", "flame", "flute", "forest", "fossil", "fox",
+    "frost", "fudge", "galaxy", "garden", "garlic", "gecko", "geyser", "ginger",
+    "glacier", "globe", "goat", "gold", "grape", "gravel", "guitar", "hammer",
+    "harbor", "harp", "hazel", "helmet", "heron", "hippo", "honey", "hornet",
+    "hotel", "husky", "igloo", "indigo", "iris", "island", "ivory", "jacket",
+    "jaguar", "jasmine", "jelly", "jewel", "jungle", "kayak", "kettle", "kiwi",
+    "koala", "ladder", "lagoon", "lemon", "leopard", "lilac", "lime", "linen",
+    "lion", "lizard", "llama", "lobster", "locket", "lotus", "magnet", "mango",
+    "maple", "marble", "meadow", "melon", "meteor", "mint", "mirror", "moose",
+    "mosaic", "motor", "muffin", "nectar", "needle", "nickel", "noodle", "nutmeg",
+    "oasis", "ocean", "olive", "onion", "opal", "orange", "orbit", "orchid",
+    "otter", "owl", "oyster", "paddle", "panda", "papaya", "parrot", "peach",
+    "pebble", "pepper", "piano", "pilot", "pine", "pirate", "pixel", "planet",
+    "plum", "pocket", "polar", "pony", "poppy", "prism", "puffin", "pumpkin",
+    "quail", "quartz", "quill", "rabbit", "radar", "radish", "raven", "reef",
+    "rhino", "ribbon", "rocket", "rose", "ruby", "saddle", "salmon", "sandal",
+    "satin", "scarf", "shark", "silver", "sketch", "socket", "spider", "spruce",
+    "squid", "stone", "sugar", "summit", "tango", "teapot", "temple", "tiger",
+    "toast", "tomato", "topaz", "torch", "tractor", "tulip", "tundra", "turtle",
+    "velvet", "violin", "walnut", "walrus", "wizard", "yogurt", "zebra", "zipper",
+];
+
+/// Fingerprint of a raw public key.
+pub fn of(public_key: &[u8]) -> KeyFingerprint {
+    let digest = Sha256::new()
+        .chain_update(DOMAIN)
+        .chain_update(public_key)
+        .finalize();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&digest[..8]);
+
+    let words = head
+        .iter()
+        .map(|&byte| WORDS[usize::from(byte)])
+        .collect::<Vec<_>>()
+        .join(" ");
+    let digits = format!("{:020}", u64::from_be_bytes(head));
+    let code = digits
+        .as_bytes()
+        .chunks(5)
+        .map(|group| std::str::from_utf8(group).expect("digits are ascii"))
+        .collect::<Vec<_>>()
+        .join("-");
+    KeyFingerprint { words, code }
+}
+
+/// Fingerprint of a hex-encoded public key, as wallet responses carry it.
+pub fn of_hex(public_key_hex: &str) -> Result<KeyFingerprint> {
+    Ok(of(&from_hex(public_key_hex)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn words_are_distinct() {
+        assert_eq!(WORDS.iter().collect::<HashSet<_>>().len(), WORDS.len());
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_key_specific() {
+        let fingerprint =
+            of_hex("94f8dfdfba44f45de67ddd5a1fe9f6d1fd085966632b6544a9acf7bb18731bd8").expect("valid key hex should fingerprint");
+        assert_eq!(fingerprint.words, "rose gravel beach mango castle tango bamboo goat");
+        assert_eq!(fingerprint.code, "15813-57163-91029-35922");
+        assert_eq!(fingerprint.words.split(' ').count(), 8);
+
+        let other =
+            of_hex("94f8dfdfba44f45de67ddd5a1fe9f6d1fd085966632b6544a9acf7bb18731bd9").expect("valid key hex should fingerprint");
+        assert_ne!(other, fingerprint);
+        assert!(of_hex("not hex").is_err());
+    }
+}
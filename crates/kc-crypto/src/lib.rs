@@ -1,25 +1,403 @@
 use anyhow::{Result, anyhow};
-use ed25519_dalek::{Signature, Signer as DalekSigner, SigningKey, Verifier};
+#[cfg(feature = "bls")]
+use blst::{
+    BLST_ERROR,
+    min_pk::{
+        AggregateSignature as BlsAggregateSignature, PublicKey as BlsPublicKey,
+        SecretKey as BlsSecretKey, Signature as BlsSignature,
+    },
+};
+use ed25519_dalek::{Signature, Signer as DalekSigner, SigningKey, Verifier, VerifyingKey};
 #[cfg(feature = "secp256k1")]
 use k256::ecdsa::{
     Signature as Secp256k1Signature, SigningKey as Secp256k1SigningKey,
     VerifyingKey as Secp256k1VerifyingKey,
-    signature::{Signer as K256Signer, Verifier as K256Verifier},
+    signature::hazmat::PrehashSigner,
 };
-use kc_api_types::SignPurpose;
+use kc_api_types::{ChainDomainTags, SignPurpose, TransactionEnvelope};
+#[cfg(feature = "secp256k1")]
+use ripemd::Ripemd160;
 use rand::rngs::OsRng;
+#[cfg(feature = "bls")]
+use rand::RngCore;
+use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
-use zeroize::Zeroize;
+use std::borrow::Cow;
+use std::fmt;
+#[cfg(unix)]
+use std::collections::BTreeMap;
+#[cfg(unix)]
+use std::sync::{Mutex, OnceLock};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::encoding::to_hex;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub mod aead;
+pub mod encoding;
+pub mod fingerprint;
+pub mod memo;
+pub mod passphrase;
+pub mod sigv4;
+pub mod vectors;
 
 pub trait Signer: Send + Sync {
-    fn sign(&self, payload: &[u8], purpose: SignPurpose) -> Result<Vec<u8>>;
+    /// Sign under the default `keycortex:v1` domain.
+    fn sign(&self, payload: &[u8], purpose: SignPurpose) -> Result<Vec<u8>> {
+        self.sign_in_domain(&SigningDomain::default(), payload, purpose)
+    }
+
+    fn sign_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>>;
+}
+
+/// Domain separation applied to every payload before signing.
+///
+/// The signed bytes are `{namespace}:{version}:{purpose_tag}:{payload}`.
+/// Deployments on other chains can supply their own namespace/version/tags
+/// (typically from chain config) instead of the KeyCortex defaults.
+/// [`SignPurpose::Custom`] names use the tag `custom.{name}` and must be
+/// listed in `custom_purposes` to pass [`SigningDomain::check_purpose`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningDomain {
+    pub namespace: String,
+    pub version: String,
+    pub transaction_tag: String,
+    pub auth_tag: String,
+    pub proof_tag: String,
+    pub custom_purposes: Vec<String>,
+    /// Appended to every purpose tag; set by [`SigningDomain::with_envelope`].
+    tag_suffix: String,
 }
 
+const CUSTOM_TAG_PREFIX: &str = "custom.";
+
+impl Default for SigningDomain {
+    fn default() -> Self {
+        Self::new("keycortex", "v1")
+    }
+}
+
+impl SigningDomain {
+    /// Domain with the given namespace/version and the standard purpose tags.
+    pub fn new(namespace: &str, version: &str) -> Self {
+        Self {
+            namespace: namespace.to_owned(),
+            version: version.to_owned(),
+            transaction_tag: "transaction".to_owned(),
+            auth_tag: "auth".to_owned(),
+            proof_tag: "proof".to_owned(),
+            custom_purposes: Vec::new(),
+            tag_suffix: String::new(),
+        }
+    }
+
+    /// This domain with `names` registered as custom purposes.
+    pub fn with_custom_purposes<I, S>(mut self, names: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for name in names {
+            let name = name.into();
+            validate_custom_purpose(&name)?;
+            if !self.custom_purposes.contains(&name) {
+                self.custom_purposes.push(name);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Reject custom purposes this domain does not register.
+    pub fn check_purpose(&self, purpose: &SignPurpose) -> Result<()> {
+        match purpose {
+            SignPurpose::Custom(name) if !self.custom_purposes.contains(name) => {
+                Err(anyhow!("sign purpose '{name}' is not registered"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parse a domain from chain config tags (`namespace:version:purpose`).
+    /// All three tags must share the same namespace and version.
+    pub fn from_domain_tags(tags: &ChainDomainTags) -> Result<Self> {
+        let (namespace, version, transaction_tag) = split_domain_tag(&tags.tx_domain_tag)?;
+        let (auth_ns, auth_version, auth_tag) = split_domain_tag(&tags.auth_domain_tag)?;
+        let (proof_ns, proof_version, proof_tag) = split_domain_tag(&tags.proof_domain_tag)?;
+
+        if auth_ns != namespace || proof_ns != namespace {
+            return Err(anyhow!("domain tags must share the same namespace"));
+        }
+        if auth_version != version || proof_version != version {
+            return Err(anyhow!("domain tags must share the same version"));
+        }
+        for tag in [transaction_tag, auth_tag, proof_tag] {
+            if tag.starts_with(CUSTOM_TAG_PREFIX) {
+                return Err(anyhow!(
+                    "purpose tag '{tag}' uses the reserved '{CUSTOM_TAG_PREFIX}' prefix"
+                ));
+            }
+        }
+
+        Self {
+            namespace: namespace.to_owned(),
+            version: version.to_owned(),
+            transaction_tag: transaction_tag.to_owned(),
+            auth_tag: auth_tag.to_owned(),
+            proof_tag: proof_tag.to_owned(),
+            custom_purposes: Vec::new(),
+            tag_suffix: String::new(),
+        }
+        .with_custom_purposes(tags.custom_purposes.iter().cloned())
+    }
+
+    pub fn purpose_tag(&self, purpose: &SignPurpose) -> Cow<'_, str> {
+        match purpose {
+            SignPurpose::Transaction => Cow::Borrowed(&self.transaction_tag),
+            SignPurpose::Auth => Cow::Borrowed(&self.auth_tag),
+            SignPurpose::Proof => Cow::Borrowed(&self.proof_tag),
+            SignPurpose::Custom(name) => Cow::Owned(format!("{CUSTOM_TAG_PREFIX}{name}")),
+        }
+    }
+
+    /// Full domain tag for a purpose, e.g. `keycortex:v1:transaction`.
+    pub fn domain_tag(&self, purpose: &SignPurpose) -> String {
+        format!(
+            "{}:{}:{}{}",
+            self.namespace,
+            self.version,
+            self.purpose_tag(purpose),
+            self.tag_suffix
+        )
+    }
+
+    pub fn domain_tags(&self) -> ChainDomainTags {
+        ChainDomainTags {
+            tx_domain_tag: self.domain_tag(&SignPurpose::Transaction),
+            auth_domain_tag: self.domain_tag(&SignPurpose::Auth),
+            proof_domain_tag: self.domain_tag(&SignPurpose::Proof),
+            custom_purposes: self.custom_purposes.clone(),
+        }
+    }
+
+    pub fn signing_input(&self, payload: &[u8], purpose: &SignPurpose) -> Vec<u8> {
+        let tag = self.domain_tag(purpose);
+        let mut signing_input = Vec::with_capacity(tag.len() + 1 + payload.len());
+        signing_input.extend_from_slice(tag.as_bytes());
+        signing_input.extend_from_slice(b":");
+        signing_input.extend_from_slice(payload);
+        signing_input
+    }
+
+    /// This domain with `envelope` appended to every purpose tag, e.g.
+    /// `keycortex:v1:transaction#v2;chain=flowcortex-l1;nonce=7;expires_at=1700000000000`.
+    ///
+    /// Any [`Signer`] signs v2 envelopes through `sign_in_domain` with the
+    /// returned domain. The `#` after the purpose tag keeps v2 inputs
+    /// disjoint from v1 ones, whose tag is always followed by `:`.
+    pub fn with_envelope(&self, envelope: &TransactionEnvelope) -> Result<Self> {
+        let chain_ok = !envelope.chain_id.is_empty()
+            && envelope
+                .chain_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !chain_ok {
+            return Err(anyhow!(
+                "invalid envelope chain id '{}'; expected [A-Za-z0-9._-]+",
+                envelope.chain_id
+            ));
+        }
+        Ok(Self {
+            tag_suffix: format!(
+                "{}#v2;chain={};nonce={};expires_at={}",
+                self.tag_suffix, envelope.chain_id, envelope.nonce, envelope.expires_at_epoch_ms
+            ),
+            ..self.clone()
+        })
+    }
+
+    /// Signed bytes for `payload` bound to `envelope`'s chain, nonce and expiry.
+    pub fn signing_input_v2(
+        &self,
+        payload: &[u8],
+        purpose: &SignPurpose,
+        envelope: &TransactionEnvelope,
+    ) -> Result<Vec<u8>> {
+        Ok(self.with_envelope(envelope)?.signing_input(payload, purpose))
+    }
+}
+
+fn validate_custom_purpose(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if !valid {
+        return Err(anyhow!(
+            "invalid custom purpose '{name}'; expected 1-32 of [a-z0-9_-]"
+        ));
+    }
+    if matches!(SignPurpose::from(name.to_owned()), SignPurpose::Custom(_)) {
+        Ok(())
+    } else {
+        Err(anyhow!("'{name}' is a built-in sign purpose"))
+    }
+}
+
+fn split_domain_tag(tag: &str) -> Result<(&str, &str, &str)> {
+    let mut parts = tag.rsplitn(3, ':');
+    let purpose = parts.next().unwrap_or_default();
+    let version = parts.next().unwrap_or_default();
+    let namespace = parts.next().unwrap_or_default();
+    if namespace.is_empty() || version.is_empty() || purpose.is_empty() {
+        return Err(anyhow!("invalid domain tag '{tag}'; expected namespace:version:purpose"));
+    }
+    Ok((namespace, version, purpose))
+}
+
+/// Decrypted 32-byte secret key, wiped when dropped.
+///
+/// The bytes are kept on the heap so their address is stable; on Unix the
+/// backing page is `mlock`ed on a best-effort basis to keep it out of swap.
+/// Several secrets can share a page, so pages are refcounted and only
+/// unlocked once the last secret on them is dropped.
+pub struct SecretKeyMaterial {
+    bytes: Box<[u8; 32]>,
+    locked: bool,
+}
+
+impl SecretKeyMaterial {
+    pub fn new(mut secret_key: [u8; 32]) -> Self {
+        let mut material = Self::zeroed();
+        material.bytes.copy_from_slice(&secret_key);
+        secret_key.zeroize();
+        material
+    }
+
+    fn zeroed() -> Self {
+        let bytes = Box::new([0_u8; 32]);
+        let locked = lock_memory(bytes.as_ptr(), bytes.len());
+        Self { bytes, locked }
+    }
+
+    pub fn expose_secret(&self) -> &[u8; 32] {
+        &self.bytes
+    }
+
+    /// Whether the backing memory was successfully locked into RAM.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl Drop for SecretKeyMaterial {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+        if self.locked {
+            unlock_memory(self.bytes.as_ptr(), self.bytes.len());
+        }
+    }
+}
+
+impl ZeroizeOnDrop for SecretKeyMaterial {}
+
+impl fmt::Debug for SecretKeyMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKeyMaterial([REDACTED])")
+    }
+}
+
+/// Pages currently `mlock`ed for [`SecretKeyMaterial`], keyed by page address,
+/// with the number of live secrets on each.
+#[cfg(unix)]
+static LOCKED_PAGES: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+#[cfg(unix)]
+fn page_size() -> usize {
+    static PAGE_SIZE: OnceLock<usize> = OnceLock::new();
+    *PAGE_SIZE.get_or_init(|| {
+        // SAFETY: `sysconf` has no preconditions.
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        usize::try_from(size).ok().filter(|size| *size > 0).unwrap_or(4096)
+    })
+}
+
+#[cfg(unix)]
+fn pages_spanned(ptr: *const u8, len: usize) -> Vec<usize> {
+    let page = page_size();
+    let start = ptr as usize / page * page;
+    let end = ptr as usize + len.max(1);
+    (start..end).step_by(page).collect()
+}
+
+#[cfg(unix)]
+fn lock_memory(ptr: *const u8, len: usize) -> bool {
+    let Ok(mut locked) = LOCKED_PAGES.lock() else {
+        return false;
+    };
+    let pages = pages_spanned(ptr, len);
+    for (index, page) in pages.iter().enumerate() {
+        if !locked.contains_key(page) {
+            // SAFETY: `page` is the start of a mapped page holding part of the
+            // live allocation at `ptr`.
+            if unsafe { libc::mlock(*page as *const libc::c_void, page_size()) } != 0 {
+                release_pages(&mut locked, &pages[..index]);
+                return false;
+            }
+        }
+        *locked.entry(*page).or_insert(0) += 1;
+    }
+    true
+}
+
+#[cfg(not(unix))]
+fn lock_memory(_ptr: *const u8, _len: usize) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn unlock_memory(ptr: *const u8, len: usize) {
+    let mut locked = match LOCKED_PAGES.lock() {
+        Ok(locked) => locked,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    release_pages(&mut locked, &pages_spanned(ptr, len));
+}
+
+#[cfg(unix)]
+fn release_pages(locked: &mut BTreeMap<usize, usize>, pages: &[usize]) {
+    for page in pages {
+        let Some(count) = locked.get_mut(page) else {
+            continue;
+        };
+        *count -= 1;
+        if *count == 0 {
+            locked.remove(page);
+            // SAFETY: `page` was locked by `lock_memory` and no live secret
+            // remains on it.
+            unsafe {
+                libc::munlock(*page as *const libc::c_void, page_size());
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn unlock_memory(_ptr: *const u8, _len: usize) {}
+
 pub struct Ed25519Signer {
     signing_key: SigningKey,
 }
 
 impl Ed25519Signer {
+    pub const SIGNATURE_SCHEME: &'static str = "ed25519";
+
     pub fn new_random() -> Self {
         let mut rng = OsRng;
         let signing_key = SigningKey::generate(&mut rng);
@@ -35,14 +413,28 @@ impl Ed25519Signer {
     }
 
     pub fn wallet_address(&self) -> String {
-        let digest = Sha256::digest(self.public_key_bytes());
-        format!("0x{}", to_hex(&digest[..20]))
+        self.public_key().wallet_address()
+    }
+
+    /// See [`Ed25519PublicKey::bech32_address`].
+    pub fn bech32_address(&self, hrp: &str) -> Result<String> {
+        self.public_key().bech32_address(hrp)
+    }
+
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        Ed25519PublicKey {
+            verifying_key: self.signing_key.verifying_key(),
+        }
     }
 
     pub fn secret_key_bytes(&self) -> [u8; 32] {
         self.signing_key.to_bytes()
     }
 
+    pub fn secret_key_material(&self) -> SecretKeyMaterial {
+        SecretKeyMaterial::new(self.signing_key.to_bytes())
+    }
+
     pub fn from_secret_key_bytes(mut secret_key: [u8; 32]) -> Self {
         let signing_key = SigningKey::from_bytes(&secret_key);
         secret_key.zeroize();
@@ -51,6 +443,12 @@ impl Ed25519Signer {
         }
     }
 
+    pub fn from_key_material(secret_key: &SecretKeyMaterial) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(secret_key.expose_secret()),
+        }
+    }
+
     /// Derive a deterministic Ed25519 keypair from a passphrase.
     /// Uses domain-tagged PBKDF-style SHA-256 derivation (1000 rounds).
     /// The same passphrase always produces the same wallet address.
@@ -77,7 +475,85 @@ impl Ed25519Signer {
         Self { signing_key }
     }
 
+    /// Derive a session keypair from this key and a fresh `salt`: the seed
+    /// is HMAC-SHA256, keyed with the secret key, over
+    /// `keycortex:session-key:v1:{salt}`. Each salt gives an unrelated key,
+    /// and the session key reveals nothing about this one.
+    pub fn derive_session_key(&self, salt: &[u8]) -> Self {
+        let mut secret_key = self.signing_key.to_bytes();
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(&secret_key).expect("hmac accepts any key length");
+        secret_key.zeroize();
+        mac.update(b"keycortex:session-key:v1:");
+        mac.update(salt);
+        Self::from_secret_key_bytes(mac.finalize().into_bytes().into())
+    }
+
     pub fn verify(&self, payload: &[u8], purpose: SignPurpose, signature: &[u8]) -> Result<bool> {
+        self.verify_in_domain(&SigningDomain::default(), payload, purpose, signature)
+    }
+
+    pub fn verify_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+        signature: &[u8],
+    ) -> Result<bool> {
+        self.public_key()
+            .verify_in_domain(domain, payload, purpose, signature)
+    }
+}
+
+/// Public half of an Ed25519 wallet key, for keys whose secret lives outside
+/// KeyCortex (KMS, HSM, hardware or client-side wallets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519PublicKey {
+    verifying_key: VerifyingKey,
+}
+
+impl Ed25519PublicKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        let verifying_key =
+            VerifyingKey::from_bytes(bytes).map_err(|_| anyhow!("invalid ed25519 public key"))?;
+        Ok(Self { verifying_key })
+    }
+
+    pub fn from_hex(public_key_hex: &str) -> Result<Self> {
+        let bytes: [u8; 32] = encoding::from_hex(public_key_hex)?
+            .try_into()
+            .map_err(|_| anyhow!("ed25519 public key must be 32 bytes"))?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.verifying_key.to_bytes()
+    }
+
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.to_bytes())
+    }
+
+    /// `0x` + the first 20 bytes of SHA-256 over the public key.
+    pub fn wallet_address(&self) -> String {
+        let digest = Sha256::digest(self.to_bytes());
+        format!("0x{}", to_hex(&digest[..20]))
+    }
+
+    /// The same 20 bytes as [`Self::wallet_address`] as bech32 under `hrp`,
+    /// which is how Cosmos SDK chains address an Ed25519 key.
+    pub fn bech32_address(&self, hrp: &str) -> Result<String> {
+        let digest = Sha256::digest(self.to_bytes());
+        encoding::to_bech32(hrp, &digest[..20])
+    }
+
+    pub fn verify_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+        signature: &[u8],
+    ) -> Result<bool> {
         if payload.is_empty() {
             return Err(anyhow!("payload cannot be empty"));
         }
@@ -86,15 +562,11 @@ impl Ed25519Signer {
             return Err(anyhow!("invalid ed25519 signature length"));
         }
 
-        let signing_input = signing_input(payload, purpose);
+        let signing_input = domain.signing_input(payload, &purpose);
         let signature = Signature::from_slice(signature)
             .map_err(|_| anyhow!("invalid ed25519 signature format"))?;
 
-        Ok(self
-            .signing_key
-            .verifying_key()
-            .verify(&signing_input, &signature)
-            .is_ok())
+        Ok(self.verifying_key.verify(&signing_input, &signature).is_ok())
     }
 }
 
@@ -105,6 +577,8 @@ pub struct Secp256k1Signer {
 
 #[cfg(feature = "secp256k1")]
 impl Secp256k1Signer {
+    pub const SIGNATURE_SCHEME: &'static str = "secp256k1";
+
     pub fn new_random() -> Self {
         let mut rng = OsRng;
         let signing_key = Secp256k1SigningKey::random(&mut rng);
@@ -125,6 +599,35 @@ impl Secp256k1Signer {
         format!("0x{}", to_hex(&digest[..20]))
     }
 
+    /// RIPEMD-160 of SHA-256 over the compressed public key (Bitcoin's
+    /// HASH160).
+    pub fn public_key_hash(&self) -> [u8; 20] {
+        Ripemd160::digest(Sha256::digest(self.public_key_bytes())).into()
+    }
+
+    /// Cosmos SDK account address: [`Self::public_key_hash`] as bech32
+    /// under `hrp` (e.g. `cosmos`).
+    pub fn bech32_address(&self, hrp: &str) -> Result<String> {
+        encoding::to_bech32(hrp, &self.public_key_hash())
+    }
+
+    /// Bitcoin native segwit (P2WPKH) address under `hrp` (`bc`, `tb`, `bcrt`).
+    pub fn p2wpkh_address(&self, hrp: &str) -> Result<String> {
+        encoding::to_segwit_address(hrp, 0, &self.public_key_hash())
+    }
+
+    /// DER-encoded, low-S ECDSA signature over a digest the caller has
+    /// already computed, such as a Bitcoin sighash. No signing domain is
+    /// applied, so only use this for chain-defined transaction digests.
+    pub fn sign_digest_der(&self, digest: &[u8; 32]) -> Result<Vec<u8>> {
+        let signature: Secp256k1Signature = self
+            .signing_key
+            .sign_prehash(digest)
+            .map_err(|_| anyhow!("secp256k1 digest signing failed"))?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+
     pub fn secret_key_bytes(&self) -> [u8; 32] {
         self.signing_key.to_bytes().into()
     }
@@ -137,6 +640,16 @@ impl Secp256k1Signer {
     }
 
     pub fn verify(&self, payload: &[u8], purpose: SignPurpose, signature: &[u8]) -> Result<bool> {
+        self.verify_in_domain(&SigningDomain::default(), payload, purpose, signature)
+    }
+
+    pub fn verify_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+        signature: &[u8],
+    ) -> Result<bool> {
         if payload.is_empty() {
             return Err(anyhow!("payload cannot be empty"));
         }
@@ -145,7 +658,7 @@ impl Secp256k1Signer {
             return Err(anyhow!("invalid secp256k1 signature length"));
         }
 
-        let signing_input = signing_input(payload, purpose);
+        let signing_input = domain.signing_input(payload, &purpose);
         let parsed = Secp256k1Signature::try_from(signature)
             .map_err(|_| anyhow!("invalid secp256k1 signature format"))?;
         let verifying_key: Secp256k1VerifyingKey = *self.signing_key.verifying_key();
@@ -156,51 +669,209 @@ impl Secp256k1Signer {
 
 #[cfg(feature = "secp256k1")]
 impl Signer for Secp256k1Signer {
-    fn sign(&self, payload: &[u8], purpose: SignPurpose) -> Result<Vec<u8>> {
+    fn sign_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>> {
         if payload.is_empty() {
             return Err(anyhow!("payload cannot be empty"));
         }
 
-        let signing_input = signing_input(payload, purpose);
+        let signing_input = domain.signing_input(payload, &purpose);
         let signature: Secp256k1Signature = self.signing_key.sign(&signing_input);
         Ok(signature.to_bytes().to_vec())
     }
 }
 
 impl Signer for Ed25519Signer {
-    fn sign(&self, payload: &[u8], purpose: SignPurpose) -> Result<Vec<u8>> {
+    fn sign_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>> {
         if payload.is_empty() {
             return Err(anyhow!("payload cannot be empty"));
         }
 
-        let signing_input = signing_input(payload, purpose);
+        let signing_input = domain.signing_input(payload, &purpose);
 
         let signature: Signature = self.signing_key.sign(&signing_input);
         Ok(signature.to_bytes().to_vec())
     }
 }
 
-fn signing_input(payload: &[u8], purpose: SignPurpose) -> Vec<u8> {
-    let purpose_tag = match purpose {
-        SignPurpose::Transaction => "transaction",
-        SignPurpose::Auth => "auth",
-        SignPurpose::Proof => "proof",
-    };
+#[cfg(feature = "bls")]
+const BLS_SIG_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+#[cfg(feature = "bls")]
+const BLS_POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// BLS12-381 signer (min-pk: 48-byte public keys, 96-byte signatures).
+///
+/// Uses the proof-of-possession ciphersuite, so signatures from many keys over
+/// the same payload can be aggregated and checked in one pairing. Only
+/// aggregate over public keys whose proof of possession has been verified,
+/// otherwise rogue-key attacks apply.
+#[cfg(feature = "bls")]
+pub struct Bls12381Signer {
+    secret_key: BlsSecretKey,
+}
+
+#[cfg(feature = "bls")]
+impl Bls12381Signer {
+    pub const SIGNATURE_SCHEME: &'static str = "bls12-381";
+
+    pub fn new_random() -> Self {
+        let mut ikm = [0_u8; 32];
+        OsRng.fill_bytes(&mut ikm);
+        let secret_key = BlsSecretKey::key_gen(&ikm, &[]).expect("32-byte ikm is always accepted");
+        ikm.zeroize();
+        Self { secret_key }
+    }
+
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        self.secret_key.sk_to_pk().to_bytes().to_vec()
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        to_hex(&self.public_key_bytes())
+    }
+
+    pub fn wallet_address(&self) -> String {
+        let digest = Sha256::digest(self.public_key_bytes());
+        format!("0x{}", to_hex(&digest[..20]))
+    }
+
+    pub fn secret_key_bytes(&self) -> [u8; 32] {
+        self.secret_key.to_bytes()
+    }
+
+    pub fn from_secret_key_bytes(mut secret_key: [u8; 32]) -> Result<Self> {
+        let parsed = BlsSecretKey::from_bytes(&secret_key)
+            .map_err(|_| anyhow!("invalid bls12-381 secret key"));
+        secret_key.zeroize();
+        Ok(Self {
+            secret_key: parsed?,
+        })
+    }
+
+    /// Signature over this signer's public key, proving it holds the secret key.
+    pub fn proof_of_possession(&self) -> Vec<u8> {
+        self.secret_key
+            .sign(&self.public_key_bytes(), BLS_POP_DST, &[])
+            .to_bytes()
+            .to_vec()
+    }
+
+    pub fn verify_proof_of_possession(public_key: &[u8], proof: &[u8]) -> Result<bool> {
+        let public_key = parse_bls_public_key(public_key)?;
+        let proof = parse_bls_signature(proof)?;
+        Ok(proof.verify(true, &public_key.to_bytes(), BLS_POP_DST, &[], &public_key, true)
+            == BLST_ERROR::BLST_SUCCESS)
+    }
+
+    pub fn verify(&self, payload: &[u8], purpose: SignPurpose, signature: &[u8]) -> Result<bool> {
+        self.verify_in_domain(&SigningDomain::default(), payload, purpose, signature)
+    }
+
+    pub fn verify_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+        signature: &[u8],
+    ) -> Result<bool> {
+        Self::verify_aggregate_in_domain(
+            domain,
+            payload,
+            purpose,
+            signature,
+            &[&self.public_key_bytes()],
+        )
+    }
+
+    /// Combine signatures over the same payload into one 96-byte signature.
+    pub fn aggregate_signatures(signatures: &[&[u8]]) -> Result<Vec<u8>> {
+        if signatures.is_empty() {
+            return Err(anyhow!("at least one signature is required"));
+        }
+        let parsed = signatures
+            .iter()
+            .map(|signature| parse_bls_signature(signature))
+            .collect::<Result<Vec<_>>>()?;
+        let refs: Vec<&BlsSignature> = parsed.iter().collect();
+        let aggregate = BlsAggregateSignature::aggregate(&refs, true)
+            .map_err(|err| anyhow!("bls aggregation failed: {err:?}"))?;
+        Ok(aggregate.to_signature().to_bytes().to_vec())
+    }
+
+    /// Verify an aggregate signature by `public_keys` over one domain-tagged payload.
+    pub fn verify_aggregate_in_domain(
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+        signature: &[u8],
+        public_keys: &[&[u8]],
+    ) -> Result<bool> {
+        if payload.is_empty() {
+            return Err(anyhow!("payload cannot be empty"));
+        }
+        if public_keys.is_empty() {
+            return Err(anyhow!("at least one public key is required"));
+        }
+
+        let signature = parse_bls_signature(signature)?;
+        let parsed = public_keys
+            .iter()
+            .map(|public_key| parse_bls_public_key(public_key))
+            .collect::<Result<Vec<_>>>()?;
+        let refs: Vec<&BlsPublicKey> = parsed.iter().collect();
+
+        let signing_input = domain.signing_input(payload, &purpose);
+        Ok(signature.fast_aggregate_verify(true, &signing_input, BLS_SIG_DST, &refs)
+            == BLST_ERROR::BLST_SUCCESS)
+    }
+}
+
+#[cfg(feature = "bls")]
+impl Signer for Bls12381Signer {
+    fn sign_in_domain(
+        &self,
+        domain: &SigningDomain,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Vec<u8>> {
+        if payload.is_empty() {
+            return Err(anyhow!("payload cannot be empty"));
+        }
+
+        let signing_input = domain.signing_input(payload, &purpose);
+        Ok(self
+            .secret_key
+            .sign(&signing_input, BLS_SIG_DST, &[])
+            .to_bytes()
+            .to_vec())
+    }
+}
 
-    let mut signing_input = Vec::with_capacity(32 + payload.len());
-    signing_input.extend_from_slice(b"keycortex:v1:");
-    signing_input.extend_from_slice(purpose_tag.as_bytes());
-    signing_input.extend_from_slice(b":");
-    signing_input.extend_from_slice(payload);
-    signing_input
+#[cfg(feature = "bls")]
+fn parse_bls_public_key(bytes: &[u8]) -> Result<BlsPublicKey> {
+    let public_key = BlsPublicKey::from_bytes(bytes)
+        .map_err(|_| anyhow!("invalid bls12-381 public key"))?;
+    public_key
+        .validate()
+        .map_err(|_| anyhow!("invalid bls12-381 public key"))?;
+    Ok(public_key)
 }
 
-fn to_hex(input: &[u8]) -> String {
-    let mut output = String::with_capacity(input.len() * 2);
-    for byte in input {
-        output.push_str(&format!("{byte:02x}"));
+#[cfg(feature = "bls")]
+fn parse_bls_signature(bytes: &[u8]) -> Result<BlsSignature> {
+    if bytes.len() != 96 {
+        return Err(anyhow!("invalid bls12-381 signature length"));
     }
-    output
+    BlsSignature::from_bytes(bytes).map_err(|_| anyhow!("invalid bls12-381 signature format"))
 }
 
 pub fn encrypt_key_material(secret_key: &[u8; 32], encryption_key: &str) -> Result<Vec<u8>> {
@@ -217,7 +888,7 @@ pub fn encrypt_key_material(secret_key: &[u8; 32], encryption_key: &str) -> Resu
     Ok(encrypted)
 }
 
-pub fn decrypt_key_material(encrypted: &[u8], encryption_key: &str) -> Result<[u8; 32]> {
+pub fn decrypt_key_material(encrypted: &[u8], encryption_key: &str) -> Result<SecretKeyMaterial> {
     if encryption_key.trim().is_empty() {
         return Err(anyhow!("encryption key cannot be empty"));
     }
@@ -227,10 +898,10 @@ pub fn decrypt_key_material(encrypted: &[u8], encryption_key: &str) -> Result<[u
     }
 
     let mut key_stream = derive_key_stream(encryption_key, encrypted.len());
-    let mut decrypted = [0_u8; 32];
+    let mut decrypted = SecretKeyMaterial::zeroed();
 
     for (index, byte) in encrypted.iter().enumerate() {
-        decrypted[index] = byte ^ key_stream[index];
+        decrypted.bytes[index] = byte ^ key_stream[index];
     }
 
     key_stream.zeroize();
@@ -238,6 +909,25 @@ pub fn decrypt_key_material(encrypted: &[u8], encryption_key: &str) -> Result<[u
     Ok(decrypted)
 }
 
+fn derive_key_stream(seed: &str, len: usize) -> Vec<u8> {
+    let mut stream = Vec::with_capacity(len);
+    let mut counter: u64 = 0;
+    while stream.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(seed.as_bytes());
+        hasher.update(counter.to_le_bytes());
+        let block = hasher.finalize();
+        for byte in block {
+            if stream.len() == len {
+                break;
+            }
+            stream.push(byte);
+        }
+        counter += 1;
+    }
+    stream
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +945,189 @@ mod tests {
         assert!(valid);
     }
 
+    #[test]
+    fn decrypted_key_material_roundtrips_and_redacts() {
+        let signer = Ed25519Signer::new_random();
+        let encrypted =
+            encrypt_key_material(signer.secret_key_material().expose_secret(), "test-key")
+                .expect("encrypt should succeed");
+        let material = decrypt_key_material(&encrypted, "test-key").expect("decrypt should succeed");
+
+        let restored = Ed25519Signer::from_key_material(&material);
+        assert_eq!(restored.wallet_address(), signer.wallet_address());
+        assert_eq!(format!("{material:?}"), "SecretKeyMaterial([REDACTED])");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dropping_a_secret_keeps_pages_shared_with_live_secrets_locked() {
+        let first = SecretKeyMaterial::new([1; 32]);
+        let second = SecretKeyMaterial::new([2; 32]);
+        if !(first.is_locked() && second.is_locked()) {
+            // mlock is unavailable here (e.g. RLIMIT_MEMLOCK is zero).
+            return;
+        }
+        let second_pages = pages_spanned(second.expose_secret().as_ptr(), 32);
+
+        drop(first);
+
+        let locked = LOCKED_PAGES.lock().expect("page table should not be poisoned");
+        for page in &second_pages {
+            assert!(locked.get(page).is_some_and(|count| *count >= 1));
+        }
+    }
+
+    #[test]
+    fn session_keys_depend_on_the_key_and_the_salt() {
+        let signer = Ed25519Signer::from_secret_key_bytes([7u8; 32]);
+        let session = signer.derive_session_key(b"salt-1");
+        assert_eq!(
+            session.public_key_hex(),
+            signer.derive_session_key(b"salt-1").public_key_hex()
+        );
+        assert_ne!(session.public_key_hex(), signer.public_key_hex());
+        assert_ne!(
+            session.public_key_hex(),
+            signer.derive_session_key(b"salt-2").public_key_hex()
+        );
+        assert_ne!(
+            session.public_key_hex(),
+            Ed25519Signer::from_secret_key_bytes([8u8; 32])
+                .derive_session_key(b"salt-1")
+                .public_key_hex()
+        );
+    }
+
+    #[test]
+    fn custom_signing_domain_is_not_interchangeable() {
+        let signer = Ed25519Signer::new_random();
+        let domain = SigningDomain::new("othernet", "v2");
+        let payload = b"test-payload";
+        let signature = signer
+            .sign_in_domain(&domain, payload, SignPurpose::Auth)
+            .expect("sign should succeed");
+
+        assert!(signer
+            .verify_in_domain(&domain, payload, SignPurpose::Auth, &signature)
+            .expect("verify should succeed"));
+        assert!(!signer
+            .verify(payload, SignPurpose::Auth, &signature)
+            .expect("verify should succeed"));
+    }
+
+    #[test]
+    fn signing_domain_roundtrips_through_chain_tags() {
+        let tags = SigningDomain::default().domain_tags();
+        assert_eq!(tags.tx_domain_tag, "keycortex:v1:transaction");
+
+        let parsed = SigningDomain::from_domain_tags(&tags).expect("tags should parse");
+        assert_eq!(parsed, SigningDomain::default());
+    }
+
+    #[test]
+    fn custom_purposes_are_registered_and_domain_separated() {
+        let domain = SigningDomain::default()
+            .with_custom_purposes(["delegation", "session"])
+            .expect("names should validate");
+        let delegation = SignPurpose::from("delegation".to_owned());
+        assert_eq!(delegation, SignPurpose::Custom("delegation".to_owned()));
+        assert_eq!(domain.domain_tag(&delegation), "keycortex:v1:custom.delegation");
+        domain.check_purpose(&delegation).expect("registered purpose");
+        domain.check_purpose(&SignPurpose::Auth).expect("built-in purpose");
+        assert!(domain
+            .check_purpose(&SignPurpose::Custom("voting".to_owned()))
+            .is_err());
+
+        let signer = Ed25519Signer::new_random();
+        let signature = signer
+            .sign_in_domain(&domain, b"payload", delegation.clone())
+            .expect("sign should succeed");
+        assert!(!signer
+            .verify_in_domain(&domain, b"payload", SignPurpose::Custom("session".to_owned()), &signature)
+            .expect("verify should succeed"));
+
+        let tags = domain.domain_tags();
+        assert_eq!(tags.custom_purposes, vec!["delegation", "session"]);
+        assert_eq!(SigningDomain::from_domain_tags(&tags).expect("tags should parse"), domain);
+
+        for bad in ["", "Delegation", "auth", "a:b"] {
+            assert!(SigningDomain::default().with_custom_purposes([bad]).is_err(), "{bad}");
+        }
+        let mut reserved = SigningDomain::default().domain_tags();
+        reserved.auth_domain_tag = "keycortex:v1:custom.auth".to_owned();
+        assert!(SigningDomain::from_domain_tags(&reserved).is_err());
+    }
+
+    #[test]
+    fn envelope_signatures_bind_chain_nonce_and_expiry() {
+        let domain = SigningDomain::default();
+        let envelope = TransactionEnvelope {
+            chain_id: "flowcortex-l1".to_owned(),
+            nonce: 7,
+            expires_at_epoch_ms: 1_700_000_000_000,
+        };
+        let input = domain
+            .signing_input_v2(b"payload", &SignPurpose::Transaction, &envelope)
+            .expect("envelope should apply");
+        assert_eq!(
+            input,
+            b"keycortex:v1:transaction#v2;chain=flowcortex-l1;nonce=7;expires_at=1700000000000:payload"
+        );
+
+        let signer = Ed25519Signer::new_random();
+        let bound = domain.with_envelope(&envelope).expect("envelope should apply");
+        let signature = signer
+            .sign_in_domain(&bound, b"payload", SignPurpose::Transaction)
+            .expect("sign should succeed");
+        assert!(signer
+            .verify_in_domain(&bound, b"payload", SignPurpose::Transaction, &signature)
+            .expect("verify should succeed"));
+        assert!(!signer
+            .verify(b"payload", SignPurpose::Transaction, &signature)
+            .expect("verify should succeed"));
+
+        for other in [
+            TransactionEnvelope { chain_id: "evm-1".to_owned(), ..envelope.clone() },
+            TransactionEnvelope { nonce: 8, ..envelope.clone() },
+            TransactionEnvelope { expires_at_epoch_ms: 1, ..envelope.clone() },
+        ] {
+            let domain = domain.with_envelope(&other).expect("envelope should apply");
+            assert!(!signer
+                .verify_in_domain(&domain, b"payload", SignPurpose::Transaction, &signature)
+                .expect("verify should succeed"));
+        }
+
+        let bad_chain = TransactionEnvelope { chain_id: "a;nonce=1".to_owned(), ..envelope };
+        assert!(domain.with_envelope(&bad_chain).is_err());
+    }
+
+    #[test]
+    fn ed25519_bech32_address_carries_wallet_address_bytes() {
+        let signer = Ed25519Signer::new_random();
+        let address = signer.bech32_address("cosmos").expect("encodes");
+        assert!(address.starts_with("cosmos1"), "{address}");
+        let (hrp, data) = encoding::from_bech32(&address).expect("decodes");
+        assert_eq!(hrp, "cosmos");
+        assert_eq!(format!("0x{}", to_hex(&data)), signer.wallet_address());
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn secp256k1_bech32_address_is_hash160_of_compressed_key() {
+        let mut secret_key = [0u8; 32];
+        secret_key[31] = 1;
+        let signer = Secp256k1Signer::from_secret_key_bytes(secret_key).expect("valid key");
+        let (hrp, data) =
+            encoding::from_bech32(&signer.bech32_address("cosmos").expect("encodes")).expect("decodes");
+        assert_eq!(hrp, "cosmos");
+        // HASH160 of the generator point, familiar from BIP-173's P2WPKH example.
+        assert_eq!(to_hex(&data), "751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(
+            signer.p2wpkh_address("bc").expect("encodes"),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+    }
+
     #[cfg(feature = "secp256k1")]
     #[test]
     fn secp256k1_sign_verify_roundtrip() {
@@ -268,23 +1141,51 @@ mod tests {
             .expect("verify should succeed");
         assert!(valid);
     }
-}
 
-fn derive_key_stream(seed: &str, len: usize) -> Vec<u8> {
-    let mut stream = Vec::with_capacity(len);
-    let mut counter: u64 = 0;
-    while stream.len() < len {
-        let mut hasher = Sha256::new();
-        hasher.update(seed.as_bytes());
-        hasher.update(counter.to_le_bytes());
-        let block = hasher.finalize();
-        for byte in block {
-            if stream.len() == len {
-                break;
-            }
-            stream.push(byte);
+    #[cfg(feature = "bls")]
+    #[test]
+    fn bls_aggregate_signature_verifies_for_all_signers() {
+        let signers: Vec<Bls12381Signer> = (0..3).map(|_| Bls12381Signer::new_random()).collect();
+        let payload = b"block-42";
+
+        for signer in &signers {
+            assert!(Bls12381Signer::verify_proof_of_possession(
+                &signer.public_key_bytes(),
+                &signer.proof_of_possession()
+            )
+            .expect("pop verify should succeed"));
         }
-        counter += 1;
+
+        let signatures: Vec<Vec<u8>> = signers
+            .iter()
+            .map(|signer| signer.sign(payload, SignPurpose::Proof).expect("sign should succeed"))
+            .collect();
+        assert!(signers[0]
+            .verify(payload, SignPurpose::Proof, &signatures[0])
+            .expect("verify should succeed"));
+
+        let signature_refs: Vec<&[u8]> = signatures.iter().map(Vec::as_slice).collect();
+        let aggregate =
+            Bls12381Signer::aggregate_signatures(&signature_refs).expect("aggregate should succeed");
+        let public_keys: Vec<Vec<u8>> = signers.iter().map(Bls12381Signer::public_key_bytes).collect();
+        let key_refs: Vec<&[u8]> = public_keys.iter().map(Vec::as_slice).collect();
+
+        let domain = SigningDomain::default();
+        assert!(Bls12381Signer::verify_aggregate_in_domain(
+            &domain,
+            payload,
+            SignPurpose::Proof,
+            &aggregate,
+            &key_refs
+        )
+        .expect("aggregate verify should succeed"));
+        assert!(!Bls12381Signer::verify_aggregate_in_domain(
+            &domain,
+            payload,
+            SignPurpose::Auth,
+            &aggregate,
+            &key_refs
+        )
+        .expect("aggregate verify should succeed"));
     }
-    stream
 }
@@ -0,0 +1,124 @@
+//! Wallet-to-wallet notes sealed to the recipient's Ed25519 wallet key.
+//!
+//! The recipient key is mapped to X25519 (the Montgomery form of the
+//! Edwards point; the secret side uses the clamped Ed25519 scalar), so any
+//! wallet can receive notes without publishing a second key. Each note uses
+//! a fresh ephemeral X25519 key; the content key is HMAC-SHA256 over the
+//! shared secret, bound to both public keys. Sealed notes are
+//! `MAGIC || ephemeral_public || nonce || ciphertext+tag` under
+//! XChaCha20-Poly1305.
+
+use anyhow::{Result, anyhow};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use crate::{Ed25519PublicKey, Ed25519Signer};
+
+/// Marks a note sealed by [`seal`] (format version 1).
+pub const MEMO_MAGIC: &[u8; 4] = b"kcm1";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const KDF_LABEL: &[u8] = b"keycortex:memo:v1";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Encrypt `note` so only the holder of `recipient`'s secret key can read it.
+pub fn seal(recipient: &Ed25519PublicKey, note: &[u8]) -> Result<Vec<u8>> {
+    let recipient_point = recipient.verifying_key.to_montgomery();
+    let mut ephemeral_secret = [0u8; 32];
+    OsRng.fill_bytes(&mut ephemeral_secret);
+    let ephemeral_public = MontgomeryPoint::mul_base_clamped(ephemeral_secret);
+    let shared = recipient_point.mul_clamped(ephemeral_secret);
+    ephemeral_secret.zeroize();
+
+    let cipher = content_cipher(shared, &ephemeral_public, &recipient_point)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, note)
+        .map_err(|_| anyhow!("memo encryption failed"))?;
+
+    let mut sealed = Vec::with_capacity(MEMO_MAGIC.len() + KEY_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MEMO_MAGIC);
+    sealed.extend_from_slice(ephemeral_public.as_bytes());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt a note sealed to `recipient`'s public key.
+pub fn open(recipient: &Ed25519Signer, sealed: &[u8]) -> Result<Vec<u8>> {
+    let body = sealed
+        .strip_prefix(MEMO_MAGIC.as_slice())
+        .ok_or_else(|| anyhow!("value is not a sealed memo"))?;
+    if body.len() < KEY_LEN + NONCE_LEN + TAG_LEN {
+        return Err(anyhow!("sealed memo is truncated"));
+    }
+    let (ephemeral_public, rest) = body.split_at(KEY_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let ephemeral_public = MontgomeryPoint(ephemeral_public.try_into()?);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into()?;
+
+    let recipient_point = recipient.signing_key.verifying_key().to_montgomery();
+    let mut scalar = recipient.signing_key.to_scalar_bytes();
+    let shared = ephemeral_public.mul_clamped(scalar);
+    scalar.zeroize();
+
+    content_cipher(shared, &ephemeral_public, &recipient_point)?
+        .decrypt(&XNonce::from(nonce), ciphertext)
+        .map_err(|_| anyhow!("memo decryption failed: wrong key or tampered memo"))
+}
+
+fn content_cipher(
+    shared: MontgomeryPoint,
+    ephemeral_public: &MontgomeryPoint,
+    recipient_public: &MontgomeryPoint,
+) -> Result<XChaCha20Poly1305> {
+    let mut shared = shared.to_bytes();
+    if shared == [0u8; 32] {
+        return Err(anyhow!("memo key agreement produced a low-order point"));
+    }
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(&shared).expect("hmac accepts any key length");
+    shared.zeroize();
+    mac.update(KDF_LABEL);
+    mac.update(ephemeral_public.as_bytes());
+    mac.update(recipient_public.as_bytes());
+    let mut key: [u8; KEY_LEN] = mac.finalize().into_bytes().into();
+    let cipher = XChaCha20Poly1305::new((&key).into());
+    key.zeroize();
+    Ok(cipher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipient_opens_what_was_sealed_to_it() {
+        let recipient = Ed25519Signer::new_random();
+        let sealed = seal(&recipient.public_key(), b"rent for march").unwrap();
+        assert!(sealed.starts_with(MEMO_MAGIC));
+        assert_eq!(open(&recipient, &sealed).unwrap(), b"rent for march");
+
+        let again = seal(&recipient.public_key(), b"rent for march").unwrap();
+        assert_ne!(sealed, again, "each memo uses a fresh ephemeral key");
+    }
+
+    #[test]
+    fn other_keys_and_tampering_are_rejected() {
+        let recipient = Ed25519Signer::new_random();
+        let sealed = seal(&recipient.public_key(), b"invoice 42").unwrap();
+
+        assert!(open(&Ed25519Signer::new_random(), &sealed).is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&recipient, &tampered).is_err());
+        assert!(open(&recipient, &sealed[..40]).is_err());
+        assert!(open(&recipient, b"kce1 not a memo").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Passphrase strength estimation for deterministic wallets.
+//!
+//! A passphrase wallet's address is public, and anyone can grind candidate
+//! passphrases through [`crate::Ed25519Signer::from_passphrase`] offline, so the
+//! estimate is deliberately pessimistic. Dictionary words, including l33t
+//! variants, count as a single guess from the list. Repeats and keyboard or
+//! alphabet runs count as roughly one bit per character.
+
+/// Common passwords and words, most frequent first.
+const COMMON: &[&str] = &[
+    "password", "123456", "12345678", "qwerty", "123456789", "12345", "1234", "111111",
+    "1234567", "dragon", "123123", "baseball", "abc123", "football", "monkey", "letmein",
+    "696969", "shadow", "master", "666666", "qwertyuiop", "123321", "mustang", "1234567890",
+    "michael", "654321", "superman", "1qaz2wsx", "7777777", "121212", "000000", "qazwsx",
+    "123qwe", "killer", "trustno1", "jordan", "jennifer", "zxcvbnm", "asdfgh", "hunter",
+    "buster", "soccer", "harley", "batman", "andrew", "tigger", "sunshine", "iloveyou",
+    "2000", "charlie", "robert", "thomas", "hockey", "ranger", "daniel",
+    "starwars", "112233", "george", "computer", "michelle", "jessica", "pepper",
+    "1111", "zxcvbn", "555555", "11111111", "131313", "freedom", "777777", "pass",
+    "maggie", "159753", "aaaaaa", "ginger", "princess", "joshua", "cheese", "amanda",
+    "summer", "love", "ashley", "nicole", "chelsea", "biteme", "matthew", "access",
+    "yankees", "987654321", "dallas", "austin", "thunder", "taylor", "matrix", "admin",
+    "welcome", "login", "secret", "changeme", "default", "guest", "root",
+    "correcthorsebatterystaple", "correct", "horse", "battery", "staple", "wallet",
+    "bitcoin", "crypto", "ethereum", "satoshi", "keycortex", "flowcortex", "proof",
+    "flower", "money", "seed", "phrase", "mnemonic", "ledger", "coin", "token",
+    "hello", "world", "test", "testing", "demo", "sample", "example", "qwerty123",
+    "winter", "spring", "autumn", "monday", "friday", "january", "december", "family",
+];
+
+/// Dictionary matches shorter than this are scored character by character.
+const MIN_WORD_LEN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrengthReport {
+    /// Estimated bits of guessing entropy.
+    pub entropy_bits: f64,
+    /// Length in characters (not bytes).
+    pub length: usize,
+    /// Dictionary entries found in the passphrase, after l33t normalisation.
+    pub dictionary_words: Vec<String>,
+    /// What makes the passphrase weak.
+    pub warnings: Vec<String>,
+    /// How to make it stronger.
+    pub suggestions: Vec<String>,
+}
+
+impl StrengthReport {
+    pub fn meets(&self, min_entropy_bits: f64) -> bool {
+        self.entropy_bits >= min_entropy_bits
+    }
+}
+
+/// Estimate how hard `passphrase` is to guess.
+pub fn passphrase_strength(passphrase: &str) -> StrengthReport {
+    let chars: Vec<char> = passphrase.chars().collect();
+    let lower: Vec<char> = passphrase.to_lowercase().chars().collect();
+    let normalized: Vec<char> = chars.iter().map(|&c| normalize(c)).collect();
+    let mut report = StrengthReport {
+        entropy_bits: 0.0,
+        length: chars.len(),
+        dictionary_words: Vec::new(),
+        warnings: Vec::new(),
+        suggestions: Vec::new(),
+    };
+    if chars.is_empty() {
+        report.warnings.push("passphrase is empty".to_owned());
+        report
+            .suggestions
+            .push("use four or more uncommon words, or 16+ random characters".to_owned());
+        return report;
+    }
+
+    let compact: String = normalized.iter().filter(|c| c.is_alphanumeric()).collect();
+    if let Some(rank) = COMMON
+        .iter()
+        .position(|w| w.chars().map(normalize).collect::<String>() == compact)
+    {
+        report.entropy_bits = ((rank + 2) as f64).log2();
+        report.dictionary_words.push(COMMON[rank].to_owned());
+        report
+            .warnings
+            .push("this is one of the most commonly used passwords".to_owned());
+        report
+            .suggestions
+            .push("pick something that is not a known password or phrase".to_owned());
+        return report;
+    }
+
+    let pool_bits = (pool_size(&chars) as f64).log2();
+    let word_bits = ((COMMON.len() * 2) as f64).log2();
+    let mut predictable_run = 0usize;
+    let mut i = 0;
+    while i < normalized.len() {
+        if let Some(word) = longest_word_at(&normalized, i) {
+            report.entropy_bits += word_bits;
+            report.dictionary_words.push(word.to_owned());
+            i += word.chars().count();
+            continue;
+        }
+        let predictable = i > 0 && i < lower.len() && {
+            let (prev, cur) = (u32::from(lower[i - 1]), u32::from(lower[i]));
+            prev == cur || prev + 1 == cur || cur + 1 == prev
+        };
+        if predictable {
+            report.entropy_bits += 1.0;
+            predictable_run += 1;
+        } else {
+            report.entropy_bits += pool_bits;
+        }
+        i += 1;
+    }
+
+    if !report.dictionary_words.is_empty() {
+        report.warnings.push(format!(
+            "contains common words or passwords: {}",
+            report.dictionary_words.join(", ")
+        ));
+        report
+            .suggestions
+            .push("avoid common words; l33t substitutions like 'p@ssw0rd' do not help".to_owned());
+    }
+    if predictable_run * 3 >= chars.len() {
+        report
+            .warnings
+            .push("repeated characters or sequences like 'aaaa' or '1234' are easy to guess".to_owned());
+    }
+    if chars.len() < 12 {
+        report.warnings.push(format!("only {} characters long", chars.len()));
+        report
+            .suggestions
+            .push("make it longer; length adds more strength than symbols".to_owned());
+    }
+    if pool_size(&chars) <= 26 {
+        report
+            .suggestions
+            .push("mix in upper case letters, digits or symbols".to_owned());
+    }
+    report
+}
+
+fn normalize(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        _ => c.to_lowercase().next().unwrap_or(c),
+    }
+}
+
+fn pool_size(chars: &[char]) -> u32 {
+    let mut pool = 0;
+    if chars.iter().any(char::is_ascii_lowercase) {
+        pool += 26;
+    }
+    if chars.iter().any(char::is_ascii_uppercase) {
+        pool += 26;
+    }
+    if chars.iter().any(char::is_ascii_digit) {
+        pool += 10;
+    }
+    if chars.iter().any(|c| c.is_ascii() && !c.is_ascii_alphanumeric()) {
+        pool += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        pool += 100;
+    }
+    pool.max(2)
+}
+
+/// Longest dictionary entry starting at `start`, compared against the
+/// l33t-normalised form of both.
+fn longest_word_at(normalized: &[char], start: usize) -> Option<&'static str> {
+    COMMON
+        .iter()
+        .filter(|word| word.chars().count() >= MIN_WORD_LEN)
+        .filter(|word| {
+            let word: Vec<char> = word.chars().map(normalize).collect();
+            normalized[start..].starts_with(&word)
+        })
+        .max_by_key(|word| word.len())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_and_leet_passwords_score_near_zero() {
+        for weak in ["password", "P@ssw0rd", "123456", "Correct Horse Battery Staple"] {
+            let report = passphrase_strength(weak);
+            assert!(report.entropy_bits < 10.0, "{weak}: {}", report.entropy_bits);
+            assert!(!report.warnings.is_empty());
+        }
+        assert_eq!(passphrase_strength("").entropy_bits, 0.0);
+    }
+
+    #[test]
+    fn repeats_and_sequences_are_discounted() {
+        let report = passphrase_strength("zzzzzzzzzzzzzzzz");
+        assert!(report.entropy_bits < 25.0, "{}", report.entropy_bits);
+        assert!(report.warnings.iter().any(|w| w.contains("sequences")));
+        assert!(passphrase_strength("abcdefghijklmnop").entropy_bits < 25.0);
+    }
+
+    #[test]
+    fn long_random_and_multi_word_passphrases_score_high() {
+        assert!(passphrase_strength("vK7#qPz!2mR9&xLw").meets(80.0));
+        let words = passphrase_strength("glacier-umbrella-fossil-tangerine");
+        assert!(words.meets(100.0), "{}", words.entropy_bits);
+        assert!(words.warnings.is_empty(), "{:?}", words.warnings);
+
+        let with_word = passphrase_strength("mypassword2024");
+        assert_eq!(with_word.dictionary_words, vec!["password".to_owned()]);
+        assert!(with_word.entropy_bits < passphrase_strength("mqzfkxrtjw2024").entropy_bits);
+    }
+}
@@ -0,0 +1,135 @@
+//! Canonical test vectors for KeyCortex key derivation and domain-tagged signing.
+//!
+//! The vectors live only in `vectors/keycortex-signing-v1.json`, which external
+//! SDKs (JS, Python, ...) use to check that they derive identical wallets and
+//! produce byte-identical signatures. [`verify_all`] is the reference harness.
+
+use anyhow::{Result, anyhow};
+use kc_api_types::SignPurpose;
+use serde::Deserialize;
+
+use crate::encoding::{from_hex, to_hex};
+use crate::{Ed25519Signer, Signer, SigningDomain};
+
+/// The published vector file, embedded at build time.
+pub const VECTORS_JSON: &str = include_str!("../vectors/keycortex-signing-v1.json");
+
+/// Passphrase → Ed25519 keypair → wallet address.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeriveVector {
+    pub passphrase: String,
+    pub public_key_hex: String,
+    pub wallet_address: String,
+}
+
+/// Passphrase-derived key signing `payload` under a domain and purpose.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SignVector {
+    pub passphrase: String,
+    pub namespace: String,
+    pub version: String,
+    pub purpose: String,
+    pub payload_hex: String,
+    /// `{namespace}:{version}:{purpose_tag}:{payload}` as signed.
+    pub signing_input_hex: String,
+    pub signature_hex: String,
+}
+
+/// The `derive` and `sign` sections of [`VECTORS_JSON`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vectors {
+    pub derive: Vec<DeriveVector>,
+    pub sign: Vec<SignVector>,
+}
+
+/// Parse [`VECTORS_JSON`].
+pub fn load() -> Result<Vectors> {
+    serde_json::from_str(VECTORS_JSON).map_err(|err| anyhow!("invalid vector file: {err}"))
+}
+
+pub fn parse_purpose(purpose: &str) -> Result<SignPurpose> {
+    match purpose {
+        "transaction" => Ok(SignPurpose::Transaction),
+        "auth" => Ok(SignPurpose::Auth),
+        "proof" => Ok(SignPurpose::Proof),
+        other => Err(anyhow!("unknown sign purpose '{other}'")),
+    }
+}
+
+pub fn check_derive_vector(vector: &DeriveVector) -> Result<()> {
+    let signer = Ed25519Signer::from_passphrase(&vector.passphrase);
+    expect_eq(
+        "public_key_hex",
+        &vector.passphrase,
+        &signer.public_key_hex(),
+        &vector.public_key_hex,
+    )?;
+    expect_eq(
+        "wallet_address",
+        &vector.passphrase,
+        &signer.wallet_address(),
+        &vector.wallet_address,
+    )
+}
+
+pub fn check_sign_vector(vector: &SignVector) -> Result<()> {
+    let signer = Ed25519Signer::from_passphrase(&vector.passphrase);
+    let domain = SigningDomain::new(&vector.namespace, &vector.version);
+    let purpose = parse_purpose(&vector.purpose)?;
+    let payload = from_hex(&vector.payload_hex)?;
+
+    let signing_input = domain.signing_input(&payload, &purpose);
+    expect_eq(
+        "signing_input_hex",
+        &vector.passphrase,
+        &to_hex(&signing_input),
+        &vector.signing_input_hex,
+    )?;
+
+    let signature = signer.sign_in_domain(&domain, &payload, purpose.clone())?;
+    expect_eq(
+        "signature_hex",
+        &vector.passphrase,
+        &to_hex(&signature),
+        &vector.signature_hex,
+    )?;
+
+    if !signer.verify_in_domain(&domain, &payload, purpose, &from_hex(&vector.signature_hex)?)? {
+        return Err(anyhow!(
+            "signature vector for '{}' does not verify",
+            vector.passphrase
+        ));
+    }
+    Ok(())
+}
+
+/// Check every published vector against this implementation; returns the count checked.
+pub fn verify_all() -> Result<usize> {
+    let vectors = load()?;
+    for vector in &vectors.derive {
+        check_derive_vector(vector)?;
+    }
+    for vector in &vectors.sign {
+        check_sign_vector(vector)?;
+    }
+    Ok(vectors.derive.len() + vectors.sign.len())
+}
+
+fn expect_eq(field: &str, passphrase: &str, actual: &str, expected: &str) -> Result<()> {
+    if actual != expected {
+        return Err(anyhow!(
+            "vector mismatch for '{passphrase}' {field}: expected {expected}, got {actual}"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_vectors_match_implementation() {
+        assert_eq!(verify_all().expect("vectors should verify"), 7);
+    }
+}
@@ -0,0 +1,455 @@
+//! Encrypted backups of the RocksDB keystore to an S3-compatible bucket.
+//!
+//! [`BackupTarget::backup`] uploads two objects per run, under a
+//! configurable prefix (default `keycortex/`):
+//!
+//! - `keystore/{epoch_ms}.kcbk`: every RocksDB entry as stored, each a
+//!   big-endian `u32` key length, the key, a `u32` value length and the
+//!   value. Values sealed under `KEYCORTEX_STORAGE_MASTER_KEY` stay sealed.
+//! - `audit/{epoch_ms}.ndjson.kcbk`: the RocksDB audit log as one JSON
+//!   event per line, newest first.
+//!
+//! Both are streamed: entries are read and sealed a [`CHUNK_SIZE`] frame at
+//! a time under a backup key of their own, see [`open_object`], and each frame
+//! goes up as one part of a multipart upload. An upload that fails part way
+//! is aborted, so the bucket never holds a partial object.
+//!
+//! [`open_keystore_snapshot`] and [`open_audit_export`] read objects back;
+//! [`RocksDbKeystore::restore_raw_entries`] loads a snapshot into an empty
+//! store.
+
+mod s3;
+mod sealed;
+
+use anyhow::{Context, Result, anyhow, bail};
+use kc_crypto::aead::MasterKey;
+use kc_crypto::sigv4::AwsCredentials;
+use kc_storage::{AuditEventRecord, RocksDbKeystore};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+pub use crate::s3::S3Config;
+pub use crate::sealed::{CHUNK_SIZE, OBJECT_MAGIC, open_object};
+use crate::s3::S3Client;
+use crate::sealed::SealedWriter;
+
+const DEFAULT_PREFIX: &str = "keycortex/";
+
+/// Audit events read per storage call.
+const AUDIT_PAGE: usize = 500;
+
+/// Objects written by one [`BackupTarget::backup`] run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupReport {
+    pub keystore_object: String,
+    pub keystore_entries: usize,
+    pub audit_object: String,
+    pub audit_events: usize,
+    /// Ciphertext bytes uploaded across both objects.
+    pub bytes_uploaded: u64,
+}
+
+pub struct BackupTarget {
+    s3: S3Client,
+    prefix: String,
+    key: Arc<MasterKey>,
+}
+
+impl BackupTarget {
+    pub fn new(config: S3Config, prefix: impl Into<String>, key: MasterKey) -> Self {
+        Self {
+            s3: S3Client::new(config),
+            prefix: prefix.into(),
+            key: Arc::new(key),
+        }
+    }
+
+    /// Configured from `KEYCORTEX_BACKUP_S3_*`; `None` unless
+    /// `KEYCORTEX_BACKUP_S3_BUCKET` is set. Credentials come from the usual
+    /// `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` / `AWS_SESSION_TOKEN`.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(bucket) = env_var("KEYCORTEX_BACKUP_S3_BUCKET") else {
+            return Ok(None);
+        };
+        let region = env_var("KEYCORTEX_BACKUP_S3_REGION").unwrap_or_else(|| "us-east-1".to_owned());
+        let endpoint = env_var("KEYCORTEX_BACKUP_S3_ENDPOINT")
+            .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        let prefix = env_var("KEYCORTEX_BACKUP_S3_PREFIX").unwrap_or_else(|| DEFAULT_PREFIX.to_owned());
+        let key = env_var("KEYCORTEX_BACKUP_ENCRYPTION_KEY")
+            .ok_or_else(|| anyhow!("KEYCORTEX_BACKUP_ENCRYPTION_KEY is required for S3 backups"))
+            .and_then(|hex| MasterKey::from_hex(&hex).context("KEYCORTEX_BACKUP_ENCRYPTION_KEY"))?;
+        let config = S3Config {
+            endpoint,
+            bucket,
+            region,
+            credentials: AwsCredentials::from_env()?,
+        };
+        Ok(Some(Self::new(config, prefix, key)))
+    }
+
+    pub fn bucket(&self) -> &str {
+        self.s3.bucket()
+    }
+
+    /// Upload a keystore snapshot and an audit export named after `now_epoch_ms`.
+    pub async fn backup(&self, keystore: Arc<RocksDbKeystore>, now_epoch_ms: u128) -> Result<BackupReport> {
+        let keystore_object = format!("{}keystore/{now_epoch_ms}.kcbk", self.prefix);
+        let snapshot = Arc::clone(&keystore);
+        let (keystore_entries, keystore_bytes) = self
+            .upload_sealed(&keystore_object, move |writer| {
+                snapshot.for_each_raw_entry(|key, value| {
+                    writer.write_all(&u32::try_from(key.len())?.to_be_bytes())?;
+                    writer.write_all(key)?;
+                    writer.write_all(&u32::try_from(value.len())?.to_be_bytes())?;
+                    writer.write_all(value)
+                })
+            })
+            .await
+            .context("keystore snapshot")?;
+
+        let audit_object = format!("{}audit/{now_epoch_ms}.ndjson.kcbk", self.prefix);
+        let (audit_events, audit_bytes) = self
+            .upload_sealed(&audit_object, move |writer| {
+                let mut count = 0;
+                let mut cursor: Option<String> = None;
+                loop {
+                    let page = keystore.list_audit_events_page(AUDIT_PAGE, cursor.as_deref(), None, None, None)?;
+                    for event in &page.events {
+                        let mut line = serde_json::to_vec(event)?;
+                        line.push(b'\n');
+                        writer.write_all(&line)?;
+                    }
+                    count += page.events.len();
+                    match page.next_cursor {
+                        Some(next) => cursor = Some(next),
+                        None => return Ok(count),
+                    }
+                }
+            })
+            .await
+            .context("audit export")?;
+
+        Ok(BackupReport {
+            keystore_object,
+            keystore_entries,
+            audit_object,
+            audit_events,
+            bytes_uploaded: keystore_bytes + audit_bytes,
+        })
+    }
+
+    /// Run `produce` on a blocking thread and upload what it writes as one
+    /// sealed object. Returns the count `produce` reports and the bytes sent.
+    async fn upload_sealed<F>(&self, object_key: &str, produce: F) -> Result<(usize, u64)>
+    where
+        F: FnOnce(&mut SealedWriter) -> Result<usize> + Send + 'static,
+    {
+        // One frame in flight while the next is read and sealed.
+        let (frames_tx, mut frames) = mpsc::channel(1);
+        let mut writer = SealedWriter::new(Arc::clone(&self.key), object_key.to_owned(), frames_tx);
+        let producer = tokio::task::spawn_blocking(move || {
+            let count = produce(&mut writer)?;
+            writer.finish()?;
+            Ok::<_, anyhow::Error>(count)
+        });
+
+        let upload_id = self.s3.create_multipart_upload(object_key).await?;
+        let uploaded = async {
+            let mut etags = Vec::new();
+            let mut bytes = 0u64;
+            while let Some(frame) = frames.recv().await {
+                bytes += frame.len() as u64;
+                etags.push(self.s3.upload_part(object_key, &upload_id, etags.len() + 1, frame).await?);
+            }
+            Ok::<_, anyhow::Error>((etags, bytes))
+        }
+        .await;
+        // Unblocks the producer if the upload stopped early.
+        drop(frames);
+        let produced = producer.await.map_err(|err| anyhow!("backup reader panicked: {err}"));
+
+        match (uploaded, produced) {
+            (Ok((etags, bytes)), Ok(Ok(count))) => {
+                self.s3
+                    .complete_multipart_upload(object_key, &upload_id, &etags)
+                    .await?;
+                Ok((count, bytes))
+            }
+            (Err(err), _) | (_, Err(err)) | (_, Ok(Err(err))) => {
+                let _ = self.s3.abort_multipart_upload(object_key, &upload_id).await;
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Decrypt a `keystore/` object into the entries
+/// [`RocksDbKeystore::restore_raw_entries`] takes.
+pub fn open_keystore_snapshot(key: &MasterKey, object_key: &str, object: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let plaintext = open_object(key, object_key, object)?;
+    let mut rest = plaintext.as_slice();
+    let mut entries = Vec::new();
+    while !rest.is_empty() {
+        let entry_key = take_field(&mut rest)?;
+        let value = take_field(&mut rest)?;
+        entries.push((entry_key, value));
+    }
+    Ok(entries)
+}
+
+/// Decrypt an `audit/` object.
+pub fn open_audit_export(key: &MasterKey, object_key: &str, object: &[u8]) -> Result<Vec<AuditEventRecord>> {
+    let plaintext = open_object(key, object_key, object)?;
+    plaintext
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).context("audit export line"))
+        .collect()
+}
+
+fn take_field(rest: &mut &[u8]) -> Result<Vec<u8>> {
+    let Some((length, body)) = rest.split_first_chunk::<4>() else {
+        bail!("keystore snapshot is truncated");
+    };
+    let length = usize::try_from(u32::from_be_bytes(*length))?;
+    if body.len() < length {
+        bail!("keystore snapshot is truncated");
+    }
+    let (field, after) = body.split_at(length);
+    *rest = after;
+    Ok(field.to_vec())
+}
+
+/// Parse a cron-style schedule: `@hourly`, `@daily`, `@weekly`, or
+/// `@every <n><s|m|h|d>` such as `@every 6h`.
+pub fn parse_schedule(schedule: &str) -> Result<Duration> {
+    let schedule = schedule.trim();
+    let interval = match schedule {
+        "@hourly" => Duration::from_secs(60 * 60),
+        "@daily" | "@midnight" => Duration::from_secs(24 * 60 * 60),
+        "@weekly" => Duration::from_secs(7 * 24 * 60 * 60),
+        _ => {
+            let every = schedule
+                .strip_prefix("@every")
+                .map(str::trim)
+                .filter(|every| !every.is_empty())
+                .ok_or_else(|| anyhow!("unsupported backup schedule '{schedule}'"))?;
+            let split = every.len() - every.chars().last().map_or(0, char::len_utf8);
+            let (count, unit) = every.split_at(split);
+            let count: u64 = count
+                .parse()
+                .map_err(|_| anyhow!("unsupported backup schedule '{schedule}'"))?;
+            let unit = match unit {
+                "s" => 1,
+                "m" => 60,
+                "h" => 60 * 60,
+                "d" => 24 * 60 * 60,
+                _ => bail!("unsupported backup schedule '{schedule}'"),
+            };
+            Duration::from_secs(count * unit)
+        }
+    };
+    if interval.is_zero() {
+        bail!("backup schedule must be longer than zero");
+    }
+    Ok(interval)
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router,
+        body::Bytes,
+        extract::{DefaultBodyLimit, Path, Query, State},
+        http::{HeaderMap, Method, StatusCode},
+        response::{IntoResponse, Response},
+        routing::any,
+    };
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Just enough of S3's multipart upload to receive a backup.
+    #[derive(Default)]
+    struct MockS3 {
+        uploads: Mutex<HashMap<String, BTreeMap<usize, Vec<u8>>>>,
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+        aborted: Mutex<Vec<String>>,
+        /// Part number to reject with a 500.
+        fail_part: Option<usize>,
+    }
+
+    async fn mock_s3(
+        State(mock): State<Arc<MockS3>>,
+        method: Method,
+        Path((_bucket, key)): Path<(String, String)>,
+        Query(query): Query<HashMap<String, String>>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response {
+        let signed = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256 Credential=test-key/"));
+        if !signed {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        match (method, query.get("uploadId"), query.get("partNumber")) {
+            (Method::POST, None, _) if query.contains_key("uploads") => {
+                let upload_id = format!("upload-{key}");
+                mock.uploads.lock().unwrap().insert(upload_id.clone(), BTreeMap::new());
+                format!("<InitiateMultipartUploadResult><UploadId>{upload_id}</UploadId></InitiateMultipartUploadResult>")
+                    .into_response()
+            }
+            (Method::PUT, Some(upload_id), Some(part_number)) => {
+                let part_number: usize = part_number.parse().unwrap();
+                if mock.fail_part == Some(part_number) {
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+                mock.uploads
+                    .lock()
+                    .unwrap()
+                    .get_mut(upload_id)
+                    .unwrap()
+                    .insert(part_number, body.to_vec());
+                ([("etag", format!("\"etag-{part_number}\""))], "").into_response()
+            }
+            (Method::POST, Some(upload_id), None) => {
+                let parts = mock.uploads.lock().unwrap().remove(upload_id).unwrap();
+                assert_eq!(
+                    body.iter().filter(|byte| **byte == b'<').count(),
+                    2 + 6 * parts.len(),
+                    "one Part per uploaded part"
+                );
+                mock.objects.lock().unwrap().insert(key, parts.into_values().flatten().collect());
+                "<CompleteMultipartUploadResult></CompleteMultipartUploadResult>".into_response()
+            }
+            (Method::DELETE, Some(upload_id), None) => {
+                mock.uploads.lock().unwrap().remove(upload_id);
+                mock.aborted.lock().unwrap().push(key);
+                StatusCode::NO_CONTENT.into_response()
+            }
+            _ => StatusCode::BAD_REQUEST.into_response(),
+        }
+    }
+
+    async fn start_mock(mock: Arc<MockS3>) -> String {
+        let app = Router::new()
+            .route("/{bucket}/{*key}", any(mock_s3))
+            .layer(DefaultBodyLimit::disable())
+            .with_state(mock);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://{address}")
+    }
+
+    fn target(endpoint: String) -> BackupTarget {
+        let config = S3Config {
+            endpoint,
+            bucket: "backups".to_owned(),
+            region: "us-east-1".to_owned(),
+            credentials: AwsCredentials {
+                access_key_id: "test-key".to_owned(),
+                secret_access_key: "test-secret".to_owned(),
+                session_token: None,
+            },
+        };
+        BackupTarget::new(config, "kc/", MasterKey::from_bytes([7; 32]))
+    }
+
+    fn seeded_keystore(temp_dir: &TempDir) -> Arc<RocksDbKeystore> {
+        let keystore = RocksDbKeystore::open_default(temp_dir.path().join("db").to_str().unwrap()).unwrap();
+        keystore.save_wallet_label("0xa", "Treasury").unwrap();
+        // Large enough to span several frames.
+        keystore.save_wallet_label("0xbig", &"x".repeat(CHUNK_SIZE + 1)).unwrap();
+        for index in 0..3 {
+            keystore
+                .append_audit_event(AuditEventRecord {
+                    event_id: String::new(),
+                    event_type: "backup_test".to_owned(),
+                    wallet_address: Some("0xa".to_owned()),
+                    user_id: None,
+                    chain: None,
+                    outcome: "success".to_owned(),
+                    message: None,
+                    timestamp_epoch_ms: 1_700_000_000_000 + index,
+                    tenant: None,
+                })
+                .unwrap();
+        }
+        Arc::new(keystore)
+    }
+
+    #[tokio::test]
+    async fn backup_round_trips_through_s3() {
+        let mock = Arc::new(MockS3::default());
+        let target = target(start_mock(Arc::clone(&mock)).await);
+        let temp_dir = TempDir::new().unwrap();
+        let keystore = seeded_keystore(&temp_dir);
+
+        let report = target.backup(Arc::clone(&keystore), 42).await.unwrap();
+        assert_eq!(report.keystore_object, "kc/keystore/42.kcbk");
+        assert_eq!(report.audit_object, "kc/audit/42.ndjson.kcbk");
+        assert_eq!(report.audit_events, 3);
+
+        let objects = mock.objects.lock().unwrap();
+        let snapshot = &objects[&report.keystore_object];
+        assert!(snapshot.starts_with(OBJECT_MAGIC));
+        assert!(!snapshot.windows(8).any(|window| window == b"Treasury"));
+        let key = MasterKey::from_bytes([7; 32]);
+        let entries = open_keystore_snapshot(&key, &report.keystore_object, snapshot).unwrap();
+        assert_eq!(entries.len(), report.keystore_entries);
+
+        let restored_dir = TempDir::new().unwrap();
+        let restored = RocksDbKeystore::open_default(restored_dir.path().join("db").to_str().unwrap()).unwrap();
+        restored.restore_raw_entries(entries).unwrap();
+        assert_eq!(restored.load_wallet_label("0xa").unwrap().as_deref(), Some("Treasury"));
+        assert_eq!(restored.list_audit_events(10, None, None, None).unwrap().len(), 3);
+
+        let audit = open_audit_export(&key, &report.audit_object, &objects[&report.audit_object]).unwrap();
+        assert_eq!(audit.len(), 3);
+        assert_eq!(audit[0].timestamp_epoch_ms, 1_700_000_000_002);
+
+        // Frames are bound to their object and to the end of it.
+        assert!(open_object(&key, &report.audit_object, snapshot).is_err());
+        let first_frame = 5 + 4 + usize::try_from(u32::from_be_bytes(snapshot[5..9].try_into().unwrap())).unwrap();
+        assert!(open_object(&key, &report.keystore_object, &snapshot[..first_frame]).is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_part_aborts_the_upload() {
+        let mock = Arc::new(MockS3 {
+            fail_part: Some(2),
+            ..MockS3::default()
+        });
+        let target = target(start_mock(Arc::clone(&mock)).await);
+        let temp_dir = TempDir::new().unwrap();
+
+        let err = target.backup(seeded_keystore(&temp_dir), 42).await.unwrap_err();
+        assert!(format!("{err:#}").contains("HTTP 500"), "{err:#}");
+        assert_eq!(*mock.aborted.lock().unwrap(), vec!["kc/keystore/42.kcbk".to_owned()]);
+        assert!(mock.objects.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn schedules_parse_cron_shorthands() {
+        assert_eq!(parse_schedule("@daily").unwrap(), Duration::from_secs(86_400));
+        assert_eq!(parse_schedule(" @every 6h ").unwrap(), Duration::from_secs(6 * 3_600));
+        assert_eq!(parse_schedule("@every 90m").unwrap(), Duration::from_secs(90 * 60));
+        assert!(parse_schedule("0 3 * * *").is_err());
+        assert!(parse_schedule("@every 0s").is_err());
+        assert!(parse_schedule("@every 5é").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! The S3 multipart-upload calls a backup needs, signed with
+//! [`kc_crypto::sigv4`]. Path-style URLs (`{endpoint}/{bucket}/{key}`) work
+//! with AWS and with S3-compatible stores such as MinIO or Ceph.
+
+use anyhow::{Context, Result, anyhow};
+use kc_crypto::encoding::to_hex;
+use kc_crypto::sigv4::{AwsCredentials, SigV4Request, amz_date, authorization_header};
+use reqwest::Method;
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where backups go.
+#[derive(Clone)]
+pub struct S3Config {
+    /// e.g. `https://s3.eu-west-1.amazonaws.com` or `http://minio:9000`.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub credentials: AwsCredentials,
+}
+
+pub(crate) struct S3Client {
+    http: reqwest::Client,
+    config: S3Config,
+}
+
+impl S3Client {
+    pub(crate) fn new(config: S3Config) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    pub(crate) fn bucket(&self) -> &str {
+        &self.config.bucket
+    }
+
+    pub(crate) async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let body = self
+            .send(Method::POST, key, &[("uploads", "")], Vec::new())
+            .await?
+            .text()
+            .await
+            .context("s3 create multipart upload response")?;
+        xml_element(&body, "UploadId")
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| anyhow!("s3 create multipart upload returned no UploadId"))
+    }
+
+    /// Upload part `part_number` (from 1) and return its ETag.
+    pub(crate) async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Vec<u8>,
+    ) -> Result<String> {
+        let part_number = part_number.to_string();
+        let response = self
+            .send(
+                Method::PUT,
+                key,
+                &[("partNumber", &part_number), ("uploadId", upload_id)],
+                body,
+            )
+            .await?;
+        response
+            .headers()
+            .get("etag")
+            .and_then(|etag| etag.to_str().ok())
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| anyhow!("s3 upload part {part_number} returned no ETag"))
+    }
+
+    pub(crate) async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let mut body = String::from("<CompleteMultipartUpload>");
+        for (index, etag) in etags.iter().enumerate() {
+            body.push_str(&format!(
+                "<Part><PartNumber>{}</PartNumber><ETag>{etag}</ETag></Part>",
+                index + 1
+            ));
+        }
+        body.push_str("</CompleteMultipartUpload>");
+        let response = self
+            .send(Method::POST, key, &[("uploadId", upload_id)], body.into_bytes())
+            .await?
+            .text()
+            .await
+            .context("s3 complete multipart upload response")?;
+        // S3 can report a failed completion in the body of a 200.
+        if let Some(code) = xml_element(&response, "Code") {
+            return Err(anyhow!("s3 complete multipart upload failed: {code}"));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.send(Method::DELETE, key, &[("uploadId", upload_id)], Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        let endpoint = self.config.endpoint.trim_end_matches('/');
+        let path = format!(
+            "/{}/{}",
+            uri_encode(&self.config.bucket, false),
+            uri_encode(key, false)
+        );
+        let mut query: Vec<String> = query
+            .iter()
+            .map(|(name, value)| format!("{}={}", uri_encode(name, true), uri_encode(value, true)))
+            .collect();
+        query.sort();
+        let query = query.join("&");
+        let url = format!("{endpoint}{path}?{query}");
+
+        let parsed = reqwest::Url::parse(&url).context("invalid S3 endpoint")?;
+        let host = match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_owned(),
+            (None, _) => return Err(anyhow!("S3 endpoint has no host")),
+        };
+        let amz_date = amz_date(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs());
+        let mut headers = vec![
+            ("host", host),
+            ("x-amz-content-sha256", to_hex(&Sha256::digest(&body))),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.config.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        let authorization = authorization_header(
+            &self.config.credentials,
+            &SigV4Request {
+                method: method.as_str(),
+                path: &path,
+                query: &query,
+                headers: &headers,
+                payload: &body,
+            },
+            &amz_date,
+            &self.config.region,
+            "s3",
+        );
+
+        let mut request = self
+            .http
+            .request(method.clone(), parsed)
+            .header("authorization", authorization);
+        for (name, value) in &headers {
+            if *name != "host" {
+                request = request.header(*name, value);
+            }
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("s3 {method} transport"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("s3 {method} HTTP {status}: {text}"));
+        }
+        Ok(response)
+    }
+}
+
+/// SigV4 URI encoding: everything but unreserved characters is
+/// percent-encoded, and `/` too unless encoding a path.
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(char::from(byte));
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Text of the first `<name>` element; enough for S3's flat responses.
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{name}>");
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{name}>"))?;
+    Some(&xml[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_encode_keeps_unreserved_and_path_slashes() {
+        assert_eq!(uri_encode("keycortex/keystore/1.kcbk", false), "keycortex/keystore/1.kcbk");
+        assert_eq!(uri_encode("a b+c/d", true), "a%20b%2Bc%2Fd");
+    }
+
+    #[test]
+    fn xml_element_reads_first_match() {
+        let xml = "<InitiateMultipartUploadResult><Bucket>b</Bucket><UploadId>abc</UploadId></InitiateMultipartUploadResult>";
+        assert_eq!(xml_element(xml, "UploadId"), Some("abc"));
+        assert_eq!(xml_element(xml, "Code"), None);
+    }
+}
@@ -0,0 +1,112 @@
+//! Client-side encryption of backup objects.
+//!
+//! An object is [`OBJECT_MAGIC`] followed by frames, each a big-endian `u32`
+//! length and a [`MasterKey::seal`]ed chunk of at most [`CHUNK_SIZE`] bytes.
+//! A frame's associated data is the object key, its index and whether it is
+//! the last frame, so frames cannot be reordered, moved between objects or
+//! dropped from the end without [`open_object`] noticing. The bucket only
+//! ever sees ciphertext.
+
+use anyhow::{Context, Result, anyhow, bail};
+use kc_crypto::aead::MasterKey;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Marks a sealed backup object (format version 1).
+pub const OBJECT_MAGIC: &[u8; 5] = b"kcbk1";
+
+/// Plaintext bytes per frame. Each frame goes up as one multipart-upload
+/// part, and S3 wants every part but the last to be at least 5 MiB.
+pub const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+fn frame_aad(object_key: &str, index: u32, last: bool) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(object_key.len() + 6);
+    aad.extend_from_slice(object_key.as_bytes());
+    aad.push(0);
+    aad.extend_from_slice(&index.to_be_bytes());
+    aad.push(u8::from(last));
+    aad
+}
+
+/// Buffers plaintext and hands each sealed frame to the uploader.
+pub(crate) struct SealedWriter {
+    key: Arc<MasterKey>,
+    object_key: String,
+    frames: mpsc::Sender<Vec<u8>>,
+    buffer: Vec<u8>,
+    index: u32,
+}
+
+impl SealedWriter {
+    pub(crate) fn new(key: Arc<MasterKey>, object_key: String, frames: mpsc::Sender<Vec<u8>>) -> Self {
+        Self {
+            key,
+            object_key,
+            frames,
+            buffer: Vec::new(),
+            index: 0,
+        }
+    }
+
+    pub(crate) fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.buffer.extend_from_slice(bytes);
+        while self.buffer.len() >= CHUNK_SIZE {
+            let rest = self.buffer.split_off(CHUNK_SIZE);
+            let chunk = std::mem::replace(&mut self.buffer, rest);
+            self.send(&chunk, false)?;
+        }
+        Ok(())
+    }
+
+    /// Seal what is left as the last frame, which may be empty.
+    pub(crate) fn finish(mut self) -> Result<()> {
+        let chunk = std::mem::take(&mut self.buffer);
+        self.send(&chunk, true)
+    }
+
+    fn send(&mut self, chunk: &[u8], last: bool) -> Result<()> {
+        let sealed = self
+            .key
+            .seal(chunk, &frame_aad(&self.object_key, self.index, last))?;
+        let mut frame = Vec::with_capacity(OBJECT_MAGIC.len() + 4 + sealed.len());
+        if self.index == 0 {
+            frame.extend_from_slice(OBJECT_MAGIC);
+        }
+        frame.extend_from_slice(&u32::try_from(sealed.len())?.to_be_bytes());
+        frame.extend_from_slice(&sealed);
+        self.frames
+            .blocking_send(frame)
+            .map_err(|_| anyhow!("backup upload stopped"))?;
+        self.index += 1;
+        Ok(())
+    }
+}
+
+/// Decrypt a whole object written under `object_key`.
+pub fn open_object(key: &MasterKey, object_key: &str, object: &[u8]) -> Result<Vec<u8>> {
+    let mut rest = object
+        .strip_prefix(OBJECT_MAGIC.as_slice())
+        .ok_or_else(|| anyhow!("not a KeyCortex backup object"))?;
+    let mut plaintext = Vec::new();
+    let mut index = 0u32;
+    loop {
+        let Some((length, body)) = rest.split_first_chunk::<4>() else {
+            bail!("backup object is truncated");
+        };
+        let length = usize::try_from(u32::from_be_bytes(*length))?;
+        if body.len() < length {
+            bail!("backup object is truncated");
+        }
+        let (sealed, after) = body.split_at(length);
+        let last = after.is_empty();
+        let chunk = key
+            .open(sealed, &frame_aad(object_key, index, last))
+            .with_context(|| format!("backup frame {index}"))?;
+        plaintext.extend_from_slice(&chunk);
+        if last {
+            return Ok(plaintext);
+        }
+        rest = after;
+        index += 1;
+    }
+}
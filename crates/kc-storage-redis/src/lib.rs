@@ -0,0 +1,265 @@
+//! Redis backend for the shared-state stores in
+//! [`kc_storage::shared_state`] and for [`kc_auth_adapter::ChallengeStore`],
+//! so wallet-service replicas behind a load balancer see the same
+//! challenges, nonce claims and idempotency records.
+//!
+//! Keys, under a configurable prefix (default `keycortex:`):
+//!
+//! - `challenge:{challenge}`: hash of `issued_at`, `expires_at`, `used`,
+//!   `used_at`, expiring [`CHALLENGE_RETENTION`] after the challenge does,
+//!   so there is nothing for [`ChallengeStore::sweep`] to do.
+//! - `nonce:{wallet_address}`: last claimed nonce, no expiry.
+//! - `idempotency:{key}`: JSON [`SubmitIdempotencyRecord`], expiring after
+//!   [`IDEMPOTENCY_TTL`]. RocksDB keeps the durable copy.
+//! - `idempotency-claim:{key}`: set with `NX` while a submission under the
+//!   key is in flight, expiring after the claim's TTL.
+//!
+//! Every check-and-update runs as a Lua script or a single conditional
+//! command, so it is atomic across replicas.
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use kc_auth_adapter::{CHALLENGE_RETENTION, ChallengeOutcome, ChallengeRecord, ChallengeStore};
+use kc_storage::{IdempotencyStore, NonceStore, SubmitIdempotencyRecord};
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, Script};
+use std::time::Duration;
+
+/// How long an idempotency record stays in Redis.
+pub const IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const DEFAULT_KEY_PREFIX: &str = "keycortex:";
+
+/// Returns `missing`, `used`, or the challenge's `expires_at` after marking
+/// it used.
+const CONSUME_CHALLENGE: &str = r"
+if redis.call('EXISTS', KEYS[1]) == 0 then return 'missing' end
+if redis.call('HGET', KEYS[1], 'used') == '1' then return 'used' end
+redis.call('HSET', KEYS[1], 'used', '1', 'used_at', ARGV[1])
+return redis.call('HGET', KEYS[1], 'expires_at')
+";
+
+/// ARGV: nonce, persisted last nonce or ''. Returns 1 if claimed.
+const CLAIM_NONCE: &str = r"
+local last = redis.call('GET', KEYS[1]) or ARGV[2]
+if last ~= '' and tonumber(ARGV[1]) <= tonumber(last) then return 0 end
+redis.call('SET', KEYS[1], ARGV[1])
+return 1
+";
+
+const RELEASE_NONCE: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then redis.call('DEL', KEYS[1]) end
+return 0
+";
+
+/// ARGV: settled nonce or '', chain's last nonce or ''. Returns 1 if replaced.
+const RECONCILE_NONCE: &str = r"
+local claimed = redis.call('GET', KEYS[1])
+if claimed and (ARGV[1] == '' or tonumber(claimed) > tonumber(ARGV[1])) then return 0 end
+if ARGV[2] == '' then redis.call('DEL', KEYS[1]) else redis.call('SET', KEYS[1], ARGV[2]) end
+return 1
+";
+
+/// Implements every shared-state store over one multiplexed connection,
+/// which reconnects on its own after Redis restarts.
+#[derive(Clone)]
+pub struct RedisSharedState {
+    conn: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisSharedState {
+    /// Connect to `url` (e.g. `redis://cache:6379/0`, or `rediss://` for TLS).
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).context("invalid Redis URL")?;
+        let conn = ConnectionManager::new(client)
+            .await
+            .context("connecting to Redis")?;
+        Ok(Self {
+            conn,
+            prefix: DEFAULT_KEY_PREFIX.to_owned(),
+        })
+    }
+
+    /// Namespace keys, e.g. per environment sharing one Redis.
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_owned();
+        self
+    }
+
+    pub fn key_prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn key(&self, kind: &str, id: &str) -> String {
+        format!("{}{kind}:{id}", self.prefix)
+    }
+}
+
+#[async_trait]
+impl ChallengeStore for RedisSharedState {
+    async fn insert(&self, challenge: &str, record: ChallengeRecord) -> Result<()> {
+        let key = self.key("challenge", challenge);
+        let mut fields = vec![
+            ("issued_at", record.issued_at_epoch_ms.to_string()),
+            ("expires_at", record.expires_at_epoch_ms.to_string()),
+            ("used", if record.used { "1" } else { "0" }.to_owned()),
+        ];
+        if let Some(used_at) = record.used_at_epoch_ms {
+            fields.push(("used_at", used_at.to_string()));
+        }
+        let expire_at_ms = record.expires_at_epoch_ms + CHALLENGE_RETENTION.as_millis();
+        redis::pipe()
+            .atomic()
+            .del(&key)
+            .hset_multiple(&key, &fields)
+            .cmd("PEXPIREAT")
+            .arg(&key)
+            .arg(u64::try_from(expire_at_ms).unwrap_or(u64::MAX))
+            .query_async::<()>(&mut self.conn.clone())
+            .await
+            .context("storing challenge in Redis")
+    }
+
+    async fn consume(&self, challenge: &str, now_epoch_ms: u128) -> Result<ChallengeOutcome> {
+        let reply: String = Script::new(CONSUME_CHALLENGE)
+            .key(self.key("challenge", challenge))
+            .arg(now_epoch_ms.to_string())
+            .invoke_async(&mut self.conn.clone())
+            .await
+            .context("consuming challenge in Redis")?;
+        Ok(match reply.as_str() {
+            "missing" => ChallengeOutcome::NotFound,
+            "used" => ChallengeOutcome::AlreadyUsed,
+            expires_at => {
+                let expires_at: u128 = expires_at
+                    .parse()
+                    .with_context(|| format!("challenge expires_at {expires_at:?}"))?;
+                if now_epoch_ms > expires_at {
+                    ChallengeOutcome::Expired
+                } else {
+                    ChallengeOutcome::Consumed
+                }
+            }
+        })
+    }
+
+    async fn sweep(&self, _cutoff_epoch_ms: u128) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+#[async_trait]
+impl NonceStore for RedisSharedState {
+    async fn last(&self, wallet_address: &str) -> Result<Option<u64>> {
+        self.conn
+            .clone()
+            .get(self.key("nonce", wallet_address))
+            .await
+            .context("reading nonce from Redis")
+    }
+
+    async fn claim(&self, wallet_address: &str, nonce: u64, persisted_last: Option<u64>) -> Result<bool> {
+        if nonce > (1 << 53) {
+            // Lua numbers are doubles; larger nonces would compare inexactly.
+            bail!("nonce {nonce} is too large for the Redis nonce store");
+        }
+        let claimed: i64 = Script::new(CLAIM_NONCE)
+            .key(self.key("nonce", wallet_address))
+            .arg(nonce)
+            .arg(persisted_last.map(|last| last.to_string()).unwrap_or_default())
+            .invoke_async(&mut self.conn.clone())
+            .await
+            .context("claiming nonce in Redis")?;
+        Ok(claimed == 1)
+    }
+
+    async fn release(&self, wallet_address: &str, nonce: u64) -> Result<()> {
+        Script::new(RELEASE_NONCE)
+            .key(self.key("nonce", wallet_address))
+            .arg(nonce)
+            .invoke_async::<i64>(&mut self.conn.clone())
+            .await
+            .context("releasing nonce in Redis")?;
+        Ok(())
+    }
+
+    async fn reconcile(&self, wallet_address: &str, settled: Option<u64>, chain_last: Option<u64>) -> Result<bool> {
+        let replaced: i64 = Script::new(RECONCILE_NONCE)
+            .key(self.key("nonce", wallet_address))
+            .arg(settled.map(|settled| settled.to_string()).unwrap_or_default())
+            .arg(chain_last.map(|last| last.to_string()).unwrap_or_default())
+            .invoke_async(&mut self.conn.clone())
+            .await
+            .context("reconciling nonce in Redis")?;
+        Ok(replaced == 1)
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for RedisSharedState {
+    async fn get(&self, idempotency_key: &str) -> Result<Option<SubmitIdempotencyRecord>> {
+        let raw: Option<String> = self
+            .conn
+            .clone()
+            .get(self.key("idempotency", idempotency_key))
+            .await
+            .context("reading idempotency record from Redis")?;
+        raw.map(|raw| serde_json::from_str(&raw).context("idempotency record"))
+            .transpose()
+    }
+
+    async fn put(&self, record: &SubmitIdempotencyRecord) -> Result<()> {
+        self.conn
+            .clone()
+            .set_ex::<_, _, ()>(
+                self.key("idempotency", &record.idempotency_key),
+                serde_json::to_string(record)?,
+                IDEMPOTENCY_TTL.as_secs(),
+            )
+            .await
+            .context("storing idempotency record in Redis")
+    }
+
+    async fn claim(&self, idempotency_key: &str, ttl: Duration) -> Result<bool> {
+        let set: Option<String> = redis::cmd("SET")
+            .arg(self.key("idempotency-claim", idempotency_key))
+            .arg(1)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut self.conn.clone())
+            .await
+            .context("claiming idempotency key in Redis")?;
+        Ok(set.is_some())
+    }
+
+    async fn release(&self, idempotency_key: &str) -> Result<()> {
+        self.conn
+            .clone()
+            .del::<_, ()>(self.key("idempotency-claim", idempotency_key))
+            .await
+            .context("releasing idempotency key in Redis")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kc_storage::shared_state::conformance;
+
+    /// Runs against `TEST_REDIS_URL` when set; skipped otherwise.
+    #[tokio::test]
+    async fn redis_shared_state_conforms() -> Result<()> {
+        let Ok(url) = std::env::var("TEST_REDIS_URL") else {
+            return Ok(());
+        };
+        let prefix = format!("keycortex-test:{}:", std::process::id());
+        let store = RedisSharedState::connect(&url).await?.with_key_prefix(&prefix);
+
+        kc_auth_adapter::conformance::check_challenge_store(&store).await;
+        conformance::check_nonce_store(&store).await;
+        conformance::check_idempotency_store(&store).await;
+        Ok(())
+    }
+}
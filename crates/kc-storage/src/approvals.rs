@@ -0,0 +1,73 @@
+//! Transfers held for a second person's approval.
+//!
+//! A transfer over a wallet's `approval_above` limit is kept as an
+//! [`ApprovalRecord`] under `approval:` instead of being signed, until an
+//! ops principal other than the one who asked for it approves or rejects it.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::RocksDbKeystore;
+
+/// A held transfer and what became of it.
+///
+/// `status` moves `pending` → `approved` → `submitted` | `failed`, or
+/// `pending` → `rejected`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub approval_id: String,
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    /// In base units.
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+    /// Why the signing policy held the transfer.
+    pub reason: String,
+    /// The principal that submitted the transfer, if the submit was
+    /// authenticated.
+    #[serde(default)]
+    pub requested_by: Option<String>,
+    pub requested_at_epoch_ms: u128,
+    #[serde(default)]
+    pub decided_by: Option<String>,
+    #[serde(default)]
+    pub decided_at_epoch_ms: Option<u128>,
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    /// Why an approved transfer could not be submitted.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl RocksDbKeystore {
+    fn key_for_approval(approval_id: &str) -> String {
+        format!("approval:{approval_id}")
+    }
+
+    pub fn save_approval(&self, record: &ApprovalRecord) -> Result<()> {
+        self.put(
+            Self::key_for_approval(&record.approval_id),
+            serde_json::to_vec(record)?,
+        )
+    }
+
+    pub fn load_approval(&self, approval_id: &str) -> Result<Option<ApprovalRecord>> {
+        let Some(raw) = self.get(Self::key_for_approval(approval_id))? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&raw)?))
+    }
+
+    /// Every held transfer, in no particular order.
+    pub fn list_approvals(&self) -> Result<Vec<ApprovalRecord>> {
+        let mut records = Vec::new();
+        for approval_id in self.scan_prefix_addresses("approval:")? {
+            if let Some(record) = self.load_approval(&approval_id)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
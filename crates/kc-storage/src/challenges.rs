@@ -0,0 +1,81 @@
+//! Auth challenges kept in the keystore.
+//!
+//! A [`ChallengeRecord`] under `challenge:{challenge}` lives from issue
+//! until [`RocksDbKeystore::delete_challenges_expired_before`] removes it,
+//! used or not. Consuming one is serialised by a lock, so a challenge is
+//! only ever consumed once.
+
+use anyhow::{Result, anyhow};
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+
+use crate::RocksDbKeystore;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeRecord {
+    pub issued_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+    pub used: bool,
+    pub used_at_epoch_ms: Option<u128>,
+}
+
+impl RocksDbKeystore {
+    fn key_for_challenge(challenge: &str) -> String {
+        format!("challenge:{challenge}")
+    }
+
+    pub fn save_challenge(&self, challenge: &str, record: &ChallengeRecord) -> Result<()> {
+        self.put(Self::key_for_challenge(challenge), serde_json::to_vec(record)?)
+    }
+
+    pub fn load_challenge(&self, challenge: &str) -> Result<Option<ChallengeRecord>> {
+        let Some(raw) = self.get(Self::key_for_challenge(challenge))? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&raw)?))
+    }
+
+    /// Mark `challenge` used at `now_epoch_ms`, returning its record as it
+    /// was before; a record already used is returned unchanged.
+    pub fn consume_challenge(&self, challenge: &str, now_epoch_ms: u128) -> Result<Option<ChallengeRecord>> {
+        let _guard = self
+            .challenge_lock
+            .lock()
+            .map_err(|_| anyhow!("challenge lock poisoned"))?;
+        let Some(record) = self.load_challenge(challenge)? else {
+            return Ok(None);
+        };
+        if !record.used {
+            let used = ChallengeRecord {
+                used: true,
+                used_at_epoch_ms: Some(now_epoch_ms),
+                ..record.clone()
+            };
+            self.save_challenge(challenge, &used)?;
+        }
+        Ok(Some(record))
+    }
+
+    /// Remove challenges that expired before `cutoff_epoch_ms`, returning
+    /// how many were removed.
+    pub fn delete_challenges_expired_before(&self, cutoff_epoch_ms: u128) -> Result<u64> {
+        let _guard = self
+            .challenge_lock
+            .lock()
+            .map_err(|_| anyhow!("challenge lock poisoned"))?;
+        let mut batch = WriteBatch::default();
+        let mut removed = 0;
+        for challenge in self.scan_prefix_addresses("challenge:")? {
+            if let Some(record) = self.load_challenge(&challenge)?
+                && record.expires_at_epoch_ms < cutoff_epoch_ms
+            {
+                batch.delete(Self::key_for_challenge(&challenge).as_bytes());
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            self.write(batch)?;
+        }
+        Ok(removed)
+    }
+}
@@ -0,0 +1,473 @@
+//! Behaviour every [`Keystore`] backend must share. Each backend gets one
+//! test that runs the whole suite against a fresh store.
+
+use crate::{
+    AuditEventRecord, DeadlineExceeded, EncryptedKeystore, InMemoryKeystore, Keystore,
+    RocksDbKeystore, SubmitIdempotencyRecord, SubmittedTxRecord, WalletBindingRecord,
+    WalletActivity, WalletMetadataRecord, WalletNonceRecord, WalletUndelete, WalletUsage,
+};
+use kc_crypto::aead::{MasterKey, is_sealed};
+use tempfile::TempDir;
+
+async fn check_keystore(keystore: &dyn Keystore) {
+    assert!(!keystore.has_wallet("0xabc").await.unwrap());
+    assert_eq!(keystore.load_encrypted_key("0xabc").await.unwrap(), None);
+    assert!(
+        keystore
+            .list_wallet_addresses(0, usize::MAX)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    for address in ["0xc", "0xa", "0xb"] {
+        keystore
+            .save_encrypted_key(address, address.as_bytes().to_vec())
+            .await
+            .unwrap();
+    }
+    assert!(keystore.has_wallet("0xa").await.unwrap());
+    assert_eq!(
+        keystore.load_encrypted_key("0xb").await.unwrap(),
+        Some(b"0xb".to_vec())
+    );
+
+    // Saving again overwrites.
+    keystore
+        .save_encrypted_key("0xb", vec![1, 2, 3])
+        .await
+        .unwrap();
+    assert_eq!(
+        keystore.load_encrypted_key("0xb").await.unwrap(),
+        Some(vec![1, 2, 3])
+    );
+
+    // Listing is sorted by address and pages with offset/limit.
+    assert_eq!(
+        keystore.list_wallet_addresses(0, usize::MAX).await.unwrap(),
+        ["0xa", "0xb", "0xc"]
+    );
+    assert_eq!(keystore.list_wallet_addresses(1, 1).await.unwrap(), ["0xb"]);
+    assert_eq!(
+        keystore.list_wallet_addresses(2, 10).await.unwrap(),
+        ["0xc"]
+    );
+    assert!(
+        keystore
+            .list_wallet_addresses(3, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        keystore
+            .list_wallet_addresses(0, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    assert!(keystore.delete_encrypted_key("0xa").await.unwrap());
+    assert!(!keystore.delete_encrypted_key("0xa").await.unwrap());
+    assert!(!keystore.has_wallet("0xa").await.unwrap());
+    assert_eq!(keystore.load_encrypted_key("0xa").await.unwrap(), None);
+    assert_eq!(
+        keystore.list_wallet_addresses(0, usize::MAX).await.unwrap(),
+        ["0xb", "0xc"]
+    );
+}
+
+#[tokio::test]
+async fn in_memory_keystore_conforms() {
+    check_keystore(&InMemoryKeystore::default()).await;
+}
+
+#[tokio::test]
+async fn rocksdb_keystore_conforms() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    check_keystore(&keystore).await;
+}
+
+#[tokio::test]
+async fn encrypted_keystore_conforms_and_seals_values() {
+    let keystore =
+        EncryptedKeystore::new(InMemoryKeystore::default(), MasterKey::from_bytes([3; 32]));
+    check_keystore(&keystore).await;
+
+    let stored = keystore
+        .inner()
+        .load_encrypted_key("0xb")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(is_sealed(&stored));
+    assert_ne!(stored, vec![1, 2, 3]);
+}
+
+fn nonce_record(wallet_address: &str, last_nonce: u64) -> WalletNonceRecord {
+    WalletNonceRecord {
+        wallet_address: wallet_address.to_owned(),
+        last_nonce,
+        updated_at_epoch_ms: 0,
+    }
+}
+
+#[tokio::test]
+async fn encrypted_rocksdb_seals_legacy_plaintext_once_then_rejects_it() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().to_str().unwrap();
+    {
+        let plain = RocksDbKeystore::open_default(path).unwrap();
+        plain
+            .save_wallet_nonce(&nonce_record("0xlegacy", 4))
+            .unwrap();
+    }
+    let keystore = RocksDbKeystore::open_default(path)
+        .unwrap()
+        .with_encryption(MasterKey::from_bytes([5; 32]))
+        .unwrap();
+    check_keystore(&keystore).await;
+
+    assert_eq!(
+        keystore
+            .load_wallet_nonce("0xlegacy")
+            .unwrap()
+            .map(|r| r.last_nonce),
+        Some(4)
+    );
+    keystore
+        .save_wallet_nonce(&nonce_record("0xnew", 9))
+        .unwrap();
+    assert_eq!(
+        keystore
+            .load_wallet_nonce("0xnew")
+            .unwrap()
+            .map(|r| r.last_nonce),
+        Some(9)
+    );
+    drop(keystore);
+
+    // On disk the legacy and new records are sealed. A plaintext record
+    // written behind the master key's back is refused, not trusted.
+    let raw = RocksDbKeystore::open_default(path).unwrap();
+    assert!(raw.load_wallet_nonce("0xnew").is_err());
+    assert!(raw.load_wallet_nonce("0xlegacy").is_err());
+    raw.save_wallet_nonce(&nonce_record("0xsneaked", 1)).unwrap();
+    drop(raw);
+    let keystore = RocksDbKeystore::open_default(path)
+        .unwrap()
+        .with_encryption(MasterKey::from_bytes([5; 32]))
+        .unwrap();
+    assert!(keystore.load_wallet_nonce("0xsneaked").is_err());
+    assert!(keystore.load_wallet_nonce("0xlegacy").unwrap().is_some());
+    drop(keystore);
+
+    // Another key cannot open the store.
+    assert!(
+        RocksDbKeystore::open_default(path)
+            .unwrap()
+            .with_encryption(MasterKey::from_bytes([6; 32]))
+            .is_err()
+    );
+}
+
+#[test]
+fn encrypted_rocksdb_submission_batch_writes_every_record() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap())
+        .unwrap()
+        .with_encryption(MasterKey::from_bytes([5; 32]))
+        .unwrap();
+    let tx = SubmittedTxRecord {
+        tx_hash: "tx-1".to_owned(),
+        status: "submitted".to_owned(),
+        accepted: true,
+        chain: "flowcortex-l1".to_owned(),
+        from: "0xfrom".to_owned(),
+        to: "0xto".to_owned(),
+        asset: "FloweR".to_owned(),
+        amount: "5".to_owned(),
+        submitted_at_epoch_ms: 0,
+        block_height: None,
+        confirmations: None,
+        status_history: Vec::new(),
+    };
+    let idempotency = SubmitIdempotencyRecord {
+        idempotency_key: "idem-1".to_owned(),
+        accepted: true,
+        tx_hash: "tx-1".to_owned(),
+        signature: "ab".to_owned(),
+        expires_at_epoch_ms: None,
+        created_at_epoch_ms: 0,
+    };
+    keystore
+        .save_submission_atomic(&tx, Some(&nonce_record("0xfrom", 3)), Some(&idempotency))
+        .unwrap();
+
+    assert!(keystore.load_submitted_tx("tx-1").unwrap().is_some());
+    assert_eq!(
+        keystore
+            .load_wallet_nonce("0xfrom")
+            .unwrap()
+            .map(|r| r.last_nonce),
+        Some(3)
+    );
+    assert_eq!(
+        keystore
+            .load_submit_idempotency("idem-1")
+            .unwrap()
+            .map(|r| r.tx_hash),
+        Some("tx-1".to_owned())
+    );
+}
+
+#[test]
+fn rocksdb_scans_stop_at_their_deadline() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    keystore
+        .save_submitted_tx(&SubmittedTxRecord {
+            tx_hash: "tx-1".to_owned(),
+            status: "submitted".to_owned(),
+            accepted: true,
+            chain: "flowcortex-l1".to_owned(),
+            from: "0xfrom".to_owned(),
+            to: "0xto".to_owned(),
+            asset: "FloweR".to_owned(),
+            amount: "5".to_owned(),
+            submitted_at_epoch_ms: 0,
+            block_height: None,
+            confirmations: None,
+            status_history: Vec::new(),
+        })
+        .unwrap();
+    let wallets = ["0xfrom".to_owned()];
+
+    let expired = keystore
+        .list_submitted_txs_from_within(Some(std::time::Instant::now()), &wallets, 10)
+        .unwrap_err();
+    assert!(expired.is::<DeadlineExceeded>());
+
+    let later = std::time::Instant::now() + std::time::Duration::from_secs(60);
+    assert_eq!(
+        keystore
+            .list_submitted_txs_from_within(Some(later), &wallets, 10)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn rocksdb_tx_history_pages_newest_first_per_wallet() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    for (index, (from, to)) in [("0xa", "0xb"), ("0xb", "0xa"), ("0xa", "0xc"), ("0xc", "0xb")]
+        .into_iter()
+        .enumerate()
+    {
+        keystore
+            .save_submitted_tx(&SubmittedTxRecord {
+                tx_hash: format!("tx-{index}"),
+                status: "submitted".to_owned(),
+                accepted: true,
+                chain: "flowcortex-l1".to_owned(),
+                from: from.to_owned(),
+                to: to.to_owned(),
+                asset: "FloweR".to_owned(),
+                amount: "5".to_owned(),
+                submitted_at_epoch_ms: index as u128 * 10,
+                block_height: None,
+                confirmations: None,
+                status_history: Vec::new(),
+            })
+            .unwrap();
+    }
+    // A status update must not duplicate the index entry.
+    let mut updated = keystore.load_submitted_tx("tx-2").unwrap().unwrap();
+    updated.status = "confirmed".to_owned();
+    keystore.save_submitted_tx(&updated).unwrap();
+
+    let first = keystore.list_submitted_txs("0xa", 2, None).unwrap();
+    let hashes: Vec<_> = first.records.iter().map(|r| r.tx_hash.as_str()).collect();
+    assert_eq!(hashes, ["tx-2", "tx-1"]);
+    assert_eq!(first.records[0].status, "confirmed");
+    let second = keystore
+        .list_submitted_txs("0xa", 2, first.next_cursor.as_deref())
+        .unwrap();
+    let hashes: Vec<_> = second.records.iter().map(|r| r.tx_hash.as_str()).collect();
+    assert_eq!(hashes, ["tx-0"]);
+    assert_eq!(second.next_cursor, None);
+
+    let exact = keystore.list_submitted_txs("0xc", 2, None).unwrap();
+    assert_eq!(exact.records.len(), 2);
+    assert_eq!(exact.next_cursor, None);
+    assert!(keystore.list_submitted_txs("0xd", 2, None).unwrap().records.is_empty());
+}
+
+#[tokio::test]
+async fn rocksdb_storage_stats_count_keys_per_prefix() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    keystore.save_encrypted_key("0xa", vec![1]).await.unwrap();
+    keystore.save_encrypted_key("0xb", vec![2]).await.unwrap();
+    keystore.save_wallet_label("0xa", "savings").unwrap();
+
+    let stats = keystore.storage_stats().unwrap();
+    assert!(stats.last_write_latency_micros.is_some());
+    assert!(stats.key_counts.is_empty());
+
+    let counted = keystore.storage_stats_with_key_counts(None).unwrap();
+    assert_eq!(counted.key_counts.get("wallet-key"), Some(&2));
+    assert_eq!(counted.key_counts.get("wallet-label"), Some(&1));
+    assert!(counted.key_counts.contains_key("meta"));
+
+    let expired = keystore
+        .storage_stats_with_key_counts(Some(std::time::Instant::now()))
+        .unwrap_err();
+    assert!(expired.is::<DeadlineExceeded>());
+}
+
+#[tokio::test]
+async fn rocksdb_audit_events_and_wallets_page_by_cursor() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    for (index, outcome) in ["success", "failure", "success", "success"].into_iter().enumerate() {
+        keystore
+            .append_audit_event(AuditEventRecord {
+                event_id: format!("event-{index}"),
+                event_type: "wallet_sign".to_owned(),
+                wallet_address: Some("0xa".to_owned()),
+                user_id: None,
+                chain: None,
+                outcome: outcome.to_owned(),
+                message: None,
+                timestamp_epoch_ms: 1_760_000_000_000 + index as u128,
+                tenant: None,
+            })
+            .unwrap();
+    }
+
+    let first = keystore
+        .list_audit_events_page(2, None, None, Some("0xa"), Some("success"))
+        .unwrap();
+    let ids: Vec<_> = first.events.iter().map(|event| event.event_id.as_str()).collect();
+    assert_eq!(ids, ["event-3", "event-2"]);
+    let second = keystore
+        .list_audit_events_page(2, first.next_cursor.as_deref(), None, Some("0xa"), Some("success"))
+        .unwrap();
+    let ids: Vec<_> = second.events.iter().map(|event| event.event_id.as_str()).collect();
+    assert_eq!(ids, ["event-0"]);
+    assert_eq!(second.next_cursor, None);
+
+    let unfiltered = keystore.list_audit_events_page(10, None, None, None, None).unwrap();
+    assert_eq!(unfiltered.events.len(), 4);
+    assert_eq!(unfiltered.events[0].event_id, "event-3");
+
+    for address in ["0xc", "0xa", "0xb"] {
+        keystore.save_encrypted_key(address, vec![1]).await.unwrap();
+    }
+    assert_eq!(keystore.list_wallet_addresses_after(None, 2).unwrap(), ["0xa", "0xb"]);
+    assert_eq!(keystore.list_wallet_addresses_after(Some("0xb"), 2).unwrap(), ["0xc"]);
+    assert!(keystore.list_wallet_addresses_after(Some("0xc"), 2).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn rocksdb_tombstoned_wallets_restore_until_purged() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    keystore.save_encrypted_key("0xa", vec![7]).await.unwrap();
+    keystore.save_wallet_label("0xa", "savings").unwrap();
+    keystore.save_device_wallet("device-1", "0xa").unwrap();
+    let binding = WalletBindingRecord {
+        wallet_address: "0xa".to_owned(),
+        user_id: "user-1".to_owned(),
+        chain: "flowcortex-l1".to_owned(),
+        last_verified_epoch_ms: 1,
+    };
+    keystore.save_wallet_binding(&binding).unwrap();
+
+    let deleted = keystore
+        .mark_wallet_deleted("0xa", "user request", "ops-1", 1_000, 500)
+        .unwrap()
+        .expect("wallet exists");
+    assert_eq!(deleted.tombstone.purge_after_epoch_ms, 1_500);
+    assert_eq!(deleted.tombstone.binding, Some(binding.clone()));
+    assert_eq!(deleted.audit_event.event_type, "wallet_delete");
+    assert!(!keystore.has_wallet("0xa").await.unwrap());
+    assert_eq!(keystore.load_wallet_binding("0xa").unwrap(), None);
+    assert_eq!(keystore.load_wallet_label("0xa").unwrap(), None);
+    assert!(keystore.list_device_wallets("device-1").unwrap().is_empty());
+    let audit = keystore.list_audit_events(10, Some("wallet_delete"), Some("0xa"), None).unwrap();
+    assert_eq!(audit.len(), 1);
+    assert!(keystore.mark_wallet_deleted("0xb", "", "ops-1", 1_000, 500).unwrap().is_none());
+
+    assert!(matches!(
+        keystore.undelete_wallet("0xa", "ops-1", 1_200).unwrap(),
+        WalletUndelete::Restored(_)
+    ));
+    assert_eq!(keystore.load_encrypted_key("0xa").await.unwrap(), Some(vec![7]));
+    assert_eq!(keystore.load_wallet_binding("0xa").unwrap(), Some(binding));
+    assert_eq!(keystore.load_wallet_label("0xa").unwrap().as_deref(), Some("savings"));
+    assert_eq!(keystore.list_device_wallets("device-1").unwrap(), ["0xa"]);
+    assert_eq!(keystore.undelete_wallet("0xa", "ops-1", 1_200).unwrap(), WalletUndelete::NotDeleted);
+
+    keystore.mark_wallet_deleted("0xa", "again", "ops-1", 2_000, 500).unwrap();
+    assert_eq!(keystore.purge_expired_tombstones(2_499).unwrap(), 0);
+    assert_eq!(keystore.purge_expired_tombstones(2_500).unwrap(), 1);
+    assert_eq!(keystore.purge_expired_tombstones(3_000).unwrap(), 0);
+    assert_eq!(keystore.undelete_wallet("0xa", "ops-1", 2_100).unwrap(), WalletUndelete::Expired);
+    let tombstone = keystore.load_wallet_tombstone("0xa").unwrap().expect("kept after purge");
+    assert_eq!(tombstone.purged_at_epoch_ms, Some(2_500));
+    let key_counts = keystore.storage_stats_with_key_counts(None).unwrap().key_counts;
+    assert_eq!(key_counts.get("wallet-tombstone-key"), None);
+}
+
+#[test]
+fn rocksdb_wallet_activity_updates_usage_in_metadata() {
+    let dir = TempDir::new().unwrap();
+    let keystore = RocksDbKeystore::open_default(dir.path().to_str().unwrap()).unwrap();
+    assert_eq!(keystore.record_wallet_activity("0xa", WalletActivity::Signed, 1).unwrap(), None);
+
+    keystore
+        .save_wallet_metadata(&WalletMetadataRecord {
+            wallet_address: "0xa".to_owned(),
+            label: Some("savings".to_owned()),
+            created_at_epoch_ms: Some(1),
+            key_scheme: "ed25519".to_owned(),
+            usage: WalletUsage::default(),
+        })
+        .unwrap();
+    keystore.record_wallet_activity("0xa", WalletActivity::Signed, 10).unwrap();
+    keystore.save_wallet_label("0xa", "renamed").unwrap();
+    keystore.record_wallet_activity("0xa", WalletActivity::SignedAndSubmitted, 20).unwrap();
+    let updated = keystore
+        .record_wallet_activity("0xa", WalletActivity::Submitted, 30)
+        .unwrap()
+        .expect("metadata exists");
+    let expected = WalletUsage {
+        signature_count: 2,
+        last_signed_at_epoch_ms: Some(20),
+        last_submitted_at_epoch_ms: Some(30),
+    };
+    assert_eq!(updated.usage, expected);
+    assert_eq!(expected.last_used_at_epoch_ms(), Some(30));
+    let loaded = keystore.load_wallet_metadata("0xa").unwrap().unwrap();
+    assert_eq!(loaded.usage, expected);
+    assert_eq!(loaded.label.as_deref(), Some("renamed"));
+}
+
+#[cfg(feature = "sled")]
+#[tokio::test]
+async fn sled_keystore_conforms() {
+    let dir = TempDir::new().unwrap();
+    check_keystore(&crate::SledKeystore::open(dir.path()).unwrap()).await;
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn sqlite_keystore_conforms() {
+    let dir = TempDir::new().unwrap();
+    check_keystore(&crate::SqliteKeystore::open(dir.path().join("keystore.db")).unwrap()).await;
+}
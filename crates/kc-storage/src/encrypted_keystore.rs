@@ -0,0 +1,65 @@
+//! [`Keystore`] decorator that seals every stored value with a master key.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use kc_crypto::aead::MasterKey;
+
+use crate::Keystore;
+
+/// Wraps any [`Keystore`] so values reach the inner store already sealed
+/// (XChaCha20-Poly1305, bound to the wallet address). Addresses stay in the
+/// clear so listing and lookups still work.
+///
+/// [`RocksDbKeystore`](crate::RocksDbKeystore) also stores bindings, nonces
+/// and audit events outside this trait; seal those with
+/// [`RocksDbKeystore::with_encryption`](crate::RocksDbKeystore::with_encryption).
+pub struct EncryptedKeystore<K: Keystore> {
+    inner: K,
+    master_key: MasterKey,
+}
+
+impl<K: Keystore> EncryptedKeystore<K> {
+    pub fn new(inner: K, master_key: MasterKey) -> Self {
+        Self { inner, master_key }
+    }
+
+    pub fn inner(&self) -> &K {
+        &self.inner
+    }
+
+    fn associated_data(wallet_address: &str) -> Vec<u8> {
+        format!("wallet-key:{wallet_address}").into_bytes()
+    }
+}
+
+#[async_trait]
+impl<K: Keystore> Keystore for EncryptedKeystore<K> {
+    async fn save_encrypted_key(&self, wallet_address: &str, encrypted_key: Vec<u8>) -> Result<()> {
+        let sealed = self
+            .master_key
+            .seal(&encrypted_key, &Self::associated_data(wallet_address))?;
+        self.inner.save_encrypted_key(wallet_address, sealed).await
+    }
+
+    async fn load_encrypted_key(&self, wallet_address: &str) -> Result<Option<Vec<u8>>> {
+        match self.inner.load_encrypted_key(wallet_address).await? {
+            Some(sealed) => Ok(Some(
+                self.master_key
+                    .open(&sealed, &Self::associated_data(wallet_address))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>> {
+        self.inner.list_wallet_addresses(offset, limit).await
+    }
+
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool> {
+        self.inner.delete_encrypted_key(wallet_address).await
+    }
+
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool> {
+        self.inner.has_wallet(wallet_address).await
+    }
+}
@@ -0,0 +1,128 @@
+//! Change notifications from [`RocksDbKeystore`](crate::RocksDbKeystore).
+//!
+//! Every successful write of a key, binding, tx status or audit event is
+//! published on a broadcast channel, so consumers such as webhooks or
+//! WebSocket push can react without polling RocksDB. Publishing never blocks
+//! a write: with no subscribers the event is dropped, and a subscriber that
+//! falls more than [`EVENT_CHANNEL_CAPACITY`] events behind receives
+//! `RecvError::Lagged` and skips ahead.
+
+use tokio::sync::broadcast;
+
+use crate::{AuditEventRecord, WalletBindingRecord};
+
+/// Events buffered per subscriber before the oldest are dropped.
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageEvent {
+    /// Encrypted key material was saved for a wallet (created, imported or
+    /// rotated).
+    KeySaved { wallet_address: String },
+    /// A wallet binding was created or replaced.
+    BindingUpdated(WalletBindingRecord),
+    /// A submitted transaction was recorded, or its status moved.
+    /// `previous_status` is `None` for a newly recorded transaction.
+    TxStatusChanged {
+        tx_hash: String,
+        from: String,
+        previous_status: Option<String>,
+        status: String,
+    },
+    /// An audit event was appended.
+    AuditAppended(AuditEventRecord),
+}
+
+pub(crate) fn channel() -> broadcast::Sender<StorageEvent> {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keystore, RocksDbKeystore, SubmittedTxRecord};
+    use tempfile::TempDir;
+
+    fn tx(status: &str) -> SubmittedTxRecord {
+        SubmittedTxRecord {
+            tx_hash: "0xtx".to_owned(),
+            from: "0xfrom".to_owned(),
+            to: "0xto".to_owned(),
+            amount: "1".to_owned(),
+            asset: "PROOF".to_owned(),
+            chain: "flowcortex-l1".to_owned(),
+            status: status.to_owned(),
+            accepted: true,
+            submitted_at_epoch_ms: 1,
+            block_height: None,
+            confirmations: None,
+            status_history: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn rocksdb_writes_publish_storage_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let keystore = RocksDbKeystore::open_default(temp_dir.path().to_str().unwrap()).unwrap();
+        let mut events = keystore.subscribe();
+
+        keystore.save_encrypted_key("0xa", vec![1]).await.unwrap();
+        assert_eq!(
+            events.recv().await.unwrap(),
+            StorageEvent::KeySaved {
+                wallet_address: "0xa".to_owned()
+            }
+        );
+
+        let binding = WalletBindingRecord {
+            wallet_address: "0xa".to_owned(),
+            user_id: "user-1".to_owned(),
+            chain: "flowcortex-l1".to_owned(),
+            last_verified_epoch_ms: 1,
+        };
+        keystore.save_wallet_binding(&binding).unwrap();
+        assert_eq!(
+            events.recv().await.unwrap(),
+            StorageEvent::BindingUpdated(binding)
+        );
+
+        keystore.save_submission_atomic(&tx("submitted"), None, None).unwrap();
+        keystore.save_submitted_tx(&tx("submitted")).unwrap();
+        keystore.save_submitted_tx(&tx("confirmed")).unwrap();
+        let status_changes = [
+            (None, "submitted"),
+            (Some("submitted"), "confirmed"),
+        ];
+        for (previous, status) in status_changes {
+            assert_eq!(
+                events.recv().await.unwrap(),
+                StorageEvent::TxStatusChanged {
+                    tx_hash: "0xtx".to_owned(),
+                    from: "0xfrom".to_owned(),
+                    previous_status: previous.map(str::to_owned),
+                    status: status.to_owned(),
+                },
+                "rewriting an unchanged status publishes nothing"
+            );
+        }
+
+        let event_id = keystore
+            .append_audit_event(AuditEventRecord {
+                event_id: String::new(),
+                event_type: "test".to_owned(),
+                wallet_address: None,
+                user_id: None,
+                chain: None,
+                outcome: "success".to_owned(),
+                message: None,
+                timestamp_epoch_ms: 2,
+                tenant: None,
+            })
+            .unwrap();
+        match events.recv().await.unwrap() {
+            StorageEvent::AuditAppended(record) => assert_eq!(record.event_id, event_id),
+            other => panic!("unexpected event {other:?}"),
+        }
+        assert!(events.try_recv().is_err());
+    }
+}
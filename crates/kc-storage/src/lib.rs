@@ -1,17 +1,63 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
-use rocksdb::{DB, IteratorMode, Options};
+use rocksdb::{DB, Direction, IteratorMode, Options, WriteBatch};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tokio::sync::{RwLock, broadcast};
+use kc_crypto::aead::{self, MasterKey};
 use uuid::Uuid;
 
+use tenants::KeySpace;
+
+mod approvals;
+mod challenges;
+#[cfg(test)]
+mod conformance;
+mod encrypted_keystore;
+mod events;
+mod migrations;
+mod outbox;
+mod policies;
+mod session_keys;
+pub mod shared_state;
+#[cfg(feature = "sled")]
+mod sled_keystore;
+#[cfg(feature = "sqlite")]
+mod sqlite_keystore;
+mod tenants;
+mod tombstones;
+mod tracked_txs;
+mod usage;
+
+pub use approvals::ApprovalRecord;
+pub use challenges::ChallengeRecord;
+pub use encrypted_keystore::EncryptedKeystore;
+pub use events::{EVENT_CHANNEL_CAPACITY, StorageEvent};
+pub use migrations::{LATEST_SCHEMA_VERSION, MIGRATIONS, Migration};
+pub use outbox::{OUTBOX_KIND_AUDIT, OUTBOX_KIND_TX_STATUS, OutboxEventRecord};
+pub use policies::{SpendingLimit, WalletPolicyRecord};
+pub use session_keys::SessionKeyRecord;
+pub use shared_state::{IdempotencyStore, InMemoryIdempotencyStore, InMemoryNonceStore, NonceStore};
+#[cfg(feature = "sled")]
+pub use sled_keystore::SledKeystore;
+#[cfg(feature = "sqlite")]
+pub use sqlite_keystore::SqliteKeystore;
+pub use tombstones::{WalletTombstoneChange, WalletTombstoneRecord, WalletUndelete};
+pub use tracked_txs::TrackedTxRecord;
+pub use usage::{WalletActivity, WalletUsage};
+
 #[async_trait]
 pub trait Keystore: Send + Sync {
     async fn save_encrypted_key(&self, wallet_address: &str, encrypted_key: Vec<u8>) -> Result<()>;
     async fn load_encrypted_key(&self, wallet_address: &str) -> Result<Option<Vec<u8>>>;
-    async fn list_wallet_addresses(&self) -> Result<Vec<String>>;
+    /// Wallets holding key material, sorted by address.
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>>;
+    /// Remove a wallet's key material. Returns `false` if there was none.
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool>;
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool>;
 }
 
 #[derive(Default)]
@@ -27,9 +73,17 @@ impl Keystore for NoopKeystore {
         Ok(None)
     }
 
-    async fn list_wallet_addresses(&self) -> Result<Vec<String>> {
+    async fn list_wallet_addresses(&self, _offset: usize, _limit: usize) -> Result<Vec<String>> {
         Ok(vec![])
     }
+
+    async fn delete_encrypted_key(&self, _wallet_address: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn has_wallet(&self, _wallet_address: &str) -> Result<bool> {
+        Ok(false)
+    }
 }
 
 #[derive(Default)]
@@ -50,17 +104,132 @@ impl Keystore for InMemoryKeystore {
         Ok(guard.get(wallet_address).cloned())
     }
 
-    async fn list_wallet_addresses(&self) -> Result<Vec<String>> {
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>> {
+        let guard = self.keys.read().await;
+        let mut addresses: Vec<String> = guard.keys().cloned().collect();
+        addresses.sort();
+        Ok(addresses.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool> {
+        let mut guard = self.keys.write().await;
+        Ok(guard.remove(wallet_address).is_some())
+    }
+
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool> {
         let guard = self.keys.read().await;
-        Ok(guard.keys().cloned().collect())
+        Ok(guard.contains_key(wallet_address))
+    }
+}
+
+/// A shared keystore, so one store can back several owners.
+#[async_trait]
+impl<K: Keystore + ?Sized> Keystore for Arc<K> {
+    async fn save_encrypted_key(&self, wallet_address: &str, encrypted_key: Vec<u8>) -> Result<()> {
+        (**self).save_encrypted_key(wallet_address, encrypted_key).await
+    }
+
+    async fn load_encrypted_key(&self, wallet_address: &str) -> Result<Option<Vec<u8>>> {
+        (**self).load_encrypted_key(wallet_address).await
+    }
+
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>> {
+        (**self).list_wallet_addresses(offset, limit).await
+    }
+
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool> {
+        (**self).delete_encrypted_key(wallet_address).await
+    }
+
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool> {
+        (**self).has_wallet(wallet_address).await
+    }
+}
+
+/// Runs before every RocksDB operation, e.g. to inject latency in soak tests.
+pub type AccessHook = Arc<dyn Fn() + Send + Sync>;
+
+/// Error (inside `anyhow::Error`) from a scan that ran past its caller's
+/// deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineExceeded;
+
+impl std::fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("storage scan exceeded the request deadline")
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+fn check_deadline(deadline: Option<Instant>) -> Result<()> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(DeadlineExceeded.into()),
+        _ => Ok(()),
     }
 }
 
+/// Point-in-time RocksDB figures for capacity planning.
+///
+/// The size and key estimates come from RocksDB properties and are cheap to
+/// read; `key_counts` needs a full key scan and is only filled by
+/// [`RocksDbKeystore::storage_stats_with_key_counts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageStats {
+    pub estimated_keys: Option<u64>,
+    pub estimated_live_data_bytes: Option<u64>,
+    pub total_sst_files_bytes: Option<u64>,
+    pub memtable_bytes: Option<u64>,
+    pub compaction_pending: bool,
+    pub pending_compaction_bytes: Option<u64>,
+    pub running_compactions: Option<u64>,
+    /// Duration of the most recent write or write batch; `None` until the
+    /// first write since the store was opened.
+    pub last_write_latency_micros: Option<u64>,
+    /// Keys per prefix (the part before the first `:`), e.g. `wallet-key`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub key_counts: BTreeMap<String, u64>,
+}
+
+/// Stored in [`RocksDbKeystore::last_write_micros`] before the first write.
+const NO_WRITE_YET: u64 = u64::MAX;
+
+/// Keys whose values may be stored unsealed: the schema version is read on
+/// open, before [`RocksDbKeystore::with_encryption`] applies the master key.
+const PLAINTEXT_KEY_PREFIX: &[u8] = b"meta:";
+
+/// Written once [`RocksDbKeystore::with_encryption`] has sealed the values
+/// of a store that was written without encryption.
+const VALUES_SEALED_KEY: &[u8] = b"meta:values-sealed";
+
 pub struct RocksDbKeystore {
     db: Arc<DB>,
+    access_hook: Option<AccessHook>,
+    master_key: Option<Arc<MasterKey>>,
+    events: broadcast::Sender<StorageEvent>,
+    last_write_micros: Arc<AtomicU64>,
+    /// Held while [`RocksDbKeystore::record_wallet_activity`] updates a
+    /// wallet's usage counters.
+    usage_lock: Arc<Mutex<()>>,
+    /// Held while a challenge is consumed or expired ones are removed.
+    challenge_lock: Arc<Mutex<()>>,
+    /// Held while [`RocksDbKeystore::claim_conditional_transfer`] moves a
+    /// conditional transfer out of a status.
+    escrow_lock: Arc<Mutex<()>>,
+    /// Held while [`RocksDbKeystore::rotate_wallet_key`] checks and moves a
+    /// wallet's active key generation.
+    rotation_lock: Arc<Mutex<()>>,
+    /// Set by [`RocksDbKeystore::with_event_outbox`].
+    outbox_enabled: bool,
+    outbox_sequence: Arc<AtomicU64>,
+    /// Set on views from [`RocksDbKeystore::for_tenant`].
+    tenant: Option<Arc<str>>,
+    /// Prepended to every key this view reads or writes; empty for the
+    /// default view.
+    key_prefix: Arc<[u8]>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WalletBindingRecord {
     pub wallet_address: String,
     pub user_id: String,
@@ -79,7 +248,7 @@ pub struct WalletIdentity {
     pub bank_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AuditEventRecord {
     pub event_id: String,
     pub event_type: String,
@@ -89,6 +258,35 @@ pub struct AuditEventRecord {
     pub outcome: String,
     pub message: Option<String>,
     pub timestamp_epoch_ms: u128,
+    /// Tenant whose keystore view recorded the event; see
+    /// [`RocksDbKeystore::for_tenant`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+impl AuditEventRecord {
+    /// Position of this event in the audit trail, for
+    /// [`RocksDbKeystore::list_audit_events_page`] cursors.
+    pub fn page_cursor(&self) -> String {
+        format!("{}:{}", self.timestamp_epoch_ms, self.event_id)
+    }
+}
+
+/// Split an [`AuditEventRecord::page_cursor`] into its timestamp and event id.
+pub fn parse_audit_cursor(cursor: &str) -> Option<(u128, &str)> {
+    let (timestamp, event_id) = cursor.split_once(':')?;
+    if event_id.is_empty() {
+        return None;
+    }
+    Some((timestamp.parse().ok()?, event_id))
+}
+
+/// One page of the audit trail, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventPage {
+    pub events: Vec<AuditEventRecord>,
+    /// Pass back as `cursor` for the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +295,8 @@ pub struct SubmitIdempotencyRecord {
     pub accepted: bool,
     pub tx_hash: String,
     pub signature: String,
+    #[serde(default)]
+    pub expires_at_epoch_ms: Option<u128>,
     pub created_at_epoch_ms: u128,
 }
 
@@ -118,6 +318,270 @@ pub struct SubmittedTxRecord {
     pub asset: String,
     pub amount: String,
     pub submitted_at_epoch_ms: u128,
+    /// Block that included the transaction, once the chain reports it.
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    #[serde(default)]
+    pub confirmations: Option<u64>,
+    /// Every status the transaction has been seen in, oldest first.
+    #[serde(default)]
+    pub status_history: Vec<TxStatusChange>,
+}
+
+impl SubmittedTxRecord {
+    /// Position of this record in a wallet's history, for
+    /// [`RocksDbKeystore::list_submitted_txs`] cursors.
+    pub fn history_cursor(&self) -> String {
+        format!("{:020}:{}", self.submitted_at_epoch_ms, self.tx_hash)
+    }
+}
+
+/// Split a [`SubmittedTxRecord::history_cursor`] into its submit time and tx hash.
+pub fn parse_history_cursor(cursor: &str) -> Option<(u128, &str)> {
+    let (submitted_at, tx_hash) = cursor.split_once(':')?;
+    if submitted_at.len() != 20 || tx_hash.is_empty() {
+        return None;
+    }
+    Some((submitted_at.parse().ok()?, tx_hash))
+}
+
+/// One page of a wallet's transaction history, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct SubmittedTxPage {
+    pub records: Vec<SubmittedTxRecord>,
+    /// Pass back as `cursor` for the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// One step of a submitted transaction's status timeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxStatusChange {
+    pub status: String,
+    pub at_epoch_ms: u128,
+}
+
+/// An address tracked for balance/history only; KeyCortex holds no key for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchOnlyWalletRecord {
+    pub wallet_address: String,
+    pub chain: String,
+    pub created_at_epoch_ms: u128,
+}
+
+/// A wallet whose secret key lives outside KeyCortex (hardware, client-side).
+/// Only the public key is stored; every transaction arrives pre-signed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalKeyRecord {
+    pub wallet_address: String,
+    pub chain: String,
+    pub key_type: String,
+    /// Hex-encoded public key.
+    pub public_key: String,
+    pub created_at_epoch_ms: u128,
+}
+
+/// A chain adapter configured at runtime via `/ops/chains`.
+///
+/// `kind` selects the adapter implementation (`flowcortex`, `evm`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainAdapterRecord {
+    pub chain_id: String,
+    pub kind: String,
+    pub endpoint: String,
+    /// Nodes to fail over to after `endpoint` (FlowCortex only).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_endpoints: Vec<String>,
+    pub enabled: bool,
+    /// EIP-155 chain id expected from `eth_chainId` (EVM only).
+    pub evm_chain_id: Option<u64>,
+    /// Explorer URL for a transaction, with `{tx_hash}` as the placeholder.
+    #[serde(default)]
+    pub explorer_tx_url: Option<String>,
+    /// Explorer URL for an account, with `{address}` as the placeholder.
+    #[serde(default)]
+    pub explorer_address_url: Option<String>,
+    /// Explorer URL for a block, with `{height}` as the placeholder.
+    #[serde(default)]
+    pub explorer_block_url: Option<String>,
+    /// ERC-20 contract address per asset symbol (EVM only).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub erc20_tokens: BTreeMap<String, String>,
+    /// Bech32 address prefix, e.g. `cosmos` or Bitcoin's `bc` (Cosmos and
+    /// Bitcoin only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bech32_prefix: Option<String>,
+    /// Bank denom per asset symbol, e.g. `ATOM` → `uatom` (Cosmos only).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub denoms: BTreeMap<String, String>,
+    pub updated_by: String,
+    pub updated_at_epoch_ms: u128,
+}
+
+/// A transfer held until a designated approver wallet signs its digest.
+///
+/// `status` moves `pending` → `releasing` → `released`, or `pending` →
+/// `cancelled` | `expired`. A release whose submit fails goes back to
+/// `pending`, or to `failed` when the submit timed out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalTransferRecord {
+    pub transfer_id: String,
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub chain: String,
+    pub approver_wallet: String,
+    pub digest: String,
+    pub created_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+    pub resolved_at_epoch_ms: Option<u128>,
+    pub tx_hash: Option<String>,
+}
+
+/// A transfer between chains, carried out as a debit into the bridge wallet
+/// on `source_chain` and, once that confirms, a credit out of the bridge
+/// wallet on `target_chain`.
+///
+/// `status` moves `pending` → `source_submitted` → `source_confirmed` →
+/// `target_submitted` → `completed`, or to `failed` from any of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeTransferRecord {
+    pub bridge_id: String,
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub asset: String,
+    pub source_chain: String,
+    pub target_chain: String,
+    /// Bridge wallet the source leg pays into.
+    pub source_bridge_wallet: String,
+    /// Bridge wallet the target leg pays out of.
+    pub target_bridge_wallet: String,
+    pub source_tx_hash: Option<String>,
+    pub target_tx_hash: Option<String>,
+    /// Why the transfer failed, or the last error submitting the target leg.
+    pub error: Option<String>,
+    pub created_at_epoch_ms: u128,
+    pub updated_at_epoch_ms: u128,
+    pub status_history: Vec<TxStatusChange>,
+}
+
+/// Delivery of a conditional transfer's approval request over one
+/// notification channel, keyed by transfer and channel name.
+///
+/// `status` moves `pending` → `delivered` | `failed`, or is `skipped` when
+/// the channel cannot reach the approver.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalNotificationRecord {
+    pub transfer_id: String,
+    pub channel: String,
+    /// `webhook`, `slack` or `smtp`.
+    pub kind: String,
+    /// Email address for `smtp`; webhook URLs are not recorded.
+    pub recipient: Option<String>,
+    pub status: String,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at_epoch_ms: u128,
+    pub updated_at_epoch_ms: u128,
+}
+
+/// Encrypted note attached to an accepted transfer, keyed by tx hash.
+///
+/// `ciphertext` is hex of a `kc_crypto::memo` sealed note; only the
+/// recipient wallet's key opens it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferMemoRecord {
+    pub tx_hash: String,
+    pub from: String,
+    pub to: String,
+    pub ciphertext: String,
+    pub created_at_epoch_ms: u128,
+}
+
+/// An authenticated session, keyed by the token's `jti`, or by the SHA-256
+/// of a token without one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub session_id: String,
+    pub user_id: String,
+    pub issued_at_epoch_ms: Option<u128>,
+    pub expires_at_epoch_ms: Option<u128>,
+    pub first_seen_epoch_ms: u128,
+    pub last_seen_epoch_ms: u128,
+    pub revoked: bool,
+    pub revoked_at_epoch_ms: Option<u128>,
+    pub revoked_by: Option<String>,
+}
+
+/// `key_scheme` of wallets whose Ed25519 key KeyCortex custodies.
+pub const KEY_SCHEME_ED25519: &str = "ed25519";
+/// `key_scheme` of watch-only wallets, which have no key.
+pub const KEY_SCHEME_WATCH_ONLY: &str = "watch-only";
+
+/// Descriptive data kept alongside a wallet's key record.
+///
+/// `label` is stored under its own `wallet-label:` key, so renames through
+/// `save_wallet_label` show up here too. `usage` is kept up to date by
+/// `record_wallet_activity`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletMetadataRecord {
+    pub wallet_address: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// `None` for wallets created before metadata was recorded.
+    #[serde(default)]
+    pub created_at_epoch_ms: Option<u128>,
+    /// [`KEY_SCHEME_ED25519`], [`KEY_SCHEME_WATCH_ONLY`], or an external
+    /// key's `key_type`.
+    pub key_scheme: String,
+    #[serde(default)]
+    pub usage: WalletUsage,
+}
+
+/// The part of [`WalletMetadataRecord`] stored under `wallet-meta:`.
+#[derive(Serialize, Deserialize)]
+struct StoredWalletMetadata {
+    created_at_epoch_ms: Option<u128>,
+    key_scheme: String,
+    #[serde(default)]
+    usage: WalletUsage,
+}
+
+/// One generation of a wallet's signing key.
+///
+/// The active generation's material is also what `load_encrypted_key` returns;
+/// the wallet address stays fixed across rotations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletKeyHistoryRecord {
+    pub wallet_address: String,
+    pub key_version: u32,
+    pub public_key: String,
+    pub encrypted_key: Vec<u8>,
+    pub created_at_epoch_ms: u128,
+    pub retired_at_epoch_ms: Option<u128>,
+    /// Hex signature by the previous key over the rotation linkage payload.
+    pub linkage_signature: Option<String>,
+}
+
+/// A browser/device registered by a user, identified by the UI-provided fingerprint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserDeviceRecord {
+    pub device_id: String,
+    pub user_id: String,
+    pub label: Option<String>,
+    pub first_seen_epoch_ms: u128,
+    pub last_seen_epoch_ms: u128,
+    pub trusted: bool,
+    pub approved_at_epoch_ms: Option<u128>,
+    /// `first-device`, `signature` or `ops:{operator}`.
+    pub approved_via: Option<String>,
+    pub revoked: bool,
+    /// Hex Ed25519 key the device proves itself with.
+    #[serde(default)]
+    pub public_key: Option<String>,
 }
 
 impl RocksDbKeystore {
@@ -125,7 +589,201 @@ impl RocksDbKeystore {
         let mut options = Options::default();
         options.create_if_missing(true);
         let db = DB::open(&options, path)?;
-        Ok(Self { db: Arc::new(db) })
+        let keystore = Self {
+            db: Arc::new(db),
+            access_hook: None,
+            master_key: None,
+            events: events::channel(),
+            last_write_micros: Arc::new(AtomicU64::new(NO_WRITE_YET)),
+            usage_lock: Arc::new(Mutex::new(())),
+            challenge_lock: Arc::new(Mutex::new(())),
+            escrow_lock: Arc::new(Mutex::new(())),
+            rotation_lock: Arc::new(Mutex::new(())),
+            outbox_enabled: false,
+            outbox_sequence: Arc::new(AtomicU64::new(0)),
+            tenant: None,
+            key_prefix: Arc::from(&b""[..]),
+        };
+        keystore.migrate_to_latest()?;
+        Ok(keystore)
+    }
+
+    pub fn with_access_hook(mut self, hook: AccessHook) -> Self {
+        self.access_hook = Some(hook);
+        self
+    }
+
+    /// Seal every value with `master_key`, bound to its RocksDB key. The
+    /// first time a store is opened with encryption, values written before
+    /// are sealed in place; from then on a value that is not sealed fails
+    /// to read instead of being trusted. Fails if the store was sealed
+    /// under another key.
+    pub fn with_encryption(mut self, master_key: MasterKey) -> Result<Self> {
+        self.master_key = Some(Arc::new(master_key));
+        if self.get(VALUES_SEALED_KEY)?.is_none() {
+            self.seal_plaintext_values()?;
+            self.put(VALUES_SEALED_KEY, b"1")?;
+        }
+        Ok(self)
+    }
+
+    /// Seal every value not yet sealed, except the `meta:` records read
+    /// before the master key is applied. Safe to rerun.
+    fn seal_plaintext_values(&self) -> Result<()> {
+        let Some(master_key) = &self.master_key else {
+            return Ok(());
+        };
+        let mut batch = WriteBatch::default();
+        for entry in self.db().iterator(IteratorMode::Start) {
+            let (key, value) = entry?;
+            if key.starts_with(PLAINTEXT_KEY_PREFIX) || aead::is_sealed(&value) {
+                continue;
+            }
+            batch.put(&key, master_key.seal(&value, &self.stored_key(&key))?);
+        }
+        self.write(batch)
+    }
+
+    /// Receive a [`StorageEvent`] for every write from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<StorageEvent> {
+        self.events.subscribe()
+    }
+
+    fn publish(&self, event: StorageEvent) {
+        // An error only means nobody is subscribed.
+        let _ = self.events.send(event);
+    }
+
+    /// The database as this view sees it; see [`tenants`].
+    fn db(&self) -> KeySpace<'_> {
+        if let Some(hook) = &self.access_hook {
+            hook();
+        }
+        KeySpace::new(&self.db, &self.key_prefix)
+    }
+
+    fn put(&self, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<()> {
+        let key = key.as_ref();
+        let sealed = match &self.master_key {
+            Some(master_key) => Some(master_key.seal(value.as_ref(), &self.stored_key(key))?),
+            None => None,
+        };
+        let started = Instant::now();
+        match sealed {
+            Some(sealed) => self.db().put(key, sealed)?,
+            None => self.db().put(key, value)?,
+        }
+        self.record_write_latency(started);
+        Ok(())
+    }
+
+    /// Commit `batch`, timing it like [`Self::put`].
+    fn write(&self, batch: WriteBatch) -> Result<()> {
+        let started = Instant::now();
+        self.db().write(batch)?;
+        self.record_write_latency(started);
+        Ok(())
+    }
+
+    fn record_write_latency(&self, started: Instant) {
+        let micros = u64::try_from(started.elapsed().as_micros()).unwrap_or(NO_WRITE_YET - 1);
+        self.last_write_micros.store(micros.min(NO_WRITE_YET - 1), Ordering::Relaxed);
+    }
+
+    /// RocksDB size, compaction and write-latency figures, without key counts.
+    pub fn storage_stats(&self) -> Result<StorageStats> {
+        let db = self.db();
+        let property = |name: &str| db.property_int_value(name);
+        let last_write = self.last_write_micros.load(Ordering::Relaxed);
+        Ok(StorageStats {
+            estimated_keys: property("rocksdb.estimate-num-keys")?,
+            estimated_live_data_bytes: property("rocksdb.estimate-live-data-size")?,
+            total_sst_files_bytes: property("rocksdb.total-sst-files-size")?,
+            memtable_bytes: property("rocksdb.cur-size-all-mem-tables")?,
+            compaction_pending: property("rocksdb.compaction-pending")?.unwrap_or(0) > 0,
+            pending_compaction_bytes: property("rocksdb.estimate-pending-compaction-bytes")?,
+            running_compactions: property("rocksdb.num-running-compactions")?,
+            last_write_latency_micros: (last_write != NO_WRITE_YET).then_some(last_write),
+            key_counts: BTreeMap::new(),
+        })
+    }
+
+    /// [`Self::storage_stats`] plus per-prefix key counts from a full key
+    /// scan. Counts are approximate while writes are in flight.
+    pub fn storage_stats_with_key_counts(&self, deadline: Option<Instant>) -> Result<StorageStats> {
+        let mut stats = self.storage_stats()?;
+        for entry in self.db().iterator(IteratorMode::Start) {
+            check_deadline(deadline)?;
+            let (key, _) = entry?;
+            let prefix = key.split(|byte| *byte == b':').next().unwrap_or(&key);
+            let prefix = String::from_utf8_lossy(prefix);
+            match stats.key_counts.get_mut(prefix.as_ref()) {
+                Some(count) => *count += 1,
+                None => {
+                    stats.key_counts.insert(prefix.into_owned(), 1);
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Visit every key and value as stored, in key order, for backups.
+    /// Values sealed under the storage master key stay sealed. A single
+    /// RocksDB iterator reads from an implicit snapshot, so the entries are
+    /// consistent with each other while writes go on. Returns the count.
+    pub fn for_each_raw_entry(&self, mut visit: impl FnMut(&[u8], &[u8]) -> Result<()>) -> Result<usize> {
+        let mut count = 0;
+        for entry in self.db().iterator(IteratorMode::Start) {
+            let (key, value) = entry?;
+            visit(&key, &value)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Write entries read by [`Self::for_each_raw_entry`] back unchanged,
+    /// e.g. into an empty store when restoring a backup. Sealed values only
+    /// open under the master key they were sealed with.
+    pub fn restore_raw_entries(&self, entries: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> Result<usize> {
+        let mut batch = WriteBatch::default();
+        let mut count = 0;
+        for (key, value) in entries {
+            batch.put(key, value);
+            count += 1;
+        }
+        self.write(batch)?;
+        Ok(count)
+    }
+
+    /// Stage a write in `batch`, sealed the same way as [`Self::put`].
+    fn batch_put(&self, batch: &mut WriteBatch, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<()> {
+        let key = key.as_ref();
+        match &self.master_key {
+            Some(master_key) => batch.put(key, master_key.seal(value.as_ref(), &self.stored_key(key))?),
+            None => batch.put(key, value),
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Vec<u8>>> {
+        let key = key.as_ref();
+        match self.db().get(key)? {
+            Some(value) => Ok(Some(self.decode(key, value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Open a value read straight from the database, e.g. by an iterator.
+    fn decode(&self, key: &[u8], value: impl Into<Vec<u8>>) -> Result<Vec<u8>> {
+        let value = value.into();
+        match &self.master_key {
+            Some(master_key) if aead::is_sealed(&value) => master_key.open(&value, &self.stored_key(key)),
+            Some(_) if !key.starts_with(PLAINTEXT_KEY_PREFIX) => Err(anyhow!(
+                "value under '{}' is not sealed under the storage master key",
+                String::from_utf8_lossy(key)
+            )),
+            Some(_) | None => Ok(value),
+        }
     }
 
     fn key_for_wallet(wallet_address: &str) -> String {
@@ -140,6 +798,10 @@ impl RocksDbKeystore {
         format!("audit:{timestamp_epoch_ms}:{event_id}")
     }
 
+    fn key_for_audit_by_wallet(wallet_address: &str, timestamp_epoch_ms: u128, event_id: &str) -> String {
+        format!("audit-by-wallet:{wallet_address}:{timestamp_epoch_ms}:{event_id}")
+    }
+
     fn key_for_idempotency(idempotency_key: &str) -> String {
         format!("idempotency:{idempotency_key}")
     }
@@ -152,10 +814,18 @@ impl RocksDbKeystore {
         format!("submitted-tx:{tx_hash}")
     }
 
+    fn key_for_tx_by_wallet(wallet_address: &str, cursor: &str) -> String {
+        format!("tx-by-wallet:{wallet_address}:{cursor}")
+    }
+
     fn key_for_wallet_label(wallet_address: &str) -> String {
         format!("wallet-label:{wallet_address}")
     }
 
+    fn key_for_wallet_metadata(wallet_address: &str) -> String {
+        format!("wallet-meta:{wallet_address}")
+    }
+
     fn key_for_device_wallet(device_id: &str, wallet_address: &str) -> String {
         format!("device-wallet:{device_id}:{wallet_address}")
     }
@@ -168,6 +838,58 @@ impl RocksDbKeystore {
         format!("wallet-device:{wallet_address}")
     }
 
+    fn key_for_wallet_honeytoken(wallet_address: &str) -> String {
+        format!("wallet-honeytoken:{wallet_address}")
+    }
+
+    fn key_for_watch_wallet(wallet_address: &str) -> String {
+        format!("wallet-watch:{wallet_address}")
+    }
+
+    fn key_for_external_key(wallet_address: &str) -> String {
+        format!("wallet-external-key:{wallet_address}")
+    }
+
+    fn key_for_chain_adapter(chain_id: &str) -> String {
+        format!("chain-adapter:{chain_id}")
+    }
+
+    fn key_for_conditional_transfer(transfer_id: &str) -> String {
+        format!("conditional-transfer:{transfer_id}")
+    }
+
+    fn key_for_bridge_transfer(bridge_id: &str) -> String {
+        format!("bridge-transfer:{bridge_id}")
+    }
+
+    fn key_for_approval_notification(transfer_id: &str, channel: &str) -> String {
+        format!("approval-notification:{transfer_id}:{channel}")
+    }
+
+    fn key_for_transfer_memo(tx_hash: &str) -> String {
+        format!("transfer-memo:{tx_hash}")
+    }
+
+    fn key_for_session(session_id: &str) -> String {
+        format!("session:{session_id}")
+    }
+
+    fn key_for_user_session(user_id: &str, session_id: &str) -> String {
+        format!("user-session:{user_id}:{session_id}")
+    }
+
+    fn key_for_wallet_key_history(wallet_address: &str, key_version: u32) -> String {
+        format!("wallet-key-history:{wallet_address}:{key_version:010}")
+    }
+
+    fn key_for_wallet_active_key(wallet_address: &str) -> String {
+        format!("wallet-active-key:{wallet_address}")
+    }
+
+    fn key_for_user_device(user_id: &str, device_id: &str) -> String {
+        format!("user-device:{user_id}:{device_id}")
+    }
+
     fn device_wallet_prefix(device_id: &str) -> String {
         format!("device-wallet:{device_id}:")
     }
@@ -189,24 +911,24 @@ impl RocksDbKeystore {
     /// Link a wallet to a device and record the reverse mapping.
     pub fn save_device_wallet(&self, device_id: &str, wallet_address: &str) -> Result<()> {
         let key = Self::key_for_device_wallet(device_id, wallet_address);
-        self.db.put(key.as_bytes(), b"1")?;
+        self.put(key.as_bytes(), b"1")?;
         // Reverse: wallet → device
         let rev = Self::key_for_wallet_device(wallet_address);
-        self.db.put(rev.as_bytes(), device_id.as_bytes())?;
+        self.put(rev.as_bytes(), device_id.as_bytes())?;
         Ok(())
     }
 
     /// Save contact info (email/phone) for a device.
     pub fn save_device_contact(&self, device_id: &str, contact: &str) -> Result<()> {
         let key = Self::key_for_device_contact(device_id);
-        self.db.put(key.as_bytes(), contact.as_bytes())?;
+        self.put(key.as_bytes(), contact.as_bytes())?;
         Ok(())
     }
 
     /// Load contact info for a device.
     pub fn load_device_contact(&self, device_id: &str) -> Result<Option<String>> {
         let key = Self::key_for_device_contact(device_id);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(String::from_utf8(raw)?)),
             None => Ok(None),
@@ -216,7 +938,7 @@ impl RocksDbKeystore {
     /// Load the device that owns a wallet.
     pub fn load_wallet_device(&self, wallet_address: &str) -> Result<Option<String>> {
         let key = Self::key_for_wallet_device(wallet_address);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(String::from_utf8(raw)?)),
             None => Ok(None),
@@ -226,9 +948,9 @@ impl RocksDbKeystore {
     /// Unlink a wallet from a device.
     pub fn remove_device_wallet(&self, device_id: &str, wallet_address: &str) -> Result<()> {
         let key = Self::key_for_device_wallet(device_id, wallet_address);
-        self.db.delete(key.as_bytes())?;
+        self.db().delete(key.as_bytes())?;
         let rev = Self::key_for_wallet_device(wallet_address);
-        self.db.delete(rev.as_bytes())?;
+        self.db().delete(rev.as_bytes())?;
         Ok(())
     }
 
@@ -237,7 +959,7 @@ impl RocksDbKeystore {
         let prefix = Self::device_wallet_prefix(device_id);
         let prefix_bytes = prefix.as_bytes();
         let mut addresses = Vec::new();
-        for entry in self.db.iterator(IteratorMode::Start) {
+        for entry in self.db().iterator(IteratorMode::Start) {
             let (key, _) = entry?;
             if key.as_ref().starts_with(prefix_bytes) {
                 if let Ok(k) = std::str::from_utf8(&key) {
@@ -256,9 +978,10 @@ impl RocksDbKeystore {
         let prefix = b"device-contact:";
         let contact_lower = contact.trim().to_lowercase();
         let mut device_ids = Vec::new();
-        for entry in self.db.iterator(IteratorMode::Start) {
+        for entry in self.db().iterator(IteratorMode::Start) {
             let (key, value) = entry?;
             if key.as_ref().starts_with(prefix) {
+                let value = self.decode(&key, value)?;
                 if let (Ok(k), Ok(v)) = (std::str::from_utf8(&key), std::str::from_utf8(&value)) {
                     if v.trim().to_lowercase() == contact_lower {
                         if let Some(did) = k.strip_prefix("device-contact:") {
@@ -279,38 +1002,38 @@ impl RocksDbKeystore {
         if let Ok(Some(old)) = self.load_wallet_identity(wallet_address) {
             if let Some(ref e) = old.email {
                 let k = Self::key_for_email_wallet(e, wallet_address);
-                let _ = self.db.delete(k.as_bytes());
+                let _ = self.db().delete(k.as_bytes());
             }
             if let Some(ref p) = old.phone {
                 let k = Self::key_for_phone_wallet(p, wallet_address);
-                let _ = self.db.delete(k.as_bytes());
+                let _ = self.db().delete(k.as_bytes());
             }
             if let Some(ref b) = old.bank_id {
                 let k = Self::key_for_bank_wallet(b, wallet_address);
-                let _ = self.db.delete(k.as_bytes());
+                let _ = self.db().delete(k.as_bytes());
             }
         }
         // Save identity blob
         let key = Self::key_for_wallet_identity(wallet_address);
         let value = serde_json::to_vec(identity)?;
-        self.db.put(key.as_bytes(), value)?;
+        self.put(key.as_bytes(), value)?;
         // Write reverse indices
         if let Some(ref e) = identity.email {
             if !e.trim().is_empty() {
                 let k = Self::key_for_email_wallet(e, wallet_address);
-                self.db.put(k.as_bytes(), b"1")?;
+                self.put(k.as_bytes(), b"1")?;
             }
         }
         if let Some(ref p) = identity.phone {
             if !p.trim().is_empty() {
                 let k = Self::key_for_phone_wallet(p, wallet_address);
-                self.db.put(k.as_bytes(), b"1")?;
+                self.put(k.as_bytes(), b"1")?;
             }
         }
         if let Some(ref b) = identity.bank_id {
             if !b.trim().is_empty() {
                 let k = Self::key_for_bank_wallet(b, wallet_address);
-                self.db.put(k.as_bytes(), b"1")?;
+                self.put(k.as_bytes(), b"1")?;
             }
         }
         Ok(())
@@ -319,7 +1042,7 @@ impl RocksDbKeystore {
     /// Load identity fields for a wallet.
     pub fn load_wallet_identity(&self, wallet_address: &str) -> Result<Option<WalletIdentity>> {
         let key = Self::key_for_wallet_identity(wallet_address);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(serde_json::from_slice::<WalletIdentity>(&raw)?)),
             None => Ok(None),
@@ -348,7 +1071,7 @@ impl RocksDbKeystore {
     fn scan_prefix_addresses(&self, prefix: &str) -> Result<Vec<String>> {
         let prefix_bytes = prefix.as_bytes();
         let mut addresses = Vec::new();
-        for entry in self.db.iterator(IteratorMode::Start) {
+        for entry in self.db().iterator(IteratorMode::Start) {
             let (key, _) = entry?;
             if key.as_ref().starts_with(prefix_bytes) {
                 if let Ok(k) = std::str::from_utf8(&key) {
@@ -365,62 +1088,719 @@ impl RocksDbKeystore {
 
     pub fn save_wallet_label(&self, wallet_address: &str, label: &str) -> Result<()> {
         let key = Self::key_for_wallet_label(wallet_address);
-        self.db.put(key.as_bytes(), label.as_bytes())?;
+        self.put(key.as_bytes(), label.as_bytes())?;
         Ok(())
     }
 
     pub fn load_wallet_label(&self, wallet_address: &str) -> Result<Option<String>> {
         let key = Self::key_for_wallet_label(wallet_address);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(String::from_utf8(raw)?)),
             None => Ok(None),
         }
     }
 
-    pub fn save_wallet_binding(&self, record: &WalletBindingRecord) -> Result<()> {
-        let key = Self::key_for_wallet_binding(&record.wallet_address);
-        let value = serde_json::to_vec(record)?;
-        self.db.put(key.as_bytes(), value)?;
+    /// `(wallet_address, label)` for every labelled wallet, sorted by address.
+    pub fn list_wallet_labels(&self) -> Result<Vec<(String, String)>> {
+        let mut labels = Vec::new();
+        for wallet_address in self.scan_prefix_addresses("wallet-label:")? {
+            if let Some(label) = self.load_wallet_label(&wallet_address)? {
+                labels.push((wallet_address, label));
+            }
+        }
+        Ok(labels)
+    }
+
+    /// Store a new wallet's key material together with its metadata and
+    /// label in one write batch.
+    pub fn save_encrypted_key_with_metadata(
+        &self,
+        encrypted_key: Vec<u8>,
+        metadata: &WalletMetadataRecord,
+    ) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        self.batch_put(&mut batch, Self::key_for_wallet(&metadata.wallet_address), encrypted_key)?;
+        self.batch_wallet_metadata(&mut batch, metadata)?;
+        self.write(batch)?;
+        self.publish(StorageEvent::KeySaved {
+            wallet_address: metadata.wallet_address.clone(),
+        });
         Ok(())
     }
 
-    pub fn load_wallet_binding(&self, wallet_address: &str) -> Result<Option<WalletBindingRecord>> {
-        let key = Self::key_for_wallet_binding(wallet_address);
-        let value = self.db.get(key.as_bytes())?;
-        match value {
-            Some(raw) => Ok(Some(serde_json::from_slice::<WalletBindingRecord>(&raw)?)),
-            None => Ok(None),
-        }
+    /// Save metadata and its label; a `None` label removes the stored one.
+    pub fn save_wallet_metadata(&self, metadata: &WalletMetadataRecord) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        self.batch_wallet_metadata(&mut batch, metadata)?;
+        self.write(batch)?;
+        Ok(())
     }
 
-    pub fn append_audit_event(&self, mut record: AuditEventRecord) -> Result<String> {
-        if record.event_id.trim().is_empty() {
-            record.event_id = Uuid::new_v4().to_string();
+    fn batch_wallet_metadata(&self, batch: &mut WriteBatch, metadata: &WalletMetadataRecord) -> Result<()> {
+        let stored = StoredWalletMetadata {
+            created_at_epoch_ms: metadata.created_at_epoch_ms,
+            key_scheme: metadata.key_scheme.clone(),
+            usage: metadata.usage,
+        };
+        self.batch_put(
+            batch,
+            Self::key_for_wallet_metadata(&metadata.wallet_address),
+            serde_json::to_vec(&stored)?,
+        )?;
+        let label_key = Self::key_for_wallet_label(&metadata.wallet_address);
+        match &metadata.label {
+            Some(label) => self.batch_put(batch, label_key, label)?,
+            None => batch.delete(label_key),
         }
-        let key = Self::key_for_audit_event(record.timestamp_epoch_ms, &record.event_id);
-        let value = serde_json::to_vec(&record)?;
-        self.db.put(key.as_bytes(), value)?;
-        Ok(record.event_id)
+        Ok(())
     }
 
-    pub fn list_audit_events(
-        &self,
-        limit: usize,
-        event_type: Option<&str>,
-        wallet_address: Option<&str>,
-        outcome: Option<&str>,
-    ) -> Result<Vec<AuditEventRecord>> {
-        let mut events = Vec::new();
+    pub fn load_wallet_metadata(&self, wallet_address: &str) -> Result<Option<WalletMetadataRecord>> {
+        let Some(raw) = self.get(Self::key_for_wallet_metadata(wallet_address))? else {
+            return Ok(None);
+        };
+        let stored: StoredWalletMetadata = serde_json::from_slice(&raw)?;
+        Ok(Some(WalletMetadataRecord {
+            wallet_address: wallet_address.to_owned(),
+            label: self.load_wallet_label(wallet_address)?,
+            created_at_epoch_ms: stored.created_at_epoch_ms,
+            key_scheme: stored.key_scheme,
+            usage: stored.usage,
+        }))
+    }
 
-        for entry in self.db.iterator(IteratorMode::Start) {
-            let (key, value) = entry?;
-            if !key.as_ref().starts_with(b"audit:") {
+    /// Metadata for every wallet that has it, sorted by address.
+    pub fn list_wallet_metadata(&self) -> Result<Vec<WalletMetadataRecord>> {
+        let mut records = Vec::new();
+        for wallet_address in self.scan_prefix_addresses("wallet-meta:")? {
+            records.extend(self.load_wallet_metadata(&wallet_address)?);
+        }
+        Ok(records)
+    }
+
+    /// Schema migration 2: metadata for wallets stored before it existed.
+    /// Creation times are kept where a watch-only or external-key record has
+    /// one and left unset otherwise.
+    fn backfill_wallet_metadata(&self) -> Result<()> {
+        let mut known: HashMap<String, (Option<u128>, String)> = HashMap::new();
+        for wallet_address in self.scan_prefix_addresses("wallet-key:")? {
+            known.insert(wallet_address, (None, KEY_SCHEME_ED25519.to_owned()));
+        }
+        for wallet_address in self.list_external_key_wallets()? {
+            if let Some(record) = self.load_external_key(&wallet_address)? {
+                known.insert(wallet_address, (Some(record.created_at_epoch_ms), record.key_type));
+            }
+        }
+        for wallet_address in self.list_watch_wallets()? {
+            if let Some(record) = self.load_watch_wallet(&wallet_address)? {
+                known
+                    .entry(wallet_address)
+                    .or_insert((Some(record.created_at_epoch_ms), KEY_SCHEME_WATCH_ONLY.to_owned()));
+            }
+        }
+        for (wallet_address, (created_at_epoch_ms, key_scheme)) in known {
+            if self.load_wallet_metadata(&wallet_address)?.is_some() {
                 continue;
             }
+            self.save_wallet_metadata(&WalletMetadataRecord {
+                label: self.load_wallet_label(&wallet_address)?,
+                wallet_address,
+                created_at_epoch_ms,
+                key_scheme,
+                usage: WalletUsage::default(),
+            })?;
+        }
+        Ok(())
+    }
 
-            let record = serde_json::from_slice::<AuditEventRecord>(&value)?;
+    /// Flag (or unflag) a wallet as a honeytoken tripwire.
+    pub fn set_wallet_honeytoken(&self, wallet_address: &str, honeytoken: bool) -> Result<()> {
+        let key = Self::key_for_wallet_honeytoken(wallet_address);
+        if honeytoken {
+            self.put(key.as_bytes(), b"1")?;
+        } else {
+            self.db().delete(key.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn is_wallet_honeytoken(&self, wallet_address: &str) -> Result<bool> {
+        let key = Self::key_for_wallet_honeytoken(wallet_address);
+        Ok(self.get(key.as_bytes())?.is_some())
+    }
+
+    pub fn list_honeytoken_wallets(&self) -> Result<Vec<String>> {
+        self.scan_prefix_addresses("wallet-honeytoken:")
+    }
+
+    pub fn save_watch_wallet(&self, record: &WatchOnlyWalletRecord) -> Result<()> {
+        let key = Self::key_for_watch_wallet(&record.wallet_address);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_watch_wallet(&self, wallet_address: &str) -> Result<Option<WatchOnlyWalletRecord>> {
+        let key = Self::key_for_watch_wallet(wallet_address);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn list_watch_wallets(&self) -> Result<Vec<String>> {
+        self.scan_prefix_addresses("wallet-watch:")
+    }
+
+    pub fn save_external_key(&self, record: &ExternalKeyRecord) -> Result<()> {
+        let key = Self::key_for_external_key(&record.wallet_address);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_external_key(&self, wallet_address: &str) -> Result<Option<ExternalKeyRecord>> {
+        let key = Self::key_for_external_key(wallet_address);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn list_external_key_wallets(&self) -> Result<Vec<String>> {
+        self.scan_prefix_addresses("wallet-external-key:")
+    }
+
+    pub fn save_chain_adapter(&self, record: &ChainAdapterRecord) -> Result<()> {
+        let key = Self::key_for_chain_adapter(&record.chain_id);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_chain_adapter(&self, chain_id: &str) -> Result<Option<ChainAdapterRecord>> {
+        let key = Self::key_for_chain_adapter(chain_id);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn list_chain_adapters(&self) -> Result<Vec<ChainAdapterRecord>> {
+        let mut records = Vec::new();
+        for chain_id in self.scan_prefix_addresses("chain-adapter:")? {
+            if let Some(record) = self.load_chain_adapter(&chain_id)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    pub fn save_conditional_transfer(&self, record: &ConditionalTransferRecord) -> Result<()> {
+        let key = Self::key_for_conditional_transfer(&record.transfer_id);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_conditional_transfer(&self, transfer_id: &str) -> Result<Option<ConditionalTransferRecord>> {
+        let key = Self::key_for_conditional_transfer(transfer_id);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<ConditionalTransferRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Apply `update` to a conditional transfer still in `from_status` and
+    /// save it, returning the updated record; `None` if the transfer is
+    /// missing or has already left `from_status`. Serialised by a lock, so
+    /// only one caller ever moves a transfer out of a given status.
+    pub fn claim_conditional_transfer(
+        &self,
+        transfer_id: &str,
+        from_status: &str,
+        update: impl FnOnce(&mut ConditionalTransferRecord),
+    ) -> Result<Option<ConditionalTransferRecord>> {
+        let _guard = self
+            .escrow_lock
+            .lock()
+            .map_err(|_| anyhow!("escrow lock poisoned"))?;
+        let Some(mut record) = self.load_conditional_transfer(transfer_id)? else {
+            return Ok(None);
+        };
+        if record.status != from_status {
+            return Ok(None);
+        }
+        update(&mut record);
+        self.save_conditional_transfer(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Every conditional transfer, ordered by transfer id.
+    pub fn list_conditional_transfers(&self) -> Result<Vec<ConditionalTransferRecord>> {
+        let mut records = Vec::new();
+        for transfer_id in self.scan_prefix_addresses("conditional-transfer:")? {
+            if let Some(record) = self.load_conditional_transfer(&transfer_id)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    pub fn save_bridge_transfer(&self, record: &BridgeTransferRecord) -> Result<()> {
+        let key = Self::key_for_bridge_transfer(&record.bridge_id);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_bridge_transfer(&self, bridge_id: &str) -> Result<Option<BridgeTransferRecord>> {
+        let key = Self::key_for_bridge_transfer(bridge_id);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<BridgeTransferRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_approval_notification(&self, record: &ApprovalNotificationRecord) -> Result<()> {
+        let key = Self::key_for_approval_notification(&record.transfer_id, &record.channel);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Approval notifications for one transfer, or for all transfers, ordered
+    /// by transfer and channel.
+    pub fn list_approval_notifications(
+        &self,
+        transfer_id: Option<&str>,
+    ) -> Result<Vec<ApprovalNotificationRecord>> {
+        let prefix = match transfer_id {
+            Some(transfer_id) => format!("approval-notification:{transfer_id}:"),
+            None => "approval-notification:".to_owned(),
+        };
+        let mut records = Vec::new();
+        for suffix in self.scan_prefix_addresses(&prefix)? {
+            if let Some(raw) = self.get(format!("{prefix}{suffix}").as_bytes())? {
+                records.push(serde_json::from_slice::<ApprovalNotificationRecord>(&raw)?);
+            }
+        }
+        Ok(records)
+    }
 
+    pub fn save_transfer_memo(&self, record: &TransferMemoRecord) -> Result<()> {
+        let key = Self::key_for_transfer_memo(&record.tx_hash);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_transfer_memo(&self, tx_hash: &str) -> Result<Option<TransferMemoRecord>> {
+        let key = Self::key_for_transfer_memo(tx_hash);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<TransferMemoRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_session(&self, record: &SessionRecord) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        self.batch_put(
+            &mut batch,
+            Self::key_for_session(&record.session_id),
+            serde_json::to_vec(record)?,
+        )?;
+        self.batch_put(
+            &mut batch,
+            Self::key_for_user_session(&record.user_id, &record.session_id),
+            b"1",
+        )?;
+        self.write(batch)
+    }
+
+    pub fn load_session(&self, session_id: &str) -> Result<Option<SessionRecord>> {
+        let key = Self::key_for_session(session_id);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<SessionRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List all sessions for a user, most recently seen first.
+    pub fn list_user_sessions(&self, user_id: &str) -> Result<Vec<SessionRecord>> {
+        let prefix = format!("user-session:{user_id}:");
+        let mut sessions = Vec::new();
+        for session_id in self.scan_prefix_addresses(&prefix)? {
+            if let Some(record) = self.load_session(&session_id)? {
+                sessions.push(record);
+            }
+        }
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.last_seen_epoch_ms));
+        Ok(sessions)
+    }
+
+    /// Delete sessions whose token expired before `now_epoch_ms`, revoked or
+    /// not: an expired token is refused anyway. Returns how many went.
+    pub fn prune_expired_sessions(&self, now_epoch_ms: u128) -> Result<usize> {
+        let mut batch = WriteBatch::default();
+        let mut pruned = 0;
+        for session_id in self.scan_prefix_addresses("session:")? {
+            let Some(record) = self.load_session(&session_id)? else {
+                continue;
+            };
+            if record.expires_at_epoch_ms.is_none_or(|expires| expires > now_epoch_ms) {
+                continue;
+            }
+            batch.delete(Self::key_for_session(&session_id));
+            batch.delete(Self::key_for_user_session(&record.user_id, &session_id));
+            pruned += 1;
+        }
+        self.write(batch)?;
+        Ok(pruned)
+    }
+
+    /// Mark a session revoked. Returns the updated record, or `None` if unknown.
+    pub fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_by: &str,
+        revoked_at_epoch_ms: u128,
+    ) -> Result<Option<SessionRecord>> {
+        let Some(mut record) = self.load_session(session_id)? else {
+            return Ok(None);
+        };
+        if !record.revoked {
+            record.revoked = true;
+            record.revoked_at_epoch_ms = Some(revoked_at_epoch_ms);
+            record.revoked_by = Some(revoked_by.to_owned());
+            self.save_session(&record)?;
+        }
+        Ok(Some(record))
+    }
+
+    pub fn save_wallet_key_history(&self, record: &WalletKeyHistoryRecord) -> Result<()> {
+        let key = Self::key_for_wallet_key_history(&record.wallet_address, record.key_version);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_wallet_key_history(
+        &self,
+        wallet_address: &str,
+        key_version: u32,
+    ) -> Result<Option<WalletKeyHistoryRecord>> {
+        let key = Self::key_for_wallet_key_history(wallet_address, key_version);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<WalletKeyHistoryRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List all key generations of a wallet, oldest first.
+    pub fn list_wallet_key_history(&self, wallet_address: &str) -> Result<Vec<WalletKeyHistoryRecord>> {
+        let prefix = format!("wallet-key-history:{wallet_address}:");
+        let mut records = Vec::new();
+        for version in self.scan_prefix_addresses(&prefix)? {
+            let Ok(version) = version.parse::<u32>() else {
+                continue;
+            };
+            if let Some(record) = self.load_wallet_key_history(wallet_address, version)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Point a wallet at the key generation currently used for signing.
+    pub fn set_active_key_version(&self, wallet_address: &str, key_version: u32) -> Result<()> {
+        let key = Self::key_for_wallet_active_key(wallet_address);
+        self.put(key.as_bytes(), key_version.to_string().as_bytes())?;
+        Ok(())
+    }
+
+    /// Retire the key generation in `retired` and make `active` the one the
+    /// wallet signs with: both history records, the wallet's key material
+    /// and its active version go in one write batch. Serialised by a lock;
+    /// `false`, with nothing written, if the wallet's active generation is
+    /// no longer `retired`'s because another rotation got there first.
+    pub fn rotate_wallet_key(
+        &self,
+        retired: &WalletKeyHistoryRecord,
+        active: &WalletKeyHistoryRecord,
+    ) -> Result<bool> {
+        let wallet_address = &active.wallet_address;
+        let _guard = self
+            .rotation_lock
+            .lock()
+            .map_err(|_| anyhow!("key rotation lock poisoned"))?;
+        if self.load_active_key_version(wallet_address)?.unwrap_or(1) != retired.key_version {
+            return Ok(false);
+        }
+        let mut batch = WriteBatch::default();
+        for record in [retired, active] {
+            self.batch_put(
+                &mut batch,
+                Self::key_for_wallet_key_history(&record.wallet_address, record.key_version),
+                serde_json::to_vec(record)?,
+            )?;
+        }
+        self.batch_put(&mut batch, Self::key_for_wallet(wallet_address), &active.encrypted_key)?;
+        self.batch_put(
+            &mut batch,
+            Self::key_for_wallet_active_key(wallet_address),
+            active.key_version.to_string(),
+        )?;
+        self.write(batch)?;
+        self.publish(StorageEvent::KeySaved {
+            wallet_address: wallet_address.clone(),
+        });
+        Ok(true)
+    }
+
+    /// `None` means the wallet has never been rotated (implicit version 1).
+    pub fn load_active_key_version(&self, wallet_address: &str) -> Result<Option<u32>> {
+        let key = Self::key_for_wallet_active_key(wallet_address);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(std::str::from_utf8(&raw)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_user_device(&self, record: &UserDeviceRecord) -> Result<()> {
+        let key = Self::key_for_user_device(&record.user_id, &record.device_id);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn load_user_device(&self, user_id: &str, device_id: &str) -> Result<Option<UserDeviceRecord>> {
+        let key = Self::key_for_user_device(user_id, device_id);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<UserDeviceRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List all devices registered by a user, most recently seen first.
+    pub fn list_user_devices(&self, user_id: &str) -> Result<Vec<UserDeviceRecord>> {
+        let prefix = format!("user-device:{user_id}:");
+        let mut devices = Vec::new();
+        for device_id in self.scan_prefix_addresses(&prefix)? {
+            if let Some(record) = self.load_user_device(user_id, &device_id)? {
+                devices.push(record);
+            }
+        }
+        devices.sort_by_key(|d| std::cmp::Reverse(d.last_seen_epoch_ms));
+        Ok(devices)
+    }
+
+    pub fn save_wallet_binding(&self, record: &WalletBindingRecord) -> Result<()> {
+        let key = Self::key_for_wallet_binding(&record.wallet_address);
+        let value = serde_json::to_vec(record)?;
+        self.put(key.as_bytes(), value)?;
+        self.publish(StorageEvent::BindingUpdated(record.clone()));
+        Ok(())
+    }
+
+    pub fn list_wallet_bindings(&self) -> Result<Vec<WalletBindingRecord>> {
+        let mut records = Vec::new();
+        for wallet_address in self.scan_prefix_addresses("wallet-binding:")? {
+            if let Some(record) = self.load_wallet_binding(&wallet_address)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    pub fn load_wallet_binding(&self, wallet_address: &str) -> Result<Option<WalletBindingRecord>> {
+        let key = Self::key_for_wallet_binding(wallet_address);
+        let value = self.get(key.as_bytes())?;
+        match value {
+            Some(raw) => Ok(Some(serde_json::from_slice::<WalletBindingRecord>(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn append_audit_event(&self, record: AuditEventRecord) -> Result<String> {
+        let mut batch = WriteBatch::default();
+        let record = self.batch_audit_event(&mut batch, record)?;
+        self.write(batch)?;
+        let event_id = record.event_id.clone();
+        self.publish(StorageEvent::AuditAppended(record));
+        Ok(event_id)
+    }
+
+    /// Stage an audit event and its wallet index entry, assigning an id if
+    /// it has none. The caller publishes the returned record once written.
+    fn batch_audit_event(&self, batch: &mut WriteBatch, mut record: AuditEventRecord) -> Result<AuditEventRecord> {
+        if record.event_id.trim().is_empty() {
+            record.event_id = Uuid::new_v4().to_string();
+        }
+        if let Some(tenant) = &self.tenant {
+            record.tenant = Some(tenant.to_string());
+        }
+        let key = Self::key_for_audit_event(record.timestamp_epoch_ms, &record.event_id);
+        self.batch_put(batch, &key, serde_json::to_vec(&record)?)?;
+        if let Some(wallet_address) = &record.wallet_address {
+            let idx = Self::key_for_audit_by_wallet(wallet_address, record.timestamp_epoch_ms, &record.event_id);
+            self.batch_put(batch, idx, &key)?;
+        }
+        self.batch_outbox_audit(batch, &record)?;
+        Ok(record)
+    }
+
+    /// Point `audit-by-wallet:{addr}:{ts}:{id}` at the event stored under `key`.
+    fn index_audit_event(&self, key: &str, record: &AuditEventRecord) -> Result<()> {
+        if let Some(wallet_address) = &record.wallet_address {
+            let idx = Self::key_for_audit_by_wallet(wallet_address, record.timestamp_epoch_ms, &record.event_id);
+            self.put(idx.as_bytes(), key.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Index audit events written before the wallet index existed. Schema
+    /// migration 1; stores that ran it before schema versioning carry the
+    /// marker and skip the rescan.
+    fn backfill_audit_wallet_index(&self) -> Result<()> {
+        const MARKER: &[u8] = b"meta:audit-by-wallet-index";
+        if self.get(MARKER)?.is_some() {
+            return Ok(());
+        }
+        for entry in self.db().iterator(IteratorMode::From(b"audit:", Direction::Forward)) {
+            let (key, value) = entry?;
+            if !key.starts_with(b"audit:") {
+                break;
+            }
+            let value = self.decode(&key, value)?;
+            let record = serde_json::from_slice::<AuditEventRecord>(&value)?;
+            self.index_audit_event(std::str::from_utf8(&key)?, &record)?;
+        }
+        self.put(MARKER, b"1")?;
+        Ok(())
+    }
+
+    fn audit_events_for_wallet(&self, wallet_address: &str) -> Result<Vec<AuditEventRecord>> {
+        let prefix = format!("audit-by-wallet:{wallet_address}:");
+        let mut events = Vec::new();
+        for entry in self.db().iterator(IteratorMode::From(prefix.as_bytes(), Direction::Forward)) {
+            let (key, event_key) = entry?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let event_key = self.decode(&key, event_key)?;
+            // Index entries for pruned events are removed with them; skip any stragglers.
+            if let Some(value) = self.get(&event_key)? {
+                events.push(serde_json::from_slice::<AuditEventRecord>(&value)?);
+            }
+        }
+        Ok(events)
+    }
+
+    pub fn list_audit_events(
+        &self,
+        limit: usize,
+        event_type: Option<&str>,
+        wallet_address: Option<&str>,
+        outcome: Option<&str>,
+    ) -> Result<Vec<AuditEventRecord>> {
+        self.list_audit_events_within(None, limit, event_type, wallet_address, outcome)
+    }
+
+    /// Audit events matching the filters, newest first, starting after
+    /// `cursor` (an [`AuditEventPage::next_cursor`]) when given. Unlike
+    /// [`Self::list_audit_events`] it reads no further than the page.
+    ///
+    /// Events come in key order, which is timestamp order for timestamps
+    /// with the same number of digits, as every epoch-ms time since 2001 has.
+    pub fn list_audit_events_page(
+        &self,
+        limit: usize,
+        cursor: Option<&str>,
+        event_type: Option<&str>,
+        wallet_address: Option<&str>,
+        outcome: Option<&str>,
+    ) -> Result<AuditEventPage> {
+        let prefix = match wallet_address {
+            Some(wallet_address) => format!("audit-by-wallet:{wallet_address}:"),
+            None => "audit:".to_owned(),
+        };
+        // `~` sorts after every cursor, so a reverse scan from it starts at the newest entry.
+        let start = format!("{prefix}{}", cursor.unwrap_or("~"));
+        let mut events: Vec<AuditEventRecord> = Vec::new();
+        let mut next_cursor = None;
+        for entry in self.db().iterator(IteratorMode::From(start.as_bytes(), Direction::Reverse)) {
+            let (key, value) = entry?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            if *key == *start.as_bytes() {
+                continue;
+            }
+            if events.len() == limit {
+                next_cursor = events.last().map(AuditEventRecord::page_cursor);
+                break;
+            }
+            let value = self.decode(&key, value)?;
+            let record = if wallet_address.is_some() {
+                // Index entries for pruned events are removed with them; skip any stragglers.
+                match self.get(&value)? {
+                    Some(event) => serde_json::from_slice::<AuditEventRecord>(&event)?,
+                    None => continue,
+                }
+            } else {
+                serde_json::from_slice::<AuditEventRecord>(&value)?
+            };
+            if event_type.is_some_and(|expected| record.event_type != expected)
+                || outcome.is_some_and(|expected| record.outcome != expected)
+            {
+                continue;
+            }
+            events.push(record);
+        }
+        Ok(AuditEventPage { events, next_cursor })
+    }
+
+    /// Like [`Self::list_audit_events`], failing with [`DeadlineExceeded`]
+    /// once `deadline` passes during a full scan.
+    pub fn list_audit_events_within(
+        &self,
+        deadline: Option<Instant>,
+        limit: usize,
+        event_type: Option<&str>,
+        wallet_address: Option<&str>,
+        outcome: Option<&str>,
+    ) -> Result<Vec<AuditEventRecord>> {
+        let candidates = match wallet_address {
+            Some(wallet_address) => self.audit_events_for_wallet(wallet_address)?,
+            None => {
+                let mut all = Vec::new();
+                for entry in self.db().iterator(IteratorMode::From(b"audit:", Direction::Forward)) {
+                    check_deadline(deadline)?;
+                    let (key, value) = entry?;
+                    if !key.starts_with(b"audit:") {
+                        break;
+                    }
+                    let value = self.decode(&key, value)?;
+                    all.push(serde_json::from_slice::<AuditEventRecord>(&value)?);
+                }
+                all
+            }
+        };
+
+        let mut events = Vec::new();
+        for record in candidates {
             if let Some(expected) = event_type {
                 if record.event_type != expected {
                     continue;
@@ -442,7 +1822,7 @@ impl RocksDbKeystore {
             events.push(record);
         }
 
-        events.sort_by(|a, b| b.timestamp_epoch_ms.cmp(&a.timestamp_epoch_ms));
+        events.sort_by_key(|e| std::cmp::Reverse(e.timestamp_epoch_ms));
         if events.len() > limit {
             events.truncate(limit);
         }
@@ -450,16 +1830,48 @@ impl RocksDbKeystore {
         Ok(events)
     }
 
+    /// Delete audit events recorded before `older_than_epoch_ms` and return
+    /// how many were removed.
+    pub fn prune_audit_events(&self, older_than_epoch_ms: u128) -> Result<usize> {
+        let mut pruned = 0;
+        for entry in self.db().iterator(IteratorMode::From(b"audit:", Direction::Forward)) {
+            let (key, value) = entry?;
+            let Some(rest) = key.strip_prefix(b"audit:".as_slice()) else {
+                break;
+            };
+            let timestamp = std::str::from_utf8(rest)
+                .ok()
+                .and_then(|rest| rest.split(':').next())
+                .and_then(|ts| ts.parse::<u128>().ok());
+            if timestamp.is_some_and(|ts| ts < older_than_epoch_ms) {
+                let value = self.decode(&key, value)?;
+                if let Ok(record) = serde_json::from_slice::<AuditEventRecord>(&value) {
+                    if let Some(wallet_address) = &record.wallet_address {
+                        let idx = Self::key_for_audit_by_wallet(
+                            wallet_address,
+                            record.timestamp_epoch_ms,
+                            &record.event_id,
+                        );
+                        self.db().delete(idx.as_bytes())?;
+                    }
+                }
+                self.db().delete(&key)?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+
     pub fn save_submit_idempotency(&self, record: &SubmitIdempotencyRecord) -> Result<()> {
         let key = Self::key_for_idempotency(&record.idempotency_key);
         let value = serde_json::to_vec(record)?;
-        self.db.put(key.as_bytes(), value)?;
+        self.put(key.as_bytes(), value)?;
         Ok(())
     }
 
     pub fn load_submit_idempotency(&self, idempotency_key: &str) -> Result<Option<SubmitIdempotencyRecord>> {
         let key = Self::key_for_idempotency(idempotency_key);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(serde_json::from_slice::<SubmitIdempotencyRecord>(&raw)?)),
             None => Ok(None),
@@ -468,7 +1880,7 @@ impl RocksDbKeystore {
 
     pub fn load_wallet_nonce(&self, wallet_address: &str) -> Result<Option<WalletNonceRecord>> {
         let key = Self::key_for_wallet_nonce(wallet_address);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(serde_json::from_slice::<WalletNonceRecord>(&raw)?)),
             None => Ok(None),
@@ -478,20 +1890,195 @@ impl RocksDbKeystore {
     pub fn save_wallet_nonce(&self, record: &WalletNonceRecord) -> Result<()> {
         let key = Self::key_for_wallet_nonce(&record.wallet_address);
         let value = serde_json::to_vec(record)?;
-        self.db.put(key.as_bytes(), value)?;
+        self.put(key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn delete_wallet_nonce(&self, wallet_address: &str) -> Result<()> {
+        let key = Self::key_for_wallet_nonce(wallet_address);
+        self.db().delete(key.as_bytes())?;
         Ok(())
     }
 
     pub fn save_submitted_tx(&self, record: &SubmittedTxRecord) -> Result<()> {
-        let key = Self::key_for_submitted_tx(&record.tx_hash);
-        let value = serde_json::to_vec(record)?;
-        self.db.put(key.as_bytes(), value)?;
+        let previous_status = self.load_submitted_tx(&record.tx_hash)?.map(|previous| previous.status);
+        let mut batch = WriteBatch::default();
+        self.batch_put(
+            &mut batch,
+            Self::key_for_submitted_tx(&record.tx_hash),
+            serde_json::to_vec(record)?,
+        )?;
+        self.batch_tx_index(&mut batch, record)?;
+        self.batch_outbox_tx_status(&mut batch, record, previous_status.as_deref())?;
+        self.write(batch)?;
+        self.publish_tx_status(record, previous_status);
+        Ok(())
+    }
+
+    /// Point `tx-by-wallet:{addr}:{cursor}` at the tx for its sender and
+    /// its recipient.
+    fn batch_tx_index(&self, batch: &mut WriteBatch, record: &SubmittedTxRecord) -> Result<()> {
+        let cursor = record.history_cursor();
+        self.batch_put(batch, Self::key_for_tx_by_wallet(&record.from, &cursor), &record.tx_hash)?;
+        if record.to != record.from {
+            self.batch_put(batch, Self::key_for_tx_by_wallet(&record.to, &cursor), &record.tx_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Index transactions saved before the wallet history index existed.
+    /// Schema migration 3.
+    fn backfill_tx_wallet_index(&self) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        for entry in self.db().iterator(IteratorMode::From(b"submitted-tx:", Direction::Forward)) {
+            let (key, value) = entry?;
+            if !key.starts_with(b"submitted-tx:") {
+                break;
+            }
+            let value = self.decode(&key, value)?;
+            self.batch_tx_index(&mut batch, &serde_json::from_slice::<SubmittedTxRecord>(&value)?)?;
+        }
+        self.write(batch)?;
+        Ok(())
+    }
+
+    /// Keystore wallet addresses in order, starting after `after` when given.
+    /// Unlike the `offset` of [`Keystore::list_wallet_addresses`], resuming
+    /// costs nothing however far into the keystore the page is.
+    pub fn list_wallet_addresses_after(&self, after: Option<&str>, limit: usize) -> Result<Vec<String>> {
+        let prefix = b"wallet-key:";
+        let start = Self::key_for_wallet(after.unwrap_or(""));
+        let mut addresses = Vec::new();
+        for entry in self.db().iterator(IteratorMode::From(start.as_bytes(), Direction::Forward)) {
+            let (key, _) = entry?;
+            if !key.starts_with(prefix) || addresses.len() >= limit {
+                break;
+            }
+            if after.is_some() && *key == *start.as_bytes() {
+                continue;
+            }
+            addresses.push(std::str::from_utf8(&key[prefix.len()..])?.to_owned());
+        }
+        Ok(addresses)
+    }
+
+    /// Transfers sent or received by `wallet_address`, newest first, starting
+    /// after `cursor` (a [`SubmittedTxPage::next_cursor`]) when given.
+    pub fn list_submitted_txs(
+        &self,
+        wallet_address: &str,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> Result<SubmittedTxPage> {
+        let prefix = format!("tx-by-wallet:{wallet_address}:");
+        // `~` sorts after every cursor, so a reverse scan from it starts at the newest entry.
+        let start = Self::key_for_tx_by_wallet(wallet_address, cursor.unwrap_or("~"));
+        let mut records = Vec::new();
+        let mut next_cursor = None;
+        for entry in self.db().iterator(IteratorMode::From(start.as_bytes(), Direction::Reverse)) {
+            let (key, tx_hash) = entry?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            if *key == *start.as_bytes() {
+                continue;
+            }
+            if records.len() == limit {
+                next_cursor = records.last().map(SubmittedTxRecord::history_cursor);
+                break;
+            }
+            let tx_hash = self.decode(&key, tx_hash)?;
+            if let Some(record) = self.load_submitted_tx(std::str::from_utf8(&tx_hash)?)? {
+                records.push(record);
+            }
+        }
+        Ok(SubmittedTxPage {
+            records,
+            next_cursor,
+        })
+    }
+
+    fn publish_tx_status(&self, record: &SubmittedTxRecord, previous_status: Option<String>) {
+        if previous_status.as_deref() != Some(record.status.as_str()) {
+            self.publish(StorageEvent::TxStatusChanged {
+                tx_hash: record.tx_hash.clone(),
+                from: record.from.clone(),
+                previous_status,
+                status: record.status.clone(),
+            });
+        }
+    }
+
+    /// Persist everything a successful submit produces in one RocksDB write
+    /// batch, so a crash cannot leave a tx record without its nonce or
+    /// idempotency record, or the other way round.
+    pub fn save_submission_atomic(
+        &self,
+        tx: &SubmittedTxRecord,
+        nonce: Option<&WalletNonceRecord>,
+        idempotency: Option<&SubmitIdempotencyRecord>,
+    ) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        self.batch_put(
+            &mut batch,
+            Self::key_for_submitted_tx(&tx.tx_hash),
+            serde_json::to_vec(tx)?,
+        )?;
+        self.batch_tx_index(&mut batch, tx)?;
+        if let Some(nonce) = nonce {
+            self.batch_put(
+                &mut batch,
+                Self::key_for_wallet_nonce(&nonce.wallet_address),
+                serde_json::to_vec(nonce)?,
+            )?;
+        }
+        if let Some(idempotency) = idempotency {
+            self.batch_put(
+                &mut batch,
+                Self::key_for_idempotency(&idempotency.idempotency_key),
+                serde_json::to_vec(idempotency)?,
+            )?;
+        }
+        self.batch_outbox_tx_status(&mut batch, tx, None)?;
+        self.write(batch)?;
+        self.publish_tx_status(tx, None);
         Ok(())
     }
 
+    /// Transfers sent from any of `wallet_addresses`, newest first.
+    pub fn list_submitted_txs_from(&self, wallet_addresses: &[String], limit: usize) -> Result<Vec<SubmittedTxRecord>> {
+        self.list_submitted_txs_from_within(None, wallet_addresses, limit)
+    }
+
+    /// Like [`Self::list_submitted_txs_from`], failing with
+    /// [`DeadlineExceeded`] once `deadline` passes.
+    pub fn list_submitted_txs_from_within(
+        &self,
+        deadline: Option<Instant>,
+        wallet_addresses: &[String],
+        limit: usize,
+    ) -> Result<Vec<SubmittedTxRecord>> {
+        let mut records = Vec::new();
+        for entry in self.db().iterator(IteratorMode::From(b"submitted-tx:", Direction::Forward)) {
+            check_deadline(deadline)?;
+            let (key, value) = entry?;
+            if !key.starts_with(b"submitted-tx:") {
+                break;
+            }
+            let value = self.decode(&key, value)?;
+            let record = serde_json::from_slice::<SubmittedTxRecord>(&value)?;
+            if wallet_addresses.contains(&record.from) {
+                records.push(record);
+            }
+        }
+        records.sort_by_key(|r| std::cmp::Reverse(r.submitted_at_epoch_ms));
+        records.truncate(limit);
+        Ok(records)
+    }
+
     pub fn load_submitted_tx(&self, tx_hash: &str) -> Result<Option<SubmittedTxRecord>> {
         let key = Self::key_for_submitted_tx(tx_hash);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         match value {
             Some(raw) => Ok(Some(serde_json::from_slice::<SubmittedTxRecord>(&raw)?)),
             None => Ok(None),
@@ -503,28 +2090,55 @@ impl RocksDbKeystore {
 impl Keystore for RocksDbKeystore {
     async fn save_encrypted_key(&self, wallet_address: &str, encrypted_key: Vec<u8>) -> Result<()> {
         let key = Self::key_for_wallet(wallet_address);
-        self.db.put(key.as_bytes(), encrypted_key)?;
+        self.put(key.as_bytes(), encrypted_key)?;
+        self.publish(StorageEvent::KeySaved {
+            wallet_address: wallet_address.to_owned(),
+        });
         Ok(())
     }
 
     async fn load_encrypted_key(&self, wallet_address: &str) -> Result<Option<Vec<u8>>> {
         let key = Self::key_for_wallet(wallet_address);
-        let value = self.db.get(key.as_bytes())?;
+        let value = self.get(key.as_bytes())?;
         Ok(value.map(|v| v.to_vec()))
     }
 
-    async fn list_wallet_addresses(&self) -> Result<Vec<String>> {
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>> {
         let prefix = b"wallet-key:";
         let mut addresses = Vec::new();
-        for entry in self.db.iterator(IteratorMode::Start) {
+        // Keys sort by address, so the prefix range is already in order.
+        for entry in self
+            .db()
+            .iterator(IteratorMode::From(prefix, Direction::Forward))
+            .skip(offset)
+        {
             let (key, _) = entry?;
-            if key.as_ref().starts_with(prefix) {
-                if let Ok(k) = std::str::from_utf8(&key) {
-                    addresses.push(k.strip_prefix("wallet-key:").unwrap_or(k).to_owned());
-                }
+            if !key.as_ref().starts_with(prefix) || addresses.len() >= limit {
+                break;
+            }
+            if let Ok(k) = std::str::from_utf8(&key) {
+                addresses.push(k.strip_prefix("wallet-key:").unwrap_or(k).to_owned());
             }
         }
-        addresses.sort();
         Ok(addresses)
     }
+
+    /// Also removes the wallet's metadata and label.
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool> {
+        let key = Self::key_for_wallet(wallet_address);
+        if self.db().get(key.as_bytes())?.is_none() {
+            return Ok(false);
+        }
+        let mut batch = WriteBatch::default();
+        batch.delete(key.as_bytes());
+        batch.delete(Self::key_for_wallet_metadata(wallet_address));
+        batch.delete(Self::key_for_wallet_label(wallet_address));
+        self.write(batch)?;
+        Ok(true)
+    }
+
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool> {
+        let key = Self::key_for_wallet(wallet_address);
+        Ok(self.get(key.as_bytes())?.is_some())
+    }
 }
@@ -0,0 +1,171 @@
+//! Schema versioning for [`RocksDbKeystore`] data.
+//!
+//! The store records its schema version under `meta:schema-version`. Each
+//! entry in [`MIGRATIONS`] upgrades the data by one version and is applied
+//! in order by [`RocksDbKeystore::migrate_to_latest`], which
+//! [`RocksDbKeystore::open_default`] runs on every open. A store without a
+//! version record is at version 0.
+//!
+//! To change a key format, append a migration with the next version number.
+//! Migrations must be idempotent: the version is recorded after a migration
+//! finishes, so a crash part way through reruns it on the next open.
+
+use anyhow::{Context, Result, bail};
+
+use crate::RocksDbKeystore;
+
+const SCHEMA_VERSION_KEY: &[u8] = b"meta:schema-version";
+
+/// One upgrade step, from `version - 1` to `version`.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    apply: fn(&RocksDbKeystore) -> Result<()>,
+}
+
+/// Every migration, in version order.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "audit-by-wallet index",
+        apply: RocksDbKeystore::backfill_audit_wallet_index,
+    },
+    Migration {
+        version: 2,
+        name: "wallet metadata",
+        apply: RocksDbKeystore::backfill_wallet_metadata,
+    },
+    Migration {
+        version: 3,
+        name: "tx-by-wallet index",
+        apply: RocksDbKeystore::backfill_tx_wallet_index,
+    },
+];
+
+/// The version a store is at once every migration has run.
+pub const LATEST_SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+impl RocksDbKeystore {
+    /// The schema version recorded in the store; 0 if none is recorded.
+    pub fn schema_version(&self) -> Result<u32> {
+        match self.get(SCHEMA_VERSION_KEY)? {
+            Some(raw) => serde_json::from_slice(&raw).context("schema version record"),
+            None => Ok(0),
+        }
+    }
+
+    /// Apply every migration newer than the recorded schema version.
+    /// Returns the versions applied, oldest first.
+    ///
+    /// Fails if the store was written by a newer build, rather than reading
+    /// data in a format this build does not know.
+    pub fn migrate_to_latest(&self) -> Result<Vec<u32>> {
+        let current = self.schema_version()?;
+        if current > LATEST_SCHEMA_VERSION {
+            bail!(
+                "keystore schema version {current} is newer than this build supports ({LATEST_SCHEMA_VERSION})"
+            );
+        }
+        let mut applied = Vec::new();
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            (migration.apply)(self).with_context(|| {
+                format!("schema migration {} ({})", migration.version, migration.name)
+            })?;
+            self.put(SCHEMA_VERSION_KEY, serde_json::to_vec(&migration.version)?)?;
+            applied.push(migration.version);
+        }
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuditEventRecord;
+    use tempfile::TempDir;
+
+    #[test]
+    fn migrations_are_numbered_consecutively() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1, "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn open_migrates_legacy_data_and_rejects_newer_schemas() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_str().unwrap();
+        {
+            let keystore = RocksDbKeystore::open_default(path).unwrap();
+            assert_eq!(keystore.schema_version().unwrap(), LATEST_SCHEMA_VERSION);
+            assert!(keystore.migrate_to_latest().unwrap().is_empty());
+
+            // Roll back to a version-0 store holding an unindexed audit event.
+            keystore
+                .put(
+                    RocksDbKeystore::key_for_audit_event(5, "legacy"),
+                    serde_json::to_vec(&AuditEventRecord {
+                        event_id: "legacy".to_owned(),
+                        event_type: "test".to_owned(),
+                        wallet_address: Some("0xold".to_owned()),
+                        user_id: None,
+                        chain: None,
+                        outcome: "success".to_owned(),
+                        message: None,
+                        timestamp_epoch_ms: 5,
+                        tenant: None,
+                    })
+                    .unwrap(),
+                )
+                .unwrap();
+            // ...and a wallet key without metadata.
+            keystore.put("wallet-key:0xold", b"sealed-key").unwrap();
+            keystore.save_wallet_label("0xold", "Legacy").unwrap();
+            // ...and a transaction without wallet history entries.
+            keystore
+                .put(
+                    "submitted-tx:tx-old",
+                    serde_json::to_vec(&crate::SubmittedTxRecord {
+                        tx_hash: "tx-old".to_owned(),
+                        status: "submitted".to_owned(),
+                        accepted: true,
+                        chain: "flowcortex-l1".to_owned(),
+                        from: "0xold".to_owned(),
+                        to: "0xnew".to_owned(),
+                        asset: "PROOF".to_owned(),
+                        amount: "1".to_owned(),
+                        submitted_at_epoch_ms: 5,
+                        block_height: None,
+                        confirmations: None,
+                        status_history: Vec::new(),
+                    })
+                    .unwrap(),
+                )
+                .unwrap();
+            keystore.db().delete(SCHEMA_VERSION_KEY).unwrap();
+            keystore.db().delete(b"meta:audit-by-wallet-index").unwrap();
+        }
+
+        let keystore = RocksDbKeystore::open_default(path).unwrap();
+        assert_eq!(keystore.schema_version().unwrap(), LATEST_SCHEMA_VERSION);
+        let events = keystore
+            .list_audit_events(10, None, Some("0xold"), None)
+            .unwrap();
+        assert_eq!(events.len(), 1, "migration 1 indexed the legacy event");
+        let metadata = keystore.load_wallet_metadata("0xold").unwrap().expect("migration 2 backfilled metadata");
+        assert_eq!(metadata.key_scheme, crate::KEY_SCHEME_ED25519);
+        assert_eq!(metadata.label.as_deref(), Some("Legacy"));
+        assert_eq!(metadata.created_at_epoch_ms, None);
+        for wallet in ["0xold", "0xnew"] {
+            let history = keystore.list_submitted_txs(wallet, 10, None).unwrap();
+            assert_eq!(history.records.len(), 1, "migration 3 indexed the legacy tx for {wallet}");
+        }
+
+        keystore
+            .put(SCHEMA_VERSION_KEY, serde_json::to_vec(&(LATEST_SCHEMA_VERSION + 1)).unwrap())
+            .unwrap();
+        drop(keystore);
+        let err = RocksDbKeystore::open_default(path).err().expect("newer schema is rejected");
+        assert!(format!("{err:#}").contains("newer than this build supports"));
+    }
+}
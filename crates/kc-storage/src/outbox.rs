@@ -0,0 +1,214 @@
+//! Transactional outbox for mirroring custody events to a message broker.
+//!
+//! With [`RocksDbKeystore::with_event_outbox`], every audit event and every
+//! tx status change also stages an [`OutboxEventRecord`] under
+//! `event-outbox:` in the same write batch, so an event is queued exactly
+//! when its write commits. A publisher drains the outbox in order with
+//! [`RocksDbKeystore::list_outbox_events`] and removes what the broker
+//! acknowledged with [`RocksDbKeystore::delete_outbox_events`]. An event
+//! that was published but not yet deleted when the process stopped is sent
+//! again, so delivery is at least once and consumers dedupe on `event_id`.
+
+use anyhow::Result;
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{AuditEventRecord, RocksDbKeystore, SubmittedTxRecord};
+
+const OUTBOX_PREFIX: &str = "event-outbox:";
+
+pub const OUTBOX_KIND_AUDIT: &str = "audit";
+pub const OUTBOX_KIND_TX_STATUS: &str = "tx_status";
+
+/// A custody event waiting to be published.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEventRecord {
+    /// `{epoch_ms}-{sequence}`, increasing in queue order.
+    pub event_id: String,
+    /// [`OUTBOX_KIND_AUDIT`] or [`OUTBOX_KIND_TX_STATUS`].
+    pub kind: String,
+    pub created_at_epoch_ms: u128,
+    /// The [`AuditEventRecord`], or the tx status change.
+    pub data: serde_json::Value,
+}
+
+impl RocksDbKeystore {
+    /// Stage an [`OutboxEventRecord`] alongside every audit event and tx
+    /// status change written from now on.
+    pub fn with_event_outbox(mut self) -> Self {
+        self.outbox_enabled = true;
+        self
+    }
+
+    fn key_for_outbox_event(event_id: &str) -> String {
+        format!("{OUTBOX_PREFIX}{event_id}")
+    }
+
+    fn batch_outbox_event(&self, batch: &mut WriteBatch, kind: &str, data: serde_json::Value) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let sequence = self.outbox_sequence.fetch_add(1, Ordering::Relaxed);
+        // Zero-padded so keys sort in queue order.
+        let event_id = format!("{now:020}-{sequence:010}");
+        let record = OutboxEventRecord {
+            event_id,
+            kind: kind.to_owned(),
+            created_at_epoch_ms: now,
+            data,
+        };
+        self.batch_put(
+            batch,
+            Self::key_for_outbox_event(&record.event_id),
+            serde_json::to_vec(&record)?,
+        )
+    }
+
+    pub(crate) fn batch_outbox_audit(&self, batch: &mut WriteBatch, record: &AuditEventRecord) -> Result<()> {
+        if !self.outbox_enabled {
+            return Ok(());
+        }
+        self.batch_outbox_event(batch, OUTBOX_KIND_AUDIT, serde_json::to_value(record)?)
+    }
+
+    /// Stage a tx status event when `record` is new or its status moved.
+    pub(crate) fn batch_outbox_tx_status(
+        &self,
+        batch: &mut WriteBatch,
+        record: &SubmittedTxRecord,
+        previous_status: Option<&str>,
+    ) -> Result<()> {
+        if !self.outbox_enabled || previous_status == Some(record.status.as_str()) {
+            return Ok(());
+        }
+        let data = serde_json::json!({
+            "tx_hash": record.tx_hash,
+            "chain": record.chain,
+            "from": record.from,
+            "to": record.to,
+            "asset": record.asset,
+            "amount": record.amount,
+            "previous_status": previous_status,
+            "status": record.status,
+            "block_height": record.block_height,
+        });
+        self.batch_outbox_event(batch, OUTBOX_KIND_TX_STATUS, data)
+    }
+
+    /// The oldest `limit` queued events, in queue order.
+    pub fn list_outbox_events(&self, limit: usize) -> Result<Vec<OutboxEventRecord>> {
+        let mut events = Vec::new();
+        for entry in self
+            .db()
+            .iterator(IteratorMode::From(OUTBOX_PREFIX.as_bytes(), Direction::Forward))
+        {
+            if events.len() >= limit {
+                break;
+            }
+            let (key, value) = entry?;
+            if !key.starts_with(OUTBOX_PREFIX.as_bytes()) {
+                break;
+            }
+            events.push(serde_json::from_slice(&self.decode(&key, value)?)?);
+        }
+        Ok(events)
+    }
+
+    pub fn count_outbox_events(&self) -> Result<usize> {
+        let mut count = 0;
+        for entry in self
+            .db()
+            .iterator(IteratorMode::From(OUTBOX_PREFIX.as_bytes(), Direction::Forward))
+        {
+            let (key, _) = entry?;
+            if !key.starts_with(OUTBOX_PREFIX.as_bytes()) {
+                break;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Remove published events from the outbox.
+    pub fn delete_outbox_events(&self, event_ids: &[String]) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        for event_id in event_ids {
+            batch.delete(Self::key_for_outbox_event(event_id));
+        }
+        self.write(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Keystore;
+    use tempfile::TempDir;
+
+    fn tx(status: &str) -> SubmittedTxRecord {
+        SubmittedTxRecord {
+            tx_hash: "0xtx".to_owned(),
+            from: "0xfrom".to_owned(),
+            to: "0xto".to_owned(),
+            amount: "1".to_owned(),
+            asset: "PROOF".to_owned(),
+            chain: "flowcortex-l1".to_owned(),
+            status: status.to_owned(),
+            accepted: true,
+            submitted_at_epoch_ms: 1,
+            block_height: None,
+            confirmations: None,
+            status_history: Vec::new(),
+        }
+    }
+
+    fn audit(event_type: &str) -> AuditEventRecord {
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: event_type.to_owned(),
+            wallet_address: Some("0xfrom".to_owned()),
+            user_id: None,
+            chain: None,
+            outcome: "success".to_owned(),
+            message: None,
+            timestamp_epoch_ms: 1,
+            tenant: None,
+        }
+    }
+
+    #[test]
+    fn stages_audit_and_status_events_in_order_when_enabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let keystore = RocksDbKeystore::open_default(temp_dir.path().to_str().unwrap())
+            .unwrap()
+            .with_event_outbox();
+
+        let event_id = keystore.append_audit_event(audit("wallet_sign")).unwrap();
+        keystore.save_submission_atomic(&tx("submitted"), None, None).unwrap();
+        keystore.save_submitted_tx(&tx("submitted")).unwrap();
+        keystore.save_submitted_tx(&tx("confirmed")).unwrap();
+
+        let events = keystore.list_outbox_events(10).unwrap();
+        let kinds: Vec<_> = events.iter().map(|event| event.kind.as_str()).collect();
+        assert_eq!(kinds, [OUTBOX_KIND_AUDIT, OUTBOX_KIND_TX_STATUS, OUTBOX_KIND_TX_STATUS]);
+        assert_eq!(events[0].data["event_id"], event_id.as_str());
+        assert_eq!(events[1].data["previous_status"], serde_json::Value::Null);
+        assert_eq!(events[2].data["previous_status"], "submitted");
+        assert_eq!(events[2].data["status"], "confirmed");
+        assert_eq!(keystore.list_outbox_events(1).unwrap(), events[..1]);
+
+        keystore.delete_outbox_events(&[events[0].event_id.clone()]).unwrap();
+        assert_eq!(keystore.count_outbox_events().unwrap(), 2);
+        assert_eq!(keystore.list_outbox_events(10).unwrap(), events[1..]);
+    }
+
+    #[tokio::test]
+    async fn stages_nothing_when_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let keystore = RocksDbKeystore::open_default(temp_dir.path().to_str().unwrap()).unwrap();
+        keystore.append_audit_event(audit("wallet_sign")).unwrap();
+        keystore.save_submitted_tx(&tx("submitted")).unwrap();
+        keystore.save_encrypted_key("0xfrom", vec![1]).await.unwrap();
+        assert_eq!(keystore.count_outbox_events().unwrap(), 0);
+    }
+}
@@ -0,0 +1,72 @@
+//! Per-wallet signing policy.
+//!
+//! A [`WalletPolicyRecord`] under `wallet-policy:` holds what KeyCortex
+//! checks before it signs with a wallet's key: spending limits per asset,
+//! destination allow and deny lists, and the purposes the key may sign for.
+//! A wallet without one signs without restriction.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::RocksDbKeystore;
+
+/// Limits on transfers of one asset, in base units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    /// Larger transfers are refused.
+    #[serde(default)]
+    pub max_amount: Option<u128>,
+    /// Larger transfers are held for approval before they are signed.
+    #[serde(default)]
+    pub approval_above: Option<u128>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletPolicyRecord {
+    pub wallet_address: String,
+    /// Keyed by asset symbol; assets without an entry are not limited.
+    #[serde(default)]
+    pub spending_limits: BTreeMap<String, SpendingLimit>,
+    /// When non-empty, the only wallets transfers may go to.
+    #[serde(default)]
+    pub allowed_destinations: BTreeSet<String>,
+    #[serde(default)]
+    pub denied_destinations: BTreeSet<String>,
+    /// When non-empty, the only purposes the key signs for, as
+    /// `SignPurpose::as_str` names them.
+    #[serde(default)]
+    pub allowed_purposes: BTreeSet<String>,
+    pub updated_by: String,
+    pub updated_at_epoch_ms: u128,
+}
+
+impl RocksDbKeystore {
+    fn key_for_wallet_policy(wallet_address: &str) -> String {
+        format!("wallet-policy:{wallet_address}")
+    }
+
+    pub fn save_wallet_policy(&self, record: &WalletPolicyRecord) -> Result<()> {
+        self.put(
+            Self::key_for_wallet_policy(&record.wallet_address),
+            serde_json::to_vec(record)?,
+        )
+    }
+
+    pub fn load_wallet_policy(&self, wallet_address: &str) -> Result<Option<WalletPolicyRecord>> {
+        let Some(raw) = self.get(Self::key_for_wallet_policy(wallet_address))? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&raw)?))
+    }
+
+    /// Remove a wallet's policy. Returns `false` if it had none.
+    pub fn delete_wallet_policy(&self, wallet_address: &str) -> Result<bool> {
+        let key = Self::key_for_wallet_policy(wallet_address);
+        if self.get(&key)?.is_none() {
+            return Ok(false);
+        }
+        self.db().delete(key.as_bytes())?;
+        Ok(true)
+    }
+}
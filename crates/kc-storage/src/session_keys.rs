@@ -0,0 +1,49 @@
+//! Session keys: short-lived keys a wallet delegates some of its signing to.
+//!
+//! A [`SessionKeyRecord`] under `session-key:{wallet}:{id}` keeps the
+//! session key, encrypted like a wallet key, with what the wallet key
+//! attested about it.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::RocksDbKeystore;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionKeyRecord {
+    pub session_key_id: String,
+    pub wallet_address: String,
+    /// Hex Ed25519 public key.
+    pub public_key: String,
+    pub encrypted_secret_key: Vec<u8>,
+    /// Sign purpose names, as `SignPurpose::as_str` gives them.
+    pub purposes: Vec<String>,
+    pub issued_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+    /// Hex signature of the wallet key over the attestation.
+    pub attestation_signature: String,
+}
+
+impl RocksDbKeystore {
+    fn key_for_session_key(wallet_address: &str, session_key_id: &str) -> String {
+        format!("session-key:{wallet_address}:{session_key_id}")
+    }
+
+    pub fn save_session_key(&self, record: &SessionKeyRecord) -> Result<()> {
+        self.put(
+            Self::key_for_session_key(&record.wallet_address, &record.session_key_id),
+            serde_json::to_vec(record)?,
+        )
+    }
+
+    pub fn load_session_key(
+        &self,
+        wallet_address: &str,
+        session_key_id: &str,
+    ) -> Result<Option<SessionKeyRecord>> {
+        let Some(raw) = self.get(Self::key_for_session_key(wallet_address, session_key_id))? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&raw)?))
+    }
+}
@@ -0,0 +1,187 @@
+//! Short-lived state that every wallet-service replica must agree on: the
+//! last nonce claimed per wallet, and `Idempotency-Key` responses. Auth
+//! challenges have a store of their own in `kc-auth-adapter`.
+//!
+//! The in-memory stores here suit a single replica. Replicas behind a load
+//! balancer share these through `kc-storage-redis` instead, so two replicas
+//! cannot both accept the same nonce. Every check-and-update is a single
+//! atomic operation on the store.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::SubmitIdempotencyRecord;
+
+#[async_trait]
+pub trait NonceStore: Send + Sync {
+    /// Last nonce claimed for the wallet, if this store has seen one.
+    async fn last(&self, wallet_address: &str) -> Result<Option<u64>>;
+    /// Claim `nonce` if it is above the wallet's last claimed nonce, or above
+    /// `persisted_last` when the store has none. Returns `false` on replay.
+    async fn claim(&self, wallet_address: &str, nonce: u64, persisted_last: Option<u64>) -> Result<bool>;
+    /// Drop the claim on `nonce` if it is still the wallet's latest.
+    async fn release(&self, wallet_address: &str, nonce: u64) -> Result<()>;
+    /// Replace the wallet's last claimed nonce with `chain_last`, the chain's
+    /// view, unless a claim above `settled` (the last persisted nonce) is in
+    /// flight. Returns whether it was replaced.
+    async fn reconcile(&self, wallet_address: &str, settled: Option<u64>, chain_last: Option<u64>) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    async fn get(&self, idempotency_key: &str) -> Result<Option<SubmitIdempotencyRecord>>;
+    async fn put(&self, record: &SubmitIdempotencyRecord) -> Result<()>;
+    /// Reserve `idempotency_key` for a submission in flight, for at most
+    /// `ttl`. Returns `false` while another request holds it.
+    async fn claim(&self, idempotency_key: &str, ttl: Duration) -> Result<bool>;
+    /// Drop the reservation once the submission has finished either way.
+    async fn release(&self, idempotency_key: &str) -> Result<()>;
+}
+
+#[derive(Default)]
+pub struct InMemoryNonceStore {
+    nonces: RwLock<HashMap<String, u64>>,
+}
+
+#[async_trait]
+impl NonceStore for InMemoryNonceStore {
+    async fn last(&self, wallet_address: &str) -> Result<Option<u64>> {
+        Ok(self.nonces.read().await.get(wallet_address).copied())
+    }
+
+    async fn claim(&self, wallet_address: &str, nonce: u64, persisted_last: Option<u64>) -> Result<bool> {
+        let mut nonces = self.nonces.write().await;
+        let last = nonces.get(wallet_address).copied().or(persisted_last);
+        if last.is_some_and(|last| nonce <= last) {
+            return Ok(false);
+        }
+        nonces.insert(wallet_address.to_owned(), nonce);
+        Ok(true)
+    }
+
+    async fn release(&self, wallet_address: &str, nonce: u64) -> Result<()> {
+        let mut nonces = self.nonces.write().await;
+        if nonces.get(wallet_address) == Some(&nonce) {
+            nonces.remove(wallet_address);
+        }
+        Ok(())
+    }
+
+    async fn reconcile(&self, wallet_address: &str, settled: Option<u64>, chain_last: Option<u64>) -> Result<bool> {
+        let mut nonces = self.nonces.write().await;
+        if let Some(&claimed) = nonces.get(wallet_address)
+            && settled.is_none_or(|settled| claimed > settled)
+        {
+            return Ok(false);
+        }
+        match chain_last {
+            Some(last) => nonces.insert(wallet_address.to_owned(), last),
+            None => nonces.remove(wallet_address),
+        };
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryIdempotencyStore {
+    records: RwLock<HashMap<String, SubmitIdempotencyRecord>>,
+    claims: RwLock<HashMap<String, Instant>>,
+}
+
+#[async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    async fn get(&self, idempotency_key: &str) -> Result<Option<SubmitIdempotencyRecord>> {
+        Ok(self.records.read().await.get(idempotency_key).cloned())
+    }
+
+    async fn put(&self, record: &SubmitIdempotencyRecord) -> Result<()> {
+        self.records
+            .write()
+            .await
+            .insert(record.idempotency_key.clone(), record.clone());
+        Ok(())
+    }
+
+    async fn claim(&self, idempotency_key: &str, ttl: Duration) -> Result<bool> {
+        let mut claims = self.claims.write().await;
+        let now = Instant::now();
+        if claims.get(idempotency_key).is_some_and(|expires_at| *expires_at > now) {
+            return Ok(false);
+        }
+        claims.insert(idempotency_key.to_owned(), now + ttl);
+        Ok(true)
+    }
+
+    async fn release(&self, idempotency_key: &str) -> Result<()> {
+        self.claims.write().await.remove(idempotency_key);
+        Ok(())
+    }
+}
+
+/// Behaviour every shared-state backend must share; run by each backend's
+/// tests against empty stores.
+#[doc(hidden)]
+pub mod conformance {
+    use super::*;
+
+    pub async fn check_nonce_store(store: &dyn NonceStore) {
+        assert_eq!(store.last("0xa").await.expect("last nonce should load"), None);
+        assert!(!store.claim("0xa", 3, Some(3)).await.expect("claim should run"), "persisted nonce is a floor");
+        assert!(store.claim("0xa", 4, Some(3)).await.expect("claim should run"));
+        assert!(!store.claim("0xa", 4, None).await.expect("claim should run"), "replay");
+        assert!(store.claim("0xa", 9, Some(3)).await.expect("claim should run"));
+        assert_eq!(store.last("0xa").await.expect("last nonce should load"), Some(9));
+
+        store.release("0xa", 4).await.expect("release should succeed");
+        assert_eq!(store.last("0xa").await.expect("last nonce should load"), Some(9), "only the latest claim is released");
+        store.release("0xa", 9).await.expect("release should succeed");
+        assert_eq!(store.last("0xa").await.expect("last nonce should load"), None);
+        assert!(store.claim("0xa", 9, Some(4)).await.expect("claim should run"), "a released nonce can be retried");
+
+        assert!(!store.reconcile("0xa", Some(4), Some(2)).await.expect("reconcile should run"), "claim 9 is in flight");
+        assert!(store.reconcile("0xa", Some(9), Some(2)).await.expect("reconcile should run"));
+        assert_eq!(store.last("0xa").await.expect("last nonce should load"), Some(2));
+        assert!(store.claim("0xa", 3, Some(9)).await.expect("claim should run"), "the chain's view replaces the floor");
+        assert!(store.reconcile("0xa", Some(3), None).await.expect("reconcile should run"));
+        assert_eq!(store.last("0xa").await.expect("last nonce should load"), None);
+        assert!(store.reconcile("0xb", None, Some(6)).await.expect("reconcile should run"));
+        assert_eq!(store.last("0xb").await.expect("last nonce should load"), Some(6));
+    }
+
+    pub async fn check_idempotency_store(store: &dyn IdempotencyStore) {
+        assert!(store.get("key-1").await.expect("record lookup should succeed").is_none());
+        let record = SubmitIdempotencyRecord {
+            idempotency_key: "key-1".to_owned(),
+            accepted: true,
+            tx_hash: "0xtx".to_owned(),
+            signature: "sig".to_owned(),
+            expires_at_epoch_ms: Some(9_000),
+            created_at_epoch_ms: 1_000,
+        };
+        store.put(&record).await.expect("record should store");
+        let loaded = store.get("key-1").await.expect("record lookup should succeed").expect("stored record");
+        assert_eq!(loaded.tx_hash, "0xtx");
+        assert_eq!(loaded.expires_at_epoch_ms, Some(9_000));
+
+        let ttl = Duration::from_secs(60);
+        assert!(store.claim("key-2", ttl).await.expect("claim should run"));
+        assert!(!store.claim("key-2", ttl).await.expect("claim should run"), "claimed while in flight");
+        store.release("key-2").await.expect("release should succeed");
+        assert!(store.claim("key-2", ttl).await.expect("claim should run"), "a released key can be claimed again");
+        store.release("key-2").await.expect("release should succeed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_shared_state_conforms() {
+        conformance::check_nonce_store(&InMemoryNonceStore::default()).await;
+        conformance::check_idempotency_store(&InMemoryIdempotencyStore::default()).await;
+    }
+}
@@ -0,0 +1,61 @@
+//! Sled-backed [`Keystore`] for lightweight and embedded deployments that
+//! should not need RocksDB's native toolchain. Enabled by the `sled` feature.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::Keystore;
+
+const WALLET_KEY_PREFIX: &str = "wallet-key:";
+
+pub struct SledKeystore {
+    db: sled::Db,
+}
+
+impl SledKeystore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            db: sled::open(path)?,
+        })
+    }
+
+    fn key_for_wallet(wallet_address: &str) -> String {
+        format!("{WALLET_KEY_PREFIX}{wallet_address}")
+    }
+}
+
+#[async_trait]
+impl Keystore for SledKeystore {
+    async fn save_encrypted_key(&self, wallet_address: &str, encrypted_key: Vec<u8>) -> Result<()> {
+        self.db
+            .insert(Self::key_for_wallet(wallet_address), encrypted_key)?;
+        self.db.flush_async().await?;
+        Ok(())
+    }
+
+    async fn load_encrypted_key(&self, wallet_address: &str) -> Result<Option<Vec<u8>>> {
+        let value = self.db.get(Self::key_for_wallet(wallet_address))?;
+        Ok(value.map(|v| v.to_vec()))
+    }
+
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>> {
+        let mut addresses = Vec::new();
+        for entry in self.db.scan_prefix(WALLET_KEY_PREFIX).skip(offset).take(limit) {
+            let (key, _) = entry?;
+            let key = std::str::from_utf8(&key)?;
+            addresses.push(key.strip_prefix(WALLET_KEY_PREFIX).unwrap_or(key).to_owned());
+        }
+        Ok(addresses)
+    }
+
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool> {
+        let removed = self.db.remove(Self::key_for_wallet(wallet_address))?;
+        self.db.flush_async().await?;
+        Ok(removed.is_some())
+    }
+
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool> {
+        Ok(self.db.contains_key(Self::key_for_wallet(wallet_address))?)
+    }
+}
@@ -0,0 +1,101 @@
+//! SQLite-backed [`Keystore`] for desktop embedding and dev setups, using a
+//! bundled SQLite so no system library is needed. Enabled by the `sqlite`
+//! feature.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::Keystore;
+
+pub struct SqliteKeystore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteKeystore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// A private in-memory database, for tests and throwaway setups.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS wallet_keys (
+                 wallet_address TEXT PRIMARY KEY,
+                 encrypted_key  BLOB NOT NULL
+             );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow!("sqlite connection lock poisoned"))
+    }
+}
+
+#[async_trait]
+impl Keystore for SqliteKeystore {
+    async fn save_encrypted_key(&self, wallet_address: &str, encrypted_key: Vec<u8>) -> Result<()> {
+        self.conn()?.execute(
+            "INSERT INTO wallet_keys (wallet_address, encrypted_key) VALUES (?1, ?2)
+             ON CONFLICT (wallet_address) DO UPDATE SET encrypted_key = excluded.encrypted_key",
+            params![wallet_address, encrypted_key],
+        )?;
+        Ok(())
+    }
+
+    async fn load_encrypted_key(&self, wallet_address: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .conn()?
+            .query_row(
+                "SELECT encrypted_key FROM wallet_keys WHERE wallet_address = ?1",
+                params![wallet_address],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    async fn list_wallet_addresses(&self, offset: usize, limit: usize) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT wallet_address FROM wallet_keys ORDER BY wallet_address LIMIT ?1 OFFSET ?2",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+        let addresses = stmt
+            .query_map(params![limit, offset], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(addresses)
+    }
+
+    async fn delete_encrypted_key(&self, wallet_address: &str) -> Result<bool> {
+        let deleted = self.conn()?.execute(
+            "DELETE FROM wallet_keys WHERE wallet_address = ?1",
+            params![wallet_address],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    async fn has_wallet(&self, wallet_address: &str) -> Result<bool> {
+        Ok(self
+            .conn()?
+            .query_row(
+                "SELECT 1 FROM wallet_keys WHERE wallet_address = ?1",
+                params![wallet_address],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+}
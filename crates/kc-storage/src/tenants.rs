@@ -0,0 +1,204 @@
+//! Tenant views of the keystore.
+//!
+//! [`RocksDbKeystore::for_tenant`] shares the database with the keystore it
+//! came from but reads and writes every key under `tenant:{id}:`, so each
+//! tenant sees only its own wallets, audit trail and transfers. No key of
+//! the default view starts with `tenant:`, so its prefix scans never reach
+//! a tenant's keys. Values sealed under the storage master key are bound to
+//! the full stored key, so they cannot be copied from one tenant to another.
+
+use anyhow::{Result, bail};
+use rocksdb::{DB, Direction, IteratorMode, WriteBatch, WriteBatchIterator};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::{RocksDbKeystore, events};
+
+const TENANT_KEY_PREFIX: &str = "tenant:";
+
+impl RocksDbKeystore {
+    /// A view of this keystore's database for `tenant`, which must be 1-64
+    /// characters of `a-z`, `0-9` and `-`. The view has its own change
+    /// events and schema version, and stamps `tenant` on every audit event
+    /// it appends.
+    pub fn for_tenant(&self, tenant: &str) -> Result<Self> {
+        if tenant.is_empty()
+            || tenant.len() > 64
+            || !tenant.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        {
+            bail!("tenant id must be 1-64 characters of a-z, 0-9 and -");
+        }
+        let view = Self {
+            db: Arc::clone(&self.db),
+            access_hook: self.access_hook.clone(),
+            master_key: self.master_key.clone(),
+            events: events::channel(),
+            last_write_micros: Arc::clone(&self.last_write_micros),
+            usage_lock: Arc::clone(&self.usage_lock),
+            challenge_lock: Arc::clone(&self.challenge_lock),
+            escrow_lock: Arc::clone(&self.escrow_lock),
+            rotation_lock: Arc::clone(&self.rotation_lock),
+            outbox_enabled: self.outbox_enabled,
+            outbox_sequence: Arc::clone(&self.outbox_sequence),
+            tenant: Some(Arc::from(tenant)),
+            key_prefix: Arc::from(format!("{TENANT_KEY_PREFIX}{tenant}:").into_bytes()),
+        };
+        view.migrate_to_latest()?;
+        Ok(view)
+    }
+
+    /// The tenant of a view from [`Self::for_tenant`].
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    /// `key` as stored in the database.
+    pub(crate) fn stored_key<'k>(&self, key: &'k [u8]) -> Cow<'k, [u8]> {
+        if self.key_prefix.is_empty() {
+            Cow::Borrowed(key)
+        } else {
+            Cow::Owned([&self.key_prefix[..], key].concat())
+        }
+    }
+}
+
+/// A stored entry, as read back from [`KeySpace::iterator`].
+type Entry = Result<(Box<[u8]>, Box<[u8]>), rocksdb::Error>;
+
+/// The database with every key under a prefix: keys passed in gain it,
+/// and keys read back lose it.
+pub(crate) struct KeySpace<'a> {
+    db: &'a DB,
+    prefix: &'a [u8],
+}
+
+impl<'a> KeySpace<'a> {
+    pub(crate) fn new(db: &'a DB, prefix: &'a [u8]) -> Self {
+        Self { db, prefix }
+    }
+
+    fn key<'k>(&self, key: &'k [u8]) -> Cow<'k, [u8]> {
+        if self.prefix.is_empty() {
+            Cow::Borrowed(key)
+        } else {
+            Cow::Owned([self.prefix, key].concat())
+        }
+    }
+
+    pub(crate) fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Vec<u8>>, rocksdb::Error> {
+        self.db.get(self.key(key.as_ref()))
+    }
+
+    pub(crate) fn put(&self, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<(), rocksdb::Error> {
+        self.db.put(self.key(key.as_ref()), value)
+    }
+
+    pub(crate) fn delete(&self, key: impl AsRef<[u8]>) -> Result<(), rocksdb::Error> {
+        self.db.delete(self.key(key.as_ref()))
+    }
+
+    /// Commit `batch`, whose keys are relative to the prefix.
+    pub(crate) fn write(&self, batch: WriteBatch) -> Result<(), rocksdb::Error> {
+        if self.prefix.is_empty() {
+            return self.db.write(batch);
+        }
+        let mut prefixed = Prefixed {
+            prefix: self.prefix,
+            batch: WriteBatch::default(),
+        };
+        batch.iterate(&mut prefixed);
+        self.db.write(prefixed.batch)
+    }
+
+    pub(crate) fn property_int_value(&self, name: &str) -> Result<Option<u64>, rocksdb::Error> {
+        self.db.property_int_value(name)
+    }
+
+    /// Entries under the prefix, from `mode`, with the prefix removed from
+    /// their keys.
+    pub(crate) fn iterator(
+        &self,
+        mode: IteratorMode,
+    ) -> impl Iterator<Item = Entry> + 'a {
+        let prefix = self.prefix;
+        let entries = if prefix.is_empty() {
+            self.db.iterator(mode)
+        } else {
+            let (start, direction) = match mode {
+                IteratorMode::Start => (prefix.to_vec(), Direction::Forward),
+                // Keys are text, so none under the prefix sorts after 0xff.
+                IteratorMode::End => ([prefix, &[0xff]].concat(), Direction::Reverse),
+                IteratorMode::From(key, direction) => ([prefix, key].concat(), direction),
+            };
+            self.db.iterator(IteratorMode::From(&start, direction))
+        };
+        entries
+            .take_while(move |entry| entry.as_ref().map_or(true, |(key, _)| key.starts_with(prefix)))
+            .map(move |entry| entry.map(|(key, value)| (key[prefix.len()..].into(), value)))
+    }
+}
+
+struct Prefixed<'a> {
+    prefix: &'a [u8],
+    batch: WriteBatch,
+}
+
+impl WriteBatchIterator for Prefixed<'_> {
+    fn put(&mut self, key: Box<[u8]>, value: Box<[u8]>) {
+        self.batch.put([self.prefix, &key].concat(), value);
+    }
+
+    fn delete(&mut self, key: Box<[u8]>) {
+        self.batch.delete([self.prefix, &key].concat());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuditEventRecord, Keystore, RocksDbKeystore};
+    use kc_crypto::aead::MasterKey;
+    use tempfile::TempDir;
+
+    fn audit_event(wallet_address: &str) -> AuditEventRecord {
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "wallet_create".to_owned(),
+            wallet_address: Some(wallet_address.to_owned()),
+            user_id: None,
+            chain: None,
+            outcome: "success".to_owned(),
+            message: None,
+            timestamp_epoch_ms: 1_000,
+            tenant: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn tenant_views_only_see_their_own_keys() {
+        let dir = TempDir::new().expect("temp dir should create");
+        let keystore = RocksDbKeystore::open_default(dir.path().to_str().expect("temp path should be utf-8"))
+            .expect("keystore should open")
+            .with_encryption(MasterKey::from_bytes([7; 32]))
+            .expect("an empty store should accept a master key");
+        let payments = keystore.for_tenant("payments").expect("payments is a valid tenant id");
+        let lending = keystore.for_tenant("lending").expect("lending is a valid tenant id");
+
+        keystore.save_encrypted_key("0xdefault", vec![1]).await.expect("default key should save");
+        payments.save_encrypted_key("0xpayments", vec![2]).await.expect("tenant key should save");
+        payments.append_audit_event(audit_event("0xpayments")).expect("audit event should append");
+
+        assert_eq!(keystore.list_wallet_addresses(0, 10).await.expect("wallets should list"), ["0xdefault"]);
+        assert_eq!(payments.list_wallet_addresses(0, 10).await.expect("wallets should list"), ["0xpayments"]);
+        assert!(lending.list_wallet_addresses(0, 10).await.expect("wallets should list").is_empty());
+        assert_eq!(payments.load_encrypted_key("0xpayments").await.expect("key lookup should succeed"), Some(vec![2]));
+        assert_eq!(lending.load_encrypted_key("0xpayments").await.expect("key lookup should succeed"), None);
+
+        let events = payments.list_audit_events(10, None, None, None).expect("audit events should list");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tenant.as_deref(), Some("payments"));
+        assert!(keystore.list_audit_events(10, None, None, None).expect("audit events should list").is_empty());
+
+        assert!(keystore.for_tenant("Payments").is_err());
+        assert!(keystore.for_tenant("").is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Soft deletion of custodied wallets.
+//!
+//! [`RocksDbKeystore::mark_wallet_deleted`] moves a wallet's key material
+//! from `wallet-key:` to `wallet-tombstone-key:` and records a
+//! [`WalletTombstoneRecord`] under `wallet-tombstone:`, holding the binding,
+//! metadata and device link it removed. Until the grace period ends,
+//! [`RocksDbKeystore::undelete_wallet`] puts all of it back.
+//! [`RocksDbKeystore::purge_expired_tombstones`] then destroys the key
+//! material, including rotated-out generations, but keeps the tombstone as
+//! a record of the deletion.
+
+use anyhow::Result;
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    AuditEventRecord, RocksDbKeystore, StorageEvent, WalletBindingRecord, WalletMetadataRecord,
+};
+
+/// A soft-deleted wallet and everything needed to restore it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletTombstoneRecord {
+    pub wallet_address: String,
+    pub reason: String,
+    pub actor: String,
+    pub deleted_at_epoch_ms: u128,
+    /// The wallet can be restored until this time.
+    pub purge_after_epoch_ms: u128,
+    /// Set once the key material has been destroyed.
+    #[serde(default)]
+    pub purged_at_epoch_ms: Option<u128>,
+    #[serde(default)]
+    pub binding: Option<WalletBindingRecord>,
+    #[serde(default)]
+    pub metadata: Option<WalletMetadataRecord>,
+    /// Kept apart from `metadata`, which wallets stored before metadata
+    /// was recorded may lack.
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+/// A deletion or restore, with the audit event written alongside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletTombstoneChange {
+    pub tombstone: WalletTombstoneRecord,
+    pub audit_event: AuditEventRecord,
+}
+
+/// Result of [`RocksDbKeystore::undelete_wallet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletUndelete {
+    Restored(Box<WalletTombstoneChange>),
+    /// No tombstone for the address.
+    NotDeleted,
+    /// The grace period has ended; the key material is or will be purged.
+    Expired,
+    /// A wallet with the same address was created since the deletion.
+    AddressInUse,
+}
+
+impl RocksDbKeystore {
+    fn key_for_wallet_tombstone(wallet_address: &str) -> String {
+        format!("wallet-tombstone:{wallet_address}")
+    }
+
+    fn key_for_tombstoned_key(wallet_address: &str) -> String {
+        format!("wallet-tombstone-key:{wallet_address}")
+    }
+
+    /// Tombstone a custodied wallet: its key, binding, metadata and device
+    /// link are removed and kept for [`Self::undelete_wallet`] until
+    /// `now_epoch_ms + grace_period_ms`. A `wallet_delete` audit event is
+    /// written in the same batch. `None` if the wallet has no key.
+    pub fn mark_wallet_deleted(
+        &self,
+        wallet_address: &str,
+        reason: &str,
+        actor: &str,
+        now_epoch_ms: u128,
+        grace_period_ms: u128,
+    ) -> Result<Option<WalletTombstoneChange>> {
+        let key = Self::key_for_wallet(wallet_address);
+        let Some(encrypted_key) = self.get(&key)? else {
+            return Ok(None);
+        };
+        let tombstone = WalletTombstoneRecord {
+            wallet_address: wallet_address.to_owned(),
+            reason: reason.to_owned(),
+            actor: actor.to_owned(),
+            deleted_at_epoch_ms: now_epoch_ms,
+            purge_after_epoch_ms: now_epoch_ms.saturating_add(grace_period_ms),
+            purged_at_epoch_ms: None,
+            binding: self.load_wallet_binding(wallet_address)?,
+            metadata: self.load_wallet_metadata(wallet_address)?,
+            label: self.load_wallet_label(wallet_address)?,
+            device_id: self.load_wallet_device(wallet_address)?,
+        };
+
+        let mut batch = WriteBatch::default();
+        batch.delete(&key);
+        self.batch_put(&mut batch, Self::key_for_tombstoned_key(wallet_address), encrypted_key)?;
+        batch.delete(Self::key_for_wallet_binding(wallet_address));
+        batch.delete(Self::key_for_wallet_metadata(wallet_address));
+        batch.delete(Self::key_for_wallet_label(wallet_address));
+        if let Some(device_id) = &tombstone.device_id {
+            batch.delete(Self::key_for_device_wallet(device_id, wallet_address));
+            batch.delete(Self::key_for_wallet_device(wallet_address));
+        }
+        self.batch_put(
+            &mut batch,
+            Self::key_for_wallet_tombstone(wallet_address),
+            serde_json::to_vec(&tombstone)?,
+        )?;
+        let audit_event = self.batch_audit_event(
+            &mut batch,
+            tombstone_audit_event("wallet_delete", &tombstone, actor, reason, now_epoch_ms),
+        )?;
+        self.write(batch)?;
+        self.publish(StorageEvent::AuditAppended(audit_event.clone()));
+
+        Ok(Some(WalletTombstoneChange {
+            tombstone,
+            audit_event,
+        }))
+    }
+
+    /// Restore a wallet tombstoned by [`Self::mark_wallet_deleted`] within
+    /// its grace period, writing a `wallet_undelete` audit event.
+    pub fn undelete_wallet(&self, wallet_address: &str, actor: &str, now_epoch_ms: u128) -> Result<WalletUndelete> {
+        let Some(tombstone) = self.load_wallet_tombstone(wallet_address)? else {
+            return Ok(WalletUndelete::NotDeleted);
+        };
+        if tombstone.purged_at_epoch_ms.is_some() || now_epoch_ms >= tombstone.purge_after_epoch_ms {
+            return Ok(WalletUndelete::Expired);
+        }
+        if self.get(Self::key_for_wallet(wallet_address))?.is_some() {
+            return Ok(WalletUndelete::AddressInUse);
+        }
+        let tombstoned_key = Self::key_for_tombstoned_key(wallet_address);
+        let Some(encrypted_key) = self.get(&tombstoned_key)? else {
+            return Ok(WalletUndelete::Expired);
+        };
+
+        let mut batch = WriteBatch::default();
+        self.batch_put(&mut batch, Self::key_for_wallet(wallet_address), encrypted_key)?;
+        batch.delete(&tombstoned_key);
+        batch.delete(Self::key_for_wallet_tombstone(wallet_address));
+        if let Some(binding) = &tombstone.binding {
+            self.batch_put(
+                &mut batch,
+                Self::key_for_wallet_binding(wallet_address),
+                serde_json::to_vec(binding)?,
+            )?;
+        }
+        if let Some(metadata) = &tombstone.metadata {
+            self.batch_wallet_metadata(&mut batch, metadata)?;
+        }
+        if let Some(label) = &tombstone.label {
+            self.batch_put(&mut batch, Self::key_for_wallet_label(wallet_address), label)?;
+        }
+        if let Some(device_id) = &tombstone.device_id {
+            self.batch_put(&mut batch, Self::key_for_device_wallet(device_id, wallet_address), b"1")?;
+            self.batch_put(&mut batch, Self::key_for_wallet_device(wallet_address), device_id)?;
+        }
+        let audit_event = self.batch_audit_event(
+            &mut batch,
+            tombstone_audit_event("wallet_undelete", &tombstone, actor, "restored", now_epoch_ms),
+        )?;
+        self.write(batch)?;
+        self.publish(StorageEvent::KeySaved {
+            wallet_address: wallet_address.to_owned(),
+        });
+        if let Some(binding) = &tombstone.binding {
+            self.publish(StorageEvent::BindingUpdated(binding.clone()));
+        }
+        self.publish(StorageEvent::AuditAppended(audit_event.clone()));
+
+        Ok(WalletUndelete::Restored(Box::new(WalletTombstoneChange {
+            tombstone,
+            audit_event,
+        })))
+    }
+
+    pub fn load_wallet_tombstone(&self, wallet_address: &str) -> Result<Option<WalletTombstoneRecord>> {
+        match self.get(Self::key_for_wallet_tombstone(wallet_address))? {
+            Some(raw) => Ok(Some(serde_json::from_slice(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Every tombstone, purged or not, by address.
+    pub fn list_wallet_tombstones(&self) -> Result<Vec<WalletTombstoneRecord>> {
+        let mut tombstones = Vec::new();
+        for wallet_address in self.scan_prefix_addresses("wallet-tombstone:")? {
+            tombstones.extend(self.load_wallet_tombstone(&wallet_address)?);
+        }
+        Ok(tombstones)
+    }
+
+    /// Destroy the key material of wallets whose grace period ended before
+    /// `now_epoch_ms`, including rotated-out key generations. Returns how
+    /// many wallets were purged.
+    pub fn purge_expired_tombstones(&self, now_epoch_ms: u128) -> Result<usize> {
+        let mut purged = 0;
+        for mut tombstone in self.list_wallet_tombstones()? {
+            if tombstone.purged_at_epoch_ms.is_some() || now_epoch_ms < tombstone.purge_after_epoch_ms {
+                continue;
+            }
+            let wallet_address = tombstone.wallet_address.clone();
+            let mut batch = WriteBatch::default();
+            batch.delete(Self::key_for_tombstoned_key(&wallet_address));
+            batch.delete(Self::key_for_wallet_active_key(&wallet_address));
+            let history_prefix = format!("wallet-key-history:{wallet_address}:");
+            for entry in self
+                .db()
+                .iterator(IteratorMode::From(history_prefix.as_bytes(), Direction::Forward))
+            {
+                let (key, _) = entry?;
+                if !key.starts_with(history_prefix.as_bytes()) {
+                    break;
+                }
+                batch.delete(key);
+            }
+            tombstone.purged_at_epoch_ms = Some(now_epoch_ms);
+            self.batch_put(
+                &mut batch,
+                Self::key_for_wallet_tombstone(&wallet_address),
+                serde_json::to_vec(&tombstone)?,
+            )?;
+            self.write(batch)?;
+            purged += 1;
+        }
+        Ok(purged)
+    }
+}
+
+fn tombstone_audit_event(
+    event_type: &str,
+    tombstone: &WalletTombstoneRecord,
+    actor: &str,
+    message: &str,
+    timestamp_epoch_ms: u128,
+) -> AuditEventRecord {
+    AuditEventRecord {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: event_type.to_owned(),
+        wallet_address: Some(tombstone.wallet_address.clone()),
+        user_id: Some(actor.to_owned()),
+        chain: None,
+        outcome: "success".to_owned(),
+        message: Some(message.to_owned()),
+        timestamp_epoch_ms,
+        tenant: None,
+    }
+}
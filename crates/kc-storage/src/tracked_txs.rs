@@ -0,0 +1,69 @@
+//! Submitted transfers still being watched until they settle.
+//!
+//! A [`TrackedTxRecord`] under `tracked-tx:{tx_hash}` keeps what is needed
+//! to query a submitted transfer's status again and to broadcast it a
+//! second time, alongside its [`crate::SubmittedTxRecord`]. It is removed
+//! once the transfer is confirmed, fails, or is given up on.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::RocksDbKeystore;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedTxRecord {
+    pub tx_hash: String,
+    pub chain: String,
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    /// Base units, as submitted.
+    pub amount: String,
+    /// Decimals of `asset` on `chain` when the transfer was submitted.
+    pub decimals: u8,
+    /// Hex signature the transfer was broadcast with.
+    pub signed_payload: String,
+    pub submitted_at_epoch_ms: u128,
+    /// Status queries made so far, which set the backoff before the next.
+    #[serde(default)]
+    pub checks: u32,
+    #[serde(default)]
+    pub resubmissions: u32,
+    pub next_check_at_epoch_ms: u128,
+}
+
+impl RocksDbKeystore {
+    fn key_for_tracked_tx(tx_hash: &str) -> String {
+        format!("tracked-tx:{tx_hash}")
+    }
+
+    pub fn save_tracked_tx(&self, record: &TrackedTxRecord) -> Result<()> {
+        self.put(
+            Self::key_for_tracked_tx(&record.tx_hash),
+            serde_json::to_vec(record)?,
+        )
+    }
+
+    pub fn load_tracked_tx(&self, tx_hash: &str) -> Result<Option<TrackedTxRecord>> {
+        let Some(raw) = self.get(Self::key_for_tracked_tx(tx_hash))? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&raw)?))
+    }
+
+    pub fn delete_tracked_tx(&self, tx_hash: &str) -> Result<()> {
+        self.db()
+            .delete(Self::key_for_tracked_tx(tx_hash).as_bytes())?;
+        Ok(())
+    }
+
+    pub fn list_tracked_txs(&self) -> Result<Vec<TrackedTxRecord>> {
+        let mut records = Vec::new();
+        for tx_hash in self.scan_prefix_addresses("tracked-tx:")? {
+            if let Some(record) = self.load_tracked_tx(&tx_hash)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
@@ -0,0 +1,82 @@
+//! Per-wallet signing and submission counters.
+//!
+//! [`RocksDbKeystore::record_wallet_activity`] updates the [`WalletUsage`]
+//! held in a wallet's metadata record whenever KeyCortex signs with the
+//! wallet's key or submits a transfer from it. Updates are serialised by a
+//! lock so concurrent signatures are all counted.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{RocksDbKeystore, StoredWalletMetadata, WalletMetadataRecord};
+
+/// How much a wallet has been used, kept in its [`WalletMetadataRecord`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletUsage {
+    /// Signatures KeyCortex has made with the wallet's key.
+    #[serde(default)]
+    pub signature_count: u64,
+    #[serde(default)]
+    pub last_signed_at_epoch_ms: Option<u128>,
+    /// Last transfer submitted from the wallet, signed here or elsewhere.
+    #[serde(default)]
+    pub last_submitted_at_epoch_ms: Option<u128>,
+}
+
+impl WalletUsage {
+    /// The later of the last signature and the last submission.
+    pub fn last_used_at_epoch_ms(&self) -> Option<u128> {
+        self.last_signed_at_epoch_ms.max(self.last_submitted_at_epoch_ms)
+    }
+}
+
+/// What [`RocksDbKeystore::record_wallet_activity`] counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletActivity {
+    /// A payload signed with the wallet's key.
+    Signed,
+    /// A transfer signed with the wallet's key and submitted.
+    SignedAndSubmitted,
+    /// A transfer signed outside KeyCortex and submitted.
+    Submitted,
+}
+
+impl RocksDbKeystore {
+    /// Count `activity` against the wallet and return its updated metadata.
+    /// Wallets without a metadata record, such as one deleted while it was
+    /// signing, are left alone and give `None`.
+    pub fn record_wallet_activity(
+        &self,
+        wallet_address: &str,
+        activity: WalletActivity,
+        now_epoch_ms: u128,
+    ) -> Result<Option<WalletMetadataRecord>> {
+        let _guard = self
+            .usage_lock
+            .lock()
+            .map_err(|_| anyhow!("wallet usage lock poisoned"))?;
+        let Some(mut metadata) = self.load_wallet_metadata(wallet_address)? else {
+            return Ok(None);
+        };
+        let usage = &mut metadata.usage;
+        if matches!(activity, WalletActivity::Signed | WalletActivity::SignedAndSubmitted) {
+            usage.signature_count = usage.signature_count.saturating_add(1);
+            usage.last_signed_at_epoch_ms = Some(now_epoch_ms);
+        }
+        if matches!(activity, WalletActivity::SignedAndSubmitted | WalletActivity::Submitted) {
+            usage.last_submitted_at_epoch_ms = Some(now_epoch_ms);
+        }
+        // Only the `wallet-meta:` entry: rewriting the label could undo a
+        // rename that raced this update.
+        let stored = StoredWalletMetadata {
+            created_at_epoch_ms: metadata.created_at_epoch_ms,
+            key_scheme: metadata.key_scheme.clone(),
+            usage: metadata.usage,
+        };
+        self.put(
+            Self::key_for_wallet_metadata(wallet_address),
+            serde_json::to_vec(&stored)?,
+        )?;
+        Ok(Some(metadata))
+    }
+}
@@ -0,0 +1,276 @@
+//! The two-person rule for transfers a signing policy holds.
+//!
+//! A transfer a [`crate::SigningPolicy`] answers with
+//! [`crate::PolicyDecision::RequireApproval`] is held in an [`ApprovalQueue`]
+//! instead of being signed. It goes ahead only once a principal other than
+//! the one who submitted it approves it; [`ApprovalQueue::decide`] checks
+//! that, and lets each held transfer be decided, and so signed, once. A
+//! transfer whose submitter is unknown can never be decided.
+
+use anyhow::Result;
+use kc_api_types::WalletSubmitRequest;
+use kc_storage::{ApprovalRecord, RocksDbKeystore};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Where held transfers are kept.
+pub trait ApprovalStore: Send + Sync {
+    fn save(&self, record: &ApprovalRecord) -> Result<()>;
+    fn load(&self, approval_id: &str) -> Result<Option<ApprovalRecord>>;
+    fn list(&self) -> Result<Vec<ApprovalRecord>>;
+}
+
+impl ApprovalStore for RocksDbKeystore {
+    fn save(&self, record: &ApprovalRecord) -> Result<()> {
+        self.save_approval(record)
+    }
+
+    fn load(&self, approval_id: &str) -> Result<Option<ApprovalRecord>> {
+        self.load_approval(approval_id)
+    }
+
+    fn list(&self) -> Result<Vec<ApprovalRecord>> {
+        self.list_approvals()
+    }
+}
+
+/// Why [`ApprovalQueue::decide`] refused a decision.
+#[derive(Debug)]
+pub enum ApprovalError {
+    NotFound,
+    /// The transfer is no longer pending; its status is given.
+    AlreadyDecided(String),
+    /// The principal deciding is the one who submitted the transfer.
+    SelfApproval,
+    /// Nobody is recorded as having submitted the transfer, so there is no
+    /// way to tell a second principal from the first.
+    UnknownRequester,
+    Storage(anyhow::Error),
+}
+
+impl fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("approval not found"),
+            Self::AlreadyDecided(status) => write!(f, "approval is already {status}"),
+            Self::SelfApproval => {
+                f.write_str("a transfer must be approved by someone other than its submitter")
+            }
+            Self::UnknownRequester => {
+                f.write_str("the transfer's submitter is unknown, so it cannot be decided")
+            }
+            Self::Storage(err) => write!(f, "{err:#}"),
+        }
+    }
+}
+
+impl std::error::Error for ApprovalError {}
+
+impl From<anyhow::Error> for ApprovalError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::Storage(err)
+    }
+}
+
+/// Transfers held for approval, and the decisions made on them.
+pub struct ApprovalQueue {
+    store: Arc<dyn ApprovalStore>,
+    /// Held while a decision is checked and saved, so two principals
+    /// deciding at once cannot both approve.
+    deciding: Mutex<()>,
+}
+
+impl ApprovalQueue {
+    pub fn new(store: Arc<dyn ApprovalStore>) -> Self {
+        Self {
+            store,
+            deciding: Mutex::new(()),
+        }
+    }
+
+    /// Hold `request`, submitted by `requested_by`, for approval, for
+    /// `reason`. The record keeps the
+    /// transfer itself, not its nonce or expiry, which are worked out again
+    /// when it is signed.
+    pub fn hold(
+        &self,
+        request: &WalletSubmitRequest,
+        reason: String,
+        requested_by: String,
+        now_epoch_ms: u128,
+    ) -> Result<ApprovalRecord> {
+        let record = ApprovalRecord {
+            approval_id: Uuid::new_v4().to_string(),
+            status: "pending".to_owned(),
+            from: request.from.clone(),
+            to: request.to.clone(),
+            amount: request.amount.clone(),
+            asset: request.asset.clone(),
+            chain: request.chain.clone(),
+            reason,
+            requested_by: Some(requested_by),
+            requested_at_epoch_ms: now_epoch_ms,
+            decided_by: None,
+            decided_at_epoch_ms: None,
+            tx_hash: None,
+            error: None,
+        };
+        self.store.save(&record)?;
+        Ok(record)
+    }
+
+    pub fn get(&self, approval_id: &str) -> Result<Option<ApprovalRecord>> {
+        self.store.load(approval_id)
+    }
+
+    /// Held transfers, newest first; only those in `status` when given.
+    pub fn list(&self, status: Option<&str>) -> Result<Vec<ApprovalRecord>> {
+        let mut records = self.store.list()?;
+        records.retain(|record| status.is_none_or(|status| record.status == status));
+        records.sort_by(|a, b| {
+            b.requested_at_epoch_ms
+                .cmp(&a.requested_at_epoch_ms)
+                .then_with(|| a.approval_id.cmp(&b.approval_id))
+        });
+        Ok(records)
+    }
+
+    /// Approve or reject a pending transfer as `decided_by`, who must not be
+    /// the principal that submitted it. An approved transfer stays
+    /// `approved` until [`ApprovalQueue::finish`] records its submission.
+    pub fn decide(
+        &self,
+        approval_id: &str,
+        decided_by: &str,
+        approve: bool,
+        now_epoch_ms: u128,
+    ) -> Result<ApprovalRecord, ApprovalError> {
+        let _deciding = self.deciding.lock().unwrap_or_else(|e| e.into_inner());
+        let mut record = self.store.load(approval_id)?.ok_or(ApprovalError::NotFound)?;
+        if record.status != "pending" {
+            return Err(ApprovalError::AlreadyDecided(record.status));
+        }
+        match record.requested_by.as_deref() {
+            None => return Err(ApprovalError::UnknownRequester),
+            Some(requested_by) if requested_by == decided_by => {
+                return Err(ApprovalError::SelfApproval);
+            }
+            Some(_) => {}
+        }
+        record.status = if approve { "approved" } else { "rejected" }.to_owned();
+        record.decided_by = Some(decided_by.to_owned());
+        record.decided_at_epoch_ms = Some(now_epoch_ms);
+        self.store.save(&record)?;
+        Ok(record)
+    }
+
+    /// Record how submitting an approved transfer went: its transaction
+    /// hash, or why it failed.
+    pub fn finish(&self, record: &mut ApprovalRecord, submitted: Result<String, String>) -> Result<()> {
+        match submitted {
+            Ok(tx_hash) => {
+                record.status = "submitted".to_owned();
+                record.tx_hash = Some(tx_hash);
+            }
+            Err(error) => {
+                record.status = "failed".to_owned();
+                record.error = Some(error);
+            }
+        }
+        self.store.save(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, ApprovalRecord>>);
+
+    impl ApprovalStore for MemoryStore {
+        fn save(&self, record: &ApprovalRecord) -> Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(record.approval_id.clone(), record.clone());
+            Ok(())
+        }
+
+        fn load(&self, approval_id: &str) -> Result<Option<ApprovalRecord>> {
+            Ok(self.0.lock().unwrap().get(approval_id).cloned())
+        }
+
+        fn list(&self) -> Result<Vec<ApprovalRecord>> {
+            Ok(self.0.lock().unwrap().values().cloned().collect())
+        }
+    }
+
+    fn transfer(amount: &str) -> WalletSubmitRequest {
+        WalletSubmitRequest {
+            from: "0xa".to_owned(),
+            to: "0xb".to_owned(),
+            amount: amount.to_owned(),
+            asset: "PROOF".to_owned(),
+            chain: "flowcortex-l1".to_owned(),
+            nonce: 7,
+            signed_payload: None,
+            expires_at_epoch_ms: None,
+            memo: None,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn held_transfers_need_a_second_principal() {
+        let store = Arc::new(MemoryStore::default());
+        let queue = ApprovalQueue::new(store.clone());
+        let first = queue
+            .hold(&transfer("500"), "over 100".to_owned(), "ops-1".to_owned(), 1_000)
+            .unwrap();
+        let second = queue.hold(&transfer("900"), "over 100".to_owned(), "ops-3".to_owned(), 2_000).unwrap();
+        assert_eq!(first.status, "pending");
+        let pending: Vec<_> = queue
+            .list(Some("pending"))
+            .unwrap()
+            .into_iter()
+            .map(|record| record.approval_id)
+            .collect();
+        assert_eq!(pending, vec![second.approval_id.clone(), first.approval_id.clone()]);
+
+        assert!(matches!(
+            queue.decide(&first.approval_id, "ops-1", true, 3_000),
+            Err(ApprovalError::SelfApproval)
+        ));
+        assert!(matches!(queue.decide("missing", "ops-2", true, 3_000), Err(ApprovalError::NotFound)));
+
+        let mut approved = queue.decide(&first.approval_id, "ops-2", true, 3_000).unwrap();
+        assert_eq!(approved.status, "approved");
+        assert_eq!(approved.decided_by.as_deref(), Some("ops-2"));
+        assert!(matches!(
+            queue.decide(&first.approval_id, "ops-3", true, 4_000),
+            Err(ApprovalError::AlreadyDecided(status)) if status == "approved"
+        ));
+        queue.finish(&mut approved, Ok("0xhash".to_owned())).unwrap();
+        assert_eq!(queue.get(&first.approval_id).unwrap(), Some(approved));
+
+        let rejected = queue.decide(&second.approval_id, "ops-1", false, 4_000).unwrap();
+        assert_eq!(rejected.status, "rejected");
+        assert!(queue.list(Some("pending")).unwrap().is_empty());
+        assert_eq!(queue.list(None).unwrap().len(), 2);
+
+        // Nobody is known to have submitted this one, so nobody may decide it.
+        let anonymous = ApprovalRecord {
+            approval_id: "anonymous".to_owned(),
+            requested_by: None,
+            ..second
+        };
+        store.save(&ApprovalRecord { status: "pending".to_owned(), ..anonymous }).unwrap();
+        assert!(matches!(
+            queue.decide("anonymous", "ops-2", false, 5_000),
+            Err(ApprovalError::UnknownRequester)
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+//! Audit events for what the core signs, submits and decides.
+//!
+//! The core builds an [`AuditEvent`] for every signature it makes, every
+//! transfer a [`crate::TransactionPipeline`] runs and every signing policy
+//! decision short of allowing, and hands it as an [`AuditEventRecord`] to
+//! the [`AuditSink`] set with [`crate::WalletCore::with_audit_sink`]. The
+//! trail is then the same whichever front end asked for the operation.
+
+use async_trait::async_trait;
+use kc_storage::{AuditEventRecord, RocksDbKeystore};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::PolicyDecision;
+
+/// Where audit events go.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Record `event`. A sink that fails to reports it itself: auditing
+    /// never fails the operation audited.
+    async fn record(&self, event: AuditEventRecord);
+}
+
+#[async_trait]
+impl AuditSink for RocksDbKeystore {
+    async fn record(&self, event: AuditEventRecord) {
+        // The keystore has nowhere to report a failure; callers that need to
+        // know wrap it in a sink of their own.
+        let _ = self.append_audit_event(event);
+    }
+}
+
+/// Something the core did that belongs in the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+    /// A payload signed with the wallet key, or with one of its session
+    /// keys when `session_key_id` is set.
+    Signed {
+        wallet_address: String,
+        purpose: String,
+        session_key_id: Option<String>,
+    },
+    SessionKeyIssued {
+        wallet_address: String,
+        session_key_id: String,
+        purposes: Vec<String>,
+        expires_at_epoch_ms: u128,
+    },
+    /// A signing policy's decision on what was about to be signed.
+    PolicyDecided {
+        wallet_address: String,
+        purpose: String,
+        decision: PolicyDecision,
+    },
+    /// A transfer a [`crate::TransactionPipeline`] ran.
+    Transfer {
+        from: String,
+        to: String,
+        amount: String,
+        asset: String,
+        chain: String,
+        outcome: TransferOutcome,
+    },
+}
+
+/// How far a transfer got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferOutcome {
+    /// Held for approval before signing.
+    Held { approval_id: String },
+    Submitted { tx_hash: String, accepted: bool },
+    /// Stopped by an error in the named stage: `validation`, `policy`,
+    /// `signing`, `broadcast` or `persistence`.
+    Stopped { stage: &'static str },
+}
+
+impl AuditEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::Signed { .. } => "wallet_sign",
+            Self::SessionKeyIssued { .. } => "wallet_session_key",
+            Self::PolicyDecided { .. } => "signing_policy",
+            Self::Transfer { .. } => "transfer_submit",
+        }
+    }
+
+    pub fn outcome(&self) -> &'static str {
+        match self {
+            Self::Signed { .. } => "signed",
+            Self::SessionKeyIssued { .. } => "issued",
+            Self::PolicyDecided { decision, .. } => match decision {
+                PolicyDecision::Allow => "allow",
+                PolicyDecision::Deny(_) => "deny",
+                PolicyDecision::RequireApproval(_) => "require_approval",
+            },
+            Self::Transfer { outcome, .. } => match outcome {
+                TransferOutcome::Held { .. } => "held",
+                TransferOutcome::Submitted { accepted: true, .. } => "accepted",
+                TransferOutcome::Submitted { accepted: false, .. } => "rejected",
+                TransferOutcome::Stopped { .. } => "failed",
+            },
+        }
+    }
+
+    /// The event as the audit trail stores it, at `timestamp_epoch_ms`; the
+    /// store assigns its id.
+    pub fn into_record(self, timestamp_epoch_ms: u128) -> AuditEventRecord {
+        let event_type = self.event_type().to_owned();
+        let outcome = self.outcome().to_owned();
+        let (wallet_address, chain, message) = match self {
+            Self::Signed {
+                wallet_address,
+                purpose,
+                session_key_id,
+            } => {
+                let message = match session_key_id {
+                    Some(id) => format!("purpose {purpose} with session key {id}"),
+                    None => format!("purpose {purpose}"),
+                };
+                (wallet_address, None, message)
+            }
+            Self::SessionKeyIssued {
+                wallet_address,
+                session_key_id,
+                purposes,
+                expires_at_epoch_ms,
+            } => {
+                let message = format!(
+                    "session key {session_key_id} for {} until {expires_at_epoch_ms}",
+                    purposes.join(", ")
+                );
+                (wallet_address, None, message)
+            }
+            Self::PolicyDecided {
+                wallet_address,
+                purpose,
+                decision,
+            } => {
+                let message = match decision {
+                    PolicyDecision::Allow => format!("purpose {purpose}"),
+                    PolicyDecision::Deny(reason) | PolicyDecision::RequireApproval(reason) => {
+                        format!("purpose {purpose}: {reason}")
+                    }
+                };
+                (wallet_address, None, message)
+            }
+            Self::Transfer {
+                from,
+                to,
+                amount,
+                asset,
+                chain,
+                outcome,
+            } => {
+                let detail = match outcome {
+                    TransferOutcome::Held { approval_id } => format!("approval {approval_id}"),
+                    TransferOutcome::Submitted { tx_hash, .. } => format!("tx {tx_hash}"),
+                    TransferOutcome::Stopped { stage } => format!("stopped at {stage}"),
+                };
+                (from, Some(chain), format!("{amount} {asset} to {to}; {detail}"))
+            }
+        };
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type,
+            wallet_address: Some(wallet_address),
+            user_id: None,
+            chain,
+            outcome,
+            message: Some(message),
+            timestamp_epoch_ms,
+            tenant: None,
+        }
+    }
+}
+
+/// Hand `event` to `sink`, stamped with the current time.
+pub(crate) async fn emit(sink: &dyn AuditSink, event: AuditEvent) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    sink.record(event.into_record(now)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DynWalletCore, KeystoreSignerProvider, PolicyContext, PurposePolicy};
+    use kc_api_types::SignPurpose;
+    use kc_chain_client::ChainRegistry;
+    use kc_crypto::{Ed25519Signer, encrypt_key_material};
+    use kc_storage::{InMemoryKeystore, Keystore};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<AuditEventRecord>>);
+
+    #[async_trait]
+    impl AuditSink for RecordingSink {
+        async fn record(&self, event: AuditEventRecord) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn core_audits_signatures_and_policy_refusals() {
+        let signer = Ed25519Signer::new_random();
+        let wallet = signer.wallet_address();
+        let keystore = Arc::new(InMemoryKeystore::default());
+        keystore
+            .save_encrypted_key(
+                &wallet,
+                encrypt_key_material(&signer.secret_key_bytes(), "test-key").unwrap(),
+            )
+            .await
+            .unwrap();
+        let sink = Arc::new(RecordingSink::default());
+        let core = DynWalletCore::new_dyn(
+            Arc::new(KeystoreSignerProvider::new(Arc::clone(&keystore), Arc::from("test-key"))),
+            keystore,
+            ChainRegistry::default(),
+        )
+        .with_audit_sink(sink.clone());
+
+        core.sign_payload(&wallet, b"hello", SignPurpose::Auth)
+            .await
+            .unwrap()
+            .expect("wallet has a custodied key");
+        let policy = PurposePolicy {
+            allowed: ["auth".to_owned()].into(),
+        };
+        let ctx = |purpose| PolicyContext {
+            wallet_address: &wallet,
+            purpose,
+            transfer: None,
+        };
+        assert_eq!(core.check_policy(&policy, &ctx(&SignPurpose::Auth)).await, PolicyDecision::Allow);
+        assert!(matches!(
+            core.check_policy(&policy, &ctx(&SignPurpose::Proof)).await,
+            PolicyDecision::Deny(_)
+        ));
+
+        let events = sink.0.lock().unwrap().clone();
+        assert_eq!(events.len(), 2, "allowed decisions are not audited: {events:?}");
+        assert_eq!(events[0].event_type, "wallet_sign");
+        assert_eq!(events[0].outcome, "signed");
+        assert_eq!(events[0].wallet_address.as_deref(), Some(wallet.as_str()));
+        assert_eq!(events[0].message.as_deref(), Some("purpose auth"));
+        assert_eq!(events[1].event_type, "signing_policy");
+        assert_eq!(events[1].outcome, "deny");
+        assert_eq!(
+            events[1].message.as_deref(),
+            Some("purpose proof: the wallet's key may not sign for purpose proof")
+        );
+        assert!(events.iter().all(|event| event.timestamp_epoch_ms > 0));
+    }
+}
@@ -1,30 +1,272 @@
 use anyhow::{Result, anyhow};
-use kc_api_types::SignPurpose;
-use kc_chain_client::{ChainRegistry, SubmitTxRequest, SubmitTxResult};
-use kc_crypto::Signer;
+use kc_api_types::{ChainAssetInfo, SessionKeyAttestation, SignPurpose, WalletPortfolioResponse};
+use kc_chain_client::{ChainAdapter, ChainRegistry, SubmitTxRequest, SubmitTxResult};
+use kc_crypto::{Ed25519Signer, Signer, SigningDomain};
 use kc_storage::Keystore;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-pub struct WalletCore<S, K> {
-    signer: S,
+use crate::session_keys::SessionKeys;
+
+mod approvals;
+mod audit;
+mod nonces;
+mod pipeline;
+mod policy;
+mod portfolio;
+mod session_keys;
+mod signers;
+mod submissions;
+
+pub use approvals::{ApprovalError, ApprovalQueue, ApprovalStore};
+pub use audit::{AuditEvent, AuditSink, TransferOutcome};
+pub use nonces::{
+    MAX_LIVE_RESERVATIONS, NonceClaim, NonceLedger, NonceManager, NonceReconciliation,
+    NonceReservation,
+};
+pub use pipeline::{
+    PendingTransaction, SignWithProvider, SubmitToRegistry, TransactionPipeline, TransactionStage,
+};
+pub use policy::{
+    DestinationPolicy, PolicyContext, PolicyDecision, PurposePolicy, SigningPolicy,
+    SpendingLimitPolicy, TransferDetails, WalletPolicy,
+};
+pub use portfolio::{DEFAULT_PORTFOLIO_TIMEOUT, PortfolioSource, portfolio};
+pub use session_keys::{
+    DEFAULT_SESSION_KEY_TTL, IssuedSessionKey, MAX_SESSION_KEY_TTL, SessionKeyError, SessionKeyStore,
+};
+pub use signers::{KeystoreSignerProvider, SignerProvider};
+pub use submissions::{SubmissionPolicy, SubmissionStore, SubmissionTracker, SubmissionUpdate};
+
+/// Signs and submits for every wallet its [`SignerProvider`] custodies.
+pub struct WalletCore<P, K> {
+    signers: P,
     keystore: K,
     chain_registry: ChainRegistry,
+    signing_domain: SigningDomain,
+    /// Assets [`WalletCore::portfolio`] looks up, by chain.
+    portfolio_assets: BTreeMap<String, Vec<ChainAssetInfo>>,
+    portfolio_timeout: Duration,
+    /// Set by [`WalletCore::with_session_keys`].
+    session_keys: Option<SessionKeys>,
+    /// Set by [`WalletCore::with_audit_sink`].
+    audit: Option<Arc<dyn AuditSink>>,
+}
+
+/// A [`WalletCore`] over trait objects: one type whatever signs and stores
+/// for it, to hold in shared state such as an axum `AppState`.
+pub type DynWalletCore = WalletCore<Arc<dyn SignerProvider>, Arc<dyn Keystore>>;
+
+impl DynWalletCore {
+    pub fn new_dyn(
+        signers: Arc<dyn SignerProvider>,
+        keystore: Arc<dyn Keystore>,
+        chain_registry: ChainRegistry,
+    ) -> Self {
+        Self::new(signers, keystore, chain_registry)
+    }
 }
 
-impl<S, K> WalletCore<S, K>
+impl<P, K> WalletCore<P, K>
 where
-    S: Signer,
+    P: SignerProvider,
     K: Keystore,
 {
-    pub fn new(signer: S, keystore: K, chain_registry: ChainRegistry) -> Self {
+    pub fn new(signers: P, keystore: K, chain_registry: ChainRegistry) -> Self {
         Self {
-            signer,
+            signers,
             keystore,
             chain_registry,
+            signing_domain: SigningDomain::default(),
+            portfolio_assets: BTreeMap::new(),
+            portfolio_timeout: DEFAULT_PORTFOLIO_TIMEOUT,
+            session_keys: None,
+            audit: None,
+        }
+    }
+
+    /// Register `adapter` for transfers and portfolios on its chain.
+    pub fn with_chain(mut self, adapter: Arc<dyn ChainAdapter>) -> Self {
+        self.chain_registry.register(adapter);
+        self
+    }
+
+    /// Use a chain-specific signing domain instead of the `keycortex:v1` default.
+    pub fn with_signing_domain(mut self, signing_domain: SigningDomain) -> Self {
+        self.signing_domain = signing_domain;
+        self
+    }
+
+    /// List the assets [`portfolio`](Self::portfolio) looks up on `chain`.
+    pub fn with_portfolio_assets(mut self, chain: &str, assets: Vec<ChainAssetInfo>) -> Self {
+        self.portfolio_assets.insert(chain.to_owned(), assets);
+        self
+    }
+
+    /// How long each chain may take to answer [`portfolio`](Self::portfolio).
+    pub fn with_portfolio_timeout(mut self, timeout: Duration) -> Self {
+        self.portfolio_timeout = timeout;
+        self
+    }
+
+    /// Register session keys in `store`, their secrets encrypted with
+    /// `encryption_key`.
+    pub fn with_session_keys(mut self, store: Arc<dyn SessionKeyStore>, encryption_key: Arc<str>) -> Self {
+        self.session_keys = Some(SessionKeys {
+            store,
+            encryption_key,
+        });
+        self
+    }
+
+    /// Audit what the core signs, submits and decides to `sink`.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit = Some(sink);
+        self
+    }
+
+    pub fn audit_sink(&self) -> Option<&dyn AuditSink> {
+        self.audit.as_deref()
+    }
+
+    /// Audit `event`, for operations done outside the core, such as signing
+    /// with a KMS-held key, to land in the same trail.
+    pub async fn audit(&self, event: AuditEvent) {
+        if let Some(sink) = self.audit_sink() {
+            audit::emit(sink, event).await;
+        }
+    }
+
+    pub fn signing_domain(&self) -> &SigningDomain {
+        &self.signing_domain
+    }
+
+    /// The signer for `wallet_address`; `None` if it has no custodied key.
+    pub async fn signer_for(&self, wallet_address: &str) -> Result<Option<Ed25519Signer>> {
+        self.signers.signer_for(wallet_address).await
+    }
+
+    /// Sign `payload` with the key of `wallet_address` in the core's
+    /// signing domain; `None` if it has no custodied key.
+    pub async fn sign_payload(
+        &self,
+        wallet_address: &str,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<Option<Vec<u8>>> {
+        let Some(signer) = self.signer_for(wallet_address).await? else {
+            return Ok(None);
+        };
+        let audited = AuditEvent::Signed {
+            wallet_address: wallet_address.to_owned(),
+            purpose: purpose.as_str().to_owned(),
+            session_key_id: None,
+        };
+        let signature = signer.sign_in_domain(&self.signing_domain, payload, purpose)?;
+        self.audit(audited).await;
+        Ok(Some(signature))
+    }
+
+    /// `policy`'s decision on what `ctx` describes, audited unless it
+    /// allows signing.
+    pub async fn check_policy(&self, policy: &dyn SigningPolicy, ctx: &PolicyContext<'_>) -> PolicyDecision {
+        let decision = policy.evaluate(ctx);
+        if decision != PolicyDecision::Allow {
+            self.audit(AuditEvent::PolicyDecided {
+                wallet_address: ctx.wallet_address.to_owned(),
+                purpose: ctx.purpose.as_str().to_owned(),
+                decision: decision.clone(),
+            })
+            .await;
+        }
+        decision
+    }
+
+    /// Derive a session key for `wallet_address` that may sign for
+    /// `purposes` until `ttl` has passed, attest it with the wallet key and
+    /// register it. `None` if the wallet has no custodied key.
+    pub async fn issue_session_key(
+        &self,
+        wallet_address: &str,
+        ttl: Duration,
+        purposes: Vec<SignPurpose>,
+    ) -> Result<Option<IssuedSessionKey>, SessionKeyError> {
+        let session_keys = self.session_keys()?;
+        let Some(wallet_key) = self.signer_for(wallet_address).await? else {
+            return Ok(None);
+        };
+        let issued = session_keys.issue(&wallet_key, &self.signing_domain, ttl, purposes)?;
+        self.audit(AuditEvent::SessionKeyIssued {
+            wallet_address: wallet_address.to_owned(),
+            session_key_id: issued.session_key_id.clone(),
+            purposes: issued
+                .attestation
+                .purposes
+                .iter()
+                .map(|purpose| purpose.as_str().to_owned())
+                .collect(),
+            expires_at_epoch_ms: issued.attestation.expires_at_epoch_ms,
+        })
+        .await;
+        Ok(Some(issued))
+    }
+
+    /// Sign `payload` with a session key of `wallet_address` in the core's
+    /// signing domain, without loading the wallet key. Returns the
+    /// signature and the key's attestation.
+    pub async fn sign_with_session_key(
+        &self,
+        wallet_address: &str,
+        session_key_id: &str,
+        payload: &[u8],
+        purpose: SignPurpose,
+    ) -> Result<(Vec<u8>, SessionKeyAttestation), SessionKeyError> {
+        let (signer, attestation) =
+            self.session_keys()?
+                .signer(wallet_address, session_key_id, &purpose)?;
+        let audited = AuditEvent::Signed {
+            wallet_address: wallet_address.to_owned(),
+            purpose: purpose.as_str().to_owned(),
+            session_key_id: Some(session_key_id.to_owned()),
+        };
+        let signature = signer.sign_in_domain(&self.signing_domain, payload, purpose)?;
+        self.audit(audited).await;
+        Ok((signature, attestation))
+    }
+
+    fn session_keys(&self) -> Result<&SessionKeys, SessionKeyError> {
+        self.session_keys
+            .as_ref()
+            .ok_or_else(|| SessionKeyError::Other(anyhow!("no session key store is configured")))
+    }
+
+    /// A pipeline that signs a transfer with its source wallet's custodied
+    /// key and submits it through the core's chain registry, audited to the
+    /// core's sink. Add validation, policy and persistence stages to taste.
+    pub fn transfer_pipeline(&self) -> TransactionPipeline<'_, anyhow::Error> {
+        let pipeline = TransactionPipeline::new()
+            .sign(SignWithProvider(&self.signers))
+            .broadcast(SubmitToRegistry(&self.chain_registry));
+        match self.audit_sink() {
+            Some(sink) => pipeline.audit(sink),
+            None => pipeline,
         }
     }
 
-    pub async fn sign_payload(&self, payload: &[u8], purpose: SignPurpose) -> Result<Vec<u8>> {
-        self.signer.sign(payload, purpose)
+    /// What `wallet_address` holds on every registered chain with assets
+    /// listed, asking the chains concurrently.
+    pub async fn portfolio(&self, wallet_address: &str) -> WalletPortfolioResponse {
+        let mut adapters = self.chain_registry.adapters();
+        adapters.sort_by(|a, b| a.chain_id().cmp(b.chain_id()));
+        let sources = adapters
+            .into_iter()
+            .filter_map(|adapter| {
+                let assets = self.portfolio_assets.get(adapter.chain_id())?.clone();
+                Some(PortfolioSource { adapter, assets })
+            })
+            .collect();
+        portfolio(wallet_address, sources, self.portfolio_timeout).await
     }
 
     pub async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
@@ -0,0 +1,338 @@
+//! Per-wallet transaction nonces.
+//!
+//! A [`NonceManager`] answers "which nonce next?" from three sources: the
+//! last nonce settled by a persisted submission (its [`NonceLedger`]),
+//! claims by submissions still in flight (its [`NonceStore`], shared across
+//! replicas when Redis is configured), and nonces reserved for external
+//! signers. It claims a transfer's nonce before broadcast and releases it if
+//! the transfer never reaches the chain, reports nonces a claim skips over,
+//! and brings its view in line with a chain that reports account nonces.
+
+use anyhow::Result;
+use kc_storage::{NonceStore, RocksDbKeystore, WalletNonceRecord};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+/// Most live reservations a wallet can hold at once.
+pub const MAX_LIVE_RESERVATIONS: usize = 16;
+
+/// Where the last nonce of each wallet's persisted submissions is kept.
+pub trait NonceLedger: Send + Sync {
+    fn settled(&self, wallet_address: &str) -> Result<Option<u64>>;
+    /// Replace the settled nonce; `None` forgets it.
+    fn set_settled(&self, wallet_address: &str, last_nonce: Option<u64>) -> Result<()>;
+}
+
+impl NonceLedger for RocksDbKeystore {
+    fn settled(&self, wallet_address: &str) -> Result<Option<u64>> {
+        Ok(self
+            .load_wallet_nonce(wallet_address)?
+            .map(|record| record.last_nonce))
+    }
+
+    fn set_settled(&self, wallet_address: &str, last_nonce: Option<u64>) -> Result<()> {
+        match last_nonce {
+            Some(last_nonce) => self.save_wallet_nonce(&WalletNonceRecord {
+                wallet_address: wallet_address.to_owned(),
+                last_nonce,
+                updated_at_epoch_ms: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis(),
+            }),
+            None => self.delete_wallet_nonce(wallet_address),
+        }
+    }
+}
+
+/// A nonce held for an external signer until it submits or the TTL lapses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceReservation {
+    pub reservation_id: String,
+    pub nonce: u64,
+    pub expires_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonceClaim {
+    Accepted {
+        /// Nonces between the wallet's last one and this one that nobody
+        /// holds a reservation for. A chain that orders nonces strictly
+        /// stalls the transfer until they are used.
+        gap: Option<RangeInclusive<u64>>,
+    },
+    /// The nonce is not above the wallet's last one.
+    Replay,
+}
+
+/// What [`NonceManager::reconcile`] replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceReconciliation {
+    pub settled: Option<u64>,
+    pub chain_last: Option<u64>,
+}
+
+pub struct NonceManager {
+    claims: Arc<dyn NonceStore>,
+    ledger: Arc<dyn NonceLedger>,
+    reservations: RwLock<HashMap<String, Vec<NonceReservation>>>,
+}
+
+impl NonceManager {
+    pub fn new(claims: Arc<dyn NonceStore>, ledger: Arc<dyn NonceLedger>) -> Self {
+        Self {
+            claims,
+            ledger,
+            reservations: RwLock::default(),
+        }
+    }
+
+    /// Last nonce of a persisted submission from the wallet.
+    pub fn settled(&self, wallet_address: &str) -> Result<Option<u64>> {
+        self.ledger.settled(wallet_address)
+    }
+
+    /// Last nonce claimed for the wallet, in flight or settled.
+    pub async fn last(&self, wallet_address: &str) -> Result<Option<u64>> {
+        match self.claims.last(wallet_address).await? {
+            Some(last) => Ok(Some(last)),
+            None => self.settled(wallet_address),
+        }
+    }
+
+    /// Lowest nonce above `last_nonce` not held by a live reservation.
+    pub async fn next_free(&self, wallet_address: &str, last_nonce: u64, now_epoch_ms: u128) -> u64 {
+        let reservations = self.reservations.read().await;
+        reservations
+            .get(wallet_address)
+            .map_or(last_nonce.saturating_add(1), |entries| {
+                first_free(entries, last_nonce, now_epoch_ms)
+            })
+    }
+
+    /// Next nonce to sign with: above the last claimed one, skipping
+    /// reservations.
+    pub async fn next(&self, wallet_address: &str, now_epoch_ms: u128) -> Result<u64> {
+        let last_nonce = self.last(wallet_address).await?.unwrap_or(0);
+        Ok(self.next_free(wallet_address, last_nonce, now_epoch_ms).await)
+    }
+
+    /// Whether [`claim`](Self::claim) would accept `nonce`, without
+    /// claiming it.
+    pub async fn check(&self, wallet_address: &str, nonce: u64, now_epoch_ms: u128) -> Result<NonceClaim> {
+        let last = self.last(wallet_address).await?;
+        if last.is_some_and(|last| nonce <= last) {
+            return Ok(NonceClaim::Replay);
+        }
+        Ok(NonceClaim::Accepted {
+            gap: self.gap(wallet_address, last, nonce, now_epoch_ms).await,
+        })
+    }
+
+    /// Claim `nonce` for a submission, consuming its reservation if it has
+    /// one. Once claimed, [`release`](Self::release) it unless the transfer
+    /// reaches the chain and is persisted.
+    pub async fn claim(&self, wallet_address: &str, nonce: u64, now_epoch_ms: u128) -> Result<NonceClaim> {
+        let settled = self.settled(wallet_address)?;
+        let last = self.claims.last(wallet_address).await?.or(settled);
+        if !self.claims.claim(wallet_address, nonce, settled).await? {
+            return Ok(NonceClaim::Replay);
+        }
+        let gap = self.gap(wallet_address, last, nonce, now_epoch_ms).await;
+        self.consume_reservation(wallet_address, nonce).await;
+        Ok(NonceClaim::Accepted { gap })
+    }
+
+    /// Give back a claimed nonce whose transfer never reached the chain, so
+    /// it can be retried. A later claim is left in place.
+    pub async fn release(&self, wallet_address: &str, nonce: u64) -> Result<()> {
+        self.claims.release(wallet_address, nonce).await
+    }
+
+    /// Take the chain's account nonce, the next one it expects, as the
+    /// truth: the wallet's nonces drift from it when another service signs
+    /// with the same key, or the chain drops a transfer recorded as
+    /// accepted. Left alone while a submission from the wallet is in flight.
+    pub async fn reconcile(
+        &self,
+        wallet_address: &str,
+        account_nonce: u64,
+    ) -> Result<Option<NonceReconciliation>> {
+        let settled = self.settled(wallet_address)?;
+        let chain_last = account_nonce.checked_sub(1);
+        if settled == chain_last
+            || !self
+                .claims
+                .reconcile(wallet_address, settled, chain_last)
+                .await?
+        {
+            return Ok(None);
+        }
+        self.ledger.set_settled(wallet_address, chain_last)?;
+        Ok(Some(NonceReconciliation { settled, chain_last }))
+    }
+
+    /// Hold the next free nonce for an external signer for `ttl_ms`; `None`
+    /// when the wallet already holds [`MAX_LIVE_RESERVATIONS`].
+    pub async fn reserve(
+        &self,
+        wallet_address: &str,
+        reservation_id: String,
+        ttl_ms: u128,
+        now_epoch_ms: u128,
+    ) -> Result<Option<NonceReservation>> {
+        // Held across the nonce lookup so concurrent reservations cannot collide.
+        let mut reservations = self.reservations.write().await;
+        let last_nonce = self.last(wallet_address).await?.unwrap_or(0);
+        let entries = reservations.entry(wallet_address.to_owned()).or_default();
+        entries.retain(|r| r.expires_at_epoch_ms > now_epoch_ms && r.nonce > last_nonce);
+        if entries.len() >= MAX_LIVE_RESERVATIONS {
+            return Ok(None);
+        }
+        let reservation = NonceReservation {
+            reservation_id,
+            nonce: first_free(entries, last_nonce, now_epoch_ms),
+            expires_at_epoch_ms: now_epoch_ms + ttl_ms,
+        };
+        entries.push(reservation.clone());
+        Ok(Some(reservation))
+    }
+
+    /// The wallet's reservations, including lapsed ones not yet pruned.
+    pub async fn reservations(&self, wallet_address: &str) -> Vec<NonceReservation> {
+        self.reservations
+            .read()
+            .await
+            .get(wallet_address)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn consume_reservation(&self, wallet_address: &str, nonce: u64) {
+        let mut reservations = self.reservations.write().await;
+        if let Some(entries) = reservations.get_mut(wallet_address) {
+            entries.retain(|r| r.nonce != nonce);
+            if entries.is_empty() {
+                reservations.remove(wallet_address);
+            }
+        }
+    }
+
+    async fn gap(
+        &self,
+        wallet_address: &str,
+        last: Option<u64>,
+        nonce: u64,
+        now_epoch_ms: u128,
+    ) -> Option<RangeInclusive<u64>> {
+        let skipped = last?.checked_add(1)?..=nonce.checked_sub(1)?;
+        if skipped.is_empty() {
+            return None;
+        }
+        let reservations = self.reservations.read().await;
+        let reserved = reservations.get(wallet_address).map_or(0, |entries| {
+            entries
+                .iter()
+                .filter(|r| r.expires_at_epoch_ms > now_epoch_ms && skipped.contains(&r.nonce))
+                .count() as u64
+        });
+        (reserved < skipped.end() - skipped.start() + 1).then_some(skipped)
+    }
+}
+
+fn first_free(reservations: &[NonceReservation], last_nonce: u64, now_epoch_ms: u128) -> u64 {
+    let mut nonce = last_nonce.saturating_add(1);
+    while reservations
+        .iter()
+        .any(|r| r.nonce == nonce && r.expires_at_epoch_ms > now_epoch_ms)
+    {
+        nonce = nonce.saturating_add(1);
+    }
+    nonce
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kc_storage::InMemoryNonceStore;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryLedger(Mutex<HashMap<String, u64>>);
+
+    impl NonceLedger for MemoryLedger {
+        fn settled(&self, wallet_address: &str) -> Result<Option<u64>> {
+            Ok(self.0.lock().unwrap().get(wallet_address).copied())
+        }
+
+        fn set_settled(&self, wallet_address: &str, last_nonce: Option<u64>) -> Result<()> {
+            let mut settled = self.0.lock().unwrap();
+            match last_nonce {
+                Some(last_nonce) => settled.insert(wallet_address.to_owned(), last_nonce),
+                None => settled.remove(wallet_address),
+            };
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn claims_reservations_gaps_and_reconciliation() {
+        let ledger = Arc::new(MemoryLedger::default());
+        ledger.set_settled("0xa", Some(3)).unwrap();
+        let nonces = NonceManager::new(Arc::new(InMemoryNonceStore::default()), ledger.clone());
+        let now = 1_000;
+
+        assert_eq!(nonces.next("0xa", now).await.unwrap(), 4);
+        let reserved = nonces.reserve("0xa", "r-4".to_owned(), 500, now).await.unwrap().unwrap();
+        assert_eq!(reserved.nonce, 4);
+        assert_eq!(nonces.next("0xa", now).await.unwrap(), 5, "reserved nonces are skipped");
+
+        assert_eq!(nonces.claim("0xa", 3, now).await.unwrap(), NonceClaim::Replay);
+        assert_eq!(
+            nonces.claim("0xa", 5, now).await.unwrap(),
+            NonceClaim::Accepted { gap: None },
+            "nonce 4 is reserved, so there is no gap"
+        );
+        assert_eq!(
+            nonces.check("0xa", 8, now).await.unwrap(),
+            NonceClaim::Accepted { gap: Some(6..=7) }
+        );
+
+        // The broadcast fails: nonce 5 can be claimed again.
+        nonces.release("0xa", 5).await.unwrap();
+        assert_eq!(nonces.last("0xa").await.unwrap(), Some(3));
+        assert!(matches!(nonces.claim("0xa", 5, now).await.unwrap(), NonceClaim::Accepted { .. }));
+
+        // Claiming a reserved nonce consumes the reservation; a lapsed one is
+        // handed out again.
+        nonces.release("0xa", 5).await.unwrap();
+        nonces.claim("0xa", 4, now).await.unwrap();
+        assert!(nonces.reservations("0xa").await.is_empty());
+        let lapsing = nonces.reserve("0xa", "r-5".to_owned(), 500, now).await.unwrap().unwrap();
+        assert_eq!(lapsing.nonce, 5);
+        let reissued = nonces.reserve("0xa", "r-5b".to_owned(), 500, now + 500).await.unwrap().unwrap();
+        assert_eq!(reissued.nonce, 5);
+        assert_eq!(nonces.reservations("0xa").await, vec![reissued.clone()]);
+
+        // A wallet holds at most MAX_LIVE_RESERVATIONS until some lapse.
+        for i in 1..MAX_LIVE_RESERVATIONS {
+            nonces.reserve("0xa", format!("r-cap-{i}"), 500, now + 500).await.unwrap().unwrap();
+        }
+        assert_eq!(nonces.reserve("0xa", "r-over".to_owned(), 500, now + 500).await.unwrap(), None);
+        assert!(nonces.reserve("0xa", "r-later".to_owned(), 500, now + 1_000).await.unwrap().is_some());
+
+        // A claim in flight holds off reconciliation; once settled, the
+        // chain's account nonce wins.
+        assert_eq!(nonces.reconcile("0xa", 10).await.unwrap(), None);
+        ledger.set_settled("0xa", Some(4)).unwrap();
+        assert_eq!(
+            nonces.reconcile("0xa", 10).await.unwrap(),
+            Some(NonceReconciliation {
+                settled: Some(4),
+                chain_last: Some(9),
+            })
+        );
+        assert_eq!(nonces.settled("0xa").unwrap(), Some(9));
+        assert_eq!(nonces.reconcile("0xa", 10).await.unwrap(), None);
+    }
+}
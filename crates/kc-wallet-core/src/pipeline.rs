@@ -0,0 +1,395 @@
+//! Transfers as a sequence of pluggable stages.
+//!
+//! A [`TransactionPipeline`] takes a [`PendingTransaction`] through its
+//! validation stages, its policy stages, then signing, broadcast and
+//! persistence, stopping at the first error. Every stage is optional: a dry
+//! run swaps broadcast for a simulation and skips persistence, and a
+//! transfer signed elsewhere has no signing stage, only a validation stage
+//! that checks the signature it arrived with. A policy stage may also hold
+//! the transfer for approval, which ends the run before signing. With an
+//! [`AuditSink`] set, every run other than a dry run is audited.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use kc_api_types::{
+    Amount, AssetSymbol, ChainId, SignPurpose, TxEnvelopeError, TxEnvelopeV1, WalletAddress,
+    WalletSubmitRequest,
+};
+use kc_chain_client::{
+    ChainRegistry, NonceStrategy, SimulationResult, SubmitTxRequest, SubmitTxResult,
+};
+use kc_crypto::encoding::to_hex;
+use kc_crypto::{Signer, SigningDomain};
+
+use crate::audit::{self, AuditEvent, AuditSink, TransferOutcome};
+use crate::SignerProvider;
+
+/// A transfer and what the stages have worked out about it so far.
+pub struct PendingTransaction {
+    pub request: WalletSubmitRequest,
+    /// The domain the transfer is signed in, or its signature checked in.
+    pub signing_domain: SigningDomain,
+    /// How the chain orders the source wallet's transfers, once a stage
+    /// has checked the nonce.
+    pub nonce_strategy: Option<NonceStrategy>,
+    pub signature: Option<Vec<u8>>,
+    pub submitted: Option<SubmitTxResult>,
+    pub simulation: Option<SimulationResult>,
+    /// Set by a policy stage that holds the transfer for approval; the
+    /// pipeline then stops before signing.
+    pub approval_id: Option<String>,
+}
+
+impl PendingTransaction {
+    pub fn new(request: WalletSubmitRequest, signing_domain: SigningDomain) -> Self {
+        Self {
+            request,
+            signing_domain,
+            nonce_strategy: None,
+            signature: None,
+            submitted: None,
+            simulation: None,
+            approval_id: None,
+        }
+    }
+
+    /// The canonical [`TxEnvelopeV1`] payload that is signed with purpose
+    /// `Transaction`.
+    pub fn canonical_payload(&self) -> Result<String, TxEnvelopeError> {
+        TxEnvelopeV1::from_request(&self.request).map(|envelope| envelope.canonical())
+    }
+
+    /// The hex signature, once a stage has set it.
+    pub fn signature_hex(&self) -> Option<String> {
+        self.signature.as_deref().map(to_hex)
+    }
+}
+
+/// One step of a [`TransactionPipeline`], failing with `E`.
+#[async_trait]
+pub trait TransactionStage<E>: Send + Sync {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), E>;
+}
+
+type BoxedStage<'a, E> = Box<dyn TransactionStage<E> + 'a>;
+
+pub struct TransactionPipeline<'a, E> {
+    validation: Vec<BoxedStage<'a, E>>,
+    policy: Vec<BoxedStage<'a, E>>,
+    signing: Option<BoxedStage<'a, E>>,
+    broadcast: Option<BoxedStage<'a, E>>,
+    persistence: Option<BoxedStage<'a, E>>,
+    audit: Option<&'a dyn AuditSink>,
+}
+
+impl<E> Default for TransactionPipeline<'_, E> {
+    fn default() -> Self {
+        Self {
+            validation: Vec::new(),
+            policy: Vec::new(),
+            signing: None,
+            broadcast: None,
+            persistence: None,
+            audit: None,
+        }
+    }
+}
+
+impl<'a, E: Send> TransactionPipeline<'a, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a validation stage; they run in the order added.
+    pub fn validate(mut self, stage: impl TransactionStage<E> + 'a) -> Self {
+        self.validation.push(Box::new(stage));
+        self
+    }
+
+    /// Add a policy stage; they run in the order added, after validation.
+    pub fn policy(mut self, stage: impl TransactionStage<E> + 'a) -> Self {
+        self.policy.push(Box::new(stage));
+        self
+    }
+
+    pub fn sign(mut self, stage: impl TransactionStage<E> + 'a) -> Self {
+        self.signing = Some(Box::new(stage));
+        self
+    }
+
+    pub fn broadcast(mut self, stage: impl TransactionStage<E> + 'a) -> Self {
+        self.broadcast = Some(Box::new(stage));
+        self
+    }
+
+    pub fn persist(mut self, stage: impl TransactionStage<E> + 'a) -> Self {
+        self.persistence = Some(Box::new(stage));
+        self
+    }
+
+    /// Audit each run to `sink`.
+    pub fn audit(mut self, sink: &'a dyn AuditSink) -> Self {
+        self.audit = Some(sink);
+        self
+    }
+
+    /// Run every stage over `tx`, stopping after the policy stages if one
+    /// holds it for approval. On error `tx` keeps what the earlier stages
+    /// set, e.g. the signature of a transfer the chain refused.
+    pub async fn run(&self, tx: &mut PendingTransaction) -> Result<(), E> {
+        let (stage, result) = match self.run_stages(tx).await {
+            Ok(()) => (None, Ok(())),
+            Err((stage, err)) => (Some(stage), Err(err)),
+        };
+        if let Some(sink) = self.audit.filter(|_| !tx.request.dry_run) {
+            if let Some(event) = transfer_event(tx, stage) {
+                audit::emit(sink, event).await;
+            }
+        }
+        result
+    }
+
+    /// The stages in order, each with the name it is audited under.
+    async fn run_stages(&self, tx: &mut PendingTransaction) -> Result<(), (&'static str, E)> {
+        let checks = self
+            .validation
+            .iter()
+            .map(|stage| ("validation", stage))
+            .chain(self.policy.iter().map(|stage| ("policy", stage)));
+        for (name, stage) in checks {
+            stage.run(tx).await.map_err(|err| (name, err))?;
+        }
+        if tx.approval_id.is_some() {
+            return Ok(());
+        }
+        let stages = [
+            ("signing", &self.signing),
+            ("broadcast", &self.broadcast),
+            ("persistence", &self.persistence),
+        ];
+        for (name, stage) in stages {
+            if let Some(stage) = stage {
+                stage.run(tx).await.map_err(|err| (name, err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The audit event for a run over `tx` that stopped in `stopped_at`, or
+/// finished; `None` for a run that neither held nor submitted it.
+fn transfer_event(tx: &PendingTransaction, stopped_at: Option<&'static str>) -> Option<AuditEvent> {
+    let outcome = match (stopped_at, &tx.approval_id, &tx.submitted) {
+        (Some(stage), _, _) => TransferOutcome::Stopped { stage },
+        (None, Some(approval_id), _) => TransferOutcome::Held {
+            approval_id: approval_id.clone(),
+        },
+        (None, None, Some(submitted)) => TransferOutcome::Submitted {
+            tx_hash: submitted.tx_hash.clone(),
+            accepted: submitted.accepted,
+        },
+        (None, None, None) => return None,
+    };
+    let request = &tx.request;
+    Some(AuditEvent::Transfer {
+        from: request.from.clone(),
+        to: request.to.clone(),
+        amount: request.amount.clone(),
+        asset: request.asset.clone(),
+        chain: request.chain.clone(),
+        outcome,
+    })
+}
+
+/// Signs with the custodied key of the transfer's source wallet.
+pub struct SignWithProvider<'a, P>(pub &'a P);
+
+#[async_trait]
+impl<P: SignerProvider> TransactionStage<anyhow::Error> for SignWithProvider<'_, P> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<()> {
+        let signer = self
+            .0
+            .signer_for(&tx.request.from)
+            .await?
+            .ok_or_else(|| anyhow!("no custodied key for wallet {}", tx.request.from))?;
+        let payload = tx.canonical_payload()?;
+        tx.signature = Some(signer.sign_in_domain(
+            &tx.signing_domain,
+            payload.as_bytes(),
+            SignPurpose::Transaction,
+        )?);
+        Ok(())
+    }
+}
+
+/// Submits the signed transfer to the adapter registered for its chain.
+pub struct SubmitToRegistry<'a>(pub &'a ChainRegistry);
+
+#[async_trait]
+impl TransactionStage<anyhow::Error> for SubmitToRegistry<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<()> {
+        let adapter = self
+            .0
+            .adapter(&tx.request.chain)
+            .ok_or_else(|| anyhow!("unsupported chain: {}", tx.request.chain))?;
+        let signed_payload = tx
+            .signature_hex()
+            .ok_or_else(|| anyhow!("transfer is not signed"))?;
+        // Adapters work in base units; bounding the amount by the asset's
+        // decimals is left to a validation stage that knows them.
+        let amount = Amount::new(Amount::parse_base_units(&tx.request.amount)?, 0);
+        tx.submitted = Some(
+            adapter
+                .submit_transaction(SubmitTxRequest {
+                    from: WalletAddress(tx.request.from.clone()),
+                    to: WalletAddress(tx.request.to.clone()),
+                    amount,
+                    asset: AssetSymbol(tx.request.asset.clone()),
+                    chain: ChainId(tx.request.chain.clone()),
+                    signed_payload,
+                    chain_payload: None,
+                })
+                .await?,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WalletCore;
+    use kc_chain_client::{BalanceResult, ChainAdapter, TxStatusRequest, TxStatusResult};
+    use kc_crypto::{Ed25519Signer, encrypt_key_material};
+    use kc_storage::{InMemoryKeystore, Keystore};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct RecordingAdapter {
+        submitted: Mutex<Vec<SubmitTxRequest>>,
+    }
+
+    #[async_trait]
+    impl ChainAdapter for RecordingAdapter {
+        fn chain_id(&self) -> &str {
+            "flowcortex-l1"
+        }
+
+        async fn get_balance(&self, _: &WalletAddress, _: &AssetSymbol) -> Result<BalanceResult> {
+            Err(anyhow!("not used by pipeline tests"))
+        }
+
+        async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+            self.submitted.lock().unwrap().push(req);
+            Ok(SubmitTxResult {
+                tx_hash: "0xabc".to_owned(),
+                accepted: true,
+            })
+        }
+
+        async fn get_transaction_status(&self, _: TxStatusRequest) -> Result<TxStatusResult> {
+            Err(anyhow!("not used by pipeline tests"))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<kc_storage::AuditEventRecord>>);
+
+    #[async_trait]
+    impl AuditSink for RecordingSink {
+        async fn record(&self, event: kc_storage::AuditEventRecord) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    struct RejectZero;
+
+    #[async_trait]
+    impl TransactionStage<anyhow::Error> for RejectZero {
+        async fn run(&self, tx: &mut PendingTransaction) -> Result<()> {
+            anyhow::ensure!(tx.request.amount != "0", "amount must be positive");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn core_pipeline_signs_with_the_source_wallet_key_and_submits() {
+        let signer = Ed25519Signer::new_random();
+        let wallet = signer.wallet_address();
+        let keystore = Arc::new(InMemoryKeystore::default());
+        keystore
+            .save_encrypted_key(
+                &wallet,
+                encrypt_key_material(&signer.secret_key_bytes(), "test-key").unwrap(),
+            )
+            .await
+            .unwrap();
+        let adapter = Arc::new(RecordingAdapter::default());
+        let mut registry = ChainRegistry::default();
+        registry.register(adapter.clone());
+        let audit = Arc::new(RecordingSink::default());
+        let core = WalletCore::new(
+            crate::KeystoreSignerProvider::new(Arc::clone(&keystore), Arc::from("test-key")),
+            keystore,
+            registry,
+        )
+        .with_audit_sink(audit.clone());
+        let pipeline = core.transfer_pipeline().validate(RejectZero);
+        let request = |amount: &str| WalletSubmitRequest {
+            from: wallet.clone(),
+            to: format!("0x{}", "1".repeat(40)),
+            amount: amount.to_owned(),
+            asset: "PROOF".to_owned(),
+            chain: "flowcortex-l1".to_owned(),
+            nonce: 1,
+            signed_payload: None,
+            expires_at_epoch_ms: None,
+            memo: None,
+            dry_run: false,
+        };
+
+        let mut rejected = PendingTransaction::new(request("0"), SigningDomain::default());
+        assert!(pipeline.run(&mut rejected).await.is_err());
+        assert!(rejected.signature.is_none(), "validation runs before signing");
+
+        let mut tx = PendingTransaction::new(request("25"), SigningDomain::default());
+        pipeline.run(&mut tx).await.expect("pipeline should run");
+        assert_eq!(tx.submitted.as_ref().map(|result| result.accepted), Some(true));
+        let payload = tx.canonical_payload().unwrap();
+        assert!(signer
+            .public_key()
+            .verify_in_domain(
+                &SigningDomain::default(),
+                payload.as_bytes(),
+                SignPurpose::Transaction,
+                tx.signature.as_deref().unwrap(),
+            )
+            .unwrap());
+        let submitted = adapter.submitted.lock().unwrap();
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(Some(&submitted[0].signed_payload), tx.signature_hex().as_ref());
+
+        let audited: Vec<_> = audit
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| (event.event_type.clone(), event.outcome.clone(), event.message.clone()))
+            .collect();
+        assert_eq!(
+            audited,
+            [
+                (
+                    "transfer_submit".to_owned(),
+                    "failed".to_owned(),
+                    Some(format!("0 PROOF to 0x{}; stopped at validation", "1".repeat(40))),
+                ),
+                (
+                    "transfer_submit".to_owned(),
+                    "accepted".to_owned(),
+                    Some(format!("25 PROOF to 0x{}; tx 0xabc", "1".repeat(40))),
+                ),
+            ]
+        );
+    }
+}
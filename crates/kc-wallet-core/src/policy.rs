@@ -0,0 +1,181 @@
+//! Policy checks made before a wallet's key signs.
+//!
+//! A [`SigningPolicy`] looks at what is about to be signed and allows it,
+//! denies it, or holds it for approval. [`WalletPolicy`] combines the
+//! built-in policies a [`WalletPolicyRecord`] configures:
+//! [`SpendingLimitPolicy`], [`DestinationPolicy`] and [`PurposePolicy`].
+
+use kc_api_types::SignPurpose;
+use kc_storage::{SpendingLimit, WalletPolicyRecord};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A transfer about to be signed.
+#[derive(Debug, Clone, Copy)]
+pub struct TransferDetails<'a> {
+    pub to: &'a str,
+    pub asset: &'a str,
+    pub chain: &'a str,
+    /// In base units.
+    pub amount: u128,
+}
+
+/// What a [`SigningPolicy`] is asked about.
+#[derive(Debug, Clone, Copy)]
+pub struct PolicyContext<'a> {
+    pub wallet_address: &'a str,
+    pub purpose: &'a SignPurpose,
+    /// Set when the payload is a transfer.
+    pub transfer: Option<TransferDetails<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    Allow,
+    Deny(String),
+    /// May be signed once someone approves it, for the reason given.
+    RequireApproval(String),
+}
+
+impl PolicyDecision {
+    /// Deny over approval over allow; between equals, `self` wins.
+    pub fn and(self, other: PolicyDecision) -> PolicyDecision {
+        match (self, other) {
+            (deny @ Self::Deny(_), _) | (_, deny @ Self::Deny(_)) => deny,
+            (approval @ Self::RequireApproval(_), _) | (_, approval @ Self::RequireApproval(_)) => {
+                approval
+            }
+            (Self::Allow, Self::Allow) => Self::Allow,
+        }
+    }
+}
+
+pub trait SigningPolicy: Send + Sync {
+    fn evaluate(&self, ctx: &PolicyContext<'_>) -> PolicyDecision;
+}
+
+/// Caps transfers per asset, and holds large ones for approval.
+#[derive(Debug, Clone, Default)]
+pub struct SpendingLimitPolicy {
+    pub limits: BTreeMap<String, SpendingLimit>,
+}
+
+impl SigningPolicy for SpendingLimitPolicy {
+    fn evaluate(&self, ctx: &PolicyContext<'_>) -> PolicyDecision {
+        if self.limits.is_empty() {
+            return PolicyDecision::Allow;
+        }
+        let Some(transfer) = ctx.transfer else {
+            return unchecked_transaction(ctx, "spending limits");
+        };
+        let Some(limit) = self.limits.get(transfer.asset) else {
+            return PolicyDecision::Allow;
+        };
+        if let Some(max) = limit.max_amount.filter(|max| transfer.amount > *max) {
+            return PolicyDecision::Deny(format!(
+                "amount exceeds the wallet's limit of {max} {}",
+                transfer.asset
+            ));
+        }
+        if let Some(threshold) = limit.approval_above.filter(|above| transfer.amount > *above) {
+            return PolicyDecision::RequireApproval(format!(
+                "amount is above the wallet's approval threshold of {threshold} {}",
+                transfer.asset
+            ));
+        }
+        PolicyDecision::Allow
+    }
+}
+
+/// Restricts where transfers may go. The deny list wins over the allow
+/// list; an empty allow list allows every destination not denied.
+#[derive(Debug, Clone, Default)]
+pub struct DestinationPolicy {
+    pub allowed: BTreeSet<String>,
+    pub denied: BTreeSet<String>,
+}
+
+impl SigningPolicy for DestinationPolicy {
+    fn evaluate(&self, ctx: &PolicyContext<'_>) -> PolicyDecision {
+        if self.allowed.is_empty() && self.denied.is_empty() {
+            return PolicyDecision::Allow;
+        }
+        let Some(transfer) = ctx.transfer else {
+            return unchecked_transaction(ctx, "destination lists");
+        };
+        if self.denied.contains(transfer.to) {
+            return PolicyDecision::Deny(format!("destination {} is denied", transfer.to));
+        }
+        if !self.allowed.is_empty() && !self.allowed.contains(transfer.to) {
+            return PolicyDecision::Deny(format!(
+                "destination {} is not on the wallet's allow list",
+                transfer.to
+            ));
+        }
+        PolicyDecision::Allow
+    }
+}
+
+/// A transaction-purpose payload that is not a transfer, such as a raw
+/// `/wallet/sign` payload, cannot be checked against `limits`, so a wallet
+/// with them refuses it.
+fn unchecked_transaction(ctx: &PolicyContext<'_>, limits: &str) -> PolicyDecision {
+    if *ctx.purpose == SignPurpose::Transaction {
+        PolicyDecision::Deny(format!(
+            "the wallet has {limits}; sign transfers by submitting them"
+        ))
+    } else {
+        PolicyDecision::Allow
+    }
+}
+
+/// Limits the purposes the key signs for; empty allows all.
+#[derive(Debug, Clone, Default)]
+pub struct PurposePolicy {
+    pub allowed: BTreeSet<String>,
+}
+
+impl SigningPolicy for PurposePolicy {
+    fn evaluate(&self, ctx: &PolicyContext<'_>) -> PolicyDecision {
+        if self.allowed.is_empty() || self.allowed.contains(ctx.purpose.as_str()) {
+            return PolicyDecision::Allow;
+        }
+        PolicyDecision::Deny(format!(
+            "the wallet's key may not sign for purpose {}",
+            ctx.purpose.as_str()
+        ))
+    }
+}
+
+/// Every built-in policy a wallet's [`WalletPolicyRecord`] configures.
+#[derive(Debug, Clone, Default)]
+pub struct WalletPolicy {
+    pub spending: SpendingLimitPolicy,
+    pub destinations: DestinationPolicy,
+    pub purposes: PurposePolicy,
+}
+
+impl From<&WalletPolicyRecord> for WalletPolicy {
+    fn from(record: &WalletPolicyRecord) -> Self {
+        Self {
+            spending: SpendingLimitPolicy {
+                limits: record.spending_limits.clone(),
+            },
+            destinations: DestinationPolicy {
+                allowed: record.allowed_destinations.clone(),
+                denied: record.denied_destinations.clone(),
+            },
+            purposes: PurposePolicy {
+                allowed: record.allowed_purposes.clone(),
+            },
+        }
+    }
+}
+
+impl SigningPolicy for WalletPolicy {
+    fn evaluate(&self, ctx: &PolicyContext<'_>) -> PolicyDecision {
+        self.purposes
+            .evaluate(ctx)
+            .and(self.destinations.evaluate(ctx))
+            .and(self.spending.evaluate(ctx))
+    }
+}
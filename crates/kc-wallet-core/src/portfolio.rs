@@ -0,0 +1,199 @@
+//! One wallet's holdings across every chain.
+//!
+//! [`portfolio`] asks each chain for the wallet's balance of every asset
+//! listed for it, all chains at once. A chain that fails or is slower than
+//! the timeout costs only its own assets, which are reported in `errors`
+//! instead of failing the whole portfolio.
+
+use futures_util::future::join_all;
+use kc_api_types::{
+    Amount, AssetHolding, AssetSymbol, ChainAssetInfo, HoldingError, WalletAddress,
+    WalletPortfolioResponse,
+};
+use kc_chain_client::ChainAdapter;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long one chain may take for all of a wallet's assets, unless
+/// [`crate::WalletCore::with_portfolio_timeout`] says otherwise.
+pub const DEFAULT_PORTFOLIO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A chain and the assets to look up on it.
+pub struct PortfolioSource {
+    pub adapter: Arc<dyn ChainAdapter>,
+    pub assets: Vec<ChainAssetInfo>,
+}
+
+/// The holdings of `wallet_address` on every source, each source given
+/// `timeout`. Holdings keep the order of `sources` and their assets.
+pub async fn portfolio(
+    wallet_address: &str,
+    sources: Vec<PortfolioSource>,
+    timeout: Duration,
+) -> WalletPortfolioResponse {
+    let wallet = WalletAddress(wallet_address.to_owned());
+    let chains = join_all(
+        sources
+            .iter()
+            .map(|source| chain_holdings(&wallet, source, timeout)),
+    )
+    .await;
+
+    let mut holdings = Vec::new();
+    let mut errors = Vec::new();
+    for lookups in chains {
+        for lookup in lookups {
+            match lookup {
+                Ok(holding) => holdings.push(holding),
+                Err(error) => errors.push(error),
+            }
+        }
+    }
+    WalletPortfolioResponse {
+        wallet_address: wallet_address.to_owned(),
+        holdings,
+        as_of_epoch_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis())
+            .unwrap_or_default(),
+        errors,
+    }
+}
+
+async fn chain_holdings(
+    wallet: &WalletAddress,
+    source: &PortfolioSource,
+    timeout: Duration,
+) -> Vec<Result<AssetHolding, HoldingError>> {
+    let chain = source.adapter.chain_id();
+    let lookups = join_all(source.assets.iter().map(|asset| async move {
+        let balance = source
+            .adapter
+            .get_balance(wallet, &AssetSymbol(asset.symbol.clone()))
+            .await
+            .map_err(|err| holding_error(chain, asset, format!("{err:#}")))?;
+        Ok(AssetHolding::new(
+            asset.symbol.clone(),
+            chain.to_owned(),
+            Amount::new(balance.amount.base_units(), asset.decimals),
+        ))
+    }));
+    match tokio::time::timeout(timeout, lookups).await {
+        Ok(lookups) => lookups,
+        Err(_) => {
+            let error = format!("{chain} did not answer within {}ms", timeout.as_millis());
+            source
+                .assets
+                .iter()
+                .map(|asset| Err(holding_error(chain, asset, error.clone())))
+                .collect()
+        }
+    }
+}
+
+fn holding_error(chain: &str, asset: &ChainAssetInfo, error: String) -> HoldingError {
+    HoldingError {
+        asset: asset.symbol.clone(),
+        chain: chain.to_owned(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DynWalletCore, KeystoreSignerProvider};
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
+    use kc_api_types::ChainId;
+    use kc_chain_client::{
+        BalanceResult, ChainRegistry, SubmitTxRequest, SubmitTxResult, TxStatusRequest, TxStatusResult,
+    };
+    use kc_storage::InMemoryKeystore;
+
+    enum Behaviour {
+        Holds(u128),
+        Fails,
+        Hangs,
+    }
+
+    struct FixedAdapter(&'static str, Behaviour);
+
+    #[async_trait]
+    impl ChainAdapter for FixedAdapter {
+        fn chain_id(&self) -> &str {
+            self.0
+        }
+
+        async fn get_balance(&self, wallet: &WalletAddress, asset: &AssetSymbol) -> Result<BalanceResult> {
+            let amount = match self.1 {
+                Behaviour::Holds(amount) => amount,
+                Behaviour::Fails => return Err(anyhow!("node unavailable")),
+                Behaviour::Hangs => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    0
+                }
+            };
+            Ok(BalanceResult {
+                wallet_address: wallet.clone(),
+                chain: ChainId(self.0.to_owned()),
+                asset: asset.clone(),
+                amount: Amount::new(amount, 0),
+            })
+        }
+
+        async fn submit_transaction(&self, _: SubmitTxRequest) -> Result<SubmitTxResult> {
+            Err(anyhow!("not used by portfolio tests"))
+        }
+
+        async fn get_transaction_status(&self, _: TxStatusRequest) -> Result<TxStatusResult> {
+            Err(anyhow!("not used by portfolio tests"))
+        }
+    }
+
+    fn asset(symbol: &str, decimals: u8) -> ChainAssetInfo {
+        ChainAssetInfo {
+            symbol: symbol.to_owned(),
+            asset_type: "native".to_owned(),
+            decimals,
+            fee_payment_support: true,
+            contract: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn portfolio_keeps_answers_from_healthy_chains() {
+        let keystore = Arc::new(InMemoryKeystore::default());
+        let core = DynWalletCore::new_dyn(
+            Arc::new(KeystoreSignerProvider::new(Arc::clone(&keystore), Arc::from("test-key"))),
+            keystore,
+            ChainRegistry::default(),
+        )
+        .with_chain(Arc::new(FixedAdapter("a-chain", Behaviour::Holds(1_500))))
+        .with_chain(Arc::new(FixedAdapter("b-chain", Behaviour::Fails)))
+        .with_chain(Arc::new(FixedAdapter("c-chain", Behaviour::Hangs)))
+        .with_chain(Arc::new(FixedAdapter("unlisted", Behaviour::Holds(7))))
+        .with_portfolio_assets("a-chain", vec![asset("PROOF", 2), asset("FloweR", 6)])
+        .with_portfolio_assets("b-chain", vec![asset("ETH", 18)])
+        .with_portfolio_assets("c-chain", vec![asset("ATOM", 6)])
+        .with_portfolio_timeout(Duration::from_millis(50));
+
+        let portfolio = core.portfolio("0xabc").await;
+        assert_eq!(portfolio.wallet_address, "0xabc");
+        assert_eq!(
+            portfolio.holdings,
+            vec![
+                AssetHolding::new("PROOF".to_owned(), "a-chain".to_owned(), Amount::new(1_500, 2)),
+                AssetHolding::new("FloweR".to_owned(), "a-chain".to_owned(), Amount::new(1_500, 6)),
+            ]
+        );
+        let failed: Vec<_> = portfolio
+            .errors
+            .iter()
+            .map(|error| (error.chain.as_str(), error.asset.as_str()))
+            .collect();
+        assert_eq!(failed, vec![("b-chain", "ETH"), ("c-chain", "ATOM")]);
+        assert!(portfolio.errors[0].error.contains("node unavailable"));
+        assert!(portfolio.errors[1].error.contains("did not answer within 50ms"));
+    }
+}
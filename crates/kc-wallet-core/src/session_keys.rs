@@ -0,0 +1,317 @@
+//! Session keys derived from a wallet key.
+//!
+//! [`crate::WalletCore::issue_session_key`] derives a fresh keypair from the
+//! wallet key, has the wallet key sign a [`SessionKeyAttestation`] for it and
+//! registers it in a [`SessionKeyStore`]. Until it expires,
+//! [`crate::WalletCore::sign_with_session_key`] then signs the attested
+//! purposes with the session key alone, leaving the wallet key untouched.
+
+use anyhow::Result;
+use kc_api_types::{SessionKeyAttestation, SignPurpose};
+use kc_crypto::encoding::to_hex;
+use kc_crypto::{Ed25519Signer, Signer, SigningDomain, decrypt_key_material, encrypt_key_material};
+use kc_storage::{RocksDbKeystore, SessionKeyRecord};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// Lifetime of a session key when the caller does not ask for one.
+pub const DEFAULT_SESSION_KEY_TTL: Duration = Duration::from_secs(60 * 60);
+/// Longest lifetime a session key may have.
+pub const MAX_SESSION_KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where issued session keys are registered.
+pub trait SessionKeyStore: Send + Sync {
+    fn save(&self, record: &SessionKeyRecord) -> Result<()>;
+    fn load(&self, wallet_address: &str, session_key_id: &str) -> Result<Option<SessionKeyRecord>>;
+}
+
+impl SessionKeyStore for RocksDbKeystore {
+    fn save(&self, record: &SessionKeyRecord) -> Result<()> {
+        self.save_session_key(record)
+    }
+
+    fn load(&self, wallet_address: &str, session_key_id: &str) -> Result<Option<SessionKeyRecord>> {
+        self.load_session_key(wallet_address, session_key_id)
+    }
+}
+
+/// A registered session key, as the caller learns of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuedSessionKey {
+    pub session_key_id: String,
+    pub attestation: SessionKeyAttestation,
+}
+
+#[derive(Debug)]
+pub enum SessionKeyError {
+    /// The request for a session key cannot be met, for the reason given.
+    Invalid(String),
+    NotFound,
+    Expired,
+    /// The session key was not issued for this purpose.
+    PurposeNotAttested(String),
+    Other(anyhow::Error),
+}
+
+impl fmt::Display for SessionKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(reason) => f.write_str(reason),
+            Self::NotFound => f.write_str("session key not found"),
+            Self::Expired => f.write_str("session key has expired"),
+            Self::PurposeNotAttested(purpose) => {
+                write!(f, "session key may not sign for purpose '{purpose}'")
+            }
+            Self::Other(err) => write!(f, "{err:#}"),
+        }
+    }
+}
+
+impl std::error::Error for SessionKeyError {}
+
+impl From<anyhow::Error> for SessionKeyError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::Other(err)
+    }
+}
+
+/// The store session keys are registered in, and the key their secrets
+/// are encrypted with.
+pub(crate) struct SessionKeys {
+    pub(crate) store: Arc<dyn SessionKeyStore>,
+    pub(crate) encryption_key: Arc<str>,
+}
+
+impl SessionKeys {
+    /// Derive a session key from `wallet_key`, attest it and register it.
+    pub(crate) fn issue(
+        &self,
+        wallet_key: &Ed25519Signer,
+        domain: &SigningDomain,
+        ttl: Duration,
+        purposes: Vec<SignPurpose>,
+    ) -> Result<IssuedSessionKey, SessionKeyError> {
+        if ttl.is_zero() || ttl > MAX_SESSION_KEY_TTL {
+            return Err(SessionKeyError::Invalid(format!(
+                "session key lifetime must be positive and at most {} seconds",
+                MAX_SESSION_KEY_TTL.as_secs()
+            )));
+        }
+        if purposes.is_empty() {
+            return Err(SessionKeyError::Invalid("at least one purpose is required".to_owned()));
+        }
+        for purpose in &purposes {
+            if *purpose == SignPurpose::Transaction {
+                return Err(SessionKeyError::Invalid(
+                    "session keys cannot sign transactions".to_owned(),
+                ));
+            }
+            domain
+                .check_purpose(purpose)
+                .map_err(|err| SessionKeyError::Invalid(err.to_string()))?;
+        }
+
+        let session_key_id = Uuid::new_v4().to_string();
+        let session_key = wallet_key.derive_session_key(session_key_id.as_bytes());
+        let issued_at_epoch_ms = now_epoch_ms()?;
+        let mut attestation = SessionKeyAttestation {
+            wallet_address: wallet_key.wallet_address(),
+            session_public_key: session_key.public_key_hex(),
+            purposes,
+            issued_at_epoch_ms,
+            expires_at_epoch_ms: issued_at_epoch_ms + ttl.as_millis(),
+            signature: String::new(),
+        };
+        attestation.signature = to_hex(&wallet_key.sign_in_domain(
+            domain,
+            attestation.canonical_payload().as_bytes(),
+            SignPurpose::Custom(SessionKeyAttestation::SIGN_PURPOSE.to_owned()),
+        )?);
+
+        self.store.save(&SessionKeyRecord {
+            session_key_id: session_key_id.clone(),
+            wallet_address: attestation.wallet_address.clone(),
+            public_key: attestation.session_public_key.clone(),
+            encrypted_secret_key: encrypt_key_material(
+                session_key.secret_key_material().expose_secret(),
+                &self.encryption_key,
+            )?,
+            purposes: attestation
+                .purposes
+                .iter()
+                .map(|purpose| purpose.as_str().to_owned())
+                .collect(),
+            issued_at_epoch_ms,
+            expires_at_epoch_ms: attestation.expires_at_epoch_ms,
+            attestation_signature: attestation.signature.clone(),
+        })?;
+        Ok(IssuedSessionKey {
+            session_key_id,
+            attestation,
+        })
+    }
+
+    /// The session key of `wallet_address`, if it is live and attested for
+    /// `purpose`, with its attestation.
+    pub(crate) fn signer(
+        &self,
+        wallet_address: &str,
+        session_key_id: &str,
+        purpose: &SignPurpose,
+    ) -> Result<(Ed25519Signer, SessionKeyAttestation), SessionKeyError> {
+        let record = self
+            .store
+            .load(wallet_address, session_key_id)?
+            .ok_or(SessionKeyError::NotFound)?;
+        if now_epoch_ms()? >= record.expires_at_epoch_ms {
+            return Err(SessionKeyError::Expired);
+        }
+        if !record.purposes.iter().any(|name| name == purpose.as_str()) {
+            return Err(SessionKeyError::PurposeNotAttested(purpose.as_str().to_owned()));
+        }
+        let signer = Ed25519Signer::from_key_material(&decrypt_key_material(
+            &record.encrypted_secret_key,
+            &self.encryption_key,
+        )?);
+        let attestation = SessionKeyAttestation {
+            wallet_address: record.wallet_address,
+            session_public_key: record.public_key,
+            purposes: record.purposes.into_iter().map(SignPurpose::from).collect(),
+            issued_at_epoch_ms: record.issued_at_epoch_ms,
+            expires_at_epoch_ms: record.expires_at_epoch_ms,
+            signature: record.attestation_signature,
+        };
+        Ok((signer, attestation))
+    }
+}
+
+fn now_epoch_ms() -> Result<u128> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DynWalletCore, KeystoreSignerProvider};
+    use kc_chain_client::ChainRegistry;
+    use kc_crypto::Ed25519PublicKey;
+    use kc_storage::{InMemoryKeystore, Keystore};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, SessionKeyRecord>>);
+
+    impl SessionKeyStore for MemoryStore {
+        fn save(&self, record: &SessionKeyRecord) -> Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(record.session_key_id.clone(), record.clone());
+            Ok(())
+        }
+
+        fn load(&self, wallet_address: &str, session_key_id: &str) -> Result<Option<SessionKeyRecord>> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .get(session_key_id)
+                .filter(|record| record.wallet_address == wallet_address)
+                .cloned())
+        }
+    }
+
+    #[tokio::test]
+    async fn session_keys_sign_only_what_the_wallet_attested() {
+        let wallet_key = Ed25519Signer::new_random();
+        let wallet = wallet_key.wallet_address();
+        let keystore = Arc::new(InMemoryKeystore::default());
+        keystore
+            .save_encrypted_key(
+                &wallet,
+                encrypt_key_material(&wallet_key.secret_key_bytes(), "test-key").unwrap(),
+            )
+            .await
+            .unwrap();
+        let core = DynWalletCore::new_dyn(
+            Arc::new(KeystoreSignerProvider::new(Arc::clone(&keystore), Arc::from("test-key"))),
+            keystore,
+            ChainRegistry::default(),
+        )
+        .with_session_keys(Arc::new(MemoryStore::default()), Arc::from("session-key"));
+        let domain = SigningDomain::default();
+
+        let issued = core
+            .issue_session_key(&wallet, DEFAULT_SESSION_KEY_TTL, vec![SignPurpose::Auth])
+            .await
+            .unwrap()
+            .expect("wallet has a custodied key");
+        let attestation = &issued.attestation;
+        assert_eq!(attestation.wallet_address, wallet);
+        assert_eq!(
+            attestation.expires_at_epoch_ms - attestation.issued_at_epoch_ms,
+            DEFAULT_SESSION_KEY_TTL.as_millis()
+        );
+        assert!(wallet_key
+            .verify_in_domain(
+                &domain,
+                attestation.canonical_payload().as_bytes(),
+                SignPurpose::Custom(SessionKeyAttestation::SIGN_PURPOSE.to_owned()),
+                &kc_crypto::encoding::from_hex(&attestation.signature).unwrap(),
+            )
+            .unwrap());
+
+        let (signature, attested) = core
+            .sign_with_session_key(&wallet, &issued.session_key_id, b"challenge", SignPurpose::Auth)
+            .await
+            .unwrap();
+        assert_eq!(&attested, attestation);
+        let session_key = Ed25519PublicKey::from_hex(&attestation.session_public_key).unwrap();
+        assert!(session_key
+            .verify_in_domain(&domain, b"challenge", SignPurpose::Auth, &signature)
+            .unwrap());
+        assert!(!wallet_key
+            .verify_in_domain(&domain, b"challenge", SignPurpose::Auth, &signature)
+            .unwrap());
+
+        assert!(matches!(
+            core.sign_with_session_key(&wallet, &issued.session_key_id, b"x", SignPurpose::Proof)
+                .await,
+            Err(SessionKeyError::PurposeNotAttested(purpose)) if purpose == "proof"
+        ));
+        assert!(matches!(
+            core.sign_with_session_key("0xother", &issued.session_key_id, b"x", SignPurpose::Auth)
+                .await,
+            Err(SessionKeyError::NotFound)
+        ));
+        assert!(matches!(
+            core.issue_session_key(&wallet, DEFAULT_SESSION_KEY_TTL, vec![SignPurpose::Transaction])
+                .await,
+            Err(SessionKeyError::Invalid(_))
+        ));
+        assert!(matches!(
+            core.issue_session_key(&wallet, MAX_SESSION_KEY_TTL * 2, vec![SignPurpose::Auth]).await,
+            Err(SessionKeyError::Invalid(_))
+        ));
+        assert!(core
+            .issue_session_key("0xmissing", DEFAULT_SESSION_KEY_TTL, vec![SignPurpose::Auth])
+            .await
+            .unwrap()
+            .is_none());
+
+        let brief = core
+            .issue_session_key(&wallet, Duration::from_millis(1), vec![SignPurpose::Auth])
+            .await
+            .unwrap()
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(matches!(
+            core.sign_with_session_key(&wallet, &brief.session_key_id, b"x", SignPurpose::Auth)
+                .await,
+            Err(SessionKeyError::Expired)
+        ));
+    }
+}
@@ -0,0 +1,48 @@
+//! Signers for custodied wallets, looked up by address.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use kc_crypto::{Ed25519Signer, decrypt_key_material};
+use kc_storage::Keystore;
+use std::sync::Arc;
+
+/// Hands out the signer for any wallet whose key is custodied.
+#[async_trait]
+pub trait SignerProvider: Send + Sync {
+    /// `None` if `wallet_address` has no custodied key.
+    async fn signer_for(&self, wallet_address: &str) -> Result<Option<Ed25519Signer>>;
+}
+
+#[async_trait]
+impl<P: SignerProvider + ?Sized> SignerProvider for Arc<P> {
+    async fn signer_for(&self, wallet_address: &str) -> Result<Option<Ed25519Signer>> {
+        (**self).signer_for(wallet_address).await
+    }
+}
+
+/// Keys held encrypted in a [`Keystore`] under a master encryption key, as
+/// `encrypt_key_material` writes them.
+pub struct KeystoreSignerProvider<K> {
+    keystore: K,
+    encryption_key: Arc<str>,
+}
+
+impl<K: Keystore> KeystoreSignerProvider<K> {
+    pub fn new(keystore: K, encryption_key: Arc<str>) -> Self {
+        Self {
+            keystore,
+            encryption_key,
+        }
+    }
+}
+
+#[async_trait]
+impl<K: Keystore> SignerProvider for KeystoreSignerProvider<K> {
+    async fn signer_for(&self, wallet_address: &str) -> Result<Option<Ed25519Signer>> {
+        let Some(encrypted_key) = self.keystore.load_encrypted_key(wallet_address).await? else {
+            return Ok(None);
+        };
+        let secret_key = decrypt_key_material(&encrypted_key, &self.encryption_key)?;
+        Ok(Some(Ed25519Signer::from_key_material(&secret_key)))
+    }
+}
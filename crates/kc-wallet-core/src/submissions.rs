@@ -0,0 +1,420 @@
+//! Watching submitted transfers until they settle.
+//!
+//! A broadcast transfer is handed to a [`SubmissionTracker`], which keeps a
+//! [`TrackedTxRecord`] for it. Each [`SubmissionTracker::poll`] asks the
+//! chain about the transfers due for a check, doubling the wait between
+//! checks of the same transfer, and saves its [`SubmittedTxRecord`] when the
+//! status changes; the RocksDB keystore turns that save into the
+//! `TxStatusChanged` event that webhooks and the event broker follow.
+//!
+//! A transfer the chain has lost (`not_found` or `dropped`) is broadcast
+//! again, unchanged, while it is within the [`SubmissionPolicy`]'s resubmit
+//! window; after that it is marked `dropped`. Tracking ends once a transfer
+//! is `confirmed`, `failed` or `dropped`, or has been watched for longer
+//! than [`SubmissionPolicy::give_up_after`].
+
+use anyhow::{Result, anyhow};
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress};
+use kc_chain_client::{ChainAdapter, SubmitTxRequest, TxStatusRequest, TxStatusResult};
+use kc_storage::{RocksDbKeystore, SubmittedTxRecord, TrackedTxRecord, TxStatusChange};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Statuses a chain reports for a transfer it no longer knows about, which
+/// broadcasting it again may fix.
+const TRANSIENT_STATUSES: &[&str] = &["not_found", "dropped"];
+/// Statuses after which a transfer is no longer watched.
+const FINAL_STATUSES: &[&str] = &["confirmed", "failed", "dropped"];
+
+/// Where tracked transfers, and the tx records they update, are kept.
+pub trait SubmissionStore: Send + Sync {
+    fn track(&self, record: &TrackedTxRecord) -> Result<()>;
+    fn untrack(&self, tx_hash: &str) -> Result<()>;
+    fn tracked(&self) -> Result<Vec<TrackedTxRecord>>;
+    fn load_tx(&self, tx_hash: &str) -> Result<Option<SubmittedTxRecord>>;
+    fn save_tx(&self, record: &SubmittedTxRecord) -> Result<()>;
+}
+
+impl SubmissionStore for RocksDbKeystore {
+    fn track(&self, record: &TrackedTxRecord) -> Result<()> {
+        self.save_tracked_tx(record)
+    }
+
+    fn untrack(&self, tx_hash: &str) -> Result<()> {
+        self.delete_tracked_tx(tx_hash)
+    }
+
+    fn tracked(&self) -> Result<Vec<TrackedTxRecord>> {
+        self.list_tracked_txs()
+    }
+
+    fn load_tx(&self, tx_hash: &str) -> Result<Option<SubmittedTxRecord>> {
+        self.load_submitted_tx(tx_hash)
+    }
+
+    fn save_tx(&self, record: &SubmittedTxRecord) -> Result<()> {
+        self.save_submitted_tx(record)
+    }
+}
+
+/// How a [`SubmissionTracker`] paces its checks and when it resubmits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmissionPolicy {
+    /// Wait before the first check of a transfer.
+    pub initial_backoff: Duration,
+    /// Longest wait between two checks of a transfer.
+    pub max_backoff: Duration,
+    /// How long after it was first submitted a lost transfer is broadcast
+    /// again.
+    pub resubmit_window: Duration,
+    pub max_resubmissions: u32,
+    /// How long a transfer that never settles is watched.
+    pub give_up_after: Duration,
+}
+
+impl Default for SubmissionPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(5 * 60),
+            resubmit_window: Duration::from_secs(10 * 60),
+            max_resubmissions: 3,
+            give_up_after: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl SubmissionPolicy {
+    /// Wait after the `checks`-th check of a transfer.
+    fn backoff(&self, checks: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1_u32 << checks.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+/// A status change [`SubmissionTracker::poll`] saved.
+#[derive(Debug, Clone)]
+pub struct SubmissionUpdate {
+    pub previous_status: String,
+    pub record: SubmittedTxRecord,
+}
+
+/// Watches submitted transfers; see the module docs.
+pub struct SubmissionTracker {
+    store: Arc<dyn SubmissionStore>,
+    policy: SubmissionPolicy,
+}
+
+impl SubmissionTracker {
+    pub fn new(store: Arc<dyn SubmissionStore>, policy: SubmissionPolicy) -> Self {
+        Self { store, policy }
+    }
+
+    pub fn policy(&self) -> &SubmissionPolicy {
+        &self.policy
+    }
+
+    /// Start watching `submitted`, broadcast with `signed_payload` for an
+    /// asset with `decimals`.
+    pub fn track(&self, submitted: &SubmittedTxRecord, decimals: u8, signed_payload: &str) -> Result<()> {
+        self.store.track(&TrackedTxRecord {
+            tx_hash: submitted.tx_hash.clone(),
+            chain: submitted.chain.clone(),
+            from: submitted.from.clone(),
+            to: submitted.to.clone(),
+            asset: submitted.asset.clone(),
+            amount: submitted.amount.clone(),
+            decimals,
+            signed_payload: signed_payload.to_owned(),
+            submitted_at_epoch_ms: submitted.submitted_at_epoch_ms,
+            checks: 0,
+            resubmissions: 0,
+            next_check_at_epoch_ms: submitted.submitted_at_epoch_ms
+                + self.policy.initial_backoff.as_millis(),
+        })
+    }
+
+    /// Transfers being watched.
+    pub fn tracked(&self) -> Result<Vec<TrackedTxRecord>> {
+        self.store.tracked()
+    }
+
+    /// Check every transfer due by `now_epoch_ms` with the adapter
+    /// `adapter_for` gives for its chain, resubmitting lost ones, and return
+    /// the status changes saved. A chain that cannot be reached only puts
+    /// the next check off.
+    pub async fn poll<F>(&self, adapter_for: F, now_epoch_ms: u128) -> Result<Vec<SubmissionUpdate>>
+    where
+        F: Fn(&str) -> Option<Arc<dyn ChainAdapter>> + Sync,
+    {
+        let mut updates = Vec::new();
+        for mut tracked in self.store.tracked()? {
+            if tracked.next_check_at_epoch_ms > now_epoch_ms {
+                continue;
+            }
+            let Some(mut record) = self.store.load_tx(&tracked.tx_hash)? else {
+                self.store.untrack(&tracked.tx_hash)?;
+                continue;
+            };
+            let status = match adapter_for(&tracked.chain) {
+                Some(adapter) => self.check(adapter.as_ref(), &mut tracked, now_epoch_ms).await,
+                None => Err(anyhow!("chain '{}' is not enabled", tracked.chain)),
+            };
+
+            tracked.checks = tracked.checks.saturating_add(1);
+            tracked.next_check_at_epoch_ms =
+                now_epoch_ms + self.policy.backoff(tracked.checks).as_millis();
+            let previous_status = record.status.clone();
+            if let Ok(status) = status {
+                if status.status != record.status {
+                    record.status_history.push(TxStatusChange {
+                        status: status.status.clone(),
+                        at_epoch_ms: now_epoch_ms,
+                    });
+                }
+                record.status = status.status;
+                record.accepted = status.accepted;
+                record.block_height = status.block_height.or(record.block_height);
+                record.confirmations = status.confirmations.or(record.confirmations);
+                self.store.save_tx(&record)?;
+            }
+
+            let watched_for = now_epoch_ms.saturating_sub(tracked.submitted_at_epoch_ms);
+            if FINAL_STATUSES.contains(&record.status.as_str())
+                || watched_for >= self.policy.give_up_after.as_millis()
+            {
+                self.store.untrack(&tracked.tx_hash)?;
+            } else {
+                self.store.track(&tracked)?;
+            }
+            if record.status != previous_status {
+                updates.push(SubmissionUpdate {
+                    previous_status,
+                    record,
+                });
+            }
+        }
+        Ok(updates)
+    }
+
+    /// The transfer's status now: as the chain reports it, `resubmitted`
+    /// once a lost transfer has been broadcast again, or `dropped` once it
+    /// is lost for good.
+    async fn check(
+        &self,
+        adapter: &dyn ChainAdapter,
+        tracked: &mut TrackedTxRecord,
+        now_epoch_ms: u128,
+    ) -> Result<TxStatusResult> {
+        let status = adapter
+            .get_transaction_status(TxStatusRequest {
+                tx_hash: tracked.tx_hash.clone(),
+                chain: ChainId(tracked.chain.clone()),
+            })
+            .await?;
+        if !TRANSIENT_STATUSES.contains(&status.status.as_str()) {
+            return Ok(status);
+        }
+        let unsettled = |status: &str, accepted| TxStatusResult {
+            tx_hash: tracked.tx_hash.clone(),
+            status: status.to_owned(),
+            accepted,
+            block_height: None,
+            confirmations: None,
+        };
+
+        let in_window = now_epoch_ms
+            < tracked.submitted_at_epoch_ms + self.policy.resubmit_window.as_millis();
+        if !in_window || tracked.resubmissions >= self.policy.max_resubmissions {
+            return Ok(unsettled("dropped", false));
+        }
+        tracked.resubmissions += 1;
+        let resubmitted = adapter
+            .submit_transaction(SubmitTxRequest {
+                from: WalletAddress(tracked.from.clone()),
+                to: WalletAddress(tracked.to.clone()),
+                amount: Amount::new(Amount::parse_base_units(&tracked.amount)?, tracked.decimals),
+                asset: AssetSymbol(tracked.asset.clone()),
+                chain: ChainId(tracked.chain.clone()),
+                signed_payload: tracked.signed_payload.clone(),
+                chain_payload: None,
+            })
+            .await?;
+        Ok(unsettled("resubmitted", resubmitted.accepted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use kc_chain_client::{BalanceResult, SubmitTxResult};
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        tracked: Mutex<HashMap<String, TrackedTxRecord>>,
+        txs: Mutex<HashMap<String, SubmittedTxRecord>>,
+    }
+
+    impl SubmissionStore for MemoryStore {
+        fn track(&self, record: &TrackedTxRecord) -> Result<()> {
+            self.tracked
+                .lock()
+                .unwrap()
+                .insert(record.tx_hash.clone(), record.clone());
+            Ok(())
+        }
+
+        fn untrack(&self, tx_hash: &str) -> Result<()> {
+            self.tracked.lock().unwrap().remove(tx_hash);
+            Ok(())
+        }
+
+        fn tracked(&self) -> Result<Vec<TrackedTxRecord>> {
+            Ok(self.tracked.lock().unwrap().values().cloned().collect())
+        }
+
+        fn load_tx(&self, tx_hash: &str) -> Result<Option<SubmittedTxRecord>> {
+            Ok(self.txs.lock().unwrap().get(tx_hash).cloned())
+        }
+
+        fn save_tx(&self, record: &SubmittedTxRecord) -> Result<()> {
+            self.txs
+                .lock()
+                .unwrap()
+                .insert(record.tx_hash.clone(), record.clone());
+            Ok(())
+        }
+    }
+
+    /// Answers status queries from a script, `not_found` once it runs out.
+    #[derive(Default)]
+    struct ScriptedChain {
+        statuses: Mutex<VecDeque<&'static str>>,
+        resubmitted: Mutex<Vec<SubmitTxRequest>>,
+    }
+
+    #[async_trait]
+    impl ChainAdapter for ScriptedChain {
+        fn chain_id(&self) -> &str {
+            "flowcortex-l1"
+        }
+
+        async fn get_balance(&self, _: &WalletAddress, _: &AssetSymbol) -> Result<BalanceResult> {
+            Err(anyhow!("not used by submission tests"))
+        }
+
+        async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+            self.resubmitted.lock().unwrap().push(req);
+            Ok(SubmitTxResult {
+                tx_hash: "0xabc".to_owned(),
+                accepted: true,
+            })
+        }
+
+        async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+            let status = self.statuses.lock().unwrap().pop_front().unwrap_or("not_found");
+            Ok(TxStatusResult {
+                tx_hash: req.tx_hash,
+                status: status.to_owned(),
+                accepted: status != "not_found",
+                block_height: (status == "confirmed").then_some(7),
+                confirmations: (status == "confirmed").then_some(1),
+            })
+        }
+    }
+
+    fn submitted(tx_hash: &str) -> SubmittedTxRecord {
+        SubmittedTxRecord {
+            tx_hash: tx_hash.to_owned(),
+            status: "submitted".to_owned(),
+            accepted: true,
+            chain: "flowcortex-l1".to_owned(),
+            from: "0xa".to_owned(),
+            to: "0xb".to_owned(),
+            asset: "PROOF".to_owned(),
+            amount: "25".to_owned(),
+            submitted_at_epoch_ms: 0,
+            block_height: None,
+            confirmations: None,
+            status_history: Vec::new(),
+        }
+    }
+
+    fn statuses(store: &MemoryStore, tx_hash: &str) -> Vec<String> {
+        store.txs.lock().unwrap()[tx_hash]
+            .status_history
+            .iter()
+            .map(|change| change.status.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn tracker_backs_off_resubmits_lost_transfers_and_stops_once_settled() {
+        let store = Arc::new(MemoryStore::default());
+        let policy = SubmissionPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(400),
+            resubmit_window: Duration::from_millis(1_000),
+            max_resubmissions: 1,
+            give_up_after: Duration::from_millis(10_000),
+        };
+        let tracker = SubmissionTracker::new(store.clone(), policy);
+        let chain = Arc::new(ScriptedChain::default());
+        chain
+            .statuses
+            .lock()
+            .unwrap()
+            .extend(["pending", "not_found", "confirmed"]);
+        let adapter: Arc<dyn ChainAdapter> = chain.clone();
+        let lookup = |chain: &str| (chain == "flowcortex-l1").then(|| Arc::clone(&adapter));
+        for tx_hash in ["0xsettles", "0xlost"] {
+            store.save_tx(&submitted(tx_hash)).unwrap();
+        }
+        tracker.track(&submitted("0xsettles"), 18, "aa").unwrap();
+
+        assert!(tracker.poll(lookup, 50).await.unwrap().is_empty(), "not due yet");
+        let updates = tracker.poll(lookup, 100).await.unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].previous_status, "submitted");
+        assert_eq!(updates[0].record.status, "pending");
+        assert_eq!(tracker.tracked().unwrap()[0].next_check_at_epoch_ms, 300);
+
+        tracker.poll(lookup, 300).await.unwrap();
+        assert_eq!(store.txs.lock().unwrap()["0xsettles"].status, "resubmitted");
+        {
+            let resubmitted = chain.resubmitted.lock().unwrap();
+            assert_eq!(resubmitted.len(), 1);
+            assert_eq!(resubmitted[0].signed_payload, "aa");
+            assert_eq!(resubmitted[0].amount, Amount::new(25, 18));
+        }
+        // Backoff doubles up to its cap.
+        assert_eq!(tracker.tracked().unwrap()[0].next_check_at_epoch_ms, 700);
+
+        let updates = tracker.poll(lookup, 700).await.unwrap();
+        assert_eq!(updates[0].record.status, "confirmed");
+        assert_eq!(updates[0].record.block_height, Some(7));
+        assert_eq!(statuses(&store, "0xsettles"), ["pending", "resubmitted", "confirmed"]);
+        assert!(tracker.tracked().unwrap().is_empty(), "settled transfers are not watched");
+
+        // Lost past its resubmissions, a transfer is dropped.
+        tracker.track(&submitted("0xlost"), 18, "bb").unwrap();
+        tracker.poll(lookup, 100).await.unwrap();
+        let updates = tracker.poll(lookup, 300).await.unwrap();
+        assert_eq!(updates[0].record.status, "dropped");
+        assert_eq!(statuses(&store, "0xlost"), ["resubmitted", "dropped"]);
+        assert_eq!(chain.resubmitted.lock().unwrap().len(), 2);
+        assert!(tracker.tracked().unwrap().is_empty());
+
+        // An unknown chain only puts the next check off.
+        let mut elsewhere = submitted("0xelsewhere");
+        elsewhere.chain = "other".to_owned();
+        store.save_tx(&elsewhere).unwrap();
+        tracker.track(&elsewhere, 18, "cc").unwrap();
+        assert!(tracker.poll(lookup, 100).await.unwrap().is_empty());
+        assert_eq!(tracker.tracked().unwrap()[0].checks, 1);
+    }
+}
@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS submitted_txs (
+  tx_hash TEXT PRIMARY KEY,
+  status TEXT NOT NULL,
+  accepted BOOLEAN NOT NULL,
+  chain TEXT NOT NULL,
+  from_address TEXT NOT NULL,
+  to_address TEXT NOT NULL,
+  asset TEXT NOT NULL,
+  amount TEXT NOT NULL,
+  submitted_at_epoch_ms BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS wallet_nonces (
+  wallet_address TEXT PRIMARY KEY,
+  last_nonce BIGINT NOT NULL,
+  updated_at_epoch_ms BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS submit_idempotency (
+  idempotency_key TEXT PRIMARY KEY,
+  tx_hash TEXT NOT NULL,
+  accepted BOOLEAN NOT NULL,
+  signature TEXT NOT NULL,
+  expires_at_epoch_ms BIGINT NULL,
+  created_at_epoch_ms BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_submitted_txs_from
+  ON submitted_txs(from_address, submitted_at_epoch_ms DESC);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS wallet_metadata (
+  wallet_address TEXT PRIMARY KEY,
+  label TEXT NULL,
+  created_at_epoch_ms BIGINT NULL,
+  key_scheme TEXT NOT NULL,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_submitted_txs_to
+  ON submitted_txs(to_address, submitted_at_epoch_ms DESC);
@@ -0,0 +1,4 @@
+ALTER TABLE wallet_metadata
+  ADD COLUMN IF NOT EXISTS signature_count BIGINT NOT NULL DEFAULT 0,
+  ADD COLUMN IF NOT EXISTS last_signed_at_epoch_ms BIGINT NULL,
+  ADD COLUMN IF NOT EXISTS last_submitted_at_epoch_ms BIGINT NULL;
@@ -0,0 +1,211 @@
+//! Transfers a wallet's signing policy holds for approval, and the ops
+//! endpoints that decide them.
+//!
+//! `POST /wallet/submit` holds a transfer over its wallet's `approval_above`
+//! limit instead of signing it. An ops principal other than the one who
+//! submitted it then approves it, which signs and submits it with the
+//! wallet's next nonce, or rejects it. Deciding also takes a wallet session
+//! from a fresh verification of a wallet bound to the decider.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{
+    ApiError, TransferApprovalDecideRequest, TransferApprovalListResponse, TransferApprovalResponse,
+    WalletSubmitRequest,
+};
+use kc_storage::{ApprovalRecord, AuditEventRecord};
+use kc_wallet_core::ApprovalError;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::deadline::RequestContext;
+use crate::ops::require_ops_access;
+use crate::pipeline::{OnApproval, TxSigner};
+use crate::wallet_sessions::WalletSession;
+use crate::{
+    AppState, ApiResult, bad_request, epoch_ms, forbidden, internal_error, not_found, unauthorized,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApprovalListQuery {
+    /// Only approvals in this status, e.g. `pending`.
+    pub(crate) status: Option<String>,
+}
+
+/// Hold `request` for approval, for `reason`. Only an authenticated
+/// submit can be held, since its submitter may not approve it.
+pub(crate) async fn hold(
+    state: &AppState,
+    request: &WalletSubmitRequest,
+    reason: String,
+    requested_by: Option<&str>,
+) -> Result<ApprovalRecord, (StatusCode, Json<ApiError>)> {
+    let requested_by = requested_by.ok_or_else(|| {
+        unauthorized("this transfer needs approval; submit it with an AuthBuddy token")
+    })?;
+    let record = state
+        .approvals
+        .hold(
+            request,
+            reason,
+            requested_by.to_owned(),
+            epoch_ms().map_err(internal_error)?,
+        )
+        .map_err(internal_error)?;
+    audit(state, &record, "transfer_approval_held", record.requested_by.clone()).await;
+    Ok(record)
+}
+
+/// GET /wallet/approvals
+pub(crate) async fn list_approvals(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ApprovalListQuery>,
+) -> ApiResult<TransferApprovalListResponse> {
+    require_ops_access(&state, &headers, "list_approvals", None).await?;
+    let approvals = state
+        .approvals
+        .list(query.status.as_deref())
+        .map_err(internal_error)?;
+    Ok(Json(TransferApprovalListResponse {
+        approvals: approvals.into_iter().map(response).collect(),
+    }))
+}
+
+/// POST /wallet/approvals/{approval_id}/decide — approve, which signs and
+/// submits the transfer, or reject a held transfer.
+pub(crate) async fn decide_approval(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    session: Option<Extension<WalletSession>>,
+    Path(approval_id): Path<String>,
+    Json(request): Json<TransferApprovalDecideRequest>,
+) -> ApiResult<TransferApprovalResponse> {
+    let ops_user = require_ops_access(&state, &headers, "decide_approval", None).await?;
+    crate::wallet_sessions::require_recent_verification(&state, session.as_deref(), &ops_user)?;
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+
+    let mut record = state
+        .approvals
+        .decide(
+            &approval_id,
+            &ops_user,
+            request.approve,
+            epoch_ms().map_err(internal_error)?,
+        )
+        .map_err(|err| match err {
+            ApprovalError::NotFound => not_found(&err.to_string()),
+            ApprovalError::AlreadyDecided(_) => bad_request(&err.to_string()),
+            ApprovalError::SelfApproval | ApprovalError::UnknownRequester => {
+                forbidden(&err.to_string())
+            }
+            ApprovalError::Storage(err) => internal_error(format!("{err:#}")),
+        })?;
+    audit(&state, &record, "transfer_approval_decided", Some(ops_user)).await;
+    if !request.approve {
+        return Ok(Json(response(record)));
+    }
+
+    // The decision stands whatever happens next; a failed submission is
+    // recorded on the approval rather than returned as an error.
+    let submitted = submit_approved(&state, &ctx, &record)
+        .await
+        .map_err(|(_, Json(error))| error.message)
+        .and_then(|submitted| {
+            if submitted.accepted {
+                Ok(submitted.tx_hash)
+            } else {
+                Err("chain did not accept the transfer".to_owned())
+            }
+        });
+    state
+        .approvals
+        .finish(&mut record, submitted)
+        .map_err(internal_error)?;
+    audit(&state, &record, "transfer_approval_submitted", record.decided_by.clone()).await;
+    Ok(Json(response(record)))
+}
+
+async fn submit_approved(
+    state: &AppState,
+    ctx: &RequestContext,
+    record: &ApprovalRecord,
+) -> Result<kc_api_types::WalletSubmitResponse, (StatusCode, Json<ApiError>)> {
+    let transfer = WalletSubmitRequest {
+        from: record.from.clone(),
+        to: record.to.clone(),
+        amount: record.amount.clone(),
+        asset: record.asset.clone(),
+        chain: record.chain.clone(),
+        nonce: crate::submit::next_nonce(state, &record.from)
+            .await
+            .map_err(internal_error)?,
+        signed_payload: None,
+        expires_at_epoch_ms: None,
+        memo: None,
+        dry_run: false,
+    };
+    if let Some(kms) = state.kms_keys.get(&record.from) {
+        return crate::pipeline::sign_and_submit_with(
+            state,
+            ctx,
+            TxSigner::Kms(&kms),
+            &transfer,
+            None,
+            OnApproval::Approved,
+        )
+        .await;
+    }
+    let signer = crate::escrow::load_custodied_signer(state, &record.from)
+        .await?
+        .ok_or_else(|| bad_request("source wallet not found"))?;
+    crate::pipeline::sign_and_submit_with(
+        state,
+        ctx,
+        TxSigner::Custodied(&signer),
+        &transfer,
+        None,
+        OnApproval::Approved,
+    )
+        .await
+}
+
+fn response(record: ApprovalRecord) -> TransferApprovalResponse {
+    TransferApprovalResponse {
+        approval_id: record.approval_id,
+        status: record.status,
+        from: record.from,
+        to: record.to,
+        amount: record.amount,
+        asset: record.asset,
+        chain: record.chain,
+        reason: record.reason,
+        requested_by: record.requested_by,
+        requested_at_epoch_ms: record.requested_at_epoch_ms,
+        decided_by: record.decided_by,
+        decided_at_epoch_ms: record.decided_at_epoch_ms,
+        tx_hash: record.tx_hash,
+        error: record.error,
+    }
+}
+
+async fn audit(state: &AppState, record: &ApprovalRecord, event_type: &str, user_id: Option<String>) {
+    crate::auth::append_audit_event(
+        state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: event_type.to_owned(),
+            wallet_address: Some(record.from.clone()),
+            user_id,
+            chain: Some(record.chain.clone()),
+            outcome: record.status.clone(),
+            message: Some(format!("approval {}: {}", record.approval_id, record.reason)),
+            timestamp_epoch_ms: epoch_ms().unwrap_or_default(),
+            tenant: None,
+        },
+    )
+    .await;
+}
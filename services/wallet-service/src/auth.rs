@@ -6,10 +6,12 @@ pub struct AuthBuddyBindCallback {
     pub wallet_address: String,
     pub chain: String,
     pub bound_at_epoch_ms: u128,
+    /// Explorer page for the wallet, when the chain has an address template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explorer_address_url: Option<String>,
 }
 
 pub trait AuthBuddyCallback: Send + Sync {
-    fn callback_url(&self) -> Option<&str>;
     fn notify_bind(&self, payload: &AuthBuddyBindCallback);
 }
 
@@ -18,13 +20,9 @@ pub struct DefaultAuthBuddyCallback {
 }
 
 impl AuthBuddyCallback for DefaultAuthBuddyCallback {
-    fn callback_url(&self) -> Option<&str> {
-        self.url.as_deref()
-    }
     fn notify_bind(&self, payload: &AuthBuddyBindCallback) {
-        if let Some(url) = &self.url {
+        if let Some(url) = self.url.clone() {
             let client = Client::new();
-            let url = self.url.clone().unwrap_or_default();
             let payload = payload.clone();
             tokio::spawn(async move {
                 let res = client.post(url)
@@ -38,24 +36,30 @@ impl AuthBuddyCallback for DefaultAuthBuddyCallback {
         }
     }
 }
+use async_trait::async_trait;
 use axum::{
     Json,
     extract::State,
     http::HeaderMap,
 };
-use axum::http::StatusCode;
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
-use kc_api_types::{AuthBindRequest, AuthBindResponse, AuthChallengeResponse, AuthVerifyRequest, AuthVerifyResponse};
+use kc_api_types::{AuthBindRequest, AuthBindResponse, AuthChallengeRequest, AuthChallengeResponse, AuthVerifyRequest, AuthVerifyResponse, WalletAddress};
+use kc_api_types::webhook::WebhookEvent;
+use kc_auth_adapter::{ChallengeOutcome, SignInMessage};
 use kc_chain_flowcortex::FLOWCORTEX_L1;
-use kc_crypto::{Ed25519Signer, decrypt_key_material};
-use kc_storage::{AuditEventRecord, Keystore, WalletBindingRecord};
+use kc_storage::{AuditEventRecord, Keystore, RocksDbKeystore, WalletBindingRecord};
+use kc_wallet_core::AuditSink;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::warn;
-use uuid::Uuid;
 
-use crate::{AppState, ApiResult, ChallengeRecord, bad_request, epoch_ms, from_hex, internal_error, unauthorized};
+use crate::db::PostgresRepository;
+use crate::{
+    AppState, ApiResult, DbFallbackCounters, bad_request, epoch_ms, from_hex, internal_error, parse_field, to_hex,
+    unauthorized,
+};
 
 #[derive(Debug, Deserialize)]
 struct AuthBuddyClaims {
@@ -63,8 +67,12 @@ struct AuthBuddyClaims {
     roles: Option<Vec<String>>,
     role: Option<String>,
     exp: Option<u64>,
+    iat: Option<u64>,
+    jti: Option<String>,
     iss: Option<String>,
     aud: Option<String>,
+    /// Tenant the token acts for; see [`crate::tenants`].
+    tenant: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -73,47 +81,54 @@ pub(crate) struct AuthPrincipal {
     pub(crate) roles: Vec<String>,
 }
 
+impl AuthPrincipal {
+    pub(crate) fn is_ops_admin(&self) -> bool {
+        self.roles.iter().any(|role| role == "ops-admin")
+    }
+}
+
+
+/// Statement in the sign-in messages `/auth/challenge` issues.
+const SIGN_IN_STATEMENT: &str = "Sign in to KeyCortex. Signing proves you control this wallet; it does not move any funds.";
 
 pub(crate) async fn auth_challenge(
     State(state): State<Arc<AppState>>,
+    request: Option<Json<AuthChallengeRequest>>,
 ) -> ApiResult<AuthChallengeResponse> {
-    let now = epoch_ms().map_err(internal_error)?;
-    let challenge = Uuid::new_v4().to_string();
-    let expires_in: u64 = 300; // 5 minutes
-    let expires_at = now + (expires_in as u128 * 1000);
-
-    let record = ChallengeRecord {
-        issued_at_epoch_ms: now,
-        expires_at_epoch_ms: expires_at,
-        used: false,
-        used_at_epoch_ms: None,
-    };
-
-    {
-        let mut store = state.challenge_store.write().await;
-        store.insert(challenge.clone(), record);
+    let wallet_address = request.and_then(|Json(request)| request.wallet_address);
+    if let Some(wallet_address) = &wallet_address {
+        parse_field::<WalletAddress>("wallet_address", wallet_address)?;
     }
+    let now = epoch_ms().map_err(internal_error)?;
+    let pending = state.challenges.issue(now).await.map_err(internal_error)?;
 
-    if let Some(repo) = &state.postgres_repo {
-        if let Err(err) = repo.upsert_challenge(&challenge, now, expires_at).await {
+    if let Some(repo) = &state.challenge_mirror {
+        if let Err(err) = repo.upsert_challenge(&pending.challenge, &pending.record).await {
             state.db_fallback_counters.inc_challenge_persist_failures();
             warn!("failed to persist challenge in Postgres: {}", err);
         }
     }
 
-    Ok(Json(AuthChallengeResponse {
-        challenge,
-        expires_in,
-    }))
+    let mut response = kc_auth_adapter::challenge_response(&pending);
+    response.message = wallet_address.map(|wallet_address| {
+        SignInMessage::new(
+            &state.sign_in_origin,
+            &wallet_address,
+            &pending.challenge,
+            pending.record.issued_at_epoch_ms,
+            pending.record.expires_at_epoch_ms,
+        )
+        .with_statement(SIGN_IN_STATEMENT)
+        .to_string()
+    });
+    Ok(Json(response))
 }
 
 pub(crate) async fn auth_verify(
     State(state): State<Arc<AppState>>,
     Json(request): Json<AuthVerifyRequest>,
 ) -> ApiResult<AuthVerifyResponse> {
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
 
     if request.challenge.trim().is_empty() {
         return Err(bad_request("challenge is required"));
@@ -125,20 +140,30 @@ pub(crate) async fn auth_verify(
 
     let now = epoch_ms().map_err(internal_error)?;
 
+    if let Some(text) = &request.message {
+        SignInMessage::parse(text)
+            .and_then(|message| {
+                message.check(&state.sign_in_origin, &request.wallet_address, &request.challenge, now)
+            })
+            .map_err(|err| bad_request(&format!("invalid sign-in message: {err}")))?;
+    }
+    let signed = request
+        .message
+        .as_deref()
+        .unwrap_or(&request.challenge)
+        .as_bytes();
+
+    match state
+        .challenges
+        .consume(&request.challenge, now)
+        .await
+        .map_err(internal_error)?
     {
-        let mut store = state.challenge_store.write().await;
-        let record = store
-            .get_mut(&request.challenge)
-            .ok_or_else(|| bad_request("challenge not found"))?;
-
-        if record.used {
-            return Err(bad_request("challenge already used"));
-        }
-
-        if now > record.expires_at_epoch_ms {
-            record.used = true;
-            record.used_at_epoch_ms = Some(now);
-            if let Some(repo) = &state.postgres_repo {
+        ChallengeOutcome::Consumed => {}
+        ChallengeOutcome::NotFound => return Err(bad_request("challenge not found")),
+        ChallengeOutcome::AlreadyUsed => return Err(bad_request("challenge already used")),
+        ChallengeOutcome::Expired => {
+            if let Some(repo) = &state.challenge_mirror {
                 if let Err(err) = repo.mark_challenge_used(&request.challenge, now).await {
                     state.db_fallback_counters.inc_challenge_mark_used_failures();
                     warn!("failed to mark challenge used in Postgres: {}", err);
@@ -146,50 +171,74 @@ pub(crate) async fn auth_verify(
             }
             return Err(bad_request("challenge expired"));
         }
-
-        record.used = true;
-        record.used_at_epoch_ms = Some(now);
-    }
-
-    let encrypted_key = state
-        .keystore
-        .load_encrypted_key(&request.wallet_address)
-        .await
-        .map_err(internal_error)?
-        .ok_or_else(|| bad_request("wallet not found"))?;
-
-    let mut secret_key = decrypt_key_material(&encrypted_key, state.encryption_key.as_ref())
-        .map_err(internal_error)?;
-
-    let signer = Ed25519Signer::from_secret_key_bytes(secret_key);
-    secret_key.fill(0);
-    let derived_wallet_address = signer.wallet_address();
-    if derived_wallet_address != request.wallet_address {
-        return Err(bad_request("wallet address mismatch"));
     }
 
     let signature_bytes = from_hex(&request.signature)
         .map_err(|e| bad_request(&format!("invalid signature hex: {e}")))?;
 
-    let valid = signer
-        .verify(
-            request.challenge.as_bytes(),
-            kc_api_types::SignPurpose::Auth,
-            &signature_bytes,
-        )
-        .map_err(internal_error)?;
+    let valid = if let Some(public_key) =
+        crate::external::load_public_key(&state, &request.wallet_address)?
+    {
+        public_key
+            .verify_in_domain(
+                &state.signing_domain,
+                signed,
+                kc_api_types::SignPurpose::Auth,
+                &signature_bytes,
+            )
+            .map_err(internal_error)?
+    } else {
+        let signer = state
+            .wallet_core
+            .signer_for(&request.wallet_address)
+            .await
+            .map_err(internal_error)?
+            .ok_or_else(|| bad_request("wallet not found"))?;
+        let key_matches = crate::key_rotation::key_matches_wallet(&state, &request.wallet_address, &signer)
+            .map_err(internal_error)?;
+        if !key_matches {
+            return Err(bad_request("wallet address mismatch"));
+        }
 
-    if let Some(repo) = &state.postgres_repo {
+        signer
+            .verify_in_domain(
+                &state.signing_domain,
+                signed,
+                kc_api_types::SignPurpose::Auth,
+                &signature_bytes,
+            )
+            .map_err(internal_error)?
+    };
+
+    if let Some(repo) = &state.challenge_mirror {
         if let Err(err) = repo.mark_challenge_used(&request.challenge, now).await {
             state.db_fallback_counters.inc_challenge_mark_used_failures();
             warn!("failed to mark challenge used in Postgres: {}", err);
         }
     }
 
+    let session = if valid {
+        crate::webhooks::emit(
+            &state,
+            WebhookEvent::ChallengeVerified {
+                wallet_address: request.wallet_address.clone(),
+            },
+        );
+        Some(
+            state
+                .wallet_sessions
+                .issue(&state.keystore, &request.wallet_address, now, now)
+                .map_err(internal_error)?,
+        )
+    } else {
+        None
+    };
+
     Ok(Json(AuthVerifyResponse {
         valid,
         wallet_address: request.wallet_address,
         verified_at_epoch_ms: now,
+        session,
     }))
 }
 
@@ -205,9 +254,7 @@ pub(crate) async fn auth_bind(
         Err(msg) => return Err(unauthorized(&msg)),
     };
 
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
 
     if request.chain != FLOWCORTEX_L1 {
         return Err(bad_request("unsupported chain; only flowcortex-l1 is supported"));
@@ -220,7 +267,8 @@ pub(crate) async fn auth_bind(
         .load_encrypted_key(&request.wallet_address)
         .await
         .map_err(internal_error)?
-        .is_some();
+        .is_some()
+        || crate::external::load_public_key(&state, &request.wallet_address)?.is_some();
     if !wallet_exists {
         return Err(bad_request("wallet not found"));
     }
@@ -255,6 +303,7 @@ pub(crate) async fn auth_bind(
             outcome: "success".to_owned(),
             message: Some("wallet binding persisted".to_owned()),
             timestamp_epoch_ms: now,
+            tenant: None,
         },
     )
     .await;
@@ -266,6 +315,11 @@ pub(crate) async fn auth_bind(
             wallet_address: request.wallet_address.clone(),
             chain: request.chain.clone(),
             bound_at_epoch_ms: now,
+            explorer_address_url: crate::chains::explorer_address_url(
+                &state,
+                &request.chain,
+                &request.wallet_address,
+            ),
         };
         callback.notify_bind(&payload);
     }
@@ -283,20 +337,43 @@ pub(crate) fn parse_authbuddy_principal(
     headers: &HeaderMap,
     state: &AppState,
 ) -> Result<AuthPrincipal, String> {
-    let auth_header = headers
-        .get("authorization")
-        .and_then(|value| value.to_str().ok())
-        .ok_or_else(|| "missing Authorization header".to_owned())?;
+    let claims = verify_authbuddy_claims(headers, state)?;
+    let user_id = claims.sub.trim().to_owned();
 
-    if !auth_header.starts_with("Bearer ") {
-        return Err("invalid Authorization format".to_owned());
-    }
+    // A token without a jti is tracked, and revoked, by its hash.
+    let session_id = match claims.jti.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+        Some(jti) => jti.to_owned(),
+        None => to_hex(&Sha256::digest(bearer_token(headers)?.as_bytes())),
+    };
+    crate::sessions::track_session(state, &user_id, &session_id, claims.iat, claims.exp)?;
 
-    let token = auth_header.trim_start_matches("Bearer ").trim();
-    if token.is_empty() {
-        return Err("missing bearer token".to_owned());
+    let mut roles = claims.roles.unwrap_or_default();
+    if let Some(role) = claims.role {
+        for entry in role.split(',') {
+            let value = entry.trim();
+            if !value.is_empty() {
+                roles.push(value.to_owned());
+            }
+        }
     }
 
+    Ok(AuthPrincipal { user_id, roles })
+}
+
+/// The `tenant` claim of the request's AuthBuddy token, if it carries a
+/// valid one.
+pub(crate) fn authbuddy_tenant(headers: &HeaderMap, state: &AppState) -> Option<String> {
+    verify_authbuddy_claims(headers, state)
+        .ok()?
+        .tenant
+        .map(|tenant| tenant.trim().to_owned())
+        .filter(|tenant| !tenant.is_empty())
+}
+
+/// Claims of the bearer token in `headers`, checked against the AuthBuddy
+/// keys, expiry, issuer, audience and subject.
+fn verify_authbuddy_claims(headers: &HeaderMap, state: &AppState) -> Result<AuthBuddyClaims, String> {
+    let token = bearer_token(headers)?;
     let jwks_snapshot = state
         .authbuddy_jwks
         .read()
@@ -341,22 +418,28 @@ pub(crate) fn parse_authbuddy_principal(
         }
     }
 
-    let user_id = claims.sub.trim().to_owned();
-    if user_id.is_empty() {
+    if claims.sub.trim().is_empty() {
         return Err("invalid AuthBuddy JWT subject".to_owned());
     }
 
-    let mut roles = claims.roles.unwrap_or_default();
-    if let Some(role) = claims.role {
-        for entry in role.split(',') {
-            let value = entry.trim();
-            if !value.is_empty() {
-                roles.push(value.to_owned());
-            }
-        }
+    Ok(claims)
+}
+
+fn bearer_token(headers: &HeaderMap) -> Result<&str, String> {
+    let auth_header = headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| "missing Authorization header".to_owned())?;
+
+    if !auth_header.starts_with("Bearer ") {
+        return Err("invalid Authorization format".to_owned());
     }
 
-    Ok(AuthPrincipal { user_id, roles })
+    let token = auth_header.trim_start_matches("Bearer ").trim();
+    if token.is_empty() {
+        return Err("missing bearer token".to_owned());
+    }
+    Ok(token)
 }
 
 fn decode_authbuddy_hs256_claims(token: &str, jwt_secret: &str) -> Result<AuthBuddyClaims, String> {
@@ -405,12 +488,47 @@ fn decode_authbuddy_rs256_claims(token: &str, jwks: &JwkSet) -> Result<AuthBuddy
 }
 
 pub(crate) async fn append_audit_event(state: &AppState, event: AuditEventRecord) {
+    append(&state.keystore, state.postgres_repo.as_ref(), &state.db_fallback_counters, event).await;
+}
+
+/// The audit trail as the wallet core writes to it: the keystore, mirrored
+/// to Postgres when configured, like [`append_audit_event`].
+pub(crate) struct AuditLog {
+    pub(crate) keystore: Arc<RocksDbKeystore>,
+    pub(crate) postgres_repo: Option<Arc<PostgresRepository>>,
+    pub(crate) db_fallback_counters: Arc<DbFallbackCounters>,
+}
+
+#[async_trait]
+impl AuditSink for AuditLog {
+    async fn record(&self, event: AuditEventRecord) {
+        append(&self.keystore, self.postgres_repo.as_ref(), &self.db_fallback_counters, event).await;
+    }
+}
+
+async fn append(
+    keystore: &RocksDbKeystore,
+    postgres_repo: Option<&Arc<PostgresRepository>>,
+    db_fallback_counters: &DbFallbackCounters,
+    event: AuditEventRecord,
+) {
     let event_for_postgres = event.clone();
-    let _ = state.keystore.append_audit_event(event);
-    if let Some(repo) = &state.postgres_repo {
+    let _ = keystore.append_audit_event(event);
+    if let Some(repo) = postgres_repo {
         if let Err(err) = repo.append_audit_event(&event_for_postgres).await {
-            state.db_fallback_counters.inc_audit_write_failures();
+            db_fallback_counters.inc_audit_write_failures();
             warn!("failed to append audit event to Postgres: {}", err);
         }
     }
 }
+
+/// Drop RocksDB audit events older than `retention_days` before `now_epoch_ms`.
+/// Postgres `verification_logs` are left to the database's own retention.
+pub(crate) fn prune_audit_events(
+    keystore: &RocksDbKeystore,
+    retention_days: u64,
+    now_epoch_ms: u128,
+) -> anyhow::Result<usize> {
+    let retention_ms = u128::from(retention_days) * 24 * 60 * 60 * 1000;
+    keystore.prune_audit_events(now_epoch_ms.saturating_sub(retention_ms))
+}
@@ -0,0 +1,281 @@
+use anyhow::{Result, bail};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{
+    AssetSymbol, ChainId, WalletBridgeRequest, WalletBridgeResponse, WalletSubmitRequest, WalletSubmitResponse,
+    WalletAddress, WalletTxStatusChange,
+};
+use kc_bridge::{BridgeIntent, Leg, Step};
+use kc_chain_client::TxStatusRequest;
+use kc_storage::{AuditEventRecord, BridgeTransferRecord};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::deadline::RequestContext;
+use crate::pipeline::TxSigner;
+use crate::wallet_sessions::WalletScope;
+use crate::{AppState, ApiResult, ApiError, bad_request, epoch_ms, internal_error, not_found, parse_field};
+
+/// Bridge wallet per chain from `KEYCORTEX_BRIDGE_WALLETS`, a comma-separated
+/// list of `chain=wallet_address`. Each must be a wallet this service can
+/// sign for.
+pub(crate) fn wallets_from_env() -> Result<HashMap<String, String>> {
+    let mut wallets = HashMap::new();
+    for entry in env::var("KEYCORTEX_BRIDGE_WALLETS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let Some((chain, wallet_address)) = entry.split_once('=') else {
+            bail!("KEYCORTEX_BRIDGE_WALLETS entry '{entry}' must be chain=wallet_address");
+        };
+        wallets.insert(chain.trim().to_owned(), wallet_address.trim().to_owned());
+    }
+    Ok(wallets)
+}
+
+fn bridge_wallet(state: &AppState, chain: &str) -> Result<String, (StatusCode, Json<ApiError>)> {
+    state
+        .bridge_wallets
+        .get(chain)
+        .cloned()
+        .ok_or_else(|| bad_request(&format!("no bridge wallet is configured for chain '{chain}'")))
+}
+
+fn response(record: BridgeTransferRecord) -> WalletBridgeResponse {
+    WalletBridgeResponse {
+        bridge_id: record.bridge_id,
+        status: record.status,
+        from: record.from,
+        to: record.to,
+        amount: record.amount,
+        asset: record.asset,
+        source_chain: record.source_chain,
+        target_chain: record.target_chain,
+        source_tx_hash: record.source_tx_hash,
+        target_tx_hash: record.target_tx_hash,
+        error: record.error,
+        created_at_epoch_ms: record.created_at_epoch_ms,
+        updated_at_epoch_ms: record.updated_at_epoch_ms,
+        status_history: record
+            .status_history
+            .into_iter()
+            .map(|change| WalletTxStatusChange {
+                status: change.status,
+                at_epoch_ms: change.at_epoch_ms,
+            })
+            .collect(),
+    }
+}
+
+fn save_transfer(
+    state: &AppState,
+    record: &BridgeTransferRecord,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    state
+        .keystore
+        .save_bridge_transfer(record)
+        .map_err(internal_error)
+}
+
+async fn audit(state: &AppState, record: &BridgeTransferRecord, event_type: &str) {
+    crate::auth::append_audit_event(
+        state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: event_type.to_owned(),
+            wallet_address: Some(record.from.clone()),
+            user_id: None,
+            chain: Some(record.source_chain.clone()),
+            outcome: record.status.clone(),
+            message: Some(format!(
+                "bridge {} to {} on {}",
+                record.bridge_id, record.to, record.target_chain
+            )),
+            timestamp_epoch_ms: epoch_ms().unwrap_or_default(),
+            tenant: None,
+        },
+    )
+    .await;
+}
+
+/// Sign and submit one leg with the next free nonce of the paying wallet,
+/// which is custodied or held in KMS.
+async fn submit_leg(
+    state: &AppState,
+    ctx: &RequestContext,
+    record: &BridgeTransferRecord,
+    leg: Leg,
+) -> Result<WalletSubmitResponse, (StatusCode, Json<ApiError>)> {
+    let transfer = kc_bridge::leg_transfer(record, leg);
+    let request = WalletSubmitRequest {
+        nonce: crate::submit::next_nonce(state, &transfer.from)
+            .await
+            .map_err(internal_error)?,
+        from: transfer.from,
+        to: transfer.to,
+        amount: record.amount.clone(),
+        asset: record.asset.clone(),
+        chain: transfer.chain,
+        signed_payload: None,
+        expires_at_epoch_ms: None,
+        memo: None,
+        dry_run: false,
+    };
+    if let Some(kms) = state.kms_keys.get(&request.from) {
+        return crate::pipeline::sign_and_submit(state, ctx, TxSigner::Kms(&kms), &request, None).await;
+    }
+    let signer = crate::escrow::load_custodied_signer(state, &request.from)
+        .await?
+        .ok_or_else(|| bad_request(&format!("{leg} wallet {} not found", request.from)))?;
+    crate::pipeline::sign_and_submit(state, ctx, TxSigner::Custodied(&signer), &request, None).await
+}
+
+/// Drive the transfer as far as the chains allow, saving every step.
+///
+/// An error submitting the source leg is returned before anything is saved.
+/// Once the source funds are with the bridge, a failed target submit is kept
+/// in `error` and retried on the next poll.
+async fn advance(
+    state: &AppState,
+    ctx: &RequestContext,
+    record: &mut BridgeTransferRecord,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    loop {
+        let now = epoch_ms().map_err(internal_error)?;
+        match kc_bridge::next_step(record) {
+            Step::Submit(leg) => match submit_leg(state, ctx, record, leg).await {
+                Ok(submitted) => {
+                    kc_bridge::record_submitted(record, leg, submitted.tx_hash, submitted.accepted, now);
+                }
+                Err(err) if leg == Leg::Source => return Err(err),
+                Err((_, Json(err))) => {
+                    warn!("bridge {} target leg not submitted: {}", record.bridge_id, err.message);
+                    record.error = Some(format!("target leg: {}", err.message));
+                    record.updated_at_epoch_ms = now;
+                    return save_transfer(state, record);
+                }
+            },
+            Step::Poll { leg, tx_hash } => {
+                let chain = kc_bridge::leg_transfer(record, leg).chain;
+                let adapter = crate::chains::adapter(state, &chain)?;
+                let status = ctx
+                    .run(
+                        "get_transaction_status",
+                        adapter.get_transaction_status(TxStatusRequest {
+                            tx_hash,
+                            chain: ChainId(chain),
+                        }),
+                    )
+                    .await;
+                match status {
+                    Ok(status) if kc_bridge::record_status(record, leg, &status, now) => {}
+                    Ok(_) => return Ok(()),
+                    Err(err) => {
+                        warn!(
+                            "failed to poll bridge {} {leg} leg: {}. Returning last persisted state",
+                            record.bridge_id, err
+                        );
+                        return Ok(());
+                    }
+                }
+            }
+            Step::Wait | Step::Done => return Ok(()),
+        }
+        save_transfer(state, record)?;
+    }
+}
+
+/// POST /wallet/bridge — move funds from a wallet on one chain to a wallet on
+/// another through the configured bridge wallets.
+pub(crate) async fn bridge_create(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    scope: WalletScope,
+    Json(request): Json<WalletBridgeRequest>,
+) -> ApiResult<WalletBridgeResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    parse_field::<WalletAddress>("from", &request.from)?;
+    scope.authorize(&request.from)?;
+    parse_field::<WalletAddress>("to", &request.to)?;
+    if request.amount.trim().is_empty() {
+        return Err(bad_request("amount is required"));
+    }
+    parse_field::<AssetSymbol>("asset", &request.asset)?;
+    parse_field::<ChainId>("source_chain", &request.source_chain)?;
+    parse_field::<ChainId>("target_chain", &request.target_chain)?;
+    if request.source_chain == request.target_chain {
+        return Err(bad_request("source_chain and target_chain must differ"));
+    }
+    for chain in [&request.source_chain, &request.target_chain] {
+        crate::chains::adapter(&state, chain)?;
+        crate::chains::amount(&state, chain, &request.asset, &request.amount)?;
+    }
+    let source_bridge_wallet = bridge_wallet(&state, &request.source_chain)?;
+    let target_bridge_wallet = bridge_wallet(&state, &request.target_chain)?;
+
+    crate::honeytoken::trip_if_honeytoken(&state, &request.from, "wallet_bridge").await;
+    crate::watch::reject_watch_only(&state, &request.from)?;
+    if crate::escrow::load_custodied_signer(&state, &request.from)
+        .await?
+        .is_none()
+    {
+        return Err(bad_request("source wallet not found"));
+    }
+    crate::devices::enforce_trusted_device(
+        &state,
+        &headers,
+        &request.from,
+        &request.to,
+        &request.amount,
+    )
+    .await?;
+
+    let mut record = kc_bridge::new_transfer(
+        format!("bridge-{}", Uuid::new_v4()),
+        BridgeIntent {
+            from: request.from,
+            to: request.to,
+            amount: request.amount,
+            asset: request.asset,
+            source_chain: request.source_chain,
+            target_chain: request.target_chain,
+        },
+        source_bridge_wallet,
+        target_bridge_wallet,
+        epoch_ms().map_err(internal_error)?,
+    );
+    advance(&state, &ctx, &mut record).await?;
+    audit(&state, &record, "bridge_create").await;
+
+    Ok(Json(response(record)))
+}
+
+/// GET /wallet/bridge/{bridge_id} — poll both legs and move the transfer on.
+pub(crate) async fn bridge_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(bridge_id): Path<String>,
+) -> ApiResult<WalletBridgeResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let mut record = state
+        .keystore
+        .load_bridge_transfer(&bridge_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("bridge transfer not found"))?;
+    let before = record.status.clone();
+    advance(&state, &ctx, &mut record).await?;
+    if record.status != before
+        && (record.status == kc_bridge::COMPLETED || record.status == kc_bridge::FAILED)
+    {
+        audit(&state, &record, &format!("bridge_{}", record.status)).await;
+    }
+    Ok(Json(response(record)))
+}
@@ -0,0 +1,421 @@
+//! Portable bundle of non-secret operational data, for seeding one
+//! environment (e.g. staging) from another's shape.
+//!
+//! `GET /ops/bundle/export` downloads wallet bindings, labels, watch-only
+//! wallets, external public keys and chain adapters as JSON.
+//! `POST /ops/bundle/import` merges a bundle in. Key material, nonces,
+//! sessions, identities, audit events and transactions are never included.
+//! An imported binding or label refers to a wallet the target environment
+//! has no key for until one is created or restored there.
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Response,
+};
+use kc_crypto::Ed25519PublicKey;
+use kc_storage::{
+    AuditEventRecord, ChainAdapterRecord, ExternalKeyRecord, KEY_SCHEME_WATCH_ONLY, Keystore,
+    WalletBindingRecord, WalletMetadataRecord, WalletUsage, WatchOnlyWalletRecord,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::ops::{
+    explorer_template, require_ops_access, validate_bech32_prefix, validate_chain_endpoint,
+    validate_chain_id, validate_chain_kind, validate_denoms, validate_erc20_tokens,
+};
+use crate::{ApiResult, AppState, ApiError, bad_request, epoch_ms, internal_error};
+
+const BUNDLE_FORMAT: &str = "keycortex-ops-bundle";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OpsBundle {
+    pub(crate) format: String,
+    pub(crate) version: u32,
+    #[serde(default)]
+    pub(crate) exported_at_epoch_ms: u128,
+    #[serde(default)]
+    pub(crate) bindings: Vec<WalletBindingRecord>,
+    #[serde(default)]
+    pub(crate) labels: Vec<BundleLabel>,
+    #[serde(default)]
+    pub(crate) watch_wallets: Vec<WatchOnlyWalletRecord>,
+    #[serde(default)]
+    pub(crate) external_keys: Vec<ExternalKeyRecord>,
+    #[serde(default)]
+    pub(crate) chain_adapters: Vec<ChainAdapterRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct BundleLabel {
+    pub(crate) wallet_address: String,
+    pub(crate) label: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsBundleImportQuery {
+    /// Replace records that already exist instead of keeping them.
+    pub(crate) overwrite: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct ImportCounts {
+    pub(crate) imported: usize,
+    /// Already present and kept, because `overwrite` was not set.
+    pub(crate) skipped: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct OpsBundleImportResponse {
+    pub(crate) bindings: ImportCounts,
+    pub(crate) labels: ImportCounts,
+    pub(crate) watch_wallets: ImportCounts,
+    pub(crate) external_keys: ImportCounts,
+    pub(crate) chain_adapters: ImportCounts,
+}
+
+impl ImportCounts {
+    fn record(&mut self, imported: bool) {
+        if imported {
+            self.imported += 1;
+        } else {
+            self.skipped += 1;
+        }
+    }
+}
+
+/// GET /ops/bundle/export — non-secret operational data as a JSON download.
+pub(crate) async fn ops_export_bundle(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ApiError>)> {
+    require_ops_access(&state, &headers, "ops_export_bundle", None).await?;
+
+    let keystore = &state.keystore;
+    let mut watch_wallets = Vec::new();
+    for wallet_address in keystore.list_watch_wallets().map_err(internal_error)? {
+        watch_wallets.extend(keystore.load_watch_wallet(&wallet_address).map_err(internal_error)?);
+    }
+    let mut external_keys = Vec::new();
+    for wallet_address in keystore.list_external_key_wallets().map_err(internal_error)? {
+        external_keys.extend(keystore.load_external_key(&wallet_address).map_err(internal_error)?);
+    }
+    let now = epoch_ms().map_err(internal_error)?;
+    let bundle = OpsBundle {
+        format: BUNDLE_FORMAT.to_owned(),
+        version: BUNDLE_VERSION,
+        exported_at_epoch_ms: now,
+        bindings: keystore.list_wallet_bindings().map_err(internal_error)?,
+        labels: keystore
+            .list_wallet_labels()
+            .map_err(internal_error)?
+            .into_iter()
+            .map(|(wallet_address, label)| BundleLabel {
+                wallet_address,
+                label,
+            })
+            .collect(),
+        watch_wallets,
+        external_keys,
+        // The env-configured built-in chain is not persisted; the target
+        // environment configures its own.
+        chain_adapters: keystore.list_chain_adapters().map_err(internal_error)?,
+    };
+
+    let body = serde_json::to_vec_pretty(&bundle).map_err(internal_error)?;
+    Ok(crate::export::download(
+        &format!("keycortex-bundle-{now}.json"),
+        "application/json",
+        Body::from(body),
+    ))
+}
+
+/// POST /ops/bundle/import — merge a bundle. The whole bundle is validated
+/// before anything is written.
+pub(crate) async fn ops_import_bundle(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<OpsBundleImportQuery>,
+    Json(bundle): Json<OpsBundle>,
+) -> ApiResult<OpsBundleImportResponse> {
+    let ops_user = require_ops_access(&state, &headers, "ops_import_bundle", None).await?;
+    let overwrite = query.overwrite.unwrap_or(false);
+    check_format(&bundle)?;
+    let chain_adapters = validate_bundle(&bundle, &ops_user)?;
+
+    let keystore = &state.keystore;
+    let mut response = OpsBundleImportResponse::default();
+
+    for binding in &bundle.bindings {
+        let write = overwrite
+            || keystore
+                .load_wallet_binding(&binding.wallet_address)
+                .map_err(internal_error)?
+                .is_none();
+        if write {
+            keystore.save_wallet_binding(binding).map_err(internal_error)?;
+            if let Some(repo) = &state.postgres_repo {
+                if let Err(err) = repo.save_wallet_binding(binding).await {
+                    state.db_fallback_counters.inc_binding_write_failures();
+                    warn!("failed to persist imported binding in Postgres: {}", err);
+                }
+            }
+        }
+        response.bindings.record(write);
+    }
+
+    for entry in &bundle.labels {
+        let write = overwrite
+            || keystore
+                .load_wallet_label(&entry.wallet_address)
+                .map_err(internal_error)?
+                .is_none();
+        if write {
+            keystore
+                .save_wallet_label(&entry.wallet_address, entry.label.trim())
+                .map_err(internal_error)?;
+            crate::mirror_wallet_metadata(&state, &entry.wallet_address).await;
+        }
+        response.labels.record(write);
+    }
+
+    for record in &bundle.watch_wallets {
+        // A custodied or external key for the address outranks watch-only.
+        let write = (overwrite
+            || keystore
+                .load_watch_wallet(&record.wallet_address)
+                .map_err(internal_error)?
+                .is_none())
+            && !holds_key(&state, &record.wallet_address).await?;
+        if write {
+            keystore.save_watch_wallet(record).map_err(internal_error)?;
+            save_metadata(
+                &state,
+                &record.wallet_address,
+                record.created_at_epoch_ms,
+                KEY_SCHEME_WATCH_ONLY,
+            )
+            .await?;
+        }
+        response.watch_wallets.record(write);
+    }
+
+    for record in &bundle.external_keys {
+        let write = (overwrite
+            || keystore
+                .load_external_key(&record.wallet_address)
+                .map_err(internal_error)?
+                .is_none())
+            && !keystore
+                .has_wallet(&record.wallet_address)
+                .await
+                .map_err(internal_error)?;
+        if write {
+            keystore.save_external_key(record).map_err(internal_error)?;
+            save_metadata(
+                &state,
+                &record.wallet_address,
+                record.created_at_epoch_ms,
+                &record.key_type,
+            )
+            .await?;
+        }
+        response.external_keys.record(write);
+    }
+
+    for record in chain_adapters {
+        let write = overwrite
+            || state
+                .chains
+                .read()
+                .map_err(|_| internal_error("chain table lock poisoned"))?
+                .config(&record.chain_id)
+                .is_none();
+        if write {
+            keystore.save_chain_adapter(&record).map_err(internal_error)?;
+            state
+                .chains
+                .write()
+                .map_err(|_| internal_error("chain table lock poisoned"))?
+                .apply(record);
+        }
+        response.chain_adapters.record(write);
+    }
+
+    crate::auth::append_audit_event(
+        &state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "ops_import_bundle".to_owned(),
+            wallet_address: None,
+            user_id: Some(ops_user),
+            chain: None,
+            outcome: "success".to_owned(),
+            message: Some(format!(
+                "overwrite={overwrite} bindings={} labels={} watch_wallets={} external_keys={} chain_adapters={}",
+                response.bindings.imported,
+                response.labels.imported,
+                response.watch_wallets.imported,
+                response.external_keys.imported,
+                response.chain_adapters.imported,
+            )),
+            timestamp_epoch_ms: epoch_ms().map_err(internal_error)?,
+            tenant: None,
+        },
+    )
+    .await;
+
+    Ok(Json(response))
+}
+
+/// Record metadata for an imported watch-only or external wallet, keeping
+/// any label already present.
+async fn save_metadata(
+    state: &AppState,
+    wallet_address: &str,
+    created_at_epoch_ms: u128,
+    key_scheme: &str,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    let label = state
+        .keystore
+        .load_wallet_label(wallet_address)
+        .map_err(internal_error)?;
+    state
+        .keystore
+        .save_wallet_metadata(&WalletMetadataRecord {
+            wallet_address: wallet_address.to_owned(),
+            label,
+            created_at_epoch_ms: Some(created_at_epoch_ms),
+            key_scheme: key_scheme.to_owned(),
+            usage: WalletUsage::default(),
+        })
+        .map_err(internal_error)?;
+    crate::mirror_wallet_metadata(state, wallet_address).await;
+    Ok(())
+}
+
+async fn holds_key(
+    state: &AppState,
+    wallet_address: &str,
+) -> Result<bool, (StatusCode, Json<ApiError>)> {
+    Ok(state
+        .keystore
+        .has_wallet(wallet_address)
+        .await
+        .map_err(internal_error)?
+        || state
+            .keystore
+            .load_external_key(wallet_address)
+            .map_err(internal_error)?
+            .is_some())
+}
+
+fn check_format(bundle: &OpsBundle) -> Result<(), (StatusCode, Json<ApiError>)> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(bad_request(&format!("format must be {BUNDLE_FORMAT}")));
+    }
+    if bundle.version != BUNDLE_VERSION {
+        return Err(bad_request(&format!(
+            "unsupported bundle version {}; expected {BUNDLE_VERSION}",
+            bundle.version
+        )));
+    }
+    Ok(())
+}
+
+/// Check every entry, returning the chain adapters as they will be stored.
+fn validate_bundle(
+    bundle: &OpsBundle,
+    ops_user: &str,
+) -> Result<Vec<ChainAdapterRecord>, (StatusCode, Json<ApiError>)> {
+    let addresses = bundle
+        .bindings
+        .iter()
+        .map(|b| &b.wallet_address)
+        .chain(bundle.labels.iter().map(|l| &l.wallet_address))
+        .chain(bundle.watch_wallets.iter().map(|w| &w.wallet_address))
+        .chain(bundle.external_keys.iter().map(|k| &k.wallet_address));
+    for address in addresses {
+        if address.trim().is_empty() || address.trim() != address {
+            return Err(bad_request(&format!("invalid wallet_address {address:?}")));
+        }
+    }
+    if bundle.bindings.iter().any(|b| b.user_id.trim().is_empty()) {
+        return Err(bad_request("every binding needs a user_id"));
+    }
+    if bundle.labels.iter().any(|l| l.label.trim().is_empty()) {
+        return Err(bad_request("labels must not be empty"));
+    }
+    for record in &bundle.external_keys {
+        let derived = Ed25519PublicKey::from_hex(&record.public_key)
+            .map(|key| key.wallet_address())
+            .map_err(|e| bad_request(&format!("invalid public_key: {e}")))?;
+        if derived != record.wallet_address {
+            return Err(bad_request(&format!(
+                "public_key does not match wallet_address {}",
+                record.wallet_address
+            )));
+        }
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    bundle
+        .chain_adapters
+        .iter()
+        .map(|record| {
+            validate_chain_id(&record.chain_id)?;
+            validate_chain_kind(&record.kind)?;
+            let endpoint = record.endpoint.trim().trim_end_matches('/').to_owned();
+            validate_chain_endpoint(&endpoint)?;
+            let fallback_endpoints = record
+                .fallback_endpoints
+                .iter()
+                .map(|endpoint| {
+                    let endpoint = endpoint.trim().trim_end_matches('/').to_owned();
+                    validate_chain_endpoint(&endpoint)?;
+                    Ok(endpoint)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(ChainAdapterRecord {
+                chain_id: record.chain_id.clone(),
+                kind: record.kind.clone(),
+                endpoint,
+                fallback_endpoints,
+                enabled: record.enabled,
+                evm_chain_id: record.evm_chain_id,
+                explorer_tx_url: explorer_template(
+                    record.explorer_tx_url.clone(),
+                    None,
+                    "explorer_tx_url",
+                    "{tx_hash}",
+                )?,
+                explorer_address_url: explorer_template(
+                    record.explorer_address_url.clone(),
+                    None,
+                    "explorer_address_url",
+                    "{address}",
+                )?,
+                explorer_block_url: explorer_template(
+                    record.explorer_block_url.clone(),
+                    None,
+                    "explorer_block_url",
+                    "{height}",
+                )?,
+                erc20_tokens: validate_erc20_tokens(record.erc20_tokens.clone())?,
+                bech32_prefix: record
+                    .bech32_prefix
+                    .as_deref()
+                    .map(validate_bech32_prefix)
+                    .transpose()?,
+                denoms: validate_denoms(record.denoms.clone())?,
+                updated_by: format!("import:{ops_user}"),
+                updated_at_epoch_ms: now,
+            })
+        })
+        .collect()
+}
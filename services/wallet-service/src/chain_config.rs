@@ -1,44 +1,110 @@
+use anyhow::bail;
 use axum::{Json, extract::State};
 use kc_api_types::{ChainAssetInfo, ChainConfigResponse, ChainDomainTags};
+use kc_chain_client::SigningConfig;
+use kc_crypto::{Ed25519Signer, SigningDomain};
+use std::env;
 use std::sync::Arc;
 
 use crate::{AppState, ApiResult};
 
+const SIGNING_ENV_VARS: [&str; 3] = [
+    "KEYCORTEX_SIGNING_NAMESPACE",
+    "KEYCORTEX_SIGNING_VERSION",
+    "KEYCORTEX_SIGNING_CUSTOM_PURPOSES",
+];
+
+/// Load the signing domain for this deployment, which WalletCore signs
+/// under and `/chain/config` advertises.
+///
+/// Taken from the `[signing]` table of `KEYCORTEX_CHAINS_CONFIG`, which can
+/// also rename the purpose tags; without one, from the
+/// `KEYCORTEX_SIGNING_*` variables. Setting both is an error.
+pub(crate) fn signing_domain(config: Option<&SigningConfig>) -> anyhow::Result<SigningDomain> {
+    let Some(config) = config else {
+        return signing_domain_from_env();
+    };
+    if let Some(name) = SIGNING_ENV_VARS
+        .into_iter()
+        .find(|name| env::var(name).is_ok_and(|value| !value.trim().is_empty()))
+    {
+        bail!("{name} is set but KEYCORTEX_CHAINS_CONFIG has a [signing] table; set the signing domain in one place");
+    }
+    signing_domain_from_config(config)
+}
+
+fn signing_domain_from_config(config: &SigningConfig) -> anyhow::Result<SigningDomain> {
+    let tag = |purpose: &str| format!("{}:{}:{}", config.namespace.trim(), config.version.trim(), purpose.trim());
+    SigningDomain::from_domain_tags(&ChainDomainTags {
+        tx_domain_tag: tag(&config.transaction_tag),
+        auth_domain_tag: tag(&config.auth_tag),
+        proof_domain_tag: tag(&config.proof_tag),
+        custom_purposes: config.custom_purposes.iter().map(|name| name.trim().to_owned()).collect(),
+    })
+}
+
+/// Reads `KEYCORTEX_SIGNING_NAMESPACE` and `KEYCORTEX_SIGNING_VERSION`
+/// (defaults: `keycortex`, `v1`), plus the comma-separated custom purposes in
+/// `KEYCORTEX_SIGNING_CUSTOM_PURPOSES`.
+fn signing_domain_from_env() -> anyhow::Result<SigningDomain> {
+    let namespace = env::var("KEYCORTEX_SIGNING_NAMESPACE")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "keycortex".to_owned());
+    let version = env::var("KEYCORTEX_SIGNING_VERSION")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "v1".to_owned());
+    let custom_purposes = env::var("KEYCORTEX_SIGNING_CUSTOM_PURPOSES").unwrap_or_default();
+    SigningDomain::new(namespace.trim(), version.trim()).with_custom_purposes(
+        custom_purposes
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty()),
+    )
+}
+
+/// Assets enabled on FlowCortex L1 for MVP.
+pub(crate) fn flowcortex_assets() -> Vec<ChainAssetInfo> {
+    vec![
+        ChainAssetInfo {
+            symbol: "PROOF".to_owned(),
+            asset_type: "native".to_owned(),
+            decimals: 18,
+            fee_payment_support: true,
+            contract: None,
+        },
+        ChainAssetInfo {
+            symbol: "FloweR".to_owned(),
+            asset_type: "native-stablecoin".to_owned(),
+            decimals: 6,
+            fee_payment_support: false,
+            contract: None,
+        },
+    ]
+}
+
 /// Returns the canonical chain configuration for FlowCortex L1.
 ///
 /// This provides clients (Treasury UI, FortressDigital, ProofCortex) with
 /// the authoritative chain identity, domain tags, and asset metadata needed
 /// for deterministic signing, verification, and proof-circuit alignment.
+/// `explorer` carries the deep-link templates set for the chain, so clients
+/// need no per-environment explorer hosts of their own.
 ///
 /// MVP: only flowcortex-l1 with PROOF and FloweR.
 pub(crate) async fn chain_config(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
 ) -> ApiResult<ChainConfigResponse> {
     Ok(Json(ChainConfigResponse {
         chain_slug: "flowcortex-l1".to_owned(),
         chain_id_numeric: None, // TBD — awaiting FlowCortex team confirmation
-        signature_scheme: "ed25519".to_owned(),
+        signature_scheme: Ed25519Signer::SIGNATURE_SCHEME.to_owned(),
         address_scheme: "sha256-truncated-20".to_owned(),
-        domains: ChainDomainTags {
-            tx_domain_tag: "keycortex:v1:transaction".to_owned(),
-            auth_domain_tag: "keycortex:v1:auth".to_owned(),
-            proof_domain_tag: "keycortex:v1:proof".to_owned(),
-        },
-        assets: vec![
-            ChainAssetInfo {
-                symbol: "PROOF".to_owned(),
-                asset_type: "native".to_owned(),
-                decimals: 18,
-                fee_payment_support: true,
-            },
-            ChainAssetInfo {
-                symbol: "FloweR".to_owned(),
-                asset_type: "native-stablecoin".to_owned(),
-                decimals: 6,
-                fee_payment_support: false,
-            },
-        ],
+        domains: state.signing_domain.domain_tags(),
+        assets: crate::chains::assets(&state, "flowcortex-l1"),
         finality_rule: "deterministic-single-confirmation".to_owned(),
         environment: "devnet".to_owned(),
+        explorer: crate::chains::explorer(&state, "flowcortex-l1"),
     }))
 }
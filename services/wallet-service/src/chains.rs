@@ -0,0 +1,613 @@
+//! Runtime chain adapter table behind `/ops/chains`.
+//!
+//! The env-configured FlowCortex L1 adapter is the built-in entry. Chains in
+//! the `chains.toml` named by `KEYCORTEX_CHAINS_CONFIG` are installed over
+//! it at startup. Records saved through `/ops/chains` are persisted in
+//! RocksDB and replayed last, overriding either when they share a chain id.
+//!
+//! A background task calls [`refresh_health`] every
+//! `KEYCORTEX_CHAIN_HEALTH_INTERVAL_SECONDS` (default 30), so `/readyz` can
+//! report each chain's reachability without waiting on the nodes.
+
+use anyhow::{Context, Result, anyhow};
+use axum::{Json, http::StatusCode};
+use kc_api_types::{Amount, AmountError, ChainAssetInfo, ChainExplorerTemplates};
+use kc_chain_bitcoin::BitcoinAdapter;
+use kc_chain_client::asset::DEFAULT_TOKEN_DECIMALS;
+use kc_chain_client::{
+    AdapterHealth, AssetDescriptor, ChainAdapter, ChainConfig, ChainRegistry, ChainRegistryConfig,
+    HttpClientConfig, SharedHttp, probe_all,
+};
+use kc_chain_cosmos::CosmosAdapter;
+use kc_chain_ethereum::EthereumAdapter;
+use kc_chain_flowcortex::{FLOWCORTEX_L1, FlowCortexAdapter};
+use kc_storage::{ChainAdapterRecord, RocksDbKeystore};
+use kc_wallet_core::PortfolioSource;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::chaos::Chaos;
+use crate::ops::{
+    explorer_template, validate_bech32_prefix, validate_chain_endpoint, validate_chain_id,
+    validate_chain_kind, validate_denoms, validate_erc20_tokens, validate_kind_fields,
+};
+use crate::{AppState, ApiError, bad_request, internal_error};
+
+pub(crate) const KIND_FLOWCORTEX: &str = "flowcortex";
+pub(crate) const KIND_EVM: &str = "evm";
+pub(crate) const KIND_COSMOS: &str = "cosmos";
+pub(crate) const KIND_BITCOIN: &str = "bitcoin";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_HEALTH_INTERVAL_SECONDS: u64 = 30;
+
+/// Adapters currently serving traffic plus the config each was built from.
+pub(crate) struct ChainTable {
+    adapters: ChainRegistry,
+    configs: BTreeMap<String, ChainAdapterRecord>,
+    /// Assets listed for a chain in `chains.toml`.
+    assets: BTreeMap<String, Vec<ChainAssetInfo>>,
+    chaos: Option<Arc<Chaos>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ChainHealth {
+    pub healthy: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Last background probe of a live adapter, as `/readyz` reports it.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ChainReachability {
+    /// `None` until the first probe completes.
+    pub reachable: Option<bool>,
+    pub latency_ms: Option<u64>,
+    pub block_height: Option<u64>,
+    pub checked_at_epoch_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ChainTable {
+    /// Start from the env-configured FlowCortex L1 adapter.
+    pub fn with_builtin(endpoint: &str, adapter: Arc<dyn ChainAdapter>) -> Self {
+        let mut table = Self {
+            adapters: ChainRegistry::default(),
+            configs: BTreeMap::new(),
+            assets: BTreeMap::new(),
+            chaos: None,
+        };
+        table.configs.insert(
+            FLOWCORTEX_L1.to_owned(),
+            ChainAdapterRecord {
+                chain_id: FLOWCORTEX_L1.to_owned(),
+                kind: KIND_FLOWCORTEX.to_owned(),
+                endpoint: endpoint.trim_end_matches('/').to_owned(),
+                fallback_endpoints: Vec::new(),
+                enabled: true,
+                evm_chain_id: None,
+                explorer_tx_url: None,
+                explorer_address_url: None,
+                explorer_block_url: None,
+                erc20_tokens: BTreeMap::new(),
+                bech32_prefix: None,
+                denoms: BTreeMap::new(),
+                updated_by: "env".to_owned(),
+                updated_at_epoch_ms: 0,
+            },
+        );
+        table.adapters.register(adapter);
+        table
+    }
+
+    /// Set the explorer templates for the built-in FlowCortex L1 entry.
+    pub fn with_builtin_explorer(mut self, explorer: ChainExplorerTemplates) -> Self {
+        if let Some(record) = self.configs.get_mut(FLOWCORTEX_L1) {
+            record.explorer_tx_url = explorer.tx_url;
+            record.explorer_address_url = explorer.address_url;
+            record.explorer_block_url = explorer.block_url;
+        }
+        self
+    }
+
+    /// Build adapters, current and future, on the pooled clients in `http`.
+    /// The built-in adapter is expected to use them already.
+    pub fn with_http(mut self, http: SharedHttp) -> Self {
+        self.adapters = std::mem::take(&mut self.adapters).with_http(http);
+        self
+    }
+
+    /// Route every adapter, current and future, through chaos fault injection.
+    pub fn with_chaos(mut self, chaos: Arc<Chaos>) -> Self {
+        for chain_id in self.adapters.chain_ids() {
+            if let Some(adapter) = self.adapters.remove(&chain_id) {
+                self.adapters.register(chaos.wrap_adapter(adapter));
+            }
+        }
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Install every chain in `config`, replacing entries with the same id.
+    pub fn load_config(&mut self, config: &ChainRegistryConfig) -> Result<()> {
+        for chain in &config.chains {
+            let record = config_record(chain).with_context(|| format!("chain '{}'", chain.slug))?;
+            if !chain.assets.is_empty() {
+                let assets = chain
+                    .assets
+                    .iter()
+                    .map(|(symbol, asset)| ChainAssetInfo {
+                        symbol: symbol.clone(),
+                        asset_type: asset.asset_type.clone(),
+                        decimals: asset.decimals,
+                        fee_payment_support: asset.fee_payment,
+                        contract: asset.contract.clone(),
+                    })
+                    .collect();
+                self.assets.insert(record.chain_id.clone(), assets);
+            }
+            self.apply(record);
+        }
+        Ok(())
+    }
+
+    /// Replay records saved through `/ops/chains`.
+    pub fn load_persisted(&mut self, keystore: &RocksDbKeystore) -> Result<()> {
+        for record in keystore.list_chain_adapters()? {
+            self.apply(record);
+        }
+        Ok(())
+    }
+
+    /// Install `record`, building or dropping its adapter as needed.
+    pub fn apply(&mut self, record: ChainAdapterRecord) {
+        self.adapters.remove(&record.chain_id);
+        if record.enabled {
+            let assets = self.assets.get(&record.chain_id).map(Vec::as_slice);
+            if let Some(adapter) = build_adapter(&record, assets, self.adapters.http()) {
+                let adapter = match &self.chaos {
+                    Some(chaos) => chaos.wrap_adapter(adapter),
+                    None => adapter,
+                };
+                self.adapters.register(adapter);
+            }
+        }
+        self.configs.insert(record.chain_id.clone(), record);
+    }
+
+    /// Serve `adapter` without a config entry, as tests do for extra chains.
+    #[cfg(test)]
+    pub fn register(&mut self, adapter: Arc<dyn ChainAdapter>) {
+        self.adapters.register(adapter);
+    }
+
+    pub fn adapter(&self, chain_id: &str) -> Option<Arc<dyn ChainAdapter>> {
+        self.adapters.adapter(chain_id)
+    }
+
+    pub fn config(&self, chain_id: &str) -> Option<&ChainAdapterRecord> {
+        self.configs.get(chain_id)
+    }
+
+    pub fn configs(&self) -> impl Iterator<Item = &ChainAdapterRecord> {
+        self.configs.values()
+    }
+
+    pub fn assets(&self, chain_id: &str) -> Option<&[ChainAssetInfo]> {
+        self.assets.get(chain_id).map(Vec::as_slice)
+    }
+
+    pub fn adapters(&self) -> Vec<Arc<dyn ChainAdapter>> {
+        self.adapters.adapters()
+    }
+
+    pub fn http(&self) -> &SharedHttp {
+        self.adapters.http()
+    }
+
+    pub fn record_health(&mut self, chain_id: &str, health: AdapterHealth) {
+        self.adapters.record_health(chain_id, health);
+    }
+
+    /// Latest probe of every live adapter, by chain id.
+    pub fn reachability(&self) -> BTreeMap<String, ChainReachability> {
+        self.adapters
+            .chain_ids()
+            .into_iter()
+            .map(|chain_id| {
+                let status = self.adapters.health(&chain_id);
+                let reachability = ChainReachability {
+                    reachable: status.map(|status| status.health.reachable),
+                    latency_ms: status.map(|status| status.health.latency_ms),
+                    block_height: status.and_then(|status| status.health.block_height),
+                    checked_at_epoch_ms: status.map(|status| status.checked_at_epoch_ms),
+                    detail: status.and_then(|status| status.health.detail.clone()),
+                };
+                (chain_id, reachability)
+            })
+            .collect()
+    }
+}
+
+/// The `chains.toml` at `KEYCORTEX_CHAINS_CONFIG`, if set.
+pub(crate) fn config_from_env() -> Result<Option<ChainRegistryConfig>> {
+    match env::var("KEYCORTEX_CHAINS_CONFIG") {
+        Ok(path) if !path.trim().is_empty() => ChainRegistryConfig::load(Path::new(path.trim())).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// The record `/ops/chains` would save for `chain`, held to the same rules.
+fn config_record(chain: &ChainConfig) -> Result<ChainAdapterRecord> {
+    let check = |(_, Json(err)): (StatusCode, Json<ApiError>)| anyhow!(err.message);
+    let chain_id = chain.slug.trim().to_owned();
+    validate_chain_id(&chain_id).map_err(check)?;
+    let kind = chain.adapter.trim().to_ascii_lowercase();
+    validate_chain_kind(&kind).map_err(check)?;
+    let mut endpoints = chain
+        .endpoints
+        .iter()
+        .map(|endpoint| {
+            let endpoint = endpoint.trim().trim_end_matches('/').to_owned();
+            validate_chain_endpoint(&endpoint).map_err(check)?;
+            Ok(endpoint)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if endpoints.len() > 1 && kind != KIND_FLOWCORTEX {
+        return Err(anyhow!("only flowcortex chains fail over between several endpoints"));
+    }
+    let endpoint = endpoints.remove(0);
+
+    let assets_with = |field: fn(&kc_chain_client::AssetConfig) -> Option<&String>| {
+        chain
+            .assets
+            .iter()
+            .filter_map(|(symbol, asset)| Some((symbol.clone(), field(asset)?.clone())))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let erc20_tokens = validate_erc20_tokens(assets_with(|asset| asset.contract.as_ref())).map_err(check)?;
+    let denoms = validate_denoms(assets_with(|asset| asset.denom.as_ref())).map_err(check)?;
+    let bech32_prefix = chain
+        .bech32_prefix
+        .as_deref()
+        .map(validate_bech32_prefix)
+        .transpose()
+        .map_err(check)?;
+    validate_kind_fields(&kind, &erc20_tokens, bech32_prefix.as_deref(), &denoms).map_err(check)?;
+
+    let template = |value: &Option<String>, field: &str, placeholder: &str| {
+        explorer_template(value.clone(), None, field, placeholder).map_err(check)
+    };
+    Ok(ChainAdapterRecord {
+        explorer_tx_url: template(&chain.explorer_tx_url, "explorer_tx_url", "{tx_hash}")?,
+        explorer_address_url: template(&chain.explorer_address_url, "explorer_address_url", "{address}")?,
+        explorer_block_url: template(&chain.explorer_block_url, "explorer_block_url", "{height}")?,
+        evm_chain_id: chain.evm_chain_id.filter(|_| kind == KIND_EVM),
+        chain_id,
+        kind,
+        endpoint,
+        fallback_endpoints: endpoints,
+        enabled: chain.enabled,
+        erc20_tokens,
+        bech32_prefix,
+        denoms,
+        updated_by: "config".to_owned(),
+        updated_at_epoch_ms: 0,
+    })
+}
+
+/// Pool size and timeouts for the clients every adapter shares, from
+/// `KEYCORTEX_CHAIN_HTTP_*`.
+pub(crate) fn http_config_from_env() -> HttpClientConfig {
+    let var = |name: &str| {
+        env::var(name)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|value| *value > 0)
+    };
+    let defaults = HttpClientConfig::default();
+    HttpClientConfig {
+        pool_max_idle_per_host: var("KEYCORTEX_CHAIN_HTTP_POOL_MAX_IDLE_PER_HOST")
+            .and_then(|idle| usize::try_from(idle).ok())
+            .unwrap_or(defaults.pool_max_idle_per_host),
+        pool_idle_timeout: var("KEYCORTEX_CHAIN_HTTP_POOL_IDLE_TIMEOUT_SECONDS")
+            .map_or(defaults.pool_idle_timeout, Duration::from_secs),
+        connect_timeout: var("KEYCORTEX_CHAIN_HTTP_CONNECT_TIMEOUT_SECONDS")
+            .map_or(defaults.connect_timeout, Duration::from_secs),
+        request_timeout: var("KEYCORTEX_CHAIN_HTTP_TIMEOUT_SECONDS")
+            .map_or(defaults.request_timeout, Duration::from_secs),
+    }
+}
+
+pub(crate) fn health_interval_from_env() -> Duration {
+    let seconds = env::var("KEYCORTEX_CHAIN_HEALTH_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_HEALTH_INTERVAL_SECONDS);
+    Duration::from_secs(seconds)
+}
+
+/// Probe every live adapter and keep the results for `/readyz`.
+pub(crate) async fn refresh_health(state: &AppState) {
+    let adapters = match state.chains.read() {
+        Ok(table) => table.adapters(),
+        Err(_) => return,
+    };
+    let results = probe_all(adapters, PROBE_TIMEOUT).await;
+    if let Ok(mut table) = state.chains.write() {
+        for (chain_id, health) in results {
+            table.record_health(&chain_id, health);
+        }
+    }
+}
+
+/// `assets` are the chain's configured assets, which give ERC-20 tokens
+/// their decimals; tokens without one are taken to use 18.
+fn build_adapter(
+    record: &ChainAdapterRecord,
+    assets: Option<&[ChainAssetInfo]>,
+    http: &SharedHttp,
+) -> Option<Arc<dyn ChainAdapter>> {
+    match record.kind.as_str() {
+        KIND_FLOWCORTEX => {
+            let endpoints: Vec<String> = std::iter::once(&record.endpoint)
+                .chain(&record.fallback_endpoints)
+                .cloned()
+                .collect();
+            Some(Arc::new(
+                FlowCortexAdapter::for_chain_endpoints(&record.chain_id, &endpoints)
+                    .with_http_client(http.self_signed.clone()),
+            ))
+        }
+        KIND_EVM => Some(Arc::new(record.erc20_tokens.iter().fold(
+            EthereumAdapter::new(&record.chain_id, &record.endpoint).with_http_client(http.verified.clone()),
+            |adapter, (symbol, contract)| {
+                let decimals = assets
+                    .and_then(|assets| assets.iter().find(|asset| asset.symbol.eq_ignore_ascii_case(symbol)))
+                    .map_or(DEFAULT_TOKEN_DECIMALS, |asset| asset.decimals);
+                adapter.with_asset(AssetDescriptor::contract(symbol, contract, decimals))
+            },
+        ))),
+        KIND_COSMOS => {
+            let prefix = record.bech32_prefix.as_deref()?;
+            Some(Arc::new(record.denoms.iter().fold(
+                CosmosAdapter::new(&record.chain_id, &record.endpoint, prefix).with_http_client(http.verified.clone()),
+                |adapter, (symbol, denom)| adapter.with_denom(symbol, denom),
+            )))
+        }
+        KIND_BITCOIN => {
+            let prefix = record.bech32_prefix.as_deref()?;
+            Some(Arc::new(
+                BitcoinAdapter::new(&record.chain_id, &record.endpoint, prefix).with_http_client(http.verified.clone()),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// The live adapter for `chain`, if one is registered and enabled.
+pub(crate) fn lookup(state: &AppState, chain: &str) -> Option<Arc<dyn ChainAdapter>> {
+    state
+        .chains
+        .read()
+        .ok()
+        .and_then(|table| table.adapter(chain))
+}
+
+/// Assets enabled on `chain`: those `chains.toml` lists for it, else
+/// FlowCortex L1's.
+pub(crate) fn assets(state: &AppState, chain: &str) -> Vec<ChainAssetInfo> {
+    state
+        .chains
+        .read()
+        .ok()
+        .and_then(|table| table.assets(chain).map(<[_]>::to_vec))
+        .unwrap_or_else(crate::chain_config::flowcortex_assets)
+}
+
+/// Every live adapter with the assets enabled on it, by chain id. Chains
+/// other than FlowCortex L1 without assets in `chains.toml` are left out.
+pub(crate) fn portfolio_sources(state: &AppState) -> Vec<PortfolioSource> {
+    let Ok(table) = state.chains.read() else {
+        return Vec::new();
+    };
+    let mut adapters = table.adapters();
+    adapters.sort_by(|a, b| a.chain_id().cmp(b.chain_id()));
+    adapters
+        .into_iter()
+        .filter_map(|adapter| {
+            let assets = match table.assets(adapter.chain_id()) {
+                Some(assets) => assets.to_vec(),
+                None if adapter.chain_id() == FLOWCORTEX_L1 => crate::chain_config::flowcortex_assets(),
+                None => return None,
+            };
+            Some(PortfolioSource { adapter, assets })
+        })
+        .collect()
+}
+
+/// The enabled asset `symbol` on `chain`, as a 400 for handlers.
+pub(crate) fn asset(
+    state: &AppState,
+    chain: &str,
+    symbol: &str,
+) -> Result<ChainAssetInfo, (StatusCode, Json<ApiError>)> {
+    let assets = assets(state, chain);
+    if let Some(asset) = assets.iter().find(|asset| asset.symbol == symbol) {
+        return Ok(asset.clone());
+    }
+    let symbols: Vec<&str> = assets.iter().map(|asset| asset.symbol.as_str()).collect();
+    let listed = match symbols.split_last() {
+        Some((last, [])) => format!("{last} is"),
+        Some((last, rest)) => format!("{} and {last} are", rest.join(", ")),
+        None => "no assets are".to_owned(),
+    };
+    Err(bad_request(&format!("unsupported asset for MVP; only {listed} enabled")))
+}
+
+/// `amount` of the enabled asset `symbol` on `chain`, bounded by the
+/// asset's decimals, as a 400 for handlers.
+pub(crate) fn amount(
+    state: &AppState,
+    chain: &str,
+    symbol: &str,
+    amount: &str,
+) -> Result<Amount, (StatusCode, Json<ApiError>)> {
+    let asset = asset(state, chain, symbol)?;
+    Amount::parse(amount, asset.decimals).map_err(|err| match err {
+        AmountError::AboveMaximum { decimals } => bad_request(&format!(
+            "amount exceeds the maximum for {} ({decimals} decimals)",
+            asset.symbol
+        )),
+        err => bad_request(&err.to_string()),
+    })
+}
+
+/// Explorer templates configured for `chain`; all unset for unknown chains.
+pub(crate) fn explorer(state: &AppState, chain: &str) -> ChainExplorerTemplates {
+    let Ok(table) = state.chains.read() else {
+        return ChainExplorerTemplates::default();
+    };
+    table
+        .config(chain)
+        .map(|record| ChainExplorerTemplates {
+            tx_url: record.explorer_tx_url.clone(),
+            address_url: record.explorer_address_url.clone(),
+            block_url: record.explorer_block_url.clone(),
+        })
+        .unwrap_or_default()
+}
+
+/// Explorer page for `tx_hash`, from the chain's `explorer_tx_url` template.
+pub(crate) fn explorer_tx_url(state: &AppState, chain: &str, tx_hash: &str) -> Option<String> {
+    explorer(state, chain)
+        .tx_url
+        .map(|template| template.replace("{tx_hash}", tx_hash))
+}
+
+/// Explorer page for `address`, from the chain's `explorer_address_url` template.
+pub(crate) fn explorer_address_url(state: &AppState, chain: &str, address: &str) -> Option<String> {
+    explorer(state, chain)
+        .address_url
+        .map(|template| template.replace("{address}", address))
+}
+
+/// The shared adapter clients, for probes outside the adapters.
+pub(crate) fn http(state: &AppState) -> SharedHttp {
+    state
+        .chains
+        .read()
+        .map(|table| table.http().clone())
+        .unwrap_or_default()
+}
+
+/// Like [`lookup`], as a 400 for handlers.
+pub(crate) fn adapter(
+    state: &AppState,
+    chain: &str,
+) -> Result<Arc<dyn ChainAdapter>, (StatusCode, Json<ApiError>)> {
+    let table = state
+        .chains
+        .read()
+        .map_err(|_| internal_error("chain table lock poisoned"))?;
+    table
+        .adapter(chain)
+        .ok_or_else(|| bad_request(&format!("chain '{chain}' is not enabled")))
+}
+
+/// Check that the node behind `record` answers, and for EVM that it reports
+/// the expected chain id.
+pub(crate) async fn probe(http: &SharedHttp, record: &ChainAdapterRecord) -> ChainHealth {
+    let started = Instant::now();
+    let outcome = match record.kind.as_str() {
+        KIND_FLOWCORTEX => probe_flowcortex(&http.self_signed, &record.endpoint).await,
+        KIND_EVM => probe_evm(&http.verified, &record.endpoint, record.evm_chain_id).await,
+        KIND_COSMOS => probe_cosmos(&http.verified, &record.endpoint).await,
+        KIND_BITCOIN => probe_bitcoin(&http.verified, &record.endpoint).await,
+        other => Err(anyhow!("unknown adapter kind '{other}'")),
+    };
+    ChainHealth {
+        healthy: outcome.is_ok(),
+        latency_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        detail: outcome.err().map(|err| format!("{err:#}")),
+    }
+}
+
+async fn probe_flowcortex(http: &reqwest::Client, endpoint: &str) -> Result<()> {
+    let response = http
+        .get(format!("{endpoint}/blocks"))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .context("flowcortex probe transport")?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("flowcortex probe HTTP {status}"));
+    }
+    Ok(())
+}
+
+async fn probe_evm(http: &reqwest::Client, endpoint: &str, expected: Option<u64>) -> Result<()> {
+    let response = http
+        .post(endpoint)
+        .timeout(PROBE_TIMEOUT)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_chainId",
+            "params": [],
+        }))
+        .send()
+        .await
+        .context("eth_chainId transport")?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("eth_chainId HTTP {status}"));
+    }
+    let body: serde_json::Value = response.json().await.context("eth_chainId response")?;
+    let reported = body["result"]
+        .as_str()
+        .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| anyhow!("eth_chainId returned no chain id: {body}"))?;
+    match expected {
+        Some(expected) if expected != reported => Err(anyhow!(
+            "node reports chain id {reported}, expected {expected}"
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn probe_cosmos(http: &reqwest::Client, endpoint: &str) -> Result<()> {
+    let response = http
+        .get(format!("{endpoint}/cosmos/base/tendermint/v1beta1/blocks/latest"))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .context("cosmos probe transport")?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("cosmos probe HTTP {status}"));
+    }
+    Ok(())
+}
+
+async fn probe_bitcoin(http: &reqwest::Client, endpoint: &str) -> Result<()> {
+    let response = http
+        .get(format!("{endpoint}/blocks/tip/height"))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .context("bitcoin probe transport")?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("bitcoin probe HTTP {status}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Env-gated fault injection for soak testing.
+//!
+//! Off unless `KEYCORTEX_CHAOS_ENABLED=true`. Each fault fires at its own
+//! rate, a probability in `[0, 1]`:
+//!
+//! - `KEYCORTEX_CHAOS_STORAGE_LATENCY_RATE` stalls a RocksDB operation for
+//!   `KEYCORTEX_CHAOS_STORAGE_LATENCY_MS` (default 50).
+//! - `KEYCORTEX_CHAOS_POSTGRES_FAILURE_RATE` fails a Postgres query before it
+//!   is sent, exercising the RocksDB fallbacks.
+//! - `KEYCORTEX_CHAOS_CHAIN_TIMEOUT_RATE` makes a chain adapter call wait
+//!   `KEYCORTEX_CHAOS_CHAIN_TIMEOUT_MS` (default 2000) and then fail.
+//!
+//! Injected fault counts are reported under `chaos` in `/health` and
+//! `/startupz`.
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use kc_api_types::{AssetSymbol, WalletAddress};
+use kc_chain_client::{
+    AdapterHealth, BalanceResult, ChainAdapter, NonceStrategy, SubmitTxRequest, SubmitTxResult, TxStatusRequest,
+    TxStatusResult,
+};
+use kc_storage::AccessHook;
+use serde::Serialize;
+use std::env;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+const DEFAULT_STORAGE_LATENCY_MS: u64 = 50;
+const DEFAULT_CHAIN_TIMEOUT_MS: u64 = 2_000;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChaosConfig {
+    pub storage_latency_rate: f64,
+    pub storage_latency: Duration,
+    pub postgres_failure_rate: f64,
+    pub chain_timeout_rate: f64,
+    pub chain_timeout: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ChaosSnapshot {
+    storage_latency_rate: f64,
+    postgres_failure_rate: f64,
+    chain_timeout_rate: f64,
+    storage_delays_injected: u64,
+    postgres_failures_injected: u64,
+    chain_timeouts_injected: u64,
+}
+
+pub(crate) struct Chaos {
+    config: ChaosConfig,
+    storage_delays: AtomicU64,
+    postgres_failures: AtomicU64,
+    chain_timeouts: AtomicU64,
+}
+
+impl ChaosConfig {
+    pub fn from_env() -> Result<Option<Self>> {
+        let enabled = env::var("KEYCORTEX_CHAOS_ENABLED")
+            .map(|value| value.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            storage_latency_rate: rate_from_env("KEYCORTEX_CHAOS_STORAGE_LATENCY_RATE")?,
+            storage_latency: millis_from_env(
+                "KEYCORTEX_CHAOS_STORAGE_LATENCY_MS",
+                DEFAULT_STORAGE_LATENCY_MS,
+            )?,
+            postgres_failure_rate: rate_from_env("KEYCORTEX_CHAOS_POSTGRES_FAILURE_RATE")?,
+            chain_timeout_rate: rate_from_env("KEYCORTEX_CHAOS_CHAIN_TIMEOUT_RATE")?,
+            chain_timeout: millis_from_env(
+                "KEYCORTEX_CHAOS_CHAIN_TIMEOUT_MS",
+                DEFAULT_CHAIN_TIMEOUT_MS,
+            )?,
+        }))
+    }
+}
+
+fn rate_from_env(name: &str) -> Result<f64> {
+    let Ok(value) = env::var(name) else {
+        return Ok(0.0);
+    };
+    let rate: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("{name} must be a number"))?;
+    if !(0.0..=1.0).contains(&rate) {
+        bail!("{name} must be between 0 and 1");
+    }
+    Ok(rate)
+}
+
+fn millis_from_env(name: &str, default: u64) -> Result<Duration> {
+    let millis = match env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .with_context(|| format!("{name} must be a whole number of milliseconds"))?,
+        Err(_) => default,
+    };
+    Ok(Duration::from_millis(millis))
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            storage_delays: AtomicU64::new(0),
+            postgres_failures: AtomicU64::new(0),
+            chain_timeouts: AtomicU64::new(0),
+        })
+    }
+
+    fn roll(rate: f64) -> bool {
+        rate > 0.0 && rand::random::<f64>() < rate
+    }
+
+    /// RocksDB hook that blocks the calling thread, as a stalled disk would.
+    pub fn storage_hook(self: &Arc<Self>) -> AccessHook {
+        let chaos = Arc::clone(self);
+        Arc::new(move || {
+            if Self::roll(chaos.config.storage_latency_rate) {
+                chaos.storage_delays.fetch_add(1, Ordering::Relaxed);
+                std::thread::sleep(chaos.config.storage_latency);
+            }
+        })
+    }
+
+    /// Called before each Postgres query.
+    pub fn postgres_fault(&self) -> Result<()> {
+        if Self::roll(self.config.postgres_failure_rate) {
+            self.postgres_failures.fetch_add(1, Ordering::Relaxed);
+            bail!("chaos: injected Postgres failure");
+        }
+        Ok(())
+    }
+
+    async fn chain_fault(&self) -> Result<()> {
+        if Self::roll(self.config.chain_timeout_rate) {
+            self.chain_timeouts.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(self.config.chain_timeout).await;
+            bail!(
+                "chaos: injected chain timeout after {} ms",
+                self.config.chain_timeout.as_millis()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn wrap_adapter(self: &Arc<Self>, inner: Arc<dyn ChainAdapter>) -> Arc<dyn ChainAdapter> {
+        Arc::new(ChaosAdapter {
+            inner,
+            chaos: Arc::clone(self),
+        })
+    }
+
+    pub fn snapshot(&self) -> ChaosSnapshot {
+        ChaosSnapshot {
+            storage_latency_rate: self.config.storage_latency_rate,
+            postgres_failure_rate: self.config.postgres_failure_rate,
+            chain_timeout_rate: self.config.chain_timeout_rate,
+            storage_delays_injected: self.storage_delays.load(Ordering::Relaxed),
+            postgres_failures_injected: self.postgres_failures.load(Ordering::Relaxed),
+            chain_timeouts_injected: self.chain_timeouts.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Chain adapter whose network calls may time out under chaos.
+struct ChaosAdapter {
+    inner: Arc<dyn ChainAdapter>,
+    chaos: Arc<Chaos>,
+}
+
+#[async_trait]
+impl ChainAdapter for ChaosAdapter {
+    fn chain_id(&self) -> &str {
+        self.inner.chain_id()
+    }
+
+    fn nonce_strategy(&self) -> NonceStrategy {
+        self.inner.nonce_strategy()
+    }
+
+    async fn get_account_nonce(&self, wallet_address: &WalletAddress) -> Result<u64> {
+        self.chaos.chain_fault().await?;
+        self.inner.get_account_nonce(wallet_address).await
+    }
+
+    async fn get_recent_blockhash(&self) -> Result<String> {
+        self.chaos.chain_fault().await?;
+        self.inner.get_recent_blockhash().await
+    }
+
+    /// An injected timeout reports the chain unreachable, as a real one would.
+    async fn health(&self) -> AdapterHealth {
+        if let Err(err) = self.chaos.chain_fault().await {
+            return AdapterHealth::unreachable(
+                u64::try_from(self.chaos.config.chain_timeout.as_millis()).unwrap_or(u64::MAX),
+                format!("{err:#}"),
+            );
+        }
+        self.inner.health().await
+    }
+
+    async fn get_balance(
+        &self,
+        wallet_address: &WalletAddress,
+        asset: &AssetSymbol,
+    ) -> Result<BalanceResult> {
+        self.chaos.chain_fault().await?;
+        self.inner.get_balance(wallet_address, asset).await
+    }
+
+    async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+        self.chaos.chain_fault().await?;
+        self.inner.submit_transaction(req).await
+    }
+
+    async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+        self.chaos.chain_fault().await?;
+        self.inner.get_transaction_status(req).await
+    }
+}
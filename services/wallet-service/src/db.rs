@@ -1,13 +1,23 @@
 use anyhow::Context;
-use kc_storage::{AuditEventRecord, WalletBindingRecord};
+use async_trait::async_trait;
+use kc_auth_adapter::{ChallengeOutcome, ChallengeRecord, ChallengeStore};
+use kc_storage::{
+    AuditEventPage, AuditEventRecord, SubmitIdempotencyRecord, SubmittedTxPage, SubmittedTxRecord,
+    WalletBindingRecord, WalletMetadataRecord, WalletNonceRecord, parse_audit_cursor,
+    parse_history_cursor,
+};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio_postgres::{Client, NoTls};
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::chaos::Chaos;
+
 pub(crate) struct PostgresRepository {
     client: Client,
+    chaos: Option<Arc<Chaos>>,
 }
 
 impl PostgresRepository {
@@ -22,10 +32,23 @@ impl PostgresRepository {
             }
         });
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            chaos: None,
+        })
+    }
+
+    pub(crate) fn with_chaos(mut self, chaos: Arc<Chaos>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    fn inject_fault(&self) -> anyhow::Result<()> {
+        self.chaos.as_ref().map_or(Ok(()), |chaos| chaos.postgres_fault())
     }
 
     pub(crate) async fn save_wallet_binding(&self, record: &WalletBindingRecord) -> anyhow::Result<()> {
+        self.inject_fault()?;
         self.client
             .execute(
                 "INSERT INTO wallet_bindings (wallet_address, user_id, chain, last_verified_epoch_ms, updated_at)
@@ -49,6 +72,19 @@ impl PostgresRepository {
         Ok(())
     }
 
+    pub(crate) async fn delete_wallet_binding(&self, wallet_address: &str) -> anyhow::Result<()> {
+        self.inject_fault()?;
+        self.client
+            .execute(
+                "DELETE FROM wallet_bindings WHERE wallet_address = $1",
+                &[&wallet_address],
+            )
+            .await
+            .context("failed to delete wallet binding from Postgres")?;
+
+        Ok(())
+    }
+
     pub(crate) async fn run_migrations_from_dir(&self, migrations_dir: &str) -> anyhow::Result<usize> {
         let mut files: Vec<PathBuf> = fs::read_dir(migrations_dir)
             .with_context(|| format!("failed to read migrations directory: {migrations_dir}"))?
@@ -75,6 +111,7 @@ impl PostgresRepository {
         &self,
         wallet_address: &str,
     ) -> anyhow::Result<Option<WalletBindingRecord>> {
+        self.inject_fault()?;
         let row = self
             .client
             .query_opt(
@@ -94,7 +131,86 @@ impl PostgresRepository {
         }))
     }
 
+    pub(crate) async fn save_wallet_metadata(&self, record: &WalletMetadataRecord) -> anyhow::Result<()> {
+        self.inject_fault()?;
+        self.client
+            .execute(
+                "INSERT INTO wallet_metadata (
+                   wallet_address, label, created_at_epoch_ms, key_scheme, signature_count,
+                   last_signed_at_epoch_ms, last_submitted_at_epoch_ms, updated_at
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+                 ON CONFLICT (wallet_address)
+                 DO UPDATE SET
+                   label = EXCLUDED.label,
+                   created_at_epoch_ms = EXCLUDED.created_at_epoch_ms,
+                   key_scheme = EXCLUDED.key_scheme,
+                   signature_count = EXCLUDED.signature_count,
+                   last_signed_at_epoch_ms = EXCLUDED.last_signed_at_epoch_ms,
+                   last_submitted_at_epoch_ms = EXCLUDED.last_submitted_at_epoch_ms,
+                   updated_at = NOW()",
+                &[
+                    &record.wallet_address,
+                    &record.label,
+                    &record.created_at_epoch_ms.map(to_i64),
+                    &record.key_scheme,
+                    &i64::try_from(record.usage.signature_count).unwrap_or(i64::MAX),
+                    &record.usage.last_signed_at_epoch_ms.map(to_i64),
+                    &record.usage.last_submitted_at_epoch_ms.map(to_i64),
+                ],
+            )
+            .await
+            .context("failed to save wallet metadata to Postgres")?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn delete_wallet_metadata(&self, wallet_address: &str) -> anyhow::Result<()> {
+        self.inject_fault()?;
+        self.client
+            .execute(
+                "DELETE FROM wallet_metadata WHERE wallet_address = $1",
+                &[&wallet_address],
+            )
+            .await
+            .context("failed to delete wallet metadata from Postgres")?;
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn load_wallet_metadata(
+        &self,
+        wallet_address: &str,
+    ) -> anyhow::Result<Option<WalletMetadataRecord>> {
+        self.inject_fault()?;
+        let row = self
+            .client
+            .query_opt(
+                "SELECT wallet_address, label, created_at_epoch_ms, key_scheme, signature_count,
+                        last_signed_at_epoch_ms, last_submitted_at_epoch_ms
+                 FROM wallet_metadata
+                 WHERE wallet_address = $1",
+                &[&wallet_address],
+            )
+            .await
+            .context("failed to load wallet metadata from Postgres")?;
+
+        Ok(row.map(|entry| WalletMetadataRecord {
+            wallet_address: entry.get::<_, String>(0),
+            label: entry.get::<_, Option<String>>(1),
+            created_at_epoch_ms: entry.get::<_, Option<i64>>(2).map(from_i64),
+            key_scheme: entry.get::<_, String>(3),
+            usage: kc_storage::WalletUsage {
+                signature_count: u64::try_from(entry.get::<_, i64>(4)).unwrap_or_default(),
+                last_signed_at_epoch_ms: entry.get::<_, Option<i64>>(5).map(from_i64),
+                last_submitted_at_epoch_ms: entry.get::<_, Option<i64>>(6).map(from_i64),
+            },
+        }))
+    }
+
     pub(crate) async fn append_audit_event(&self, record: &AuditEventRecord) -> anyhow::Result<String> {
+        self.inject_fault()?;
         let event_id = if record.event_id.trim().is_empty() {
             Uuid::new_v4().to_string()
         } else {
@@ -130,6 +246,7 @@ impl PostgresRepository {
         wallet_address: Option<&str>,
         outcome: Option<&str>,
     ) -> anyhow::Result<Vec<AuditEventRecord>> {
+        self.inject_fault()?;
         let rows = self
             .client
             .query(
@@ -161,33 +278,228 @@ impl PostgresRepository {
                 outcome: row.get::<_, String>(5),
                 message: row.get::<_, Option<String>>(6),
                 timestamp_epoch_ms: from_i64(row.get::<_, i64>(7)),
+                tenant: None,
             })
             .collect();
 
         Ok(events)
     }
 
+    /// Audit events matching the filters, newest first, in the same order
+    /// and with the same cursors as
+    /// [`kc_storage::RocksDbKeystore::list_audit_events_page`].
+    pub(crate) async fn list_audit_events_page(
+        &self,
+        limit: usize,
+        cursor: Option<&str>,
+        event_type: Option<&str>,
+        wallet_address: Option<&str>,
+        outcome: Option<&str>,
+    ) -> anyhow::Result<AuditEventPage> {
+        self.inject_fault()?;
+        let cursor = match cursor {
+            Some(cursor) => Some(
+                parse_audit_cursor(cursor).ok_or_else(|| anyhow::anyhow!("invalid audit cursor"))?,
+            ),
+            None => None,
+        };
+        let rows = self
+            .client
+            .query(
+                "SELECT log_id, event_type, wallet_address, user_id, chain, outcome, message, timestamp_epoch_ms
+                 FROM verification_logs
+                 WHERE ($1::TEXT IS NULL OR event_type = $1)
+                   AND ($2::TEXT IS NULL OR wallet_address = $2)
+                   AND ($3::TEXT IS NULL OR outcome = $3)
+                   AND ($4::BIGINT IS NULL
+                        OR (timestamp_epoch_ms, log_id COLLATE \"C\") < ($4, $5::TEXT COLLATE \"C\"))
+                 ORDER BY timestamp_epoch_ms DESC, log_id COLLATE \"C\" DESC
+                 LIMIT $6",
+                &[
+                    &event_type,
+                    &wallet_address,
+                    &outcome,
+                    &cursor.map(|(timestamp, _)| to_i64(timestamp)),
+                    &cursor.map(|(_, event_id)| event_id),
+                    &(limit as i64 + 1),
+                ],
+            )
+            .await
+            .context("failed to list audit events from Postgres")?;
+
+        let mut events: Vec<AuditEventRecord> = rows
+            .into_iter()
+            .map(|row| AuditEventRecord {
+                event_id: row.get::<_, String>(0),
+                event_type: row.get::<_, String>(1),
+                wallet_address: row.get::<_, Option<String>>(2),
+                user_id: row.get::<_, Option<String>>(3),
+                chain: row.get::<_, Option<String>>(4),
+                outcome: row.get::<_, String>(5),
+                message: row.get::<_, Option<String>>(6),
+                timestamp_epoch_ms: from_i64(row.get::<_, i64>(7)),
+                tenant: None,
+            })
+            .collect();
+        let next_cursor = if events.len() > limit {
+            events.truncate(limit);
+            events.last().map(AuditEventRecord::page_cursor)
+        } else {
+            None
+        };
+
+        Ok(AuditEventPage { events, next_cursor })
+    }
+
+    /// Mirror a submission's tx, nonce and idempotency records.
+    ///
+    /// The client is shared, so it cannot open a `BEGIN`/`COMMIT` block
+    /// without other queries interleaving. The three writes are instead one
+    /// statement of data-modifying CTEs, which Postgres applies atomically.
+    pub(crate) async fn save_submission(
+        &self,
+        tx: &SubmittedTxRecord,
+        nonce: Option<&WalletNonceRecord>,
+        idempotency: Option<&SubmitIdempotencyRecord>,
+    ) -> anyhow::Result<()> {
+        self.inject_fault()?;
+        self.client
+            .execute(
+                "WITH tx AS (
+                   INSERT INTO submitted_txs
+                   (tx_hash, status, accepted, chain, from_address, to_address, asset, amount, submitted_at_epoch_ms)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                   ON CONFLICT (tx_hash)
+                   DO UPDATE SET status = EXCLUDED.status, accepted = EXCLUDED.accepted
+                 ), nonce AS (
+                   INSERT INTO wallet_nonces (wallet_address, last_nonce, updated_at_epoch_ms)
+                   SELECT $10::TEXT, $11::BIGINT, $12::BIGINT WHERE $10::TEXT IS NOT NULL
+                   ON CONFLICT (wallet_address)
+                   DO UPDATE SET
+                     last_nonce = EXCLUDED.last_nonce,
+                     updated_at_epoch_ms = EXCLUDED.updated_at_epoch_ms
+                 )
+                 INSERT INTO submit_idempotency
+                 (idempotency_key, tx_hash, accepted, signature, expires_at_epoch_ms, created_at_epoch_ms)
+                 SELECT $13::TEXT, $1, $3, $14::TEXT, $15::BIGINT, $16::BIGINT
+                 WHERE $13::TEXT IS NOT NULL
+                 ON CONFLICT (idempotency_key) DO NOTHING",
+                &[
+                    &tx.tx_hash,
+                    &tx.status,
+                    &tx.accepted,
+                    &tx.chain,
+                    &tx.from,
+                    &tx.to,
+                    &tx.asset,
+                    &tx.amount,
+                    &to_i64(tx.submitted_at_epoch_ms),
+                    &nonce.map(|n| n.wallet_address.as_str()),
+                    &nonce.map(|n| to_i64(u128::from(n.last_nonce))),
+                    &nonce.map(|n| to_i64(n.updated_at_epoch_ms)),
+                    &idempotency.map(|i| i.idempotency_key.as_str()),
+                    &idempotency.map(|i| i.signature.as_str()),
+                    &idempotency.and_then(|i| i.expires_at_epoch_ms).map(to_i64),
+                    &idempotency.map(|i| to_i64(i.created_at_epoch_ms)),
+                ],
+            )
+            .await
+            .context("failed to save submission to Postgres")?;
+
+        Ok(())
+    }
+
+    /// Transfers sent or received by `wallet_address`, newest first, in the
+    /// same order and with the same cursors as
+    /// [`kc_storage::RocksDbKeystore::list_submitted_txs`]. The mirror keeps no
+    /// block heights or status timeline.
+    pub(crate) async fn list_submitted_txs(
+        &self,
+        wallet_address: &str,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> anyhow::Result<SubmittedTxPage> {
+        self.inject_fault()?;
+        let cursor = match cursor {
+            Some(cursor) => Some(
+                parse_history_cursor(cursor)
+                    .ok_or_else(|| anyhow::anyhow!("invalid history cursor"))?,
+            ),
+            None => None,
+        };
+        let rows = self
+            .client
+            .query(
+                "SELECT tx_hash, status, accepted, chain, from_address, to_address, asset, amount, submitted_at_epoch_ms
+                 FROM submitted_txs
+                 WHERE (from_address = $1 OR to_address = $1)
+                   AND ($2::BIGINT IS NULL
+                        OR (submitted_at_epoch_ms, tx_hash COLLATE \"C\") < ($2, $3::TEXT COLLATE \"C\"))
+                 ORDER BY submitted_at_epoch_ms DESC, tx_hash COLLATE \"C\" DESC
+                 LIMIT $4",
+                &[
+                    &wallet_address,
+                    &cursor.map(|(submitted_at, _)| to_i64(submitted_at)),
+                    &cursor.map(|(_, tx_hash)| tx_hash),
+                    &(limit as i64 + 1),
+                ],
+            )
+            .await
+            .context("failed to list submitted txs from Postgres")?;
+
+        let mut records: Vec<SubmittedTxRecord> = rows
+            .into_iter()
+            .map(|row| SubmittedTxRecord {
+                tx_hash: row.get::<_, String>(0),
+                status: row.get::<_, String>(1),
+                accepted: row.get::<_, bool>(2),
+                chain: row.get::<_, String>(3),
+                from: row.get::<_, String>(4),
+                to: row.get::<_, String>(5),
+                asset: row.get::<_, String>(6),
+                amount: row.get::<_, String>(7),
+                submitted_at_epoch_ms: from_i64(row.get::<_, i64>(8)),
+                block_height: None,
+                confirmations: None,
+                status_history: Vec::new(),
+            })
+            .collect();
+        let next_cursor = if records.len() > limit {
+            records.truncate(limit);
+            records.last().map(SubmittedTxRecord::history_cursor)
+        } else {
+            None
+        };
+
+        Ok(SubmittedTxPage {
+            records,
+            next_cursor,
+        })
+    }
+
     pub(crate) async fn upsert_challenge(
         &self,
         challenge: &str,
-        issued_at_epoch_ms: u128,
-        expires_at_epoch_ms: u128,
+        record: &ChallengeRecord,
     ) -> anyhow::Result<()> {
+        self.inject_fault()?;
         self.client
             .execute(
                 "INSERT INTO challenge_store (challenge, issued_at_epoch_ms, expires_at_epoch_ms, used, used_at_epoch_ms, updated_at)
-                 VALUES ($1, $2, $3, FALSE, NULL, NOW())
+                 VALUES ($1, $2, $3, $4, $5, NOW())
                  ON CONFLICT (challenge)
                  DO UPDATE SET
                    issued_at_epoch_ms = EXCLUDED.issued_at_epoch_ms,
                    expires_at_epoch_ms = EXCLUDED.expires_at_epoch_ms,
-                   used = FALSE,
-                   used_at_epoch_ms = NULL,
+                   used = EXCLUDED.used,
+                   used_at_epoch_ms = EXCLUDED.used_at_epoch_ms,
                    updated_at = NOW()",
                 &[
                     &challenge,
-                    &to_i64(issued_at_epoch_ms),
-                    &to_i64(expires_at_epoch_ms),
+                    &to_i64(record.issued_at_epoch_ms),
+                    &to_i64(record.expires_at_epoch_ms),
+                    &record.used,
+                    &record.used_at_epoch_ms.map(to_i64),
                 ],
             )
             .await
@@ -201,6 +513,7 @@ impl PostgresRepository {
         challenge: &str,
         used_at_epoch_ms: u128,
     ) -> anyhow::Result<()> {
+        self.inject_fault()?;
         self.client
             .execute(
                 "UPDATE challenge_store
@@ -215,6 +528,58 @@ impl PostgresRepository {
     }
 }
 
+#[async_trait]
+impl ChallengeStore for PostgresRepository {
+    async fn insert(&self, challenge: &str, record: ChallengeRecord) -> anyhow::Result<()> {
+        self.upsert_challenge(challenge, &record).await
+    }
+
+    async fn consume(&self, challenge: &str, now_epoch_ms: u128) -> anyhow::Result<ChallengeOutcome> {
+        self.inject_fault()?;
+        let consumed = self
+            .client
+            .query_opt(
+                "UPDATE challenge_store
+                 SET used = TRUE, used_at_epoch_ms = $2, updated_at = NOW()
+                 WHERE challenge = $1 AND NOT used
+                 RETURNING expires_at_epoch_ms",
+                &[&challenge, &to_i64(now_epoch_ms)],
+            )
+            .await
+            .context("failed to consume challenge in Postgres")?;
+        if let Some(row) = consumed {
+            let expires_at_epoch_ms = from_i64(row.get(0));
+            return Ok(if now_epoch_ms > expires_at_epoch_ms {
+                ChallengeOutcome::Expired
+            } else {
+                ChallengeOutcome::Consumed
+            });
+        }
+        let exists = self
+            .client
+            .query_opt("SELECT 1 FROM challenge_store WHERE challenge = $1", &[&challenge])
+            .await
+            .context("failed to load challenge from Postgres")?
+            .is_some();
+        Ok(if exists {
+            ChallengeOutcome::AlreadyUsed
+        } else {
+            ChallengeOutcome::NotFound
+        })
+    }
+
+    async fn sweep(&self, cutoff_epoch_ms: u128) -> anyhow::Result<u64> {
+        self.inject_fault()?;
+        self.client
+            .execute(
+                "DELETE FROM challenge_store WHERE expires_at_epoch_ms < $1",
+                &[&to_i64(cutoff_epoch_ms)],
+            )
+            .await
+            .context("failed to sweep challenges in Postgres")
+    }
+}
+
 fn to_i64(value: u128) -> i64 {
     value.min(i64::MAX as u128) as i64
 }
@@ -230,6 +595,7 @@ fn from_i64(value: i64) -> u128 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use kc_storage::WalletUsage;
     use std::env;
 
     fn migrations_dir() -> String {
@@ -307,6 +673,7 @@ mod tests {
             outcome: "success".to_owned(),
             message: Some("integration test".to_owned()),
             timestamp_epoch_ms: 1_700_000_000_123,
+            tenant: None,
         };
 
         let event_id = repo.append_audit_event(&event).await?;
@@ -326,6 +693,49 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn postgres_audit_events_page_by_cursor() -> anyhow::Result<()> {
+        let Some(repo) = setup_repo().await? else {
+            return Ok(());
+        };
+
+        let wallet_address = format!("test-wallet-{}", Uuid::new_v4());
+        for index in 0..3 {
+            repo.append_audit_event(&AuditEventRecord {
+                event_id: format!("page-{index}-{}", Uuid::new_v4()),
+                event_type: "page_test".to_owned(),
+                wallet_address: Some(wallet_address.clone()),
+                user_id: None,
+                chain: None,
+                outcome: "success".to_owned(),
+                message: None,
+                timestamp_epoch_ms: 1_700_000_000_000 + index,
+                tenant: None,
+            })
+            .await?;
+        }
+
+        let first = repo
+            .list_audit_events_page(2, None, Some("page_test"), Some(wallet_address.as_str()), None)
+            .await?;
+        assert_eq!(first.events.len(), 2);
+        assert!(first.events[0].event_id.starts_with("page-2-"));
+        let second = repo
+            .list_audit_events_page(
+                2,
+                first.next_cursor.as_deref(),
+                Some("page_test"),
+                Some(wallet_address.as_str()),
+                None,
+            )
+            .await?;
+        assert_eq!(second.events.len(), 1);
+        assert!(second.events[0].event_id.starts_with("page-0-"));
+        assert_eq!(second.next_cursor, None);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn postgres_challenge_lifecycle_roundtrip() -> anyhow::Result<()> {
         let Some(repo) = setup_repo().await? else {
@@ -337,7 +747,16 @@ mod tests {
         let expires = issued + 120_000;
         let used_at = issued + 5_000;
 
-        repo.upsert_challenge(&challenge, issued, expires).await?;
+        repo.upsert_challenge(
+            &challenge,
+            &ChallengeRecord {
+                issued_at_epoch_ms: issued,
+                expires_at_epoch_ms: expires,
+                used: false,
+                used_at_epoch_ms: None,
+            },
+        )
+        .await?;
         repo.mark_challenge_used(&challenge, used_at).await?;
 
         let row = repo
@@ -360,4 +779,146 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn postgres_challenge_store_conforms() -> anyhow::Result<()> {
+        let Some(repo) = setup_repo().await? else {
+            return Ok(());
+        };
+
+        kc_auth_adapter::conformance::check_challenge_store(&repo).await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn postgres_submission_writes_all_records() -> anyhow::Result<()> {
+        let Some(repo) = setup_repo().await? else {
+            return Ok(());
+        };
+
+        let tx_hash = format!("tx-{}", Uuid::new_v4());
+        let wallet_address = format!("test-wallet-{}", Uuid::new_v4());
+        let idempotency_key = format!("idem-{}", Uuid::new_v4());
+        let tx = SubmittedTxRecord {
+            tx_hash: tx_hash.clone(),
+            status: "submitted".to_owned(),
+            accepted: true,
+            chain: "flowcortex-l1".to_owned(),
+            from: wallet_address.clone(),
+            to: "0xreceiver".to_owned(),
+            asset: "FloweR".to_owned(),
+            amount: "5".to_owned(),
+            submitted_at_epoch_ms: 1_700_000_000_000,
+            block_height: None,
+            confirmations: None,
+            status_history: Vec::new(),
+        };
+        let nonce = WalletNonceRecord {
+            wallet_address: wallet_address.clone(),
+            last_nonce: 4,
+            updated_at_epoch_ms: 1_700_000_000_000,
+        };
+        let idempotency = SubmitIdempotencyRecord {
+            idempotency_key: idempotency_key.clone(),
+            accepted: true,
+            tx_hash: tx_hash.clone(),
+            signature: "ab".to_owned(),
+            expires_at_epoch_ms: None,
+            created_at_epoch_ms: 1_700_000_000_000,
+        };
+
+        repo.save_submission(&tx, Some(&nonce), Some(&idempotency)).await?;
+
+        let row = repo
+            .client
+            .query_one(
+                "SELECT
+                   (SELECT COUNT(*) FROM submitted_txs WHERE tx_hash = $1),
+                   (SELECT last_nonce FROM wallet_nonces WHERE wallet_address = $2),
+                   (SELECT tx_hash FROM submit_idempotency WHERE idempotency_key = $3)",
+                &[&tx_hash, &wallet_address, &idempotency_key],
+            )
+            .await?;
+
+        assert_eq!(row.get::<_, i64>(0), 1);
+        assert_eq!(row.get::<_, Option<i64>>(1), Some(4));
+        assert_eq!(row.get::<_, Option<String>>(2), Some(tx_hash));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn postgres_tx_history_pages_by_wallet() -> anyhow::Result<()> {
+        let Some(repo) = setup_repo().await? else {
+            return Ok(());
+        };
+
+        let wallet_address = format!("test-wallet-{}", Uuid::new_v4());
+        for (index, (from, to)) in [
+            (wallet_address.as_str(), "0xreceiver"),
+            ("0xsender", wallet_address.as_str()),
+            (wallet_address.as_str(), "0xreceiver"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let tx = SubmittedTxRecord {
+                tx_hash: format!("tx-{index}-{}", Uuid::new_v4()),
+                status: "submitted".to_owned(),
+                accepted: true,
+                chain: "flowcortex-l1".to_owned(),
+                from: from.to_owned(),
+                to: to.to_owned(),
+                asset: "FloweR".to_owned(),
+                amount: "5".to_owned(),
+                submitted_at_epoch_ms: 1_700_000_000_000 + index as u128,
+                block_height: None,
+                confirmations: None,
+                status_history: Vec::new(),
+            };
+            repo.save_submission(&tx, None, None).await?;
+        }
+
+        let first = repo.list_submitted_txs(&wallet_address, 2, None).await?;
+        assert_eq!(first.records.len(), 2);
+        assert!(first.records[0].tx_hash.starts_with("tx-2-"));
+        assert!(first.records[1].tx_hash.starts_with("tx-1-"));
+        let second = repo
+            .list_submitted_txs(&wallet_address, 2, first.next_cursor.as_deref())
+            .await?;
+        assert_eq!(second.records.len(), 1);
+        assert!(second.records[0].tx_hash.starts_with("tx-0-"));
+        assert_eq!(second.next_cursor, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn postgres_wallet_metadata_roundtrip() -> anyhow::Result<()> {
+        let Some(repo) = setup_repo().await? else {
+            return Ok(());
+        };
+
+        let wallet_address = format!("test-wallet-{}", Uuid::new_v4());
+        let mut record = WalletMetadataRecord {
+            wallet_address: wallet_address.clone(),
+            label: Some("Treasury".to_owned()),
+            created_at_epoch_ms: Some(1_700_000_000_000),
+            key_scheme: "ed25519".to_owned(),
+            usage: WalletUsage {
+                signature_count: 3,
+                last_signed_at_epoch_ms: Some(1_700_000_100_000),
+                last_submitted_at_epoch_ms: None,
+            },
+        };
+        repo.save_wallet_metadata(&record).await?;
+        record.label = None;
+        repo.save_wallet_metadata(&record).await?;
+        assert_eq!(repo.load_wallet_metadata(&wallet_address).await?, Some(record));
+
+        repo.delete_wallet_metadata(&wallet_address).await?;
+        assert!(repo.load_wallet_metadata(&wallet_address).await?.is_none());
+
+        Ok(())
+    }
 }
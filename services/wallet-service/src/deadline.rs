@@ -0,0 +1,131 @@
+//! Per-request time budget for chain adapter calls and long storage scans.
+//!
+//! Every request gets a deadline `KEYCORTEX_REQUEST_TIMEOUT_MS` (default
+//! 15000) after it arrives. A client may ask for a shorter budget with the
+//! `X-Request-Timeout-Ms` header, never a longer one. A chain call or scan
+//! still running at the deadline fails with `504 Gateway Timeout`, so a slow
+//! FlowCortex node cannot hold a connection open indefinitely.
+//!
+//! Storage writes are never cut short: once a chain has accepted a transfer
+//! its records are persisted even if the deadline has passed.
+
+use anyhow::Result;
+use axum::{
+    Json,
+    http::{HeaderMap, StatusCode},
+};
+use std::env;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::{AppState, ApiError, bad_request, gateway_timeout, upstream_error};
+
+pub(crate) const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+pub(crate) const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_millis(15_000);
+
+/// Error (inside `anyhow::Error`) from a chain call cut off at the deadline.
+#[derive(Debug)]
+pub(crate) struct DeadlineExceeded {
+    operation: &'static str,
+}
+
+impl std::fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} exceeded the request deadline", self.operation)
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+/// `KEYCORTEX_REQUEST_TIMEOUT_MS`, or [`DEFAULT_REQUEST_TIMEOUT`].
+pub(crate) fn timeout_from_env() -> Duration {
+    env::var("KEYCORTEX_REQUEST_TIMEOUT_MS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT)
+}
+
+/// Deadline carried from a handler into the chain and storage calls it makes.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestContext {
+    deadline: Instant,
+}
+
+impl RequestContext {
+    pub(crate) fn with_budget(budget: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + budget,
+        }
+    }
+
+    /// The service budget, shortened by `X-Request-Timeout-Ms` if present.
+    pub(crate) fn from_headers(
+        state: &AppState,
+        headers: &HeaderMap,
+    ) -> Result<Self, (StatusCode, Json<ApiError>)> {
+        let requested = match headers.get(REQUEST_TIMEOUT_HEADER) {
+            Some(value) => Some(
+                value
+                    .to_str()
+                    .ok()
+                    .and_then(|value| value.trim().parse::<u64>().ok())
+                    .filter(|ms| *ms > 0)
+                    .map(Duration::from_millis)
+                    .ok_or_else(|| {
+                        bad_request("X-Request-Timeout-Ms must be a positive number of milliseconds")
+                    })?,
+            ),
+            None => None,
+        };
+        let budget = requested.map_or(state.request_timeout, |requested| {
+            requested.min(state.request_timeout)
+        });
+        Ok(Self::with_budget(budget))
+    }
+
+    /// The deadline for storage scans, which check it between entries.
+    pub(crate) fn scan_deadline(&self) -> Option<std::time::Instant> {
+        Some(self.deadline.into_std())
+    }
+
+    /// Time left before the deadline.
+    pub(crate) fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// 504 if the deadline has passed, for loops doing one lookup per item.
+    pub(crate) fn check(&self, operation: &str) -> Result<(), (StatusCode, Json<ApiError>)> {
+        if Instant::now() >= self.deadline {
+            return Err(gateway_timeout(&format!(
+                "{operation} exceeded the request deadline"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run a chain call, failing with [`DeadlineExceeded`] if it outlasts
+    /// the deadline.
+    pub(crate) async fn run<T>(
+        &self,
+        operation: &'static str,
+        call: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match tokio::time::timeout_at(self.deadline, call).await {
+            Ok(result) => result,
+            Err(_) => Err(DeadlineExceeded { operation }.into()),
+        }
+    }
+}
+
+/// 504 for a missed deadline, 500 for anything else, which is the chain
+/// or storage call failing.
+pub(crate) fn api_error(err: anyhow::Error) -> (StatusCode, Json<ApiError>) {
+    if err.is::<DeadlineExceeded>() || err.is::<kc_storage::DeadlineExceeded>() {
+        gateway_timeout(&err.to_string())
+    } else {
+        upstream_error(err)
+    }
+}
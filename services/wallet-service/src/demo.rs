@@ -0,0 +1,448 @@
+//! Demo mode: an in-process ledger in place of the FlowCortex node, and a
+//! deterministic fixture generator behind `POST /ops/demo/seed`.
+//!
+//! Off unless `KEYCORTEX_DEMO_MODE=true`. The built-in `flowcortex-l1`
+//! adapter is then a [`DemoLedger`], which holds balances in memory, settles
+//! every transfer into a new block and never touches the network.
+//!
+//! For a given seed, seeding always produces the same wallets, labels,
+//! bindings, balances, transfers and tx hashes. Wallet keys are derived from
+//! the seed. Only timestamps follow the clock. The ledger lives in memory, so
+//! startup re-seeds with `KEYCORTEX_DEMO_SEED` (default `keycortex-demo`):
+//! already-recorded fixture transfers are replayed into the ledger, not
+//! re-submitted. Transfers made by hand after seeding are not replayed.
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{Amount, AssetSymbol, ChainId, WalletAddress, WalletSubmitRequest};
+use kc_chain_client::{
+    AdapterHealth, BalanceResult, ChainAdapter, SubmitTxRequest, SubmitTxResult, TxStatusRequest, TxStatusResult,
+};
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_crypto::{Ed25519Signer, encrypt_key_material};
+use kc_storage::{
+    AuditEventRecord, KEY_SCHEME_ED25519, Keystore, WalletBindingRecord, WalletMetadataRecord,
+    WalletUsage,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use crate::deadline::RequestContext;
+use crate::ops::require_ops_access;
+use crate::pipeline::TxSigner;
+use crate::{ApiResult, AppState, ApiError, bad_request, epoch_ms, internal_error, not_found, to_hex};
+
+pub(crate) const DEFAULT_SEED: &str = "keycortex-demo";
+const DEFAULT_WALLETS: usize = 6;
+const MAX_WALLETS: usize = 50;
+const DEFAULT_TRANSFERS_PER_WALLET: u64 = 3;
+const MAX_TRANSFERS_PER_WALLET: u64 = 20;
+
+const LABELS: &[&str] = &[
+    "Treasury",
+    "Payroll",
+    "Operations",
+    "Vendor Payments",
+    "Savings",
+    "Market Making",
+    "Grants",
+    "Escrow Reserve",
+];
+
+const PROOF_UNIT: u128 = 1_000_000_000_000_000_000;
+const FLOWER_UNIT: u128 = 1_000_000;
+
+/// Whether `KEYCORTEX_DEMO_MODE` is on.
+pub(crate) fn enabled_from_env() -> bool {
+    matches!(
+        env::var("KEYCORTEX_DEMO_MODE")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// Seed used for the startup fixture.
+pub(crate) fn seed_from_env() -> String {
+    env::var("KEYCORTEX_DEMO_SEED")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SEED.to_owned())
+}
+
+#[derive(Default)]
+struct LedgerState {
+    /// Base units keyed by (wallet, asset).
+    balances: HashMap<(String, String), u128>,
+    /// Block height each transaction settled in.
+    txs: HashMap<String, u64>,
+    /// Transfers seen per sender, so tx hashes repeat across runs.
+    sent: HashMap<String, u64>,
+    height: u64,
+}
+
+/// In-memory FlowCortex L1 stand-in. Transfers settle immediately, one per
+/// block; a transfer the sender cannot cover is rejected.
+#[derive(Default)]
+pub(crate) struct DemoLedger {
+    state: Mutex<LedgerState>,
+}
+
+impl DemoLedger {
+    fn lock(&self) -> std::sync::MutexGuard<'_, LedgerState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Set a wallet's balances, as a faucet would, and restart its transfer
+    /// count so replayed fixture transfers get their original hashes.
+    pub(crate) fn reset(&self, wallet_address: &str, balances: &[(&str, u128)]) {
+        let mut ledger = self.lock();
+        ledger.sent.remove(wallet_address);
+        for (asset, amount) in balances {
+            ledger
+                .balances
+                .insert((wallet_address.to_owned(), (*asset).to_owned()), *amount);
+        }
+    }
+
+    pub(crate) fn balance(&self, wallet_address: &str, asset: &str) -> u128 {
+        self.lock()
+            .balances
+            .get(&(wallet_address.to_owned(), asset.to_owned()))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl ChainAdapter for DemoLedger {
+    fn chain_id(&self) -> &str {
+        FLOWCORTEX_L1
+    }
+
+    /// In process, so always reachable.
+    async fn health(&self) -> AdapterHealth {
+        AdapterHealth::measure(async { Ok(None) }).await
+    }
+
+    async fn get_balance(&self, wallet_address: &WalletAddress, asset: &AssetSymbol) -> Result<BalanceResult> {
+        Ok(BalanceResult {
+            wallet_address: wallet_address.clone(),
+            chain: ChainId(FLOWCORTEX_L1.to_owned()),
+            asset: asset.clone(),
+            amount: Amount::new(self.balance(&wallet_address.0, &asset.0), 0),
+        })
+    }
+
+    async fn submit_transaction(&self, req: SubmitTxRequest) -> Result<SubmitTxResult> {
+        let amount = req.amount.base_units();
+        let mut ledger = self.lock();
+        let sequence = {
+            let sent = ledger.sent.entry(req.from.0.clone()).or_default();
+            *sent += 1;
+            *sent
+        };
+        let tx_hash = format!(
+            "0x{}",
+            to_hex(&Sha256::digest(format!(
+                "demo-tx:{}:{}:{}:{}:{sequence}",
+                req.from.0, req.to.0, req.asset.0, req.amount
+            )))
+        );
+
+        let from_key = (req.from.0.clone(), req.asset.0.clone());
+        let available = ledger.balances.get(&from_key).copied().unwrap_or(0);
+        if available < amount {
+            return Ok(SubmitTxResult {
+                tx_hash,
+                accepted: false,
+            });
+        }
+        ledger.balances.insert(from_key, available - amount);
+        *ledger
+            .balances
+            .entry((req.to.0.clone(), req.asset.0.clone()))
+            .or_default() += amount;
+        ledger.height += 1;
+        let height = ledger.height;
+        ledger.txs.insert(tx_hash.clone(), height);
+        Ok(SubmitTxResult {
+            tx_hash,
+            accepted: true,
+        })
+    }
+
+    async fn get_transaction_status(&self, req: TxStatusRequest) -> Result<TxStatusResult> {
+        let ledger = self.lock();
+        let Some(&block_height) = ledger.txs.get(&req.tx_hash) else {
+            bail!("unknown demo transaction {}", req.tx_hash);
+        };
+        Ok(TxStatusResult {
+            tx_hash: req.tx_hash,
+            status: "confirmed".to_owned(),
+            accepted: true,
+            block_height: Some(block_height),
+            confirmations: Some(ledger.height - block_height + 1),
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct DemoSeedRequest {
+    pub(crate) seed: Option<String>,
+    /// Number of wallets, 2–50 (default 6).
+    pub(crate) wallets: Option<usize>,
+    /// Transfers each wallet sends, 0–20 (default 3).
+    pub(crate) transfers_per_wallet: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DemoWallet {
+    pub(crate) wallet_address: String,
+    pub(crate) label: String,
+    pub(crate) user_id: String,
+    /// Ledger balance per asset after seeding, in base units.
+    pub(crate) balances: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DemoSeedResponse {
+    pub(crate) seed: String,
+    pub(crate) wallets: Vec<DemoWallet>,
+    /// Fixture transfers submitted by this call.
+    pub(crate) transfers_submitted: usize,
+    /// Fixture transfers already recorded by an earlier seed, replayed into
+    /// the ledger only.
+    pub(crate) transfers_replayed: usize,
+}
+
+/// POST /ops/demo/seed — populate demo wallets, balances, transfers and audit
+/// history. Only available in demo mode.
+pub(crate) async fn ops_demo_seed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<DemoSeedRequest>,
+) -> ApiResult<DemoSeedResponse> {
+    require_ops_access(&state, &headers, "ops_demo_seed", None).await?;
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    Ok(Json(seed(&state, &ctx, &request).await?))
+}
+
+/// Create or top up the fixture for `request`. Safe to repeat.
+pub(crate) async fn seed(
+    state: &AppState,
+    ctx: &RequestContext,
+    request: &DemoSeedRequest,
+) -> Result<DemoSeedResponse, (StatusCode, Json<ApiError>)> {
+    let Some(ledger) = &state.demo_ledger else {
+        return Err(not_found("demo mode is off; set KEYCORTEX_DEMO_MODE=true"));
+    };
+    let seed = request
+        .seed
+        .as_deref()
+        .map(str::trim)
+        .filter(|seed| !seed.is_empty())
+        .unwrap_or(DEFAULT_SEED)
+        .to_owned();
+    let wallet_count = request.wallets.unwrap_or(DEFAULT_WALLETS);
+    if !(2..=MAX_WALLETS).contains(&wallet_count) {
+        return Err(bad_request(&format!("wallets must be between 2 and {MAX_WALLETS}")));
+    }
+    let transfers_per_wallet = request
+        .transfers_per_wallet
+        .unwrap_or(DEFAULT_TRANSFERS_PER_WALLET);
+    if transfers_per_wallet > MAX_TRANSFERS_PER_WALLET {
+        return Err(bad_request(&format!(
+            "transfers_per_wallet must be at most {MAX_TRANSFERS_PER_WALLET}"
+        )));
+    }
+
+    let signers: Vec<Ed25519Signer> = (0..wallet_count)
+        .map(|index| Ed25519Signer::from_passphrase(&format!("{seed}:wallet:{index}")))
+        .collect();
+    let mut wallets = Vec::with_capacity(wallet_count);
+    for (index, signer) in signers.iter().enumerate() {
+        wallets.push(seed_wallet(state, ledger, signer, index).await?);
+    }
+
+    let mut transfers_submitted = 0;
+    let mut transfers_replayed = 0;
+    for (index, signer) in signers.iter().enumerate() {
+        let from = &wallets[index].wallet_address;
+        let last_nonce = state
+            .nonces
+            .last(from)
+            .await
+            .map_err(internal_error)?
+            .unwrap_or(0);
+        for nonce in 1..=transfers_per_wallet {
+            let to = &wallets[(index + nonce as usize) % wallet_count].wallet_address;
+            let (asset, amount) = if nonce % 2 == 1 {
+                ("PROOF", (u128::from(nonce) * 10 + index as u128) * PROOF_UNIT)
+            } else {
+                ("FloweR", (u128::from(nonce) * 100 + index as u128 * 7) * FLOWER_UNIT)
+            };
+            let transfer = WalletSubmitRequest {
+                from: from.clone(),
+                to: to.clone(),
+                amount: amount.to_string(),
+                asset: asset.to_owned(),
+                chain: FLOWCORTEX_L1.to_owned(),
+                nonce,
+                signed_payload: None,
+                expires_at_epoch_ms: None,
+                memo: None,
+                dry_run: false,
+            };
+            if nonce <= last_nonce {
+                let amount = crate::chains::amount(state, &transfer.chain, &transfer.asset, &transfer.amount)?;
+                ledger
+                    .submit_transaction(SubmitTxRequest {
+                        from: WalletAddress(transfer.from),
+                        to: WalletAddress(transfer.to),
+                        amount,
+                        asset: AssetSymbol(transfer.asset),
+                        chain: ChainId(transfer.chain),
+                        signed_payload: String::new(),
+                        chain_payload: None,
+                    })
+                    .await
+                    .map_err(internal_error)?;
+                transfers_replayed += 1;
+            } else {
+                crate::pipeline::sign_and_submit(state, ctx, TxSigner::Custodied(signer), &transfer, None).await?;
+                transfers_submitted += 1;
+            }
+        }
+    }
+
+    for wallet in &mut wallets {
+        for asset in ["PROOF", "FloweR"] {
+            wallet.balances.insert(
+                asset.to_owned(),
+                ledger.balance(&wallet.wallet_address, asset).to_string(),
+            );
+        }
+    }
+
+    Ok(DemoSeedResponse {
+        seed,
+        wallets,
+        transfers_submitted,
+        transfers_replayed,
+    })
+}
+
+/// Store the wallet's key and binding if missing, set its label and reset
+/// its ledger balances.
+async fn seed_wallet(
+    state: &AppState,
+    ledger: &DemoLedger,
+    signer: &Ed25519Signer,
+    index: usize,
+) -> Result<DemoWallet, (StatusCode, Json<ApiError>)> {
+    let wallet_address = signer.wallet_address();
+    let mut label = LABELS[index % LABELS.len()].to_owned();
+    if index >= LABELS.len() {
+        label = format!("{label} {}", index / LABELS.len() + 1);
+    }
+    let user_id = format!("demo-user-{}", index % 3 + 1);
+    let now = epoch_ms().map_err(internal_error)?;
+
+    if !state
+        .keystore
+        .has_wallet(&wallet_address)
+        .await
+        .map_err(internal_error)?
+    {
+        let encrypted_key = encrypt_key_material(
+            signer.secret_key_material().expose_secret(),
+            state.encryption_key.as_ref(),
+        )
+        .map_err(internal_error)?;
+        state
+            .keystore
+            .save_encrypted_key_with_metadata(
+                encrypted_key,
+                &WalletMetadataRecord {
+                    wallet_address: wallet_address.clone(),
+                    label: Some(label.clone()),
+                    created_at_epoch_ms: Some(now),
+                    key_scheme: KEY_SCHEME_ED25519.to_owned(),
+                    usage: WalletUsage::default(),
+                },
+            )
+            .map_err(internal_error)?;
+    } else {
+        state
+            .keystore
+            .save_wallet_label(&wallet_address, &label)
+            .map_err(internal_error)?;
+    }
+    crate::mirror_wallet_metadata(state, &wallet_address).await;
+
+    if state
+        .keystore
+        .load_wallet_binding(&wallet_address)
+        .map_err(internal_error)?
+        .is_none()
+    {
+        let binding = WalletBindingRecord {
+            wallet_address: wallet_address.clone(),
+            user_id: user_id.clone(),
+            chain: FLOWCORTEX_L1.to_owned(),
+            last_verified_epoch_ms: now,
+        };
+        state
+            .keystore
+            .save_wallet_binding(&binding)
+            .map_err(internal_error)?;
+        if let Some(repo) = &state.postgres_repo {
+            if let Err(err) = repo.save_wallet_binding(&binding).await {
+                state.db_fallback_counters.inc_binding_write_failures();
+                warn!("failed to persist demo binding in Postgres: {}", err);
+            }
+        }
+        crate::auth::append_audit_event(
+            state,
+            AuditEventRecord {
+                event_id: String::new(),
+                event_type: "auth_bind".to_owned(),
+                wallet_address: Some(wallet_address.clone()),
+                user_id: Some(user_id.clone()),
+                chain: Some(FLOWCORTEX_L1.to_owned()),
+                outcome: "success".to_owned(),
+                message: Some("demo fixture binding".to_owned()),
+                timestamp_epoch_ms: now,
+                tenant: None,
+            },
+        )
+        .await;
+    }
+
+    let index = index as u128;
+    ledger.reset(
+        &wallet_address,
+        &[
+            ("PROOF", (1_000 + 250 * index) * PROOF_UNIT),
+            ("FloweR", (50_000 + 5_000 * index) * FLOWER_UNIT),
+        ],
+    );
+
+    Ok(DemoWallet {
+        wallet_address,
+        label,
+        user_id,
+        balances: BTreeMap::new(),
+    })
+}
@@ -0,0 +1,431 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use kc_api_types::{
+    DeviceApproveRequest, DeviceListResponse, DeviceRegisterRequest, DeviceSummary, SignPurpose,
+    WalletAddress,
+};
+use kc_auth_adapter::ChallengeOutcome;
+use kc_crypto::Ed25519PublicKey;
+use kc_storage::{AuditEventRecord, UserDeviceRecord};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    AppState, ApiResult, bad_request, epoch_ms, forbidden, from_hex, internal_error, not_found,
+    parse_field, unauthorized,
+};
+
+/// Header carrying the UI device fingerprint on policy-checked requests.
+pub(crate) const DEVICE_ID_HEADER: &str = "x-device-id";
+/// Header carrying the device's hex signature over [`device_proof_payload`].
+pub(crate) const DEVICE_SIGNATURE_HEADER: &str = "x-device-signature";
+/// Header carrying the epoch-ms time the device signed at.
+pub(crate) const DEVICE_TIMESTAMP_HEADER: &str = "x-device-timestamp";
+
+/// How far a device proof's timestamp may be from the server clock.
+const DEVICE_PROOF_MAX_SKEW_MS: u128 = 5 * 60 * 1000;
+
+/// What a device signs (purpose `auth`) to vouch for a transfer.
+pub(crate) fn device_proof_payload(
+    device_id: &str,
+    from: &str,
+    to: &str,
+    amount: &str,
+    timestamp_epoch_ms: u128,
+) -> String {
+    format!("device-proof:{device_id}:{from}:{to}:{amount}:{timestamp_epoch_ms}")
+}
+
+pub(crate) fn summary(record: UserDeviceRecord) -> DeviceSummary {
+    DeviceSummary {
+        device_id: record.device_id,
+        user_id: record.user_id,
+        label: record.label,
+        first_seen_epoch_ms: record.first_seen_epoch_ms,
+        last_seen_epoch_ms: record.last_seen_epoch_ms,
+        trusted: record.trusted,
+        approved_at_epoch_ms: record.approved_at_epoch_ms,
+        approved_via: record.approved_via,
+        revoked: record.revoked,
+        public_key: record.public_key,
+    }
+}
+
+/// Mark a device trusted and record how it was approved.
+pub(crate) async fn approve_device(
+    state: &AppState,
+    mut record: UserDeviceRecord,
+    approved_via: String,
+    actor: &str,
+) -> ApiResult<DeviceSummary> {
+    if record.revoked {
+        return Err(bad_request("device has been revoked; register it again"));
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    record.trusted = true;
+    record.approved_at_epoch_ms = Some(now);
+    record.approved_via = Some(approved_via.clone());
+    state
+        .keystore
+        .save_user_device(&record)
+        .map_err(internal_error)?;
+
+    crate::auth::append_audit_event(
+        state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "device_approve".to_owned(),
+            wallet_address: None,
+            user_id: Some(actor.to_owned()),
+            chain: None,
+            outcome: "success".to_owned(),
+            message: Some(format!(
+                "device {} of user {} trusted via {}",
+                record.device_id, record.user_id, approved_via
+            )),
+            timestamp_epoch_ms: now,
+            tenant: None,
+        },
+    )
+    .await;
+
+    Ok(Json(summary(record)))
+}
+
+/// POST /auth/devices — register (or refresh) the caller's device.
+///
+/// A user's first device is trusted immediately; later ones, and any device
+/// registered once all earlier ones were revoked, stay pending until
+/// approved by signature or by an operator. So does a device whose key
+/// changes.
+pub(crate) async fn auth_register_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<DeviceRegisterRequest>,
+) -> ApiResult<DeviceSummary> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    let device_id = request.device_id.trim();
+    if device_id.is_empty() {
+        return Err(bad_request("device_id is required"));
+    }
+    let label = request
+        .label
+        .map(|l| l.trim().to_owned())
+        .filter(|l| !l.is_empty());
+    let public_key = request
+        .public_key
+        .map(|key| key.trim().to_ascii_lowercase())
+        .filter(|key| !key.is_empty());
+    if let Some(key) = &public_key {
+        Ed25519PublicKey::from_hex(key)
+            .map_err(|e| bad_request(&format!("invalid public_key: {e}")))?;
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let existing = state
+        .keystore
+        .load_user_device(&principal.user_id, device_id)
+        .map_err(internal_error)?;
+
+    let record = match existing {
+        Some(mut record) if !record.revoked => {
+            record.last_seen_epoch_ms = now;
+            if label.is_some() {
+                record.label = label;
+            }
+            if public_key.is_some() && public_key != record.public_key {
+                record.public_key = public_key;
+                record.trusted = false;
+                record.approved_at_epoch_ms = None;
+                record.approved_via = None;
+            }
+            record
+        }
+        _ => {
+            let first_device = state
+                .keystore
+                .list_user_devices(&principal.user_id)
+                .map_err(internal_error)?
+                .is_empty();
+            UserDeviceRecord {
+                device_id: device_id.to_owned(),
+                user_id: principal.user_id.clone(),
+                label,
+                first_seen_epoch_ms: now,
+                last_seen_epoch_ms: now,
+                trusted: first_device,
+                approved_at_epoch_ms: first_device.then_some(now),
+                approved_via: first_device.then(|| "first-device".to_owned()),
+                revoked: false,
+                public_key,
+            }
+        }
+    };
+
+    state
+        .keystore
+        .save_user_device(&record)
+        .map_err(internal_error)?;
+
+    Ok(Json(summary(record)))
+}
+
+/// GET /auth/devices — list the caller's devices.
+pub(crate) async fn auth_list_devices(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<DeviceListResponse> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    let devices: Vec<DeviceSummary> = state
+        .keystore
+        .list_user_devices(&principal.user_id)
+        .map_err(internal_error)?
+        .into_iter()
+        .map(summary)
+        .collect();
+    let total = devices.len();
+
+    Ok(Json(DeviceListResponse {
+        user_id: principal.user_id,
+        devices,
+        total,
+    }))
+}
+
+/// POST /auth/devices/{device_id}/approve — signature-based approval.
+///
+/// The caller signs `{challenge}:device-approve:{device_id}` (purpose `auth`)
+/// with a wallet bound to them, using a fresh `/auth/challenge`.
+pub(crate) async fn auth_approve_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+    Json(request): Json<DeviceApproveRequest>,
+) -> ApiResult<DeviceSummary> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    if request.challenge.trim().is_empty() {
+        return Err(bad_request("challenge is required"));
+    }
+    if request.signature.trim().is_empty() {
+        return Err(bad_request("signature is required"));
+    }
+
+    let record = state
+        .keystore
+        .load_user_device(&principal.user_id, &device_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("device not found"))?;
+
+    let bound_to_caller = state
+        .keystore
+        .load_wallet_binding(&request.wallet_address)
+        .map_err(internal_error)?
+        .is_some_and(|binding| binding.user_id == principal.user_id);
+    if !bound_to_caller {
+        return Err(unauthorized("wallet is not bound to the caller"));
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    match state
+        .challenges
+        .consume(&request.challenge, now)
+        .await
+        .map_err(internal_error)?
+    {
+        ChallengeOutcome::Consumed => {}
+        ChallengeOutcome::NotFound => return Err(bad_request("challenge not found")),
+        ChallengeOutcome::AlreadyUsed => return Err(bad_request("challenge already used")),
+        ChallengeOutcome::Expired => return Err(bad_request("challenge expired")),
+    }
+    if let Some(repo) = &state.challenge_mirror {
+        if let Err(err) = repo.mark_challenge_used(&request.challenge, now).await {
+            state
+                .db_fallback_counters
+                .inc_challenge_mark_used_failures();
+            warn!("failed to mark challenge used in Postgres: {}", err);
+        }
+    }
+
+    let signer = state
+        .wallet_core
+        .signer_for(&request.wallet_address)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| bad_request("wallet not found"))?;
+
+    let signature_bytes = from_hex(&request.signature)
+        .map_err(|e| bad_request(&format!("invalid signature hex: {e}")))?;
+    let payload = format!("{}:device-approve:{}", request.challenge, device_id);
+    let valid = signer
+        .verify_in_domain(
+            &state.signing_domain,
+            payload.as_bytes(),
+            SignPurpose::Auth,
+            &signature_bytes,
+        )
+        .map_err(internal_error)?;
+    if !valid {
+        return Err(unauthorized("device approval signature is invalid"));
+    }
+
+    approve_device(&state, record, "signature".to_owned(), &principal.user_id).await
+}
+
+/// POST /auth/devices/{device_id}/revoke — drop trust for one of the caller's devices.
+pub(crate) async fn auth_revoke_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+) -> ApiResult<DeviceSummary> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    let mut record = state
+        .keystore
+        .load_user_device(&principal.user_id, &device_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("device not found"))?;
+
+    let now = epoch_ms().map_err(internal_error)?;
+    record.trusted = false;
+    record.revoked = true;
+    state
+        .keystore
+        .save_user_device(&record)
+        .map_err(internal_error)?;
+
+    crate::auth::append_audit_event(
+        &state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "device_revoke".to_owned(),
+            wallet_address: None,
+            user_id: Some(principal.user_id.clone()),
+            chain: None,
+            outcome: "success".to_owned(),
+            message: Some(format!("device {} revoked", record.device_id)),
+            timestamp_epoch_ms: now,
+            tenant: None,
+        },
+    )
+    .await;
+
+    Ok(Json(summary(record)))
+}
+
+/// Enforce the trusted-device policy for a transfer from `wallet_address`
+/// to `to`.
+///
+/// When `KEYCORTEX_TRUSTED_DEVICE_SUBMIT_THRESHOLD` is set, transfers whose
+/// amount exceeds it must come from the user the source wallet is bound to,
+/// on a device that user trusts: the request carries their AuthBuddy token,
+/// an `X-Device-Id`, and the device key's signature over
+/// [`device_proof_payload`] with a fresh `X-Device-Timestamp`.
+pub(crate) async fn enforce_trusted_device(
+    state: &AppState,
+    headers: &HeaderMap,
+    wallet_address: &str,
+    to: &str,
+    amount: &str,
+) -> Result<(), (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    let Some(threshold) = state.trusted_device_submit_threshold else {
+        return Ok(());
+    };
+    let base_units: u128 = amount
+        .trim()
+        .parse()
+        .map_err(|_| bad_request("amount must be a non-negative integer"))?;
+    if base_units <= threshold {
+        return Ok(());
+    }
+
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let device_id = header(DEVICE_ID_HEADER)
+        .ok_or_else(|| unauthorized("trusted device required for submits above threshold"))?;
+
+    let binding = state
+        .keystore
+        .load_wallet_binding(wallet_address)
+        .map_err(internal_error)?
+        .ok_or_else(|| unauthorized("trusted device required for submits above threshold"))?;
+    let principal = crate::auth::parse_authbuddy_principal(headers, state)
+        .map_err(|msg| unauthorized(&msg))?;
+    if principal.user_id != binding.user_id {
+        return Err(forbidden("wallet is not bound to the caller"));
+    }
+
+    let Some(mut device) = state
+        .keystore
+        .load_user_device(&binding.user_id, device_id)
+        .map_err(internal_error)?
+        .filter(|d| d.trusted && !d.revoked)
+    else {
+        crate::auth::append_audit_event(
+            state,
+            AuditEventRecord {
+                event_id: String::new(),
+                event_type: "device_policy".to_owned(),
+                wallet_address: Some(wallet_address.to_owned()),
+                user_id: Some(binding.user_id.clone()),
+                chain: None,
+                outcome: "denied".to_owned(),
+                message: Some(format!("untrusted device {device_id} for amount {amount}")),
+                timestamp_epoch_ms: epoch_ms().unwrap_or_default(),
+                tenant: None,
+            },
+        )
+        .await;
+        return Err(unauthorized(
+            "device is not trusted for submits above threshold",
+        ));
+    };
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let device_key = device
+        .public_key
+        .as_deref()
+        .ok_or_else(|| unauthorized("device has no registered key; register it with public_key"))?;
+    let device_key = Ed25519PublicKey::from_hex(device_key).map_err(internal_error)?;
+    let timestamp: u128 = header(DEVICE_TIMESTAMP_HEADER)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| unauthorized("X-Device-Timestamp is required"))?;
+    if timestamp.abs_diff(now) > DEVICE_PROOF_MAX_SKEW_MS {
+        return Err(unauthorized("device proof is stale"));
+    }
+    let signature = header(DEVICE_SIGNATURE_HEADER)
+        .ok_or_else(|| unauthorized("X-Device-Signature is required"))
+        .and_then(|value| {
+            from_hex(value).map_err(|e| bad_request(&format!("invalid device signature hex: {e}")))
+        })?;
+    let payload = device_proof_payload(device_id, wallet_address, to, amount, timestamp);
+    let valid = device_key
+        .verify_in_domain(&state.signing_domain, payload.as_bytes(), SignPurpose::Auth, &signature)
+        .unwrap_or(false);
+    if !valid {
+        return Err(unauthorized("device signature is invalid"));
+    }
+
+    device.last_seen_epoch_ms = now;
+    state
+        .keystore
+        .save_user_device(&device)
+        .map_err(internal_error)?;
+    Ok(())
+}
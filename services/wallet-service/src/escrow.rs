@@ -0,0 +1,519 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{
+    ConditionalTransferApproveRequest, ConditionalTransferCancelRequest,
+    ConditionalTransferCreateRequest, ConditionalTransferListResponse,
+    ConditionalTransferResponse, SignPurpose,
+    AssetSymbol, ChainId, WalletAddress, WalletSubmitRequest, WalletSubmitResponse,
+};
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_crypto::Ed25519Signer;
+use kc_storage::{
+    AuditEventRecord, ConditionalTransferRecord, SubmittedTxRecord, TxStatusChange,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::deadline::RequestContext;
+use crate::pipeline::TxSigner;
+use crate::wallet_sessions::WalletScope;
+use crate::{
+    AppState, ApiResult, ApiError, bad_request, epoch_ms, forbidden, from_hex,
+    internal_error, not_found, parse_field, to_hex, unauthorized,
+};
+
+const DEFAULT_EXPIRES_IN_SECONDS: u64 = 24 * 60 * 60;
+const MAX_EXPIRES_IN_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+fn transfer_digest(record: &ConditionalTransferRecord) -> String {
+    let canonical = format!(
+        "keycortex:escrow:v1:{}:{}:{}:{}:{}:{}:{}:{}",
+        record.transfer_id,
+        record.from,
+        record.to,
+        record.amount,
+        record.asset,
+        record.chain,
+        record.approver_wallet,
+        record.expires_at_epoch_ms
+    );
+    to_hex(&Sha256::digest(canonical.as_bytes()))
+}
+
+fn response(record: ConditionalTransferRecord) -> ConditionalTransferResponse {
+    ConditionalTransferResponse {
+        transfer_id: record.transfer_id,
+        status: record.status,
+        from: record.from,
+        to: record.to,
+        amount: record.amount,
+        asset: record.asset,
+        chain: record.chain,
+        approver_wallet: record.approver_wallet,
+        digest: record.digest,
+        created_at_epoch_ms: record.created_at_epoch_ms,
+        expires_at_epoch_ms: record.expires_at_epoch_ms,
+        resolved_at_epoch_ms: record.resolved_at_epoch_ms,
+        tx_hash: record.tx_hash,
+    }
+}
+
+pub(crate) async fn load_custodied_signer(
+    state: &AppState,
+    wallet_address: &str,
+) -> Result<Option<Ed25519Signer>, (StatusCode, Json<ApiError>)> {
+    let Some(signer) = state
+        .wallet_core
+        .signer_for(wallet_address)
+        .await
+        .map_err(internal_error)?
+    else {
+        return Ok(None);
+    };
+
+    if !crate::key_rotation::key_matches_wallet(state, wallet_address, &signer)
+        .map_err(internal_error)?
+    {
+        return Err(bad_request("wallet address does not match custodied key"));
+    }
+    Ok(Some(signer))
+}
+
+/// Persist the transfer and mirror its state into tx history under `transfer_id`.
+fn save_transfer(
+    state: &AppState,
+    record: &ConditionalTransferRecord,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    state
+        .keystore
+        .save_conditional_transfer(record)
+        .map_err(internal_error)?;
+    mirror_transfer(state, record)
+}
+
+/// Move the transfer out of `from_status`, refusing if another request got
+/// there first, so a transfer is released or cancelled at most once.
+fn claim_transfer(
+    state: &AppState,
+    transfer_id: &str,
+    from_status: &str,
+    update: impl FnOnce(&mut ConditionalTransferRecord),
+) -> Result<ConditionalTransferRecord, (StatusCode, Json<ApiError>)> {
+    let claimed = state
+        .keystore
+        .claim_conditional_transfer(transfer_id, from_status, update)
+        .map_err(internal_error)?;
+    let Some(record) = claimed else {
+        let current = state
+            .keystore
+            .load_conditional_transfer(transfer_id)
+            .map_err(internal_error)?
+            .ok_or_else(|| not_found("conditional transfer not found"))?;
+        return Err(bad_request(&format!(
+            "conditional transfer already {}",
+            current.status
+        )));
+    };
+    mirror_transfer(state, &record)?;
+    Ok(record)
+}
+
+fn mirror_transfer(
+    state: &AppState,
+    record: &ConditionalTransferRecord,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    state
+        .keystore
+        .save_submitted_tx(&SubmittedTxRecord {
+            tx_hash: record.transfer_id.clone(),
+            status: format!("escrow_{}", record.status),
+            accepted: !matches!(record.status.as_str(), "cancelled" | "expired" | "failed"),
+            chain: record.chain.clone(),
+            from: record.from.clone(),
+            to: record.to.clone(),
+            asset: record.asset.clone(),
+            amount: record.amount.clone(),
+            submitted_at_epoch_ms: record.created_at_epoch_ms,
+            block_height: None,
+            confirmations: None,
+            status_history: escrow_history(record),
+        })
+        .map_err(internal_error)
+}
+
+/// The escrow record keeps only creation and resolution times, which is
+/// enough to rebuild its timeline on every save.
+fn escrow_history(record: &ConditionalTransferRecord) -> Vec<TxStatusChange> {
+    let mut history = vec![TxStatusChange {
+        status: "escrow_pending".to_owned(),
+        at_epoch_ms: record.created_at_epoch_ms,
+    }];
+    if let Some(resolved_at) = record.resolved_at_epoch_ms {
+        history.push(TxStatusChange {
+            status: format!("escrow_{}", record.status),
+            at_epoch_ms: resolved_at,
+        });
+    }
+    history
+}
+
+async fn audit(state: &AppState, record: &ConditionalTransferRecord, event_type: &str) {
+    crate::auth::append_audit_event(
+        state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: event_type.to_owned(),
+            wallet_address: Some(record.from.clone()),
+            user_id: None,
+            chain: Some(record.chain.clone()),
+            outcome: record.status.clone(),
+            message: Some(format!(
+                "transfer {} approver {}",
+                record.transfer_id, record.approver_wallet
+            )),
+            timestamp_epoch_ms: epoch_ms().unwrap_or_default(),
+            tenant: None,
+        },
+    )
+    .await;
+}
+
+/// Load a transfer, flipping it to `expired` if its window has passed.
+pub(crate) async fn load_transfer(
+    state: &AppState,
+    transfer_id: &str,
+) -> Result<ConditionalTransferRecord, (StatusCode, Json<ApiError>)> {
+    let record = state
+        .keystore
+        .load_conditional_transfer(transfer_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("conditional transfer not found"))?;
+
+    let now = epoch_ms().map_err(internal_error)?;
+    if record.status == "pending" && now > record.expires_at_epoch_ms {
+        // A release or cancel that claimed it in the meantime wins.
+        if let Ok(expired) = claim_transfer(state, transfer_id, "pending", |record| {
+            record.status = "expired".to_owned();
+            record.resolved_at_epoch_ms = Some(now);
+        }) {
+            audit(state, &expired, "escrow_expire").await;
+            return Ok(expired);
+        }
+        return state
+            .keystore
+            .load_conditional_transfer(transfer_id)
+            .map_err(internal_error)?
+            .ok_or_else(|| not_found("conditional transfer not found"));
+    }
+    Ok(record)
+}
+
+/// POST /wallet/escrow — hold a transfer until `approver_wallet` signs its digest.
+pub(crate) async fn escrow_create(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    scope: WalletScope,
+    Json(request): Json<ConditionalTransferCreateRequest>,
+) -> ApiResult<ConditionalTransferResponse> {
+    parse_field::<WalletAddress>("from", &request.from)?;
+    scope.authorize(&request.from)?;
+    parse_field::<WalletAddress>("to", &request.to)?;
+    if request.amount.trim().is_empty() {
+        return Err(bad_request("amount is required"));
+    }
+    parse_field::<WalletAddress>("approver_wallet", &request.approver_wallet)?;
+    if request.approver_wallet == request.from {
+        return Err(bad_request("approver_wallet must differ from the source wallet"));
+    }
+    parse_field::<AssetSymbol>("asset", &request.asset)?;
+    parse_field::<ChainId>("chain", &request.chain)?;
+    if request.chain != FLOWCORTEX_L1 {
+        return Err(bad_request("unsupported chain for MVP; only flowcortex-l1 is enabled"));
+    }
+    crate::chains::amount(&state, &request.chain, &request.asset, &request.amount)?;
+    let expires_in = request
+        .expires_in_seconds
+        .unwrap_or(DEFAULT_EXPIRES_IN_SECONDS);
+    if expires_in == 0 || expires_in > MAX_EXPIRES_IN_SECONDS {
+        return Err(bad_request("expires_in_seconds must be between 1 and 2592000"));
+    }
+
+    crate::honeytoken::trip_if_honeytoken(&state, &request.from, "wallet_escrow_create").await;
+    crate::watch::reject_watch_only(&state, &request.from)?;
+
+    if load_custodied_signer(&state, &request.from).await?.is_none() {
+        return Err(bad_request("source wallet not found"));
+    }
+    if load_custodied_signer(&state, &request.approver_wallet)
+        .await?
+        .is_none()
+    {
+        return Err(bad_request("approver wallet not found"));
+    }
+    crate::devices::enforce_trusted_device(
+        &state,
+        &headers,
+        &request.from,
+        &request.to,
+        &request.amount,
+    )
+    .await?;
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let mut record = ConditionalTransferRecord {
+        transfer_id: format!("escrow-{}", Uuid::new_v4()),
+        status: "pending".to_owned(),
+        from: request.from,
+        to: request.to,
+        amount: request.amount,
+        asset: request.asset,
+        chain: request.chain,
+        approver_wallet: request.approver_wallet,
+        digest: String::new(),
+        created_at_epoch_ms: now,
+        expires_at_epoch_ms: now + u128::from(expires_in) * 1000,
+        resolved_at_epoch_ms: None,
+        tx_hash: None,
+    };
+    record.digest = transfer_digest(&record);
+
+    save_transfer(&state, &record)?;
+    audit(&state, &record, "escrow_create").await;
+    crate::notify::notify_approvers(&state, &record).await;
+
+    Ok(Json(response(record)))
+}
+
+/// Filters for listing conditional transfers.
+#[derive(Debug, Deserialize)]
+pub(crate) struct EscrowListQuery {
+    approver_wallet: Option<String>,
+    status: Option<String>,
+}
+
+/// GET /wallet/escrow?approver_wallet=&status= — the transfers waiting on
+/// (or decided by) one approver wallet, oldest first.
+pub(crate) async fn escrow_list(
+    State(state): State<Arc<AppState>>,
+    scope: WalletScope,
+    Query(query): Query<EscrowListQuery>,
+) -> ApiResult<ConditionalTransferListResponse> {
+    let approver_wallet = query
+        .approver_wallet
+        .filter(|wallet| !wallet.trim().is_empty())
+        .ok_or_else(|| bad_request("approver_wallet is required"))?;
+    scope.authorize(&approver_wallet)?;
+
+    let mut transfers = Vec::new();
+    for record in state
+        .keystore
+        .list_conditional_transfers()
+        .map_err(internal_error)?
+    {
+        if record.approver_wallet != approver_wallet {
+            continue;
+        }
+        // Reload so a lapsed transfer is reported as expired.
+        let record = load_transfer(&state, &record.transfer_id).await?;
+        if query.status.as_ref().is_none_or(|status| *status == record.status) {
+            transfers.push(record);
+        }
+    }
+    transfers.sort_by_key(|record| record.created_at_epoch_ms);
+
+    let total = transfers.len();
+    Ok(Json(ConditionalTransferListResponse {
+        transfers: transfers.into_iter().map(response).collect(),
+        total,
+    }))
+}
+
+/// Signature of an approval notification's deep link.
+#[derive(Debug, Deserialize)]
+pub(crate) struct EscrowLinkQuery {
+    expires: Option<String>,
+    sig: Option<String>,
+}
+
+/// GET /wallet/escrow/{transfer_id} — with `expires` and `sig` when opened
+/// from an approval notification, which are checked before answering.
+pub(crate) async fn escrow_status(
+    State(state): State<Arc<AppState>>,
+    Path(transfer_id): Path<String>,
+    Query(link): Query<EscrowLinkQuery>,
+) -> ApiResult<ConditionalTransferResponse> {
+    let record = load_transfer(&state, &transfer_id).await?;
+    if link.expires.is_some() || link.sig.is_some() {
+        let notifier = state
+            .approval_notifier
+            .as_ref()
+            .ok_or_else(|| forbidden("approval links are not enabled"))?;
+        let valid = notifier.verify_link(
+            &record,
+            link.expires.as_deref().unwrap_or_default(),
+            link.sig.as_deref().unwrap_or_default(),
+            epoch_ms().map_err(internal_error)?,
+        );
+        if !valid {
+            return Err(forbidden("approval link is invalid or expired"));
+        }
+    }
+    Ok(Json(response(record)))
+}
+
+/// POST /wallet/escrow/{transfer_id}/approve — verify the approver signature and release.
+pub(crate) async fn escrow_approve(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    scope: WalletScope,
+    Path(transfer_id): Path<String>,
+    Json(request): Json<ConditionalTransferApproveRequest>,
+) -> ApiResult<ConditionalTransferResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    if request.signature.trim().is_empty() {
+        return Err(bad_request("signature is required"));
+    }
+
+    let record = load_transfer(&state, &transfer_id).await?;
+    match record.status.as_str() {
+        "pending" => {}
+        "expired" => return Err(bad_request("conditional transfer expired")),
+        other => return Err(bad_request(&format!("conditional transfer already {other}"))),
+    }
+    scope.authorize(&record.approver_wallet)?;
+
+    let approver = load_custodied_signer(&state, &record.approver_wallet)
+        .await?
+        .ok_or_else(|| bad_request("approver wallet not found"))?;
+    let signature_bytes = from_hex(&request.signature)
+        .map_err(|e| bad_request(&format!("invalid signature hex: {e}")))?;
+    let valid = approver
+        .verify_in_domain(
+            &state.signing_domain,
+            record.digest.as_bytes(),
+            SignPurpose::Transaction,
+            &signature_bytes,
+        )
+        .map_err(internal_error)?;
+    if !valid {
+        return Err(unauthorized("approver signature is invalid"));
+    }
+
+    let sender = load_custodied_signer(&state, &record.from)
+        .await?
+        .ok_or_else(|| bad_request("source wallet not found"))?;
+    let mut record = claim_transfer(&state, &transfer_id, "pending", |record| {
+        record.status = "releasing".to_owned();
+    })?;
+    let submitted = release(&state, &ctx, &sender, &record).await;
+    let submitted = match submitted {
+        Ok(submitted) => submitted,
+        Err(error) => {
+            // Nothing reached the chain unless the submit timed out, in
+            // which case the outcome is unknown and a retry could pay twice.
+            if error.0 == StatusCode::GATEWAY_TIMEOUT {
+                record.status = "failed".to_owned();
+                record.resolved_at_epoch_ms = Some(epoch_ms().map_err(internal_error)?);
+            } else {
+                record.status = "pending".to_owned();
+            }
+            save_transfer(&state, &record)?;
+            return Err(error);
+        }
+    };
+
+    record.status = "released".to_owned();
+    record.resolved_at_epoch_ms = Some(epoch_ms().map_err(internal_error)?);
+    record.tx_hash = Some(submitted.tx_hash);
+    save_transfer(&state, &record)?;
+    audit(&state, &record, "escrow_release").await;
+
+    Ok(Json(response(record)))
+}
+
+/// Sign and submit the transfer a claimed escrow holds.
+async fn release(
+    state: &AppState,
+    ctx: &RequestContext,
+    sender: &Ed25519Signer,
+    record: &ConditionalTransferRecord,
+) -> Result<WalletSubmitResponse, (StatusCode, Json<ApiError>)> {
+    let nonce = crate::submit::next_nonce(state, &record.from)
+        .await
+        .map_err(internal_error)?;
+    crate::pipeline::sign_and_submit(
+        state,
+        ctx,
+        TxSigner::Custodied(sender),
+        &WalletSubmitRequest {
+            from: record.from.clone(),
+            to: record.to.clone(),
+            amount: record.amount.clone(),
+            asset: record.asset.clone(),
+            chain: record.chain.clone(),
+            nonce,
+            signed_payload: None,
+            expires_at_epoch_ms: None,
+            memo: None,
+            dry_run: false,
+        },
+        None,
+    )
+    .await
+}
+
+/// POST /wallet/escrow/{transfer_id}/cancel — sender or approver withdraws a pending transfer.
+pub(crate) async fn escrow_cancel(
+    State(state): State<Arc<AppState>>,
+    scope: WalletScope,
+    Path(transfer_id): Path<String>,
+    Json(request): Json<ConditionalTransferCancelRequest>,
+) -> ApiResult<ConditionalTransferResponse> {
+    if request.signature.trim().is_empty() {
+        return Err(bad_request("signature is required"));
+    }
+
+    let record = load_transfer(&state, &transfer_id).await?;
+    if record.status != "pending" {
+        return Err(bad_request(&format!(
+            "conditional transfer already {}",
+            record.status
+        )));
+    }
+    if request.wallet_address != record.from && request.wallet_address != record.approver_wallet {
+        return Err(unauthorized("only the sender or approver may cancel"));
+    }
+    scope.authorize(&request.wallet_address)?;
+
+    let signer = load_custodied_signer(&state, &request.wallet_address)
+        .await?
+        .ok_or_else(|| bad_request("wallet not found"))?;
+    let signature_bytes = from_hex(&request.signature)
+        .map_err(|e| bad_request(&format!("invalid signature hex: {e}")))?;
+    let cancel_payload = format!("cancel:{}", record.digest);
+    let valid = signer
+        .verify_in_domain(
+            &state.signing_domain,
+            cancel_payload.as_bytes(),
+            SignPurpose::Transaction,
+            &signature_bytes,
+        )
+        .map_err(internal_error)?;
+    if !valid {
+        return Err(unauthorized("cancel signature is invalid"));
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let record = claim_transfer(&state, &transfer_id, "pending", |record| {
+        record.status = "cancelled".to_owned();
+        record.resolved_at_epoch_ms = Some(now);
+    })?;
+    audit(&state, &record, "escrow_cancel").await;
+
+    Ok(Json(response(record)))
+}
@@ -0,0 +1,94 @@
+//! File downloads for export endpoints.
+//!
+//! Every export goes through [`download`], so they all send the same
+//! `Content-Disposition` and stream their body. The CORS layer exposes
+//! `Content-Disposition`, which lets a cross-origin UI read the filename.
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use futures_util::stream;
+use std::convert::Infallible;
+
+/// Response that browsers save as `filename` instead of rendering.
+pub(crate) fn download(filename: &str, content_type: &'static str, body: Body) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (header::CONTENT_DISPOSITION, content_disposition(filename)),
+            (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Stream `rows` as a CSV file, one chunk per line, after a `columns` header.
+pub(crate) fn csv_download<I>(filename: &str, columns: &[&str], rows: I) -> Response
+where
+    I: IntoIterator<Item = Vec<String>>,
+    I::IntoIter: Send + 'static,
+{
+    let header_line = csv_line(columns.iter().map(|column| column.to_string()));
+    let lines = std::iter::once(header_line).chain(rows.into_iter().map(csv_line));
+    let body = Body::from_stream(stream::iter(
+        lines.map(|line| Ok::<_, Infallible>(Bytes::from(line))),
+    ));
+    download(filename, "text/csv; charset=utf-8", body)
+}
+
+/// `attachment` with a plain ASCII `filename` and an RFC 5987 `filename*`
+/// for anything else.
+fn content_disposition(filename: &str) -> HeaderValue {
+    let ascii: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let encoded: String = filename
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_') {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect();
+    HeaderValue::from_str(&format!(
+        "attachment; filename=\"{ascii}\"; filename*=UTF-8''{encoded}"
+    ))
+    .unwrap_or_else(|_| HeaderValue::from_static("attachment"))
+}
+
+fn csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|field| csv_field(&field))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Quote a field when needed, and defuse values a spreadsheet would run as a
+/// formula.
+pub(crate) fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_owned()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
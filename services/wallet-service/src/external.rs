@@ -0,0 +1,154 @@
+use axum::{Json, extract::State, http::StatusCode};
+use kc_api_types::{WalletImportPublicRequest, WalletSummary};
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_crypto::{Ed25519PublicKey, fingerprint};
+use kc_storage::{ExternalKeyRecord, Keystore, WalletMetadataRecord, WalletUsage};
+use std::sync::Arc;
+
+use crate::{AppState, ApiResult, ApiError, bad_request, epoch_ms, forbidden, internal_error};
+
+const SUPPORTED_KEY_TYPE: &str = "ed25519";
+
+/// Public key of a wallet registered via `/wallet/import-public`, if any.
+pub(crate) fn load_public_key(
+    state: &AppState,
+    wallet_address: &str,
+) -> Result<Option<Ed25519PublicKey>, (StatusCode, Json<ApiError>)> {
+    let Some(record) = state
+        .keystore
+        .load_external_key(wallet_address)
+        .map_err(internal_error)?
+    else {
+        return Ok(None);
+    };
+    Ed25519PublicKey::from_hex(&record.public_key)
+        .map(Some)
+        .map_err(internal_error)
+}
+
+/// Reject server-side signing for wallets whose key is held elsewhere.
+pub(crate) fn reject_external_key(
+    state: &AppState,
+    wallet_address: &str,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    if state
+        .keystore
+        .load_external_key(wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(forbidden(
+            "external-key wallet; sign client-side and submit with signed_payload",
+        ));
+    }
+    Ok(())
+}
+
+/// POST /wallet/import-public — register a wallet by public key only.
+pub(crate) async fn wallet_import_public(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<WalletImportPublicRequest>,
+) -> ApiResult<WalletSummary> {
+    let key_type = request
+        .key_type
+        .map(|k| k.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| SUPPORTED_KEY_TYPE.to_owned());
+    if key_type != SUPPORTED_KEY_TYPE {
+        return Err(bad_request("unsupported key_type; only ed25519 is supported"));
+    }
+
+    let chain = request.chain.unwrap_or_else(|| FLOWCORTEX_L1.to_owned());
+    if chain != FLOWCORTEX_L1 {
+        return Err(bad_request(
+            "unsupported chain for MVP; only flowcortex-l1 is enabled",
+        ));
+    }
+
+    let public_key = Ed25519PublicKey::from_hex(request.public_key.trim())
+        .map_err(|e| bad_request(&format!("invalid public_key: {e}")))?;
+    let wallet_address = public_key.wallet_address();
+
+    let custodied = state
+        .keystore
+        .load_encrypted_key(&wallet_address)
+        .await
+        .map_err(internal_error)?
+        .is_some();
+    if custodied || state.kms_keys.get(&wallet_address).is_some() {
+        return Err(bad_request("wallet is already custodied"));
+    }
+    if state
+        .keystore
+        .load_watch_wallet(&wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(bad_request("wallet is already registered as watch-only"));
+    }
+    if state
+        .keystore
+        .load_external_key(&wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(bad_request("public key is already imported"));
+    }
+
+    let created_at_epoch_ms = epoch_ms().map_err(internal_error)?;
+    state
+        .keystore
+        .save_external_key(&ExternalKeyRecord {
+            wallet_address: wallet_address.clone(),
+            chain: chain.clone(),
+            key_type: key_type.clone(),
+            public_key: public_key.to_hex(),
+            created_at_epoch_ms,
+        })
+        .map_err(internal_error)?;
+
+    let label = request
+        .label
+        .map(|l| l.trim().to_owned())
+        .filter(|l| !l.is_empty());
+    state
+        .keystore
+        .save_wallet_metadata(&WalletMetadataRecord {
+            wallet_address: wallet_address.clone(),
+            label: label.clone(),
+            created_at_epoch_ms: Some(created_at_epoch_ms),
+            key_scheme: key_type.clone(),
+            usage: WalletUsage::default(),
+        })
+        .map_err(internal_error)?;
+    crate::mirror_wallet_metadata(&state, &wallet_address).await;
+    let device_id = request
+        .device_id
+        .map(|d| d.trim().to_owned())
+        .filter(|d| !d.is_empty());
+    if let Some(device_id) = &device_id {
+        state
+            .keystore
+            .save_device_wallet(device_id, &wallet_address)
+            .map_err(internal_error)?;
+    }
+
+    Ok(Json(WalletSummary {
+        wallet_address,
+        chain,
+        bound_user_id: None,
+        public_key: Some(public_key.to_hex()),
+        key_fingerprint: fingerprint::of_hex(&public_key.to_hex()).ok(),
+        label,
+        device_id,
+        email: None,
+        phone: None,
+        bank_id: None,
+        custodied: false,
+        external_key: true,
+        created_at_epoch_ms: Some(created_at_epoch_ms),
+        key_scheme: Some(key_type),
+        signature_count: 0,
+        last_signed_at_epoch_ms: None,
+        last_submitted_at_epoch_ms: None,
+    }))
+}
@@ -1,9 +1,6 @@
 use serde::{Serialize, Deserialize};
 use base64::{Engine as _, engine::general_purpose::STANDARD};
-use kc_api_types::{
-    FortressDigitalWalletStatusRequest, FortressDigitalWalletStatusResponse,
-    WalletBindingStatus,
-};
+use kc_api_types::{FortressDigitalWalletStatusResponse, WalletBindingStatus};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FortressDigitalContextPayload {
@@ -17,25 +14,30 @@ pub struct FortressDigitalContextPayload {
     pub signature: String,    // Signed by wallet-service
 }
 
+/// Fields of a [`FortressDigitalContextPayload`] before it is signed.
+pub struct ContextPayloadParams<'a> {
+    pub wallet_address: &'a str,
+    pub user_id: &'a str,
+    pub chain: &'a str,
+    pub session_id: &'a str,
+    pub context_data: &'a str,
+    pub issued_at_epoch_ms: u128,
+    pub expires_at_epoch_ms: u128,
+}
+
 // Example function to generate and sign the payload
 pub fn generate_context_payload(
-    wallet_address: &str,
-    user_id: &str,
-    chain: &str,
-    session_id: &str,
-    context_data: &str,
-    issued_at_epoch_ms: u128,
-    expires_at_epoch_ms: u128,
+    params: ContextPayloadParams<'_>,
     signer: &impl kc_crypto::Signer,
 ) -> FortressDigitalContextPayload {
     let payload = FortressDigitalContextPayload {
-        wallet_address: wallet_address.to_owned(),
-        user_id: user_id.to_owned(),
-        chain: chain.to_owned(),
-        session_id: session_id.to_owned(),
-        issued_at_epoch_ms,
-        expires_at_epoch_ms,
-        context_data: context_data.to_owned(),
+        wallet_address: params.wallet_address.to_owned(),
+        user_id: params.user_id.to_owned(),
+        chain: params.chain.to_owned(),
+        session_id: params.session_id.to_owned(),
+        issued_at_epoch_ms: params.issued_at_epoch_ms,
+        expires_at_epoch_ms: params.expires_at_epoch_ms,
+        context_data: params.context_data.to_owned(),
         signature: String::new(), // Placeholder
     };
     let serialized = serde_json::to_string(&payload).unwrap();
@@ -55,14 +57,14 @@ pub fn generate_context_payload(
 ///   - wallet existence + binding status
 ///   - key type (local custody)
 ///   - last verification time
-///   - signing frequency hint
+///   - signature count, last sign/submit times and a frequency hint
 ///   - risk signals for policy engine
 pub fn build_wallet_status(
     wallet_address: &str,
     chain: &str,
     wallet_exists: bool,
     binding: Option<&kc_storage::WalletBindingRecord>,
-    audit_event_count: usize,
+    usage: &kc_storage::WalletUsage,
     now: u128,
 ) -> FortressDigitalWalletStatusResponse {
     let binding_status = if let Some(b) = binding {
@@ -81,7 +83,7 @@ pub fn build_wallet_status(
 
     let last_verification = binding.map(|b| b.last_verified_epoch_ms);
 
-    let frequency_hint = match audit_event_count {
+    let frequency_hint = match usage.signature_count {
         0 => "none",
         1..=5 => "low",
         6..=20 => "moderate",
@@ -117,5 +119,8 @@ pub fn build_wallet_status(
         last_verification_epoch_ms: last_verification,
         signature_frequency_hint: frequency_hint,
         risk_signals,
+        signature_count: usage.signature_count,
+        last_signed_at_epoch_ms: usage.last_signed_at_epoch_ms,
+        last_submitted_at_epoch_ms: usage.last_submitted_at_epoch_ms,
     }
 }
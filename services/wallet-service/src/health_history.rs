@@ -0,0 +1,119 @@
+//! In-memory ring buffer of periodic health snapshots behind
+//! `/ops/health/history`.
+//!
+//! A background task samples every `KEYCORTEX_HEALTH_SAMPLE_SECONDS`
+//! (default 60) and keeps the last `KEYCORTEX_HEALTH_HISTORY_SIZE` samples
+//! (default 60). Each sample carries the fallback counter deltas since the
+//! previous sample and a probe of every enabled chain, so an incident that
+//! resolved before anyone looked still shows up. History is lost on restart.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::chains::ChainHealth;
+use crate::{AppState, DbFallbackCountersSnapshot, epoch_ms};
+
+const DEFAULT_SAMPLE_SECONDS: u64 = 60;
+const DEFAULT_CAPACITY: usize = 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HealthSample {
+    pub(crate) at_epoch_ms: u128,
+    pub(crate) postgres_enabled: bool,
+    /// Counter totals at sampling time.
+    pub(crate) db_fallback_counters: DbFallbackCountersSnapshot,
+    /// Increase since the previous sample; equal to the totals for the first.
+    pub(crate) db_fallback_deltas: DbFallbackCountersSnapshot,
+    /// Probe result per enabled chain, keyed by chain id.
+    pub(crate) chains: BTreeMap<String, ChainHealth>,
+}
+
+pub(crate) struct HealthHistory {
+    interval: Duration,
+    capacity: usize,
+    samples: Mutex<VecDeque<HealthSample>>,
+}
+
+impl HealthHistory {
+    pub(crate) fn new(interval: Duration, capacity: usize) -> Self {
+        Self {
+            interval,
+            capacity: capacity.max(1),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub(crate) fn from_env() -> Self {
+        let sample_seconds = env::var("KEYCORTEX_HEALTH_SAMPLE_SECONDS")
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_SAMPLE_SECONDS);
+        let capacity = env::var("KEYCORTEX_HEALTH_HISTORY_SIZE")
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        Self::new(Duration::from_secs(sample_seconds), capacity)
+    }
+
+    pub(crate) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The most recent `limit` samples, oldest first.
+    pub(crate) fn recent(&self, limit: usize) -> Vec<HealthSample> {
+        let samples = self.samples.lock().unwrap_or_else(|err| err.into_inner());
+        samples
+            .iter()
+            .skip(samples.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+
+    fn push(&self, sample: HealthSample) {
+        let mut samples = self.samples.lock().unwrap_or_else(|err| err.into_inner());
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    fn last_counters(&self) -> Option<DbFallbackCountersSnapshot> {
+        let samples = self.samples.lock().unwrap_or_else(|err| err.into_inner());
+        samples.back().map(|sample| sample.db_fallback_counters.clone())
+    }
+}
+
+/// Take one sample and append it to `state.health_history`.
+pub(crate) async fn record_sample(state: &AppState) {
+    let enabled_chains: Vec<_> = match state.chains.read() {
+        Ok(table) => table.configs().filter(|record| record.enabled).cloned().collect(),
+        Err(_) => Vec::new(),
+    };
+    let http = crate::chains::http(state);
+    let mut chains = BTreeMap::new();
+    for record in enabled_chains {
+        let health = crate::chains::probe(&http, &record).await;
+        chains.insert(record.chain_id, health);
+    }
+
+    let counters = state.db_fallback_counters.snapshot();
+    let db_fallback_deltas = match state.health_history.last_counters() {
+        Some(previous) => counters.since(&previous),
+        None => counters.clone(),
+    };
+    state.health_history.push(HealthSample {
+        at_epoch_ms: epoch_ms().unwrap_or_default(),
+        postgres_enabled: state.postgres_repo.is_some(),
+        db_fallback_counters: counters,
+        db_fallback_deltas,
+        chains,
+    });
+}
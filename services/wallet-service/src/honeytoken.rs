@@ -0,0 +1,68 @@
+use kc_storage::AuditEventRecord;
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{error, warn};
+
+use crate::{AppState, epoch_ms};
+
+#[derive(Debug, Serialize, Clone)]
+struct HoneytokenAlert {
+    wallet_address: String,
+    operation: String,
+    detected_at_epoch_ms: u128,
+}
+
+/// Tripwire for honeytoken wallets.
+///
+/// If `wallet_address` is flagged, record a critical audit event and raise an
+/// alert (log + optional webhook at `KEYCORTEX_HONEYTOKEN_ALERT_URL`). The
+/// request itself proceeds normally so the caller cannot tell the wallet is a
+/// decoy.
+pub(crate) async fn trip_if_honeytoken(state: &AppState, wallet_address: &str, operation: &str) {
+    let flagged = match state.keystore.is_wallet_honeytoken(wallet_address) {
+        Ok(flagged) => flagged,
+        Err(err) => {
+            warn!("failed to check honeytoken flag for {}: {}", wallet_address, err);
+            return;
+        }
+    };
+    if !flagged {
+        return;
+    }
+
+    let now = epoch_ms().unwrap_or_default();
+    error!(
+        "honeytoken wallet {} accessed via {}; possible credential compromise",
+        wallet_address, operation
+    );
+
+    crate::auth::append_audit_event(
+        state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "honeytoken_access".to_owned(),
+            wallet_address: Some(wallet_address.to_owned()),
+            user_id: None,
+            chain: None,
+            outcome: "critical".to_owned(),
+            message: Some(format!("{operation}: honeytoken wallet accessed")),
+            timestamp_epoch_ms: now,
+            tenant: None,
+        },
+    )
+    .await;
+
+    if let Some(url) = state.honeytoken_alert_url.as_deref() {
+        let url = url.to_owned();
+        let payload = HoneytokenAlert {
+            wallet_address: wallet_address.to_owned(),
+            operation: operation.to_owned(),
+            detected_at_epoch_ms: now,
+        };
+        tokio::spawn(async move {
+            if let Err(err) = Client::new().post(url).json(&payload).send().await {
+                warn!("honeytoken alert webhook failed: {}", err);
+            }
+        });
+    }
+}
@@ -0,0 +1,176 @@
+use axum::{Json, extract::State, http::HeaderMap};
+use kc_api_types::{SignPurpose, WalletAddress, WalletRotateKeyRequest, WalletRotateKeyResponse};
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_crypto::{
+    Ed25519PublicKey, Ed25519Signer, Signer, decrypt_key_material, encrypt_key_material, fingerprint,
+};
+use kc_storage::{AuditEventRecord, Keystore, WalletKeyHistoryRecord};
+use std::sync::Arc;
+
+use crate::wallet_sessions::WalletScope;
+use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, parse_field, to_hex, unauthorized};
+
+/// Whether `signer` is the custodied key for `wallet_address`.
+///
+/// Unrotated wallets derive their address from the key; rotated wallets keep
+/// their address and are checked against the active key generation instead.
+pub(crate) fn key_matches_wallet(
+    state: &AppState,
+    wallet_address: &str,
+    signer: &Ed25519Signer,
+) -> anyhow::Result<bool> {
+    public_key_matches_wallet(state, wallet_address, &signer.public_key_hex())
+}
+
+/// [`key_matches_wallet`] for a hex public key whose secret is held elsewhere.
+pub(crate) fn public_key_matches_wallet(
+    state: &AppState,
+    wallet_address: &str,
+    public_key_hex: &str,
+) -> anyhow::Result<bool> {
+    if Ed25519PublicKey::from_hex(public_key_hex)?.wallet_address() == wallet_address {
+        return Ok(true);
+    }
+    let Some(version) = state.keystore.load_active_key_version(wallet_address)? else {
+        return Ok(false);
+    };
+    Ok(state
+        .keystore
+        .load_wallet_key_history(wallet_address, version)?
+        .is_some_and(|record| record.public_key == public_key_hex))
+}
+
+/// POST /wallet/rotate-key — replace a wallet's signing key, keeping its address.
+///
+/// The outgoing key signs a linkage proof over the new public key so verifiers
+/// can follow the chain of custody. Requires the bound user or `ops-admin`.
+pub(crate) async fn wallet_rotate_key(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    scope: WalletScope,
+    Json(request): Json<WalletRotateKeyRequest>,
+) -> ApiResult<WalletRotateKeyResponse> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    scope.authorize(&request.wallet_address)?;
+
+    let bound_user = state
+        .keystore
+        .load_wallet_binding(&request.wallet_address)
+        .map_err(internal_error)?
+        .map(|binding| binding.user_id);
+    if bound_user.as_deref() != Some(principal.user_id.as_str()) && !principal.is_ops_admin() {
+        return Err(unauthorized(
+            "key rotation requires the bound user or ops-admin",
+        ));
+    }
+
+    let encrypted_key = state
+        .keystore
+        .load_encrypted_key(&request.wallet_address)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| bad_request("wallet not found"))?;
+    let secret_key = decrypt_key_material(&encrypted_key, state.encryption_key.as_ref())
+        .map_err(internal_error)?;
+    let old_signer = Ed25519Signer::from_key_material(&secret_key);
+    drop(secret_key);
+
+    if !key_matches_wallet(&state, &request.wallet_address, &old_signer).map_err(internal_error)? {
+        return Err(bad_request("wallet address does not match custodied key"));
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let old_version = state
+        .keystore
+        .load_active_key_version(&request.wallet_address)
+        .map_err(internal_error)?
+        .unwrap_or(1);
+    let new_version = old_version + 1;
+
+    let mut old_record = state
+        .keystore
+        .load_wallet_key_history(&request.wallet_address, old_version)
+        .map_err(internal_error)?
+        .unwrap_or_else(|| WalletKeyHistoryRecord {
+            wallet_address: request.wallet_address.clone(),
+            key_version: old_version,
+            public_key: old_signer.public_key_hex(),
+            encrypted_key: encrypted_key.clone(),
+            created_at_epoch_ms: now,
+            retired_at_epoch_ms: None,
+            linkage_signature: None,
+        });
+    old_record.retired_at_epoch_ms = Some(now);
+
+    let new_signer = Ed25519Signer::new_random();
+    let new_public_key = new_signer.public_key_hex();
+    let new_encrypted_key = encrypt_key_material(
+        new_signer.secret_key_material().expose_secret(),
+        state.encryption_key.as_ref(),
+    )
+    .map_err(internal_error)?;
+
+    let linkage_payload = format!(
+        "key-rotation:{}:{}:{}",
+        request.wallet_address, new_version, new_public_key
+    );
+    let linkage_signature = to_hex(
+        &old_signer
+            .sign_in_domain(
+                &state.signing_domain,
+                linkage_payload.as_bytes(),
+                SignPurpose::Proof,
+            )
+            .map_err(internal_error)?,
+    );
+
+    let new_record = WalletKeyHistoryRecord {
+        wallet_address: request.wallet_address.clone(),
+        key_version: new_version,
+        public_key: new_public_key.clone(),
+        encrypted_key: new_encrypted_key,
+        created_at_epoch_ms: now,
+        retired_at_epoch_ms: None,
+        linkage_signature: Some(linkage_signature.clone()),
+    };
+
+    let rotated = state
+        .keystore
+        .rotate_wallet_key(&old_record, &new_record)
+        .map_err(internal_error)?;
+    if !rotated {
+        return Err(bad_request("wallet key was rotated concurrently; retry"));
+    }
+
+    crate::auth::append_audit_event(
+        &state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "wallet_rotate_key".to_owned(),
+            wallet_address: Some(request.wallet_address.clone()),
+            user_id: Some(principal.user_id),
+            chain: Some(FLOWCORTEX_L1.to_owned()),
+            outcome: "success".to_owned(),
+            message: Some(format!(
+                "key version {old_version} -> {new_version}; new public key {new_public_key}"
+            )),
+            timestamp_epoch_ms: now,
+            tenant: None,
+        },
+    )
+    .await;
+
+    let key_fingerprint = fingerprint::of_hex(&new_public_key).ok();
+    Ok(Json(WalletRotateKeyResponse {
+        wallet_address: request.wallet_address,
+        key_version: new_version,
+        previous_public_key: old_record.public_key,
+        public_key: new_public_key,
+        key_fingerprint,
+        linkage_signature,
+        rotated_at_epoch_ms: now,
+    }))
+}
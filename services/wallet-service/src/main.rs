@@ -1,39 +1,87 @@
+mod approvals;
+mod bridge;
+mod bundle;
 mod chain_config;
+mod chains;
+mod chaos;
+mod deadline;
+mod demo;
+mod devices;
+mod escrow;
+mod export;
+mod external;
 mod fortressdigital;
+mod health_history;
+mod honeytoken;
+mod key_rotation;
+mod memo;
+mod ndjson;
+mod nonce_reservations;
+mod notify;
+mod pipeline;
+mod policy;
+mod portfolio;
 mod proofcortex;
-use fortressdigital::{FortressDigitalContextPayload, generate_context_payload, build_wallet_status};
+mod publisher;
+mod reports;
+mod request_meta;
+mod session_keys;
+mod sessions;
+mod submissions;
+mod tenants;
+mod versions;
+mod wallet_sessions;
+mod watch;
+mod webhooks;
+use fortressdigital::{
+    ContextPayloadParams, FortressDigitalContextPayload, build_wallet_status, generate_context_payload,
+};
 use axum::{
     Json, Router,
     extract::{Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post},
 };
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use jsonwebtoken::jwk::JwkSet;
 use kc_api_types::{
-    AssetSymbol, FortressDigitalWalletStatusRequest, FortressDigitalWalletStatusResponse,
+    Amount, ApiError, AssetSymbol, ChainExplorerTemplates, ErrorCode, IdentifierError, FortressDigitalWalletStatusRequest, FortressDigitalWalletStatusResponse,
     WalletBalanceResponse, WalletCreateRequest, WalletCreateResponse, WalletListResponse,
-    WalletRenameRequest, WalletRenameResponse, WalletRestoreRequest, WalletRestoreResponse,
-    WalletSignRequest, WalletSignResponse, WalletSubmitResponse, WalletSummary, WalletAddress,
-    DeviceLinkRequest, DeviceLinkResponse, DeviceUnlinkRequest, DeviceUnlinkResponse,
+    WalletDeleteRequest, WalletDeleteResponse, WalletRenameRequest, WalletRenameResponse,
+    WalletRestoreRequest, WalletRestoreResponse,
+    WalletSignRequest, WalletSignResponse, WalletSummary, WalletAddress,
+    v2, Page, PageQuery, DeviceLinkRequest, DeviceLinkResponse, DeviceUnlinkRequest, DeviceUnlinkResponse,
     WalletLookupRequest, WalletLookupResponse,
 };
-use kc_chain_client::ChainAdapter;
+use kc_api_types::webhook::WebhookEvent;
+use kc_chain_client::ChainRegistry;
 use kc_chain_flowcortex::{FLOWCORTEX_L1, FlowCortexAdapter};
-use kc_crypto::{Ed25519Signer, Signer, decrypt_key_material, encrypt_key_material};
-use kc_storage::{Keystore, RocksDbKeystore, WalletIdentity};
+use kc_crypto::passphrase::passphrase_strength;
+use kc_crypto::{Ed25519Signer, SigningDomain, encrypt_key_material, fingerprint};
+use kc_crypto_kms::KmsKeyRegistry;
+pub(crate) use kc_crypto::encoding::{from_hex, to_hex};
+use kc_auth_adapter::{ChallengeStore, Challenges};
+use kc_storage::{
+    IdempotencyStore, InMemoryIdempotencyStore, InMemoryNonceStore, KEY_SCHEME_ED25519, Keystore, NonceStore,
+    RocksDbKeystore, StorageStats, WalletActivity, WalletIdentity, WalletMetadataRecord,
+    WalletTombstoneChange, WalletUsage,
+};
+use kc_wallet_core::{
+    ApprovalQueue, AuditSink, DynWalletCore, KeystoreSignerProvider, NonceManager,
+    SubmissionTracker,
+};
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env;
 use std::fs;
 use std::net::SocketAddr;
+use std::ops::Bound;
 use std::sync::{
     Arc, RwLock as StdRwLock,
     atomic::{AtomicU64, Ordering},
 };
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock as TokioRwLock;
 use tracing::{info, warn};
 
 mod submit;
@@ -41,6 +89,24 @@ mod auth;
 mod ops;
 mod db;
 
+/// How often the `KEYCORTEX_AUDIT_RETENTION_DAYS` pruning job runs.
+const AUDIT_RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often key material of deleted wallets past their grace period is purged.
+const TOMBSTONE_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often challenges past their retention are removed from the store.
+const CHALLENGE_SWEEP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Default `KEYCORTEX_WALLET_UNDELETE_GRACE_DAYS`.
+const DEFAULT_WALLET_UNDELETE_GRACE_DAYS: u64 = 30;
+
+/// Default `KEYCORTEX_SIGN_IN_ORIGIN`, the service on its default port.
+const DEFAULT_SIGN_IN_ORIGIN: &str = "http://localhost:8081";
+
+/// Default `KEYCORTEX_MIN_PASSPHRASE_BITS`; `0` disables the check.
+const DEFAULT_MIN_PASSPHRASE_BITS: f64 = 60.0;
+
 #[derive(Debug, Serialize)]
 struct HealthResponse {
     service: &'static str,
@@ -54,6 +120,8 @@ struct HealthResponse {
     jwks_loaded: bool,
     last_jwks_refresh_epoch_ms: Option<u128>,
     last_jwks_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chaos: Option<chaos::ChaosSnapshot>,
 }
 
 #[derive(Debug, Serialize)]
@@ -69,11 +137,16 @@ struct StartupDiagnosticsResponse {
     postgres_enabled: bool,
     postgres_startup: PostgresStartupReport,
     db_fallback_counters: DbFallbackCountersSnapshot,
+    /// Per-prefix key counts are left out; see `/ops/storage`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<StorageStats>,
     auth_mode: String,
     jwks_source: Option<String>,
     jwks_loaded: bool,
     last_jwks_refresh_epoch_ms: Option<u128>,
     last_jwks_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chaos: Option<chaos::ChaosSnapshot>,
 }
 
 #[derive(Debug, Serialize)]
@@ -89,15 +162,14 @@ struct ReadinessResponse {
     auth_mode: String,
     jwks_reachable: Option<bool>,
     reason: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub(crate) struct ErrorResponse {
-    error: String,
+    /// False when a probed chain is unreachable. Chains do not affect
+    /// `ready`: an on-chain outage is not a wallet-service failure.
+    chains_reachable: bool,
+    chains: BTreeMap<String, chains::ChainReachability>,
 }
 
 #[derive(Debug, Deserialize)]
-struct WalletBalanceQuery {
+pub(crate) struct WalletBalanceQuery {
     wallet_address: String,
     asset: Option<String>,
     chain: Option<String>,
@@ -111,7 +183,7 @@ struct JwksRuntimeStatus {
     last_error: Option<String>,
 }
 
-pub(crate) type ApiResult<T> = Result<Json<T>, (StatusCode, Json<ErrorResponse>)>;
+pub(crate) type ApiResult<T> = Result<Json<T>, (StatusCode, Json<ApiError>)>;
 
 #[derive(Debug, Clone, Serialize)]
 struct DbFallbackCountersSnapshot {
@@ -122,6 +194,9 @@ struct DbFallbackCountersSnapshot {
     binding_read_failures: u64,
     audit_write_failures: u64,
     audit_read_failures: u64,
+    submission_write_failures: u64,
+    submission_read_failures: u64,
+    metadata_write_failures: u64,
     total: u64,
 }
 
@@ -134,6 +209,9 @@ pub(crate) struct DbFallbackCounters {
     binding_read_failures: AtomicU64,
     audit_write_failures: AtomicU64,
     audit_read_failures: AtomicU64,
+    submission_write_failures: AtomicU64,
+    submission_read_failures: AtomicU64,
+    metadata_write_failures: AtomicU64,
 }
 
 impl DbFallbackCounters {
@@ -165,6 +243,18 @@ impl DbFallbackCounters {
         self.audit_read_failures.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub(crate) fn inc_submission_write_failures(&self) {
+        self.submission_write_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_submission_read_failures(&self) {
+        self.submission_read_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_metadata_write_failures(&self) {
+        self.metadata_write_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
     fn snapshot(&self) -> DbFallbackCountersSnapshot {
         let postgres_unavailable = self.postgres_unavailable.load(Ordering::Relaxed);
         let challenge_persist_failures = self.challenge_persist_failures.load(Ordering::Relaxed);
@@ -173,13 +263,19 @@ impl DbFallbackCounters {
         let binding_read_failures = self.binding_read_failures.load(Ordering::Relaxed);
         let audit_write_failures = self.audit_write_failures.load(Ordering::Relaxed);
         let audit_read_failures = self.audit_read_failures.load(Ordering::Relaxed);
+        let submission_write_failures = self.submission_write_failures.load(Ordering::Relaxed);
+        let submission_read_failures = self.submission_read_failures.load(Ordering::Relaxed);
+        let metadata_write_failures = self.metadata_write_failures.load(Ordering::Relaxed);
         let total = postgres_unavailable
             + challenge_persist_failures
             + challenge_mark_used_failures
             + binding_write_failures
             + binding_read_failures
             + audit_write_failures
-            + audit_read_failures;
+            + audit_read_failures
+            + submission_write_failures
+            + submission_read_failures
+            + metadata_write_failures;
 
         DbFallbackCountersSnapshot {
             postgres_unavailable,
@@ -189,11 +285,43 @@ impl DbFallbackCounters {
             binding_read_failures,
             audit_write_failures,
             audit_read_failures,
+            submission_write_failures,
+            submission_read_failures,
+            metadata_write_failures,
             total,
         }
     }
 }
 
+impl DbFallbackCountersSnapshot {
+    /// How much each counter grew since `previous`.
+    fn since(&self, previous: &Self) -> Self {
+        Self {
+            postgres_unavailable: self.postgres_unavailable.saturating_sub(previous.postgres_unavailable),
+            challenge_persist_failures: self
+                .challenge_persist_failures
+                .saturating_sub(previous.challenge_persist_failures),
+            challenge_mark_used_failures: self
+                .challenge_mark_used_failures
+                .saturating_sub(previous.challenge_mark_used_failures),
+            binding_write_failures: self.binding_write_failures.saturating_sub(previous.binding_write_failures),
+            binding_read_failures: self.binding_read_failures.saturating_sub(previous.binding_read_failures),
+            audit_write_failures: self.audit_write_failures.saturating_sub(previous.audit_write_failures),
+            audit_read_failures: self.audit_read_failures.saturating_sub(previous.audit_read_failures),
+            submission_write_failures: self
+                .submission_write_failures
+                .saturating_sub(previous.submission_write_failures),
+            submission_read_failures: self
+                .submission_read_failures
+                .saturating_sub(previous.submission_read_failures),
+            metadata_write_failures: self
+                .metadata_write_failures
+                .saturating_sub(previous.metadata_write_failures),
+            total: self.total.saturating_sub(previous.total),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct PostgresStartupReport {
     configured: bool,
@@ -203,30 +331,86 @@ struct PostgresStartupReport {
     last_error: Option<String>,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct ChallengeRecord {
-    pub(crate) issued_at_epoch_ms: u128,
-    pub(crate) expires_at_epoch_ms: u128,
-    pub(crate) used: bool,
-    pub(crate) used_at_epoch_ms: Option<u128>,
+/// A core signing for the wallets custodied in `keystore`, shared by every
+/// handler through [`AppState`]. Its chain registry stays empty: chain
+/// traffic goes through [`chains`], whose adapters can change at runtime.
+pub(crate) fn custody_core(
+    keystore: &Arc<RocksDbKeystore>,
+    encryption_key: &Arc<str>,
+    signing_domain: &SigningDomain,
+    audit: Arc<dyn AuditSink>,
+) -> Arc<DynWalletCore> {
+    Arc::new(
+        DynWalletCore::new_dyn(
+            Arc::new(KeystoreSignerProvider::new(
+                Arc::clone(keystore),
+                Arc::clone(encryption_key),
+            )),
+            keystore.clone(),
+            ChainRegistry::default(),
+        )
+        .with_signing_domain(signing_domain.clone())
+        .with_session_keys(keystore.clone(), Arc::clone(encryption_key))
+        .with_audit_sink(audit),
+    )
 }
 
 pub(crate) struct AppState {
     pub(crate) keystore: Arc<RocksDbKeystore>,
+    /// Loads custodied keys from `keystore`; rebuild it with
+    /// [`custody_core`] when replacing the keystore.
+    pub(crate) wallet_core: Arc<DynWalletCore>,
     pub(crate) postgres_repo: Option<Arc<db::PostgresRepository>>,
     pub(crate) db_fallback_counters: Arc<DbFallbackCounters>,
     postgres_startup: Arc<StdRwLock<PostgresStartupReport>>,
     pub(crate) encryption_key: Arc<str>,
+    pub(crate) signing_domain: SigningDomain,
+    /// Origin named in sign-in messages, from `KEYCORTEX_SIGN_IN_ORIGIN`.
+    pub(crate) sign_in_origin: Arc<str>,
+    /// Issues the wallet sessions `/auth/verify` hands out; see [`wallet_sessions`].
+    pub(crate) wallet_sessions: wallet_sessions::WalletSessions,
     pub(crate) authbuddy_jwt_secret: Arc<str>,
     pub(crate) authbuddy_jwks: Arc<StdRwLock<Option<JwkSet>>>,
     jwks_status: Arc<StdRwLock<JwksRuntimeStatus>>,
     pub(crate) authbuddy_expected_issuer: Option<Arc<str>>,
     pub(crate) authbuddy_expected_audience: Option<Arc<str>>,
-    pub(crate) challenge_store: Arc<TokioRwLock<HashMap<String, ChallengeRecord>>>,
-    pub(crate) submit_idempotency_cache: Arc<TokioRwLock<HashMap<String, WalletSubmitResponse>>>,
-    pub(crate) submit_nonce_state: Arc<TokioRwLock<HashMap<String, u64>>>,
-    pub(crate) authbuddy_callback: Option<Box<dyn crate::auth::AuthBuddyCallback + Send + Sync>>,
-    pub(crate) chain_adapter: Arc<dyn ChainAdapter>,
+    /// Kept in Postgres when `KEYCORTEX_CHALLENGE_STORE=postgres`, else in
+    /// Redis when `KEYCORTEX_REDIS_URL` is set, else in `keystore`.
+    pub(crate) challenges: Arc<Challenges>,
+    /// Postgres copy of challenges kept elsewhere.
+    pub(crate) challenge_mirror: Option<Arc<db::PostgresRepository>>,
+    pub(crate) submit_idempotency_cache: Arc<dyn IdempotencyStore>,
+    /// Claims nonces in the same store as challenges, and settles them in
+    /// `keystore`; rebuild it when replacing the keystore.
+    pub(crate) nonces: Arc<NonceManager>,
+    /// Transfers held for a second ops principal, kept in `keystore`.
+    pub(crate) approvals: Arc<ApprovalQueue>,
+    /// Watches submitted transfers until they settle; see [`submissions`].
+    pub(crate) submissions: Arc<SubmissionTracker>,
+    pub(crate) authbuddy_callback: Option<Arc<dyn crate::auth::AuthBuddyCallback + Send + Sync>>,
+    pub(crate) chains: Arc<StdRwLock<chains::ChainTable>>,
+    pub(crate) honeytoken_alert_url: Option<Arc<str>>,
+    /// Set when `KEYCORTEX_APPROVAL_NOTIFY_CHANNELS` lists a channel.
+    pub(crate) approval_notifier: Option<Arc<notify::ApprovalNotifier>>,
+    /// Set when `KEYCORTEX_EVENT_BROKER` names a broker.
+    pub(crate) event_publisher: Option<Arc<publisher::EventPublisher>>,
+    /// Set when `KEYCORTEX_WEBHOOK_URL` is set.
+    pub(crate) webhook_notifier: Option<Arc<webhooks::WebhookNotifier>>,
+    pub(crate) trusted_device_submit_threshold: Option<u128>,
+    pub(crate) min_passphrase_entropy_bits: f64,
+    pub(crate) kms_keys: Arc<KmsKeyRegistry>,
+    /// Bridge wallet per chain for `/wallet/bridge`.
+    pub(crate) bridge_wallets: HashMap<String, String>,
+    pub(crate) chaos: Option<Arc<chaos::Chaos>>,
+    pub(crate) health_history: Arc<health_history::HealthHistory>,
+    /// Set in demo mode, where it also serves as the `flowcortex-l1` adapter.
+    pub(crate) demo_ledger: Option<Arc<demo::DemoLedger>>,
+    /// Longest budget a request may have; see [`deadline`].
+    pub(crate) request_timeout: Duration,
+    /// How long a deleted wallet can be restored before its key is purged.
+    pub(crate) wallet_undelete_grace: Duration,
+    /// Wallets a tenant may hold; see [`tenants`].
+    pub(crate) wallet_quota: Option<tenants::WalletQuota>,
 }
 
 #[tokio::main]
@@ -241,7 +425,60 @@ async fn main() -> anyhow::Result<()> {
         fs::create_dir_all(parent)?;
     }
 
-    let keystore = RocksDbKeystore::open_default(&keystore_path)?;
+    let chaos = chaos::ChaosConfig::from_env()?.map(chaos::Chaos::new);
+    if chaos.is_some() {
+        warn!("chaos fault injection is enabled; do not run this configuration in production");
+    }
+
+    let mut keystore = RocksDbKeystore::open_default(&keystore_path)?;
+    info!("keystore schema version {}", keystore.schema_version()?);
+    if let Ok(master_key) = env::var("KEYCORTEX_STORAGE_MASTER_KEY") {
+        let master_key = kc_crypto::aead::MasterKey::from_hex(&master_key)
+            .map_err(|err| err.context("KEYCORTEX_STORAGE_MASTER_KEY"))?;
+        keystore = keystore.with_encryption(master_key)?;
+        info!("keystore values are encrypted at rest");
+    }
+    if let Some(chaos) = &chaos {
+        keystore = keystore.with_access_hook(chaos.storage_hook());
+    }
+    let event_publisher = publisher::EventPublisher::from_env()?.map(Arc::new);
+    if event_publisher.is_some() {
+        keystore = keystore.with_event_outbox();
+    }
+
+    let demo_ledger = demo::enabled_from_env().then(|| Arc::new(demo::DemoLedger::default()));
+    if demo_ledger.is_some() {
+        warn!("demo mode is enabled; flowcortex-l1 is an in-memory ledger, do not run this configuration in production");
+    }
+
+    let chain_http = kc_chain_client::SharedHttp::new(&chains::http_config_from_env())?;
+    let flowcortex = FlowCortexAdapter::default().with_http_client(chain_http.self_signed.clone());
+    let flowcortex_endpoint = flowcortex.endpoint().to_owned();
+    let builtin_adapter: Arc<dyn kc_chain_client::ChainAdapter> = match &demo_ledger {
+        Some(ledger) => ledger.clone(),
+        None => Arc::new(flowcortex),
+    };
+    let mut chain_table = chains::ChainTable::with_builtin(&flowcortex_endpoint, builtin_adapter).with_http(chain_http);
+    let explorer_env = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+    chain_table = chain_table.with_builtin_explorer(ChainExplorerTemplates {
+        tx_url: explorer_env("KEYCORTEX_FLOWCORTEX_EXPLORER_TX_URL"),
+        address_url: explorer_env("KEYCORTEX_FLOWCORTEX_EXPLORER_ADDRESS_URL"),
+        block_url: explorer_env("KEYCORTEX_FLOWCORTEX_EXPLORER_BLOCK_URL"),
+    });
+    if let Some(chaos) = &chaos {
+        chain_table = chain_table.with_chaos(Arc::clone(chaos));
+    }
+    let chains_config = chains::config_from_env()?;
+    if let Some(config) = &chains_config {
+        chain_table.load_config(config)?;
+        info!("configured {} chains from KEYCORTEX_CHAINS_CONFIG", config.chains.len());
+    }
+    chain_table.load_persisted(&keystore)?;
+
+    let kms_keys = KmsKeyRegistry::from_env()?;
+    if !kms_keys.is_empty() {
+        info!("loaded {} KMS-backed wallet keys", kms_keys.wallet_addresses().len());
+    }
 
     let db_fallback_counters = Arc::new(DbFallbackCounters::default());
     let mut postgres_startup = PostgresStartupReport {
@@ -283,7 +520,10 @@ async fn main() -> anyhow::Result<()> {
                     }
                     postgres_startup.enabled = true;
                     info!("connected Postgres repository");
-                    Some(Arc::new(repo))
+                    Some(Arc::new(match &chaos {
+                        Some(chaos) => repo.with_chaos(Arc::clone(chaos)),
+                        None => repo,
+                    }))
                 }
                 Err(err) => {
                     postgres_startup.last_error = Some(format!("connect failure: {}", err));
@@ -333,14 +573,81 @@ async fn main() -> anyhow::Result<()> {
         last_error: None,
     };
 
+    let redis = match env::var("KEYCORTEX_REDIS_URL")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+    {
+        Some(url) => {
+            let mut redis = kc_storage_redis::RedisSharedState::connect(&url)
+                .await
+                .map_err(|err| err.context("KEYCORTEX_REDIS_URL"))?;
+            if let Ok(prefix) = env::var("KEYCORTEX_REDIS_KEY_PREFIX") {
+                redis = redis.with_key_prefix(&prefix);
+            }
+            info!("challenges, nonces and idempotency records are shared through Redis");
+            Some(Arc::new(redis))
+        }
+        None => None,
+    };
+    let (submit_idempotency_cache, submit_nonce_state): (Arc<dyn IdempotencyStore>, Arc<dyn NonceStore>) =
+        match &redis {
+            Some(redis) => (redis.clone(), redis.clone()),
+            None => (
+                Arc::new(InMemoryIdempotencyStore::default()),
+                Arc::new(InMemoryNonceStore::default()),
+            ),
+        };
+
     let authbuddy_callback_url = env::var("AUTHBUDDY_CALLBACK_URL").ok();
-    let authbuddy_callback = authbuddy_callback_url.map(|url| Box::new(crate::auth::DefaultAuthBuddyCallback { url: Some(url) }) as Box<dyn crate::auth::AuthBuddyCallback + Send + Sync>);
+    let authbuddy_callback = authbuddy_callback_url.map(|url| Arc::new(crate::auth::DefaultAuthBuddyCallback { url: Some(url) }) as Arc<dyn crate::auth::AuthBuddyCallback + Send + Sync>);
+    let keystore = Arc::new(keystore);
+    let challenges_in_postgres = env::var("KEYCORTEX_CHALLENGE_STORE")
+        .is_ok_and(|value| value.trim().eq_ignore_ascii_case("postgres"));
+    if challenges_in_postgres && postgres_repo.is_none() {
+        warn!("KEYCORTEX_CHALLENGE_STORE=postgres but Postgres is unavailable; challenges are kept locally or in Redis");
+    }
+    let (challenge_store, challenge_mirror): (Arc<dyn ChallengeStore>, _) = match (&postgres_repo, redis.clone()) {
+        (Some(repo), _) if challenges_in_postgres => {
+            info!("challenges are shared through Postgres");
+            (repo.clone(), None)
+        }
+        (_, Some(redis)) => (redis, postgres_repo.clone()),
+        (_, None) => (keystore.clone(), postgres_repo.clone()),
+    };
+    let encryption_key = Arc::<str>::from("keycortex-dev-master-key");
+    let signing_domain =
+        chain_config::signing_domain(chains_config.as_ref().and_then(|config| config.signing.as_ref()))?;
     let state = AppState {
-        keystore: Arc::new(keystore),
+        wallet_core: custody_core(
+            &keystore,
+            &encryption_key,
+            &signing_domain,
+            Arc::new(auth::AuditLog {
+                keystore: keystore.clone(),
+                postgres_repo: postgres_repo.clone(),
+                db_fallback_counters: Arc::clone(&db_fallback_counters),
+            }),
+        ),
+        nonces: Arc::new(NonceManager::new(submit_nonce_state, keystore.clone())),
+        approvals: Arc::new(ApprovalQueue::new(keystore.clone())),
+        submissions: Arc::new(SubmissionTracker::new(
+            keystore.clone(),
+            submissions::policy_from_env(),
+        )),
+        keystore,
         postgres_repo,
         db_fallback_counters,
         postgres_startup: Arc::new(StdRwLock::new(postgres_startup)),
-        encryption_key: Arc::<str>::from("keycortex-dev-master-key"),
+        encryption_key,
+        signing_domain,
+        sign_in_origin: Arc::<str>::from(
+            env::var("KEYCORTEX_SIGN_IN_ORIGIN")
+                .ok()
+                .map(|value| value.trim().trim_end_matches('/').to_owned())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| DEFAULT_SIGN_IN_ORIGIN.to_owned()),
+        ),
+        wallet_sessions: wallet_sessions::WalletSessions::from_env()?,
         authbuddy_jwt_secret: Arc::<str>::from(
             env::var("AUTHBUDDY_JWT_SECRET")
                 .unwrap_or_else(|_| "authbuddy-dev-secret-change-me".to_owned()),
@@ -355,12 +662,50 @@ async fn main() -> anyhow::Result<()> {
             .ok()
             .filter(|value| !value.trim().is_empty())
             .map(Arc::<str>::from),
-        challenge_store: Arc::new(TokioRwLock::new(HashMap::new())),
-        submit_idempotency_cache: Arc::new(TokioRwLock::new(HashMap::new())),
-        submit_nonce_state: Arc::new(TokioRwLock::new(HashMap::new())),
+        challenges: Arc::new(Challenges::new(challenge_store)),
+        challenge_mirror,
+        submit_idempotency_cache,
         authbuddy_callback,
-        chain_adapter: Arc::new(FlowCortexAdapter::default()),
+        chains: Arc::new(StdRwLock::new(chain_table)),
+        honeytoken_alert_url: env::var("KEYCORTEX_HONEYTOKEN_ALERT_URL")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(Arc::<str>::from),
+        approval_notifier: notify::ApprovalNotifier::from_env()?.map(Arc::new),
+        event_publisher,
+        webhook_notifier: webhooks::WebhookNotifier::from_env()?.map(Arc::new),
+        trusted_device_submit_threshold: env::var("KEYCORTEX_TRUSTED_DEVICE_SUBMIT_THRESHOLD")
+            .ok()
+            .and_then(|value| value.trim().parse::<u128>().ok()),
+        min_passphrase_entropy_bits: env::var("KEYCORTEX_MIN_PASSPHRASE_BITS")
+            .ok()
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .unwrap_or(DEFAULT_MIN_PASSPHRASE_BITS),
+        kms_keys: Arc::new(kms_keys),
+        bridge_wallets: bridge::wallets_from_env()?,
+        chaos,
+        health_history: Arc::new(health_history::HealthHistory::from_env()),
+        demo_ledger,
+        request_timeout: deadline::timeout_from_env(),
+        wallet_undelete_grace: Duration::from_secs(
+            env::var("KEYCORTEX_WALLET_UNDELETE_GRACE_DAYS")
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .unwrap_or(DEFAULT_WALLET_UNDELETE_GRACE_DAYS)
+                * 24
+                * 60
+                * 60,
+        ),
+        wallet_quota: None,
     };
+    let tenants = tenants::Tenants::from_env(&state, redis.as_deref())?;
+    if tenants.len() > 0 {
+        info!("serving {} tenant(s) from KEYCORTEX_TENANTS_CONFIG", tenants.len());
+    }
+    // Keystore views the maintenance jobs cover: the default one and each tenant's.
+    let keystores: Vec<Arc<RocksDbKeystore>> = std::iter::once(Arc::clone(&state.keystore))
+        .chain(tenants.states().map(|tenant| Arc::clone(&tenant.keystore)))
+        .collect();
 
     if authbuddy_jwks_url.is_some() || authbuddy_jwks_path.is_some() {
         let jwks_cache = Arc::clone(&state.authbuddy_jwks);
@@ -448,7 +793,172 @@ async fn main() -> anyhow::Result<()> {
         });
     }
 
-    let app = build_app(state);
+    if let Some(retention_days) = env::var("KEYCORTEX_AUDIT_RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|days| *days > 0)
+    {
+        info!("pruning audit events older than {} day(s)", retention_days);
+        for keystore in &keystores {
+            let keystore = Arc::clone(keystore);
+            tokio::spawn(async move {
+                loop {
+                    let keystore = Arc::clone(&keystore);
+                    let pruned = tokio::task::spawn_blocking(move || {
+                        auth::prune_audit_events(&keystore, retention_days, epoch_ms()?)
+                    })
+                    .await;
+                    match pruned {
+                        Ok(Ok(0)) => {}
+                        Ok(Ok(count)) => info!("pruned {} audit event(s) past retention", count),
+                        Ok(Err(err)) => warn!("audit retention pruning failed: {}", err),
+                        Err(err) => warn!("audit retention task panicked: {}", err),
+                    }
+                    tokio::time::sleep(AUDIT_RETENTION_INTERVAL).await;
+                }
+            });
+        }
+    }
+    for keystore in &keystores {
+        let keystore = Arc::clone(keystore);
+        tokio::spawn(async move {
+            loop {
+                let keystore = Arc::clone(&keystore);
+                let purged =
+                    tokio::task::spawn_blocking(move || keystore.purge_expired_tombstones(epoch_ms()?)).await;
+                match purged {
+                    Ok(Ok(0)) => {}
+                    Ok(Ok(count)) => info!("purged key material of {} deleted wallet(s)", count),
+                    Ok(Err(err)) => warn!("deleted wallet purge failed: {}", err),
+                    Err(err) => warn!("deleted wallet purge task panicked: {}", err),
+                }
+                tokio::time::sleep(TOMBSTONE_PURGE_INTERVAL).await;
+            }
+        });
+    }
+    for keystore in &keystores {
+        let keystore = Arc::clone(keystore);
+        tokio::spawn(async move {
+            loop {
+                let keystore = Arc::clone(&keystore);
+                let pruned =
+                    tokio::task::spawn_blocking(move || keystore.prune_expired_sessions(epoch_ms()?)).await;
+                match pruned {
+                    Ok(Ok(0)) => {}
+                    Ok(Ok(count)) => info!("pruned {} expired session(s)", count),
+                    Ok(Err(err)) => warn!("session pruning failed: {}", err),
+                    Err(err) => warn!("session pruning task panicked: {}", err),
+                }
+                tokio::time::sleep(sessions::SESSION_PRUNE_INTERVAL).await;
+            }
+        });
+    }
+    if let Some(target) = kc_storage_backup::BackupTarget::from_env()? {
+        let schedule = env::var("KEYCORTEX_BACKUP_SCHEDULE").unwrap_or_else(|_| "@daily".to_owned());
+        let interval = kc_storage_backup::parse_schedule(&schedule)?;
+        let keystore = Arc::clone(&state.keystore);
+        info!("backing up to S3 bucket '{}' ({})", target.bucket(), schedule.trim());
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                match target.backup(Arc::clone(&keystore), epoch_ms().unwrap_or_default()).await {
+                    Ok(report) => info!(
+                        "backed up {} keystore entries to {} and {} audit events to {} ({} bytes)",
+                        report.keystore_entries,
+                        report.keystore_object,
+                        report.audit_events,
+                        report.audit_object,
+                        report.bytes_uploaded
+                    ),
+                    Err(err) => warn!("S3 backup failed: {:#}", err),
+                }
+            }
+        });
+    }
+
+    if let Some(publisher) = &state.event_publisher {
+        info!(
+            "mirroring audit and tx status events to {} topic '{}'",
+            publisher.broker_kind(),
+            publisher.topic()
+        );
+        for keystore in &keystores {
+            tokio::spawn(publisher::run(Arc::clone(publisher), Arc::clone(keystore)));
+        }
+    }
+
+    if let Some(notifier) = &state.webhook_notifier {
+        info!("posting signed webhook events to {}", notifier.url());
+        for keystore in &keystores {
+            tokio::spawn(webhooks::run(Arc::clone(notifier), keystore.subscribe()));
+        }
+    }
+
+    let state = Arc::new(state);
+    if state.demo_ledger.is_some() {
+        let request = demo::DemoSeedRequest {
+            seed: Some(demo::seed_from_env()),
+            ..Default::default()
+        };
+        let ctx = deadline::RequestContext::with_budget(state.request_timeout);
+        match demo::seed(&state, &ctx, &request).await {
+            Ok(seeded) => info!(
+                "demo fixture '{}' ready: {} wallet(s), {} transfer(s) submitted, {} replayed",
+                seeded.seed,
+                seeded.wallets.len(),
+                seeded.transfers_submitted,
+                seeded.transfers_replayed
+            ),
+            Err((_, Json(err))) => warn!("demo seeding failed: {}", err.message),
+        }
+    }
+    {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            loop {
+                health_history::record_sample(&state).await;
+                tokio::time::sleep(state.health_history.interval()).await;
+            }
+        });
+    }
+    for state in std::iter::once(&state).chain(tenants.states()) {
+        let challenges = Arc::clone(&state.challenges);
+        tokio::spawn(async move {
+            loop {
+                match challenges.sweep(epoch_ms().unwrap_or_default()).await {
+                    Ok(0) => {}
+                    Ok(count) => info!("removed {} expired challenge(s)", count),
+                    Err(err) => warn!("challenge sweep failed: {:#}", err),
+                }
+                tokio::time::sleep(CHALLENGE_SWEEP_INTERVAL).await;
+            }
+        });
+    }
+    for state in std::iter::once(&state).chain(tenants.states()) {
+        let state = Arc::clone(state);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(submissions::POLL_INTERVAL).await;
+                submissions::poll(&state, epoch_ms().unwrap_or_default()).await;
+            }
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        let interval = chains::health_interval_from_env();
+        tokio::spawn(async move {
+            loop {
+                chains::refresh_health(&state).await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+
+    let app = if tenants.len() > 0 {
+        tenants::route(build_app(Arc::clone(&state)), state, tenants)
+    } else {
+        build_app(state)
+    };
 
     let port = std::env::var("PORT")
         .ok()
@@ -541,6 +1051,7 @@ async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
         jwks_loaded: status_snapshot.loaded,
         last_jwks_refresh_epoch_ms: status_snapshot.last_refresh_epoch_ms,
         last_jwks_error: status_snapshot.last_error,
+        chaos: state.chaos.as_ref().map(|chaos| chaos.snapshot()),
     })
 }
 
@@ -593,6 +1104,11 @@ async fn startupz(State(state): State<Arc<AppState>>) -> Json<StartupDiagnostics
         });
 
     let db_fallback_counters = state.db_fallback_counters.snapshot();
+    let storage = state
+        .keystore
+        .storage_stats()
+        .map_err(|err| warn!("failed to read storage stats: {err}"))
+        .ok();
 
     Json(StartupDiagnosticsResponse {
         service: "wallet-service",
@@ -600,11 +1116,13 @@ async fn startupz(State(state): State<Arc<AppState>>) -> Json<StartupDiagnostics
         postgres_enabled,
         postgres_startup,
         db_fallback_counters,
+        storage,
         auth_mode,
         jwks_source: status_snapshot.source,
         jwks_loaded: status_snapshot.loaded,
         last_jwks_refresh_epoch_ms: status_snapshot.last_refresh_epoch_ms,
         last_jwks_error: status_snapshot.last_error,
+        chaos: state.chaos.as_ref().map(|chaos| chaos.snapshot()),
     })
 }
 
@@ -672,6 +1190,13 @@ async fn readyz(State(state): State<Arc<AppState>>) -> impl IntoResponse {
         Some("auth mode not ready".to_owned())
     };
 
+    let chains = state
+        .chains
+        .read()
+        .map(|table| table.reachability())
+        .unwrap_or_default();
+    let chains_reachable = chains.values().all(|chain| chain.reachable != Some(false));
+
     let status = if ready {
         StatusCode::OK
     } else {
@@ -692,10 +1217,32 @@ async fn readyz(State(state): State<Arc<AppState>>) -> impl IntoResponse {
             auth_mode,
             jwks_reachable,
             reason,
+            chains_reachable,
+            chains,
         }),
     )
 }
 
+/// Reject deterministic-wallet passphrases below the configured strength.
+fn enforce_passphrase_policy(
+    state: &AppState,
+    passphrase: &str,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    let report = passphrase_strength(passphrase);
+    if report.meets(state.min_passphrase_entropy_bits) {
+        return Ok(());
+    }
+    let mut message = format!(
+        "passphrase too weak: estimated {:.0} bits, minimum {:.0}",
+        report.entropy_bits, state.min_passphrase_entropy_bits
+    );
+    for warning in report.warnings.iter().chain(&report.suggestions) {
+        message.push_str("; ");
+        message.push_str(warning);
+    }
+    Err(bad_request(&message))
+}
+
 async fn wallet_create(
     State(state): State<Arc<AppState>>,
     Json(body): Json<WalletCreateRequest>,
@@ -704,28 +1251,42 @@ async fn wallet_create(
     let passphrase = body.passphrase.clone();
     let device_id = body.device_id.clone();
 
+    let wallet_slot = tenants::reserve_wallet_slot(&state).await?;
     let signer = match &passphrase {
-        Some(pp) if !pp.trim().is_empty() => Ed25519Signer::from_passphrase(pp),
+        Some(pp) if !pp.trim().is_empty() => {
+            enforce_passphrase_policy(&state, pp)?;
+            Ed25519Signer::from_passphrase(pp)
+        }
         _ => Ed25519Signer::new_random(),
     };
     let wallet_address = signer.wallet_address();
     let public_key = signer.public_key_hex();
 
-    let encrypted_key = encrypt_key_material(&signer.secret_key_bytes(), state.encryption_key.as_ref())
+    let encrypted_key = encrypt_key_material(
+        signer.secret_key_material().expose_secret(),
+        state.encryption_key.as_ref(),
+    )
         .map_err(internal_error)?;
 
     state
         .keystore
-        .save_encrypted_key(&wallet_address, encrypted_key)
-        .await
+        .save_encrypted_key_with_metadata(
+            encrypted_key,
+            &WalletMetadataRecord {
+                wallet_address: wallet_address.clone(),
+                label: label
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|lbl| !lbl.is_empty())
+                    .map(ToOwned::to_owned),
+                created_at_epoch_ms: Some(epoch_ms().map_err(internal_error)?),
+                key_scheme: KEY_SCHEME_ED25519.to_owned(),
+                usage: WalletUsage::default(),
+            },
+        )
         .map_err(internal_error)?;
-
-    // Save label if provided
-    if let Some(lbl) = &label {
-        if !lbl.trim().is_empty() {
-            let _ = state.keystore.save_wallet_label(&wallet_address, lbl.trim());
-        }
-    }
+    drop(wallet_slot);
+    mirror_wallet_metadata(&state, &wallet_address).await;
 
     // Link wallet to device if device_id provided
     if let Some(did) = &device_id {
@@ -753,28 +1314,125 @@ async fn wallet_create(
         let _ = state.keystore.save_wallet_identity(&wallet_address, &identity);
     }
 
+    webhooks::emit(
+        &state,
+        WebhookEvent::WalletCreated {
+            wallet_address: wallet_address.clone(),
+            public_key: public_key.clone(),
+            chain: FLOWCORTEX_L1.to_owned(),
+        },
+    );
+
+    let key_fingerprint = fingerprint::of_hex(&public_key).ok();
     Ok(Json(WalletCreateResponse {
         wallet_address,
         public_key,
         chain: FLOWCORTEX_L1.to_owned(),
         label,
+        key_fingerprint,
     }))
 }
 
 #[derive(Debug, Deserialize)]
-struct WalletListQuery {
+pub(crate) struct WalletListQuery {
     device_id: Option<String>,
     contact_info: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
 }
 
 async fn wallet_list(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Query(query): Query<WalletListQuery>,
-) -> ApiResult<WalletListResponse> {
-    let addresses = match (&query.device_id, &query.contact_info) {
+) -> Result<Response, (StatusCode, Json<ApiError>)> {
+    let ctx = deadline::RequestContext::from_headers(&state, &headers)?;
+    let device_addresses = device_wallet_addresses(&state, &query).map_err(internal_error)?;
+    if ndjson::requested(&headers) {
+        return wallet_list_ndjson(state, device_addresses, &query).map_err(internal_error);
+    }
+    let addresses = listed_addresses(&state, device_addresses).await?;
+    let total = addresses.len();
+    let addresses: Vec<String> = addresses
+        .into_iter()
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    let mut wallets = Vec::with_capacity(addresses.len());
+    for addr in &addresses {
+        ctx.check("wallet_list")?;
+        wallets.push(wallet_summary(&state, addr).await);
+    }
+
+    Ok(Json(WalletListResponse { wallets, total }).into_response())
+}
+
+/// Page size of `/v2/wallet/list` without a `limit`, and its largest.
+const DEFAULT_WALLET_PAGE: usize = 100;
+const MAX_WALLET_PAGE: usize = 500;
+
+/// One page of `/wallet/list` after the `cursor` address, as `/v2` serves
+/// it.
+pub(crate) async fn wallet_list_page(
+    state: &AppState,
+    headers: &HeaderMap,
+    query: &WalletListQuery,
+    page: &PageQuery,
+) -> Result<Page<WalletSummary>, (StatusCode, Json<ApiError>)> {
+    let ctx = deadline::RequestContext::from_headers(state, headers)?;
+    let device_addresses = device_wallet_addresses(state, query).map_err(internal_error)?;
+    let addresses = listed_addresses(state, device_addresses).await?;
+    let start = page
+        .start()
+        .map_or(0, |after| addresses.partition_point(|addr| addr.as_str() <= after));
+    let end = addresses
+        .len()
+        .min(start + page.limit_or(DEFAULT_WALLET_PAGE, MAX_WALLET_PAGE));
+
+    let mut wallets = Vec::with_capacity(end - start);
+    for addr in &addresses[start..end] {
+        ctx.check("wallet_list")?;
+        wallets.push(wallet_summary(state, addr).await);
+    }
+    let next_cursor = (end < addresses.len()).then(|| addresses[end - 1].clone());
+    Ok(Page::new(wallets, next_cursor).with_total_estimate(addresses.len() as u64))
+}
+
+/// Every listed wallet in address order: those on the requested device, or
+/// without a device filter keystore, KMS-backed, external-key and
+/// watch-only wallets alike. Honeytokens are left out.
+async fn listed_addresses(
+    state: &AppState,
+    device_addresses: Option<Vec<String>>,
+) -> Result<Vec<String>, (StatusCode, Json<ApiError>)> {
+    let addresses = match device_addresses {
+        Some(addresses) => addresses,
+        None => {
+            let mut addrs = state
+                .keystore
+                .list_wallet_addresses(0, usize::MAX)
+                .await
+                .map_err(internal_error)?;
+            addrs.extend(unlisted_wallet_addresses(state).map_err(internal_error)?);
+            addrs.sort();
+            addrs.dedup();
+            addrs
+        }
+    };
+    Ok(hide_honeytokens(state, addresses))
+}
+
+/// Wallets on the requested device, plus other devices sharing its contact
+/// info, sorted. `None` without a device filter.
+fn device_wallet_addresses(
+    state: &AppState,
+    query: &WalletListQuery,
+) -> anyhow::Result<Option<Vec<String>>> {
+    Ok(match (&query.device_id, &query.contact_info) {
         (Some(did), _) if !did.trim().is_empty() => {
             // Primary: filter by device_id
-            let mut addrs = state.keystore.list_device_wallets(did.trim()).map_err(internal_error)?;
+            let mut addrs = state.keystore.list_device_wallets(did.trim())?;
             // Also include wallets from other devices sharing the same contact info
             if let Some(ci) = &query.contact_info {
                 if !ci.trim().is_empty() {
@@ -794,7 +1452,7 @@ async fn wallet_list(
                 }
             }
             addrs.sort();
-            addrs
+            Some(addrs)
         }
         (_, Some(ci)) if !ci.trim().is_empty() => {
             // No device_id but contact_info provided — find all devices with this contact
@@ -811,50 +1469,207 @@ async fn wallet_list(
                 }
             }
             addrs.sort();
-            addrs
-        }
-        _ => {
-            // No device filter — return all wallets
-            state.keystore.list_wallet_addresses().await.map_err(internal_error)?
+            Some(addrs)
         }
-    };
+        _ => None,
+    })
+}
 
-    let mut wallets = Vec::with_capacity(addresses.len());
-    for addr in &addresses {
-        // Check binding
-        let binding = state.keystore.load_wallet_binding(addr).ok().flatten();
+/// Watch-only, external-key and KMS-backed wallets, which have no keystore
+/// key. There are few of them, unlike keystore wallets.
+fn unlisted_wallet_addresses(state: &AppState) -> anyhow::Result<BTreeSet<String>> {
+    let mut addresses: BTreeSet<String> = state.keystore.list_watch_wallets()?.into_iter().collect();
+    addresses.extend(state.keystore.list_external_key_wallets()?);
+    addresses.extend(state.kms_keys.wallet_addresses());
+    Ok(addresses)
+}
 
-        // Recover public key from encrypted secret key
-        let pub_key = match state.keystore.load_encrypted_key(addr).await {
-            Ok(Some(encrypted)) => {
-                match decrypt_key_material(&encrypted, state.encryption_key.as_ref()) {
-                    Ok(secret_bytes) => {
-                        let signer = Ed25519Signer::from_secret_key_bytes(secret_bytes);
-                        Some(signer.public_key_hex())
-                    }
-                    Err(_) => None,
-                }
+/// `/wallet/list` as NDJSON, without `total`. Keystore wallets are read a
+/// page at a time and merged with [`unlisted_wallet_addresses`].
+fn wallet_list_ndjson(
+    state: Arc<AppState>,
+    device_addresses: Option<Vec<String>>,
+    query: &WalletListQuery,
+) -> anyhow::Result<Response> {
+    let unlisted = match device_addresses {
+        Some(_) => BTreeSet::new(),
+        None => unlisted_wallet_addresses(&state)?,
+    };
+    let mut to_skip = query.offset.unwrap_or(0);
+    Ok(ndjson::stream_pages(query.limit, None, move |after: Option<String>| {
+        let page = wallet_address_page(&state, device_addresses.as_deref(), &unlisted, after.as_deref())
+            .map(|(addresses, next)| {
+                let mut addresses = hide_honeytokens(&state, addresses);
+                let skipped = to_skip.min(addresses.len());
+                addresses.drain(..skipped);
+                to_skip -= skipped;
+                (addresses, next)
+            });
+        let state = Arc::clone(&state);
+        async move {
+            let (addresses, next) = page?;
+            let mut wallets = Vec::with_capacity(addresses.len());
+            for addr in &addresses {
+                wallets.push(wallet_summary(&state, addr).await);
             }
-            _ => None,
+            Ok((wallets, next))
+        }
+    }))
+}
+
+/// The next sorted addresses after `after`, and the address to resume
+/// after, or `None` once every wallet has been returned.
+fn wallet_address_page(
+    state: &AppState,
+    device_addresses: Option<&[String]>,
+    unlisted: &BTreeSet<String>,
+    after: Option<&str>,
+) -> anyhow::Result<(Vec<String>, Option<String>)> {
+    if let Some(addresses) = device_addresses {
+        let start = after.map_or(0, |after| addresses.partition_point(|addr| addr.as_str() <= after));
+        let page: Vec<String> = addresses[start..].iter().take(ndjson::PAGE_SIZE).cloned().collect();
+        let next = if start + page.len() < addresses.len() {
+            page.last().cloned()
+        } else {
+            None
         };
+        return Ok((page, next));
+    }
 
-        let ident = state.keystore.load_wallet_identity(addr).ok().flatten();
+    let mut page = state.keystore.list_wallet_addresses_after(after, ndjson::PAGE_SIZE)?;
+    // A full page ends at its last address; the final page takes every
+    // remaining unlisted wallet.
+    let next = if page.len() == ndjson::PAGE_SIZE {
+        page.last().cloned()
+    } else {
+        None
+    };
+    let lower = after.map_or(Bound::Unbounded, Bound::Excluded);
+    let upper = next.as_deref().map_or(Bound::Unbounded, Bound::Included);
+    page.extend(unlisted.range::<str, _>((lower, upper)).cloned());
+    page.sort();
+    page.dedup();
+    Ok((page, next))
+}
 
-        wallets.push(WalletSummary {
-            wallet_address: addr.clone(),
-            chain: FLOWCORTEX_L1.to_owned(),
-            bound_user_id: binding.map(|b| b.user_id),
-            public_key: pub_key,
-            label: state.keystore.load_wallet_label(addr).ok().flatten(),
-            device_id: state.keystore.load_wallet_device(addr).ok().flatten(),
-            email: ident.as_ref().and_then(|i| i.email.clone()),
-            phone: ident.as_ref().and_then(|i| i.phone.clone()),
-            bank_id: ident.and_then(|i| i.bank_id),
-        });
+/// The `/wallet/list` entry for one wallet.
+async fn wallet_summary(state: &AppState, addr: &str) -> WalletSummary {
+    // Check binding
+    let binding = state.keystore.load_wallet_binding(addr).ok().flatten();
+
+    // Recover public key from encrypted secret key
+    let pub_key = match state.wallet_core.signer_for(addr).await {
+        Ok(Some(signer)) => Some(signer.public_key_hex()),
+        Ok(None) => state.kms_keys.get(addr).map(|kms| kms.public_key_hex()),
+        Err(_) => None,
+    };
+
+    let ident = state.keystore.load_wallet_identity(addr).ok().flatten();
+    let watch = state.keystore.load_watch_wallet(addr).ok().flatten();
+    let external = state.keystore.load_external_key(addr).ok().flatten();
+    let pub_key = pub_key.or_else(|| external.as_ref().map(|e| e.public_key.clone()));
+    let key_fingerprint = pub_key.as_deref().and_then(|key| fingerprint::of_hex(key).ok());
+    let metadata = state.keystore.load_wallet_metadata(addr).ok().flatten();
+    let usage = metadata.as_ref().map(|m| m.usage).unwrap_or_default();
+
+    WalletSummary {
+        wallet_address: addr.to_owned(),
+        chain: watch
+            .as_ref()
+            .map(|w| w.chain.clone())
+            .or_else(|| external.as_ref().map(|e| e.chain.clone()))
+            .unwrap_or_else(|| FLOWCORTEX_L1.to_owned()),
+        bound_user_id: binding.map(|b| b.user_id),
+        public_key: pub_key,
+        key_fingerprint,
+        label: state.keystore.load_wallet_label(addr).ok().flatten(),
+        device_id: state.keystore.load_wallet_device(addr).ok().flatten(),
+        email: ident.as_ref().and_then(|i| i.email.clone()),
+        phone: ident.as_ref().and_then(|i| i.phone.clone()),
+        bank_id: ident.and_then(|i| i.bank_id),
+        custodied: watch.is_none() && external.is_none(),
+        external_key: external.is_some(),
+        created_at_epoch_ms: metadata.as_ref().and_then(|m| m.created_at_epoch_ms),
+        key_scheme: metadata.map(|m| m.key_scheme),
+        signature_count: usage.signature_count,
+        last_signed_at_epoch_ms: usage.last_signed_at_epoch_ms,
+        last_submitted_at_epoch_ms: usage.last_submitted_at_epoch_ms,
     }
+}
 
-    let total = wallets.len();
-    Ok(Json(WalletListResponse { wallets, total }))
+/// Mirror a wallet's RocksDB metadata to Postgres, deleting the row once
+/// the wallet has none.
+pub(crate) async fn mirror_wallet_metadata(state: &AppState, wallet_address: &str) {
+    let Some(repo) = &state.postgres_repo else {
+        return;
+    };
+    let mirrored = match state.keystore.load_wallet_metadata(wallet_address) {
+        Ok(Some(record)) => repo.save_wallet_metadata(&record).await,
+        Ok(None) => repo.delete_wallet_metadata(wallet_address).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = mirrored {
+        state.db_fallback_counters.inc_metadata_write_failures();
+        warn!("failed to persist wallet metadata in Postgres: {}", err);
+    }
+}
+
+/// Count a signature or submission against the wallet and mirror the
+/// updated metadata. The signature has already been made, so a failed update
+/// is logged rather than failing the request.
+pub(crate) async fn record_wallet_activity(state: &AppState, wallet_address: &str, activity: WalletActivity) {
+    let recorded = epoch_ms().and_then(|now| {
+        let keystore = &state.keystore;
+        // KMS wallets get a metadata record on first use.
+        if state.kms_keys.get(wallet_address).is_some()
+            && keystore.load_wallet_metadata(wallet_address)?.is_none()
+        {
+            keystore.save_wallet_metadata(&WalletMetadataRecord {
+                wallet_address: wallet_address.to_owned(),
+                label: keystore.load_wallet_label(wallet_address)?,
+                created_at_epoch_ms: None,
+                key_scheme: KEY_SCHEME_ED25519.to_owned(),
+                usage: WalletUsage::default(),
+            })?;
+        }
+        keystore.record_wallet_activity(wallet_address, activity, now)
+    });
+    match recorded {
+        Ok(Some(_)) => mirror_wallet_metadata(state, wallet_address).await,
+        Ok(None) => {}
+        Err(err) => warn!("failed to record wallet activity for {}: {}", wallet_address, err),
+    }
+}
+
+/// Bring the Postgres binding, metadata and audit log in line with a wallet
+/// deleted or restored in RocksDB.
+pub(crate) async fn mirror_wallet_tombstone(state: &AppState, change: &WalletTombstoneChange) {
+    let Some(repo) = &state.postgres_repo else {
+        return;
+    };
+    let wallet_address = &change.tombstone.wallet_address;
+    let mirrored = match state.keystore.load_wallet_binding(wallet_address) {
+        Ok(Some(binding)) => repo.save_wallet_binding(&binding).await,
+        Ok(None) => repo.delete_wallet_binding(wallet_address).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = mirrored {
+        state.db_fallback_counters.inc_binding_write_failures();
+        warn!("failed to persist wallet binding in Postgres: {}", err);
+    }
+    mirror_wallet_metadata(state, wallet_address).await;
+    if let Err(err) = repo.append_audit_event(&change.audit_event).await {
+        state.db_fallback_counters.inc_audit_write_failures();
+        warn!("failed to append audit event to Postgres: {}", err);
+    }
+}
+
+/// Honeytoken wallets are never surfaced by listing APIs.
+fn hide_honeytokens(state: &AppState, addresses: Vec<String>) -> Vec<String> {
+    addresses
+        .into_iter()
+        .filter(|addr| !state.keystore.is_wallet_honeytoken(addr).unwrap_or(false))
+        .collect()
 }
 
 async fn wallet_restore(
@@ -864,6 +1679,7 @@ async fn wallet_restore(
     if request.passphrase.trim().is_empty() {
         return Err(bad_request("passphrase is required"));
     }
+    enforce_passphrase_policy(&state, &request.passphrase)?;
 
     let signer = Ed25519Signer::from_passphrase(&request.passphrase);
     let wallet_address = signer.wallet_address();
@@ -877,23 +1693,33 @@ async fn wallet_restore(
         .map_err(internal_error)?
         .is_some();
 
+    let requested_label = request
+        .label
+        .as_deref()
+        .map(str::trim)
+        .filter(|lbl| !lbl.is_empty());
     if !already_existed {
+        let _wallet_slot = tenants::reserve_wallet_slot(&state).await?;
         let encrypted_key =
-            encrypt_key_material(&signer.secret_key_bytes(), state.encryption_key.as_ref())
+            encrypt_key_material(signer.secret_key_material().expose_secret(), state.encryption_key.as_ref())
                 .map_err(internal_error)?;
         state
             .keystore
-            .save_encrypted_key(&wallet_address, encrypted_key)
-            .await
+            .save_encrypted_key_with_metadata(
+                encrypted_key,
+                &WalletMetadataRecord {
+                    wallet_address: wallet_address.clone(),
+                    label: requested_label.map(ToOwned::to_owned),
+                    created_at_epoch_ms: Some(epoch_ms().map_err(internal_error)?),
+                    key_scheme: KEY_SCHEME_ED25519.to_owned(),
+                    usage: WalletUsage::default(),
+                },
+            )
             .map_err(internal_error)?;
+    } else if let Some(lbl) = requested_label {
+        let _ = state.keystore.save_wallet_label(&wallet_address, lbl);
     }
-
-    // Save/update label if provided
-    if let Some(lbl) = &request.label {
-        if !lbl.trim().is_empty() {
-            let _ = state.keystore.save_wallet_label(&wallet_address, lbl.trim());
-        }
-    }
+    mirror_wallet_metadata(&state, &wallet_address).await;
 
     // Link wallet to device if device_id provided
     if let Some(did) = &request.device_id {
@@ -923,12 +1749,14 @@ async fn wallet_restore(
 
     let label = state.keystore.load_wallet_label(&wallet_address).ok().flatten();
 
+    let key_fingerprint = fingerprint::of_hex(&public_key).ok();
     Ok(Json(WalletRestoreResponse {
         wallet_address,
         public_key,
         chain: FLOWCORTEX_L1.to_owned(),
         label,
         already_existed,
+        key_fingerprint,
     }))
 }
 
@@ -993,35 +1821,49 @@ async fn wallet_lookup(
     }
 
     addresses.sort();
+    let addresses = hide_honeytokens(&state, addresses);
     if matched_by.is_empty() { matched_by = "none".to_owned(); }
 
     // Build summaries
     let mut wallets = Vec::with_capacity(addresses.len());
     for addr in &addresses {
         let binding = state.keystore.load_wallet_binding(addr).ok().flatten();
-        let pub_key = match state.keystore.load_encrypted_key(addr).await {
-            Ok(Some(encrypted)) => {
-                match decrypt_key_material(&encrypted, state.encryption_key.as_ref()) {
-                    Ok(secret_bytes) => {
-                        let signer = Ed25519Signer::from_secret_key_bytes(secret_bytes);
-                        Some(signer.public_key_hex())
-                    }
-                    Err(_) => None,
-                }
-            }
-            _ => None,
-        };
+        let pub_key = state
+            .wallet_core
+            .signer_for(addr)
+            .await
+            .ok()
+            .flatten()
+            .map(|signer| signer.public_key_hex());
         let ident = state.keystore.load_wallet_identity(addr).ok().flatten();
+        let watch = state.keystore.load_watch_wallet(addr).ok().flatten();
+        let external = state.keystore.load_external_key(addr).ok().flatten();
+        let pub_key = pub_key.or_else(|| external.as_ref().map(|e| e.public_key.clone()));
+        let key_fingerprint = pub_key.as_deref().and_then(|key| fingerprint::of_hex(key).ok());
+        let metadata = state.keystore.load_wallet_metadata(addr).ok().flatten();
+        let usage = metadata.as_ref().map(|m| m.usage).unwrap_or_default();
         wallets.push(WalletSummary {
             wallet_address: addr.clone(),
-            chain: FLOWCORTEX_L1.to_owned(),
+            chain: watch
+                .as_ref()
+                .map(|w| w.chain.clone())
+                .or_else(|| external.as_ref().map(|e| e.chain.clone()))
+                .unwrap_or_else(|| FLOWCORTEX_L1.to_owned()),
             bound_user_id: binding.map(|b| b.user_id),
             public_key: pub_key,
+            key_fingerprint,
             label: state.keystore.load_wallet_label(addr).ok().flatten(),
             device_id: state.keystore.load_wallet_device(addr).ok().flatten(),
             email: ident.as_ref().and_then(|i| i.email.clone()),
             phone: ident.as_ref().and_then(|i| i.phone.clone()),
             bank_id: ident.and_then(|i| i.bank_id),
+            custodied: watch.is_none() && external.is_none(),
+            external_key: external.is_some(),
+            created_at_epoch_ms: metadata.as_ref().and_then(|m| m.created_at_epoch_ms),
+            key_scheme: metadata.map(|m| m.key_scheme),
+            signature_count: usage.signature_count,
+            last_signed_at_epoch_ms: usage.last_signed_at_epoch_ms,
+            last_submitted_at_epoch_ms: usage.last_submitted_at_epoch_ms,
         });
     }
 
@@ -1031,14 +1873,14 @@ async fn wallet_lookup(
 
 async fn wallet_device_link(
     State(state): State<Arc<AppState>>,
+    scope: wallet_sessions::WalletScope,
     Json(request): Json<DeviceLinkRequest>,
 ) -> ApiResult<DeviceLinkResponse> {
     if request.device_id.trim().is_empty() {
         return Err(bad_request("device_id is required"));
     }
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    scope.authorize(&request.wallet_address)?;
 
     // Verify wallet exists on server
     let exists = state
@@ -1065,14 +1907,14 @@ async fn wallet_device_link(
 
 async fn wallet_device_unlink(
     State(state): State<Arc<AppState>>,
+    scope: wallet_sessions::WalletScope,
     Json(request): Json<DeviceUnlinkRequest>,
 ) -> ApiResult<DeviceUnlinkResponse> {
     if request.device_id.trim().is_empty() {
         return Err(bad_request("device_id is required"));
     }
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    scope.authorize(&request.wallet_address)?;
 
     state
         .keystore
@@ -1088,11 +1930,11 @@ async fn wallet_device_unlink(
 
 async fn wallet_rename(
     State(state): State<Arc<AppState>>,
+    scope: wallet_sessions::WalletScope,
     Json(request): Json<WalletRenameRequest>,
 ) -> ApiResult<WalletRenameResponse> {
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    scope.authorize(&request.wallet_address)?;
     if request.label.trim().is_empty() {
         return Err(bad_request("label is required"));
     }
@@ -1103,7 +1945,8 @@ async fn wallet_rename(
         .load_encrypted_key(&request.wallet_address)
         .await
         .map_err(internal_error)?
-        .is_some();
+        .is_some()
+        || external::load_public_key(&state, &request.wallet_address)?.is_some();
     if !exists {
         return Err(bad_request("wallet not found"));
     }
@@ -1112,6 +1955,7 @@ async fn wallet_rename(
         .keystore
         .save_wallet_label(&request.wallet_address, request.label.trim())
         .map_err(internal_error)?;
+    mirror_wallet_metadata(&state, &request.wallet_address).await;
 
     Ok(Json(WalletRenameResponse {
         wallet_address: request.wallet_address,
@@ -1119,13 +1963,67 @@ async fn wallet_rename(
     }))
 }
 
+/// Remove a custodied wallet's key material. Only the bound user or an
+/// ops-admin may delete; key history is kept for audit.
+async fn wallet_delete(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<WalletDeleteRequest>,
+) -> ApiResult<WalletDeleteResponse> {
+    let principal =
+        auth::parse_authbuddy_principal(&headers, &state).map_err(|msg| unauthorized(&msg))?;
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    if !state
+        .keystore
+        .has_wallet(&request.wallet_address)
+        .await
+        .map_err(internal_error)?
+    {
+        return Err(not_found("wallet not found"));
+    }
+
+    let bound_user = state
+        .keystore
+        .load_wallet_binding(&request.wallet_address)
+        .map_err(internal_error)?
+        .map(|binding| binding.user_id);
+    if bound_user.as_deref() != Some(principal.user_id.as_str()) && !principal.is_ops_admin() {
+        return Err(unauthorized("wallet deletion requires the bound user or ops-admin"));
+    }
+
+    let reason = request
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .unwrap_or("unspecified");
+    let change = state
+        .keystore
+        .mark_wallet_deleted(
+            &request.wallet_address,
+            reason,
+            &principal.user_id,
+            epoch_ms().map_err(internal_error)?,
+            state.wallet_undelete_grace.as_millis(),
+        )
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("wallet not found"))?;
+    mirror_wallet_tombstone(&state, &change).await;
+
+    Ok(Json(WalletDeleteResponse {
+        wallet_address: request.wallet_address,
+        deleted: true,
+        purge_after_epoch_ms: change.tombstone.purge_after_epoch_ms,
+    }))
+}
+
 async fn wallet_sign(
     State(state): State<Arc<AppState>>,
+    scope: wallet_sessions::WalletScope,
     Json(request): Json<WalletSignRequest>,
 ) -> ApiResult<WalletSignResponse> {
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    scope.authorize(&request.wallet_address)?;
 
     if request.payload.trim().is_empty() {
         return Err(bad_request("payload cannot be empty"));
@@ -1134,122 +2032,186 @@ async fn wallet_sign(
     let payload_bytes = STANDARD
         .decode(request.payload.as_bytes())
         .map_err(|_| bad_request("payload must be valid base64"))?;
+    state
+        .signing_domain
+        .check_purpose(&request.purpose)
+        .map_err(|e| bad_request(&e.to_string()))?;
+
+    honeytoken::trip_if_honeytoken(&state, &request.wallet_address, "wallet_sign").await;
+    watch::reject_watch_only(&state, &request.wallet_address)?;
+    external::reject_external_key(&state, &request.wallet_address)?;
+    policy::enforce(
+        &state,
+        &kc_wallet_core::PolicyContext {
+            wallet_address: &request.wallet_address,
+            purpose: &request.purpose,
+            transfer: None,
+        },
+    )
+    .await?;
 
-    let encrypted_key = state
-        .keystore
-        .load_encrypted_key(&request.wallet_address)
+    if let Some(session_key_id) = request.session_key_id.as_deref() {
+        let (signature_bytes, attestation) = state
+            .wallet_core
+            .sign_with_session_key(&request.wallet_address, session_key_id, &payload_bytes, request.purpose)
+            .await
+            .map_err(session_keys::session_key_error)?;
+        record_wallet_activity(&state, &request.wallet_address, WalletActivity::Signed).await;
+        return Ok(Json(WalletSignResponse {
+            signature: to_hex(&signature_bytes),
+            session_key: Some(attestation),
+        }));
+    }
+
+    if let Some(kms) = state.kms_keys.get(&request.wallet_address) {
+        let audited = kc_wallet_core::AuditEvent::Signed {
+            wallet_address: request.wallet_address.clone(),
+            purpose: request.purpose.as_str().to_owned(),
+            session_key_id: None,
+        };
+        let signature_bytes = kms
+            .sign_in_domain_async(&state.signing_domain, &payload_bytes, request.purpose)
+            .await
+            .map_err(internal_error)?;
+        state.wallet_core.audit(audited).await;
+        record_wallet_activity(&state, &request.wallet_address, WalletActivity::Signed).await;
+        return Ok(Json(WalletSignResponse {
+            signature: to_hex(&signature_bytes),
+            session_key: None,
+        }));
+    }
+
+    let signature_bytes = state
+        .wallet_core
+        .sign_payload(&request.wallet_address, &payload_bytes, request.purpose)
         .await
         .map_err(internal_error)?
         .ok_or_else(|| bad_request("wallet not found"))?;
-
-    let mut secret_key = decrypt_key_material(&encrypted_key, state.encryption_key.as_ref())
-        .map_err(internal_error)?;
-
-    let signer = Ed25519Signer::from_secret_key_bytes(secret_key);
-    secret_key.fill(0);
-    let signature_bytes = signer
-        .sign(&payload_bytes, request.purpose)
-        .map_err(internal_error)?;
+    record_wallet_activity(&state, &request.wallet_address, WalletActivity::Signed).await;
 
     Ok(Json(WalletSignResponse {
         signature: to_hex(&signature_bytes),
+        session_key: None,
     }))
 }
 
 async fn wallet_balance(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Query(query): Query<WalletBalanceQuery>,
 ) -> ApiResult<WalletBalanceResponse> {
-    if query.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    fetch_balance(&state, &headers, query)
+        .await
+        .map(|balance| Json(balance.into()))
+}
+
+/// The balance in the newest shape, for the v1 and v2 handlers to serve.
+pub(crate) async fn fetch_balance(
+    state: &Arc<AppState>,
+    headers: &HeaderMap,
+    query: WalletBalanceQuery,
+) -> Result<v2::WalletBalanceResponse, (StatusCode, Json<ApiError>)> {
+    let ctx = deadline::RequestContext::from_headers(state, headers)?;
+    parse_field::<WalletAddress>("wallet_address", &query.wallet_address)?;
 
     let chain = query.chain.unwrap_or_else(|| FLOWCORTEX_L1.to_owned());
-    if chain != FLOWCORTEX_L1 {
-        return Err(bad_request("unsupported chain for MVP; only flowcortex-l1 is enabled"));
-    }
+    let adapter = chains::adapter(state, &chain)?;
 
     let asset = query.asset.unwrap_or_else(|| "PROOF".to_owned());
-    if asset != "PROOF" && asset != "FloweR" {
-        return Err(bad_request("unsupported asset for MVP; only PROOF and FloweR are enabled"));
-    }
+    let decimals = chains::asset(state, &chain, &asset)?.decimals;
 
-    let result = state.chain_adapter
-        .get_balance(&WalletAddress(query.wallet_address.clone()), &AssetSymbol(asset.clone()))
+    honeytoken::trip_if_honeytoken(state, &query.wallet_address, "wallet_balance").await;
+
+    let result = ctx
+        .run(
+            "get_balance",
+            adapter.get_balance(&WalletAddress(query.wallet_address.clone()), &AssetSymbol(asset.clone())),
+        )
         .await
-        .map_err(internal_error)?;
+        .map_err(deadline::api_error)?;
+
+    // Decimals as the chain table advertises them, which adapters that
+    // report bare base units do not know.
+    Ok(v2::WalletBalanceResponse::new(
+        result.wallet_address.0,
+        result.chain.0,
+        result.asset.0,
+        Amount::new(result.amount.base_units(), decimals),
+    ))
+}
 
-    Ok(Json(WalletBalanceResponse {
-        wallet_address: result.wallet_address.0,
-        chain: result.chain.0,
-        asset: result.asset.0,
-        amount: result.amount,
-    }))
+fn error_response(status: StatusCode, error: ApiError) -> (StatusCode, Json<ApiError>) {
+    (status, Json(error))
 }
 
-pub(crate) fn bad_request(message: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
+pub(crate) fn bad_request(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::BAD_REQUEST, ApiError::new(ErrorCode::InvalidRequest, message))
+}
+
+/// 400 for a malformed request field, naming it in `details.field` so
+/// clients can point at the input.
+pub(crate) fn invalid_field(field: &str, err: impl std::fmt::Display) -> (StatusCode, Json<ApiError>) {
+    error_response(
         StatusCode::BAD_REQUEST,
-        Json(ErrorResponse {
-            error: message.to_owned(),
-        }),
+        ApiError::new(ErrorCode::InvalidRequest, format!("{field} {err}")).with_detail("field", field),
     )
 }
 
-pub(crate) fn unauthorized(message: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::UNAUTHORIZED,
-        Json(ErrorResponse {
-            error: message.to_owned(),
-        }),
-    )
+/// Parse request field `field` into its validated type, such as a
+/// [`WalletAddress`].
+pub(crate) fn parse_field<T>(field: &str, value: &str) -> Result<T, (StatusCode, Json<ApiError>)>
+where
+    T: std::str::FromStr<Err = IdentifierError>,
+{
+    value.parse().map_err(|err| invalid_field(field, err))
 }
 
-pub(crate) fn not_found(message: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::NOT_FOUND,
-        Json(ErrorResponse {
-            error: message.to_owned(),
-        }),
+/// 400 for a nonce that is already used, so clients know to refetch it.
+pub(crate) fn nonce_conflict(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::BAD_REQUEST, ApiError::new(ErrorCode::NonceConflict, message))
+}
+
+pub(crate) fn forbidden(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::FORBIDDEN, ApiError::new(ErrorCode::Forbidden, message))
+}
+
+pub(crate) fn unauthorized(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::UNAUTHORIZED, ApiError::new(ErrorCode::Unauthorized, message))
+}
+
+pub(crate) fn not_found(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::NOT_FOUND, ApiError::new(ErrorCode::NotFound, message))
+}
+
+pub(crate) fn internal_error(err: impl std::fmt::Display) -> (StatusCode, Json<ApiError>) {
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ApiError::new(ErrorCode::Internal, err.to_string()),
     )
 }
 
-pub(crate) fn internal_error(err: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
-    (
+/// 500 for a failed chain node or other dependency, which may recover.
+pub(crate) fn upstream_error(err: impl std::fmt::Display) -> (StatusCode, Json<ApiError>) {
+    error_response(
         StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: err.to_string(),
-        }),
+        ApiError::new(ErrorCode::Upstream, err.to_string()),
     )
 }
 
-pub(crate) fn epoch_ms() -> anyhow::Result<u128> {
-    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis())
+pub(crate) fn quota_exceeded(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::TOO_MANY_REQUESTS, ApiError::new(ErrorCode::QuotaExceeded, message))
 }
 
-pub(crate) fn to_hex(input: &[u8]) -> String {
-    let mut output = String::with_capacity(input.len() * 2);
-    for byte in input {
-        output.push_str(&format!("{byte:02x}"));
-    }
-    output
+pub(crate) fn idempotency_conflict(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::CONFLICT, ApiError::new(ErrorCode::IdempotencyConflict, message))
 }
 
-pub(crate) fn from_hex(input: &str) -> anyhow::Result<Vec<u8>> {
-    if input.len() % 2 != 0 {
-        anyhow::bail!("hex input length must be even");
-    }
+pub(crate) fn gateway_timeout(message: &str) -> (StatusCode, Json<ApiError>) {
+    error_response(StatusCode::GATEWAY_TIMEOUT, ApiError::new(ErrorCode::Timeout, message))
+}
 
-    let mut output = Vec::with_capacity(input.len() / 2);
-    let bytes = input.as_bytes();
-    let mut idx = 0;
-    while idx < bytes.len() {
-        let pair = std::str::from_utf8(&bytes[idx..idx + 2])?;
-        let value = u8::from_str_radix(pair, 16)?;
-        output.push(value);
-        idx += 2;
-    }
-    Ok(output)
+pub(crate) fn epoch_ms() -> anyhow::Result<u128> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis())
 }
 
 async fn fetch_jwks_from_url(client: &reqwest::Client, url: &str) -> Result<JwkSet, String> {
@@ -1269,13 +2231,19 @@ async fn fetch_jwks_from_url(client: &reqwest::Client, url: &str) -> Result<JwkS
         .map_err(|_| "invalid JWKS payload".to_owned())
 }
 
-fn build_app(state: AppState) -> Router {
-    let shared_state = Arc::new(state);
+fn build_app(state: impl Into<Arc<AppState>>) -> Router {
+    let shared_state = state.into();
 
     let cors = tower_http::cors::CorsLayer::new()
         .allow_origin(tower_http::cors::Any)
         .allow_methods(tower_http::cors::Any)
-        .allow_headers(tower_http::cors::Any);
+        .allow_headers(tower_http::cors::Any)
+        // Lets a cross-origin UI read the filename of an export download
+        // and the echoed client request id.
+        .expose_headers([
+            axum::http::header::CONTENT_DISPOSITION,
+            axum::http::HeaderName::from_static(kc_api_types::RequestMeta::CLIENT_REQUEST_ID_HEADER),
+        ]);
 
     Router::new()
         .route("/health", get(health))
@@ -1287,22 +2255,103 @@ fn build_app(state: AppState) -> Router {
         .route("/wallet/restore", post(wallet_restore))
         .route("/wallet/lookup", post(wallet_lookup))
         .route("/wallet/rename", post(wallet_rename))
+        .route("/wallet/delete", post(wallet_delete))
+        .route("/wallet/watch", post(watch::wallet_watch))
+        .route("/wallet/watch/batch", post(watch::wallet_watch_batch))
+        .route("/wallet/import-public", post(external::wallet_import_public))
         .route("/wallet/device-link", post(wallet_device_link))
         .route("/wallet/device-unlink", post(wallet_device_unlink))
         .route("/wallet/sign", post(wallet_sign))
+        .route("/wallet/session-key", post(session_keys::wallet_session_key))
+        .route("/wallet/rotate-key", post(key_rotation::wallet_rotate_key))
         .route("/wallet/submit", post(submit::wallet_submit))
+        .route("/wallet/submit-signed", post(submit::wallet_submit_signed))
+        .route(
+            "/wallet/escrow",
+            get(escrow::escrow_list).post(escrow::escrow_create),
+        )
+        .route("/wallet/escrow/{transfer_id}", get(escrow::escrow_status))
+        .route("/wallet/escrow/{transfer_id}/approve", post(escrow::escrow_approve))
+        .route("/wallet/escrow/{transfer_id}/cancel", post(escrow::escrow_cancel))
+        .route("/wallet/approvals", get(approvals::list_approvals))
+        .route("/wallet/approvals/{approval_id}/decide", post(approvals::decide_approval))
+        .route("/wallet/bridge", post(bridge::bridge_create))
+        .route("/wallet/bridge/{bridge_id}", get(bridge::bridge_status))
         .route("/wallet/nonce", get(submit::wallet_nonce))
+        .route("/wallet/fee-estimate", post(submit::wallet_fee_estimate))
+        .route(
+            "/wallet/{address}/nonce/reserve",
+            post(nonce_reservations::wallet_nonce_reserve),
+        )
         .route("/wallet/tx/{tx_hash}", get(submit::wallet_tx_status))
+        .route("/wallet/{address}/transactions", get(portfolio::wallet_tx_history))
+        .route("/wallet/{address}/decrypt", post(memo::wallet_decrypt_memo))
+        .route("/wallet/txs", get(portfolio::wallet_chain_txs))
         .route("/wallet/balance", get(wallet_balance))
+        .route("/v2/wallet/balance", get(versions::wallet_balance_v2))
+        .route("/v2/wallet/list", get(versions::wallet_list_v2))
+        .route(
+            "/v2/wallet/{address}/transactions",
+            get(versions::wallet_tx_history_v2),
+        )
+        .route("/v2/ops/audit", get(versions::ops_list_audit_v2))
+        .route("/wallet/balances", post(portfolio::wallet_balances))
+        .route("/wallet/portfolio", get(portfolio::wallet_portfolio))
+        .route("/wallet/activity", post(portfolio::wallet_activity))
+        .route(
+            "/wallet/activity/export",
+            get(portfolio::wallet_activity_export),
+        )
         .route("/auth/challenge", post(auth::auth_challenge))
         .route("/auth/verify", post(auth::auth_verify))
         .route("/auth/bind", post(auth::auth_bind))
+        .route("/auth/session/refresh", post(wallet_sessions::auth_session_refresh))
+        .route("/auth/sessions", get(sessions::auth_list_sessions))
+        .route(
+            "/auth/devices",
+            get(devices::auth_list_devices).post(devices::auth_register_device),
+        )
+        .route("/auth/devices/{device_id}/approve", post(devices::auth_approve_device))
+        .route("/auth/devices/{device_id}/revoke", post(devices::auth_revoke_device))
+        .route("/auth/sessions/{session_id}/revoke", post(sessions::auth_revoke_session))
         .route("/ops/bindings/{wallet_address}", get(ops::ops_get_binding))
         .route("/ops/audit", get(ops::ops_list_audit))
+        .route("/ops/audit/export", get(ops::ops_export_audit))
+        .route("/ops/health/history", get(ops::ops_health_history))
+        .route("/ops/storage", get(ops::ops_storage_stats))
+        .route("/ops/reports/dormant", get(reports::ops_dormant_report))
+        .route(
+            "/ops/approvals/notifications",
+            get(notify::ops_list_approval_notifications),
+        )
+        .route("/ops/events/publisher", get(publisher::ops_event_publisher_status))
+        .route("/ops/wallets/deleted", get(ops::ops_list_deleted_wallets))
+        .route("/ops/wallets/undelete", post(ops::ops_undelete_wallet))
+        .route(
+            "/ops/wallets/{wallet_address}/policy",
+            get(policy::ops_get_wallet_policy)
+                .put(policy::ops_set_wallet_policy)
+                .delete(policy::ops_delete_wallet_policy),
+        )
+        .route("/ops/bundle/export", get(bundle::ops_export_bundle))
+        .route("/ops/bundle/import", post(bundle::ops_import_bundle))
+        .route("/ops/demo/seed", post(demo::ops_demo_seed))
+        .route(
+            "/ops/honeytokens",
+            get(ops::ops_list_honeytokens).post(ops::ops_set_honeytoken),
+        )
+        .route("/ops/devices/{user_id}", get(ops::ops_list_user_devices))
+        .route("/ops/devices/approve", post(ops::ops_approve_device))
+        .route("/ops/chains", get(ops::ops_list_chains).post(ops::ops_upsert_chain))
         .route("/fortressdigital/context", post(fortressdigital_payload))
         .route("/fortressdigital/wallet-status", post(fortressdigital_wallet_status))
         .route("/proofcortex/commitment", post(proofcortex::proofcortex_commitment))
         .route("/chain/config", get(chain_config::chain_config))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::clone(&shared_state),
+            wallet_sessions::accept,
+        ))
+        .layer(axum::middleware::from_fn(request_meta::echo_client_request_id))
         .layer(cors)
         .with_state(shared_state)
 }
@@ -1326,13 +2375,15 @@ async fn fortressdigital_payload(
     let secret_key_bytes = STANDARD.decode(state.encryption_key.as_bytes()).unwrap_or_default();
     let signer = Ed25519Signer::from_secret_key_bytes(secret_key_bytes.try_into().unwrap_or([0u8; 32]));
     let payload = generate_context_payload(
-        &request.wallet_address,
-        &request.user_id,
-        &request.chain,
-        &request.session_id,
-        &request.context_data,
-        issued_at_epoch_ms,
-        expires_at_epoch_ms,
+        ContextPayloadParams {
+            wallet_address: &request.wallet_address,
+            user_id: &request.user_id,
+            chain: &request.chain,
+            session_id: &request.session_id,
+            context_data: &request.context_data,
+            issued_at_epoch_ms,
+            expires_at_epoch_ms,
+        },
         &signer,
     );
     Ok(Json(payload))
@@ -1344,15 +2395,13 @@ async fn fortressdigital_payload(
 ///   - wallet existence + key type
 ///   - binding status (bound to IdP user?)
 ///   - last verification timestamp
-///   - signing frequency hint
+///   - signature count, last sign/submit times and a frequency hint
 ///   - risk signal flags
 async fn fortressdigital_wallet_status(
     State(state): State<Arc<AppState>>,
     Json(request): Json<FortressDigitalWalletStatusRequest>,
 ) -> ApiResult<FortressDigitalWalletStatusResponse> {
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
 
     let now = epoch_ms().map_err(internal_error)?;
 
@@ -1361,7 +2410,8 @@ async fn fortressdigital_wallet_status(
         .load_encrypted_key(&request.wallet_address)
         .await
         .map_err(internal_error)?
-        .is_some();
+        .is_some()
+        || external::load_public_key(&state, &request.wallet_address)?.is_some();
 
     // Load binding from Postgres, fallback to RocksDB
     let binding = if let Some(repo) = &state.postgres_repo {
@@ -1382,19 +2432,19 @@ async fn fortressdigital_wallet_status(
             .map_err(internal_error)?
     };
 
-    // Count recent audit events for signing frequency hint
-    let audit_count = state
+    let usage = state
         .keystore
-        .list_audit_events(100, None, Some(&request.wallet_address), None)
+        .load_wallet_metadata(&request.wallet_address)
         .map_err(internal_error)?
-        .len();
+        .map(|metadata| metadata.usage)
+        .unwrap_or_default();
 
     let response = build_wallet_status(
         &request.wallet_address,
         &request.chain,
         wallet_exists,
         binding.as_ref(),
-        audit_count,
+        &usage,
         now,
     );
 
@@ -1410,6 +2460,7 @@ async fn fortressdigital_wallet_status(
             outcome: "success".to_owned(),
             message: Some(format!("risk_signals={}", response.risk_signals.len())),
             timestamp_epoch_ms: now,
+            tenant: None,
         },
     )
     .await;
@@ -1424,7 +2475,11 @@ mod tests {
     use axum::http::{HeaderValue, Method, Request};
     use jsonwebtoken::{EncodingKey, Header, encode};
     use kc_api_types::ChainId;
-    use kc_chain_client::{BalanceResult, SubmitTxRequest, SubmitTxResult, TxStatusRequest, TxStatusResult};
+    use kc_chain_client::{
+        BalanceResult, ChainAdapter, NonceStrategy, SubmitTxRequest, SubmitTxResult, TxStatusRequest,
+        TxStatusResult,
+    };
+    use kc_crypto::Signer;
     use serde_json::{Value, json};
     use tempfile::TempDir;
     use tower::util::ServiceExt;
@@ -1438,6 +2493,10 @@ mod tests {
             FLOWCORTEX_L1
         }
 
+        async fn health(&self) -> kc_chain_client::AdapterHealth {
+            kc_chain_client::AdapterHealth::measure(async { Ok(Some(7)) }).await
+        }
+
         async fn get_balance(
             &self,
             wallet_address: &WalletAddress,
@@ -1447,7 +2506,7 @@ mod tests {
                 wallet_address: wallet_address.clone(),
                 chain: ChainId(FLOWCORTEX_L1.to_owned()),
                 asset: asset.clone(),
-                amount: "0".to_owned(),
+                amount: kc_api_types::Amount::new(0, 0),
             })
         }
 
@@ -1475,10 +2534,92 @@ mod tests {
                 tx_hash: req.tx_hash,
                 status: "confirmed".to_owned(),
                 accepted: true,
+                block_height: Some(7),
+                confirmations: Some(3),
             })
         }
     }
 
+    /// [`MockChainAdapter`] that answers only after `delay`.
+    struct SlowChainAdapter {
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl ChainAdapter for SlowChainAdapter {
+        fn chain_id(&self) -> &str {
+            FLOWCORTEX_L1
+        }
+
+        async fn get_balance(
+            &self,
+            wallet_address: &WalletAddress,
+            asset: &AssetSymbol,
+        ) -> anyhow::Result<BalanceResult> {
+            tokio::time::sleep(self.delay).await;
+            MockChainAdapter.get_balance(wallet_address, asset).await
+        }
+
+        async fn submit_transaction(&self, req: SubmitTxRequest) -> anyhow::Result<SubmitTxResult> {
+            tokio::time::sleep(self.delay).await;
+            MockChainAdapter.submit_transaction(req).await
+        }
+
+        async fn get_transaction_status(
+            &self,
+            req: TxStatusRequest,
+        ) -> anyhow::Result<TxStatusResult> {
+            tokio::time::sleep(self.delay).await;
+            MockChainAdapter.get_transaction_status(req).await
+        }
+    }
+
+    /// [`MockChainAdapter`] with a configurable nonce model. Like a chain
+    /// counting pending transactions, each submit bumps the account nonce.
+    struct StrategyMockAdapter {
+        strategy: NonceStrategy,
+        account_nonce: Arc<std::sync::atomic::AtomicU64>,
+    }
+
+    #[async_trait::async_trait]
+    impl ChainAdapter for StrategyMockAdapter {
+        fn chain_id(&self) -> &str {
+            FLOWCORTEX_L1
+        }
+
+        fn nonce_strategy(&self) -> NonceStrategy {
+            self.strategy
+        }
+
+        async fn get_account_nonce(&self, _wallet_address: &WalletAddress) -> anyhow::Result<u64> {
+            Ok(self.account_nonce.load(std::sync::atomic::Ordering::SeqCst))
+        }
+
+        async fn get_recent_blockhash(&self) -> anyhow::Result<String> {
+            Ok("mock-blockhash".to_owned())
+        }
+
+        async fn get_balance(
+            &self,
+            wallet_address: &WalletAddress,
+            asset: &AssetSymbol,
+        ) -> anyhow::Result<BalanceResult> {
+            MockChainAdapter.get_balance(wallet_address, asset).await
+        }
+
+        async fn submit_transaction(&self, req: SubmitTxRequest) -> anyhow::Result<SubmitTxResult> {
+            self.account_nonce.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            MockChainAdapter.submit_transaction(req).await
+        }
+
+        async fn get_transaction_status(
+            &self,
+            req: TxStatusRequest,
+        ) -> anyhow::Result<TxStatusResult> {
+            MockChainAdapter.get_transaction_status(req).await
+        }
+    }
+
     fn test_state(temp_dir: &TempDir) -> AppState {
         let keystore = RocksDbKeystore::open_default(
             temp_dir
@@ -1489,8 +2630,21 @@ mod tests {
         )
         .expect("rocksdb should initialize");
 
+        let keystore = Arc::new(keystore);
+        let challenges = Arc::new(Challenges::new(keystore.clone()));
+        let encryption_key = Arc::<str>::from("test-master-key");
+        let signing_domain = SigningDomain::default()
+            .with_custom_purposes(["delegation"])
+            .expect("custom purpose");
         AppState {
-            keystore: Arc::new(keystore),
+            wallet_core: custody_core(&keystore, &encryption_key, &signing_domain, keystore.clone()),
+            nonces: Arc::new(NonceManager::new(
+                Arc::new(InMemoryNonceStore::default()),
+                keystore.clone(),
+            )),
+            approvals: Arc::new(ApprovalQueue::new(keystore.clone())),
+            submissions: Arc::new(SubmissionTracker::new(keystore.clone(), Default::default())),
+            keystore,
             postgres_repo: None,
             db_fallback_counters: Arc::new(DbFallbackCounters::default()),
             postgres_startup: Arc::new(StdRwLock::new(PostgresStartupReport {
@@ -1500,7 +2654,13 @@ mod tests {
                 migration_files_applied: 0,
                 last_error: None,
             })),
-            encryption_key: Arc::<str>::from("test-master-key"),
+            encryption_key,
+            signing_domain,
+            sign_in_origin: Arc::from(DEFAULT_SIGN_IN_ORIGIN),
+            wallet_sessions: wallet_sessions::WalletSessions::new(
+                b"test-wallet-session-secret",
+                Duration::from_secs(15 * 60),
+            ),
             authbuddy_jwt_secret: Arc::<str>::from("test-auth-secret"),
             authbuddy_jwks: Arc::new(StdRwLock::new(None)),
             jwks_status: Arc::new(StdRwLock::new(JwksRuntimeStatus {
@@ -1511,11 +2671,28 @@ mod tests {
             })),
             authbuddy_expected_issuer: None,
             authbuddy_expected_audience: None,
-            challenge_store: Arc::new(TokioRwLock::new(HashMap::new())),
-            submit_idempotency_cache: Arc::new(TokioRwLock::new(HashMap::new())),
-            submit_nonce_state: Arc::new(TokioRwLock::new(HashMap::new())),
+            challenges,
+            challenge_mirror: None,
+            submit_idempotency_cache: Arc::new(InMemoryIdempotencyStore::default()),
             authbuddy_callback: None,
-            chain_adapter: Arc::new(MockChainAdapter),
+            chains: Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+                "http://127.0.0.1:9",
+                Arc::new(MockChainAdapter),
+            ))),
+            honeytoken_alert_url: None,
+            approval_notifier: None,
+            event_publisher: None,
+            webhook_notifier: None,
+            trusted_device_submit_threshold: None,
+            min_passphrase_entropy_bits: DEFAULT_MIN_PASSPHRASE_BITS,
+            kms_keys: Arc::new(KmsKeyRegistry::default()),
+            bridge_wallets: HashMap::new(),
+            chaos: None,
+            health_history: Arc::new(health_history::HealthHistory::new(Duration::from_secs(60), 3)),
+            demo_ledger: None,
+            request_timeout: deadline::DEFAULT_REQUEST_TIMEOUT,
+            wallet_undelete_grace: Duration::from_secs(DEFAULT_WALLET_UNDELETE_GRACE_DAYS * 24 * 60 * 60),
+            wallet_quota: None,
         }
     }
 
@@ -1595,6 +2772,34 @@ mod tests {
         .expect("token should encode")
     }
 
+    fn build_session_token(secret: &str, sub: &str, jti: &str) -> String {
+        #[derive(serde::Serialize)]
+        struct Claims<'a> {
+            sub: &'a str,
+            exp: u64,
+            jti: &'a str,
+            role: &'a str,
+        }
+
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should be monotonic")
+            .as_secs()
+            + 3600;
+
+        encode(
+            &Header::default(),
+            &Claims {
+                sub,
+                exp,
+                jti,
+                role: "user",
+            },
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .expect("token should encode")
+    }
+
     #[tokio::test]
     async fn wallet_create_and_sign_contract_fields_are_present() {
         let temp_dir = TempDir::new().expect("temp dir should create");
@@ -1606,6 +2811,13 @@ mod tests {
         let wallet_address = create_body["wallet_address"]
             .as_str()
             .expect("wallet_address should be string");
+        let expected_fingerprint =
+            fingerprint::of_hex(create_body["public_key"].as_str().expect("public_key should be string"))
+                .expect("public key should be hex");
+        assert_eq!(create_body["key_fingerprint"]["words"], expected_fingerprint.words);
+        assert_eq!(create_body["key_fingerprint"]["code"], expected_fingerprint.code);
+        let (_, list_body) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(list_body["wallets"][0]["key_fingerprint"], create_body["key_fingerprint"]);
 
         let payload_b64 = base64::engine::general_purpose::STANDARD.encode("hello-sign");
         let (sign_status, sign_body) = send_json(
@@ -1700,10 +2912,264 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn wallet_submit_nonce_and_idempotency_contract() {
+    async fn auth_verify_accepts_a_signed_sign_in_message() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"].as_str().expect("address").to_owned();
+
+        let (challenge_status, challenge_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/challenge",
+            json!({ "wallet_address": wallet_address }),
+            vec![],
+        )
+        .await;
+        assert_eq!(challenge_status, StatusCode::OK, "{challenge_body}");
+        let challenge = challenge_body["challenge"].as_str().expect("challenge").to_owned();
+        let message = challenge_body["message"].as_str().expect("sign-in message").to_owned();
+        let parsed = kc_auth_adapter::SignInMessage::parse(&message).expect("message parses");
+        assert_eq!(parsed.domain, "localhost:8081");
+        assert_eq!(parsed.wallet_address, wallet_address);
+        assert_eq!(parsed.nonce, challenge);
+
+        let sign = |payload: String| {
+            send_json(
+                &app,
+                Method::POST,
+                "/wallet/sign",
+                json!({
+                    "wallet_address": wallet_address,
+                    "payload": base64::engine::general_purpose::STANDARD.encode(payload.as_bytes()),
+                    "purpose": "auth"
+                }),
+                vec![],
+            )
+        };
+        let verify = |signature: &Value, message: &str| {
+            send_json(
+                &app,
+                Method::POST,
+                "/auth/verify",
+                json!({
+                    "wallet_address": wallet_address,
+                    "signature": signature,
+                    "challenge": challenge,
+                    "message": message
+                }),
+                vec![],
+            )
+        };
+
+        let elsewhere = message.replace("localhost:8081", "evil.example");
+        let (_, forged) = sign(elsewhere.clone()).await;
+        let (status, body) = verify(&forged["signature"], &elsewhere).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid sign-in message: message is for another domain");
+
+        let (_, signed) = sign(message.clone()).await;
+        let (status, body) = verify(&signed["signature"], &message).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["valid"], true);
+
+        let (_, bare) = send_empty(&app, Method::POST, "/auth/challenge").await;
+        assert!(bare.get("message").is_none(), "no wallet, no message: {bare}");
+    }
+
+    #[tokio::test]
+    async fn wallet_session_from_verify_scopes_wallet_requests() {
         let temp_dir = TempDir::new().expect("temp dir should create");
         let app = build_app(test_state(&temp_dir));
 
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"].as_str().expect("address").to_owned();
+        let (_, other_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let other_wallet = other_body["wallet_address"].as_str().expect("address").to_owned();
+
+        let (_, challenge_body) = send_empty(&app, Method::POST, "/auth/challenge").await;
+        let challenge = challenge_body["challenge"].as_str().expect("challenge").to_owned();
+        let sign = |wallet: String, payload: String, headers: Vec<(&'static str, HeaderValue)>| {
+            send_json(
+                &app,
+                Method::POST,
+                "/wallet/sign",
+                json!({
+                    "wallet_address": wallet,
+                    "payload": base64::engine::general_purpose::STANDARD.encode(payload.as_bytes()),
+                    "purpose": "auth"
+                }),
+                headers,
+            )
+        };
+        let (_, signed) = sign(wallet_address.clone(), challenge.clone(), vec![]).await;
+        let (verify_status, verify_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/verify",
+            json!({
+                "wallet_address": wallet_address,
+                "signature": signed["signature"],
+                "challenge": challenge
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(verify_status, StatusCode::OK, "{verify_body}");
+        let session = &verify_body["session"];
+        assert_eq!(session["wallet_address"], wallet_address.as_str());
+        assert_eq!(session["verified_at_epoch_ms"], verify_body["verified_at_epoch_ms"]);
+        let token = session["token"].as_str().expect("session token").to_owned();
+        let header = || vec![("x-wallet-session", HeaderValue::from_str(&token).expect("header"))];
+
+        let (status, body) = sign(wallet_address.clone(), "hello".to_owned(), header()).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let (status, body) = sign(other_wallet, "hello".to_owned(), header()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"], "wallet session is for another wallet");
+
+        let (status, body) = sign(
+            wallet_address.clone(),
+            "hello".to_owned(),
+            vec![("x-wallet-session", HeaderValue::from_static("not-a-token"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "invalid wallet session");
+
+        let (status, refreshed) =
+            send_json(&app, Method::POST, "/auth/session/refresh", json!({}), header()).await;
+        assert_eq!(status, StatusCode::OK, "{refreshed}");
+        assert_eq!(refreshed["wallet_address"], wallet_address.as_str());
+        assert_eq!(refreshed["verified_at_epoch_ms"], verify_body["verified_at_epoch_ms"]);
+
+        let (status, _) = send_empty(&app, Method::POST, "/auth/session/refresh").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn revoked_wallet_session_is_refused() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let sessions = state.wallet_sessions.clone();
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"].as_str().expect("address").to_owned();
+        let bearer = || {
+            let token = build_hs256_token("test-auth-secret", "alice");
+            vec![("authorization", HeaderValue::from_str(&format!("Bearer {token}")).expect("header"))]
+        };
+        let (bind_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/auth/bind",
+            json!({ "wallet_address": wallet_address, "chain": "flowcortex-l1" }),
+            bearer(),
+        )
+        .await;
+        assert_eq!(bind_status, StatusCode::OK);
+
+        let now = epoch_ms().expect("clock should work");
+        let session = sessions.issue(&keystore, &wallet_address, now, now).expect("session should issue");
+        let header = || vec![("x-wallet-session", HeaderValue::from_str(&session.token).expect("header"))];
+        let rename = json!({ "wallet_address": wallet_address, "label": "savings" });
+        let (status, body) = send_json(&app, Method::POST, "/wallet/rename", rename.clone(), header()).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let (_, listed) = send_json(&app, Method::GET, "/auth/sessions", json!({}), bearer()).await;
+        assert!(
+            listed["sessions"]
+                .as_array()
+                .expect("sessions")
+                .iter()
+                .any(|listed| listed["session_id"] == session.session_id.as_str()),
+            "{listed}"
+        );
+        let revoke_uri = format!("/auth/sessions/{}/revoke", session.session_id);
+        let (status, body) = send_json(&app, Method::POST, &revoke_uri, json!({}), bearer()).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let (status, body) = send_json(&app, Method::POST, "/wallet/rename", rename.clone(), header()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "wallet session revoked");
+        let (status, _) =
+            send_json(&app, Method::POST, "/auth/session/refresh", json!({}), header()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Signed with the right secret but never issued by this service.
+        let other_dir = TempDir::new().expect("temp dir should create");
+        let other_keystore = Arc::clone(&test_state(&other_dir).keystore);
+        let forged = sessions
+            .issue(&other_keystore, &wallet_address, now, now)
+            .expect("session should issue");
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rename",
+            rename,
+            vec![("x-wallet-session", HeaderValue::from_str(&forged.token).expect("header"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "unknown wallet session");
+    }
+
+    #[tokio::test]
+    async fn required_wallet_session_guards_every_wallet_route() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        state.wallet_sessions = state.wallet_sessions.clone().required();
+        let sessions = state.wallet_sessions.clone();
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"].as_str().expect("address").to_owned();
+        let (_, other_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let other_wallet = other_body["wallet_address"].as_str().expect("address").to_owned();
+        let now = epoch_ms().expect("clock should work");
+        let token = |wallet: &str| {
+            let issued = sessions.issue(&keystore, wallet, now, now).expect("session should issue");
+            vec![("x-wallet-session", HeaderValue::from_str(&issued.token).expect("header"))]
+        };
+
+        let rename = json!({ "wallet_address": wallet_address, "label": "savings" });
+        let reserve_uri = format!("/wallet/{wallet_address}/nonce/reserve");
+        let rotate = json!({ "wallet_address": wallet_address });
+        let device = json!({ "wallet_address": wallet_address, "device_id": "phone-1" });
+        for (method, uri, body) in [
+            (Method::POST, "/wallet/rename", rename.clone()),
+            (Method::POST, reserve_uri.as_str(), json!({})),
+            (Method::POST, "/wallet/rotate-key", rotate),
+            (Method::POST, "/wallet/device-link", device),
+        ] {
+            let (status, body) = send_json(&app, method, uri, body, vec![]).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{uri}: {body}");
+            assert_eq!(body["error"], "wallet session required", "{uri}");
+        }
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/rename", rename.clone(), token(&other_wallet)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{body}");
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/rename", rename, token(&wallet_address)).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let (status, body) =
+            send_json(&app, Method::POST, &reserve_uri, json!({}), token(&wallet_address)).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+
+    #[tokio::test]
+    async fn wallet_submit_nonce_and_idempotency_contract() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let idempotency = state.submit_idempotency_cache.clone();
+        let app = build_app(state);
+
         let (create_status, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
         assert_eq!(create_status, StatusCode::OK);
         let wallet_address = create_body["wallet_address"]
@@ -1713,7 +3179,7 @@ mod tests {
 
         let submit_body = json!({
             "from": wallet_address,
-            "to": "0xdeadbeef",
+            "to": "0x00000000000000000000000000000000deadbeef",
             "amount": "1000",
             "asset": "FloweR",
             "chain": "flowcortex-l1",
@@ -1742,6 +3208,32 @@ mod tests {
         assert_eq!(submit_replay_status, StatusCode::OK);
         assert_eq!(submit_replay_response, submit_response);
 
+        // A retry while a request holding the key is in flight is turned away.
+        assert!(
+            idempotency
+                .claim("idem-2", Duration::from_secs(60))
+                .await
+                .expect("claim should succeed")
+        );
+        let (in_flight_status, in_flight_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": "1000",
+                "asset": "FloweR",
+                "chain": "flowcortex-l1",
+                "nonce": 2
+            }),
+            vec![("idempotency-key", HeaderValue::from_static("idem-2"))],
+        )
+        .await;
+        assert_eq!(in_flight_status, StatusCode::CONFLICT);
+        assert_eq!(in_flight_body["code"], "idempotency_conflict");
+        assert_eq!(in_flight_body["retryable"], true);
+
         let tx_hash = submit_response["tx_hash"]
             .as_str()
             .expect("tx_hash should be string")
@@ -1810,4 +3302,4710 @@ mod tests {
         assert_eq!(bind_body["chain"], "flowcortex-l1");
         assert!(bind_body.get("bound_at_epoch_ms").is_some());
     }
+
+    #[tokio::test]
+    async fn honeytoken_wallet_is_hidden_and_access_is_audited() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"]
+            .as_str()
+            .expect("wallet_address should be string")
+            .to_owned();
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+
+        let (flag_status, flag_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/honeytokens",
+            json!({ "wallet_address": wallet_address, "honeytoken": true }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(flag_status, StatusCode::OK);
+        assert_eq!(flag_body["honeytoken"], true);
+
+        let (_, list_body) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(list_body["total"], 0);
+
+        let (sign_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({
+                "wallet_address": wallet_address,
+                "payload": base64::engine::general_purpose::STANDARD.encode("bait"),
+                "purpose": "transaction"
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(sign_status, StatusCode::OK);
+
+        let (audit_status, audit_body) = send_json(
+            &app,
+            Method::GET,
+            "/ops/audit?event_type=honeytoken_access",
+            json!({}),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(audit_status, StatusCode::OK);
+        let events = audit_body["events"].as_array().expect("events should be array");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["outcome"], "critical");
+        assert_eq!(events[0]["wallet_address"], wallet_address);
+    }
+
+    #[tokio::test]
+    async fn revoked_session_token_is_rejected() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+
+        let token = build_session_token("test-auth-secret", "user-123", "sess-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+
+        let (list_status, list_body) = send_json(
+            &app,
+            Method::GET,
+            "/auth/sessions",
+            json!({}),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(list_status, StatusCode::OK);
+        assert_eq!(list_body["user_id"], "user-123");
+        assert_eq!(list_body["total"], 1);
+        assert_eq!(list_body["sessions"][0]["session_id"], "sess-1");
+        assert_eq!(list_body["sessions"][0]["revoked"], false);
+
+        let other = build_session_token("test-auth-secret", "user-456", "sess-2");
+        let other_value = HeaderValue::from_str(&format!("Bearer {other}"))
+            .expect("authorization header should build");
+        let (foreign_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/auth/sessions/sess-1/revoke",
+            json!({}),
+            vec![("authorization", other_value)],
+        )
+        .await;
+        assert_eq!(foreign_status, StatusCode::UNAUTHORIZED);
+
+        let (revoke_status, revoke_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/sessions/sess-1/revoke",
+            json!({}),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(revoke_status, StatusCode::OK);
+        assert_eq!(revoke_body["revoked"], true);
+
+        let (after_status, after_body) = send_json(
+            &app,
+            Method::GET,
+            "/auth/sessions",
+            json!({}),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(after_status, StatusCode::UNAUTHORIZED);
+        assert_eq!(after_body["error"], "session revoked");
+
+        // A token without a jti is a session keyed by its hash, and can be
+        // revoked like any other.
+        let bare = build_hs256_token("test-auth-secret", "user-789");
+        let bare_value =
+            HeaderValue::from_str(&format!("Bearer {bare}")).expect("authorization header should build");
+        let bare_id = to_hex(&<sha2::Sha256 as sha2::Digest>::digest(bare.as_bytes()));
+        let (_, list_body) = send_json(
+            &app,
+            Method::GET,
+            "/auth/sessions",
+            json!({}),
+            vec![("authorization", bare_value.clone())],
+        )
+        .await;
+        assert_eq!(list_body["sessions"][0]["session_id"], bare_id);
+        let (revoke_status, _) = send_json(
+            &app,
+            Method::POST,
+            &format!("/auth/sessions/{bare_id}/revoke"),
+            json!({}),
+            vec![("authorization", bare_value.clone())],
+        )
+        .await;
+        assert_eq!(revoke_status, StatusCode::OK);
+        let (after_status, _) = send_json(
+            &app,
+            Method::GET,
+            "/auth/sessions",
+            json!({}),
+            vec![("authorization", bare_value)],
+        )
+        .await;
+        assert_eq!(after_status, StatusCode::UNAUTHORIZED);
+
+        // Sessions are pruned once their token expires.
+        assert_eq!(keystore.prune_expired_sessions(0).expect("prune should run"), 0);
+        assert_eq!(keystore.prune_expired_sessions(u128::MAX).expect("prune should run"), 3);
+        assert!(keystore.list_user_sessions("user-123").expect("sessions should list").is_empty());
+    }
+
+    #[tokio::test]
+    async fn large_submit_requires_trusted_device() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        state.trusted_device_submit_threshold = Some(100);
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"]
+            .as_str()
+            .expect("wallet_address should be string")
+            .to_owned();
+
+        let token = build_hs256_token("test-auth-secret", "user-123");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+
+        let (bind_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/auth/bind",
+            json!({ "wallet_address": wallet_address, "chain": "flowcortex-l1" }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(bind_status, StatusCode::OK);
+
+        let (first_status, first_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/devices",
+            json!({ "device_id": "dev-a", "label": "Laptop" }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(first_status, StatusCode::OK);
+        assert_eq!(first_body["trusted"], true);
+        assert_eq!(first_body["approved_via"], "first-device");
+
+        let device_key = kc_crypto::Ed25519Signer::new_random();
+        let (second_status, second_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/devices",
+            json!({ "device_id": "dev-b", "public_key": device_key.public_key_hex() }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(second_status, StatusCode::OK);
+        assert_eq!(second_body["trusted"], false);
+
+        let to = "0x000000000000000000000000000000000000cafe";
+        let submit_body = json!({
+            "from": wallet_address,
+            "to": to,
+            "amount": "1000",
+            "asset": "PROOF",
+            "chain": "flowcortex-l1",
+            "nonce": 1
+        });
+        let proof = |signer: &kc_crypto::Ed25519Signer, token: &str| {
+            let timestamp = epoch_ms().expect("clock should work");
+            let payload = devices::device_proof_payload("dev-b", &wallet_address, to, "1000", timestamp);
+            let signature = signer
+                .sign_in_domain(&SigningDomain::default(), payload.as_bytes(), kc_api_types::SignPurpose::Auth)
+                .expect("device should sign");
+            vec![
+                ("x-device-id", HeaderValue::from_static("dev-b")),
+                ("x-device-timestamp", HeaderValue::from_str(&timestamp.to_string()).expect("header")),
+                ("x-device-signature", HeaderValue::from_str(&to_hex(&signature)).expect("header")),
+                ("authorization", HeaderValue::from_str(&format!("Bearer {token}")).expect("header")),
+            ]
+        };
+
+        let (untrusted_status, _) =
+            send_json(&app, Method::POST, "/wallet/submit", submit_body.clone(), proof(&device_key, &token)).await;
+        assert_eq!(untrusted_status, StatusCode::UNAUTHORIZED);
+
+        let (approve_status, approve_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/devices/approve",
+            json!({ "user_id": "user-123", "device_id": "dev-b" }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(approve_status, StatusCode::OK);
+        assert_eq!(approve_body["trusted"], true);
+
+        // The device id alone, another user's token or another key's
+        // signature do not pass for the trusted device.
+        let (bare_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            submit_body.clone(),
+            vec![("x-device-id", HeaderValue::from_static("dev-b"))],
+        )
+        .await;
+        assert_eq!(bare_status, StatusCode::UNAUTHORIZED);
+        let intruder = build_hs256_token("test-auth-secret", "user-456");
+        let (intruder_status, _) =
+            send_json(&app, Method::POST, "/wallet/submit", submit_body.clone(), proof(&device_key, &intruder)).await;
+        assert_eq!(intruder_status, StatusCode::FORBIDDEN);
+        let other_key = kc_crypto::Ed25519Signer::new_random();
+        let (forged_status, forged_body) =
+            send_json(&app, Method::POST, "/wallet/submit", submit_body.clone(), proof(&other_key, &token)).await;
+        assert_eq!(forged_status, StatusCode::UNAUTHORIZED);
+        assert_eq!(forged_body["error"], "device signature is invalid");
+
+        let (trusted_status, trusted_body) =
+            send_json(&app, Method::POST, "/wallet/submit", submit_body, proof(&device_key, &token)).await;
+        assert_eq!(trusted_status, StatusCode::OK, "{trusted_body}");
+        assert_eq!(trusted_body["accepted"], true);
+
+        let (list_status, list_body) = send_json(
+            &app,
+            Method::GET,
+            "/auth/devices",
+            json!({}),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(list_status, StatusCode::OK);
+        assert_eq!(list_body["total"], 2);
+
+        // With every device revoked, a new one is not trusted on sight.
+        for device_id in ["dev-a", "dev-b"] {
+            let (revoke_status, _) = send_json(
+                &app,
+                Method::POST,
+                &format!("/auth/devices/{device_id}/revoke"),
+                json!({}),
+                vec![("authorization", auth_value.clone())],
+            )
+            .await;
+            assert_eq!(revoke_status, StatusCode::OK);
+        }
+        let (_, fresh_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/devices",
+            json!({ "device_id": "dev-c" }),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(fresh_body["trusted"], false);
+    }
+
+    #[tokio::test]
+    async fn rotated_wallet_keeps_address_and_signs_with_new_key() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"]
+            .as_str()
+            .expect("wallet_address should be string")
+            .to_owned();
+
+        let (unauth_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rotate-key",
+            json!({ "wallet_address": wallet_address }),
+            vec![],
+        )
+        .await;
+        assert_eq!(unauth_status, StatusCode::UNAUTHORIZED);
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+
+        let (rotate_status, rotate_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rotate-key",
+            json!({ "wallet_address": wallet_address }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(rotate_status, StatusCode::OK);
+        assert_eq!(rotate_body["key_version"], 2);
+        assert_eq!(rotate_body["previous_public_key"], create_body["public_key"]);
+        assert_ne!(rotate_body["public_key"], create_body["public_key"]);
+        assert!(rotate_body["linkage_signature"].as_str().is_some_and(|s| !s.is_empty()));
+
+        let (submit_status, submit_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "10",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(submit_status, StatusCode::OK);
+        assert_eq!(submit_body["accepted"], true);
+
+        let (second_status, second_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rotate-key",
+            json!({ "wallet_address": wallet_address }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(second_status, StatusCode::OK);
+        assert_eq!(second_body["key_version"], 3);
+        assert_eq!(second_body["previous_public_key"], rotate_body["public_key"]);
+
+        // A rotation that read generation 1 before the others committed
+        // writes nothing.
+        let history = keystore
+            .list_wallet_key_history(&wallet_address)
+            .expect("history should list");
+        assert_eq!(history.len(), 3);
+        let stale = keystore
+            .rotate_wallet_key(&history[0], &history[1])
+            .expect("rotation should run");
+        assert!(!stale);
+        assert_eq!(
+            keystore.load_active_key_version(&wallet_address).expect("version should load"),
+            Some(3)
+        );
+
+        let (audit_status, audit_body) = send_json(
+            &app,
+            Method::GET,
+            "/ops/audit?event_type=wallet_rotate_key",
+            json!({}),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(audit_status, StatusCode::OK);
+        assert_eq!(audit_body["events"].as_array().map(Vec::len), Some(2));
+    }
+
+
+    #[tokio::test]
+    async fn escrow_transfer_releases_on_approver_signature() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let mut wallets = Vec::new();
+        for _ in 0..2 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            wallets.push(
+                body["wallet_address"]
+                    .as_str()
+                    .expect("wallet_address should be string")
+                    .to_owned(),
+            );
+        }
+        let (sender, approver) = (&wallets[0], &wallets[1]);
+
+        let create = |expires: u64| {
+            json!({
+                "from": sender,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "25",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "approver_wallet": approver,
+                "expires_in_seconds": expires
+            })
+        };
+
+        let (create_status, escrow) =
+            send_json(&app, Method::POST, "/wallet/escrow", create(600), vec![]).await;
+        assert_eq!(create_status, StatusCode::OK);
+        assert_eq!(escrow["status"], "pending");
+        let transfer_id = escrow["transfer_id"].as_str().expect("transfer_id").to_owned();
+        let digest = escrow["digest"].as_str().expect("digest").to_owned();
+
+        let (_, tx_body) = send_empty(&app, Method::GET, &format!("/wallet/tx/{transfer_id}")).await;
+        assert_eq!(tx_body["status"], "escrow_pending");
+
+        let inbox = format!("/wallet/escrow?approver_wallet={approver}&status=pending");
+        let (_, waiting) = send_empty(&app, Method::GET, &inbox).await;
+        assert_eq!(waiting["total"], 1);
+        assert_eq!(waiting["transfers"][0]["transfer_id"], transfer_id.as_str());
+        let (_, not_mine) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/escrow?approver_wallet={sender}&status=pending"),
+        )
+        .await;
+        assert_eq!(not_mine["total"], 0);
+        let (no_approver_status, _) = send_empty(&app, Method::GET, "/wallet/escrow").await;
+        assert_eq!(no_approver_status, StatusCode::BAD_REQUEST);
+
+        // A signature from the sender does not release the transfer.
+        let digest_b64 = base64::engine::general_purpose::STANDARD.encode(digest.as_bytes());
+        let (_, sender_sig) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": sender, "payload": digest_b64, "purpose": "transaction" }),
+            vec![],
+        )
+        .await;
+        let (wrong_status, _) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/escrow/{transfer_id}/approve"),
+            json!({ "signature": sender_sig["signature"] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(wrong_status, StatusCode::UNAUTHORIZED);
+
+        let (_, approver_sig) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": approver, "payload": digest_b64, "purpose": "transaction" }),
+            vec![],
+        )
+        .await;
+        // Of two approvals racing, only the one that claims the transfer submits it.
+        let approve_uri = format!("/wallet/escrow/{transfer_id}/approve");
+        let approve_body = json!({ "signature": approver_sig["signature"] });
+        let (first, second) = tokio::join!(
+            send_json(&app, Method::POST, &approve_uri, approve_body.clone(), vec![]),
+            send_json(&app, Method::POST, &approve_uri, approve_body.clone(), vec![]),
+        );
+        let mut statuses = [first.0, second.0];
+        statuses.sort();
+        assert_eq!(statuses, [StatusCode::OK, StatusCode::BAD_REQUEST]);
+        let released = if first.0 == StatusCode::OK { first.1 } else { second.1 };
+        assert_eq!(released["status"], "released");
+        assert!(released["tx_hash"].as_str().is_some_and(|h| !h.is_empty()));
+
+        let (_, tx_body) = send_empty(&app, Method::GET, &format!("/wallet/tx/{transfer_id}")).await;
+        assert_eq!(tx_body["status"], "escrow_released");
+        let (_, waiting) = send_empty(&app, Method::GET, &inbox).await;
+        assert_eq!(waiting["total"], 0);
+
+        let (again_status, _) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/escrow/{transfer_id}/approve"),
+            json!({ "signature": approver_sig["signature"] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(again_status, StatusCode::BAD_REQUEST);
+
+        let (_, second) = send_json(&app, Method::POST, "/wallet/escrow", create(600), vec![]).await;
+        let second_id = second["transfer_id"].as_str().expect("transfer_id").to_owned();
+        let cancel_b64 = base64::engine::general_purpose::STANDARD
+            .encode(format!("cancel:{}", second["digest"].as_str().expect("digest")));
+        let (_, cancel_sig) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": sender, "payload": cancel_b64, "purpose": "transaction" }),
+            vec![],
+        )
+        .await;
+        let (cancel_status, cancelled) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/escrow/{second_id}/cancel"),
+            json!({ "wallet_address": sender, "signature": cancel_sig["signature"] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(cancel_status, StatusCode::OK);
+        assert_eq!(cancelled["status"], "cancelled");
+
+        let mut fractional = create(600);
+        fractional["amount"] = json!("25.5");
+        let (status, body) = send_json(&app, Method::POST, "/wallet/escrow", fractional, vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "amount must be a non-negative integer");
+    }
+
+
+    #[tokio::test]
+    async fn watch_only_wallet_lists_but_cannot_sign_or_submit() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (watch_status, watch_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/watch",
+            json!({ "wallet_address": "0x0000000000000000000000000000000000000a7c", "label": "Cold storage" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(watch_status, StatusCode::OK);
+        assert_eq!(watch_body["custodied"], false);
+
+        let (_, list_body) = send_empty(&app, Method::GET, "/wallet/list").await;
+        let watched = list_body["wallets"]
+            .as_array()
+            .expect("wallets should be array")
+            .iter()
+            .find(|w| w["wallet_address"] == "0x0000000000000000000000000000000000000a7c")
+            .expect("watch-only wallet should be listed");
+        assert_eq!(watched["custodied"], false);
+        assert_eq!(watched["label"], "Cold storage");
+
+        let (balance_status, _) =
+            send_empty(&app, Method::GET, "/wallet/balance?wallet_address=0x0000000000000000000000000000000000000a7c").await;
+        assert_eq!(balance_status, StatusCode::OK);
+
+        let payload_b64 = base64::engine::general_purpose::STANDARD.encode("hello");
+        let (sign_status, sign_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": "0x0000000000000000000000000000000000000a7c", "payload": payload_b64, "purpose": "proof" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(sign_status, StatusCode::FORBIDDEN);
+        assert!(sign_body["error"].as_str().is_some_and(|e| e.contains("watch-only")));
+
+        let (submit_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": "0x0000000000000000000000000000000000000a7c",
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "1",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(submit_status, StatusCode::FORBIDDEN);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let (dup_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/watch",
+            json!({ "wallet_address": create_body["wallet_address"] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(dup_status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn external_key_wallet_requires_signed_payload_to_submit() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let device_key = Ed25519Signer::new_random();
+        let wallet_address = device_key.wallet_address();
+
+        let (import_status, import_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/import-public",
+            json!({ "public_key": device_key.public_key_hex(), "label": "Ledger" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(import_status, StatusCode::OK);
+        assert_eq!(import_body["wallet_address"], wallet_address);
+        assert_eq!(import_body["external_key"], true);
+        assert_eq!(import_body["custodied"], false);
+
+        let (_, list_body) = send_empty(&app, Method::GET, "/wallet/list").await;
+        let listed = list_body["wallets"]
+            .as_array()
+            .expect("wallets should be array")
+            .iter()
+            .find(|w| w["wallet_address"] == wallet_address)
+            .expect("external-key wallet should be listed");
+        assert_eq!(listed["public_key"], device_key.public_key_hex());
+
+        let payload_b64 = base64::engine::general_purpose::STANDARD.encode("hello");
+        let (sign_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": wallet_address, "payload": payload_b64, "purpose": "proof" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(sign_status, StatusCode::FORBIDDEN);
+
+        let (_, challenge_body) = send_empty(&app, Method::POST, "/auth/challenge").await;
+        let challenge = challenge_body["challenge"].as_str().expect("challenge").to_owned();
+        let auth_signature = device_key
+            .sign_in_domain(&SigningDomain::default(), challenge.as_bytes(), kc_api_types::SignPurpose::Auth)
+            .expect("auth sign");
+        let (verify_status, verify_body) = send_json(
+            &app,
+            Method::POST,
+            "/auth/verify",
+            json!({
+                "wallet_address": wallet_address,
+                "challenge": challenge,
+                "signature": to_hex(&auth_signature)
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(verify_status, StatusCode::OK);
+        assert_eq!(verify_body["valid"], true);
+
+        let mut transfer = json!({
+            "from": wallet_address,
+            "to": "0x000000000000000000000000000000000000cafe",
+            "amount": "1",
+            "asset": "PROOF",
+            "chain": "flowcortex-l1",
+            "nonce": 1
+        });
+        let (unsigned_status, _) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer.clone(), vec![]).await;
+        assert_eq!(unsigned_status, StatusCode::BAD_REQUEST);
+
+        let canonical = format!("from={wallet_address};to=0x000000000000000000000000000000000000cafe;amount=1;asset=PROOF;chain=flowcortex-l1;nonce=1");
+        let tx_signature = device_key
+            .sign_in_domain(&SigningDomain::default(), canonical.as_bytes(), kc_api_types::SignPurpose::Transaction)
+            .expect("tx sign");
+
+        transfer["signed_payload"] = json!(to_hex(&auth_signature));
+        let (forged_status, _) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer.clone(), vec![]).await;
+        assert_eq!(forged_status, StatusCode::UNAUTHORIZED);
+
+        transfer["signed_payload"] = json!(to_hex(&tx_signature));
+        let (submit_status, submit_body) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer, vec![]).await;
+        assert_eq!(submit_status, StatusCode::OK);
+        assert_eq!(submit_body["signature"], to_hex(&tx_signature));
+
+        let (nonce_status, nonce_body) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/nonce?wallet_address={wallet_address}"),
+        )
+        .await;
+        assert_eq!(nonce_status, StatusCode::OK);
+        assert_eq!(nonce_body["next_nonce"], 2);
+    }
+
+    #[tokio::test]
+    async fn submit_signed_verifies_signature_key_and_nonce() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let client_key = Ed25519Signer::new_random();
+        let other_key = Ed25519Signer::new_random();
+        let from = client_key.wallet_address();
+
+        let payload = format!("from={from};to=0x000000000000000000000000000000000000cafe;amount=5;asset=FloweR;chain=flowcortex-l1;nonce=1");
+        let signature = client_key
+            .sign_in_domain(&SigningDomain::default(), payload.as_bytes(), kc_api_types::SignPurpose::Transaction)
+            .expect("sign");
+        let signed = json!({
+            "payload": payload,
+            "signature": to_hex(&signature),
+            "public_key": client_key.public_key_hex()
+        });
+
+        let (wrong_key_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit-signed",
+            json!({
+                "payload": payload,
+                "signature": to_hex(&signature),
+                "public_key": other_key.public_key_hex()
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(wrong_key_status, StatusCode::UNAUTHORIZED);
+
+        let (malformed_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit-signed",
+            json!({
+                "payload": format!("to=0x000000000000000000000000000000000000cafe;from={from};amount=5;asset=FloweR;chain=flowcortex-l1;nonce=1"),
+                "signature": to_hex(&signature),
+                "public_key": client_key.public_key_hex()
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(malformed_status, StatusCode::BAD_REQUEST);
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/submit-signed", signed.clone(), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["accepted"], true);
+        assert_eq!(body["signature"], to_hex(&signature));
+
+        let (replay_status, replay_body) =
+            send_json(&app, Method::POST, "/wallet/submit-signed", signed, vec![]).await;
+        assert_eq!(replay_status, StatusCode::BAD_REQUEST);
+        assert!(replay_body["error"].as_str().is_some_and(|e| e.contains("nonce replay")));
+    }
+
+    #[tokio::test]
+    async fn nonce_reservations_are_exclusive_and_lapse() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let nonces = Arc::clone(&state.nonces);
+        let keystore = Arc::clone(&state.keystore);
+        let sessions = state.wallet_sessions.clone();
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"].as_str().expect("address").to_owned();
+        let reserve_uri = format!("/wallet/{wallet_address}/nonce/reserve");
+        let now = epoch_ms().expect("clock should work");
+        let session = sessions.issue(&keystore, &wallet_address, now, now).expect("session should issue");
+        let with_session =
+            || vec![("x-wallet-session", HeaderValue::from_str(&session.token).expect("header"))];
+        let bearer = |user: &str| {
+            let token = build_hs256_token("test-auth-secret", user);
+            vec![("authorization", HeaderValue::from_str(&format!("Bearer {token}")).expect("header"))]
+        };
+
+        // Neither the wallet's session nor its owner's token: refused.
+        let (status, _) = send_empty(&app, Method::POST, &reserve_uri).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send_json(&app, Method::POST, &reserve_uri, json!({}), bearer("user-1")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "the wallet is not bound yet");
+
+        let (first_status, first) =
+            send_json(&app, Method::POST, &reserve_uri, json!({}), with_session()).await;
+        assert_eq!(first_status, StatusCode::OK);
+        assert_eq!(first["nonce"], 1);
+        keystore
+            .save_wallet_binding(&kc_storage::WalletBindingRecord {
+                wallet_address: wallet_address.clone(),
+                user_id: "user-1".to_owned(),
+                chain: "flowcortex-l1".to_owned(),
+                last_verified_epoch_ms: 1,
+            })
+            .expect("binding");
+        let (status, _) = send_json(&app, Method::POST, &reserve_uri, json!({}), bearer("user-2")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (_, second) = send_json(
+            &app,
+            Method::POST,
+            &format!("{reserve_uri}?ttl_seconds=30"),
+            json!({}),
+            bearer("user-1"),
+        )
+        .await;
+        assert_eq!(second["nonce"], 2);
+
+        let (bad_ttl_status, _) = send_json(
+            &app,
+            Method::POST,
+            &format!("{reserve_uri}?ttl_seconds=0"),
+            json!({}),
+            with_session(),
+        )
+        .await;
+        assert_eq!(bad_ttl_status, StatusCode::BAD_REQUEST);
+        let (unknown_status, _) =
+            send_empty(&app, Method::POST, "/wallet/0xunknown/nonce/reserve").await;
+        assert_eq!(unknown_status, StatusCode::BAD_REQUEST);
+
+        let (_, nonce_body) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/nonce?wallet_address={wallet_address}"),
+        )
+        .await;
+        assert_eq!(nonce_body["next_nonce"], 3);
+
+        // Submitting a reserved nonce consumes it.
+        let (submit_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "1",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(submit_status, StatusCode::OK);
+
+        // Once nonce 2's reservation lapses it is handed out again.
+        let lapsed = second["expires_at_epoch_ms"].as_u64().expect("expiry") as u128;
+        let reissued = nonces
+            .reserve(&wallet_address, "reissued".to_owned(), 1_000, lapsed)
+            .await
+            .expect("reservation")
+            .expect("the wallet is under its reservation cap");
+        assert_eq!(reissued.nonce, 2);
+        assert_eq!(nonces.reservations(&wallet_address).await, vec![reissued]);
+
+        // A wallet cannot hoard nonces past the cap.
+        for _ in 1..kc_wallet_core::MAX_LIVE_RESERVATIONS {
+            let (status, _) =
+                send_json(&app, Method::POST, &reserve_uri, json!({}), with_session()).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (status, body) =
+            send_json(&app, Method::POST, &reserve_uri, json!({}), with_session()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS, "{body}");
+    }
+
+    #[tokio::test]
+    async fn weak_passphrases_are_rejected_on_create_and_restore() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let app = build_app(test_state(&temp_dir));
+
+        let (create_status, create_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/create",
+            json!({ "passphrase": "P@ssw0rd1" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(create_status, StatusCode::BAD_REQUEST);
+        let error = create_body["error"].as_str().expect("error message");
+        assert!(error.starts_with("passphrase too weak"), "{error}");
+        assert!(error.contains("common words"), "{error}");
+
+        let (restore_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/restore",
+            json!({ "passphrase": "aaaaaaaaaaaa" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(restore_status, StatusCode::BAD_REQUEST);
+
+        let strong = "glacier-umbrella-fossil-tangerine";
+        let (created_status, created) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/create",
+            json!({ "passphrase": strong }),
+            vec![],
+        )
+        .await;
+        assert_eq!(created_status, StatusCode::OK);
+        let (restored_status, restored) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/restore",
+            json!({ "passphrase": strong }),
+            vec![],
+        )
+        .await;
+        assert_eq!(restored_status, StatusCode::OK);
+        assert_eq!(restored["wallet_address"], created["wallet_address"]);
+
+        // Random wallets carry no passphrase and are unaffected.
+        let (random_status, _) =
+            send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        assert_eq!(random_status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ops_chains_add_disable_and_persist_adapters() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+
+        let (unauth_status, _) = send_empty(&app, Method::GET, "/ops/chains").await;
+        assert_eq!(unauth_status, StatusCode::UNAUTHORIZED);
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("header should build"),
+        )];
+
+        let (_, list_body) = send_json(&app, Method::GET, "/ops/chains", json!({}), auth.clone()).await;
+        assert_eq!(list_body["total"], 1);
+        assert_eq!(list_body["chains"][0]["chain_id"], "flowcortex-l1");
+        assert_eq!(list_body["chains"][0]["active"], true);
+        assert!(list_body["chains"][0].get("health").is_none());
+
+        // EVM networks get a JSON-RPC adapter even while their node is down.
+        let (evm_status, evm_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({
+                "chain_id": "sepolia",
+                "kind": "evm",
+                "endpoint": "http://127.0.0.1:9/",
+                "evm_chain_id": 11155111,
+                "erc20_tokens": { "usdc": "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238" }
+            }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(evm_status, StatusCode::OK);
+        assert_eq!(evm_body["endpoint"], "http://127.0.0.1:9");
+        assert_eq!(evm_body["active"], true);
+        assert_eq!(evm_body["erc20_tokens"]["USDC"], "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238");
+        assert_eq!(evm_body["health"]["healthy"], false);
+
+        let (testnet_status, testnet_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({ "chain_id": "flowcortex-testnet", "kind": "flowcortex", "endpoint": "http://127.0.0.1:9" }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(testnet_status, StatusCode::OK);
+        assert_eq!(testnet_body["active"], true);
+
+        let (cosmos_status, cosmos_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({
+                "chain_id": "cosmoshub-4",
+                "kind": "cosmos",
+                "endpoint": "http://127.0.0.1:9",
+                "bech32_prefix": "cosmos",
+                "denoms": { "atom": "uatom" }
+            }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(cosmos_status, StatusCode::OK, "{cosmos_body}");
+        assert_eq!(cosmos_body["active"], true);
+        assert_eq!(cosmos_body["bech32_prefix"], "cosmos");
+        assert_eq!(cosmos_body["denoms"]["ATOM"], "uatom");
+
+        let (bitcoin_status, bitcoin_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({
+                "chain_id": "bitcoin-testnet",
+                "kind": "bitcoin",
+                "endpoint": "http://127.0.0.1:9",
+                "bech32_prefix": "tb"
+            }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(bitcoin_status, StatusCode::OK, "{bitcoin_body}");
+        assert_eq!(bitcoin_body["active"], true);
+        assert_eq!(bitcoin_body["bech32_prefix"], "tb");
+
+        for (body, expected) in [
+            (json!({ "chain_id": "new-chain", "endpoint": "http://127.0.0.1:9" }), "kind is required"),
+            (json!({ "chain_id": "Bad Chain", "kind": "evm", "endpoint": "http://x" }), "chain_id must"),
+            (json!({ "chain_id": "sepolia", "endpoint": "ftp://x" }), "endpoint must"),
+            (json!({ "chain_id": "sepolia", "erc20_tokens": { "DAI": "0x12" } }), "erc20_tokens entry 'DAI'"),
+            (
+                json!({ "chain_id": "flowcortex-testnet", "kind": "flowcortex", "endpoint": "http://127.0.0.1:9", "erc20_tokens": { "DAI": "0x6B175474E89094C44Da98b954EedeAC495271d0F" } }),
+                "erc20_tokens are only",
+            ),
+            (
+                json!({ "chain_id": "osmosis-1", "kind": "cosmos", "endpoint": "http://127.0.0.1:9" }),
+                "bech32_prefix is required",
+            ),
+            (json!({ "chain_id": "cosmoshub-4", "bech32_prefix": "Cosmos" }), "bech32_prefix must"),
+            (json!({ "chain_id": "cosmoshub-4", "denoms": { "ATOM": "u" } }), "denoms entry 'ATOM'"),
+            (json!({ "chain_id": "sepolia", "bech32_prefix": "cosmos" }), "bech32_prefix is only"),
+            (
+                json!({ "chain_id": "bitcoin", "kind": "bitcoin", "endpoint": "http://127.0.0.1:9" }),
+                "bech32_prefix is required",
+            ),
+            (json!({ "chain_id": "bitcoin-testnet", "denoms": { "BTC": "sat" } }), "denoms are only"),
+        ] {
+            let (status, error) = send_json(&app, Method::POST, "/ops/chains", body, auth.clone()).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(error["error"].as_str().expect("error").starts_with(expected), "{error}");
+        }
+
+        // Disabling only needs the chain id; traffic for it stops at once.
+        let (disable_status, disable_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({ "chain_id": "flowcortex-l1", "enabled": false }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(disable_status, StatusCode::OK);
+        assert_eq!(disable_body["kind"], "flowcortex");
+        assert_eq!(disable_body["active"], false);
+        let (balance_status, balance_body) =
+            send_empty(&app, Method::GET, "/wallet/balance?wallet_address=0x0000000000000000000000000000000000000abc").await;
+        assert_eq!(balance_status, StatusCode::BAD_REQUEST);
+        assert_eq!(balance_body["error"], "chain 'flowcortex-l1' is not enabled");
+
+        let (_, probed) = send_json(&app, Method::GET, "/ops/chains?probe=true", json!({}), auth).await;
+        assert_eq!(probed["total"], 5);
+        assert!(probed["chains"].as_array().expect("chains").iter().all(|c| c.get("health").is_some()));
+
+        // A restart replays the persisted records over the built-in adapter.
+        let mut reloaded = chains::ChainTable::with_builtin("http://127.0.0.1:9", Arc::new(MockChainAdapter));
+        reloaded.load_persisted(&keystore).expect("records should load");
+        assert!(reloaded.adapter("flowcortex-l1").is_none());
+        assert!(reloaded.adapter("flowcortex-testnet").is_some());
+        assert_eq!(reloaded.config("sepolia").and_then(|c| c.evm_chain_id), Some(11155111));
+        assert!(reloaded.adapter("sepolia").is_some());
+        assert!(reloaded.adapter("cosmoshub-4").is_some());
+        assert!(reloaded.adapter("bitcoin-testnet").is_some());
+    }
+
+    #[tokio::test]
+    async fn envelope_signatures_bind_expiry_and_expire() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let client_key = Ed25519Signer::new_random();
+        let from = client_key.wallet_address();
+        let now = epoch_ms().expect("clock");
+
+        let payload = format!("from={from};to=0x000000000000000000000000000000000000cafe;amount=5;asset=FloweR;chain=flowcortex-l1;nonce=1");
+        let sign_until = |expires_at_epoch_ms: u128| {
+            let envelope = kc_api_types::TransactionEnvelope {
+                chain_id: "flowcortex-l1".to_owned(),
+                nonce: 1,
+                expires_at_epoch_ms,
+            };
+            let domain = SigningDomain::default().with_envelope(&envelope).expect("envelope");
+            to_hex(
+                &client_key
+                    .sign_in_domain(&domain, payload.as_bytes(), kc_api_types::SignPurpose::Transaction)
+                    .expect("sign"),
+            )
+        };
+        let expires_at = now + 60_000;
+        let signature = sign_until(expires_at);
+
+        // The expiry is part of the signed bytes, so it cannot be stretched.
+        let (stretched_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit-signed",
+            json!({
+                "payload": payload,
+                "signature": signature,
+                "public_key": client_key.public_key_hex(),
+                "expires_at_epoch_ms": expires_at + 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(stretched_status, StatusCode::UNAUTHORIZED);
+
+        // A v2 signature is not a valid v1 signature.
+        let (v1_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit-signed",
+            json!({ "payload": payload, "signature": signature, "public_key": client_key.public_key_hex() }),
+            vec![],
+        )
+        .await;
+        assert_eq!(v1_status, StatusCode::UNAUTHORIZED);
+
+        let (expired_status, expired_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit-signed",
+            json!({
+                "payload": payload,
+                "signature": sign_until(now - 1),
+                "public_key": client_key.public_key_hex(),
+                "expires_at_epoch_ms": now - 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(expired_status, StatusCode::BAD_REQUEST);
+        assert_eq!(expired_body["error"], "transaction envelope has expired");
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit-signed",
+            json!({
+                "payload": payload,
+                "signature": signature,
+                "public_key": client_key.public_key_hex(),
+                "expires_at_epoch_ms": expires_at
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["expires_at_epoch_ms"], json!(expires_at));
+
+        // Server-side signing always uses an envelope, defaulting the expiry.
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = created["wallet_address"].as_str().expect("address").to_owned();
+        let (_, submitted) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "1",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        let server_expiry = submitted["expires_at_epoch_ms"].as_u64().expect("expiry");
+        assert!(u128::from(server_expiry) > now);
+        let public_key = kc_crypto::Ed25519PublicKey::from_hex(created["public_key"].as_str().expect("key"))
+            .expect("public key");
+        let domain = SigningDomain::default()
+            .with_envelope(&kc_api_types::TransactionEnvelope {
+                chain_id: "flowcortex-l1".to_owned(),
+                nonce: 1,
+                expires_at_epoch_ms: u128::from(server_expiry),
+            })
+            .expect("envelope");
+        let server_payload = format!("from={wallet};to=0x000000000000000000000000000000000000cafe;amount=1;asset=PROOF;chain=flowcortex-l1;nonce=1");
+        let server_signature = from_hex(submitted["signature"].as_str().expect("signature")).expect("hex");
+        assert!(public_key
+            .verify_in_domain(&domain, server_payload.as_bytes(), kc_api_types::SignPurpose::Transaction, &server_signature)
+            .expect("verify"));
+    }
+
+    #[tokio::test]
+    async fn custom_sign_purposes_must_be_registered() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, config) = send_empty(&app, Method::GET, "/chain/config").await;
+        assert_eq!(config["domains"]["custom_purposes"], json!(["delegation"]));
+
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = created["wallet_address"].as_str().expect("address").to_owned();
+        let payload = base64::engine::general_purpose::STANDARD.encode("delegate-to:0xabc");
+
+        let (unknown_status, unknown_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": wallet_address, "payload": payload, "purpose": "voting" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(unknown_status, StatusCode::BAD_REQUEST);
+        assert_eq!(unknown_body["error"], "sign purpose 'voting' is not registered");
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": wallet_address, "payload": payload, "purpose": "delegation" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let signature = from_hex(body["signature"].as_str().expect("signature")).expect("hex");
+        let public_key = kc_crypto::Ed25519PublicKey::from_hex(created["public_key"].as_str().expect("key"))
+            .expect("public key");
+        let purpose = kc_api_types::SignPurpose::Custom("delegation".to_owned());
+        let domain = SigningDomain::default();
+        assert!(public_key
+            .verify_in_domain(&domain, b"delegate-to:0xabc", purpose, &signature)
+            .expect("verify"));
+        assert!(!public_key
+            .verify_in_domain(&domain, b"delegate-to:0xabc", kc_api_types::SignPurpose::Transaction, &signature)
+            .expect("verify"));
+    }
+
+    #[tokio::test]
+    async fn signing_domain_and_purpose_tags_come_from_chain_config() {
+        let config = kc_chain_client::ChainRegistryConfig::parse(
+            "[signing]\nnamespace = \"acme\"\nversion = \"v2\"\nauth_tag = \"login\"",
+        )
+        .expect("chain config should parse");
+        let domain = chain_config::signing_domain(config.signing.as_ref()).expect("signing domain");
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        state.wallet_core = custody_core(&state.keystore, &state.encryption_key, &domain, state.keystore.clone());
+        state.signing_domain = domain.clone();
+        let wallet_core = Arc::clone(&state.wallet_core);
+        let app = build_app(state);
+
+        let (_, config) = send_empty(&app, Method::GET, "/chain/config").await;
+        assert_eq!(config["domains"]["tx_domain_tag"], "acme:v2:transaction");
+        assert_eq!(config["domains"]["auth_domain_tag"], "acme:v2:login");
+        assert_eq!(wallet_core.signing_domain(), &domain);
+
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let payload = base64::engine::general_purpose::STANDARD.encode("hello");
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": created["wallet_address"], "payload": payload, "purpose": "auth" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let signature = from_hex(body["signature"].as_str().expect("signature")).expect("hex");
+        let public_key = kc_crypto::Ed25519PublicKey::from_hex(created["public_key"].as_str().expect("key"))
+            .expect("public key");
+        assert!(public_key
+            .verify_in_domain(&domain, b"hello", kc_api_types::SignPurpose::Auth, &signature)
+            .expect("verify"));
+    }
+
+    #[tokio::test]
+    async fn nonce_handling_follows_the_chain_strategy() {
+        async fn app_with(temp_dir: &TempDir, strategy: NonceStrategy) -> (Router, String) {
+            let state = test_state(temp_dir);
+            *state.chains.write().expect("chain table") = chains::ChainTable::with_builtin(
+                "http://127.0.0.1:9",
+                Arc::new(StrategyMockAdapter {
+                    strategy,
+                    account_nonce: Arc::new(std::sync::atomic::AtomicU64::new(5)),
+                }),
+            );
+            let app = build_app(state);
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            (app, body["wallet_address"].as_str().expect("address").to_owned())
+        }
+        async fn submit(app: &Router, from: &str, nonce: u64) -> (StatusCode, Value) {
+            let body = json!({
+                "from": from,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "1",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": nonce
+            });
+            send_json(app, Method::POST, "/wallet/submit", body, vec![]).await
+        }
+
+        // No nonces: zero is fine and is never recorded, so it can repeat.
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let (app, wallet) = app_with(&temp_dir, NonceStrategy::None).await;
+        assert_eq!(submit(&app, &wallet, 0).await.0, StatusCode::OK);
+        assert_eq!(submit(&app, &wallet, 0).await.0, StatusCode::OK);
+        let reserve_uri = format!("/wallet/{wallet}/nonce/reserve");
+        assert_eq!(send_empty(&app, Method::POST, &reserve_uri).await.0, StatusCode::BAD_REQUEST);
+
+        // Chain-queried: below the account nonce is stale, at or above is accepted once.
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let (app, wallet) = app_with(&temp_dir, NonceStrategy::ChainQueried).await;
+        let (_, nonce_body) =
+            send_empty(&app, Method::GET, &format!("/wallet/nonce?wallet_address={wallet}")).await;
+        assert_eq!(nonce_body["nonce_strategy"], "chain_queried");
+        assert_eq!(nonce_body["next_nonce"], 5);
+        let (stale_status, stale_body) = submit(&app, &wallet, 4).await;
+        assert_eq!(stale_status, StatusCode::BAD_REQUEST);
+        assert!(stale_body["error"].as_str().is_some_and(|e| e.contains("account nonce is 5")));
+        assert_eq!(submit(&app, &wallet, 5).await.0, StatusCode::OK);
+        assert_eq!(submit(&app, &wallet, 5).await.0, StatusCode::BAD_REQUEST);
+
+        // Recent-blockhash: /wallet/nonce hands out the blockhash instead of a counter.
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let (app, wallet) = app_with(&temp_dir, NonceStrategy::RecentBlockhash).await;
+        let (_, nonce_body) =
+            send_empty(&app, Method::GET, &format!("/wallet/nonce?wallet_address={wallet}")).await;
+        assert_eq!(nonce_body["nonce_strategy"], "recent_blockhash");
+        assert_eq!(nonce_body["recent_blockhash"], "mock-blockhash");
+    }
+
+    #[tokio::test]
+    async fn submit_amounts_are_bounded_by_asset_decimals() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = create_body["wallet_address"].as_str().expect("address").to_owned();
+
+        // 2^64 * 10^6 base units is one whole FloweR past the cap, but fine for 18-decimal PROOF.
+        let past_flower_cap = ((u128::from(u64::MAX) + 1) * 1_000_000).to_string();
+        let cases = [
+            ("FloweR", "1.5", 1, Some("amount must be a non-negative integer")),
+            ("FloweR", "340282366920938463463374607431768211456", 2, Some("amount overflows 128 bits")),
+            ("FloweR", past_flower_cap.as_str(), 3, Some("amount exceeds the maximum for FloweR (6 decimals)")),
+            ("PROOF", past_flower_cap.as_str(), 4, None),
+        ];
+        for (asset, amount, nonce, expected_error) in cases {
+            let (status, body) = send_json(
+                &app,
+                Method::POST,
+                "/wallet/submit",
+                json!({
+                    "from": wallet,
+                    "to": "0x000000000000000000000000000000000000cafe",
+                    "amount": amount,
+                    "asset": asset,
+                    "chain": "flowcortex-l1",
+                    "nonce": nonce
+                }),
+                vec![],
+            )
+            .await;
+            match expected_error {
+                Some(error) => {
+                    assert_eq!(status, StatusCode::BAD_REQUEST, "{amount}");
+                    assert_eq!(body["error"], error);
+                }
+                None => assert_eq!(status, StatusCode::OK, "{body}"),
+            }
+        }
+    }
+
+    /// Accepts one Postgres connection, completes the startup handshake and
+    /// hangs up, so every later query fails as if the database went away.
+    async fn dead_postgres_url() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let port = listener.local_addr().expect("local addr").port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            let mut len = [0u8; 4];
+            socket.read_exact(&mut len).await.expect("startup length");
+            let mut startup = vec![0u8; u32::from_be_bytes(len) as usize - 4];
+            socket.read_exact(&mut startup).await.expect("startup message");
+            // AuthenticationOk, then ReadyForQuery(idle).
+            socket.write_all(b"R\0\0\0\x08\0\0\0\0Z\0\0\0\x05I").await.expect("handshake");
+        });
+        format!("host=127.0.0.1 port={port} user=soak dbname=soak sslmode=disable")
+    }
+
+    #[tokio::test]
+    async fn chaos_soak_degrades_gracefully() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let chaos = chaos::Chaos::new(chaos::ChaosConfig {
+            storage_latency_rate: 0.2,
+            storage_latency: Duration::from_millis(1),
+            postgres_failure_rate: 0.5,
+            chain_timeout_rate: 0.3,
+            chain_timeout: Duration::from_millis(5),
+        });
+        let mut state = test_state(&temp_dir);
+        let keystore = RocksDbKeystore::open_default(
+            temp_dir.path().join("chaos.rocksdb").to_string_lossy().as_ref(),
+        )
+        .expect("keystore should open");
+        state.keystore = Arc::new(keystore.with_access_hook(chaos.storage_hook()));
+        state.wallet_core = custody_core(
+            &state.keystore,
+            &state.encryption_key,
+            &state.signing_domain,
+            state.keystore.clone(),
+        );
+        state.nonces = Arc::new(NonceManager::new(
+            Arc::new(InMemoryNonceStore::default()),
+            state.keystore.clone(),
+        ));
+        state.challenges = Arc::new(Challenges::new(state.keystore.clone()));
+        let postgres = db::PostgresRepository::connect(&dead_postgres_url().await)
+            .await
+            .expect("fake postgres handshake");
+        state.postgres_repo = Some(Arc::new(postgres.with_chaos(Arc::clone(&chaos))));
+        state.challenge_mirror = state.postgres_repo.clone();
+        state.chains = Arc::new(StdRwLock::new(
+            chains::ChainTable::with_builtin("http://127.0.0.1:9", Arc::new(MockChainAdapter))
+                .with_chaos(Arc::clone(&chaos)),
+        ));
+        state.chaos = Some(chaos);
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = create_body["wallet_address"].as_str().expect("address").to_owned();
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+
+        const ITERATIONS: u64 = 40;
+        for nonce in 1..=ITERATIONS {
+            let (challenge_status, _) = send_empty(&app, Method::POST, "/auth/challenge").await;
+            assert_eq!(challenge_status, StatusCode::OK, "challenges are kept in RocksDB");
+
+            let (audit_status, _) = send_json(
+                &app,
+                Method::GET,
+                "/ops/audit?limit=5",
+                json!({}),
+                vec![("authorization", auth_value.clone())],
+            )
+            .await;
+            assert_eq!(audit_status, StatusCode::OK, "audit reads fall back to RocksDB");
+
+            // A timed-out broadcast must not burn its nonce: retrying it succeeds.
+            let mut attempts = 0;
+            loop {
+                attempts += 1;
+                let (status, body) = send_json(
+                    &app,
+                    Method::POST,
+                    "/wallet/submit",
+                    json!({
+                        "from": wallet,
+                        "to": "0x000000000000000000000000000000000000cafe",
+                        "amount": "1",
+                        "asset": "PROOF",
+                        "chain": "flowcortex-l1",
+                        "nonce": nonce
+                    }),
+                    vec![],
+                )
+                .await;
+                if status == StatusCode::OK {
+                    break;
+                }
+                assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR, "{body}");
+                assert!(body["error"].as_str().is_some_and(|e| e.contains("chaos")), "{body}");
+                assert!(attempts < 20, "submit never got through chaos");
+            }
+        }
+
+        let (_, health) = send_empty(&app, Method::GET, "/health").await;
+        let fallbacks = &health["db_fallback_counters"];
+        assert_eq!(fallbacks["challenge_persist_failures"], ITERATIONS);
+        assert_eq!(fallbacks["audit_read_failures"], ITERATIONS);
+        assert!(fallbacks["audit_write_failures"].as_u64().is_some_and(|n| n > 0), "{health}");
+        let injected = &health["chaos"];
+        for counter in ["storage_delays_injected", "postgres_failures_injected", "chain_timeouts_injected"] {
+            assert!(injected[counter].as_u64().is_some_and(|n| n > 0), "{counter}: {health}");
+        }
+    }
+
+    #[test]
+    fn audit_retention_prunes_only_expired_events() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let day_ms: u128 = 24 * 60 * 60 * 1000;
+        let now = 400 * day_ms;
+        // 9 and 10 digit timestamps, so pruning cannot rely on lexicographic key order.
+        for (event_id, timestamp_epoch_ms) in [
+            ("ancient", 999_999_999),
+            ("expired", now - 31 * day_ms),
+            ("recent", now - 29 * day_ms),
+            ("fresh", now),
+        ] {
+            state
+                .keystore
+                .append_audit_event(kc_storage::AuditEventRecord {
+                    event_id: event_id.to_owned(),
+                    event_type: "retention_test".to_owned(),
+                    wallet_address: None,
+                    user_id: None,
+                    chain: None,
+                    outcome: "success".to_owned(),
+                    message: None,
+                    timestamp_epoch_ms,
+                    tenant: None,
+                })
+                .expect("audit event should append");
+        }
+
+        let pruned = auth::prune_audit_events(&state.keystore, 30, now).expect("prune");
+        assert_eq!(pruned, 2);
+        let remaining: Vec<String> = state
+            .keystore
+            .list_audit_events(10, Some("retention_test"), None, None)
+            .expect("list")
+            .into_iter()
+            .map(|event| event.event_id)
+            .collect();
+        assert_eq!(remaining, ["fresh", "recent"]);
+        assert_eq!(auth::prune_audit_events(&state.keystore, 30, now).expect("prune"), 0);
+    }
+
+    #[tokio::test]
+    async fn audit_wallet_filter_uses_index_and_survives_pruning() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+        for (event_id, wallet_address, timestamp_epoch_ms) in [
+            ("a-old", Some("0xaaa"), 1_000),
+            ("a-new", Some("0xaaa"), 20_000),
+            ("b", Some("0xaaab"), 15_000),
+            ("none", None, 16_000),
+        ] {
+            keystore
+                .append_audit_event(kc_storage::AuditEventRecord {
+                    event_id: event_id.to_owned(),
+                    event_type: "index_test".to_owned(),
+                    wallet_address: wallet_address.map(str::to_owned),
+                    user_id: None,
+                    chain: None,
+                    outcome: "success".to_owned(),
+                    message: None,
+                    timestamp_epoch_ms,
+                    tenant: None,
+                })
+                .expect("audit event should append");
+        }
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+        let list = |wallet: &'static str| {
+            let app = app.clone();
+            let auth_value = auth_value.clone();
+            async move {
+                let (status, body) = send_json(
+                    &app,
+                    Method::GET,
+                    &format!("/ops/audit?wallet_address={wallet}&event_type=index_test"),
+                    json!({}),
+                    vec![("authorization", auth_value)],
+                )
+                .await;
+                assert_eq!(status, StatusCode::OK, "{body}");
+                body["events"]
+                    .as_array()
+                    .expect("events")
+                    .iter()
+                    .map(|event| event["event_id"].as_str().expect("id").to_owned())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // `0xaaa` must not pick up `0xaaab`, whose index keys share its prefix.
+        assert_eq!(list("0xaaa").await, ["a-new", "a-old"]);
+        assert_eq!(list("0xaaab").await, ["b"]);
+
+        assert_eq!(keystore.prune_audit_events(10_000).expect("prune"), 1);
+        assert_eq!(list("0xaaa").await, ["a-new"]);
+    }
+
+    #[tokio::test]
+    async fn batch_balances_and_activity_span_wallets() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let mut wallets = Vec::new();
+        for _ in 0..2 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            wallets.push(body["wallet_address"].as_str().expect("address").to_owned());
+        }
+        for (index, wallet) in wallets.iter().enumerate() {
+            let (status, _) = send_json(
+                &app,
+                Method::POST,
+                "/wallet/submit",
+                json!({
+                    "from": wallet,
+                    "to": "0x000000000000000000000000000000000000cafe",
+                    "amount": format!("{}", index + 1),
+                    "asset": "PROOF",
+                    "chain": "flowcortex-l1",
+                    "nonce": 1
+                }),
+                vec![],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/balances",
+            json!({ "wallet_addresses": wallets }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["balances"].as_array().map(Vec::len), Some(4));
+        assert_eq!(body["balances"][0]["wallet_address"], wallets[0]);
+        assert_eq!(body["balances"][0]["asset"], "PROOF");
+        assert_eq!(body["balances"][1]["asset"], "FloweR");
+        assert_eq!(body["totals"][0], json!({ "asset": "PROOF", "amount": "0", "decimals": 18 }));
+
+        let (bad_asset, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/balances",
+            json!({ "wallet_addresses": wallets, "assets": ["DOGE"] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(bad_asset, StatusCode::BAD_REQUEST);
+        let (empty, _) =
+            send_json(&app, Method::POST, "/wallet/balances", json!({ "wallet_addresses": [] }), vec![]).await;
+        assert_eq!(empty, StatusCode::BAD_REQUEST);
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/activity",
+            json!({ "wallet_addresses": wallets, "limit": 10 }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let mut senders: Vec<&str> = body["activity"]
+            .as_array()
+            .expect("activity")
+            .iter()
+            .map(|entry| entry["from"].as_str().expect("from"))
+            .collect();
+        senders.sort_unstable();
+        let mut expected: Vec<&str> = wallets.iter().map(String::as_str).collect();
+        expected.sort_unstable();
+        assert_eq!(senders, expected);
+    }
+
+    #[tokio::test]
+    async fn wallet_list_pages_and_delete_removes_key() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let mut addresses = Vec::new();
+        for _ in 0..3 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            addresses.push(body["wallet_address"].as_str().expect("address").to_owned());
+        }
+        addresses.sort();
+
+        let (_, page) = send_empty(&app, Method::GET, "/wallet/list?offset=1&limit=1").await;
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["wallets"].as_array().map(Vec::len), Some(1));
+        assert_eq!(page["wallets"][0]["wallet_address"], addresses[1].as_str());
+
+        let (unauth_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/delete",
+            json!({ "wallet_address": addresses[0] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(unauth_status, StatusCode::UNAUTHORIZED);
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+        let (delete_status, delete_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/delete",
+            json!({ "wallet_address": addresses[0] }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(delete_status, StatusCode::OK);
+        assert_eq!(delete_body["deleted"], true);
+
+        let (_, list) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(list["total"], 2);
+        assert!(
+            list["wallets"]
+                .as_array()
+                .expect("wallets")
+                .iter()
+                .all(|w| w["wallet_address"] != addresses[0].as_str())
+        );
+
+        let (again_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/delete",
+            json!({ "wallet_address": addresses[0] }),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(again_status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn watch_batch_reports_each_row() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let custodied = created["wallet_address"].as_str().expect("address").to_owned();
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/watch/batch",
+            json!({
+                "device_id": "device-1",
+                "wallets": [
+                    { "wallet_address": "0x00000000000000000000000000000000000000aa", "label": "Treasury A" },
+                    { "wallet_address": "  " },
+                    { "wallet_address": custodied },
+                    { "wallet_address": "0x00000000000000000000000000000000000000bb" },
+                ],
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["registered"], 2);
+        assert_eq!(body["failed"], 2);
+        assert_eq!(body["results"][0]["ok"], true);
+        assert_eq!(body["results"][1]["error"], "wallet_address is required");
+        assert_eq!(body["results"][2]["error"], "wallet is already custodied");
+        assert_eq!(body["results"][3]["row"], 3);
+
+        let (_, list) = send_empty(&app, Method::GET, "/wallet/list?device_id=device-1").await;
+        assert_eq!(list["total"], 2);
+        assert_eq!(list["wallets"][0]["label"], "Treasury A");
+        assert_eq!(list["wallets"][0]["custodied"], false);
+
+        let (empty_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/watch/batch",
+            json!({ "wallets": [] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(empty_status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn tx_status_reports_timeline_confirmations_and_explorer_link() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        *state.chains.write().expect("chain table") =
+            chains::ChainTable::with_builtin("http://127.0.0.1:9", Arc::new(MockChainAdapter))
+                .with_builtin_explorer(ChainExplorerTemplates {
+                    tx_url: Some("https://explorer.example/tx/{tx_hash}".to_owned()),
+                    ..Default::default()
+                });
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"].as_str().expect("address").to_owned();
+        let (_, submit_body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "5",
+                "asset": "FloweR",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        let tx_hash = submit_body["tx_hash"].as_str().expect("tx_hash").to_owned();
+
+        let (status, body) = send_empty(&app, Method::GET, &format!("/wallet/tx/{tx_hash}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "confirmed");
+        assert_eq!(body["block_height"], 7);
+        assert_eq!(body["confirmations"], 3);
+        let history: Vec<&str> = body["status_history"]
+            .as_array()
+            .expect("status_history")
+            .iter()
+            .map(|change| change["status"].as_str().expect("status"))
+            .collect();
+        assert_eq!(history, ["submitted", "confirmed"]);
+        assert_eq!(body["explorer_url"], format!("https://explorer.example/tx/{tx_hash}"));
+
+        // A repeat lookup with no status change does not grow the timeline.
+        let (_, body) = send_empty(&app, Method::GET, &format!("/wallet/tx/{tx_hash}")).await;
+        assert_eq!(body["status_history"].as_array().map(Vec::len), Some(2));
+    }
+
+    #[tokio::test]
+    async fn chain_config_advertises_explorer_templates_set_through_ops() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("header should build"),
+        )];
+
+        let (_, config) = send_empty(&app, Method::GET, "/chain/config").await;
+        assert_eq!(config["explorer"]["tx_url"], Value::Null);
+
+        let (bad_status, bad_body) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({
+                "chain_id": "flowcortex-l1",
+                "explorer_block_url": "https://explorer.example/block/latest"
+            }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(bad_status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            bad_body["error"],
+            "explorer_block_url must contain a {height} placeholder"
+        );
+
+        let (status, summary) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({
+                "chain_id": "flowcortex-l1",
+                "explorer_tx_url": "https://explorer.example/tx/{tx_hash}",
+                "explorer_address_url": "https://explorer.example/address/{address}",
+                "explorer_block_url": "https://explorer.example/block/{height}"
+            }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(summary["explorer_address_url"], "https://explorer.example/address/{address}");
+
+        let (_, config) = send_empty(&app, Method::GET, "/chain/config").await;
+        assert_eq!(config["explorer"]["tx_url"], "https://explorer.example/tx/{tx_hash}");
+        assert_eq!(config["explorer"]["address_url"], "https://explorer.example/address/{address}");
+        assert_eq!(config["explorer"]["block_url"], "https://explorer.example/block/{height}");
+
+        // Omitted fields are kept; an empty string clears one template.
+        send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({ "chain_id": "flowcortex-l1", "explorer_block_url": "" }),
+            auth,
+        )
+        .await;
+        let (_, config) = send_empty(&app, Method::GET, "/chain/config").await;
+        assert_eq!(config["explorer"]["tx_url"], "https://explorer.example/tx/{tx_hash}");
+        assert_eq!(config["explorer"]["block_url"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn exports_stream_csv_downloads_readable_cross_origin() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = body["wallet_address"].as_str().expect("address").to_owned();
+        let (status, submitted) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "5",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{submitted}");
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/wallet/activity/export?wallet_addresses={wallet}"))
+            .header("origin", "http://ui.example")
+            .body(Body::empty())
+            .expect("request should build");
+        let response = app.clone().oneshot(request).await.expect("request should be handled");
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers["content-type"], "text/csv; charset=utf-8");
+        let disposition = headers["content-disposition"].to_str().expect("ascii header");
+        assert!(disposition.starts_with("attachment; filename=\"keycortex-activity-"), "{disposition}");
+        assert!(disposition.contains("filename*=UTF-8''keycortex-activity-"), "{disposition}");
+        assert_eq!(
+            headers["access-control-expose-headers"],
+            "content-disposition,x-client-request-id"
+        );
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body should decode");
+        let csv = String::from_utf8(bytes.to_vec()).expect("utf-8 csv");
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "tx_hash,submitted_at_epoch_ms,status,chain,from,to,asset,amount"
+        );
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with(submitted["tx_hash"].as_str().expect("tx hash")));
+        assert!(lines[1].ends_with(&format!(",{wallet},0x000000000000000000000000000000000000cafe,PROOF,5")));
+
+        let (unauthorized, _) = send_empty(&app, Method::GET, "/ops/audit/export").await;
+        assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/ops/audit/export?limit=5")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("request should build");
+        let response = app.oneshot(request).await.expect("request should be handled");
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body should decode");
+        let csv = String::from_utf8(bytes.to_vec()).expect("utf-8 csv");
+        assert!(csv.starts_with("event_id,timestamp_epoch_ms,event_type,outcome,"));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_and_defused() {
+        assert_eq!(export::csv_field("plain"), "plain");
+        assert_eq!(export::csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(export::csv_field("=SUM(A1)"), "'=SUM(A1)");
+    }
+
+    #[tokio::test]
+    async fn health_history_keeps_recent_samples_with_counter_deltas() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = Arc::new(test_state(&temp_dir));
+        let app = build_app(Arc::clone(&state));
+
+        for _ in 0..2 {
+            state.db_fallback_counters.inc_audit_write_failures();
+            health_history::record_sample(&state).await;
+        }
+        for _ in 0..2 {
+            health_history::record_sample(&state).await;
+        }
+
+        let (unauthorized, _) = send_empty(&app, Method::GET, "/ops/health/history").await;
+        assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+        let (status, body) = send_json(
+            &app,
+            Method::GET,
+            "/ops/health/history",
+            json!({}),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["capacity"], 3);
+        let samples = body["samples"].as_array().expect("samples");
+        assert_eq!(samples.len(), 3, "the oldest sample is evicted");
+        assert_eq!(samples[0]["db_fallback_counters"]["audit_write_failures"], 2);
+        assert_eq!(samples[0]["db_fallback_deltas"]["audit_write_failures"], 1);
+        assert_eq!(samples[1]["db_fallback_deltas"]["audit_write_failures"], 0);
+        assert_eq!(samples[0]["chains"]["flowcortex-l1"]["healthy"], false);
+        assert!(samples[0]["chains"]["flowcortex-l1"]["latency_ms"].is_u64());
+
+        let (_, latest) = send_json(
+            &app,
+            Method::GET,
+            "/ops/health/history?limit=1",
+            json!({}),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(latest["samples"].as_array().map(Vec::len), Some(1));
+        assert_eq!(latest["samples"][0]["at_epoch_ms"], samples[2]["at_epoch_ms"]);
+    }
+
+    #[tokio::test]
+    async fn ops_bundle_round_trips_without_key_material() {
+        let source_dir = TempDir::new().expect("temp dir should create");
+        let source = Arc::new(test_state(&source_dir));
+        let app = build_app(source.clone());
+        let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = body["wallet_address"].as_str().expect("address").to_owned();
+        source.keystore.save_wallet_label(&wallet, "treasury").expect("label");
+        source
+            .keystore
+            .save_wallet_binding(&kc_storage::WalletBindingRecord {
+                wallet_address: wallet.clone(),
+                user_id: "user-1".to_owned(),
+                chain: "flowcortex-l1".to_owned(),
+                last_verified_epoch_ms: 1,
+            })
+            .expect("binding");
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = || vec![("authorization", HeaderValue::from_str(&format!("Bearer {token}")).unwrap())];
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({"chain_id": "devnet", "kind": "flowcortex", "endpoint": "http://127.0.0.1:9"}),
+            auth(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/ops/bundle/export")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("request should build");
+        let response = app.oneshot(request).await.expect("request should be handled");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body should decode");
+        let bundle: Value = serde_json::from_slice(&bytes).expect("bundle json");
+        assert_eq!(bundle["format"], "keycortex-ops-bundle");
+        assert_eq!(bundle["labels"][0]["label"], "treasury");
+        assert_eq!(bundle["bindings"][0]["user_id"], "user-1");
+        assert_eq!(bundle["chain_adapters"][0]["chain_id"], "devnet");
+
+        let target_dir = TempDir::new().expect("temp dir should create");
+        let target = Arc::new(test_state(&target_dir));
+        let app = build_app(target.clone());
+        let (status, counts) =
+            send_json(&app, Method::POST, "/ops/bundle/import", bundle.clone(), auth()).await;
+        assert_eq!(status, StatusCode::OK, "{counts}");
+        assert_eq!(counts["labels"]["imported"], 1);
+        assert_eq!(counts["chain_adapters"]["imported"], 1);
+        assert!(!target.keystore.has_wallet(&wallet).await.expect("lookup"), "no key material");
+        assert!(chains::lookup(&target, "devnet").is_some());
+        assert_eq!(
+            target.keystore.load_chain_adapter("devnet").expect("load").expect("chain").updated_by,
+            "import:ops-1"
+        );
+
+        target.keystore.save_wallet_label(&wallet, "local").expect("label");
+        let (_, counts) =
+            send_json(&app, Method::POST, "/ops/bundle/import", bundle.clone(), auth()).await;
+        assert_eq!(counts["labels"]["skipped"], 1);
+        assert_eq!(target.keystore.load_wallet_label(&wallet).expect("load").as_deref(), Some("local"));
+        let (_, counts) =
+            send_json(&app, Method::POST, "/ops/bundle/import?overwrite=true", bundle.clone(), auth()).await;
+        assert_eq!(counts["labels"]["imported"], 1);
+        assert_eq!(target.keystore.load_wallet_label(&wallet).expect("load").as_deref(), Some("treasury"));
+
+        let mut invalid = bundle;
+        invalid["chain_adapters"][0]["endpoint"] = json!("ftp://nowhere");
+        let (status, _) = send_json(&app, Method::POST, "/ops/bundle/import", invalid, auth()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn demo_seed_is_reproducible() {
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = || vec![("authorization", HeaderValue::from_str(&format!("Bearer {token}")).unwrap())];
+        let request = json!({"seed": "screenshots", "wallets": 3, "transfers_per_wallet": 2});
+
+        let off_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&off_dir));
+        let (status, _) = send_json(&app, Method::POST, "/ops/demo/seed", request.clone(), auth()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "demo mode is off");
+
+        let demo_state = |temp_dir: &TempDir| {
+            let ledger = Arc::new(demo::DemoLedger::default());
+            let mut state = test_state(temp_dir);
+            state.chains = Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+                "http://127.0.0.1:9",
+                ledger.clone(),
+            )));
+            state.demo_ledger = Some(ledger);
+            Arc::new(state)
+        };
+        let mut seeded = Vec::new();
+        for _ in 0..2 {
+            let temp_dir = TempDir::new().expect("temp dir should create");
+            let state = demo_state(&temp_dir);
+            let app = build_app(state.clone());
+            let (status, first) =
+                send_json(&app, Method::POST, "/ops/demo/seed", request.clone(), auth()).await;
+            assert_eq!(status, StatusCode::OK, "{first}");
+            assert_eq!(first["transfers_submitted"], 6);
+            assert_eq!(first["wallets"][0]["label"], "Treasury");
+
+            let (_, again) = send_json(&app, Method::POST, "/ops/demo/seed", request.clone(), auth()).await;
+            assert_eq!(again["transfers_submitted"], 0);
+            assert_eq!(again["transfers_replayed"], 6);
+            assert_eq!(again["wallets"], first["wallets"], "re-seeding restores the same balances");
+
+            let wallet = first["wallets"][0]["wallet_address"].as_str().expect("address");
+            let (_, balance) = send_empty(
+                &app,
+                Method::GET,
+                &format!("/wallet/balance?wallet_address={wallet}&asset=PROOF"),
+            )
+            .await;
+            assert_eq!(balance["amount"], first["wallets"][0]["balances"]["PROOF"]);
+
+            let addresses: Vec<String> = first["wallets"]
+                .as_array()
+                .expect("wallets")
+                .iter()
+                .map(|w| w["wallet_address"].as_str().expect("address").to_owned())
+                .collect();
+            let mut tx_hashes: Vec<String> = state
+                .keystore
+                .list_submitted_txs_from(&addresses, 100)
+                .expect("txs")
+                .into_iter()
+                .map(|tx| tx.tx_hash)
+                .collect();
+            tx_hashes.sort();
+            let (status, tx) =
+                send_empty(&app, Method::GET, &format!("/wallet/tx/{}", tx_hashes[0])).await;
+            assert_eq!(status, StatusCode::OK, "{tx}");
+            assert_eq!(tx["status"], "confirmed");
+            assert!(!state.keystore.list_audit_events(10, None, Some(wallet), None).expect("audit").is_empty());
+            seeded.push((first, tx_hashes));
+        }
+        assert_eq!(seeded[0], seeded[1], "same seed, same fixture");
+    }
+
+    #[tokio::test]
+    async fn wallet_metadata_follows_create_rename_and_delete() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = Arc::new(test_state(&temp_dir));
+        let app = build_app(state.clone());
+
+        let (_, created) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/create",
+            json!({ "label": "Treasury" }),
+            vec![],
+        )
+        .await;
+        let address = created["wallet_address"].as_str().expect("address").to_owned();
+
+        let (_, list) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(list["wallets"][0]["key_scheme"], KEY_SCHEME_ED25519);
+        assert!(list["wallets"][0]["created_at_epoch_ms"].as_u64().is_some());
+
+        let (rename_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rename",
+            json!({ "wallet_address": address, "label": "Operations" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(rename_status, StatusCode::OK);
+        let metadata = state
+            .keystore
+            .load_wallet_metadata(&address)
+            .expect("metadata should load")
+            .expect("metadata should exist");
+        assert_eq!(metadata.label.as_deref(), Some("Operations"));
+        assert_eq!(metadata.key_scheme, KEY_SCHEME_ED25519);
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+        let (delete_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/delete",
+            json!({ "wallet_address": address }),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(delete_status, StatusCode::OK);
+        assert!(
+            state
+                .keystore
+                .load_wallet_metadata(&address)
+                .expect("metadata should load")
+                .is_none()
+        );
+        assert!(
+            state
+                .keystore
+                .list_wallet_metadata()
+                .expect("metadata should list")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn chain_calls_past_the_request_deadline_return_504() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        state.request_timeout = Duration::from_millis(200);
+        *state.chains.write().expect("chain table") = chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            Arc::new(SlowChainAdapter {
+                delay: Duration::from_secs(30),
+            }),
+        );
+        let app = build_app(state);
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = created["wallet_address"].as_str().expect("address").to_owned();
+        let balance_uri = format!("/wallet/balance?wallet_address={wallet}");
+
+        let (status, body) = send_json(
+            &app,
+            Method::GET,
+            &balance_uri,
+            json!({}),
+            vec![("x-request-timeout-ms", HeaderValue::from_static("20"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(body["error"], "get_balance exceeded the request deadline");
+
+        // A client can shorten the budget but not extend it.
+        let started = std::time::Instant::now();
+        let (status, _) = send_json(
+            &app,
+            Method::GET,
+            &balance_uri,
+            json!({}),
+            vec![("x-request-timeout-ms", HeaderValue::from_static("60000"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let (status, _) = send_json(
+            &app,
+            Method::GET,
+            &balance_uri,
+            json!({}),
+            vec![("x-request-timeout-ms", HeaderValue::from_static("soon"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // A timed-out submit gives its nonce back for the retry.
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "1",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(body["error"], "submit_transaction exceeded the request deadline");
+        let (_, nonce) =
+            send_empty(&app, Method::GET, &format!("/wallet/nonce?wallet_address={wallet}")).await;
+        assert_eq!(nonce["next_nonce"], 1);
+    }
+
+    #[tokio::test]
+    async fn wallet_tx_history_pages_sent_and_received_transfers() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let mut wallets = Vec::new();
+        for _ in 0..2 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            wallets.push(body["wallet_address"].as_str().expect("address").to_owned());
+        }
+        let mut tx_hashes = Vec::new();
+        for nonce in 1..=2 {
+            let (status, body) = send_json(
+                &app,
+                Method::POST,
+                "/wallet/submit",
+                json!({
+                    "from": wallets[0],
+                    "to": wallets[1],
+                    "amount": nonce.to_string(),
+                    "asset": "PROOF",
+                    "chain": "flowcortex-l1",
+                    "nonce": nonce
+                }),
+                vec![],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            tx_hashes.push(body["tx_hash"].as_str().expect("tx_hash").to_owned());
+            // Distinct submit times keep the expected order unambiguous.
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+
+        let (status, first) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/{}/transactions?limit=1", wallets[0]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["transactions"][0]["tx_hash"], tx_hashes[1].as_str());
+        let cursor = first["next_cursor"].as_str().expect("next_cursor");
+        let (_, second) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/{}/transactions?limit=1&cursor={cursor}", wallets[0]),
+        )
+        .await;
+        assert_eq!(second["transactions"][0]["tx_hash"], tx_hashes[0].as_str());
+        assert!(second.get("next_cursor").is_none());
+
+        let (_, received) =
+            send_empty(&app, Method::GET, &format!("/wallet/{}/transactions", wallets[1])).await;
+        assert_eq!(received["transactions"].as_array().map(Vec::len), Some(2));
+        assert_eq!(received["transactions"][0]["to"], wallets[1].as_str());
+
+        let (status, _) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/{}/transactions?cursor=bogus", wallets[0]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn storage_stats_show_in_startupz_and_ops_storage() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let (status, _) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, startupz) = send_empty(&app, Method::GET, "/startupz").await;
+        assert!(startupz["storage"]["last_write_latency_micros"].is_u64(), "{startupz}");
+        assert!(startupz["storage"].get("key_counts").is_none());
+
+        let (unauthorized, _) = send_empty(&app, Method::GET, "/ops/storage").await;
+        assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+        )];
+        let (status, body) = send_json(&app, Method::GET, "/ops/storage", json!({}), auth).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["schema_version"], kc_storage::LATEST_SCHEMA_VERSION);
+        assert_eq!(body["key_counts"]["wallet-key"], 1);
+        assert_eq!(body["key_counts"]["wallet-meta"], 1);
+    }
+
+    async fn send_ndjson(app: &Router, uri: &str, token: Option<&str>) -> (StatusCode, Vec<Value>) {
+        let mut request = Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header("accept", "application/x-ndjson");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).expect("request should build"))
+            .await
+            .expect("request should be handled");
+        let status = response.status();
+        if status == StatusCode::OK {
+            assert_eq!(response.headers()["content-type"], ndjson::CONTENT_TYPE);
+        }
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body should decode");
+        let lines = std::str::from_utf8(&bytes)
+            .expect("utf-8 body")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+            .collect();
+        (status, lines)
+    }
+
+    #[tokio::test]
+    async fn listings_stream_as_ndjson_across_pages() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = Arc::new(test_state(&temp_dir));
+        let app = build_app(Arc::clone(&state));
+        let mut addresses = Vec::new();
+        for _ in 0..3 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            addresses.push(body["wallet_address"].as_str().expect("address").to_owned());
+        }
+        state
+            .keystore
+            .save_watch_wallet(&kc_storage::WatchOnlyWalletRecord {
+                wallet_address: "0xwatch".to_owned(),
+                chain: FLOWCORTEX_L1.to_owned(),
+                created_at_epoch_ms: 0,
+            })
+            .expect("watch wallet should save");
+        addresses.push("0xwatch".to_owned());
+        addresses.sort();
+
+        let (status, wallets) = send_ndjson(&app, "/wallet/list?offset=1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let listed: Vec<_> = wallets.iter().map(|wallet| wallet["wallet_address"].clone()).collect();
+        assert_eq!(listed, addresses[1..]);
+
+        // More events than one page, so the stream has to follow cursors.
+        let event_count = ndjson::PAGE_SIZE + 5;
+        for index in 0..event_count {
+            state
+                .keystore
+                .append_audit_event(kc_storage::AuditEventRecord {
+                    event_id: format!("stream-{index:04}"),
+                    event_type: "stream_test".to_owned(),
+                    wallet_address: None,
+                    user_id: None,
+                    chain: None,
+                    outcome: "success".to_owned(),
+                    message: None,
+                    timestamp_epoch_ms: 1_760_000_000_000 + index as u128,
+                    tenant: None,
+                })
+                .expect("audit event should append");
+        }
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let (status, events) =
+            send_ndjson(&app, "/ops/audit?event_type=stream_test", Some(&token)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(events.len(), event_count, "no 500 cap when streaming");
+        assert_eq!(events[0]["event_id"], format!("stream-{:04}", event_count - 1));
+        assert_eq!(events[event_count - 1]["event_id"], "stream-0000");
+        let (_, capped) =
+            send_ndjson(&app, "/ops/audit?event_type=stream_test&limit=3", Some(&token)).await;
+        assert_eq!(capped.len(), 3);
+        let (unauthorized, _) = send_ndjson(&app, "/ops/audit", None).await;
+        assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+
+        for nonce in 1..=2 {
+            let (status, _) = send_json(
+                &app,
+                Method::POST,
+                "/wallet/submit",
+                json!({
+                    "from": addresses[0],
+                    "to": addresses[1],
+                    "amount": "1",
+                    "asset": "PROOF",
+                    "chain": "flowcortex-l1",
+                    "nonce": nonce
+                }),
+                vec![],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        let (_, transactions) =
+            send_ndjson(&app, &format!("/wallet/{}/transactions", addresses[1]), None).await;
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0]["to"], addresses[1].as_str());
+        assert!(
+            transactions[0]["submitted_at_epoch_ms"].as_u64()
+                >= transactions[1]["submitted_at_epoch_ms"].as_u64()
+        );
+    }
+
+    #[tokio::test]
+    async fn deleted_wallet_restores_through_ops_undelete() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let address = created["wallet_address"].as_str().expect("address").to_owned();
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+        let (status, deleted) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/delete",
+            json!({ "wallet_address": address, "reason": "customer request" }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let purge_after = deleted["purge_after_epoch_ms"].as_u64().expect("purge_after_epoch_ms");
+        assert!(u128::from(purge_after) > epoch_ms().expect("clock"));
+
+        let (_, list) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(list["total"], 0);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/ops/wallets/deleted")
+            .header("authorization", auth_value.clone())
+            .body(Body::empty())
+            .expect("request should build");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        let tombstones: Value = serde_json::from_slice(&bytes).expect("json");
+        assert_eq!(tombstones["total"], 1);
+        assert_eq!(tombstones["wallets"][0]["wallet_address"], address.as_str());
+        assert_eq!(tombstones["wallets"][0]["reason"], "customer request");
+        assert_eq!(tombstones["wallets"][0]["actor"], "ops-1");
+
+        let (status, restored) = send_json(
+            &app,
+            Method::POST,
+            "/ops/wallets/undelete",
+            json!({ "wallet_address": address }),
+            vec![("authorization", auth_value.clone())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(restored["restored"], true);
+
+        let (_, list) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(list["total"], 1);
+        assert_eq!(list["wallets"][0]["wallet_address"], address.as_str());
+
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/ops/wallets/undelete",
+            json!({ "wallet_address": address }),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn dormant_report_lists_wallets_without_recent_activity() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+        let now = epoch_ms().expect("clock");
+        let day = 24 * 60 * 60 * 1000;
+        for (address, created_days_ago) in [("0xdormant", 200), ("0xsender", 200), ("0xfresh", 10)] {
+            keystore
+                .save_encrypted_key(address, vec![1, 2, 3])
+                .await
+                .expect("key should save");
+            keystore
+                .save_wallet_metadata(&WalletMetadataRecord {
+                    wallet_address: address.to_owned(),
+                    label: None,
+                    created_at_epoch_ms: Some(now - created_days_ago * day),
+                    key_scheme: KEY_SCHEME_ED25519.to_owned(),
+                    usage: WalletUsage::default(),
+                })
+                .expect("metadata should save");
+        }
+        keystore
+            .append_audit_event(kc_storage::AuditEventRecord {
+                event_id: String::new(),
+                event_type: "auth_bind".to_owned(),
+                wallet_address: Some("0xdormant".to_owned()),
+                user_id: Some("user-1".to_owned()),
+                chain: Some(FLOWCORTEX_L1.to_owned()),
+                outcome: "success".to_owned(),
+                message: None,
+                timestamp_epoch_ms: now - 120 * day,
+                tenant: None,
+            })
+            .expect("audit event should append");
+        keystore
+            .save_submission_atomic(
+                &kc_storage::SubmittedTxRecord {
+                    tx_hash: "0xtx-recent".to_owned(),
+                    status: "confirmed".to_owned(),
+                    accepted: true,
+                    chain: FLOWCORTEX_L1.to_owned(),
+                    from: "0xsender".to_owned(),
+                    to: "0xelsewhere".to_owned(),
+                    asset: "PROOF".to_owned(),
+                    amount: "1".to_owned(),
+                    submitted_at_epoch_ms: now - 5 * day,
+                    block_height: None,
+                    confirmations: None,
+                    status_history: Vec::new(),
+                },
+                None,
+                None,
+            )
+            .expect("submission should save");
+
+        let (unauthorized, _) = send_empty(&app, Method::GET, "/ops/reports/dormant").await;
+        assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+        )];
+
+        let (status, body) =
+            send_json(&app, Method::GET, "/ops/reports/dormant?inactive_days=90", json!({}), auth.clone()).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["scanned"], 3);
+        assert_eq!(body["total"], 1);
+        let wallet = &body["wallets"][0];
+        assert_eq!(wallet["wallet_address"], "0xdormant");
+        assert_eq!(wallet["last_activity_epoch_ms"], json!(now - 120 * day));
+        assert_eq!(
+            wallet["balances"].as_array().expect("balances").len(),
+            chain_config::flowcortex_assets().len()
+        );
+
+        let (_, body) =
+            send_json(&app, Method::GET, "/ops/reports/dormant?inactive_days=150", json!({}), auth.clone()).await;
+        assert_eq!(body["total"], 0);
+
+        let (status, _) =
+            send_json(&app, Method::GET, "/ops/reports/dormant?inactive_days=0", json!({}), auth).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn wallet_usage_counts_signatures_and_submissions() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"]
+            .as_str()
+            .expect("wallet_address should be string")
+            .to_owned();
+
+        let (sign_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({
+                "wallet_address": wallet_address,
+                "payload": base64::engine::general_purpose::STANDARD.encode("hello-sign"),
+                "purpose": "proof"
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(sign_status, StatusCode::OK);
+        let (submit_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": "1000",
+                "asset": "FloweR",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(submit_status, StatusCode::OK);
+
+        let (_, list_body) = send_empty(&app, Method::GET, "/wallet/list").await;
+        let summary = &list_body["wallets"][0];
+        assert_eq!(summary["signature_count"], 2);
+        assert!(summary["last_signed_at_epoch_ms"].as_u64().is_some());
+        assert_eq!(summary["last_submitted_at_epoch_ms"], summary["last_signed_at_epoch_ms"]);
+
+        let (status_code, status_body) = send_json(
+            &app,
+            Method::POST,
+            "/fortressdigital/wallet-status",
+            json!({ "wallet_address": wallet_address, "chain": "flowcortex-l1" }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(status_body["signature_count"], 2);
+        assert_eq!(status_body["signature_frequency_hint"], "low");
+    }
+
+    #[tokio::test]
+    async fn escrow_notifies_approvers_with_retries_and_signed_link() {
+        use std::sync::Mutex;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Received {
+            hook_calls: AtomicUsize,
+            hook: Mutex<Option<(String, Value)>>,
+            slack: Mutex<Option<Value>>,
+        }
+        let received = Arc::new(Received::default());
+        let mock = Router::new()
+            .route(
+                "/hook",
+                post(|State(received): State<Arc<Received>>, headers: HeaderMap, body: String| async move {
+                    if received.hook_calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return StatusCode::SERVICE_UNAVAILABLE;
+                    }
+                    let signature = headers["x-keycortex-signature"].to_str().unwrap_or_default().to_owned();
+                    *received.hook.lock().unwrap() =
+                        Some((signature, serde_json::from_str(&body).unwrap_or_default()));
+                    StatusCode::OK
+                }),
+            )
+            .route(
+                "/slack",
+                post(|State(received): State<Arc<Received>>, Json(body): Json<Value>| async move {
+                    *received.slack.lock().unwrap() = Some(body);
+                    StatusCode::OK
+                }),
+            )
+            .with_state(Arc::clone(&received));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let mock_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        tokio::spawn(async move { axum::serve(listener, mock).await });
+
+        let channels = serde_json::from_value(json!([
+            {"name": "ops-hook", "kind": "webhook", "url": format!("{mock_url}/hook"), "secret": "hook-secret"},
+            {"name": "approvals", "kind": "slack", "url": format!("{mock_url}/slack")},
+            {"name": "email", "kind": "smtp", "relay": "127.0.0.1", "port": 9, "tls": "none", "from": "KeyCortex <noreply@example.com>"}
+        ]))
+        .expect("channels");
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        state.approval_notifier = Some(Arc::new(
+            notify::ApprovalNotifier::new(
+                channels,
+                "https://keycortex.example.com/",
+                "approval-link-secret",
+                3,
+                Duration::from_millis(10),
+            )
+            .expect("notifier"),
+        ));
+        let app = build_app(state);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .expect("authorization header should build");
+
+        let mut wallets = Vec::new();
+        for _ in 0..2 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            wallets.push(body["wallet_address"].as_str().expect("wallet_address").to_owned());
+        }
+        let (status, escrow) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/escrow",
+            json!({
+                "from": wallets[0],
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "25",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "approver_wallet": wallets[1]
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{escrow}");
+        let transfer_id = escrow["transfer_id"].as_str().expect("transfer_id").to_owned();
+
+        let uri = format!("/ops/approvals/notifications?transfer_id={transfer_id}");
+        let mut notifications = Value::Null;
+        for _ in 0..100 {
+            let (status, body) = send_json(
+                &app,
+                Method::GET,
+                &uri,
+                json!({}),
+                vec![("authorization", auth_value.clone())],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK, "{body}");
+            notifications = body;
+            let settled = notifications["notifications"]
+                .as_array()
+                .expect("notifications")
+                .iter()
+                .all(|record| record["status"] != "pending");
+            if settled {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(notifications["total"], 3, "{notifications}");
+        let by_channel = |name: &str| {
+            notifications["notifications"]
+                .as_array()
+                .expect("notifications")
+                .iter()
+                .find(|record| record["channel"] == name)
+                .cloned()
+                .expect("channel record")
+        };
+        let hook = by_channel("ops-hook");
+        assert_eq!(hook["status"], "delivered");
+        assert_eq!(hook["attempts"], 2);
+        assert_eq!(hook["last_error"], Value::Null);
+        assert_eq!(by_channel("approvals")["status"], "delivered");
+        let email = by_channel("email");
+        assert_eq!(email["status"], "skipped");
+        assert_eq!(email["last_error"], "approver wallet has no email");
+
+        let (signature, event) = received.hook.lock().unwrap().clone().expect("webhook event");
+        assert!(signature.starts_with("sha256="), "{signature}");
+        assert_eq!(event["event"], "escrow_approval_requested");
+        assert_eq!(event["transfer_id"], transfer_id);
+        assert_eq!(event["approver_wallet"], wallets[1]);
+        let slack = received.slack.lock().unwrap().clone().expect("slack message");
+        let link = event["approval_url"].as_str().expect("approval_url").to_owned();
+        assert!(slack["text"].as_str().expect("text").contains(&link));
+
+        let path = link
+            .strip_prefix("https://keycortex.example.com")
+            .expect("link under the base url");
+        assert!(path.starts_with(&format!("/wallet/escrow/{transfer_id}?expires=")), "{path}");
+        let (status, body) = send_empty(&app, Method::GET, path).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["transfer_id"], transfer_id);
+        let last = if path.ends_with('0') { '1' } else { '0' };
+        let tampered = format!("{}{last}", &path[..path.len() - 1]);
+        let (status, _) = send_empty(&app, Method::GET, &tampered).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, failed) = send_json(
+            &app,
+            Method::GET,
+            "/ops/approvals/notifications?status=failed",
+            json!({}),
+            vec![("authorization", auth_value)],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(failed["total"], 0);
+        let (status, _) = send_empty(&app, Method::GET, "/ops/approvals/notifications").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn fee_estimate_quotes_the_adapter_fee() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        *state.chains.write().expect("chain table") = chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            Arc::new(
+                kc_chain_flowcortex::FlowCortexAdapter::for_chain(FLOWCORTEX_L1, "http://127.0.0.1:9")
+                    .with_flat_fee(2500),
+            ),
+        );
+        let app = build_app(state);
+        let transfer = json!({
+            "from": "0x000000000000000000000000000000000000f00d",
+            "to": "0x000000000000000000000000000000000000cafe",
+            "amount": "1000",
+            "asset": "FloweR",
+            "chain": "flowcortex-l1"
+        });
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/fee-estimate", transfer.clone(), vec![]).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["fee"], "2500");
+        assert_eq!(body["fee_asset"], "PROOF");
+        assert_eq!(body["amount"], "1000");
+
+        for (field, value, expected) in [
+            ("to", " ", "to is required"),
+            ("amount", "1.5", "amount must be a non-negative integer"),
+            ("chain", "solana", "chain 'solana' is not enabled"),
+        ] {
+            let mut request = transfer.clone();
+            request[field] = json!(value);
+            let (status, body) = send_json(&app, Method::POST, "/wallet/fee-estimate", request, vec![]).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn transfer_memo_is_readable_only_by_the_recipient() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let mut wallets = Vec::new();
+        for _ in 0..2 {
+            let (status, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            assert_eq!(status, StatusCode::OK);
+            wallets.push(body["wallet_address"].as_str().expect("wallet_address").to_owned());
+        }
+        let (sender, recipient) = (&wallets[0], &wallets[1]);
+        let transfer = json!({
+            "from": sender,
+            "to": "0x00000000000000000000000000000000deadbeef",
+            "amount": "1000",
+            "asset": "FloweR",
+            "chain": "flowcortex-l1",
+            "nonce": 1,
+            "memo": "rent for march"
+        });
+
+        let (status, body) = send_json(&app, Method::POST, "/wallet/submit", transfer.clone(), vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "memo recipient must be a KeyCortex wallet");
+
+        let mut request = transfer.clone();
+        request["to"] = json!(recipient);
+        request["memo"] = json!("x".repeat(513));
+        let (status, body) = send_json(&app, Method::POST, "/wallet/submit", request.clone(), vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "memo must be at most 512 bytes");
+
+        request["memo"] = transfer["memo"].clone();
+        let (status, submitted) = send_json(&app, Method::POST, "/wallet/submit", request, vec![]).await;
+        assert_eq!(status, StatusCode::OK, "{submitted}");
+        let tx_hash = submitted["tx_hash"].as_str().expect("tx_hash").to_owned();
+
+        let (status, history) =
+            send_empty(&app, Method::GET, &format!("/wallet/{recipient}/transactions")).await;
+        assert_eq!(status, StatusCode::OK);
+        let ciphertext = history["transactions"][0]["encrypted_memo"]
+            .as_str()
+            .expect("history carries the sealed memo")
+            .to_owned();
+        assert!(!ciphertext.contains(&to_hex(b"rent")));
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/{recipient}/decrypt"),
+            json!({ "tx_hash": tx_hash }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["memo"], "rent for march");
+        assert_eq!(body["tx_hash"], tx_hash);
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/{sender}/decrypt"),
+            json!({ "tx_hash": tx_hash }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"], "memo is not addressed to this wallet");
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/{sender}/decrypt"),
+            json!({ "ciphertext": ciphertext }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "memo cannot be decrypted by this wallet");
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/{recipient}/decrypt"),
+            json!({ "ciphertext": ciphertext }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["memo"], "rent for march");
+    }
+
+    #[tokio::test]
+    async fn chain_txs_lists_on_chain_transfers_of_a_wallet() {
+        let node = Router::new().route(
+            "/blocks",
+            get(|| async {
+                Json(json!([
+                    { "height": 8, "transactions": [
+                        { "hash": "0xtx-ours", "from": "0xa", "to": "0xb", "token": "PROOF", "amount": "5" },
+                        { "hash": "0xtx-other", "from": "0xc", "to": "0xd", "token": "PROOF", "amount": "6" }
+                    ] },
+                    { "height": 7, "transactions": [
+                        { "hash": "0xtx-elsewhere", "from": "0xe", "to": "0xa", "token": "FloweR", "amount": "7" }
+                    ] }
+                ]))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let node_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        tokio::spawn(async move { axum::serve(listener, node).await });
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        *state.chains.write().expect("chain table") = chains::ChainTable::with_builtin(
+            &node_url,
+            Arc::new(kc_chain_flowcortex::FlowCortexAdapter::for_chain(FLOWCORTEX_L1, &node_url)),
+        );
+        state
+            .keystore
+            .save_submission_atomic(
+                &kc_storage::SubmittedTxRecord {
+                    tx_hash: "0xtx-ours".to_owned(),
+                    status: "confirmed".to_owned(),
+                    accepted: true,
+                    chain: FLOWCORTEX_L1.to_owned(),
+                    from: "0xa".to_owned(),
+                    to: "0xb".to_owned(),
+                    asset: "PROOF".to_owned(),
+                    amount: "5".to_owned(),
+                    submitted_at_epoch_ms: 1,
+                    block_height: None,
+                    confirmations: None,
+                    status_history: Vec::new(),
+                },
+                None,
+                None,
+            )
+            .expect("submission should save");
+        let app = build_app(state);
+
+        let (status, body) = send_empty(&app, Method::GET, "/wallet/txs?wallet_address=0xa&limit=1").await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["chain"], FLOWCORTEX_L1);
+        assert_eq!(body["transactions"][0]["tx_hash"], "0xtx-ours");
+        assert_eq!(body["transactions"][0]["block_height"], 8);
+        assert_eq!(body["transactions"][0]["submitted_by_keycortex"], true);
+
+        let cursor = body["next_cursor"].as_str().expect("more pages");
+        let (status, body) =
+            send_empty(&app, Method::GET, &format!("/wallet/txs?wallet_address=0xa&cursor={cursor}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["transactions"][0]["tx_hash"], "0xtx-elsewhere");
+        assert_eq!(body["transactions"][0]["submitted_by_keycortex"], false);
+        assert!(body.get("next_cursor").is_none());
+
+        let (status, body) = send_empty(&app, Method::GET, "/wallet/txs?wallet_address=0xa&chain=solana").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "chain 'solana' is not enabled");
+    }
+
+    #[tokio::test]
+    async fn event_publisher_mirrors_outbox_to_kafka_and_keeps_unacked_events() {
+        use std::sync::Mutex;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        #[derive(Default)]
+        struct Proxy {
+            down: AtomicBool,
+            records: Mutex<Vec<Value>>,
+        }
+
+        let proxy = Arc::new(Proxy::default());
+        proxy.down.store(true, Ordering::SeqCst);
+        let mock = Router::new()
+            .route(
+                "/topics/custody",
+                post(|State(proxy): State<Arc<Proxy>>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                    assert_eq!(headers["content-type"], "application/vnd.kafka.json.v2+json");
+                    if proxy.down.load(Ordering::SeqCst) {
+                        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({})));
+                    }
+                    let records = body["records"].as_array().expect("records").clone();
+                    let offsets: Vec<_> = records
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, _)| json!({"partition": 0, "offset": offset, "error_code": null, "error": null}))
+                        .collect();
+                    proxy.records.lock().unwrap().extend(records);
+                    (StatusCode::OK, Json(json!({ "offsets": offsets })))
+                }),
+            )
+            .with_state(Arc::clone(&proxy));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let proxy_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        tokio::spawn(async move { axum::serve(listener, mock).await });
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        state.keystore = Arc::new(
+            RocksDbKeystore::open_default(temp_dir.path().join("outbox.rocksdb").to_string_lossy().as_ref())
+                .expect("rocksdb should initialize")
+                .with_event_outbox(),
+        );
+        state.wallet_core = custody_core(
+            &state.keystore,
+            &state.encryption_key,
+            &state.signing_domain,
+            state.keystore.clone(),
+        );
+        state.nonces = Arc::new(NonceManager::new(
+            Arc::new(InMemoryNonceStore::default()),
+            state.keystore.clone(),
+        ));
+        let publisher = Arc::new(
+            publisher::EventPublisher::new("kafka", &proxy_url, "custody", Duration::from_millis(10))
+                .expect("publisher"),
+        );
+        state.event_publisher = Some(Arc::clone(&publisher));
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+        )];
+
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = created["wallet_address"].as_str().expect("wallet_address").to_owned();
+        let (status, submitted) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": "5",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{submitted}");
+
+        assert!(publisher.drain(&keystore).await.is_err());
+        let (status, report) = send_json(&app, Method::GET, "/ops/events/publisher", json!({}), auth.clone()).await;
+        assert_eq!(status, StatusCode::OK, "{report}");
+        assert_eq!(report["enabled"], true);
+        assert_eq!(report["broker"], "kafka");
+        assert_eq!(report["published"], 0);
+        assert!(report["pending"].as_u64().unwrap() >= 1, "{report}");
+        assert!(report["last_error"].as_str().unwrap().contains("500"), "{report}");
+
+        proxy.down.store(false, Ordering::SeqCst);
+        let published = publisher.drain(&keystore).await.expect("drain");
+        assert_eq!(keystore.count_outbox_events().unwrap(), 0);
+        let records = proxy.records.lock().unwrap().clone();
+        assert_eq!(records.len(), published);
+        let tx_event = records
+            .iter()
+            .find(|record| record["value"]["kind"] == "tx_status")
+            .expect("tx status event");
+        assert_eq!(tx_event["key"], tx_event["value"]["event_id"]);
+        assert_eq!(tx_event["value"]["data"]["tx_hash"], submitted["tx_hash"]);
+        assert_eq!(tx_event["value"]["data"]["from"], wallet_address.as_str());
+        assert!(
+            records
+                .iter()
+                .any(|record| record["value"]["kind"] == "audit"
+                    && record["value"]["data"]["event_type"] == "ops_access"),
+            "{records:?}"
+        );
+
+        let (_, report) = send_json(&app, Method::GET, "/ops/events/publisher", json!({}), auth).await;
+        assert_eq!(report["published"], published);
+        assert_eq!(report["last_error"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn chain_queried_nonce_is_reconciled_with_the_chain() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let account_nonce = Arc::new(AtomicU64::new(5));
+        *state.chains.write().expect("chain table") = chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            Arc::new(StrategyMockAdapter {
+                strategy: NonceStrategy::ChainQueried,
+                account_nonce: Arc::clone(&account_nonce),
+            }),
+        );
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = created["wallet_address"].as_str().expect("address").to_owned();
+        let submit = |nonce: u64| {
+            let body = json!({
+                "from": wallet,
+                "to": "0x000000000000000000000000000000000000cafe",
+                "amount": "1",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": nonce
+            });
+            let app = app.clone();
+            async move { send_json(&app, Method::POST, "/wallet/submit", body, vec![]).await }
+        };
+        let local_last = || keystore.load_wallet_nonce(&wallet).unwrap().map(|record| record.last_nonce);
+
+        assert_eq!(submit(5).await.0, StatusCode::OK);
+        assert_eq!(local_last(), Some(5));
+
+        // Another service spends 6..=8 with the same key.
+        account_nonce.store(9, Ordering::SeqCst);
+        let (_, nonce_body) = send_empty(&app, Method::GET, &format!("/wallet/nonce?wallet_address={wallet}")).await;
+        assert_eq!(nonce_body["next_nonce"], 9);
+        assert_eq!(local_last(), Some(8));
+        assert_eq!(submit(9).await.0, StatusCode::OK);
+        assert_eq!(local_last(), Some(9));
+
+        // The chain drops nonce 9, so it can be used again.
+        account_nonce.store(9, Ordering::SeqCst);
+        let (status, body) = submit(9).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let (status, body) = submit(9).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().is_some_and(|e| e.contains("account nonce is 10")), "{body}");
+
+        // A chain that has never seen the account clears the local nonce.
+        account_nonce.store(0, Ordering::SeqCst);
+        assert_eq!(submit(0).await.0, StatusCode::OK);
+        assert_eq!(local_last(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn readyz_reports_chain_reachability_without_failing_readiness() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = Arc::new(test_state(&temp_dir));
+        let app = build_app(Arc::clone(&state));
+
+        let (status, body) = send_empty(&app, Method::GET, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["chains"]["flowcortex-l1"]["reachable"], Value::Null);
+        assert_eq!(body["chains_reachable"], true);
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("header should build"),
+        )];
+        let (added, _) = send_json(
+            &app,
+            Method::POST,
+            "/ops/chains",
+            json!({ "chain_id": "sepolia", "kind": "evm", "endpoint": "http://127.0.0.1:9" }),
+            auth,
+        )
+        .await;
+        assert_eq!(added, StatusCode::OK);
+
+        chains::refresh_health(&state).await;
+        let (status, body) = send_empty(&app, Method::GET, "/readyz").await;
+        assert_eq!(status, StatusCode::OK, "a chain outage is not a wallet-service failure");
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["chains_reachable"], false);
+        let flowcortex = &body["chains"]["flowcortex-l1"];
+        assert_eq!(flowcortex["reachable"], true);
+        assert_eq!(flowcortex["block_height"], 7);
+        assert!(flowcortex["checked_at_epoch_ms"].as_u64().is_some());
+        let sepolia = &body["chains"]["sepolia"];
+        assert_eq!(sepolia["reachable"], false);
+        assert!(sepolia["detail"].as_str().expect("detail").contains("eth_blockNumber"));
+    }
+
+    #[tokio::test]
+    async fn chains_toml_registers_adapters_and_their_assets() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let config = kc_chain_client::ChainRegistryConfig::parse(
+            r#"
+            [[chain]]
+            slug = "flowcortex-testnet"
+            adapter = "flowcortex"
+            endpoints = ["http://127.0.0.1:9/", "http://127.0.0.1:10"]
+
+            [[chain]]
+            slug = "sepolia"
+            adapter = "EVM"
+            endpoints = ["http://127.0.0.1:9"]
+            evm_chain_id = 11155111
+            explorer_tx_url = "https://sepolia.example/tx/{tx_hash}"
+
+            [chain.assets.ETH]
+            decimals = 18
+            fee_payment = true
+
+            [chain.assets.USDC]
+            decimals = 6
+            asset_type = "erc20"
+            contract = "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
+            "#,
+        )
+        .expect("config should parse");
+        state.chains.write().expect("chain table").load_config(&config).expect("config should load");
+        let app = build_app(state);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("header should build"),
+        )];
+
+        let (_, list_body) = send_json(&app, Method::GET, "/ops/chains", json!({}), auth).await;
+        assert_eq!(list_body["total"], 3);
+        let chain = |id: &str| {
+            list_body["chains"]
+                .as_array()
+                .expect("chains")
+                .iter()
+                .find(|c| c["chain_id"] == id)
+                .cloned()
+                .expect("chain listed")
+        };
+        let testnet = chain("flowcortex-testnet");
+        assert_eq!(testnet["endpoint"], "http://127.0.0.1:9");
+        assert_eq!(testnet["fallback_endpoints"], json!(["http://127.0.0.1:10"]));
+        assert_eq!(testnet["updated_by"], "config");
+        assert_eq!(testnet["active"], true);
+        let sepolia = chain("sepolia");
+        assert_eq!(sepolia["kind"], "evm");
+        assert_eq!(sepolia["erc20_tokens"]["USDC"], "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238");
+        assert_eq!(sepolia["active"], true);
+
+        // Configured assets replace the FlowCortex defaults for that chain only.
+        let (status, body) =
+            send_empty(&app, Method::GET, "/wallet/balance?wallet_address=0x0000000000000000000000000000000000000abc&chain=sepolia&asset=PROOF").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "unsupported asset for MVP; only ETH and USDC are enabled");
+        let (status, body) =
+            send_empty(&app, Method::GET, "/wallet/balance?wallet_address=0x0000000000000000000000000000000000000abc&chain=flowcortex-testnet&asset=ETH").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "unsupported asset for MVP; only PROOF and FloweR are enabled");
+
+        let mut table = chains::ChainTable::with_builtin("http://127.0.0.1:9", Arc::new(MockChainAdapter));
+        let example = include_str!("../../../deploy/chains.example.toml");
+        let example = kc_chain_client::ChainRegistryConfig::parse(example).expect("example should parse");
+        table.load_config(&example).expect("example should load");
+        let sepolia_assets = table.assets("sepolia").expect("sepolia assets");
+        assert_eq!(sepolia_assets.len(), 2);
+        let usdc = sepolia_assets.iter().find(|asset| asset.symbol == "USDC").expect("USDC");
+        assert_eq!(usdc.contract.as_deref(), Some("0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"));
+        for (toml, expected) in [
+            (
+                "[[chain]]\nslug = \"sepolia\"\nadapter = \"evm\"\nendpoints = [\"http://a\", \"http://b\"]",
+                "only flowcortex chains fail over",
+            ),
+            ("[[chain]]\nslug = \"x\"\nadapter = \"solana\"\nendpoints = [\"http://a\"]", "unsupported kind"),
+            (
+                "[[chain]]\nslug = \"hub\"\nadapter = \"cosmos\"\nendpoints = [\"http://a\"]",
+                "bech32_prefix is required",
+            ),
+        ] {
+            let config = kc_chain_client::ChainRegistryConfig::parse(toml).expect("shape is valid");
+            let err = format!("{:#}", table.load_config(&config).expect_err(expected));
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn chain_adapters_share_the_configured_http_client() {
+        let slow_node = axum::Router::new().route(
+            "/",
+            axum::routing::post(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Json(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x1" }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            axum::serve(listener, slow_node).await.ok();
+        });
+
+        let http = kc_chain_client::SharedHttp::new(&kc_chain_client::HttpClientConfig {
+            request_timeout: Duration::from_millis(200),
+            ..Default::default()
+        })
+        .expect("clients should build");
+        let mut table = chains::ChainTable::with_builtin("http://127.0.0.1:9", Arc::new(MockChainAdapter)).with_http(http);
+        let config = kc_chain_client::ChainRegistryConfig::parse(&format!(
+            "[[chain]]\nslug = \"slow\"\nadapter = \"evm\"\nendpoints = [\"http://{address}\"]"
+        ))
+        .expect("config should parse");
+        table.load_config(&config).expect("config should load");
+
+        let started = std::time::Instant::now();
+        let health = table.adapter("slow").expect("adapter").health().await;
+        assert!(!health.reachable);
+        assert!(started.elapsed() < Duration::from_secs(2), "the shared client's timeout applies");
+    }
+
+    #[tokio::test]
+    async fn wallet_balance_surfaces_injected_chain_failures() {
+        use kc_chain_mock::{MockAdapter, Operation};
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mock = Arc::new(MockAdapter::new(FLOWCORTEX_L1).with_balance("0x0000000000000000000000000000000000000abc", "PROOF", 42));
+        let mut state = test_state(&temp_dir);
+        state.chains = Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            mock.clone(),
+        )));
+        let app = build_app(state);
+        let uri = "/wallet/balance?wallet_address=0x0000000000000000000000000000000000000abc&asset=PROOF";
+
+        let (status, body) = send_empty(&app, Method::GET, uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["amount"], "42");
+
+        mock.fail_times(Operation::Balance, 1, "node unavailable");
+        let (status, body) = send_empty(&app, Method::GET, uri).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["error"], "flowcortex-l1: node unavailable");
+        assert_eq!((body["code"].as_str(), body["retryable"].as_bool()), (Some("upstream"), Some(true)));
+        let (status, _) = send_empty(&app, Method::GET, uri).await;
+        assert_eq!(status, StatusCode::OK);
+
+        mock.set_latency(Duration::from_millis(200));
+        let timeout = vec![(deadline::REQUEST_TIMEOUT_HEADER, HeaderValue::from_static("50"))];
+        let (status, body) = send_json(&app, Method::GET, uri, json!({}), timeout).await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!((body["code"].as_str(), body["retryable"].as_bool()), (Some("timeout"), Some(true)));
+        assert_eq!(mock.calls(Operation::Balance), 4);
+    }
+
+    #[tokio::test]
+    async fn wallet_submit_dry_run_signs_without_broadcasting() {
+        use kc_chain_mock::MockAdapter;
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mock = Arc::new(MockAdapter::new(FLOWCORTEX_L1).with_fee(5, "PROOF"));
+        let mut state = test_state(&temp_dir);
+        state.chains = Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            mock.clone(),
+        )));
+        let app = build_app(state);
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = created["wallet_address"].as_str().unwrap().to_owned();
+        mock.set_balance(&wallet_address, "PROOF", 100);
+        let transfer = |amount: &str, dry_run: bool| {
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": amount,
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1,
+                "dry_run": dry_run
+            })
+        };
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer("95", true), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["accepted"], false);
+        assert_eq!(body["tx_hash"], "");
+        assert!(!body["signature"].as_str().unwrap().is_empty());
+        assert_eq!(body["simulation"], json!({ "would_accept": true, "fee": "5", "fee_asset": "PROOF" }));
+        assert!(mock.submitted().is_empty());
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer("96", true), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["simulation"]["would_accept"], false);
+        assert_eq!(
+            body["simulation"]["reason"],
+            "insufficient balance: 100 PROOF available, 101 needed"
+        );
+
+        // The dry runs left nonce 1 free.
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer("95", false), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["accepted"], true);
+        assert!(body.get("simulation").is_none());
+        assert_eq!(mock.submitted().len(), 1);
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/submit", transfer("1", true), vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("nonce replay"));
+        assert_eq!(body["code"], "nonce_conflict");
+        assert_eq!(body["retryable"], false);
+    }
+
+    #[tokio::test]
+    async fn wallet_bridge_moves_funds_across_chains_in_two_legs() {
+        use kc_chain_mock::MockAdapter;
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let source = Arc::new(MockAdapter::new(FLOWCORTEX_L1));
+        let target = Arc::new(MockAdapter::new("flowcortex-l2"));
+        let passphrases = [
+            "source bridge vault orange kettle 2931",
+            "target bridge vault violet anchor 7714",
+        ];
+        let [source_bridge, target_bridge] =
+            passphrases.map(|passphrase| Ed25519Signer::from_passphrase(passphrase).wallet_address());
+        let mut state = test_state(&temp_dir);
+        let mut table = chains::ChainTable::with_builtin("http://127.0.0.1:9", source.clone());
+        table.register(target.clone());
+        state.chains = Arc::new(StdRwLock::new(table));
+        state.bridge_wallets = HashMap::from([
+            (FLOWCORTEX_L1.to_owned(), source_bridge.clone()),
+            ("flowcortex-l2".to_owned(), target_bridge.clone()),
+        ]);
+        let app = build_app(state);
+        for passphrase in passphrases {
+            let (status, _) =
+                send_json(&app, Method::POST, "/wallet/create", json!({ "passphrase": passphrase }), vec![]).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let sender = created["wallet_address"].as_str().unwrap().to_owned();
+        source.set_balance(&sender, "PROOF", 100);
+        target.set_balance(&target_bridge, "PROOF", 1000);
+        let bridge = |source_chain: &str| {
+            json!({
+                "from": sender,
+                "to": "0x000000000000000000000000000000000000face",
+                "amount": "40",
+                "asset": "PROOF",
+                "source_chain": source_chain,
+                "target_chain": "flowcortex-l2"
+            })
+        };
+
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/bridge", bridge("flowcortex-l2"), vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "source_chain and target_chain must differ");
+
+        // The mock names its first transfer's hash after the submit count.
+        source.set_tx_status("0xmock0000000000000001", "pending", None);
+        let (status, body) =
+            send_json(&app, Method::POST, "/wallet/bridge", bridge(FLOWCORTEX_L1), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "source_submitted");
+        assert_eq!(body["source_tx_hash"], "0xmock0000000000000001");
+        assert!(body["target_tx_hash"].is_null());
+        assert_eq!(source.balance(&source_bridge, "PROOF"), 40);
+        assert!(target.submitted().is_empty());
+
+        let uri = format!("/wallet/bridge/{}", body["bridge_id"].as_str().unwrap());
+        let (_, body) = send_empty(&app, Method::GET, &uri).await;
+        assert_eq!(body["status"], "source_submitted");
+
+        source.set_tx_status("0xmock0000000000000001", "confirmed", Some(1));
+        let (status, body) = send_empty(&app, Method::GET, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "completed");
+        assert_eq!(body["target_tx_hash"], "0xmock0000000000000001");
+        let history: Vec<_> = body["status_history"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|change| change["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            history,
+            ["pending", "source_submitted", "source_confirmed", "target_submitted", "completed"]
+        );
+        assert_eq!(target.balance("0x000000000000000000000000000000000000face", "PROOF"), 40);
+        assert_eq!(target.balance(&target_bridge, "PROOF"), 960);
+
+        let (status, body) = send_empty(&app, Method::GET, "/wallet/bridge/bridge-missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn malformed_identifiers_are_rejected_with_the_field_named() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let transfer = json!({
+            "from": created["wallet_address"],
+            "to": "0x000000000000000000000000000000000000cafe",
+            "amount": "1",
+            "asset": "PROOF",
+            "chain": "flowcortex-l1",
+            "nonce": 1,
+            "dry_run": true
+        });
+        let (status, body) = send_json(&app, Method::POST, "/wallet/submit", transfer.clone(), vec![]).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        for (field, value, expected) in [
+            ("from", "0xabc", "from must be a 0x address of 40 hex digits or a bech32 address"),
+            ("to", "cosmos1!", "to must be a 0x address of 40 hex digits or a bech32 address"),
+            ("to", " ", "to is required"),
+            ("asset", "$PROOF", "asset must be 1-32 letters, digits, '.', '-' or '_', starting with a letter"),
+            ("chain", "FlowCortex L1", "chain must be 1-64 characters of a-z, 0-9 and '-'"),
+        ] {
+            let mut request = transfer.clone();
+            request[field] = json!(value);
+            let (status, body) = send_json(&app, Method::POST, "/wallet/submit", request, vec![]).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["code"], "invalid_request");
+            assert_eq!(body["error"], expected);
+            assert_eq!(body["details"]["field"], field);
+        }
+
+        // Bech32 addresses pass the format check and are left to the chain.
+        let mut request = transfer.clone();
+        request["to"] = json!("cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu");
+        let (status, body) = send_json(&app, Method::POST, "/wallet/submit", request, vec![]).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/balances",
+            json!({ "wallet_addresses": [created["wallet_address"], "0xnot-hex"] }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], "wallet_addresses[1]");
+    }
+
+    #[tokio::test]
+    async fn v2_balance_carries_decimals_while_v1_keeps_its_shape() {
+        use kc_chain_mock::MockAdapter;
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let wallet = "0x0000000000000000000000000000000000000abc";
+        let mock = Arc::new(MockAdapter::new(FLOWCORTEX_L1).with_balance(wallet, "PROOF", 42));
+        let mut state = test_state(&temp_dir);
+        state.chains = Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            mock,
+        )));
+        let app = build_app(state);
+        let query = format!("wallet/balance?wallet_address={wallet}&asset=PROOF");
+
+        let (status, v1) = send_empty(&app, Method::GET, &format!("/{query}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(v1["amount"], "42");
+
+        let (status, v2) = send_empty(&app, Method::GET, &format!("/v2/{query}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(v2["wallet_address"], wallet);
+        assert_eq!(v2["amount"], json!({ "base_units": "42", "decimals": 18 }));
+
+        let (status, error) =
+            send_empty(&app, Method::GET, "/v2/wallet/balance?wallet_address=0xnot-hex").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "invalid_request");
+        assert_eq!(
+            error["message"],
+            "wallet_address must be a 0x address of 40 hex digits or a bech32 address"
+        );
+        assert!(error.get("error").is_none());
+        assert_eq!(error["details"]["field"], "wallet_address");
+    }
+
+    #[tokio::test]
+    async fn v2_listings_share_one_page_envelope() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = Arc::new(test_state(&temp_dir));
+        let app = build_app(Arc::clone(&state));
+        let mut addresses = Vec::new();
+        for _ in 0..3 {
+            let (_, body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            addresses.push(body["wallet_address"].as_str().expect("address").to_owned());
+        }
+        addresses.sort();
+
+        let (status, first) = send_empty(&app, Method::GET, "/v2/wallet/list?limit=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["total_estimate"], 3);
+        assert_eq!(first["items"].as_array().map(Vec::len), Some(2));
+        assert_eq!(first["next_cursor"], addresses[1].as_str());
+        let (_, last) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/v2/wallet/list?limit=2&cursor={}", addresses[1]),
+        )
+        .await;
+        assert_eq!(last["items"][0]["wallet_address"], addresses[2].as_str());
+        assert!(last.get("next_cursor").is_none());
+
+        for nonce in 1..=3 {
+            let (status, _) = send_json(
+                &app,
+                Method::POST,
+                "/wallet/submit",
+                json!({
+                    "from": addresses[0],
+                    "to": addresses[1],
+                    "amount": "1",
+                    "asset": "PROOF",
+                    "chain": "flowcortex-l1",
+                    "nonce": nonce
+                }),
+                vec![],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        let history = format!("/v2/wallet/{}/transactions?limit=2", addresses[0]);
+        let (_, page) = send_empty(&app, Method::GET, &history).await;
+        assert_eq!(page["items"].as_array().map(Vec::len), Some(2));
+        let cursor = page["next_cursor"].as_str().expect("more history").to_owned();
+        let (_, rest) = send_empty(&app, Method::GET, &format!("{history}&cursor={cursor}")).await;
+        assert_eq!(rest["items"].as_array().map(Vec::len), Some(1));
+        // v1 keeps its own field names for the same page.
+        let (_, v1) = send_empty(
+            &app,
+            Method::GET,
+            &format!("/wallet/{}/transactions?limit=2", addresses[0]),
+        )
+        .await;
+        assert_eq!(v1["transactions"], page["items"]);
+        assert_eq!(v1["next_cursor"], page["next_cursor"]);
+
+        for index in 0..2 {
+            state
+                .keystore
+                .append_audit_event(kc_storage::AuditEventRecord {
+                    event_id: format!("page-{index}"),
+                    event_type: "page_test".to_owned(),
+                    wallet_address: None,
+                    user_id: None,
+                    chain: None,
+                    outcome: "success".to_owned(),
+                    message: None,
+                    timestamp_epoch_ms: 1_760_000_000_000 + index,
+                    tenant: None,
+                })
+                .expect("audit event should append");
+        }
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+        )];
+        let (status, audit) =
+            send_json(&app, Method::GET, "/v2/ops/audit?event_type=page_test&limit=1", json!({}), auth.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(audit["items"][0]["event_id"], "page-1");
+        let cursor = audit["next_cursor"].as_str().expect("more events").to_owned();
+        let (_, older) = send_json(
+            &app,
+            Method::GET,
+            &format!("/v2/ops/audit?event_type=page_test&limit=1&cursor={cursor}"),
+            json!({}),
+            auth,
+        )
+        .await;
+        assert_eq!(older["items"][0]["event_id"], "page-0");
+        let (status, denied) = send_empty(&app, Method::GET, "/v2/ops/audit").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(denied["code"], "unauthorized");
+        assert!(denied["message"].is_string());
+    }
+
+    #[tokio::test]
+    async fn webhook_events_arrive_in_signed_envelopes() {
+        use kc_api_types::webhook::{DEFAULT_TOLERANCE_MS, WebhookEnvelope, WebhookError};
+        use std::sync::Mutex;
+
+        let received = Arc::new(Mutex::new(Vec::<WebhookEnvelope>::new()));
+        let mock = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(received): State<Arc<Mutex<Vec<WebhookEnvelope>>>>,
+                     Json(envelope): Json<WebhookEnvelope>| async move {
+                        received.lock().unwrap().push(envelope);
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state(Arc::clone(&received));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let mock_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        tokio::spawn(async move { axum::serve(listener, mock).await });
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mut state = test_state(&temp_dir);
+        let notifier = Arc::new(
+            webhooks::WebhookNotifier::new(&format!("{mock_url}/hook"), "webhook-secret-123")
+                .expect("notifier"),
+        );
+        state.webhook_notifier = Some(Arc::clone(&notifier));
+        let keystore = Arc::clone(&state.keystore);
+        tokio::spawn(webhooks::run(notifier, keystore.subscribe()));
+        let app = build_app(state);
+
+        let (status, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        assert_eq!(status, StatusCode::OK, "{created}");
+        keystore
+            .save_submitted_tx(&kc_storage::SubmittedTxRecord {
+                tx_hash: "0xtx-hook".to_owned(),
+                status: "pending".to_owned(),
+                accepted: true,
+                chain: FLOWCORTEX_L1.to_owned(),
+                from: created["wallet_address"].as_str().expect("wallet_address").to_owned(),
+                to: "0x000000000000000000000000000000000000cafe".to_owned(),
+                asset: "PROOF".to_owned(),
+                amount: "5".to_owned(),
+                submitted_at_epoch_ms: 1,
+                block_height: None,
+                confirmations: None,
+                status_history: Vec::new(),
+            })
+            .expect("submission should save");
+
+        for _ in 0..100 {
+            if received.lock().unwrap().len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let mut envelopes = received.lock().unwrap().clone();
+        envelopes.sort_by(|a, b| a.event_type.cmp(&b.event_type));
+        assert_eq!(envelopes.len(), 2, "{envelopes:?}");
+        let now = epoch_ms().expect("clock");
+        let secret = b"webhook-secret-123";
+
+        assert_eq!(envelopes[0].event_type, "tx_status_changed");
+        assert_eq!(
+            envelopes[0].verify(secret, now, DEFAULT_TOLERANCE_MS),
+            Ok(WebhookEvent::TxStatusChanged {
+                tx_hash: "0xtx-hook".to_owned(),
+                from: created["wallet_address"].as_str().expect("wallet_address").to_owned(),
+                previous_status: None,
+                status: "pending".to_owned(),
+            })
+        );
+        assert_eq!(
+            envelopes[1].verify(secret, now, DEFAULT_TOLERANCE_MS),
+            Ok(WebhookEvent::WalletCreated {
+                wallet_address: created["wallet_address"].as_str().expect("wallet_address").to_owned(),
+                public_key: created["public_key"].as_str().expect("public_key").to_owned(),
+                chain: FLOWCORTEX_L1.to_owned(),
+            })
+        );
+
+        let envelope = &envelopes[1];
+        assert_eq!(
+            envelope.verify(b"some-other-secret", now, DEFAULT_TOLERANCE_MS),
+            Err(WebhookError::Signature)
+        );
+        assert_eq!(
+            envelope.verify(secret, now + DEFAULT_TOLERANCE_MS + 1_000, DEFAULT_TOLERANCE_MS),
+            Err(WebhookError::Stale)
+        );
+        let mut tampered = envelope.clone();
+        tampered.payload = tampered.payload.replace(FLOWCORTEX_L1, "flowcortex-l2");
+        assert_eq!(
+            tampered.verify(secret, now, DEFAULT_TOLERANCE_MS),
+            Err(WebhookError::PayloadHash)
+        );
+    }
+
+    #[tokio::test]
+    async fn signed_payloads_must_be_canonical_tx_envelopes() {
+        use kc_api_types::TxEnvelopeV1;
+
+        let canonical = "from=0x000000000000000000000000000000000000beef;to=0x000000000000000000000000000000000000cafe;amount=1500;asset=PROOF;chain=flowcortex-l1;nonce=7";
+        let envelope = TxEnvelopeV1::parse(canonical).expect("canonical payload parses");
+        assert_eq!(envelope.amount, 1500);
+        assert_eq!(envelope.nonce, 7);
+        assert_eq!(envelope.canonical(), canonical);
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let malformed = "payload must be from=..;to=..;amount=..;asset=..;chain=..;nonce=..";
+        for (payload, expected, field) in [
+            (canonical.replace("amount=1500", "amount=01500"), "amount must be a decimal integer without leading zeros", Some("amount")),
+            (canonical.replace("nonce=7", "nonce=+7"), "nonce must be a decimal integer without leading zeros", Some("nonce")),
+            (canonical.replace("0x000000000000000000000000000000000000beef", "0xbeef"), "from must be a 0x address of 40 hex digits or a bech32 address", Some("from")),
+            (canonical.replace("chain=flowcortex-l1", "chain=flowcortex-l1 "), "chain must be 1-64 characters of a-z, 0-9 and '-'", Some("chain")),
+            (format!("{canonical};memo=hi"), malformed, None),
+            (canonical.replacen("from=", "to=", 1), malformed, None),
+        ] {
+            let (status, body) = send_json(
+                &app,
+                Method::POST,
+                "/wallet/submit-signed",
+                json!({ "payload": payload, "signature": "00", "public_key": "00" }),
+                vec![],
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{payload}");
+            assert_eq!(body["error"], expected, "{payload}");
+            assert_eq!(body["details"]["field"].as_str(), field, "{payload}");
+        }
+    }
+
+    #[tokio::test]
+    async fn mutating_responses_echo_the_client_request_id() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+        let send = |method: Method, uri: &str, client_request_id: &str| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("x-client-request-id", client_request_id)
+                .body(Body::from("{}"))
+                .expect("request should build");
+            app.clone().oneshot(request)
+        };
+        let echoed = |response: &Response| {
+            response
+                .headers()
+                .get("x-client-request-id")
+                .map(|value| value.to_str().expect("ascii").to_owned())
+        };
+
+        let created = send(Method::POST, "/wallet/create", "ui-42").await.expect("handled");
+        assert_eq!(created.status(), StatusCode::OK);
+        assert_eq!(echoed(&created).as_deref(), Some("ui-42"));
+
+        // Handler errors carry the id too.
+        let rejected = send(Method::POST, "/wallet/sign", "ui-43").await.expect("handled");
+        assert_eq!(rejected.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(echoed(&rejected).as_deref(), Some("ui-43"));
+
+        let listed = send(Method::GET, "/wallet/list", "ui-44").await.expect("handled");
+        assert_eq!(listed.status(), StatusCode::OK);
+        assert_eq!(echoed(&listed), None);
+
+        let too_long = "x".repeat(129);
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/create",
+            json!({}),
+            vec![("x-client-request-id", HeaderValue::from_str(&too_long).expect("header"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], "x-client-request-id");
+        assert_eq!(
+            body["error"],
+            "x-client-request-id must be at most 128 visible ASCII characters without spaces"
+        );
+    }
+
+    #[tokio::test]
+    async fn wallet_policy_is_checked_before_signing() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"]
+            .as_str()
+            .expect("wallet_address should be string")
+            .to_owned();
+        let policy_uri = format!("/ops/wallets/{wallet_address}/policy");
+        let denied = "0x000000000000000000000000000000000badbeef";
+
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+        )];
+
+        let (unauth_status, _) = send_json(&app, Method::PUT, &policy_uri, json!({}), vec![]).await;
+        assert_eq!(unauth_status, StatusCode::UNAUTHORIZED);
+
+        let (bad_status, bad_body) = send_json(
+            &app,
+            Method::PUT,
+            &policy_uri,
+            json!({ "spending_limits": { "FloweR": { "max_amount": "-1" } } }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(bad_status, StatusCode::BAD_REQUEST);
+        assert_eq!(bad_body["details"]["field"], "max_amount");
+
+        let (set_status, set_body) = send_json(
+            &app,
+            Method::PUT,
+            &policy_uri,
+            json!({
+                "spending_limits": { "FloweR": { "approval_above": "5000" } },
+                "denied_destinations": [denied],
+                "allowed_purposes": ["transaction"]
+            }),
+            auth.clone(),
+        )
+        .await;
+        assert_eq!(set_status, StatusCode::OK);
+        assert_eq!(set_body["spending_limits"]["FloweR"]["approval_above"], "5000");
+        assert_eq!(set_body["updated_by"], "ops-1");
+
+        let (_, get_body) = send_json(&app, Method::GET, &policy_uri, json!({}), auth.clone()).await;
+        assert_eq!(get_body["denied_destinations"], json!([denied]));
+
+        let submit = |to: &str, amount: &str, nonce: u64| {
+            json!({
+                "from": wallet_address,
+                "to": to,
+                "amount": amount,
+                "asset": "FloweR",
+                "chain": "flowcortex-l1",
+                "nonce": nonce
+            })
+        };
+        let (denied_status, denied_body) =
+            send_json(&app, Method::POST, "/wallet/submit", submit(denied, "1000", 1), vec![]).await;
+        assert_eq!(denied_status, StatusCode::FORBIDDEN);
+        assert!(denied_body["error"].as_str().unwrap().contains("is denied"));
+
+        let allowed = "0x00000000000000000000000000000000deadbeef";
+        let (held_status, held_body) =
+            send_json(&app, Method::POST, "/wallet/submit", submit(allowed, "9000", 1), auth.clone()).await;
+        assert_eq!(held_status, StatusCode::OK, "{held_body}");
+        assert_eq!(held_body["accepted"], false);
+        assert!(held_body["approval_id"].is_string());
+
+        // Refused and held transfers give their nonce back.
+        let (ok_status, ok_body) =
+            send_json(&app, Method::POST, "/wallet/submit", submit(allowed, "1000", 1), vec![]).await;
+        assert_eq!(ok_status, StatusCode::OK, "{ok_body}");
+
+        let payload = base64::engine::general_purpose::STANDARD.encode("hello-sign");
+        let sign = |purpose: &str| {
+            json!({ "wallet_address": wallet_address, "payload": payload, "purpose": purpose })
+        };
+        let (proof_status, _) = send_json(&app, Method::POST, "/wallet/sign", sign("proof"), vec![]).await;
+        assert_eq!(proof_status, StatusCode::FORBIDDEN);
+        let (raw_status, _) =
+            send_json(&app, Method::POST, "/wallet/sign", sign("transaction"), vec![]).await;
+        assert_eq!(raw_status, StatusCode::FORBIDDEN, "raw transactions bypass the limits");
+
+        let (delete_status, delete_body) =
+            send_json(&app, Method::DELETE, &policy_uri, json!({}), auth.clone()).await;
+        assert_eq!(delete_status, StatusCode::OK);
+        assert_eq!(delete_body["deleted"], true);
+        let (proof_status, _) = send_json(&app, Method::POST, "/wallet/sign", sign("proof"), vec![]).await;
+        assert_eq!(proof_status, StatusCode::OK);
+        let (missing_status, _) = send_json(&app, Method::GET, &policy_uri, json!({}), auth).await;
+        assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn wallet_portfolio_lists_holdings_on_every_live_chain() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (invalid_status, invalid) =
+            send_empty(&app, Method::GET, "/wallet/portfolio?wallet_address=0xnot-hex").await;
+        assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+        assert_eq!(invalid["details"]["field"], "wallet_address");
+
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet = created["wallet_address"].as_str().expect("address");
+        let (status, body) =
+            send_empty(&app, Method::GET, &format!("/wallet/portfolio?wallet_address={wallet}")).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["wallet_address"], wallet);
+        assert_eq!(
+            body["holdings"],
+            json!([
+                { "asset": "PROOF", "chain": "flowcortex-l1", "amount": "0", "decimals": 18 },
+                { "asset": "FloweR", "chain": "flowcortex-l1", "amount": "0", "decimals": 6 }
+            ])
+        );
+        assert!(body.get("errors").is_none(), "{body}");
+        assert!(body["as_of_epoch_ms"].as_u64().is_some());
+    }
+
+    #[tokio::test]
+    async fn held_transfer_needs_a_second_ops_principal() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let state = test_state(&temp_dir);
+        let sessions = state.wallet_sessions.clone();
+        let keystore = Arc::clone(&state.keystore);
+        let app = build_app(state);
+
+        let (_, create_body) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = create_body["wallet_address"]
+            .as_str()
+            .expect("wallet_address should be string")
+            .to_owned();
+        let ops = |user: &str| {
+            let token = build_hs256_token("test-auth-secret", user);
+            vec![(
+                "authorization",
+                HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+            )]
+        };
+        // Each decider has a wallet of their own to re-verify with.
+        let mut own_wallets = Vec::new();
+        for user in ["ops-1", "ops-2", "ops-3"] {
+            let (_, own) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+            let own = own["wallet_address"].as_str().expect("address").to_owned();
+            let (bind_status, _) = send_json(
+                &app,
+                Method::POST,
+                "/auth/bind",
+                json!({ "wallet_address": own, "chain": "flowcortex-l1" }),
+                ops(user),
+            )
+            .await;
+            assert_eq!(bind_status, StatusCode::OK);
+            own_wallets.push((user, own));
+        }
+        let verified = |user: &str, wallet_of: &str, verified_at: u128| {
+            let (_, wallet) = own_wallets
+                .iter()
+                .find(|(owner, _)| *owner == wallet_of)
+                .expect("decider should have a wallet");
+            let now = epoch_ms().expect("clock should work");
+            let session = sessions.issue(&keystore, wallet, verified_at, now).expect("session should issue");
+            let mut headers = ops(user);
+            headers.push(("x-wallet-session", HeaderValue::from_str(&session.token).expect("header")));
+            headers
+        };
+        let decider = |user: &str| verified(user, user, epoch_ms().expect("clock should work"));
+
+        let (set_status, _) = send_json(
+            &app,
+            Method::PUT,
+            &format!("/ops/wallets/{wallet_address}/policy"),
+            json!({ "spending_limits": { "FloweR": { "approval_above": "5000" } } }),
+            ops("ops-1"),
+        )
+        .await;
+        assert_eq!(set_status, StatusCode::OK);
+
+        let submit = |nonce: u64, dry_run: bool| {
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": "9000",
+                "asset": "FloweR",
+                "chain": "flowcortex-l1",
+                "nonce": nonce,
+                "dry_run": dry_run
+            })
+        };
+        let (dry_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            submit(1, true),
+            ops("ops-1"),
+        )
+        .await;
+        assert_eq!(dry_status, StatusCode::FORBIDDEN, "a dry run is never held");
+
+        let (_, held) = send_json(&app, Method::POST, "/wallet/submit", submit(1, false), ops("ops-1")).await;
+        let approval_id = held["approval_id"].as_str().expect("approval_id").to_owned();
+        assert_eq!(held["tx_hash"], "");
+        // A transfer is only held for a submitter the service can name.
+        let (anonymous_status, _) =
+            send_json(&app, Method::POST, "/wallet/submit", submit(1, false), vec![]).await;
+        assert_eq!(anonymous_status, StatusCode::UNAUTHORIZED);
+        let (forged_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            submit(1, false),
+            vec![("authorization", HeaderValue::from_static("Bearer not-a-token"))],
+        )
+        .await;
+        assert_eq!(forged_status, StatusCode::UNAUTHORIZED);
+        let (_, second) = send_json(&app, Method::POST, "/wallet/submit", submit(1, false), ops("ops-3")).await;
+        let second_id = second["approval_id"].as_str().expect("approval_id").to_owned();
+
+        let (unauth_status, _) = send_empty(&app, Method::GET, "/wallet/approvals").await;
+        assert_eq!(unauth_status, StatusCode::UNAUTHORIZED);
+        let (_, pending) =
+            send_json(&app, Method::GET, "/wallet/approvals?status=pending", json!({}), ops("ops-2")).await;
+        let approvals = pending["approvals"].as_array().expect("approvals");
+        assert_eq!(approvals.len(), 2);
+        assert_eq!(approvals[1]["approval_id"], approval_id.as_str());
+        assert_eq!(approvals[1]["requested_by"], "ops-1");
+        assert!(approvals[1]["reason"].as_str().unwrap().contains("5000"));
+
+        let decide_uri = format!("/wallet/approvals/{approval_id}/decide");
+        let (self_status, self_body) =
+            send_json(&app, Method::POST, &decide_uri, json!({ "approve": true }), decider("ops-1")).await;
+        assert_eq!(self_status, StatusCode::FORBIDDEN, "{self_body}");
+
+        // A bearer token alone, an old verification or someone else's
+        // wallet session is not enough to decide.
+        let (bare_status, _) =
+            send_json(&app, Method::POST, &decide_uri, json!({ "approve": true }), ops("ops-2")).await;
+        assert_eq!(bare_status, StatusCode::UNAUTHORIZED);
+        let stale = epoch_ms().expect("clock should work") - 10 * 60 * 1000;
+        let (stale_status, _) = send_json(
+            &app,
+            Method::POST,
+            &decide_uri,
+            json!({ "approve": true }),
+            verified("ops-2", "ops-2", stale),
+        )
+        .await;
+        assert_eq!(stale_status, StatusCode::UNAUTHORIZED);
+        let now = epoch_ms().expect("clock should work");
+        let (borrowed_status, _) = send_json(
+            &app,
+            Method::POST,
+            &decide_uri,
+            json!({ "approve": true }),
+            verified("ops-2", "ops-3", now),
+        )
+        .await;
+        assert_eq!(borrowed_status, StatusCode::FORBIDDEN);
+
+        let (approved_status, approved) =
+            send_json(&app, Method::POST, &decide_uri, json!({ "approve": true }), decider("ops-2")).await;
+        assert_eq!(approved_status, StatusCode::OK, "{approved}");
+        assert_eq!(approved["status"], "submitted", "{approved}");
+        assert_eq!(approved["decided_by"], "ops-2");
+        assert!(!approved["tx_hash"].as_str().expect("tx_hash").is_empty());
+
+        let (again_status, _) =
+            send_json(&app, Method::POST, &decide_uri, json!({ "approve": true }), decider("ops-3")).await;
+        assert_eq!(again_status, StatusCode::BAD_REQUEST);
+
+        let (_, rejected) = send_json(
+            &app,
+            Method::POST,
+            &format!("/wallet/approvals/{second_id}/decide"),
+            json!({ "approve": false }),
+            decider("ops-1"),
+        )
+        .await;
+        assert_eq!(rejected["status"], "rejected");
+        assert!(rejected["tx_hash"].is_null());
+
+        let (_, remaining) =
+            send_json(&app, Method::GET, "/wallet/approvals?status=pending", json!({}), ops("ops-2")).await;
+        assert_eq!(remaining["approvals"], json!([]));
+        let (missing_status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/approvals/missing/decide",
+            json!({ "approve": true }),
+            decider("ops-2"),
+        )
+        .await;
+        assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn session_key_signs_in_place_of_the_wallet_key() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let app = build_app(test_state(&temp_dir));
+
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = created["wallet_address"].as_str().expect("wallet_address").to_owned();
+        let wallet_key = kc_crypto::Ed25519PublicKey::from_hex(created["public_key"].as_str().expect("key"))
+            .expect("public key");
+
+        let issue = |purposes: serde_json::Value| {
+            json!({ "wallet_address": wallet_address, "purposes": purposes, "ttl_seconds": 600 })
+        };
+        let (tx_status, tx_body) =
+            send_json(&app, Method::POST, "/wallet/session-key", issue(json!(["transaction"])), vec![]).await;
+        assert_eq!(tx_status, StatusCode::BAD_REQUEST);
+        assert_eq!(tx_body["error"], "session keys cannot sign transactions");
+
+        let (status, issued) =
+            send_json(&app, Method::POST, "/wallet/session-key", issue(json!(["auth"])), vec![]).await;
+        assert_eq!(status, StatusCode::OK, "{issued}");
+        let session_key_id = issued["session_key_id"].as_str().expect("session_key_id").to_owned();
+        let attestation: kc_api_types::SessionKeyAttestation =
+            serde_json::from_value(issued["attestation"].clone()).expect("attestation");
+        assert_eq!(attestation.expires_at_epoch_ms - attestation.issued_at_epoch_ms, 600_000);
+        let domain = SigningDomain::default();
+        assert!(wallet_key
+            .verify_in_domain(
+                &domain,
+                attestation.canonical_payload().as_bytes(),
+                kc_api_types::SignPurpose::Custom("delegation".to_owned()),
+                &from_hex(&attestation.signature).expect("hex"),
+            )
+            .expect("verify"));
+
+        let payload = base64::engine::general_purpose::STANDARD.encode("challenge-123");
+        let sign = |purpose: &str| {
+            json!({
+                "wallet_address": wallet_address,
+                "payload": payload,
+                "purpose": purpose,
+                "session_key_id": session_key_id
+            })
+        };
+        let (sign_status, signed) = send_json(&app, Method::POST, "/wallet/sign", sign("auth"), vec![]).await;
+        assert_eq!(sign_status, StatusCode::OK, "{signed}");
+        assert_eq!(signed["session_key"]["session_public_key"], attestation.session_public_key.as_str());
+        let signature = from_hex(signed["signature"].as_str().expect("signature")).expect("hex");
+        let session_key =
+            kc_crypto::Ed25519PublicKey::from_hex(&attestation.session_public_key).expect("session key");
+        assert!(session_key
+            .verify_in_domain(&domain, b"challenge-123", kc_api_types::SignPurpose::Auth, &signature)
+            .expect("verify"));
+        assert!(!wallet_key
+            .verify_in_domain(&domain, b"challenge-123", kc_api_types::SignPurpose::Auth, &signature)
+            .expect("verify"));
+
+        let (proof_status, proof_body) =
+            send_json(&app, Method::POST, "/wallet/sign", sign("proof"), vec![]).await;
+        assert_eq!(proof_status, StatusCode::BAD_REQUEST);
+        assert_eq!(proof_body["error"], "session key may not sign for purpose 'proof'");
+
+        let (_, plain) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({ "wallet_address": wallet_address, "payload": payload, "purpose": "auth" }),
+            vec![],
+        )
+        .await;
+        assert!(plain.get("session_key").is_none(), "{plain}");
+    }
+
+    #[tokio::test]
+    async fn submission_tracker_resubmits_lost_transfers_and_reports_status_changes() {
+        use kc_chain_mock::MockAdapter;
+        use kc_storage::StorageEvent;
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mock = Arc::new(MockAdapter::new(FLOWCORTEX_L1));
+        let mut state = test_state(&temp_dir);
+        state.chains = Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            mock.clone(),
+        )));
+        let state = Arc::new(state);
+        let mut events = state.keystore.subscribe();
+        let app = build_app(Arc::clone(&state));
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = created["wallet_address"].as_str().unwrap().to_owned();
+        mock.set_balance(&wallet_address, "PROOF", 100);
+
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": "10",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let tx_hash = body["tx_hash"].as_str().unwrap().to_owned();
+        let tracked = state.submissions.tracked().expect("tracked");
+        assert_eq!(tracked.len(), 1);
+        assert_eq!(tracked[0].decimals, 18);
+
+        let now = epoch_ms().expect("clock");
+        mock.set_tx_status(&tx_hash, "not_found", None);
+        submissions::poll(&state, now + 60_000).await;
+        let submitted = mock.submitted();
+        assert_eq!(submitted.len(), 2, "the lost transfer is broadcast again");
+        assert_eq!(submitted[1].signed_payload, body["signature"].as_str().unwrap());
+        let record = state.keystore.load_submitted_tx(&tx_hash).unwrap().unwrap();
+        assert_eq!(record.status, "resubmitted");
+
+        mock.set_tx_status(&tx_hash, "confirmed", Some(1));
+        submissions::poll(&state, now + 120_000).await;
+        assert!(state.submissions.tracked().unwrap().is_empty());
+
+        let mut changes = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let StorageEvent::TxStatusChanged { previous_status, status, .. } = event {
+                changes.push((previous_status, status));
+            }
+        }
+        assert_eq!(
+            changes,
+            [
+                (None, "submitted".to_owned()),
+                (Some("submitted".to_owned()), "resubmitted".to_owned()),
+                (Some("resubmitted".to_owned()), "confirmed".to_owned()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn wallet_core_audits_signing_submission_and_policy_refusals() {
+        use kc_chain_mock::MockAdapter;
+
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let mock = Arc::new(MockAdapter::new(FLOWCORTEX_L1));
+        let mut state = test_state(&temp_dir);
+        state.chains = Arc::new(StdRwLock::new(chains::ChainTable::with_builtin(
+            "http://127.0.0.1:9",
+            mock.clone(),
+        )));
+        let state = Arc::new(state);
+        let app = build_app(Arc::clone(&state));
+        let (_, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        let wallet_address = created["wallet_address"].as_str().unwrap().to_owned();
+        mock.set_balance(&wallet_address, "PROOF", 100);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let auth = vec![(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("authorization header should build"),
+        )];
+        let (status, _) = send_json(
+            &app,
+            Method::PUT,
+            &format!("/ops/wallets/{wallet_address}/policy"),
+            json!({ "allowed_purposes": ["auth", "transaction"] }),
+            auth,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let sign = |purpose: &str| {
+            json!({
+                "wallet_address": wallet_address,
+                "payload": base64::engine::general_purpose::STANDARD.encode("hello"),
+                "purpose": purpose
+            })
+        };
+        let (status, _) = send_json(&app, Method::POST, "/wallet/sign", sign("auth"), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send_json(&app, Method::POST, "/wallet/sign", sign("proof"), vec![]).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/submit",
+            json!({
+                "from": wallet_address,
+                "to": "0x00000000000000000000000000000000deadbeef",
+                "amount": "10",
+                "asset": "PROOF",
+                "chain": "flowcortex-l1",
+                "nonce": 1
+            }),
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let audited = |event_type: &str| {
+            state
+                .keystore
+                .list_audit_events(10, Some(event_type), Some(&wallet_address), None)
+                .expect("audit")
+        };
+        let signed = audited("wallet_sign");
+        assert_eq!(signed.len(), 1);
+        assert_eq!(signed[0].message.as_deref(), Some("purpose auth"));
+        let refused = audited("signing_policy");
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].outcome, "deny");
+        let submitted = audited("transfer_submit");
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].outcome, "accepted");
+        assert_eq!(submitted[0].chain.as_deref(), Some(FLOWCORTEX_L1));
+        assert!(submitted[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .ends_with(&format!("tx {}", body["tx_hash"].as_str().unwrap())));
+    }
+
+    #[tokio::test]
+    async fn tenants_are_isolated_and_held_to_their_wallet_limit() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let base = test_state(&temp_dir);
+        let api_key = "payments-test-key";
+        let config = json!({
+            "tenants": [{
+                "id": "payments",
+                "api_key_sha256": [to_hex(&<sha2::Sha256 as sha2::Digest>::digest(api_key.as_bytes()))],
+                "max_wallets": 1
+            }]
+        });
+        let tenants = tenants::Tenants::parse(&base, &config.to_string(), None).expect("config should parse");
+        let tenant_state = tenants.states().next().expect("one tenant");
+        assert!(tenant_state.kms_keys.is_empty() && tenant_state.bridge_wallets.is_empty());
+        let tenant_keystore = Arc::clone(&tenant_state.keystore);
+        let tenant_sessions = tenant_state.wallet_sessions.clone();
+        let default_sessions = base.wallet_sessions.clone();
+        let default_keystore = Arc::clone(&base.keystore);
+        let state = Arc::new(base);
+        let app = tenants::route(build_app(Arc::clone(&state)), state, tenants);
+        let key_header = || vec![(tenants::API_KEY_HEADER, HeaderValue::from_static(api_key))];
+
+        let (status, default_created) = send_json(&app, Method::POST, "/wallet/create", json!({}), vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, created) = send_json(&app, Method::POST, "/wallet/create", json!({}), key_header()).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, refused) = send_json(&app, Method::POST, "/wallet/create", json!({}), key_header()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(refused["code"], "quota_exceeded");
+
+        let (_, default_list) = send_empty(&app, Method::GET, "/wallet/list").await;
+        assert_eq!(default_list["total"], 1);
+        assert_ne!(default_list["wallets"][0]["wallet_address"], created["wallet_address"]);
+        let request = Request::builder()
+            .uri("/wallet/list")
+            .header(tenants::API_KEY_HEADER, api_key)
+            .body(Body::empty())
+            .expect("request should build");
+        let response = app.clone().oneshot(request).await.expect("request should be handled");
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body should decode");
+        let tenant_list: Value = serde_json::from_slice(&bytes).expect("response should be json");
+        assert_eq!(tenant_list["total"], 1);
+        assert_eq!(tenant_list["wallets"][0]["wallet_address"], created["wallet_address"]);
+
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/sign",
+            json!({
+                "wallet_address": created["wallet_address"],
+                "payload": base64::engine::general_purpose::STANDARD.encode("tenant-sign"),
+                "purpose": "transaction"
+            }),
+            key_header(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let token = build_hs256_token("test-auth-secret", "ops-1");
+        let mut headers = key_header();
+        headers.push((
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {token}")).expect("auth header should build"),
+        ));
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rotate-key",
+            json!({ "wallet_address": created["wallet_address"] }),
+            headers,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let events = tenant_keystore
+            .list_audit_events(10, Some("wallet_rotate_key"), None, None)
+            .expect("audit events should list");
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|event| event.tenant.as_deref() == Some("payments")));
+
+        // A wallet session is only accepted by the tenant that issued it.
+        let now = epoch_ms().expect("clock should work");
+        let tenant_wallet = created["wallet_address"].as_str().expect("address");
+        let default_wallet = default_created["wallet_address"].as_str().expect("address");
+        let tenant_session = tenant_sessions
+            .issue(&tenant_keystore, tenant_wallet, now, now)
+            .expect("session should issue");
+        let default_session = default_sessions
+            .issue(&default_keystore, default_wallet, now, now)
+            .expect("session should issue");
+        let with_session = |token: &str, mut headers: Vec<(&'static str, HeaderValue)>| {
+            headers.push(("x-wallet-session", HeaderValue::from_str(token).expect("header")));
+            headers
+        };
+        let rename = |wallet: &str| json!({ "wallet_address": wallet, "label": "savings" });
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rename",
+            rename(tenant_wallet),
+            with_session(&tenant_session.token, key_header()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rename",
+            rename(default_wallet),
+            with_session(&tenant_session.token, vec![]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "wallet session is for another tenant");
+        let (status, body) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/rename",
+            rename(tenant_wallet),
+            with_session(&default_session.token, key_header()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "wallet session is for another tenant");
+
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/wallet/create",
+            json!({}),
+            vec![(tenants::API_KEY_HEADER, HeaderValue::from_static("wrong-key"))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Demo mode's in-memory ledger cannot be shared with tenants.
+        let demo_dir = TempDir::new().expect("temp dir should create");
+        let mut demo_base = test_state(&demo_dir);
+        demo_base.demo_ledger = Some(Arc::new(demo::DemoLedger::default()));
+        let refused = tenants::Tenants::parse(&demo_base, &config.to_string(), None)
+            .err()
+            .expect("demo mode should refuse tenants");
+        assert!(refused.to_string().contains("demo mode"), "{refused}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_creates_stay_within_the_tenant_wallet_limit() {
+        let temp_dir = TempDir::new().expect("temp dir should create");
+        let base = test_state(&temp_dir);
+        let api_key = "payments-test-key";
+        let config = json!({
+            "tenants": [{
+                "id": "payments",
+                "api_key_sha256": [to_hex(&<sha2::Sha256 as sha2::Digest>::digest(api_key.as_bytes()))],
+                "max_wallets": 3
+            }]
+        });
+        let tenants = tenants::Tenants::parse(&base, &config.to_string(), None).expect("config should parse");
+        let state = Arc::new(base);
+        let app = tenants::route(build_app(Arc::clone(&state)), state, tenants);
+
+        let creates: Vec<_> = (0..12)
+            .map(|_| {
+                let app = app.clone();
+                tokio::spawn(async move {
+                    let headers = vec![(tenants::API_KEY_HEADER, HeaderValue::from_static(api_key))];
+                    send_json(&app, Method::POST, "/wallet/create", json!({}), headers).await.0
+                })
+            })
+            .collect();
+        let mut created = 0;
+        for create in creates {
+            match create.await.expect("create should finish") {
+                StatusCode::OK => created += 1,
+                status => assert_eq!(status, StatusCode::TOO_MANY_REQUESTS),
+            }
+        }
+        assert_eq!(created, 3);
+
+        let request = Request::builder()
+            .uri("/wallet/list")
+            .header(tenants::API_KEY_HEADER, api_key)
+            .body(Body::empty())
+            .expect("request should build");
+        let response = app.oneshot(request).await.expect("request should be handled");
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body should decode");
+        let tenant_list: Value = serde_json::from_slice(&bytes).expect("response should be json");
+        assert_eq!(tenant_list["total"], 3);
+    }
 }
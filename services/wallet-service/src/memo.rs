@@ -0,0 +1,166 @@
+//! Encrypted wallet-to-wallet notes attached to transfers.
+//!
+//! A `memo` on `/wallet/submit` is sealed to the recipient wallet's key with
+//! [`kc_crypto::memo`] before the transfer is signed, and stored off-chain
+//! under the tx hash once the chain accepts it. Only the recipient can read
+//! it: custodied wallets through `POST /wallet/{address}/decrypt`,
+//! external-key wallets client-side from the `encrypted_memo` in their
+//! transaction history.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use kc_api_types::{WalletDecryptMemoRequest, WalletDecryptMemoResponse, WalletSubmitRequest};
+use kc_crypto::Ed25519PublicKey;
+use kc_storage::TransferMemoRecord;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::wallet_sessions::WalletScope;
+use crate::{
+    AppState, ApiResult, ApiError, bad_request, epoch_ms, forbidden, from_hex, internal_error,
+    not_found, to_hex,
+};
+
+/// Longest memo accepted, in UTF-8 bytes.
+const MAX_MEMO_BYTES: usize = 512;
+
+/// Seal `request.memo` to the recipient's key, as hex. `None` without a memo.
+pub(crate) async fn seal_for_recipient(
+    state: &AppState,
+    request: &WalletSubmitRequest,
+) -> Result<Option<String>, (StatusCode, Json<ApiError>)> {
+    let Some(memo) = request.memo.as_deref().filter(|memo| !memo.trim().is_empty()) else {
+        return Ok(None);
+    };
+    if memo.len() > MAX_MEMO_BYTES {
+        return Err(bad_request(&format!("memo must be at most {MAX_MEMO_BYTES} bytes")));
+    }
+    let recipient = recipient_public_key(state, &request.to).await?;
+    let sealed = kc_crypto::memo::seal(&recipient, memo.as_bytes()).map_err(internal_error)?;
+    Ok(Some(to_hex(&sealed)))
+}
+
+/// The key a memo to `wallet_address` is sealed to. Only KeyCortex wallets
+/// whose key can decrypt are valid recipients.
+async fn recipient_public_key(
+    state: &AppState,
+    wallet_address: &str,
+) -> Result<Ed25519PublicKey, (StatusCode, Json<ApiError>)> {
+    if let Some(public_key) = crate::external::load_public_key(state, wallet_address)? {
+        return Ok(public_key);
+    }
+    if state.kms_keys.get(wallet_address).is_some() {
+        return Err(bad_request("memo recipient's key is held in KMS and cannot decrypt memos"));
+    }
+    if state
+        .keystore
+        .load_watch_wallet(wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(bad_request("memo recipient is a watch-only wallet with no key"));
+    }
+    let signer = state
+        .wallet_core
+        .signer_for(wallet_address)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| bad_request("memo recipient must be a KeyCortex wallet"))?;
+    Ok(signer.public_key())
+}
+
+/// Store the sealed memo of an accepted transfer. The transfer is already on
+/// its way, so a failed write is logged rather than failing the submit.
+pub(crate) fn save(state: &AppState, request: &WalletSubmitRequest, tx_hash: &str, ciphertext: String) {
+    let saved = epoch_ms().and_then(|now| {
+        state.keystore.save_transfer_memo(&TransferMemoRecord {
+            tx_hash: tx_hash.to_owned(),
+            from: request.from.clone(),
+            to: request.to.clone(),
+            ciphertext,
+            created_at_epoch_ms: now,
+        })
+    });
+    if let Err(err) = saved {
+        warn!("failed to store memo for {}: {:#}", tx_hash, err);
+    }
+}
+
+/// Hex of the memo attached to `tx_hash`, for history entries.
+pub(crate) fn encrypted_memo(state: &AppState, tx_hash: &str) -> Option<String> {
+    match state.keystore.load_transfer_memo(tx_hash) {
+        Ok(record) => record.map(|record| record.ciphertext),
+        Err(err) => {
+            warn!("failed to load memo for {}: {:#}", tx_hash, err);
+            None
+        }
+    }
+}
+
+/// POST /wallet/{address}/decrypt — read a memo sealed to a custodied wallet.
+pub(crate) async fn wallet_decrypt_memo(
+    State(state): State<Arc<AppState>>,
+    Path(wallet_address): Path<String>,
+    scope: WalletScope,
+    Json(request): Json<WalletDecryptMemoRequest>,
+) -> ApiResult<WalletDecryptMemoResponse> {
+    scope.authorize(&wallet_address)?;
+    let tx_hash = request
+        .tx_hash
+        .map(|tx_hash| tx_hash.trim().to_owned())
+        .filter(|tx_hash| !tx_hash.is_empty());
+    let ciphertext = request
+        .ciphertext
+        .map(|ciphertext| ciphertext.trim().to_owned())
+        .filter(|ciphertext| !ciphertext.is_empty());
+
+    crate::honeytoken::trip_if_honeytoken(&state, &wallet_address, "wallet_decrypt_memo").await;
+
+    let ciphertext = match (&tx_hash, ciphertext) {
+        (Some(tx_hash), None) => {
+            let record = state
+                .keystore
+                .load_transfer_memo(tx_hash)
+                .map_err(internal_error)?
+                .ok_or_else(|| not_found("no memo for this transaction"))?;
+            if record.to != wallet_address {
+                return Err(forbidden("memo is not addressed to this wallet"));
+            }
+            record.ciphertext
+        }
+        (None, Some(ciphertext)) => ciphertext,
+        _ => return Err(bad_request("exactly one of tx_hash or ciphertext is required")),
+    };
+    let sealed = from_hex(&ciphertext).map_err(|e| bad_request(&format!("invalid ciphertext hex: {e}")))?;
+
+    if state
+        .keystore
+        .load_external_key(&wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(forbidden("external-key wallet; decrypt the memo client-side"));
+    }
+    if state.kms_keys.get(&wallet_address).is_some() {
+        return Err(forbidden("wallet key is held in KMS and cannot decrypt memos"));
+    }
+    let signer = state
+        .wallet_core
+        .signer_for(&wallet_address)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("wallet not found"))?;
+
+    let memo = kc_crypto::memo::open(&signer, &sealed)
+        .map_err(|_| bad_request("memo cannot be decrypted by this wallet"))?;
+    let memo = String::from_utf8(memo).map_err(|_| bad_request("memo is not valid UTF-8"))?;
+
+    Ok(Json(WalletDecryptMemoResponse {
+        wallet_address,
+        tx_hash,
+        memo,
+    }))
+}
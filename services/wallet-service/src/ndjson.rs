@@ -0,0 +1,100 @@
+//! Newline-delimited JSON variants of the large listing endpoints.
+//!
+//! `/wallet/list`, `/ops/audit` and `/wallet/{address}/transactions` answer
+//! with one JSON object per line instead of a single document when the
+//! request sends `Accept: application/x-ndjson`. The body is read from
+//! storage a page at a time and sent as each page arrives, so memory stays
+//! flat however many records match.
+//!
+//! The status line is sent before the first page is read. A storage error
+//! after that cannot change it, so it ends the stream with a final error
+//! line, shaped like an error response body, instead.
+
+use anyhow::Result;
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use futures_util::stream;
+use kc_api_types::{ApiError, ErrorCode};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::future::Future;
+use tracing::warn;
+
+pub(crate) const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Records read from storage for each chunk of the response.
+pub(crate) const PAGE_SIZE: usize = 200;
+
+/// Whether `Accept` asks for [`CONTENT_TYPE`].
+pub(crate) fn requested(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|media_range| media_range.split(';').next())
+        .any(|media_type| media_type.trim().eq_ignore_ascii_case(CONTENT_TYPE))
+}
+
+/// Stream records page by page from `start`, sending at most `limit` of them.
+///
+/// `next_page` gets the cursor of the page to read (`None` for the very
+/// first) and returns its records with the cursor after them, or `None`
+/// after the last page.
+pub(crate) fn stream_pages<T, C, F, Fut>(
+    limit: Option<usize>,
+    start: Option<C>,
+    mut next_page: F,
+) -> Response
+where
+    T: Serialize + Send + 'static,
+    C: Send + 'static,
+    F: FnMut(Option<C>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(Vec<T>, Option<C>)>> + Send + 'static,
+{
+    let remaining = limit.unwrap_or(usize::MAX);
+    let chunks = stream::unfold(Some((start, remaining)), move |position| {
+        let page = position
+            .filter(|(_, remaining)| *remaining > 0)
+            .map(|(cursor, remaining)| (next_page(cursor), remaining));
+        async move {
+            let (page, remaining) = page?;
+            match page.await.and_then(|(mut records, next_cursor)| {
+                records.truncate(remaining);
+                let chunk = lines(&records)?;
+                Ok((chunk, next_cursor.map(|cursor| (Some(cursor), remaining - records.len()))))
+            }) {
+                Ok((chunk, position)) => Some((chunk, position)),
+                Err(err) => {
+                    warn!("streamed listing stopped early: {err}");
+                    Some((error_line(&err), None))
+                }
+            }
+        }
+    });
+
+    (
+        [(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE))],
+        Body::from_stream(stream::StreamExt::map(chunks, Ok::<_, Infallible>)),
+    )
+        .into_response()
+}
+
+fn lines<T: Serialize>(records: &[T]) -> Result<Bytes> {
+    let mut chunk = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut chunk, record)?;
+        chunk.push(b'\n');
+    }
+    Ok(Bytes::from(chunk))
+}
+
+fn error_line(err: &anyhow::Error) -> Bytes {
+    let error = ApiError::new(ErrorCode::Internal, err.to_string());
+    let mut line = serde_json::to_string(&error).unwrap_or_default();
+    line.push('\n');
+    Bytes::from(line)
+}
@@ -0,0 +1,108 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+};
+use kc_api_types::{WalletAddress, WalletNonceReservationResponse};
+use kc_chain_client::NonceStrategy;
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_storage::Keystore;
+use kc_wallet_core::MAX_LIVE_RESERVATIONS;
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::wallet_sessions::WalletScope;
+use crate::{
+    AppState, ApiResult, bad_request, epoch_ms, forbidden, internal_error, parse_field,
+    quota_exceeded, unauthorized,
+};
+
+const DEFAULT_TTL_SECONDS: u64 = 60;
+const MAX_TTL_SECONDS: u64 = 600;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NonceReserveQuery {
+    ttl_seconds: Option<u64>,
+    chain: Option<String>,
+}
+
+/// POST /wallet/{address}/nonce/reserve — hold the next nonce for an offline signer.
+///
+/// Needs the wallet's session, or the AuthBuddy token of the user the
+/// wallet is bound to. A wallet holds at most [`MAX_LIVE_RESERVATIONS`].
+pub(crate) async fn wallet_nonce_reserve(
+    State(state): State<Arc<AppState>>,
+    Path(wallet_address): Path<String>,
+    scope: WalletScope,
+    headers: HeaderMap,
+    Query(query): Query<NonceReserveQuery>,
+) -> ApiResult<WalletNonceReservationResponse> {
+    parse_field::<WalletAddress>("wallet_address", &wallet_address)?;
+    scope.authorize(&wallet_address)?;
+    let ttl_seconds = query.ttl_seconds.unwrap_or(DEFAULT_TTL_SECONDS);
+    if ttl_seconds == 0 || ttl_seconds > MAX_TTL_SECONDS {
+        return Err(bad_request("ttl_seconds must be between 1 and 600"));
+    }
+    let chain = query.chain.as_deref().unwrap_or(FLOWCORTEX_L1);
+    let strategy = crate::chains::adapter(&state, chain)?.nonce_strategy();
+    if strategy != NonceStrategy::Sequential {
+        return Err(bad_request(&format!(
+            "chain '{chain}' uses {} nonces; reservations need sequential nonces",
+            strategy.as_str()
+        )));
+    }
+
+    if !scope.is_for(&wallet_address) {
+        let principal = crate::auth::parse_authbuddy_principal(&headers, &state).map_err(|_| {
+            unauthorized("reserving a nonce needs the wallet's session or its owner's AuthBuddy token")
+        })?;
+        let owned = state
+            .keystore
+            .load_wallet_binding(&wallet_address)
+            .map_err(internal_error)?
+            .is_some_and(|binding| binding.user_id == principal.user_id);
+        if !owned {
+            return Err(forbidden("wallet is not bound to the caller"));
+        }
+    }
+
+    crate::honeytoken::trip_if_honeytoken(&state, &wallet_address, "wallet_nonce_reserve").await;
+    crate::watch::reject_watch_only(&state, &wallet_address)?;
+
+    let wallet_exists = state
+        .keystore
+        .load_encrypted_key(&wallet_address)
+        .await
+        .map_err(internal_error)?
+        .is_some()
+        || state.kms_keys.get(&wallet_address).is_some()
+        || crate::external::load_public_key(&state, &wallet_address)?.is_some();
+    if !wallet_exists {
+        return Err(bad_request("wallet not found"));
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let reservation = state
+        .nonces
+        .reserve(
+            &wallet_address,
+            Uuid::new_v4().to_string(),
+            u128::from(ttl_seconds) * 1000,
+            now,
+        )
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| {
+            quota_exceeded(&format!(
+                "wallet already holds {MAX_LIVE_RESERVATIONS} live nonce reservations"
+            ))
+        })?;
+
+    Ok(Json(WalletNonceReservationResponse {
+        wallet_address,
+        nonce: reservation.nonce,
+        reservation_id: reservation.reservation_id,
+        expires_at_epoch_ms: reservation.expires_at_epoch_ms,
+    }))
+}
@@ -0,0 +1,572 @@
+//! Approval notifications for conditional transfers.
+//!
+//! When `POST /wallet/escrow` holds a transfer for approval, every channel in
+//! `KEYCORTEX_APPROVAL_NOTIFY_CHANNELS` (a JSON array) is told about it:
+//!
+//! - `webhook` posts a JSON event, signed with `X-KeyCortex-Signature:
+//!   sha256=<hmac>` over the body when the channel has a `secret`.
+//! - `slack` posts a Slack-compatible `{"text": ...}` message.
+//! - `smtp` emails the approver wallet's identity email; approvers without
+//!   one are `skipped`.
+//!
+//! Each message carries a deep link to `GET /wallet/escrow/{transfer_id}`
+//! under `KEYCORTEX_APPROVAL_LINK_BASE_URL`, signed with
+//! `KEYCORTEX_APPROVAL_LINK_SECRET` and valid until the transfer expires.
+//! Failed deliveries are retried with exponential backoff from
+//! `KEYCORTEX_APPROVAL_NOTIFY_RETRY_MS` (default 2000) up to
+//! `KEYCORTEX_APPROVAL_NOTIFY_MAX_ATTEMPTS` (default 5) attempts, and stop
+//! once the transfer is no longer pending. Delivery status per channel is
+//! kept as an [`ApprovalNotificationRecord`] and listed by
+//! `GET /ops/approvals/notifications`. Retries do not survive a restart;
+//! deliveries interrupted by one stay `pending`.
+
+use anyhow::{Context, Result, anyhow, bail};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::HeaderMap,
+};
+use hmac::{Hmac, Mac};
+use kc_storage::{ApprovalNotificationRecord, ConditionalTransferRecord};
+use lettre::message::{Mailbox, header::ContentType};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::ops::require_ops_access;
+use crate::{ApiResult, AppState, bad_request, epoch_ms, from_hex, internal_error, to_hex};
+
+pub(crate) const KIND_WEBHOOK: &str = "webhook";
+pub(crate) const KIND_SLACK: &str = "slack";
+pub(crate) const KIND_SMTP: &str = "smtp";
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_RETRY_MS: u64 = 2_000;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// One entry of `KEYCORTEX_APPROVAL_NOTIFY_CHANNELS`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum ChannelConfig {
+    Webhook {
+        name: String,
+        url: String,
+        #[serde(default)]
+        secret: Option<String>,
+    },
+    Slack {
+        name: String,
+        url: String,
+    },
+    Smtp {
+        name: String,
+        relay: String,
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        from: String,
+        #[serde(default)]
+        tls: SmtpTls,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SmtpTls {
+    /// Upgrade a plain connection, usually on port 587.
+    #[default]
+    Starttls,
+    /// Implicit TLS, usually on port 465.
+    Tls,
+    /// Plain text; for local relays only.
+    None,
+}
+
+enum Transport {
+    Webhook { url: String, secret: Option<String> },
+    Slack { url: String },
+    Smtp { mailer: AsyncSmtpTransport<Tokio1Executor>, from: Mailbox },
+}
+
+struct Channel {
+    name: String,
+    transport: Transport,
+}
+
+impl Channel {
+    fn kind(&self) -> &'static str {
+        match self.transport {
+            Transport::Webhook { .. } => KIND_WEBHOOK,
+            Transport::Slack { .. } => KIND_SLACK,
+            Transport::Smtp { .. } => KIND_SMTP,
+        }
+    }
+}
+
+/// Webhook body for a transfer awaiting approval.
+#[derive(Debug, Serialize)]
+struct ApprovalRequestedEvent<'a> {
+    event: &'static str,
+    transfer_id: &'a str,
+    from: &'a str,
+    to: &'a str,
+    amount: &'a str,
+    asset: &'a str,
+    chain: &'a str,
+    approver_wallet: &'a str,
+    digest: &'a str,
+    expires_at_epoch_ms: u128,
+    approval_url: &'a str,
+}
+
+pub(crate) struct ApprovalNotifier {
+    channels: Vec<Channel>,
+    link_base_url: String,
+    link_secret: Vec<u8>,
+    max_attempts: u32,
+    retry_base: Duration,
+    http: reqwest::Client,
+}
+
+impl ApprovalNotifier {
+    /// `None` unless `KEYCORTEX_APPROVAL_NOTIFY_CHANNELS` lists a channel.
+    pub(crate) fn from_env() -> Result<Option<Self>> {
+        let Some(channels) = env::var("KEYCORTEX_APPROVAL_NOTIFY_CHANNELS")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let channels: Vec<ChannelConfig> = serde_json::from_str(&channels)
+            .context("KEYCORTEX_APPROVAL_NOTIFY_CHANNELS is not a JSON array of channels")?;
+        if channels.is_empty() {
+            return Ok(None);
+        }
+        let link_base_url = env::var("KEYCORTEX_APPROVAL_LINK_BASE_URL")
+            .context("KEYCORTEX_APPROVAL_LINK_BASE_URL is required for approval notifications")?;
+        let link_secret = env::var("KEYCORTEX_APPROVAL_LINK_SECRET")
+            .context("KEYCORTEX_APPROVAL_LINK_SECRET is required for approval notifications")?;
+        let max_attempts = match env::var("KEYCORTEX_APPROVAL_NOTIFY_MAX_ATTEMPTS") {
+            Ok(value) => value
+                .trim()
+                .parse::<u32>()
+                .context("KEYCORTEX_APPROVAL_NOTIFY_MAX_ATTEMPTS")?,
+            Err(_) => DEFAULT_MAX_ATTEMPTS,
+        };
+        let retry_ms = match env::var("KEYCORTEX_APPROVAL_NOTIFY_RETRY_MS") {
+            Ok(value) => value
+                .trim()
+                .parse::<u64>()
+                .context("KEYCORTEX_APPROVAL_NOTIFY_RETRY_MS")?,
+            Err(_) => DEFAULT_RETRY_MS,
+        };
+        Self::new(
+            channels,
+            &link_base_url,
+            &link_secret,
+            max_attempts,
+            Duration::from_millis(retry_ms),
+        )
+        .map(Some)
+    }
+
+    pub(crate) fn new(
+        channels: Vec<ChannelConfig>,
+        link_base_url: &str,
+        link_secret: &str,
+        max_attempts: u32,
+        retry_base: Duration,
+    ) -> Result<Self> {
+        if link_base_url.trim().is_empty() {
+            bail!("approval link base URL is empty");
+        }
+        if link_secret.len() < 16 {
+            bail!("approval link secret must be at least 16 bytes");
+        }
+        if max_attempts == 0 {
+            bail!("approval notification max attempts must be at least 1");
+        }
+        let mut names = HashSet::new();
+        let channels = channels
+            .into_iter()
+            .map(|config| {
+                let channel = build_channel(config)?;
+                if channel.name.trim().is_empty() || channel.name.contains(':') {
+                    bail!("approval channel name '{}' must be non-empty without ':'", channel.name);
+                }
+                if !names.insert(channel.name.clone()) {
+                    bail!("approval channel '{}' is listed twice", channel.name);
+                }
+                Ok(channel)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            channels,
+            link_base_url: link_base_url.trim_end_matches('/').to_owned(),
+            link_secret: link_secret.as_bytes().to_vec(),
+            max_attempts,
+            retry_base,
+            http: reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?,
+        })
+    }
+
+    /// `GET /wallet/escrow/{transfer_id}` with a signature over the transfer
+    /// and its expiry.
+    pub(crate) fn approval_link(&self, transfer: &ConditionalTransferRecord) -> String {
+        let expires = transfer.expires_at_epoch_ms.to_string();
+        let signature = to_hex(&self.link_mac(transfer, &expires).finalize().into_bytes());
+        format!(
+            "{}/wallet/escrow/{}?expires={expires}&sig={signature}",
+            self.link_base_url, transfer.transfer_id
+        )
+    }
+
+    /// Whether `signature` is this service's link signature for `transfer`
+    /// and `expires`, and `expires` has not passed.
+    pub(crate) fn verify_link(
+        &self,
+        transfer: &ConditionalTransferRecord,
+        expires: &str,
+        signature: &str,
+        now_epoch_ms: u128,
+    ) -> bool {
+        let Ok(expires_at) = expires.parse::<u128>() else {
+            return false;
+        };
+        let Ok(signature) = from_hex(signature) else {
+            return false;
+        };
+        now_epoch_ms <= expires_at && self.link_mac(transfer, expires).verify_slice(&signature).is_ok()
+    }
+
+    fn link_mac(&self, transfer: &ConditionalTransferRecord, expires: &str) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.link_secret).expect("HMAC accepts keys of any length");
+        mac.update(
+            format!(
+                "keycortex:approval-link:v1:{}:{}:{expires}",
+                transfer.transfer_id, transfer.digest
+            )
+            .as_bytes(),
+        );
+        mac
+    }
+
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        self.retry_base
+            .saturating_mul(1_u32 << (attempt - 1).min(16))
+            .min(MAX_RETRY_DELAY)
+    }
+}
+
+fn build_channel(config: ChannelConfig) -> Result<Channel> {
+    Ok(match config {
+        ChannelConfig::Webhook { name, url, secret } => Channel {
+            name,
+            transport: Transport::Webhook { url, secret },
+        },
+        ChannelConfig::Slack { name, url } => Channel {
+            name,
+            transport: Transport::Slack { url },
+        },
+        ChannelConfig::Smtp {
+            name,
+            relay,
+            port,
+            username,
+            password,
+            from,
+            tls,
+        } => {
+            let mut builder = match tls {
+                SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&relay)?,
+                SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&relay)?,
+                SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&relay),
+            };
+            if let Some(port) = port {
+                builder = builder.port(port);
+            }
+            if let Some(username) = username {
+                builder = builder.credentials(Credentials::new(username, password.unwrap_or_default()));
+            }
+            let from = from
+                .parse::<Mailbox>()
+                .with_context(|| format!("approval channel '{name}' has an invalid from address"))?;
+            Channel {
+                name,
+                transport: Transport::Smtp {
+                    mailer: builder.timeout(Some(SEND_TIMEOUT)).build(),
+                    from,
+                },
+            }
+        }
+    })
+}
+
+/// Tell every configured channel that `transfer` awaits its approver.
+/// Deliveries run in the background; this only records them as `pending`.
+pub(crate) async fn notify_approvers(state: &Arc<AppState>, transfer: &ConditionalTransferRecord) {
+    let Some(notifier) = state.approval_notifier.clone() else {
+        return;
+    };
+    let approver_email = match state.keystore.load_wallet_identity(&transfer.approver_wallet) {
+        Ok(identity) => identity.and_then(|identity| identity.email),
+        Err(err) => {
+            warn!("failed to load identity of approver {}: {}", transfer.approver_wallet, err);
+            None
+        }
+    };
+    let now = epoch_ms().unwrap_or_default();
+
+    for index in 0..notifier.channels.len() {
+        let channel = &notifier.channels[index];
+        let mut record = ApprovalNotificationRecord {
+            transfer_id: transfer.transfer_id.clone(),
+            channel: channel.name.clone(),
+            kind: channel.kind().to_owned(),
+            recipient: None,
+            status: "pending".to_owned(),
+            attempts: 0,
+            last_error: None,
+            created_at_epoch_ms: now,
+            updated_at_epoch_ms: now,
+        };
+        if matches!(channel.transport, Transport::Smtp { .. }) {
+            match &approver_email {
+                Some(email) => record.recipient = Some(email.clone()),
+                None => {
+                    record.status = "skipped".to_owned();
+                    record.last_error = Some("approver wallet has no email".to_owned());
+                }
+            }
+        }
+        save_record(state, &record);
+        if record.status != "pending" {
+            continue;
+        }
+
+        let state = Arc::clone(state);
+        let notifier = Arc::clone(&notifier);
+        let transfer = transfer.clone();
+        tokio::spawn(async move {
+            deliver(&state, &notifier, &notifier.channels[index], &transfer, record).await;
+        });
+    }
+}
+
+async fn deliver(
+    state: &AppState,
+    notifier: &ApprovalNotifier,
+    channel: &Channel,
+    transfer: &ConditionalTransferRecord,
+    mut record: ApprovalNotificationRecord,
+) {
+    let approval_url = notifier.approval_link(transfer);
+    loop {
+        if let Some(reason) = no_longer_pending(state, &transfer.transfer_id) {
+            record.status = "skipped".to_owned();
+            record.last_error = Some(reason);
+            record.updated_at_epoch_ms = epoch_ms().unwrap_or_default();
+            save_record(state, &record);
+            return;
+        }
+
+        record.attempts += 1;
+        let sent = send(notifier, channel, transfer, &approval_url, record.recipient.as_deref()).await;
+        record.updated_at_epoch_ms = epoch_ms().unwrap_or_default();
+        match sent {
+            Ok(()) => {
+                record.status = "delivered".to_owned();
+                record.last_error = None;
+                save_record(state, &record);
+                return;
+            }
+            Err(err) => {
+                record.last_error = Some(format!("{err:#}"));
+                if record.attempts >= notifier.max_attempts {
+                    record.status = "failed".to_owned();
+                    save_record(state, &record);
+                    warn!(
+                        "approval notification for {} over '{}' failed after {} attempt(s): {:#}",
+                        transfer.transfer_id, channel.name, record.attempts, err
+                    );
+                    return;
+                }
+                save_record(state, &record);
+            }
+        }
+        tokio::time::sleep(notifier.retry_delay(record.attempts)).await;
+    }
+}
+
+/// Why there is no point notifying about the transfer any more, if so.
+fn no_longer_pending(state: &AppState, transfer_id: &str) -> Option<String> {
+    match state.keystore.load_conditional_transfer(transfer_id) {
+        Ok(Some(current)) if current.status != "pending" => {
+            Some(format!("transfer {} before delivery", current.status))
+        }
+        Ok(Some(current)) if epoch_ms().unwrap_or_default() > current.expires_at_epoch_ms => {
+            Some("transfer expired before delivery".to_owned())
+        }
+        Ok(Some(_)) => None,
+        Ok(None) => Some("transfer not found".to_owned()),
+        Err(err) => {
+            warn!("failed to reload conditional transfer {}: {}", transfer_id, err);
+            None
+        }
+    }
+}
+
+fn save_record(state: &AppState, record: &ApprovalNotificationRecord) {
+    if let Err(err) = state.keystore.save_approval_notification(record) {
+        warn!(
+            "failed to save approval notification for {} over '{}': {}",
+            record.transfer_id, record.channel, err
+        );
+    }
+}
+
+async fn send(
+    notifier: &ApprovalNotifier,
+    channel: &Channel,
+    transfer: &ConditionalTransferRecord,
+    approval_url: &str,
+    recipient: Option<&str>,
+) -> Result<()> {
+    match &channel.transport {
+        Transport::Webhook { url, secret } => {
+            let body = serde_json::to_vec(&ApprovalRequestedEvent {
+                event: "escrow_approval_requested",
+                transfer_id: &transfer.transfer_id,
+                from: &transfer.from,
+                to: &transfer.to,
+                amount: &transfer.amount,
+                asset: &transfer.asset,
+                chain: &transfer.chain,
+                approver_wallet: &transfer.approver_wallet,
+                digest: &transfer.digest,
+                expires_at_epoch_ms: transfer.expires_at_epoch_ms,
+                approval_url,
+            })?;
+            let mut request = notifier
+                .http
+                .post(url)
+                .header("content-type", "application/json");
+            if let Some(secret) = secret {
+                let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+                    .expect("HMAC accepts keys of any length");
+                mac.update(&body);
+                request = request.header(
+                    "x-keycortex-signature",
+                    format!("sha256={}", to_hex(&mac.finalize().into_bytes())),
+                );
+            }
+            check_status(request.body(body).send().await?)
+        }
+        Transport::Slack { url } => {
+            let text = message_text(transfer, approval_url);
+            check_status(
+                notifier
+                    .http
+                    .post(url)
+                    .json(&serde_json::json!({ "text": text }))
+                    .send()
+                    .await?,
+            )
+        }
+        Transport::Smtp { mailer, from } => {
+            let to = recipient
+                .ok_or_else(|| anyhow!("no recipient"))?
+                .parse::<Mailbox>()
+                .context("approver email is not a valid address")?;
+            let email = Message::builder()
+                .from(from.clone())
+                .to(to)
+                .subject(format!(
+                    "KeyCortex approval requested: {} {}",
+                    transfer.amount, transfer.asset
+                ))
+                .header(ContentType::TEXT_PLAIN)
+                .body(message_text(transfer, approval_url))?;
+            mailer.send(email).await?;
+            Ok(())
+        }
+    }
+}
+
+fn check_status(response: reqwest::Response) -> Result<()> {
+    let status = response.status();
+    if !status.is_success() {
+        bail!("HTTP {status}");
+    }
+    Ok(())
+}
+
+fn message_text(transfer: &ConditionalTransferRecord, approval_url: &str) -> String {
+    format!(
+        "Approval requested for conditional transfer {}\n\
+         {} {} on {} from {} to {}\n\
+         Approver wallet: {}\n\
+         Sign digest {} to approve before {} (epoch ms).\n\
+         {}",
+        transfer.transfer_id,
+        transfer.amount,
+        transfer.asset,
+        transfer.chain,
+        transfer.from,
+        transfer.to,
+        transfer.approver_wallet,
+        transfer.digest,
+        transfer.expires_at_epoch_ms,
+        approval_url
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApprovalNotificationQuery {
+    transfer_id: Option<String>,
+    status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ApprovalNotificationListResponse {
+    notifications: Vec<ApprovalNotificationRecord>,
+    total: usize,
+}
+
+/// GET /ops/approvals/notifications — delivery status of approval
+/// notifications, optionally for one transfer or in one status.
+pub(crate) async fn ops_list_approval_notifications(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ApprovalNotificationQuery>,
+) -> ApiResult<ApprovalNotificationListResponse> {
+    require_ops_access(&state, &headers, "ops_list_approval_notifications", None).await?;
+
+    if let Some(status) = query.status.as_deref()
+        && !["pending", "delivered", "failed", "skipped"].contains(&status)
+    {
+        return Err(bad_request("status must be pending, delivered, failed or skipped"));
+    }
+    let mut notifications = state
+        .keystore
+        .list_approval_notifications(query.transfer_id.as_deref())
+        .map_err(internal_error)?;
+    if let Some(status) = query.status.as_deref() {
+        notifications.retain(|record| record.status == status);
+    }
+    let total = notifications.len();
+    Ok(Json(ApprovalNotificationListResponse { notifications, total }))
+}
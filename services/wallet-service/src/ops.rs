@@ -2,17 +2,29 @@ use axum::{
     Json,
     extract::{Path, Query, State},
     http::HeaderMap,
+    response::{IntoResponse, Response},
 };
+use kc_api_types::{ChainId, DeviceListResponse, DeviceSummary, OpsDeviceApproveRequest, Page, PageQuery, WalletAddress};
 use kc_chain_flowcortex::FLOWCORTEX_L1;
-use kc_storage::{AuditEventRecord, WalletBindingRecord};
+use kc_storage::{
+    AuditEventPage, AuditEventRecord, ChainAdapterRecord, StorageStats, WalletBindingRecord,
+    WalletTombstoneRecord, WalletUndelete,
+};
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, unauthorized};
+use crate::chains::{ChainHealth, KIND_BITCOIN, KIND_COSMOS, KIND_EVM, KIND_FLOWCORTEX};
+use crate::deadline::{self, RequestContext};
+use crate::health_history::HealthSample;
+use crate::ndjson;
+use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, parse_field, unauthorized};
 
-#[derive(Debug, Deserialize)]
+const MAX_AUDIT_EXPORT: usize = 10_000;
+
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct OpsAuditQuery {
     pub(crate) limit: Option<usize>,
     pub(crate) event_type: Option<String>,
@@ -25,6 +37,128 @@ pub(crate) struct OpsAuditResponse {
     pub(crate) events: Vec<AuditEventRecord>,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsHoneytokenRequest {
+    pub(crate) wallet_address: String,
+    pub(crate) honeytoken: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsHoneytokenResponse {
+    pub(crate) wallet_address: String,
+    pub(crate) honeytoken: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsHoneytokenListResponse {
+    pub(crate) wallets: Vec<String>,
+    pub(crate) total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsDeletedWalletListResponse {
+    pub(crate) wallets: Vec<WalletTombstoneRecord>,
+    pub(crate) total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsUndeleteRequest {
+    pub(crate) wallet_address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsUndeleteResponse {
+    pub(crate) wallet_address: String,
+    pub(crate) restored: bool,
+    pub(crate) user_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsChainListQuery {
+    pub(crate) probe: Option<bool>,
+}
+
+/// Add or update a chain adapter. Omitted fields keep their current value;
+/// `kind` and `endpoint` are required for a new chain id.
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsChainUpsertRequest {
+    pub(crate) chain_id: String,
+    pub(crate) kind: Option<String>,
+    pub(crate) endpoint: Option<String>,
+    pub(crate) enabled: Option<bool>,
+    pub(crate) evm_chain_id: Option<u64>,
+    /// Explorer URL with a `{tx_hash}` placeholder; an empty string clears it.
+    pub(crate) explorer_tx_url: Option<String>,
+    /// Explorer URL with an `{address}` placeholder; an empty string clears it.
+    pub(crate) explorer_address_url: Option<String>,
+    /// Explorer URL with a `{height}` placeholder; an empty string clears it.
+    pub(crate) explorer_block_url: Option<String>,
+    /// ERC-20 contract address per asset symbol (EVM only); replaces the
+    /// current set.
+    pub(crate) erc20_tokens: Option<BTreeMap<String, String>>,
+    /// Address prefix, required for Cosmos (`cosmos`) and Bitcoin (`bc`,
+    /// `tb`, `bcrt`) chains.
+    pub(crate) bech32_prefix: Option<String>,
+    /// Bank denom per asset symbol (Cosmos only); replaces the current set.
+    pub(crate) denoms: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsChainSummary {
+    pub(crate) chain_id: String,
+    pub(crate) kind: String,
+    pub(crate) endpoint: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fallback_endpoints: Vec<String>,
+    pub(crate) enabled: bool,
+    /// Whether an adapter is serving traffic for this chain.
+    pub(crate) active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) evm_chain_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) explorer_tx_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) explorer_address_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) explorer_block_url: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) erc20_tokens: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bech32_prefix: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) denoms: BTreeMap<String, String>,
+    pub(crate) updated_by: String,
+    pub(crate) updated_at_epoch_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) health: Option<ChainHealth>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsHealthHistoryQuery {
+    pub(crate) limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsHealthHistoryResponse {
+    pub(crate) interval_seconds: u64,
+    pub(crate) capacity: usize,
+    /// Oldest first.
+    pub(crate) samples: Vec<HealthSample>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsStorageResponse {
+    pub(crate) schema_version: u32,
+    #[serde(flatten)]
+    pub(crate) stats: StorageStats,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsChainListResponse {
+    pub(crate) chains: Vec<OpsChainSummary>,
+    pub(crate) total: usize,
+}
+
 pub(crate) async fn ops_get_binding(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -38,9 +172,7 @@ pub(crate) async fn ops_get_binding(
     )
     .await?;
 
-    if wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &wallet_address)?;
 
     let record = if let Some(repo) = &state.postgres_repo {
         match repo.load_wallet_binding(&wallet_address).await {
@@ -74,7 +206,7 @@ pub(crate) async fn ops_list_audit(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(query): Query<OpsAuditQuery>,
-) -> ApiResult<OpsAuditResponse> {
+) -> Result<Response, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
     let _ops_user = require_ops_access(
         &state,
         &headers,
@@ -83,15 +215,105 @@ pub(crate) async fn ops_list_audit(
     )
     .await?;
 
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    if ndjson::requested(&headers) {
+        // Every matching event unless `limit` is given; no 500 cap.
+        let limit = query.limit;
+        return Ok(ndjson::stream_pages(limit, None, move |cursor: Option<String>| {
+            let state = Arc::clone(&state);
+            let query = query.clone();
+            async move {
+                let page = load_audit_page(&state, &query, ndjson::PAGE_SIZE, cursor.as_deref()).await?;
+                Ok((page.events, page.next_cursor))
+            }
+        }));
+    }
+
     let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let events = load_audit_events(&state, &ctx, &query, limit).await?;
 
-    let events = if let Some(repo) = &state.postgres_repo {
-        match repo
-            .list_audit_events(
-                limit,
-                query.event_type.as_deref(),
-                query.wallet_address.as_deref(),
-                query.outcome.as_deref(),
+    Ok(Json(OpsAuditResponse { events }).into_response())
+}
+
+/// One page of the audit trail, newest first, as `/v2` serves it.
+pub(crate) async fn audit_page(
+    state: &AppState,
+    headers: &HeaderMap,
+    query: &OpsAuditQuery,
+    page: &PageQuery,
+) -> Result<Page<AuditEventRecord>, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    require_ops_access(state, headers, "ops_list_audit", query.wallet_address.as_deref()).await?;
+    let limit = page.limit_or(100, 500);
+    let events = load_audit_page(state, query, limit, page.start())
+        .await
+        .map_err(internal_error)?;
+    Ok(Page::new(events.events, events.next_cursor))
+}
+
+/// GET /ops/audit/export — the audit trail as a CSV download, with the same
+/// filters as `/ops/audit` and up to 10000 events.
+pub(crate) async fn ops_export_audit(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<OpsAuditQuery>,
+) -> Result<Response, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    let _ops_user = require_ops_access(
+        &state,
+        &headers,
+        "ops_export_audit",
+        query.wallet_address.as_deref(),
+    )
+    .await?;
+
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let limit = query.limit.unwrap_or(1000).clamp(1, MAX_AUDIT_EXPORT);
+    let events = load_audit_events(&state, &ctx, &query, limit).await?;
+    let now = epoch_ms().map_err(internal_error)?;
+
+    Ok(crate::export::csv_download(
+        &format!("keycortex-audit-{now}.csv"),
+        &[
+            "event_id",
+            "timestamp_epoch_ms",
+            "event_type",
+            "outcome",
+            "wallet_address",
+            "user_id",
+            "chain",
+            "message",
+        ],
+        events.into_iter().map(|event| {
+            vec![
+                event.event_id,
+                event.timestamp_epoch_ms.to_string(),
+                event.event_type,
+                event.outcome,
+                event.wallet_address.unwrap_or_default(),
+                event.user_id.unwrap_or_default(),
+                event.chain.unwrap_or_default(),
+                event.message.unwrap_or_default(),
+            ]
+        }),
+    ))
+}
+
+/// Audit events from Postgres when configured, falling back to RocksDB.
+async fn load_audit_events(
+    state: &AppState,
+    ctx: &RequestContext,
+    query: &OpsAuditQuery,
+    limit: usize,
+) -> Result<Vec<AuditEventRecord>, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    Ok(if let Some(repo) = &state.postgres_repo {
+        match ctx
+            .run(
+                "list_audit_events",
+                repo.list_audit_events(
+                    limit,
+                    query.event_type.as_deref(),
+                    query.wallet_address.as_deref(),
+                    query.outcome.as_deref(),
+                ),
             )
             .await
         {
@@ -104,36 +326,598 @@ pub(crate) async fn ops_list_audit(
                 );
                 state
                     .keystore
-                    .list_audit_events(
+                    .list_audit_events_within(
+                        ctx.scan_deadline(),
                         limit,
                         query.event_type.as_deref(),
                         query.wallet_address.as_deref(),
                         query.outcome.as_deref(),
                     )
-                    .map_err(internal_error)?
+                    .map_err(deadline::api_error)?
             }
         }
     } else {
         state
             .keystore
-            .list_audit_events(
+            .list_audit_events_within(
+                ctx.scan_deadline(),
                 limit,
                 query.event_type.as_deref(),
                 query.wallet_address.as_deref(),
                 query.outcome.as_deref(),
             )
-            .map_err(internal_error)?
+            .map_err(deadline::api_error)?
+    })
+}
+
+/// One [`ndjson::PAGE_SIZE`] page of audit events after `cursor`, from
+/// Postgres when configured, else RocksDB.
+async fn load_audit_page(
+    state: &AppState,
+    query: &OpsAuditQuery,
+    limit: usize,
+    cursor: Option<&str>,
+) -> anyhow::Result<AuditEventPage> {
+    if let Some(repo) = &state.postgres_repo {
+        match repo
+            .list_audit_events_page(
+                limit,
+                cursor,
+                query.event_type.as_deref(),
+                query.wallet_address.as_deref(),
+                query.outcome.as_deref(),
+            )
+            .await
+        {
+            Ok(page) => return Ok(page),
+            Err(err) => {
+                state.db_fallback_counters.inc_audit_read_failures();
+                warn!(
+                    "failed to list audit events from Postgres: {}. Falling back to RocksDB",
+                    err
+                );
+            }
+        }
+    }
+    state.keystore.list_audit_events_page(
+        limit,
+        cursor,
+        query.event_type.as_deref(),
+        query.wallet_address.as_deref(),
+        query.outcome.as_deref(),
+    )
+}
+
+/// POST /ops/honeytokens — flag or unflag a wallet as a honeytoken tripwire.
+pub(crate) async fn ops_set_honeytoken(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<OpsHoneytokenRequest>,
+) -> ApiResult<OpsHoneytokenResponse> {
+    let ops_user = require_ops_access(
+        &state,
+        &headers,
+        "ops_set_honeytoken",
+        Some(request.wallet_address.as_str()),
+    )
+    .await?;
+
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+
+    state
+        .keystore
+        .set_wallet_honeytoken(&request.wallet_address, request.honeytoken)
+        .map_err(internal_error)?;
+
+    crate::auth::append_audit_event(
+        &state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "ops_set_honeytoken".to_owned(),
+            wallet_address: Some(request.wallet_address.clone()),
+            user_id: Some(ops_user),
+            chain: Some(FLOWCORTEX_L1.to_owned()),
+            outcome: "success".to_owned(),
+            message: Some(format!("honeytoken={}", request.honeytoken)),
+            timestamp_epoch_ms: epoch_ms().unwrap_or_default(),
+            tenant: None,
+        },
+    )
+    .await;
+
+    Ok(Json(OpsHoneytokenResponse {
+        wallet_address: request.wallet_address,
+        honeytoken: request.honeytoken,
+    }))
+}
+
+/// GET /ops/honeytokens — list flagged wallets (never exposed via /wallet/list).
+pub(crate) async fn ops_list_honeytokens(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<OpsHoneytokenListResponse> {
+    let _ops_user = require_ops_access(&state, &headers, "ops_list_honeytokens", None).await?;
+
+    let wallets = state
+        .keystore
+        .list_honeytoken_wallets()
+        .map_err(internal_error)?;
+    let total = wallets.len();
+
+    Ok(Json(OpsHoneytokenListResponse { wallets, total }))
+}
+
+/// GET /ops/devices/{user_id} — list a user's registered devices.
+pub(crate) async fn ops_list_user_devices(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(user_id): Path<String>,
+) -> ApiResult<DeviceListResponse> {
+    require_ops_access(&state, &headers, "ops_list_user_devices", None).await?;
+
+    let devices: Vec<_> = state
+        .keystore
+        .list_user_devices(&user_id)
+        .map_err(internal_error)?
+        .into_iter()
+        .map(crate::devices::summary)
+        .collect();
+    let total = devices.len();
+
+    Ok(Json(DeviceListResponse {
+        user_id,
+        devices,
+        total,
+    }))
+}
+
+/// POST /ops/devices/approve — operator approval of a pending device
+/// (e.g. after out-of-band email confirmation).
+pub(crate) async fn ops_approve_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<OpsDeviceApproveRequest>,
+) -> ApiResult<DeviceSummary> {
+    let ops_user = require_ops_access(&state, &headers, "ops_approve_device", None).await?;
+
+    let record = state
+        .keystore
+        .load_user_device(&request.user_id, &request.device_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| crate::not_found("device not found"))?;
+
+    crate::devices::approve_device(&state, record, format!("ops:{ops_user}"), &ops_user).await
+}
+
+/// GET /ops/wallets/deleted — soft-deleted wallets, including purged ones.
+pub(crate) async fn ops_list_deleted_wallets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<OpsDeletedWalletListResponse> {
+    require_ops_access(&state, &headers, "ops_list_deleted_wallets", None).await?;
+
+    let wallets = state
+        .keystore
+        .list_wallet_tombstones()
+        .map_err(internal_error)?;
+    let total = wallets.len();
+
+    Ok(Json(OpsDeletedWalletListResponse { wallets, total }))
+}
+
+/// POST /ops/wallets/undelete — restore a deleted wallet's key, binding,
+/// metadata and device link before its grace period ends.
+pub(crate) async fn ops_undelete_wallet(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<OpsUndeleteRequest>,
+) -> ApiResult<OpsUndeleteResponse> {
+    let ops_user = require_ops_access(
+        &state,
+        &headers,
+        "ops_undelete_wallet",
+        Some(request.wallet_address.as_str()),
+    )
+    .await?;
+
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+
+    let undeleted = state
+        .keystore
+        .undelete_wallet(&request.wallet_address, &ops_user, epoch_ms().map_err(internal_error)?)
+        .map_err(internal_error)?;
+    let change = match undeleted {
+        WalletUndelete::Restored(change) => change,
+        WalletUndelete::NotDeleted => return Err(crate::not_found("deleted wallet not found")),
+        WalletUndelete::Expired => {
+            return Err(bad_request("undelete grace period has ended for this wallet"));
+        }
+        WalletUndelete::AddressInUse => {
+            return Err(bad_request("a wallet with this address exists again"));
+        }
     };
+    crate::mirror_wallet_tombstone(&state, &change).await;
+
+    Ok(Json(OpsUndeleteResponse {
+        wallet_address: request.wallet_address,
+        restored: true,
+        user_id: change.tombstone.binding.map(|binding| binding.user_id),
+    }))
+}
 
-    Ok(Json(OpsAuditResponse { events }))
+/// GET /ops/health/history — recent periodic health samples, oldest first.
+pub(crate) async fn ops_health_history(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<OpsHealthHistoryQuery>,
+) -> ApiResult<OpsHealthHistoryResponse> {
+    require_ops_access(&state, &headers, "ops_health_history", None).await?;
+
+    let history = &state.health_history;
+    let limit = query.limit.unwrap_or(history.capacity());
+    Ok(Json(OpsHealthHistoryResponse {
+        interval_seconds: history.interval().as_secs(),
+        capacity: history.capacity(),
+        samples: history.recent(limit),
+    }))
+}
+
+/// GET /ops/storage — RocksDB size, compaction and write-latency figures
+/// plus key counts per prefix, for capacity planning.
+pub(crate) async fn ops_storage_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<OpsStorageResponse> {
+    require_ops_access(&state, &headers, "ops_storage_stats", None).await?;
+
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let stats = state
+        .keystore
+        .storage_stats_with_key_counts(ctx.scan_deadline())
+        .map_err(deadline::api_error)?;
+    let schema_version = state.keystore.schema_version().map_err(internal_error)?;
+
+    Ok(Json(OpsStorageResponse { schema_version, stats }))
+}
+
+/// GET /ops/chains — configured chain adapters; `?probe=true` health-checks each.
+pub(crate) async fn ops_list_chains(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<OpsChainListQuery>,
+) -> ApiResult<OpsChainListResponse> {
+    require_ops_access(&state, &headers, "ops_list_chains", None).await?;
+
+    let entries: Vec<(ChainAdapterRecord, bool)> = {
+        let table = state
+            .chains
+            .read()
+            .map_err(|_| internal_error("chain table lock poisoned"))?;
+        table
+            .configs()
+            .map(|record| (record.clone(), table.adapter(&record.chain_id).is_some()))
+            .collect()
+    };
+
+    let http = crate::chains::http(&state);
+    let mut chains = Vec::with_capacity(entries.len());
+    for (record, active) in entries {
+        let health = if query.probe.unwrap_or(false) {
+            Some(crate::chains::probe(&http, &record).await)
+        } else {
+            None
+        };
+        chains.push(chain_summary(record, active, health));
+    }
+    let total = chains.len();
+
+    Ok(Json(OpsChainListResponse { chains, total }))
+}
+
+/// POST /ops/chains — add, re-point, enable or disable a chain adapter.
+///
+/// The change takes effect immediately and is persisted, so it survives restarts.
+pub(crate) async fn ops_upsert_chain(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<OpsChainUpsertRequest>,
+) -> ApiResult<OpsChainSummary> {
+    let ops_user = require_ops_access(&state, &headers, "ops_upsert_chain", None).await?;
+
+    let chain_id = request.chain_id.trim().to_owned();
+    validate_chain_id(&chain_id)?;
+
+    let existing = state
+        .chains
+        .read()
+        .map_err(|_| internal_error("chain table lock poisoned"))?
+        .config(&chain_id)
+        .cloned();
+
+    let kind = match (request.kind, &existing) {
+        (Some(kind), _) => kind.trim().to_ascii_lowercase(),
+        (None, Some(existing)) => existing.kind.clone(),
+        (None, None) => return Err(bad_request("kind is required for a new chain")),
+    };
+    validate_chain_kind(&kind)?;
+
+    let endpoint = match (request.endpoint, &existing) {
+        (Some(endpoint), _) => endpoint.trim().trim_end_matches('/').to_owned(),
+        (None, Some(existing)) => existing.endpoint.clone(),
+        (None, None) => return Err(bad_request("endpoint is required for a new chain")),
+    };
+    validate_chain_endpoint(&endpoint)?;
+
+    let explorer_tx_url = explorer_template(
+        request.explorer_tx_url,
+        existing.as_ref().and_then(|e| e.explorer_tx_url.clone()),
+        "explorer_tx_url",
+        "{tx_hash}",
+    )?;
+    let explorer_address_url = explorer_template(
+        request.explorer_address_url,
+        existing.as_ref().and_then(|e| e.explorer_address_url.clone()),
+        "explorer_address_url",
+        "{address}",
+    )?;
+    let explorer_block_url = explorer_template(
+        request.explorer_block_url,
+        existing.as_ref().and_then(|e| e.explorer_block_url.clone()),
+        "explorer_block_url",
+        "{height}",
+    )?;
+
+    let erc20_tokens = match request.erc20_tokens {
+        Some(tokens) => validate_erc20_tokens(tokens)?,
+        None => existing.as_ref().map(|e| e.erc20_tokens.clone()).unwrap_or_default(),
+    };
+    let bech32_prefix = match request.bech32_prefix {
+        Some(prefix) => Some(validate_bech32_prefix(&prefix)?),
+        None => existing.as_ref().and_then(|e| e.bech32_prefix.clone()),
+    };
+    let denoms = match request.denoms {
+        Some(denoms) => validate_denoms(denoms)?,
+        None => existing.as_ref().map(|e| e.denoms.clone()).unwrap_or_default(),
+    };
+    validate_kind_fields(&kind, &erc20_tokens, bech32_prefix.as_deref(), &denoms)?;
+    // Fallback nodes come from chains.toml and follow the chain while it
+    // stays FlowCortex.
+    let fallback_endpoints = existing
+        .as_ref()
+        .filter(|_| kind == KIND_FLOWCORTEX)
+        .map(|e| e.fallback_endpoints.clone())
+        .unwrap_or_default();
+
+    let record = ChainAdapterRecord {
+        chain_id: chain_id.clone(),
+        explorer_tx_url,
+        explorer_address_url,
+        explorer_block_url,
+        erc20_tokens,
+        bech32_prefix,
+        denoms,
+        evm_chain_id: request
+            .evm_chain_id
+            .or_else(|| existing.as_ref().and_then(|e| e.evm_chain_id))
+            .filter(|_| kind == KIND_EVM),
+        kind,
+        endpoint,
+        fallback_endpoints,
+        enabled: request
+            .enabled
+            .or_else(|| existing.as_ref().map(|e| e.enabled))
+            .unwrap_or(true),
+        updated_by: ops_user.clone(),
+        updated_at_epoch_ms: epoch_ms().map_err(internal_error)?,
+    };
+
+    state
+        .keystore
+        .save_chain_adapter(&record)
+        .map_err(internal_error)?;
+    let active = {
+        let mut table = state
+            .chains
+            .write()
+            .map_err(|_| internal_error("chain table lock poisoned"))?;
+        table.apply(record.clone());
+        table.adapter(&chain_id).is_some()
+    };
+
+    crate::auth::append_audit_event(
+        &state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "ops_upsert_chain".to_owned(),
+            wallet_address: None,
+            user_id: Some(ops_user),
+            chain: Some(chain_id),
+            outcome: "success".to_owned(),
+            message: Some(format!(
+                "kind={} endpoint={} enabled={}",
+                record.kind, record.endpoint, record.enabled
+            )),
+            timestamp_epoch_ms: record.updated_at_epoch_ms,
+            tenant: None,
+        },
+    )
+    .await;
+
+    let health = crate::chains::probe(&crate::chains::http(&state), &record).await;
+    Ok(Json(chain_summary(record, active, Some(health))))
+}
+
+/// Resolve an explorer template from an upsert: omitted keeps `existing`,
+/// an empty string clears it, anything else must be an http(s) URL carrying
+/// `placeholder`.
+pub(crate) fn validate_chain_id(
+    chain_id: &str,
+) -> Result<(), (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    parse_field::<ChainId>("chain_id", chain_id)?;
+    Ok(())
+}
+
+pub(crate) fn validate_chain_kind(
+    kind: &str,
+) -> Result<(), (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    if ![KIND_FLOWCORTEX, KIND_EVM, KIND_COSMOS, KIND_BITCOIN].contains(&kind) {
+        return Err(bad_request(
+            "unsupported kind; expected flowcortex, evm, cosmos or bitcoin",
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_chain_endpoint(
+    endpoint: &str,
+) -> Result<(), (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+        return Err(bad_request("endpoint must be an http(s) URL"));
+    }
+    Ok(())
+}
+
+/// Check that the per-kind settings only appear on chains of that kind.
+pub(crate) fn validate_kind_fields(
+    kind: &str,
+    erc20_tokens: &BTreeMap<String, String>,
+    bech32_prefix: Option<&str>,
+    denoms: &BTreeMap<String, String>,
+) -> Result<(), (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    if kind != KIND_EVM && !erc20_tokens.is_empty() {
+        return Err(bad_request("erc20_tokens are only supported for evm chains"));
+    }
+    let uses_bech32 = kind == KIND_COSMOS || kind == KIND_BITCOIN;
+    if uses_bech32 && bech32_prefix.is_none() {
+        return Err(bad_request("bech32_prefix is required for cosmos and bitcoin chains"));
+    }
+    if !uses_bech32 && bech32_prefix.is_some() {
+        return Err(bad_request("bech32_prefix is only supported for cosmos and bitcoin chains"));
+    }
+    if kind != KIND_COSMOS && !denoms.is_empty() {
+        return Err(bad_request("denoms are only supported for cosmos chains"));
+    }
+    Ok(())
+}
+
+/// Upper-case the symbols and check each contract is a 20-byte hex address.
+pub(crate) fn validate_erc20_tokens(
+    tokens: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    tokens
+        .into_iter()
+        .map(|(symbol, contract)| {
+            let symbol = symbol.trim().to_ascii_uppercase();
+            let contract = contract.trim().to_owned();
+            let valid_contract = contract
+                .strip_prefix("0x")
+                .is_some_and(|digits| digits.len() == 40 && digits.chars().all(|c| c.is_ascii_hexdigit()));
+            if symbol.is_empty() || !valid_contract {
+                return Err(bad_request(&format!(
+                    "erc20_tokens entry '{symbol}' must map a symbol to a 0x-prefixed 20-byte contract address"
+                )));
+            }
+            Ok((symbol, contract))
+        })
+        .collect()
+}
+
+/// A lowercase bech32 human-readable part such as `cosmos` or `osmo`.
+pub(crate) fn validate_bech32_prefix(
+    prefix: &str,
+) -> Result<String, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    let prefix = prefix.trim();
+    let valid = prefix
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if prefix.is_empty() || prefix.len() > 32 || !valid {
+        return Err(bad_request(
+            "bech32_prefix must be 1-32 characters of a-z and 0-9",
+        ));
+    }
+    Ok(prefix.to_owned())
+}
+
+/// Upper-case the symbols and check each denom has the bank module's shape,
+/// e.g. `uatom` or `ibc/27394FB0...`.
+pub(crate) fn validate_denoms(
+    denoms: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    denoms
+        .into_iter()
+        .map(|(symbol, denom)| {
+            let symbol = symbol.trim().to_ascii_uppercase();
+            let denom = denom.trim().to_owned();
+            let valid_denom = (3..=128).contains(&denom.len())
+                && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+                && denom
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+            if symbol.is_empty() || !valid_denom {
+                return Err(bad_request(&format!(
+                    "denoms entry '{symbol}' must map a symbol to a bank denom such as uatom"
+                )));
+            }
+            Ok((symbol, denom))
+        })
+        .collect()
+}
+
+pub(crate) fn explorer_template(
+    requested: Option<String>,
+    existing: Option<String>,
+    field: &str,
+    placeholder: &str,
+) -> Result<Option<String>, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
+    let Some(template) = requested else {
+        return Ok(existing);
+    };
+    let template = template.trim();
+    if template.is_empty() {
+        return Ok(None);
+    }
+    if !template.starts_with("http://") && !template.starts_with("https://") {
+        return Err(bad_request(&format!("{field} must be an http(s) URL")));
+    }
+    if !template.contains(placeholder) {
+        return Err(bad_request(&format!(
+            "{field} must contain a {placeholder} placeholder"
+        )));
+    }
+    Ok(Some(template.to_owned()))
+}
+
+fn chain_summary(
+    record: ChainAdapterRecord,
+    active: bool,
+    health: Option<ChainHealth>,
+) -> OpsChainSummary {
+    OpsChainSummary {
+        chain_id: record.chain_id,
+        kind: record.kind,
+        endpoint: record.endpoint,
+        fallback_endpoints: record.fallback_endpoints,
+        enabled: record.enabled,
+        active,
+        evm_chain_id: record.evm_chain_id,
+        explorer_tx_url: record.explorer_tx_url,
+        explorer_address_url: record.explorer_address_url,
+        explorer_block_url: record.explorer_block_url,
+        erc20_tokens: record.erc20_tokens,
+        bech32_prefix: record.bech32_prefix,
+        denoms: record.denoms,
+        updated_by: record.updated_by,
+        updated_at_epoch_ms: record.updated_at_epoch_ms,
+        health,
+    }
 }
 
-async fn require_ops_access(
+pub(crate) async fn require_ops_access(
     state: &AppState,
     headers: &HeaderMap,
     operation: &str,
     wallet_address: Option<&str>,
-) -> Result<String, (axum::http::StatusCode, Json<crate::ErrorResponse>)> {
+) -> Result<String, (axum::http::StatusCode, Json<kc_api_types::ApiError>)> {
     let now = epoch_ms().unwrap_or_default();
 
     let principal = match crate::auth::parse_authbuddy_principal(headers, state) {
@@ -150,6 +934,7 @@ async fn require_ops_access(
                     outcome: "denied".to_owned(),
                     message: Some(format!("{operation}: {message}")),
                     timestamp_epoch_ms: now,
+                    tenant: None,
                 },
             )
             .await;
@@ -157,8 +942,7 @@ async fn require_ops_access(
         }
     };
 
-    let has_ops_role = principal.roles.iter().any(|role| role == "ops-admin");
-    if !has_ops_role {
+    if !principal.is_ops_admin() {
         crate::auth::append_audit_event(
             state,
             AuditEventRecord {
@@ -170,6 +954,7 @@ async fn require_ops_access(
                 outcome: "denied".to_owned(),
                 message: Some(format!("{operation}: missing ops-admin role in JWT claims")),
                 timestamp_epoch_ms: now,
+                tenant: None,
             },
         )
         .await;
@@ -187,6 +972,7 @@ async fn require_ops_access(
             outcome: "success".to_owned(),
             message: Some(format!("{operation}: access granted")),
             timestamp_epoch_ms: now,
+            tenant: None,
         },
     )
     .await;
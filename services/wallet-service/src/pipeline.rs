@@ -0,0 +1,601 @@
+//! The wallet-service stages of the transfer [`TransactionPipeline`].
+//!
+//! Every transfer KeyCortex broadcasts, from `/wallet/submit` to escrow
+//! releases, bridge legs and demo seeding, goes through [`sign_and_submit`]
+//! or [`submit_presigned`]. They assemble the pipeline from the stages here:
+//!
+//! - validation: [`BindEnvelope`], then [`VerifySignature`] for transfers
+//!   signed elsewhere, then [`ClaimNonce`];
+//! - policy: [`CheckPolicy`], for transfers KeyCortex signs, which may hold
+//!   the transfer for approval and end the run;
+//! - signing: [`SignWith`], for transfers KeyCortex signs;
+//! - broadcast: [`Broadcast`], or [`Simulate`] for a dry run;
+//! - persistence: [`PersistSubmission`], skipped by a dry run.
+
+use async_trait::async_trait;
+use axum::{Json, http::StatusCode};
+use kc_api_types::{
+    Amount, AssetSymbol, ChainId, SignPurpose, SubmitSimulation, TransactionEnvelope, TxEnvelopeError,
+    WalletAddress, WalletSubmitRequest, WalletSubmitResponse,
+};
+use kc_chain_client::{NonceStrategy, SubmitTxRequest};
+use kc_crypto::{Ed25519PublicKey, Ed25519Signer, Signer, SigningDomain};
+use kc_crypto_kms::KmsSigner;
+use kc_storage::{
+    SubmitIdempotencyRecord, SubmittedTxRecord, TxStatusChange, WalletActivity, WalletNonceRecord,
+};
+use kc_wallet_core::{
+    NonceClaim, PendingTransaction, PolicyContext, PolicyDecision, TransactionPipeline, TransactionStage,
+    TransferDetails,
+};
+use tracing::{info, warn};
+
+use crate::deadline::{self, RequestContext};
+use crate::{
+    AppState, ApiError, bad_request, epoch_ms, from_hex, internal_error, invalid_field,
+    nonce_conflict, unauthorized,
+};
+
+/// Envelope lifetime for server-signed transfers without an explicit expiry.
+const DEFAULT_ENVELOPE_TTL_MS: u128 = 5 * 60 * 1000;
+/// Longest envelope lifetime accepted from callers.
+const MAX_ENVELOPE_TTL_MS: u128 = 24 * 60 * 60 * 1000;
+
+type StageError = (StatusCode, Json<ApiError>);
+type Pipeline<'a> = TransactionPipeline<'a, StageError>;
+
+/// What [`CheckPolicy`] does with a transfer the policy holds for approval.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum OnApproval<'a> {
+    /// Refuse it with 403.
+    Refuse,
+    /// Hold it for approval, noting who submitted it; the response carries
+    /// the approval id and nothing is signed.
+    Hold { requested_by: Option<&'a str> },
+    /// Sign it: it has been approved.
+    Approved,
+}
+
+/// The key a server-signed transfer is signed with.
+#[derive(Clone, Copy)]
+pub(crate) enum TxSigner<'a> {
+    Custodied(&'a Ed25519Signer),
+    /// Signed over the network, so [`SignWith`] awaits it rather than
+    /// blocking the runtime.
+    Kms(&'a KmsSigner),
+}
+
+/// Sign the transfer in a v2 envelope, expiring after
+/// [`DEFAULT_ENVELOPE_TTL_MS`] unless it names an expiry, and broadcast it.
+/// A transfer that needs approval is refused.
+pub(crate) async fn sign_and_submit(
+    state: &AppState,
+    ctx: &RequestContext,
+    signer: TxSigner<'_>,
+    request: &WalletSubmitRequest,
+    idempotency_key: Option<&str>,
+) -> Result<WalletSubmitResponse, StageError> {
+    sign_and_submit_with(state, ctx, signer, request, idempotency_key, OnApproval::Refuse).await
+}
+
+/// [`sign_and_submit`], with `on_approval` deciding what happens to a
+/// transfer that needs approval.
+pub(crate) async fn sign_and_submit_with(
+    state: &AppState,
+    ctx: &RequestContext,
+    signer: TxSigner<'_>,
+    request: &WalletSubmitRequest,
+    idempotency_key: Option<&str>,
+    on_approval: OnApproval<'_>,
+) -> Result<WalletSubmitResponse, StageError> {
+    let mut request = request.clone();
+    if request.expires_at_epoch_ms.is_none() {
+        request.expires_at_epoch_ms =
+            Some(epoch_ms().map_err(internal_error)? + DEFAULT_ENVELOPE_TTL_MS);
+    }
+    let pipeline = Pipeline::new()
+        .validate(BindEnvelope(state))
+        .validate(ClaimNonce { state, ctx })
+        .policy(CheckPolicy { state, on_approval })
+        .sign(SignWith(signer));
+    let mut tx = PendingTransaction::new(request, state.signing_domain.clone());
+    let submitted = run(pipeline, state, ctx, idempotency_key, &mut tx).await;
+
+    if tx.signature.is_some() {
+        let activity = if submitted.is_ok() && !tx.request.dry_run {
+            WalletActivity::SignedAndSubmitted
+        } else {
+            WalletActivity::Signed
+        };
+        crate::record_wallet_activity(state, &tx.request.from, activity).await;
+    }
+    submitted
+}
+
+/// Verify an externally produced signature over the canonical payload, then
+/// check the nonce and broadcast it unchanged.
+///
+/// With `expires_at_epoch_ms` set the signature must be a v2 envelope
+/// signature; otherwise it is checked as v1.
+pub(crate) async fn submit_presigned(
+    state: &AppState,
+    ctx: &RequestContext,
+    public_key: &Ed25519PublicKey,
+    request: &WalletSubmitRequest,
+    signature_hex: &str,
+    idempotency_key: Option<&str>,
+) -> Result<WalletSubmitResponse, StageError> {
+    let signature = from_hex(signature_hex)
+        .map_err(|e| bad_request(&format!("invalid signed_payload hex: {e}")))?;
+    let pipeline = Pipeline::new()
+        .validate(BindEnvelope(state))
+        .validate(VerifySignature(public_key))
+        .validate(ClaimNonce { state, ctx });
+    let mut tx = PendingTransaction::new(request.clone(), state.signing_domain.clone());
+    tx.signature = Some(signature);
+    let response = run(pipeline, state, ctx, idempotency_key, &mut tx).await?;
+    if !request.dry_run {
+        crate::record_wallet_activity(state, &request.from, WalletActivity::Submitted).await;
+    }
+    Ok(response)
+}
+
+/// Finish `pipeline` with the broadcast and persistence stages, or with a
+/// simulation for a dry run, and run `tx` through it, audited to the wallet
+/// core's sink.
+async fn run(
+    pipeline: Pipeline<'_>,
+    state: &AppState,
+    ctx: &RequestContext,
+    idempotency_key: Option<&str>,
+    tx: &mut PendingTransaction,
+) -> Result<WalletSubmitResponse, StageError> {
+    let mut pipeline = if tx.request.dry_run {
+        pipeline.broadcast(Simulate { state, ctx })
+    } else {
+        pipeline
+            .broadcast(Broadcast { state, ctx })
+            .persist(PersistSubmission {
+                state,
+                idempotency_key,
+            })
+    };
+    if let Some(sink) = state.wallet_core.audit_sink() {
+        pipeline = pipeline.audit(sink);
+    }
+    pipeline.run(tx).await?;
+    Ok(submit_response(tx))
+}
+
+fn submit_response(tx: &PendingTransaction) -> WalletSubmitResponse {
+    WalletSubmitResponse {
+        accepted: tx.submitted.as_ref().is_some_and(|result| result.accepted),
+        tx_hash: tx
+            .submitted
+            .as_ref()
+            .map(|result| result.tx_hash.clone())
+            .unwrap_or_default(),
+        signature: tx.signature_hex().unwrap_or_default(),
+        expires_at_epoch_ms: tx.request.expires_at_epoch_ms,
+        simulation: tx.simulation.as_ref().map(|result| SubmitSimulation {
+            would_accept: result.would_accept,
+            reason: result.reason.clone(),
+            fee: result.fee.as_ref().map(|fee| fee.fee.clone()),
+            fee_asset: result.fee.as_ref().map(|fee| fee.fee_asset.0.clone()),
+        }),
+        approval_id: tx.approval_id.clone(),
+    }
+}
+
+/// Binds a transfer with an expiry to its v2 [`TransactionEnvelope`], so it
+/// is signed, or its signature checked, in the envelope's domain.
+struct BindEnvelope<'a>(&'a AppState);
+
+#[async_trait]
+impl TransactionStage<StageError> for BindEnvelope<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        if let Some(expires_at) = tx.request.expires_at_epoch_ms {
+            tx.signing_domain = envelope_domain(self.0, &envelope(&tx.request, expires_at)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the signature a transfer signed outside KeyCortex arrived with.
+struct VerifySignature<'a>(&'a Ed25519PublicKey);
+
+#[async_trait]
+impl TransactionStage<StageError> for VerifySignature<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        let payload = tx.canonical_payload().map_err(envelope_error)?;
+        let valid = self
+            .0
+            .verify_in_domain(
+                &tx.signing_domain,
+                payload.as_bytes(),
+                SignPurpose::Transaction,
+                tx.signature.as_deref().unwrap_or_default(),
+            )
+            .map_err(|e| bad_request(&format!("invalid signed_payload: {e}")))?;
+        if !valid {
+            return Err(unauthorized("signed_payload does not verify against the wallet public key"));
+        }
+        Ok(())
+    }
+}
+
+/// Claims the transfer's nonce; see [`check_nonce`].
+struct ClaimNonce<'a> {
+    state: &'a AppState,
+    ctx: &'a RequestContext,
+}
+
+#[async_trait]
+impl TransactionStage<StageError> for ClaimNonce<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        tx.nonce_strategy = Some(check_nonce(self.state, self.ctx, &tx.request).await?);
+        Ok(())
+    }
+}
+
+/// Checks the transfer against the source wallet's signing policy, giving
+/// back the nonce [`ClaimNonce`] claimed if the policy refuses or holds it:
+/// a held transfer is signed with a fresh nonce once approved.
+struct CheckPolicy<'a> {
+    state: &'a AppState,
+    on_approval: OnApproval<'a>,
+}
+
+impl CheckPolicy<'_> {
+    /// The approval id if the transfer is held, `None` if it may be signed.
+    async fn approval_required(
+        &self,
+        request: &WalletSubmitRequest,
+        reason: String,
+    ) -> Result<Option<String>, StageError> {
+        match self.on_approval {
+            OnApproval::Refuse => {
+                crate::policy::refuse(PolicyDecision::RequireApproval(reason)).map(|()| None)
+            }
+            OnApproval::Hold { requested_by } => {
+                let record = crate::approvals::hold(self.state, request, reason, requested_by).await?;
+                Ok(Some(record.approval_id))
+            }
+            OnApproval::Approved => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionStage<StageError> for CheckPolicy<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        let request = &tx.request;
+        let amount =
+            Amount::parse_base_units(&request.amount).map_err(|e| invalid_field("amount", e))?;
+        let ctx = PolicyContext {
+            wallet_address: &request.from,
+            purpose: &SignPurpose::Transaction,
+            transfer: Some(TransferDetails {
+                to: &request.to,
+                asset: &request.asset,
+                chain: &request.chain,
+                amount,
+            }),
+        };
+        // A dry run is never held, only told the transfer needs approval.
+        let checked = match crate::policy::evaluate(self.state, &ctx).await {
+            Ok(PolicyDecision::RequireApproval(reason)) if !request.dry_run => {
+                self.approval_required(request, reason).await
+            }
+            Ok(decision) => crate::policy::refuse(decision).map(|()| None),
+            Err(err) => Err(err),
+        };
+        if !matches!(checked, Ok(None)) && !request.dry_run {
+            release_nonce(self.state, request).await;
+        }
+        tx.approval_id = checked?;
+        Ok(())
+    }
+}
+
+/// Signs the canonical payload with a custodied or KMS-held key.
+struct SignWith<'a>(TxSigner<'a>);
+
+#[async_trait]
+impl TransactionStage<StageError> for SignWith<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        let payload = tx.canonical_payload().map_err(envelope_error)?;
+        let signature = match self.0 {
+            TxSigner::Custodied(signer) => {
+                signer.sign_in_domain(&tx.signing_domain, payload.as_bytes(), SignPurpose::Transaction)
+            }
+            TxSigner::Kms(signer) => {
+                signer
+                    .sign_in_domain_async(&tx.signing_domain, payload.as_bytes(), SignPurpose::Transaction)
+                    .await
+            }
+        }
+        .map_err(internal_error)?;
+        tx.signature = Some(signature);
+        Ok(())
+    }
+}
+
+/// Asks the chain adapter what it would do with the signed transfer without
+/// broadcasting it. The nonce was only checked, not claimed.
+struct Simulate<'a> {
+    state: &'a AppState,
+    ctx: &'a RequestContext,
+}
+
+#[async_trait]
+impl TransactionStage<StageError> for Simulate<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        let adapter = crate::chains::adapter(self.state, &tx.request.chain)?;
+        let request = submit_tx_request(self.state, &tx.request, &tx.signature_hex().unwrap_or_default())?;
+        let result = self
+            .ctx
+            .run("simulate_transaction", adapter.simulate_transaction(request))
+            .await
+            .map_err(deadline::api_error)?;
+        tx.simulation = Some(result);
+        Ok(())
+    }
+}
+
+/// Hands the signed transfer to the chain adapter, giving its nonce back if
+/// the chain never took it.
+struct Broadcast<'a> {
+    state: &'a AppState,
+    ctx: &'a RequestContext,
+}
+
+#[async_trait]
+impl TransactionStage<StageError> for Broadcast<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        let adapter = crate::chains::adapter(self.state, &tx.request.chain)?;
+        let request = submit_tx_request(self.state, &tx.request, &tx.signature_hex().unwrap_or_default())?;
+        match self
+            .ctx
+            .run("submit_transaction", adapter.submit_transaction(request))
+            .await
+        {
+            Ok(result) => {
+                tx.submitted = Some(result);
+                Ok(())
+            }
+            Err(err) => {
+                release_nonce(self.state, &tx.request).await;
+                Err(deadline::api_error(err))
+            }
+        }
+    }
+}
+
+/// Persists the tx record, the nonce and the `Idempotency-Key` response in
+/// one atomic write, then hands an accepted transfer to the submission
+/// tracker.
+struct PersistSubmission<'a> {
+    state: &'a AppState,
+    idempotency_key: Option<&'a str>,
+}
+
+#[async_trait]
+impl TransactionStage<StageError> for PersistSubmission<'_> {
+    async fn run(&self, tx: &mut PendingTransaction) -> Result<(), StageError> {
+        let state = self.state;
+        let request = &tx.request;
+        let response = submit_response(tx);
+        let now = epoch_ms().map_err(internal_error)?;
+        let status = if response.accepted {
+            "submitted"
+        } else {
+            "rejected"
+        };
+
+        let record = SubmittedTxRecord {
+            tx_hash: response.tx_hash.clone(),
+            status: status.to_owned(),
+            accepted: response.accepted,
+            chain: request.chain.clone(),
+            from: request.from.clone(),
+            to: request.to.clone(),
+            asset: request.asset.clone(),
+            amount: request.amount.clone(),
+            submitted_at_epoch_ms: now,
+            block_height: None,
+            confirmations: None,
+            status_history: vec![TxStatusChange {
+                status: status.to_owned(),
+                at_epoch_ms: now,
+            }],
+        };
+        let nonce = tx
+            .nonce_strategy
+            .is_some_and(NonceStrategy::tracks_nonce)
+            .then(|| WalletNonceRecord {
+                wallet_address: request.from.clone(),
+                last_nonce: request.nonce,
+                updated_at_epoch_ms: now,
+            });
+        let idempotency = self.idempotency_key.map(|key| SubmitIdempotencyRecord {
+            idempotency_key: key.to_owned(),
+            accepted: response.accepted,
+            tx_hash: response.tx_hash.clone(),
+            signature: response.signature.clone(),
+            expires_at_epoch_ms: response.expires_at_epoch_ms,
+            created_at_epoch_ms: now,
+        });
+
+        state
+            .keystore
+            .save_submission_atomic(&record, nonce.as_ref(), idempotency.as_ref())
+            .map_err(internal_error)?;
+        if let Some(repo) = &state.postgres_repo {
+            if let Err(err) = repo
+                .save_submission(&record, nonce.as_ref(), idempotency.as_ref())
+                .await
+            {
+                state.db_fallback_counters.inc_submission_write_failures();
+                warn!("failed to persist submission in Postgres: {}", err);
+            }
+        }
+        if let Some(idempotency) = &idempotency {
+            crate::submit::cache_idempotency(state, idempotency).await;
+        }
+        if record.accepted {
+            crate::submissions::track(state, &record, &response.signature);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn envelope_error(err: TxEnvelopeError) -> (StatusCode, Json<ApiError>) {
+    match err {
+        TxEnvelopeError::Malformed => bad_request(&err.to_string()),
+        TxEnvelopeError::Identifier { field, error } => invalid_field(field, error),
+        TxEnvelopeError::Integer { field } => {
+            invalid_field(field, "must be a decimal integer without leading zeros")
+        }
+    }
+}
+
+
+/// The v2 envelope for `request`, rejecting expiries in the past or beyond
+/// [`MAX_ENVELOPE_TTL_MS`].
+fn envelope(
+    request: &WalletSubmitRequest,
+    expires_at_epoch_ms: u128,
+) -> Result<TransactionEnvelope, (StatusCode, Json<ApiError>)> {
+    let now = epoch_ms().map_err(internal_error)?;
+    if expires_at_epoch_ms <= now {
+        return Err(bad_request("transaction envelope has expired"));
+    }
+    if expires_at_epoch_ms > now + MAX_ENVELOPE_TTL_MS {
+        return Err(bad_request(
+            "expires_at_epoch_ms must be within 24 hours from now",
+        ));
+    }
+    Ok(TransactionEnvelope {
+        chain_id: request.chain.clone(),
+        nonce: request.nonce,
+        expires_at_epoch_ms,
+    })
+}
+
+
+/// Signing domain bound to `envelope`.
+fn envelope_domain(
+    state: &AppState,
+    envelope: &TransactionEnvelope,
+) -> Result<SigningDomain, (StatusCode, Json<ApiError>)> {
+    state
+        .signing_domain
+        .with_envelope(envelope)
+        .map_err(|e| bad_request(&e.to_string()))
+}
+
+
+/// Validate `request.nonce` under the chain adapter's [`NonceStrategy`] and,
+/// where KeyCortex tracks nonces, claim it for this submission. A dry run
+/// only checks the nonce is unused.
+async fn check_nonce(
+    state: &AppState,
+    ctx: &RequestContext,
+    request: &WalletSubmitRequest,
+) -> Result<NonceStrategy, (StatusCode, Json<ApiError>)> {
+    let adapter = crate::chains::adapter(state, &request.chain)?;
+    let strategy = adapter.nonce_strategy();
+    match strategy {
+        NonceStrategy::None | NonceStrategy::RecentBlockhash => return Ok(strategy),
+        NonceStrategy::Sequential if request.nonce == 0 => {
+            return Err(bad_request("nonce must be greater than 0"));
+        }
+        NonceStrategy::Sequential => {}
+        NonceStrategy::ChainQueried => {
+            let account_nonce = ctx
+                .run(
+                    "get_account_nonce",
+                    adapter.get_account_nonce(&WalletAddress(request.from.clone())),
+                )
+                .await
+                .map_err(deadline::api_error)?;
+            reconcile_nonce(state, &request.from, account_nonce)
+                .await
+                .map_err(internal_error)?;
+            if request.nonce < account_nonce {
+                return Err(nonce_conflict(&format!(
+                    "nonce {} is already used on chain; account nonce is {account_nonce}",
+                    request.nonce
+                )));
+            }
+        }
+    }
+
+    // Local ordering also guards against two in-flight submits racing the chain.
+    let now = epoch_ms().map_err(internal_error)?;
+    let claim = if request.dry_run {
+        state.nonces.check(&request.from, request.nonce, now).await
+    } else {
+        state.nonces.claim(&request.from, request.nonce, now).await
+    };
+    match claim.map_err(internal_error)? {
+        NonceClaim::Replay => {
+            return Err(nonce_conflict(
+                "nonce replay detected; nonce must be strictly increasing per wallet",
+            ));
+        }
+        NonceClaim::Accepted { gap: Some(gap) } if strategy == NonceStrategy::Sequential => {
+            warn!(
+                "nonce {} for {} leaves nonces {}..={} unused",
+                request.nonce,
+                request.from,
+                gap.start(),
+                gap.end()
+            );
+        }
+        NonceClaim::Accepted { .. } => {}
+    }
+    Ok(strategy)
+}
+
+
+/// Bring the local nonce state in line with the chain's account nonce for
+/// [`NonceStrategy::ChainQueried`] chains; see [`kc_wallet_core::NonceManager::reconcile`].
+pub(crate) async fn reconcile_nonce(state: &AppState, wallet_address: &str, account_nonce: u64) -> anyhow::Result<()> {
+    if let Some(reconciled) = state.nonces.reconcile(wallet_address, account_nonce).await? {
+        info!(
+            "reconciled nonce of {} with the chain: last {:?} -> {:?}",
+            wallet_address, reconciled.settled, reconciled.chain_last
+        );
+    }
+    Ok(())
+}
+
+
+/// Give back the nonce `check_nonce` claimed for a transfer the chain never
+/// took, so the caller can retry it. The persisted nonce is only written on
+/// success, so dropping the in-memory claim is enough.
+async fn release_nonce(state: &AppState, request: &WalletSubmitRequest) {
+    if let Err(err) = state.nonces.release(&request.from, request.nonce).await {
+        warn!("failed to release nonce {} for {}: {:#}", request.nonce, request.from, err);
+    }
+}
+
+
+fn submit_tx_request(
+    state: &AppState,
+    request: &WalletSubmitRequest,
+    signature_hex: &str,
+) -> Result<SubmitTxRequest, (StatusCode, Json<ApiError>)> {
+    Ok(SubmitTxRequest {
+        from: WalletAddress(request.from.clone()),
+        to: WalletAddress(request.to.clone()),
+        amount: crate::chains::amount(state, &request.chain, &request.asset, &request.amount)?,
+        asset: AssetSymbol(request.asset.clone()),
+        chain: ChainId(request.chain.clone()),
+        signed_payload: signature_hex.to_owned(),
+        chain_payload: None,
+    })
+}
+
@@ -0,0 +1,236 @@
+//! Per-wallet signing policy: the check made before KeyCortex signs with a
+//! wallet's key, and the ops endpoints that configure it.
+//!
+//! Transfers are checked by the policy stage of the transfer pipeline, which
+//! holds those needing approval when they come through `/wallet/submit`
+//! (see `crate::approvals`); payloads signed through `/wallet/sign` are
+//! checked by [`enforce`] directly. A wallet without a
+//! [`WalletPolicyRecord`] signs without restriction.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{Amount, ApiError, AssetSymbol, WalletAddress};
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_storage::{AuditEventRecord, SpendingLimit, WalletPolicyRecord};
+use kc_wallet_core::{PolicyContext, PolicyDecision, WalletPolicy};
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::ops::require_ops_access;
+use crate::{AppState, ApiResult, epoch_ms, forbidden, internal_error, invalid_field, not_found, parse_field};
+
+/// Limits on transfers of one asset, as decimal strings of base units.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct OpsSpendingLimit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_amount: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) approval_above: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpsWalletPolicyRequest {
+    #[serde(default)]
+    pub(crate) spending_limits: BTreeMap<String, OpsSpendingLimit>,
+    #[serde(default)]
+    pub(crate) allowed_destinations: BTreeSet<String>,
+    #[serde(default)]
+    pub(crate) denied_destinations: BTreeSet<String>,
+    #[serde(default)]
+    pub(crate) allowed_purposes: BTreeSet<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsWalletPolicyResponse {
+    pub(crate) wallet_address: String,
+    pub(crate) spending_limits: BTreeMap<String, OpsSpendingLimit>,
+    pub(crate) allowed_destinations: BTreeSet<String>,
+    pub(crate) denied_destinations: BTreeSet<String>,
+    pub(crate) allowed_purposes: BTreeSet<String>,
+    pub(crate) updated_by: String,
+    pub(crate) updated_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct OpsWalletPolicyDeleteResponse {
+    pub(crate) wallet_address: String,
+    pub(crate) deleted: bool,
+}
+
+/// Check what is about to be signed against the policy of
+/// `ctx.wallet_address`: 403 if it is denied or needs an approval.
+pub(crate) async fn enforce(
+    state: &AppState,
+    ctx: &PolicyContext<'_>,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    refuse(evaluate(state, ctx).await?)
+}
+
+/// The policy of `ctx.wallet_address` on what is about to be signed,
+/// audited by the wallet core unless it allows signing.
+pub(crate) async fn evaluate(
+    state: &AppState,
+    ctx: &PolicyContext<'_>,
+) -> Result<PolicyDecision, (StatusCode, Json<ApiError>)> {
+    let Some(record) = state
+        .keystore
+        .load_wallet_policy(ctx.wallet_address)
+        .map_err(internal_error)?
+    else {
+        return Ok(PolicyDecision::Allow);
+    };
+    Ok(state
+        .wallet_core
+        .check_policy(&WalletPolicy::from(&record), ctx)
+        .await)
+}
+
+/// 403 unless `decision` allows signing.
+pub(crate) fn refuse(decision: PolicyDecision) -> Result<(), (StatusCode, Json<ApiError>)> {
+    match decision {
+        PolicyDecision::Allow => Ok(()),
+        PolicyDecision::Deny(reason) => Err(forbidden(&format!("signing policy: {reason}"))),
+        PolicyDecision::RequireApproval(reason) => Err(forbidden(&format!(
+            "signing policy requires approval: {reason}"
+        ))),
+    }
+}
+
+/// GET /ops/wallets/{wallet_address}/policy
+pub(crate) async fn ops_get_wallet_policy(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(wallet_address): Path<String>,
+) -> ApiResult<OpsWalletPolicyResponse> {
+    require_ops_access(&state, &headers, "ops_get_wallet_policy", Some(&wallet_address)).await?;
+    parse_field::<WalletAddress>("wallet_address", &wallet_address)?;
+
+    let record = state
+        .keystore
+        .load_wallet_policy(&wallet_address)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("wallet has no signing policy"))?;
+    Ok(Json(response(record)))
+}
+
+/// PUT /ops/wallets/{wallet_address}/policy — replace the wallet's policy.
+pub(crate) async fn ops_set_wallet_policy(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(wallet_address): Path<String>,
+    Json(request): Json<OpsWalletPolicyRequest>,
+) -> ApiResult<OpsWalletPolicyResponse> {
+    let ops_user =
+        require_ops_access(&state, &headers, "ops_set_wallet_policy", Some(&wallet_address)).await?;
+    parse_field::<WalletAddress>("wallet_address", &wallet_address)?;
+
+    let mut spending_limits = BTreeMap::new();
+    for (asset, limit) in &request.spending_limits {
+        parse_field::<AssetSymbol>("spending_limits", asset)?;
+        spending_limits.insert(
+            asset.clone(),
+            SpendingLimit {
+                max_amount: parse_limit("max_amount", limit.max_amount.as_deref())?,
+                approval_above: parse_limit("approval_above", limit.approval_above.as_deref())?,
+            },
+        );
+    }
+    for destination in request.allowed_destinations.iter().chain(&request.denied_destinations) {
+        parse_field::<WalletAddress>("destinations", destination)?;
+    }
+
+    let record = WalletPolicyRecord {
+        wallet_address: wallet_address.clone(),
+        spending_limits,
+        allowed_destinations: request.allowed_destinations,
+        denied_destinations: request.denied_destinations,
+        allowed_purposes: request.allowed_purposes,
+        updated_by: ops_user.clone(),
+        updated_at_epoch_ms: epoch_ms().map_err(internal_error)?,
+    };
+    state
+        .keystore
+        .save_wallet_policy(&record)
+        .map_err(internal_error)?;
+
+    audit(&state, "ops_set_wallet_policy", &wallet_address, ops_user, "policy replaced").await;
+    Ok(Json(response(record)))
+}
+
+/// DELETE /ops/wallets/{wallet_address}/policy — sign without restriction.
+pub(crate) async fn ops_delete_wallet_policy(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(wallet_address): Path<String>,
+) -> ApiResult<OpsWalletPolicyDeleteResponse> {
+    let ops_user =
+        require_ops_access(&state, &headers, "ops_delete_wallet_policy", Some(&wallet_address)).await?;
+    parse_field::<WalletAddress>("wallet_address", &wallet_address)?;
+
+    let deleted = state
+        .keystore
+        .delete_wallet_policy(&wallet_address)
+        .map_err(internal_error)?;
+    if deleted {
+        audit(&state, "ops_delete_wallet_policy", &wallet_address, ops_user, "policy removed").await;
+    }
+    Ok(Json(OpsWalletPolicyDeleteResponse {
+        wallet_address,
+        deleted,
+    }))
+}
+
+fn parse_limit(
+    field: &str,
+    amount: Option<&str>,
+) -> Result<Option<u128>, (StatusCode, Json<ApiError>)> {
+    amount
+        .map(|amount| Amount::parse_base_units(amount).map_err(|err| invalid_field(field, err)))
+        .transpose()
+}
+
+fn response(record: WalletPolicyRecord) -> OpsWalletPolicyResponse {
+    let spending_limits = record
+        .spending_limits
+        .into_iter()
+        .map(|(asset, limit)| {
+            let limit = OpsSpendingLimit {
+                max_amount: limit.max_amount.map(|amount| amount.to_string()),
+                approval_above: limit.approval_above.map(|amount| amount.to_string()),
+            };
+            (asset, limit)
+        })
+        .collect();
+    OpsWalletPolicyResponse {
+        wallet_address: record.wallet_address,
+        spending_limits,
+        allowed_destinations: record.allowed_destinations,
+        denied_destinations: record.denied_destinations,
+        allowed_purposes: record.allowed_purposes,
+        updated_by: record.updated_by,
+        updated_at_epoch_ms: record.updated_at_epoch_ms,
+    }
+}
+
+async fn audit(state: &AppState, event_type: &str, wallet_address: &str, ops_user: String, message: &str) {
+    crate::auth::append_audit_event(
+        state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: event_type.to_owned(),
+            wallet_address: Some(wallet_address.to_owned()),
+            user_id: Some(ops_user),
+            chain: Some(FLOWCORTEX_L1.to_owned()),
+            outcome: "success".to_owned(),
+            message: Some(message.to_owned()),
+            timestamp_epoch_ms: epoch_ms().unwrap_or_default(),
+            tenant: None,
+        },
+    )
+    .await;
+}
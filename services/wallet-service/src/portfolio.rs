@@ -0,0 +1,418 @@
+//! Multi-wallet views for the UI dashboard: batched balances with per-asset
+//! totals, recent transfers across a set of wallets, and one wallet's
+//! paged transaction history, as KeyCortex recorded it or as the chain
+//! reports it. Also one wallet's holdings across every chain.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use kc_api_types::{
+    AssetSymbol, AssetTotal, Page, PageQuery, WalletActivityEntry, WalletActivityRequest, WalletActivityResponse,
+    WalletAddress, WalletBalanceEntry, WalletBatchBalanceRequest, WalletBatchBalanceResponse,
+    WalletChainTxEntry, WalletChainTxsResponse, WalletPortfolioResponse, WalletTxHistoryResponse,
+};
+use kc_chain_client::amount::parse_amount;
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_storage::{SubmittedTxPage, SubmittedTxRecord, parse_history_cursor};
+use kc_wallet_core::{DEFAULT_PORTFOLIO_TIMEOUT, portfolio};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::warn;
+
+use crate::deadline::{self, RequestContext};
+use crate::ndjson;
+use crate::{ApiResult, AppState, ApiError, bad_request, epoch_ms, internal_error, parse_field};
+
+const MAX_BATCH_WALLETS: usize = 50;
+const DEFAULT_ACTIVITY_LIMIT: usize = 20;
+const MAX_ACTIVITY_LIMIT: usize = 100;
+const DEFAULT_EXPORT_LIMIT: usize = 1_000;
+const MAX_EXPORT_LIMIT: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WalletPortfolioQuery {
+    wallet_address: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WalletChainTxsQuery {
+    wallet_address: String,
+    chain: Option<String>,
+    limit: Option<usize>,
+    /// `next_cursor` from the previous page.
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WalletActivityExportQuery {
+    /// Comma-separated wallet addresses.
+    pub(crate) wallet_addresses: String,
+    pub(crate) limit: Option<usize>,
+}
+
+fn validate_addresses(
+    wallet_addresses: &[String],
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    if wallet_addresses.is_empty() {
+        return Err(bad_request("wallet_addresses is required"));
+    }
+    if wallet_addresses.len() > MAX_BATCH_WALLETS {
+        return Err(bad_request("at most 50 wallet_addresses per request"));
+    }
+    for (index, address) in wallet_addresses.iter().enumerate() {
+        parse_field::<WalletAddress>(&format!("wallet_addresses[{index}]"), address)?;
+    }
+    Ok(())
+}
+
+/// GET /wallet/portfolio — what one wallet holds on every live chain.
+///
+/// Chains are asked concurrently, each for at most
+/// [`DEFAULT_PORTFOLIO_TIMEOUT`] or what is left of the request deadline; the
+/// assets of a chain that fails or runs out of time are listed in `errors`.
+pub(crate) async fn wallet_portfolio(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<WalletPortfolioQuery>,
+) -> ApiResult<WalletPortfolioResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    parse_field::<WalletAddress>("wallet_address", &query.wallet_address)?;
+    crate::honeytoken::trip_if_honeytoken(&state, &query.wallet_address, "wallet_portfolio").await;
+
+    let sources = crate::chains::portfolio_sources(&state);
+    let timeout = DEFAULT_PORTFOLIO_TIMEOUT.min(ctx.remaining());
+    Ok(Json(portfolio(&query.wallet_address, sources, timeout).await))
+}
+
+/// POST /wallet/balances — balances for every wallet/asset pair, fetched concurrently.
+pub(crate) async fn wallet_balances(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<WalletBatchBalanceRequest>,
+) -> ApiResult<WalletBatchBalanceResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    validate_addresses(&request.wallet_addresses)?;
+    let chain = request.chain.unwrap_or_else(|| FLOWCORTEX_L1.to_owned());
+    let adapter = crate::chains::adapter(&state, &chain)?;
+
+    let enabled = crate::chains::assets(&state, &chain);
+    let assets = if request.assets.is_empty() {
+        enabled.clone()
+    } else {
+        request
+            .assets
+            .iter()
+            .map(|symbol| {
+                enabled
+                    .iter()
+                    .find(|asset| &asset.symbol == symbol)
+                    .cloned()
+                    .ok_or_else(|| bad_request(&format!("unsupported asset '{symbol}'")))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut lookups = JoinSet::new();
+    for (wallet_index, wallet_address) in request.wallet_addresses.iter().enumerate() {
+        crate::honeytoken::trip_if_honeytoken(&state, wallet_address, "wallet_balances").await;
+        for (asset_index, asset) in assets.iter().enumerate() {
+            let adapter = Arc::clone(&adapter);
+            let wallet_address = wallet_address.clone();
+            let symbol = asset.symbol.clone();
+            lookups.spawn(async move {
+                let result = ctx
+                    .run(
+                        "get_balance",
+                        adapter.get_balance(
+                            &WalletAddress(wallet_address.clone()),
+                            &AssetSymbol(symbol.clone()),
+                        ),
+                    )
+                    .await;
+                let (amount, error) = match result {
+                    Ok(balance) => (Some(balance.amount.to_string()), None),
+                    Err(err) => (None, Some(format!("{err:#}"))),
+                };
+                (
+                    (wallet_index, asset_index),
+                    WalletBalanceEntry {
+                        wallet_address,
+                        asset: symbol,
+                        amount,
+                        error,
+                    },
+                )
+            });
+        }
+    }
+    let mut balances = lookups.join_all().await;
+    balances.sort_by_key(|(order, _)| *order);
+    let balances: Vec<WalletBalanceEntry> = balances.into_iter().map(|(_, entry)| entry).collect();
+
+    let totals = assets
+        .iter()
+        .map(|asset| AssetTotal {
+            asset: asset.symbol.clone(),
+            amount: balances
+                .iter()
+                .filter(|entry| entry.asset == asset.symbol)
+                .filter_map(|entry| entry.amount.as_deref())
+                .filter_map(|amount| parse_amount(amount).ok())
+                .fold(0u128, u128::saturating_add)
+                .to_string(),
+            decimals: asset.decimals,
+        })
+        .collect();
+
+    Ok(Json(WalletBatchBalanceResponse {
+        chain,
+        balances,
+        totals,
+    }))
+}
+
+/// POST /wallet/activity — recent transfers sent from any of the wallets, newest first.
+pub(crate) async fn wallet_activity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<WalletActivityRequest>,
+) -> ApiResult<WalletActivityResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    validate_addresses(&request.wallet_addresses)?;
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT);
+
+    let activity = state
+        .keystore
+        .list_submitted_txs_from_within(ctx.scan_deadline(), &request.wallet_addresses, limit)
+        .map_err(deadline::api_error)?
+        .into_iter()
+        .map(|record| activity_entry(&state, record))
+        .collect();
+
+    Ok(Json(WalletActivityResponse { activity }))
+}
+
+/// GET /wallet/{address}/transactions — transfers sent or received by one
+/// wallet, newest first, a page at a time. Read from Postgres when
+/// configured, falling back to RocksDB.
+///
+/// With `Accept: application/x-ndjson` every transfer from `cursor` on is
+/// streamed instead, up to `limit` if given.
+pub(crate) async fn wallet_tx_history(
+    State(state): State<Arc<AppState>>,
+    Path(wallet_address): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<PageQuery>,
+) -> Result<Response, (StatusCode, Json<ApiError>)> {
+    if ndjson::requested(&headers) {
+        let cursor = start_tx_history(&state, &wallet_address, &query).await?;
+        return Ok(ndjson::stream_pages(query.limit, cursor, move |cursor: Option<String>| {
+            let state = Arc::clone(&state);
+            let wallet_address = wallet_address.clone();
+            async move {
+                let page = load_tx_history(&state, &wallet_address, ndjson::PAGE_SIZE, cursor.as_deref())
+                    .await?;
+                let entries: Vec<_> = page
+                    .records
+                    .into_iter()
+                    .map(|record| activity_entry(&state, record))
+                    .collect();
+                Ok((entries, page.next_cursor))
+            }
+        }));
+    }
+
+    let page = tx_history_page(&state, &wallet_address, &query).await?;
+    Ok(Json(WalletTxHistoryResponse {
+        wallet_address,
+        transactions: page.items,
+        next_cursor: page.next_cursor,
+    })
+    .into_response())
+}
+
+/// One page of `wallet_address`'s transfers, as `/v2` serves it.
+pub(crate) async fn tx_history_page(
+    state: &AppState,
+    wallet_address: &str,
+    query: &PageQuery,
+) -> Result<Page<WalletActivityEntry>, (StatusCode, Json<ApiError>)> {
+    let cursor = start_tx_history(state, wallet_address, query).await?;
+    let limit = query.limit_or(DEFAULT_ACTIVITY_LIMIT, MAX_ACTIVITY_LIMIT);
+    let page = load_tx_history(state, wallet_address, limit, cursor.as_deref())
+        .await
+        .map_err(internal_error)?;
+    Ok(Page::new(
+        page.records
+            .into_iter()
+            .map(|record| activity_entry(state, record))
+            .collect(),
+        page.next_cursor,
+    ))
+}
+
+/// Check a history request and return the cursor to start from.
+async fn start_tx_history(
+    state: &AppState,
+    wallet_address: &str,
+    query: &PageQuery,
+) -> Result<Option<String>, (StatusCode, Json<ApiError>)> {
+    parse_field::<WalletAddress>("wallet_address", wallet_address)?;
+    let cursor = query.start();
+    if cursor.is_some_and(|cursor| parse_history_cursor(cursor).is_none()) {
+        return Err(bad_request("invalid cursor"));
+    }
+    crate::honeytoken::trip_if_honeytoken(state, wallet_address, "wallet_tx_history").await;
+    Ok(cursor.map(ToOwned::to_owned))
+}
+
+/// GET /wallet/txs — one wallet's transfers as the chain records them,
+/// including ones KeyCortex never submitted, newest first.
+pub(crate) async fn wallet_chain_txs(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<WalletChainTxsQuery>,
+) -> ApiResult<WalletChainTxsResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let wallet_address = query.wallet_address.trim().to_owned();
+    if wallet_address.is_empty() {
+        return Err(bad_request("wallet_address is required"));
+    }
+    let chain = query.chain.unwrap_or_else(|| FLOWCORTEX_L1.to_owned());
+    let adapter = crate::chains::adapter(&state, &chain)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT);
+    let cursor = query
+        .cursor
+        .map(|cursor| cursor.trim().to_owned())
+        .filter(|cursor| !cursor.is_empty());
+
+    let page = ctx
+        .run(
+            "list_transactions",
+            adapter.list_transactions(&WalletAddress(wallet_address.clone()), cursor.as_deref(), limit),
+        )
+        .await
+        .map_err(deadline::api_error)?;
+
+    let mut transactions = Vec::with_capacity(page.transactions.len());
+    for tx in page.transactions {
+        let submitted_by_keycortex = state
+            .keystore
+            .load_submitted_tx(&tx.tx_hash)
+            .map_err(internal_error)?
+            .is_some();
+        transactions.push(WalletChainTxEntry {
+            tx_hash: tx.tx_hash,
+            from: tx.from.0,
+            to: tx.to.0,
+            asset: tx.asset.0,
+            amount: tx.amount,
+            block_height: tx.block_height,
+            submitted_by_keycortex,
+        });
+    }
+
+    Ok(Json(WalletChainTxsResponse {
+        wallet_address,
+        chain,
+        transactions,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+async fn load_tx_history(
+    state: &AppState,
+    wallet_address: &str,
+    limit: usize,
+    cursor: Option<&str>,
+) -> anyhow::Result<SubmittedTxPage> {
+    if let Some(repo) = &state.postgres_repo {
+        match repo.list_submitted_txs(wallet_address, limit, cursor).await {
+            Ok(page) => return Ok(page),
+            Err(err) => {
+                state.db_fallback_counters.inc_submission_read_failures();
+                warn!(
+                    "failed to list transaction history from Postgres: {}. Falling back to RocksDB",
+                    err
+                );
+            }
+        }
+    }
+    state.keystore.list_submitted_txs(wallet_address, limit, cursor)
+}
+
+fn activity_entry(state: &AppState, record: SubmittedTxRecord) -> WalletActivityEntry {
+    WalletActivityEntry {
+        encrypted_memo: crate::memo::encrypted_memo(state, &record.tx_hash),
+        tx_hash: record.tx_hash,
+        status: record.status,
+        chain: record.chain,
+        from: record.from,
+        to: record.to,
+        asset: record.asset,
+        amount: record.amount,
+        submitted_at_epoch_ms: record.submitted_at_epoch_ms,
+    }
+}
+
+/// GET /wallet/activity/export — transfer history of the wallets as a CSV
+/// download, newest first.
+pub(crate) async fn wallet_activity_export(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<WalletActivityExportQuery>,
+) -> Result<Response, (StatusCode, Json<ApiError>)> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let wallet_addresses: Vec<String> = query
+        .wallet_addresses
+        .split(',')
+        .map(|address| address.trim().to_owned())
+        .collect();
+    validate_addresses(&wallet_addresses)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_EXPORT_LIMIT)
+        .clamp(1, MAX_EXPORT_LIMIT);
+
+    let records = state
+        .keystore
+        .list_submitted_txs_from_within(ctx.scan_deadline(), &wallet_addresses, limit)
+        .map_err(deadline::api_error)?;
+    let now = epoch_ms().map_err(internal_error)?;
+
+    Ok(crate::export::csv_download(
+        &format!("keycortex-activity-{now}.csv"),
+        &[
+            "tx_hash",
+            "submitted_at_epoch_ms",
+            "status",
+            "chain",
+            "from",
+            "to",
+            "asset",
+            "amount",
+        ],
+        records.into_iter().map(|record| {
+            vec![
+                record.tx_hash,
+                record.submitted_at_epoch_ms.to_string(),
+                record.status,
+                record.chain,
+                record.from,
+                record.to,
+                record.asset,
+                record.amount,
+            ]
+        }),
+    ))
+}
@@ -1,10 +1,10 @@
 use axum::{Json, extract::State};
-use kc_api_types::{ProofCortexCommitmentRequest, ProofCortexCommitmentResponse};
+use kc_api_types::{ProofCortexCommitmentRequest, ProofCortexCommitmentResponse, WalletAddress};
 use kc_storage::Keystore;
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
-use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, to_hex};
+use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, parse_field, to_hex};
 
 /// Domain separator for ProofCortex commitment generation.
 /// Aligns with FlowCortex proof_domain_tag.
@@ -27,9 +27,7 @@ pub(crate) async fn proofcortex_commitment(
     State(state): State<Arc<AppState>>,
     Json(request): Json<ProofCortexCommitmentRequest>,
 ) -> ApiResult<ProofCortexCommitmentResponse> {
-    if request.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
 
     if request.challenge.trim().is_empty() {
         return Err(bad_request("challenge is required"));
@@ -91,6 +89,7 @@ pub(crate) async fn proofcortex_commitment(
             outcome: "success".to_owned(),
             message: Some(format!("commitment={}", &commitment[..16])),
             timestamp_epoch_ms: now,
+            tenant: None,
         },
     )
     .await;
@@ -0,0 +1,359 @@
+//! Mirrors custody events to an external message broker for analytics.
+//!
+//! With `KEYCORTEX_EVENT_BROKER` set to `kafka` or `nats`, the keystore
+//! stages every audit event and tx status change in its outbox (see
+//! [`kc_storage::OutboxEventRecord`]) and a background task drains it every
+//! `KEYCORTEX_EVENT_PUBLISH_INTERVAL_MS` (default 1000):
+//!
+//! - `kafka` posts to a Kafka REST Proxy (v2 API) at
+//!   `KEYCORTEX_EVENT_BROKER_URL`, producing to topic
+//!   `KEYCORTEX_EVENT_TOPIC` keyed by event id.
+//! - `nats` publishes to JetStream at `KEYCORTEX_EVENT_BROKER_URL` on
+//!   subject `{KEYCORTEX_EVENT_TOPIC}.{kind}`, with `Nats-Msg-Id` set to the
+//!   event id. A stream must already capture those subjects.
+//!
+//! Events are removed from the outbox only once the broker acknowledges
+//! them, in order; a failure leaves the rest queued for the next pass, and
+//! the queue survives restarts. An event can be delivered twice when the
+//! service stops between the ack and the removal, so consumers dedupe on
+//! `event_id`. `GET /ops/events/publisher` reports the backlog.
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_nats::jetstream;
+use axum::{Json, extract::State, http::HeaderMap};
+use kc_storage::{OutboxEventRecord, RocksDbKeystore};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Mutex as TokioMutex;
+use tracing::warn;
+
+use crate::ops::require_ops_access;
+use crate::{ApiResult, AppState, epoch_ms, internal_error};
+
+pub(crate) const BROKER_KAFKA: &str = "kafka";
+pub(crate) const BROKER_NATS: &str = "nats";
+
+const DEFAULT_TOPIC: &str = "keycortex.events";
+const DEFAULT_PUBLISH_INTERVAL_MS: u64 = 1_000;
+/// Events sent per broker round trip.
+const PUBLISH_BATCH: usize = 100;
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const KAFKA_CONTENT_TYPE: &str = "application/vnd.kafka.json.v2+json";
+
+enum Broker {
+    Kafka {
+        url: String,
+        http: reqwest::Client,
+    },
+    Nats {
+        url: String,
+        /// Connected on first use and dropped after a failure, so the
+        /// service starts while the broker is down.
+        jetstream: Box<TokioMutex<Option<jetstream::Context>>>,
+    },
+}
+
+#[derive(Debug, Default)]
+struct PublisherStatus {
+    published: u64,
+    last_published_epoch_ms: Option<u128>,
+    last_error: Option<String>,
+}
+
+/// Events acknowledged by the broker, from the start of a batch, and what
+/// stopped the rest.
+struct Delivery {
+    acked: usize,
+    error: Option<anyhow::Error>,
+}
+
+pub(crate) struct EventPublisher {
+    broker: Broker,
+    topic: String,
+    interval: Duration,
+    status: Mutex<PublisherStatus>,
+}
+
+impl EventPublisher {
+    /// `None` unless `KEYCORTEX_EVENT_BROKER` names a broker.
+    pub(crate) fn from_env() -> Result<Option<Self>> {
+        let Some(kind) = env::var("KEYCORTEX_EVENT_BROKER")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let url = env::var("KEYCORTEX_EVENT_BROKER_URL")
+            .context("KEYCORTEX_EVENT_BROKER_URL is required with KEYCORTEX_EVENT_BROKER")?;
+        let topic = env::var("KEYCORTEX_EVENT_TOPIC")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_TOPIC.to_owned());
+        let interval_ms = match env::var("KEYCORTEX_EVENT_PUBLISH_INTERVAL_MS") {
+            Ok(value) => value
+                .trim()
+                .parse::<u64>()
+                .context("KEYCORTEX_EVENT_PUBLISH_INTERVAL_MS")?,
+            Err(_) => DEFAULT_PUBLISH_INTERVAL_MS,
+        };
+        Self::new(kind.trim(), &url, &topic, Duration::from_millis(interval_ms)).map(Some)
+    }
+
+    pub(crate) fn new(kind: &str, url: &str, topic: &str, interval: Duration) -> Result<Self> {
+        let url = url.trim().trim_end_matches('/').to_owned();
+        if url.is_empty() {
+            bail!("event broker URL is empty");
+        }
+        let topic = topic.trim();
+        if topic.is_empty() || topic.contains(char::is_whitespace) {
+            bail!("event topic '{topic}' must be non-empty without whitespace");
+        }
+        if interval.is_zero() {
+            bail!("event publish interval must be positive");
+        }
+        let broker = match kind {
+            BROKER_KAFKA => Broker::Kafka {
+                url,
+                http: reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?,
+            },
+            BROKER_NATS => Broker::Nats {
+                url,
+                jetstream: Box::new(TokioMutex::new(None)),
+            },
+            other => bail!("unknown event broker '{other}'; expected kafka or nats"),
+        };
+        Ok(Self {
+            broker,
+            topic: topic.to_owned(),
+            interval,
+            status: Mutex::new(PublisherStatus::default()),
+        })
+    }
+
+    pub(crate) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub(crate) fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub(crate) fn broker_kind(&self) -> &'static str {
+        match self.broker {
+            Broker::Kafka { .. } => BROKER_KAFKA,
+            Broker::Nats { .. } => BROKER_NATS,
+        }
+    }
+
+    /// Publish queued events until the outbox is empty or the broker fails.
+    /// Returns how many were published.
+    pub(crate) async fn drain(&self, keystore: &RocksDbKeystore) -> Result<usize> {
+        let mut published = 0;
+        loop {
+            let events = keystore.list_outbox_events(PUBLISH_BATCH)?;
+            if events.is_empty() {
+                return Ok(published);
+            }
+            let delivery = self.send(&events).await;
+            let acked: Vec<String> = events[..delivery.acked]
+                .iter()
+                .map(|event| event.event_id.clone())
+                .collect();
+            keystore.delete_outbox_events(&acked)?;
+            published += acked.len();
+
+            let mut status = self.status.lock().expect("publisher status");
+            if !acked.is_empty() {
+                status.published += acked.len() as u64;
+                status.last_published_epoch_ms = epoch_ms().ok();
+            }
+            if let Some(err) = delivery.error {
+                status.last_error = Some(format!("{err:#}"));
+                return Err(err);
+            }
+            status.last_error = None;
+            if events.len() < PUBLISH_BATCH {
+                return Ok(published);
+            }
+        }
+    }
+
+    async fn send(&self, events: &[OutboxEventRecord]) -> Delivery {
+        match &self.broker {
+            Broker::Kafka { url, http } => self.send_kafka(http, url, events).await,
+            Broker::Nats { url, jetstream } => {
+                let mut jetstream = jetstream.lock().await;
+                let delivery = self.send_nats(&mut jetstream, url, events).await;
+                if delivery.error.is_some() {
+                    *jetstream = None;
+                }
+                delivery
+            }
+        }
+    }
+
+    async fn send_kafka(&self, http: &reqwest::Client, url: &str, events: &[OutboxEventRecord]) -> Delivery {
+        let records: Vec<_> = events
+            .iter()
+            .map(|event| serde_json::json!({ "key": event.event_id, "value": event }))
+            .collect();
+        let response = http
+            .post(format!("{url}/topics/{}", self.topic))
+            .header("content-type", KAFKA_CONTENT_TYPE)
+            .header("accept", "application/vnd.kafka.v2+json")
+            .json(&serde_json::json!({ "records": records }))
+            .send()
+            .await;
+        let produced = match response {
+            Ok(response) if response.status().is_success() => response.json::<KafkaProduceResponse>().await,
+            Ok(response) => {
+                return Delivery {
+                    acked: 0,
+                    error: Some(anyhow!("Kafka REST proxy returned HTTP {}", response.status())),
+                };
+            }
+            Err(err) => return Delivery { acked: 0, error: Some(err.into()) },
+        };
+        let offsets = match produced {
+            Ok(produced) => produced.offsets,
+            Err(err) => {
+                return Delivery {
+                    acked: 0,
+                    error: Some(anyhow!("unreadable Kafka REST proxy response: {err}")),
+                };
+            }
+        };
+        // Offsets come back in record order; stop at the first rejection so
+        // everything after it is retried in order.
+        let acked = offsets
+            .iter()
+            .take(events.len())
+            .take_while(|offset| offset.error_code.is_none())
+            .count();
+        let error = if acked == events.len() {
+            None
+        } else {
+            let message = offsets
+                .get(acked)
+                .and_then(|offset| offset.error.clone())
+                .unwrap_or_else(|| "record not acknowledged".to_owned());
+            Some(anyhow!("Kafka rejected event {}: {message}", events[acked].event_id))
+        };
+        Delivery { acked, error }
+    }
+
+    async fn send_nats(
+        &self,
+        jetstream: &mut Option<jetstream::Context>,
+        url: &str,
+        events: &[OutboxEventRecord],
+    ) -> Delivery {
+        if jetstream.is_none() {
+            match async_nats::connect(url).await {
+                Ok(client) => *jetstream = Some(jetstream::new(client)),
+                Err(err) => {
+                    return Delivery {
+                        acked: 0,
+                        error: Some(anyhow!("cannot connect to NATS at {url}: {err}")),
+                    };
+                }
+            }
+        }
+        let context = jetstream.as_ref().expect("connected above");
+        for (acked, event) in events.iter().enumerate() {
+            if let Err(err) = publish_nats(context, &self.topic, event).await {
+                return Delivery {
+                    acked,
+                    error: Some(err.context(format!("NATS did not acknowledge event {}", event.event_id))),
+                };
+            }
+        }
+        Delivery {
+            acked: events.len(),
+            error: None,
+        }
+    }
+}
+
+async fn publish_nats(context: &jetstream::Context, topic: &str, event: &OutboxEventRecord) -> Result<()> {
+    let mut headers = async_nats::HeaderMap::new();
+    headers.insert("Nats-Msg-Id", event.event_id.as_str());
+    let payload = serde_json::to_vec(event)?;
+    let ack = context
+        .publish_with_headers(format!("{topic}.{}", event.kind), headers, payload.into())
+        .await?
+        .await?;
+    if ack.duplicate {
+        warn!("NATS stream {} already had event {}", ack.stream, event.event_id);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct KafkaProduceResponse {
+    offsets: Vec<KafkaOffset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KafkaOffset {
+    #[serde(default)]
+    error_code: Option<i64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Drain the outbox every [`EventPublisher::interval`], forever.
+pub(crate) async fn run(publisher: Arc<EventPublisher>, keystore: Arc<RocksDbKeystore>) {
+    loop {
+        if let Err(err) = publisher.drain(&keystore).await {
+            warn!("event publishing to {} failed: {:#}", publisher.broker_kind(), err);
+        }
+        tokio::time::sleep(publisher.interval()).await;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EventPublisherStatusResponse {
+    enabled: bool,
+    broker: Option<&'static str>,
+    topic: Option<String>,
+    /// Events waiting in the outbox.
+    pending: usize,
+    published: u64,
+    last_published_epoch_ms: Option<u128>,
+    last_error: Option<String>,
+}
+
+/// GET /ops/events/publisher — broker, backlog and last failure of the
+/// event publisher.
+pub(crate) async fn ops_event_publisher_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<EventPublisherStatusResponse> {
+    require_ops_access(&state, &headers, "ops_event_publisher_status", None).await?;
+
+    let pending = state.keystore.count_outbox_events().map_err(internal_error)?;
+    let Some(publisher) = state.event_publisher.as_ref() else {
+        return Ok(Json(EventPublisherStatusResponse {
+            enabled: false,
+            broker: None,
+            topic: None,
+            pending,
+            published: 0,
+            last_published_epoch_ms: None,
+            last_error: None,
+        }));
+    };
+    let status = publisher.status.lock().expect("publisher status");
+    Ok(Json(EventPublisherStatusResponse {
+        enabled: true,
+        broker: Some(publisher.broker_kind()),
+        topic: Some(publisher.topic.clone()),
+        pending,
+        published: status.published,
+        last_published_epoch_ms: status.last_published_epoch_ms,
+        last_error: status.last_error.clone(),
+    }))
+}
@@ -0,0 +1,273 @@
+//! Custody hygiene reports for operators.
+//!
+//! `GET /ops/reports/dormant` lists custodied wallets with no signing or
+//! transfer activity in a window, with their balances, so operators can
+//! decide which to sweep or retire. Activity is the newest of the wallet's
+//! latest transfer, sent or received, its last signature or submission as
+//! counted in its metadata, and its latest audit event of a
+//! [`SIGNING_EVENT_TYPES`] type.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{AssetSymbol, AssetTotal, ChainAssetInfo, WalletAddress};
+use kc_chain_client::amount::parse_amount;
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+use crate::deadline::RequestContext;
+use crate::ops::require_ops_access;
+use crate::{ApiResult, AppState, ApiError, bad_request, epoch_ms, internal_error};
+
+const DEFAULT_INACTIVE_DAYS: u64 = 90;
+const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+
+/// Audit events that need the wallet's key or its owner's signature.
+const SIGNING_EVENT_TYPES: &[&str] = &["auth_bind", "escrow_create", "escrow_release", "escrow_cancel"];
+
+/// Wallet addresses and audit events read per storage call.
+const SCAN_PAGE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DormantReportQuery {
+    inactive_days: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DormantBalance {
+    asset: String,
+    /// `None` if the chain lookup failed; see `error`.
+    amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DormantWallet {
+    wallet_address: String,
+    label: Option<String>,
+    user_id: Option<String>,
+    created_at_epoch_ms: Option<u128>,
+    /// `None` if no activity is on record.
+    last_activity_epoch_ms: Option<u128>,
+    balances: Vec<DormantBalance>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DormantReportResponse {
+    inactive_days: u64,
+    cutoff_epoch_ms: u128,
+    chain: String,
+    /// Custodied wallets checked, honeytokens excluded.
+    scanned: usize,
+    /// Least recently active first.
+    wallets: Vec<DormantWallet>,
+    total: usize,
+    /// Balance per asset across the dormant wallets.
+    totals: Vec<AssetTotal>,
+}
+
+/// GET /ops/reports/dormant — custodied wallets with no signing or transfer
+/// activity in the last `inactive_days` (default 90), with their balances.
+pub(crate) async fn ops_dormant_report(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<DormantReportQuery>,
+) -> ApiResult<DormantReportResponse> {
+    require_ops_access(&state, &headers, "ops_dormant_report", None).await?;
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+
+    let inactive_days = query.inactive_days.unwrap_or(DEFAULT_INACTIVE_DAYS);
+    if inactive_days == 0 {
+        return Err(bad_request("inactive_days must be at least 1"));
+    }
+    let cutoff_epoch_ms = epoch_ms()
+        .map_err(internal_error)?
+        .saturating_sub(u128::from(inactive_days) * DAY_MS);
+
+    let mut scanned = 0;
+    let mut wallets = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let page = state
+            .keystore
+            .list_wallet_addresses_after(after.as_deref(), SCAN_PAGE)
+            .map_err(internal_error)?;
+        let Some(last) = page.last().cloned() else {
+            break;
+        };
+        for wallet_address in page {
+            if state
+                .keystore
+                .is_wallet_honeytoken(&wallet_address)
+                .map_err(internal_error)?
+            {
+                continue;
+            }
+            scanned += 1;
+            if let Some(wallet) = dormant_wallet(&state, &ctx, wallet_address, cutoff_epoch_ms)? {
+                wallets.push(wallet);
+            }
+        }
+        after = Some(last);
+    }
+    wallets.sort_by(|a, b| {
+        a.last_activity_epoch_ms
+            .cmp(&b.last_activity_epoch_ms)
+            .then_with(|| a.wallet_address.cmp(&b.wallet_address))
+    });
+
+    let chain = FLOWCORTEX_L1.to_owned();
+    let assets = crate::chains::assets(&state, &chain);
+    fill_balances(&state, &ctx, &chain, &assets, &mut wallets).await?;
+    let totals = assets
+        .iter()
+        .map(|asset| AssetTotal {
+            asset: asset.symbol.clone(),
+            amount: wallets
+                .iter()
+                .flat_map(|wallet| &wallet.balances)
+                .filter(|balance| balance.asset == asset.symbol)
+                .filter_map(|balance| balance.amount.as_deref())
+                .filter_map(|amount| parse_amount(amount).ok())
+                .fold(0u128, u128::saturating_add)
+                .to_string(),
+            decimals: asset.decimals,
+        })
+        .collect();
+
+    let total = wallets.len();
+    Ok(Json(DormantReportResponse {
+        inactive_days,
+        cutoff_epoch_ms,
+        chain,
+        scanned,
+        wallets,
+        total,
+        totals,
+    }))
+}
+
+/// The wallet's report entry if it was created and last active before
+/// `cutoff_epoch_ms`; balances are filled in later.
+fn dormant_wallet(
+    state: &AppState,
+    ctx: &RequestContext,
+    wallet_address: String,
+    cutoff_epoch_ms: u128,
+) -> Result<Option<DormantWallet>, (StatusCode, Json<ApiError>)> {
+    let metadata = state
+        .keystore
+        .load_wallet_metadata(&wallet_address)
+        .map_err(internal_error)?;
+    let created_at_epoch_ms = metadata.as_ref().and_then(|metadata| metadata.created_at_epoch_ms);
+    if created_at_epoch_ms.is_some_and(|created| created >= cutoff_epoch_ms) {
+        return Ok(None);
+    }
+    let last_used = metadata.and_then(|metadata| metadata.usage.last_used_at_epoch_ms());
+    if last_used.is_some_and(|at| at >= cutoff_epoch_ms) {
+        return Ok(None);
+    }
+
+    let last_tx = state
+        .keystore
+        .list_submitted_txs(&wallet_address, 1, None)
+        .map_err(internal_error)?
+        .records
+        .first()
+        .map(|tx| tx.submitted_at_epoch_ms);
+    if last_tx.is_some_and(|at| at >= cutoff_epoch_ms) {
+        return Ok(None);
+    }
+    let last_signing = last_signing_event(state, ctx, &wallet_address)?;
+    if last_signing.is_some_and(|at| at >= cutoff_epoch_ms) {
+        return Ok(None);
+    }
+
+    Ok(Some(DormantWallet {
+        label: state
+            .keystore
+            .load_wallet_label(&wallet_address)
+            .map_err(internal_error)?,
+        user_id: state
+            .keystore
+            .load_wallet_binding(&wallet_address)
+            .map_err(internal_error)?
+            .map(|binding| binding.user_id),
+        created_at_epoch_ms,
+        last_activity_epoch_ms: last_used.max(last_tx).max(last_signing),
+        balances: Vec::new(),
+        wallet_address,
+    }))
+}
+
+/// Time of the wallet's newest signing audit event.
+fn last_signing_event(
+    state: &AppState,
+    ctx: &RequestContext,
+    wallet_address: &str,
+) -> Result<Option<u128>, (StatusCode, Json<ApiError>)> {
+    let mut cursor: Option<String> = None;
+    loop {
+        ctx.check("dormant wallet report")?;
+        let page = state
+            .keystore
+            .list_audit_events_page(SCAN_PAGE, cursor.as_deref(), None, Some(wallet_address), None)
+            .map_err(internal_error)?;
+        if let Some(event) = page
+            .events
+            .iter()
+            .find(|event| SIGNING_EVENT_TYPES.contains(&event.event_type.as_str()))
+        {
+            return Ok(Some(event.timestamp_epoch_ms));
+        }
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(None),
+        }
+    }
+}
+
+async fn fill_balances(
+    state: &AppState,
+    ctx: &RequestContext,
+    chain: &str,
+    assets: &[ChainAssetInfo],
+    wallets: &mut [DormantWallet],
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    let adapter = crate::chains::adapter(state, chain)?;
+    let mut lookups = JoinSet::new();
+    for (wallet_index, wallet) in wallets.iter().enumerate() {
+        for asset in assets {
+            let adapter = Arc::clone(&adapter);
+            let ctx = *ctx;
+            let wallet_address = WalletAddress(wallet.wallet_address.clone());
+            let symbol = asset.symbol.clone();
+            lookups.spawn(async move {
+                let result = ctx
+                    .run("get_balance", adapter.get_balance(&wallet_address, &AssetSymbol(symbol.clone())))
+                    .await;
+                let (amount, error) = match result {
+                    Ok(balance) => (Some(balance.amount.to_string()), None),
+                    Err(err) => (None, Some(format!("{err:#}"))),
+                };
+                (wallet_index, DormantBalance { asset: symbol, amount, error })
+            });
+        }
+    }
+    for (wallet_index, balance) in lookups.join_all().await {
+        wallets[wallet_index].balances.push(balance);
+    }
+    for wallet in wallets.iter_mut() {
+        wallet.balances.sort_by_key(|balance| {
+            assets
+                .iter()
+                .position(|asset| asset.symbol == balance.asset)
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Request metadata headers (see [`RequestMeta`]).
+//!
+//! Every `POST`, `PUT`, `PATCH` or `DELETE` response carries the request's
+//! `X-Client-Request-Id` back unchanged, errors included, so a client can
+//! tie a response to its own logs. A malformed id or `Idempotency-Key` fails
+//! the request with `400` before the handler runs.
+
+use axum::{
+    Json,
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use kc_api_types::RequestMeta;
+
+use crate::{ApiError, invalid_field};
+
+pub(crate) fn from_headers(headers: &HeaderMap) -> Result<RequestMeta, (StatusCode, Json<ApiError>)> {
+    RequestMeta::from_headers(|name| headers.get(name).and_then(|value| value.to_str().ok()))
+        .map_err(|err| invalid_field(err.header, err))
+}
+
+fn is_mutating(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+/// Validate the metadata of mutating requests and echo their client
+/// request id.
+pub(crate) async fn echo_client_request_id(request: Request, next: Next) -> Response {
+    if !is_mutating(request.method()) {
+        return next.run(request).await;
+    }
+    let meta = match from_headers(request.headers()) {
+        Ok(meta) => meta,
+        Err(err) => return err.into_response(),
+    };
+    let mut response = next.run(request).await;
+    if let Some(id) = meta
+        .client_request_id
+        .and_then(|id| HeaderValue::from_str(&id).ok())
+    {
+        response
+            .headers_mut()
+            .insert(RequestMeta::CLIENT_REQUEST_ID_HEADER, id);
+    }
+    response
+}
@@ -0,0 +1,70 @@
+//! `POST /wallet/session-key`: short-lived keys derived from a custodied
+//! wallet key, which `POST /wallet/sign` uses when given a `session_key_id`.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+};
+use kc_api_types::{ApiError, WalletAddress, WalletSessionKeyRequest, WalletSessionKeyResponse};
+use kc_storage::WalletActivity;
+use kc_wallet_core::{DEFAULT_SESSION_KEY_TTL, PolicyContext, SessionKeyError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::wallet_sessions::WalletScope;
+use crate::{AppState, ApiResult, bad_request, internal_error, not_found, parse_field};
+
+/// POST /wallet/session-key — issue a session key for the listed purposes.
+pub(crate) async fn wallet_session_key(
+    State(state): State<Arc<AppState>>,
+    scope: WalletScope,
+    Json(request): Json<WalletSessionKeyRequest>,
+) -> ApiResult<WalletSessionKeyResponse> {
+    parse_field::<WalletAddress>("wallet_address", &request.wallet_address)?;
+    scope.authorize(&request.wallet_address)?;
+
+    crate::honeytoken::trip_if_honeytoken(&state, &request.wallet_address, "wallet_session_key").await;
+    crate::watch::reject_watch_only(&state, &request.wallet_address)?;
+    crate::external::reject_external_key(&state, &request.wallet_address)?;
+    if state.kms_keys.get(&request.wallet_address).is_some() {
+        return Err(bad_request("session keys need a custodied wallet key, not a KMS key"));
+    }
+    for purpose in &request.purposes {
+        crate::policy::enforce(
+            &state,
+            &PolicyContext {
+                wallet_address: &request.wallet_address,
+                purpose,
+                transfer: None,
+            },
+        )
+        .await?;
+    }
+
+    let ttl = request
+        .ttl_seconds
+        .map_or(DEFAULT_SESSION_KEY_TTL, Duration::from_secs);
+    let issued = state
+        .wallet_core
+        .issue_session_key(&request.wallet_address, ttl, request.purposes)
+        .await
+        .map_err(session_key_error)?
+        .ok_or_else(|| bad_request("wallet not found"))?;
+    crate::record_wallet_activity(&state, &request.wallet_address, WalletActivity::Signed).await;
+
+    Ok(Json(WalletSessionKeyResponse {
+        session_key_id: issued.session_key_id,
+        attestation: issued.attestation,
+    }))
+}
+
+pub(crate) fn session_key_error(err: SessionKeyError) -> (StatusCode, Json<ApiError>) {
+    match err {
+        SessionKeyError::NotFound => not_found(&err.to_string()),
+        SessionKeyError::Other(err) => internal_error(format!("{err:#}")),
+        SessionKeyError::Invalid(_)
+        | SessionKeyError::Expired
+        | SessionKeyError::PurposeNotAttested(_) => bad_request(&err.to_string()),
+    }
+}
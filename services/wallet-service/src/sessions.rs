@@ -0,0 +1,209 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+};
+use kc_api_types::{AuthSessionListResponse, AuthSessionRevokeResponse, AuthSessionSummary};
+use kc_storage::{AuditEventRecord, SessionRecord};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, not_found, unauthorized};
+
+/// How stale a session's `last_seen_epoch_ms` may get before a request
+/// refreshes it, so a busy session is not rewritten on every request.
+const LAST_SEEN_RESOLUTION: Duration = Duration::from_secs(60);
+
+/// How often sessions whose token expired are pruned.
+pub(crate) const SESSION_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SessionListQuery {
+    /// Operators (`ops-admin`) may list another user's sessions.
+    user_id: Option<String>,
+}
+
+/// Record a sighting of session `session_id` for `user_id`, rejecting
+/// revoked sessions.
+///
+/// Called from principal parsing so every authenticated route consults the
+/// revocation list.
+pub(crate) fn track_session(
+    state: &AppState,
+    user_id: &str,
+    session_id: &str,
+    issued_at_epoch_s: Option<u64>,
+    expires_at_epoch_s: Option<u64>,
+) -> Result<(), String> {
+    let now = epoch_ms().unwrap_or_default();
+    let existing = state
+        .keystore
+        .load_session(session_id)
+        .map_err(|_| "session store unavailable".to_owned())?;
+
+    let record = match existing {
+        Some(record) if record.revoked => return Err("session revoked".to_owned()),
+        Some(record) if record.user_id != user_id => {
+            return Err("session subject mismatch".to_owned());
+        }
+        Some(record)
+            if now.saturating_sub(record.last_seen_epoch_ms) < LAST_SEEN_RESOLUTION.as_millis() =>
+        {
+            return Ok(());
+        }
+        Some(mut record) => {
+            record.last_seen_epoch_ms = now;
+            record
+        }
+        None => SessionRecord {
+            session_id: session_id.to_owned(),
+            user_id: user_id.to_owned(),
+            issued_at_epoch_ms: issued_at_epoch_s.map(|s| s as u128 * 1000),
+            expires_at_epoch_ms: expires_at_epoch_s.map(|s| s as u128 * 1000),
+            first_seen_epoch_ms: now,
+            last_seen_epoch_ms: now,
+            revoked: false,
+            revoked_at_epoch_ms: None,
+            revoked_by: None,
+        },
+    };
+
+    state
+        .keystore
+        .save_session(&record)
+        .map_err(|_| "session store unavailable".to_owned())
+}
+
+/// Record a sighting of wallet session `session_id`, rejecting sessions
+/// that are revoked or were never issued.
+pub(crate) fn track_wallet_session(state: &AppState, session_id: &str) -> Result<(), String> {
+    let now = epoch_ms().unwrap_or_default();
+    let record = match state
+        .keystore
+        .load_session(session_id)
+        .map_err(|_| "session store unavailable".to_owned())?
+    {
+        None => return Err("unknown wallet session".to_owned()),
+        Some(record) if record.revoked => return Err("wallet session revoked".to_owned()),
+        Some(record)
+            if now.saturating_sub(record.last_seen_epoch_ms) < LAST_SEEN_RESOLUTION.as_millis() =>
+        {
+            return Ok(());
+        }
+        Some(mut record) => {
+            record.last_seen_epoch_ms = now;
+            record
+        }
+    };
+
+    state
+        .keystore
+        .save_session(&record)
+        .map_err(|_| "session store unavailable".to_owned())
+}
+
+fn summary(record: SessionRecord) -> AuthSessionSummary {
+    AuthSessionSummary {
+        session_id: record.session_id,
+        user_id: record.user_id,
+        issued_at_epoch_ms: record.issued_at_epoch_ms,
+        expires_at_epoch_ms: record.expires_at_epoch_ms,
+        last_seen_epoch_ms: record.last_seen_epoch_ms,
+        revoked: record.revoked,
+        revoked_at_epoch_ms: record.revoked_at_epoch_ms,
+    }
+}
+
+/// GET /auth/sessions — list the caller's sessions (or `?user_id=` for ops-admin).
+pub(crate) async fn auth_list_sessions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<SessionListQuery>,
+) -> ApiResult<AuthSessionListResponse> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    let user_id = match query.user_id.as_deref().map(str::trim) {
+        Some(requested) if !requested.is_empty() && requested != principal.user_id => {
+            if !principal.is_ops_admin() {
+                return Err(unauthorized(
+                    "listing another user's sessions requires ops-admin",
+                ));
+            }
+            requested.to_owned()
+        }
+        _ => principal.user_id,
+    };
+
+    let sessions: Vec<AuthSessionSummary> = state
+        .keystore
+        .list_user_sessions(&user_id)
+        .map_err(internal_error)?
+        .into_iter()
+        .map(summary)
+        .collect();
+    let total = sessions.len();
+
+    Ok(Json(AuthSessionListResponse {
+        user_id,
+        sessions,
+        total,
+    }))
+}
+
+/// POST /auth/sessions/{id}/revoke — revoke a session owned by the caller (or any, for ops-admin).
+pub(crate) async fn auth_revoke_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> ApiResult<AuthSessionRevokeResponse> {
+    let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+        .map_err(|msg| unauthorized(&msg))?;
+
+    if session_id.trim().is_empty() {
+        return Err(bad_request("session id is required"));
+    }
+
+    let record = state
+        .keystore
+        .load_session(&session_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("session not found"))?;
+
+    if record.user_id != principal.user_id && !principal.is_ops_admin() {
+        return Err(unauthorized("cannot revoke another user's session"));
+    }
+
+    let now = epoch_ms().map_err(internal_error)?;
+    let revoked = state
+        .keystore
+        .revoke_session(&session_id, &principal.user_id, now)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("session not found"))?;
+
+    crate::auth::append_audit_event(
+        &state,
+        AuditEventRecord {
+            event_id: String::new(),
+            event_type: "auth_session_revoke".to_owned(),
+            wallet_address: None,
+            user_id: Some(principal.user_id.clone()),
+            chain: None,
+            outcome: "success".to_owned(),
+            message: Some(format!(
+                "session {} of user {} revoked",
+                revoked.session_id, revoked.user_id
+            )),
+            timestamp_epoch_ms: now,
+            tenant: None,
+        },
+    )
+    .await;
+
+    Ok(Json(AuthSessionRevokeResponse {
+        session_id: revoked.session_id,
+        revoked: revoked.revoked,
+        revoked_at_epoch_ms: revoked.revoked_at_epoch_ms,
+    }))
+}
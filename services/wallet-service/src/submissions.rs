@@ -0,0 +1,84 @@
+//! Background watch over submitted transfers.
+//!
+//! Every transfer the chain accepts is handed to the
+//! [`SubmissionTracker`](kc_wallet_core::SubmissionTracker), and a task
+//! started in `main` calls [`poll`] every [`POLL_INTERVAL`]. Status changes
+//! it saves reach webhooks and the event broker through the keystore's
+//! change notifications, and are mirrored to Postgres here.
+//!
+//! A transfer the chain has lost is broadcast again within
+//! `KEYCORTEX_RESUBMIT_WINDOW_SECONDS` (default 600) of its submission, at
+//! most `KEYCORTEX_RESUBMIT_MAX_ATTEMPTS` (default 3) times.
+
+use kc_storage::SubmittedTxRecord;
+use kc_wallet_core::SubmissionPolicy;
+use std::env;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::AppState;
+
+/// How often the tracker looks for transfers due a check; each transfer
+/// backs off on its own schedule.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub(crate) fn policy_from_env() -> SubmissionPolicy {
+    let mut policy = SubmissionPolicy::default();
+    if let Some(seconds) = env_u64("KEYCORTEX_RESUBMIT_WINDOW_SECONDS") {
+        policy.resubmit_window = Duration::from_secs(seconds);
+    }
+    if let Some(attempts) = env_u64("KEYCORTEX_RESUBMIT_MAX_ATTEMPTS") {
+        policy.max_resubmissions = u32::try_from(attempts).unwrap_or(u32::MAX);
+    }
+    policy
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Start watching a transfer the chain accepted, broadcast with
+/// `signed_payload`. Failing to is logged, not returned: the transfer has
+/// been submitted either way.
+pub(crate) fn track(state: &AppState, record: &SubmittedTxRecord, signed_payload: &str) {
+    let decimals = match crate::chains::asset(state, &record.chain, &record.asset) {
+        Ok(asset) => asset.decimals,
+        Err((_, error)) => {
+            warn!("not tracking {}: {}", record.tx_hash, error.message);
+            return;
+        }
+    };
+    if let Err(err) = state.submissions.track(record, decimals, signed_payload) {
+        warn!("failed to track submitted tx {}: {:#}", record.tx_hash, err);
+    }
+}
+
+/// Check the transfers due by `now_epoch_ms`.
+pub(crate) async fn poll(state: &AppState, now_epoch_ms: u128) {
+    let updates = match state
+        .submissions
+        .poll(|chain| crate::chains::lookup(state, chain), now_epoch_ms)
+        .await
+    {
+        Ok(updates) => updates,
+        Err(err) => {
+            warn!("submission tracking failed: {:#}", err);
+            return;
+        }
+    };
+    for update in updates {
+        let record = &update.record;
+        info!(
+            "tx {} moved from {} to {}",
+            record.tx_hash, update.previous_status, record.status
+        );
+        if let Some(repo) = &state.postgres_repo {
+            if let Err(err) = repo.save_submission(record, None, None).await {
+                state.db_fallback_counters.inc_submission_write_failures();
+                warn!("failed to persist tx status in Postgres: {}", err);
+            }
+        }
+    }
+}
@@ -1,259 +1,516 @@
 use axum::{
     Json,
     extract::{Path, Query, State},
-    http::HeaderMap,
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
 };
 use kc_api_types::{
-    AssetSymbol, ChainId, SignPurpose, WalletAddress, WalletNonceResponse, WalletSubmitRequest,
-    WalletSubmitResponse, WalletTxStatusResponse,
+    AssetSymbol, ChainId, WalletAddress, WalletFeeEstimateRequest, WalletFeeEstimateResponse,
+    WalletNonceResponse, WalletSubmitRequest, WalletSubmitResponse, TxEnvelopeV1,
+    WalletSubmitSignedRequest, WalletTxStatusChange, WalletTxStatusResponse,
 };
-use kc_chain_client::{SubmitTxRequest, TxStatusRequest};
+use kc_chain_client::amount::parse_amount;
+use kc_chain_client::{FeeEstimateRequest, NonceStrategy, TxStatusRequest};
 use kc_chain_flowcortex::FLOWCORTEX_L1;
-use kc_crypto::{Ed25519Signer, Signer, decrypt_key_material};
-use kc_storage::{Keystore, SubmitIdempotencyRecord, SubmittedTxRecord, WalletNonceRecord};
+use kc_crypto::Ed25519PublicKey;
+use kc_storage::{IdempotencyStore, Keystore, SubmitIdempotencyRecord, TxStatusChange};
 use serde::Deserialize;
 use tracing::warn;
 
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::deadline::{self, RequestContext};
+use crate::pipeline::{OnApproval, TxSigner};
+use crate::wallet_sessions::WalletScope;
+use crate::{
+    AppState, ApiResult, ApiError, bad_request, epoch_ms, idempotency_conflict, internal_error,
+    parse_field, unauthorized,
+};
 
-use crate::{AppState, ApiResult, bad_request, epoch_ms, internal_error, to_hex};
+/// How long an `Idempotency-Key` stays claimed when the replica holding it
+/// never releases it. Longer than any request budget.
+const IDEMPOTENCY_CLAIM_TTL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct WalletNonceQuery {
     wallet_address: String,
+    chain: Option<String>,
 }
 
 pub(crate) async fn wallet_nonce(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Query(query): Query<WalletNonceQuery>,
 ) -> ApiResult<WalletNonceResponse> {
-    if query.wallet_address.trim().is_empty() {
-        return Err(bad_request("wallet_address is required"));
-    }
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    parse_field::<WalletAddress>("wallet_address", &query.wallet_address)?;
 
     let wallet_exists = state
         .keystore
         .load_encrypted_key(&query.wallet_address)
         .await
         .map_err(internal_error)?
-        .is_some();
+        .is_some()
+        || crate::external::load_public_key(&state, &query.wallet_address)?.is_some();
 
     if !wallet_exists {
         return Err(bad_request("wallet not found"));
     }
 
-    let last_nonce = state
-        .keystore
-        .load_wallet_nonce(&query.wallet_address)
-        .map_err(internal_error)?
-        .map(|record| record.last_nonce)
-        .unwrap_or(0);
+    let chain = query.chain.unwrap_or_else(|| FLOWCORTEX_L1.to_owned());
+    let adapter = crate::chains::adapter(&state, &chain)?;
+    let strategy = adapter.nonce_strategy();
+
+    let mut recent_blockhash = None;
+    let (last_nonce, next_nonce) = match strategy {
+        NonceStrategy::None => (0, 0),
+        NonceStrategy::Sequential => {
+            let last_nonce = state
+                .nonces
+                .settled(&query.wallet_address)
+                .map_err(internal_error)?
+                .unwrap_or(0);
+            let now = epoch_ms().map_err(internal_error)?;
+            let next_nonce = state
+                .nonces
+                .next_free(&query.wallet_address, last_nonce, now)
+                .await;
+            (last_nonce, next_nonce)
+        }
+        NonceStrategy::ChainQueried => {
+            let next_nonce = ctx
+                .run(
+                    "get_account_nonce",
+                    adapter.get_account_nonce(&WalletAddress(query.wallet_address.clone())),
+                )
+                .await
+                .map_err(deadline::api_error)?;
+            crate::pipeline::reconcile_nonce(&state, &query.wallet_address, next_nonce)
+                .await
+                .map_err(internal_error)?;
+            (next_nonce.saturating_sub(1), next_nonce)
+        }
+        NonceStrategy::RecentBlockhash => {
+            recent_blockhash = Some(
+                ctx.run("get_recent_blockhash", adapter.get_recent_blockhash())
+                    .await
+                    .map_err(deadline::api_error)?,
+            );
+            (0, 0)
+        }
+    };
 
     Ok(Json(WalletNonceResponse {
         wallet_address: query.wallet_address,
         last_nonce,
-        next_nonce: last_nonce.saturating_add(1),
+        next_nonce,
+        nonce_strategy: strategy.as_str().to_owned(),
+        recent_blockhash,
     }))
 }
 
-pub(crate) async fn wallet_submit(
+/// POST /wallet/fee-estimate — what the chain would charge for a transfer,
+/// so clients can show the total debit before submitting.
+pub(crate) async fn wallet_fee_estimate(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    Json(request): Json<WalletSubmitRequest>,
-) -> ApiResult<WalletSubmitResponse> {
-    let idempotency_key = headers
-        .get("idempotency-key")
-        .and_then(|value| value.to_str().ok())
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(ToOwned::to_owned);
-
-    if let Some(key) = idempotency_key.as_deref() {
-        {
-            let cache = state.submit_idempotency_cache.read().await;
-            if let Some(existing) = cache.get(key) {
-                return Ok(Json(existing.clone()));
+    Json(request): Json<WalletFeeEstimateRequest>,
+) -> ApiResult<WalletFeeEstimateResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let from = parse_field::<WalletAddress>("from", &request.from)?;
+    let to = parse_field::<WalletAddress>("to", &request.to)?;
+    let asset = parse_field::<AssetSymbol>("asset", &request.asset)?;
+    let chain = parse_field::<ChainId>("chain", &request.chain)?;
+    parse_amount(&request.amount).map_err(|err| bad_request(&err.to_string()))?;
+    let adapter = crate::chains::adapter(&state, &request.chain)?;
+
+    let estimate = ctx
+        .run(
+            "estimate_fee",
+            adapter.estimate_fee(FeeEstimateRequest {
+                from,
+                to,
+                amount: request.amount.clone(),
+                asset,
+                chain,
+            }),
+        )
+        .await
+        .map_err(deadline::api_error)?;
+
+    Ok(Json(WalletFeeEstimateResponse {
+        chain: request.chain,
+        asset: request.asset,
+        amount: request.amount,
+        fee: estimate.fee,
+        fee_asset: estimate.fee_asset.0,
+    }))
+}
+
+/// The `Idempotency-Key` claim of a submission in flight, released when
+/// dropped so every return path gives the key back.
+struct IdempotencyClaim {
+    store: Arc<dyn IdempotencyStore>,
+    key: String,
+}
+
+impl Drop for IdempotencyClaim {
+    fn drop(&mut self) {
+        let store = self.store.clone();
+        let key = std::mem::take(&mut self.key);
+        tokio::spawn(async move {
+            if let Err(err) = store.release(&key).await {
+                warn!("idempotency key release failed: {:#}", err);
             }
+        });
+    }
+}
+
+enum Idempotent {
+    /// The response recorded the first time the key was used.
+    Replay(WalletSubmitResponse),
+    /// Go ahead, holding the key's claim when there is one.
+    Claimed(Option<IdempotencyClaim>),
+}
+
+/// Replay the response recorded under `Idempotency-Key`, or claim the key so
+/// a concurrent retry cannot submit the same transfer twice. Like the cache,
+/// the claim is best-effort: if the store fails the submission goes ahead
+/// unclaimed.
+async fn begin_idempotent(
+    state: &AppState,
+    key: Option<&str>,
+) -> Result<Idempotent, (StatusCode, Json<ApiError>)> {
+    let Some(key) = key else {
+        return Ok(Idempotent::Claimed(None));
+    };
+    if let Some(existing) = idempotent_response(state, Some(key)).await? {
+        return Ok(Idempotent::Replay(existing));
+    }
+    let claim = match state.submit_idempotency_cache.claim(key, IDEMPOTENCY_CLAIM_TTL).await {
+        Ok(true) => Some(IdempotencyClaim {
+            store: state.submit_idempotency_cache.clone(),
+            key: key.to_owned(),
+        }),
+        Ok(false) => {
+            return Err(idempotency_conflict(
+                "a request with this Idempotency-Key is still in flight",
+            ));
+        }
+        Err(err) => {
+            warn!("idempotency key claim failed, submitting unclaimed: {:#}", err);
+            None
         }
+    };
+    // The request that held the key may have finished since the check above.
+    if let Some(existing) = idempotent_response(state, Some(key)).await? {
+        return Ok(Idempotent::Replay(existing));
+    }
+    Ok(Idempotent::Claimed(claim))
+}
 
-        if let Some(existing) = state
-            .keystore
-            .load_submit_idempotency(key)
-            .map_err(internal_error)?
-        {
-            let response = WalletSubmitResponse {
-                accepted: existing.accepted,
-                tx_hash: existing.tx_hash,
-                signature: existing.signature,
+/// Response previously recorded under `Idempotency-Key`, from cache or RocksDB.
+async fn idempotent_response(
+    state: &AppState,
+    key: Option<&str>,
+) -> Result<Option<WalletSubmitResponse>, (StatusCode, Json<ApiError>)> {
+    let Some(key) = key else {
+        return Ok(None);
+    };
+    let cached = match state.submit_idempotency_cache.get(key).await {
+        Ok(cached) => cached,
+        Err(err) => {
+            warn!("idempotency cache read failed, using RocksDB: {:#}", err);
+            None
+        }
+    };
+    let existing = match cached {
+        Some(existing) => existing,
+        None => {
+            let Some(existing) = state
+                .keystore
+                .load_submit_idempotency(key)
+                .map_err(internal_error)?
+            else {
+                return Ok(None);
             };
-            let mut cache = state.submit_idempotency_cache.write().await;
-            cache.insert(key.to_owned(), response.clone());
-            return Ok(Json(response));
+            cache_idempotency(state, &existing).await;
+            existing
         }
-    }
+    };
+    Ok(Some(WalletSubmitResponse {
+        accepted: existing.accepted,
+        tx_hash: existing.tx_hash,
+        signature: existing.signature,
+        expires_at_epoch_ms: existing.expires_at_epoch_ms,
+        simulation: None,
+        approval_id: None,
+    }))
+}
 
-    if request.from.trim().is_empty() {
-        return Err(bad_request("from is required"));
-    }
-    if request.to.trim().is_empty() {
-        return Err(bad_request("to is required"));
+/// RocksDB holds the durable copy, so a failed cache write only costs a
+/// RocksDB read on retry.
+pub(crate) async fn cache_idempotency(state: &AppState, record: &SubmitIdempotencyRecord) {
+    if let Err(err) = state.submit_idempotency_cache.put(record).await {
+        warn!("idempotency cache write failed: {:#}", err);
     }
+}
+
+fn validate_transfer(state: &AppState, request: &WalletSubmitRequest) -> Result<(), (StatusCode, Json<ApiError>)> {
+    parse_field::<WalletAddress>("from", &request.from)?;
+    parse_field::<WalletAddress>("to", &request.to)?;
     if request.amount.trim().is_empty() {
         return Err(bad_request("amount is required"));
     }
-    if request.nonce == 0 {
-        return Err(bad_request("nonce must be greater than 0"));
-    }
+    parse_field::<AssetSymbol>("asset", &request.asset)?;
+    parse_field::<ChainId>("chain", &request.chain)?;
     if request.chain != FLOWCORTEX_L1 {
         return Err(bad_request("unsupported chain for MVP; only flowcortex-l1 is enabled"));
     }
-    if request.asset != "PROOF" && request.asset != "FloweR" {
-        return Err(bad_request("unsupported asset for MVP; only PROOF and FloweR are enabled"));
-    }
-
-    let encrypted_key = state
-        .keystore
-        .load_encrypted_key(&request.from)
-        .await
-        .map_err(internal_error)?
-        .ok_or_else(|| bad_request("source wallet not found"))?;
+    crate::chains::amount(state, &request.chain, &request.asset, &request.amount)?;
+    Ok(())
+}
 
-    let mut secret_key = decrypt_key_material(&encrypted_key, state.encryption_key.as_ref())
-        .map_err(internal_error)?;
-    let signer = Ed25519Signer::from_secret_key_bytes(secret_key);
-    secret_key.fill(0);
+pub(crate) async fn wallet_submit(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    scope: WalletScope,
+    Json(request): Json<WalletSubmitRequest>,
+) -> ApiResult<WalletSubmitResponse> {
+    scope.authorize(&request.from)?;
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    // A dry run neither replays nor records an idempotent response.
+    let idempotency_key = crate::request_meta::from_headers(&headers)?
+        .idempotency_key
+        .filter(|_| !request.dry_run);
+    let _claim = match begin_idempotent(&state, idempotency_key.as_deref()).await? {
+        Idempotent::Replay(existing) => return Ok(Json(existing)),
+        Idempotent::Claimed(claim) => claim,
+    };
 
-    if signer.wallet_address() != request.from {
-        return Err(bad_request("source wallet address does not match custodied key"));
-    }
+    validate_transfer(&state, &request)?;
+
+    crate::honeytoken::trip_if_honeytoken(&state, &request.from, "wallet_submit").await;
+    crate::watch::reject_watch_only(&state, &request.from)?;
+    crate::devices::enforce_trusted_device(&state, &headers, &request.from, &request.to, &request.amount)
+        .await?;
+    let memo = crate::memo::seal_for_recipient(&state, &request).await?;
+    // Whoever submits a transfer that needs approval may not approve it, so
+    // a token that does not verify is refused rather than ignored.
+    let requested_by = if headers.contains_key(AUTHORIZATION) {
+        let principal = crate::auth::parse_authbuddy_principal(&headers, &state)
+            .map_err(|err| unauthorized(&err))?;
+        Some(principal.user_id)
+    } else {
+        None
+    };
+    let on_approval = OnApproval::Hold {
+        requested_by: requested_by.as_deref(),
+    };
 
-    {
-        let mut nonce_state = state.submit_nonce_state.write().await;
-        let mut last_nonce = nonce_state.get(&request.from).copied().unwrap_or(0);
-        if last_nonce == 0 {
-            last_nonce = state
-                .keystore
-                .load_wallet_nonce(&request.from)
-                .map_err(internal_error)?
-                .map(|record| record.last_nonce)
-                .unwrap_or(0);
-        }
+    let response = if let Some(public_key) = crate::external::load_public_key(&state, &request.from)? {
+        let signature_hex = request
+            .signed_payload
+            .as_deref()
+            .map(str::trim)
+            .filter(|sig| !sig.is_empty())
+            .ok_or_else(|| bad_request("signed_payload is required for external-key wallets"))?;
+        crate::pipeline::submit_presigned(
+            &state,
+            &ctx,
+            &public_key,
+            &request,
+            signature_hex,
+            idempotency_key.as_deref(),
+        )
+        .await?
+    } else if request.signed_payload.is_some() {
+        return Err(bad_request("signed_payload is only accepted for external-key wallets"));
+    } else if let Some(kms) = state.kms_keys.get(&request.from) {
+        crate::pipeline::sign_and_submit_with(
+            &state,
+            &ctx,
+            TxSigner::Kms(&kms),
+            &request,
+            idempotency_key.as_deref(),
+            on_approval,
+        )
+        .await?
+    } else {
+        let signer = state
+            .wallet_core
+            .signer_for(&request.from)
+            .await
+            .map_err(internal_error)?
+            .ok_or_else(|| bad_request("source wallet not found"))?;
 
-        if request.nonce <= last_nonce {
-            return Err(bad_request(
-                "nonce replay detected; nonce must be strictly increasing per wallet",
-            ));
+        if !crate::key_rotation::key_matches_wallet(&state, &request.from, &signer).map_err(internal_error)? {
+            return Err(bad_request("source wallet address does not match custodied key"));
         }
 
-        nonce_state.insert(request.from.clone(), request.nonce);
+        crate::pipeline::sign_and_submit_with(
+            &state,
+            &ctx,
+            TxSigner::Custodied(&signer),
+            &request,
+            idempotency_key.as_deref(),
+            on_approval,
+        )
+        .await?
+    };
+    if let Some(memo) = memo.filter(|_| response.accepted) {
+        crate::memo::save(&state, &request, &response.tx_hash, memo);
     }
+    Ok(Json(response))
+}
 
-    let payload = format!(
-        "from={};to={};amount={};asset={};chain={};nonce={}",
-        request.from, request.to, request.amount, request.asset, request.chain, request.nonce
-    );
-
-    let signature = signer
-        .sign(payload.as_bytes(), SignPurpose::Transaction)
-        .map_err(internal_error)?;
-    let signature_hex = to_hex(&signature);
-
-    let result = state.chain_adapter
-        .submit_transaction(SubmitTxRequest {
-            from: WalletAddress(request.from.clone()),
-            to: WalletAddress(request.to.clone()),
-            amount: request.amount.clone(),
-            asset: AssetSymbol(request.asset.clone()),
-            chain: ChainId(request.chain.clone()),
-            signed_payload: signature_hex.clone(),
-        })
-        .await
-        .map_err(internal_error)?;
-
-    let response = WalletSubmitResponse {
-        accepted: result.accepted,
-        tx_hash: result.tx_hash,
-        signature: signature_hex,
+/// POST /wallet/submit-signed — broadcast a transfer signed outside KeyCortex.
+///
+/// The caller supplies the canonical payload, its signature and the signing
+/// public key; KeyCortex never touches a secret key on this path.
+pub(crate) async fn wallet_submit_signed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    scope: WalletScope,
+    Json(signed): Json<WalletSubmitSignedRequest>,
+) -> ApiResult<WalletSubmitResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
+    let idempotency_key = crate::request_meta::from_headers(&headers)?.idempotency_key;
+    let _claim = match begin_idempotent(&state, idempotency_key.as_deref()).await? {
+        Idempotent::Replay(existing) => return Ok(Json(existing)),
+        Idempotent::Claimed(claim) => claim,
     };
 
-    let now = epoch_ms().map_err(internal_error)?;
-
-    state
-        .keystore
-        .save_submitted_tx(&SubmittedTxRecord {
-            tx_hash: response.tx_hash.clone(),
-            status: if response.accepted {
-                "submitted".to_owned()
-            } else {
-                "rejected".to_owned()
-            },
-            accepted: response.accepted,
-            chain: request.chain.clone(),
-            from: request.from.clone(),
-            to: request.to.clone(),
-            asset: request.asset.clone(),
-            amount: request.amount.clone(),
-            submitted_at_epoch_ms: now,
-        })
-        .map_err(internal_error)?;
-
-    state
-        .keystore
-        .save_wallet_nonce(&WalletNonceRecord {
-            wallet_address: request.from.clone(),
-            last_nonce: request.nonce,
-            updated_at_epoch_ms: now,
-        })
-        .map_err(internal_error)?;
-
-    if let Some(key) = idempotency_key {
-        state
-            .keystore
-            .save_submit_idempotency(&SubmitIdempotencyRecord {
-                idempotency_key: key.clone(),
-                accepted: response.accepted,
-                tx_hash: response.tx_hash.clone(),
-                signature: response.signature.clone(),
-                created_at_epoch_ms: now,
-            })
-            .map_err(internal_error)?;
-
-        let mut cache = state.submit_idempotency_cache.write().await;
-        cache.insert(key, response.clone());
+    if signed.signature.trim().is_empty() {
+        return Err(bad_request("signature is required"));
+    }
+    let mut request = parse_canonical_payload(&signed.payload)?;
+    request.expires_at_epoch_ms = signed.expires_at_epoch_ms;
+    request.memo = signed.memo;
+    scope.authorize(&request.from)?;
+    validate_transfer(&state, &request)?;
+    let public_key = Ed25519PublicKey::from_hex(signed.public_key.trim())
+        .map_err(|e| bad_request(&format!("invalid public_key: {e}")))?;
+
+    crate::honeytoken::trip_if_honeytoken(&state, &request.from, "wallet_submit_signed").await;
+    crate::watch::reject_watch_only(&state, &request.from)?;
+
+    let authorized = match crate::external::load_public_key(&state, &request.from)? {
+        Some(imported) => imported == public_key,
+        None => crate::key_rotation::public_key_matches_wallet(
+            &state,
+            &request.from,
+            &public_key.to_hex(),
+        )
+        .map_err(internal_error)?,
+    };
+    if !authorized {
+        return Err(unauthorized("public_key is not the signing key of the source wallet"));
+    }
+    crate::devices::enforce_trusted_device(&state, &headers, &request.from, &request.to, &request.amount)
+        .await?;
+    let memo = crate::memo::seal_for_recipient(&state, &request).await?;
+
+    let response = crate::pipeline::submit_presigned(
+        &state,
+        &ctx,
+        &public_key,
+        &request,
+        signed.signature.trim(),
+        idempotency_key.as_deref(),
+    )
+    .await?;
+    if let Some(memo) = memo.filter(|_| response.accepted) {
+        crate::memo::save(&state, &request, &response.tx_hash, memo);
     }
 
     Ok(Json(response))
 }
 
+/// Parse a signed [`TxEnvelopeV1`] payload into a transfer request.
+fn parse_canonical_payload(
+    payload: &str,
+) -> Result<WalletSubmitRequest, (StatusCode, Json<ApiError>)> {
+    let envelope = TxEnvelopeV1::parse(payload).map_err(crate::pipeline::envelope_error)?;
+    Ok(WalletSubmitRequest {
+        from: envelope.from.0,
+        to: envelope.to.0,
+        amount: envelope.amount.to_string(),
+        asset: envelope.asset.0,
+        chain: envelope.chain.0,
+        nonce: envelope.nonce,
+        signed_payload: None,
+        expires_at_epoch_ms: None,
+        memo: None,
+        dry_run: false,
+    })
+}
+
+/// Next usable nonce for `wallet_address`, skipping nonces reserved by external signers.
+pub(crate) async fn next_nonce(state: &AppState, wallet_address: &str) -> anyhow::Result<u64> {
+    state.nonces.next(wallet_address, epoch_ms()?).await
+}
+
 pub(crate) async fn wallet_tx_status(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Path(tx_hash): Path<String>,
 ) -> ApiResult<WalletTxStatusResponse> {
+    let ctx = RequestContext::from_headers(&state, &headers)?;
     if tx_hash.trim().is_empty() {
         return Err(bad_request("tx_hash is required"));
     }
 
+    // Escrow entries track the transfer state machine, not a chain transaction.
+    let is_escrow = tx_hash.starts_with("escrow-");
+    if is_escrow {
+        crate::escrow::load_transfer(&state, &tx_hash).await?;
+    }
+
     let mut record = state
         .keystore
         .load_submitted_tx(&tx_hash)
         .map_err(internal_error)?
         .ok_or_else(|| bad_request("transaction not found"))?;
 
-    if record.chain == FLOWCORTEX_L1 {
-        match state.chain_adapter
-            .get_transaction_status(TxStatusRequest {
-                tx_hash: record.tx_hash.clone(),
-                chain: ChainId(record.chain.clone()),
-            })
+    let adapter = crate::chains::lookup(&state, &record.chain).filter(|_| !is_escrow);
+    if let Some(adapter) = adapter {
+        match ctx
+            .run(
+                "get_transaction_status",
+                adapter.get_transaction_status(TxStatusRequest {
+                    tx_hash: record.tx_hash.clone(),
+                    chain: ChainId(record.chain.clone()),
+                }),
+            )
             .await
         {
+            // A confirmed transfer that has aged out of the adapter's lookup
+            // window is still confirmed.
+            Ok(status) if record.status == "confirmed" && status.status == "not_found" => {}
             Ok(status) => {
+                let changed = record.status != status.status;
+                if changed {
+                    record.status_history.push(TxStatusChange {
+                        status: status.status.clone(),
+                        at_epoch_ms: epoch_ms().map_err(internal_error)?,
+                    });
+                }
                 record.status = status.status;
                 record.accepted = status.accepted;
+                record.block_height = status.block_height.or(record.block_height);
+                record.confirmations = status.confirmations.or(record.confirmations);
                 state
                     .keystore
                     .save_submitted_tx(&record)
                     .map_err(internal_error)?;
+                // Keeps statuses in Postgres-served history current.
+                if let (true, Some(repo)) = (changed, &state.postgres_repo) {
+                    if let Err(err) = repo.save_submission(&record, None, None).await {
+                        state.db_fallback_counters.inc_submission_write_failures();
+                        warn!("failed to persist tx status in Postgres: {}", err);
+                    }
+                }
             }
             Err(err) => {
                 warn!(
@@ -264,6 +521,16 @@ pub(crate) async fn wallet_tx_status(
         }
     }
 
+    // Records saved before the timeline existed start from their submit time.
+    if record.status_history.is_empty() {
+        record.status_history.push(TxStatusChange {
+            status: record.status.clone(),
+            at_epoch_ms: record.submitted_at_epoch_ms,
+        });
+    }
+    let explorer_url = crate::chains::explorer_tx_url(&state, &record.chain, &record.tx_hash)
+        .filter(|_| !is_escrow);
+
     Ok(Json(WalletTxStatusResponse {
         tx_hash: record.tx_hash,
         status: record.status,
@@ -274,5 +541,16 @@ pub(crate) async fn wallet_tx_status(
         asset: record.asset,
         amount: record.amount,
         submitted_at_epoch_ms: record.submitted_at_epoch_ms,
+        block_height: record.block_height,
+        confirmations: record.confirmations,
+        status_history: record
+            .status_history
+            .into_iter()
+            .map(|change| WalletTxStatusChange {
+                status: change.status,
+                at_epoch_ms: change.at_epoch_ms,
+            })
+            .collect(),
+        explorer_url,
     }))
 }
@@ -0,0 +1,345 @@
+//! Tenants: isolated product teams served by one deployment.
+//!
+//! `KEYCORTEX_TENANTS_CONFIG` names a JSON file listing the tenants. A
+//! request acts for a tenant when its `X-API-Key` is one of the tenant's
+//! keys, or its AuthBuddy token carries a `tenant` claim naming it; any
+//! other request acts for the default tenant, as without tenancy. Each
+//! tenant is served from its own [`AppState`] over a keystore view under
+//! `tenant:{id}:` (see [`kc_storage::RocksDbKeystore::for_tenant`]), so
+//! wallets, audit events, transfers and ops listings never cross tenants.
+//! A wallet session is only accepted by the tenant that issued it.
+//! A tenant may encrypt its wallet keys with its own secret, and may be
+//! held to a wallet limit and a request rate. Postgres mirroring, KMS-backed
+//! wallets and bridge wallets cover the default tenant only, and tenants
+//! cannot be configured in demo mode, whose in-memory ledger would be shared.
+
+use anyhow::{Context, Result, bail};
+use axum::{
+    Json, Router,
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use kc_api_types::ApiError;
+use kc_auth_adapter::Challenges;
+use kc_crypto_kms::KmsKeyRegistry;
+use kc_storage::{InMemoryIdempotencyStore, InMemoryNonceStore, Keystore};
+use kc_storage_redis::RedisSharedState;
+use kc_wallet_core::{ApprovalQueue, NonceManager, SubmissionTracker};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+use crate::{
+    AppState, auth, custody_core, epoch_ms, forbidden, internal_error, quota_exceeded, submissions, to_hex,
+    unauthorized,
+};
+
+pub(crate) const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TenantsConfig {
+    tenants: Vec<TenantConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TenantConfig {
+    /// 1-64 characters of `a-z`, `0-9` and `-`.
+    id: String,
+    /// SHA-256 of each API key, in hex, so the file holds no secrets.
+    #[serde(default)]
+    api_key_sha256: Vec<String>,
+    /// Environment variable holding the secret this tenant's wallet keys
+    /// are encrypted with; unset shares the default tenant's.
+    encryption_key_env: Option<String>,
+    max_wallets: Option<usize>,
+    requests_per_minute: Option<u32>,
+}
+
+struct Tenant {
+    state: Arc<AppState>,
+    router: Router,
+    requests_per_minute: Option<u32>,
+    /// The current minute since the epoch and the requests counted in it.
+    window: Mutex<(u128, u32)>,
+}
+
+impl Tenant {
+    /// Count a request against the rate quota, refusing it once the
+    /// minute's quota is used.
+    fn admit(&self, now_epoch_ms: u128) -> bool {
+        let Some(limit) = self.requests_per_minute else {
+            return true;
+        };
+        let minute = now_epoch_ms / 60_000;
+        let Ok(mut window) = self.window.lock() else {
+            return true;
+        };
+        if window.0 != minute {
+            *window = (minute, 0);
+        }
+        if window.1 >= limit {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+}
+
+/// The configured tenants, by id.
+#[derive(Default)]
+pub(crate) struct Tenants {
+    tenants: HashMap<String, Tenant>,
+    /// Tenant id by hex SHA-256 of each API key.
+    api_keys: HashMap<String, String>,
+}
+
+impl Tenants {
+    /// The tenants in `KEYCORTEX_TENANTS_CONFIG`, if set. Tenants share
+    /// `redis`, when given, under their own key prefix.
+    pub(crate) fn from_env(base: &AppState, redis: Option<&RedisSharedState>) -> Result<Self> {
+        let path = match env::var("KEYCORTEX_TENANTS_CONFIG") {
+            Ok(path) if !path.trim().is_empty() => path.trim().to_owned(),
+            _ => return Ok(Self::default()),
+        };
+        let text = std::fs::read_to_string(Path::new(&path)).with_context(|| format!("read {path}"))?;
+        Self::parse(base, &text, redis).with_context(|| format!("tenant config {path}"))
+    }
+
+    pub(crate) fn parse(base: &AppState, text: &str, redis: Option<&RedisSharedState>) -> Result<Self> {
+        let config: TenantsConfig = serde_json::from_str(text)?;
+        if base.demo_ledger.is_some() && !config.tenants.is_empty() {
+            bail!("tenants cannot be configured in demo mode");
+        }
+        let mut tenants = Self::default();
+        for tenant in config.tenants {
+            if tenants.tenants.contains_key(&tenant.id) {
+                bail!("tenant '{}' is listed twice", tenant.id);
+            }
+            let encryption_key = match &tenant.encryption_key_env {
+                Some(name) => Arc::from(
+                    env::var(name)
+                        .ok()
+                        .filter(|value| !value.trim().is_empty())
+                        .with_context(|| format!("tenant '{}': {name} is not set", tenant.id))?,
+                ),
+                None => Arc::clone(&base.encryption_key),
+            };
+            let redis = redis.map(|redis| {
+                redis
+                    .clone()
+                    .with_key_prefix(&format!("{}tenant:{}:", redis.key_prefix(), tenant.id))
+            });
+            let state = Arc::new(tenant_state(base, &tenant, encryption_key, redis)?);
+            for digest in &tenant.api_key_sha256 {
+                let digest = digest.trim().to_ascii_lowercase();
+                if digest.len() != 64 || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    bail!("tenant '{}': api_key_sha256 entries must be 64 hex digits", tenant.id);
+                }
+                if let Some(other) = tenants.api_keys.insert(digest, tenant.id.clone()) {
+                    bail!("tenants '{other}' and '{}' share an API key", tenant.id);
+                }
+            }
+            tenants.tenants.insert(
+                tenant.id.clone(),
+                Tenant {
+                    router: crate::build_app(Arc::clone(&state)),
+                    state,
+                    requests_per_minute: tenant.requests_per_minute,
+                    window: Mutex::new((0, 0)),
+                },
+            );
+        }
+        Ok(tenants)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tenants.len()
+    }
+
+    /// Each tenant's state, for the background jobs.
+    pub(crate) fn states(&self) -> impl Iterator<Item = &Arc<AppState>> {
+        self.tenants.values().map(|tenant| &tenant.state)
+    }
+
+    /// The tenant `headers` act for, or `None` for the default tenant.
+    fn resolve(&self, headers: &HeaderMap, base: &AppState) -> Result<Option<&Tenant>, (StatusCode, Json<ApiError>)> {
+        let by_key = match headers.get(API_KEY_HEADER) {
+            Some(value) => {
+                let digest = to_hex(&Sha256::digest(value.as_bytes()));
+                Some(self.api_keys.get(&digest).ok_or_else(|| unauthorized("invalid API key"))?)
+            }
+            None => None,
+        };
+        let id = match (by_key, auth::authbuddy_tenant(headers, base)) {
+            (Some(key), Some(token)) if *key != token => {
+                return Err(forbidden("API key and token name different tenants"));
+            }
+            (Some(key), _) => key.clone(),
+            (None, Some(token)) => token,
+            (None, None) => return Ok(None),
+        };
+        self.tenants
+            .get(&id)
+            .map(Some)
+            .ok_or_else(|| forbidden("unknown tenant"))
+    }
+}
+
+/// State for tenant `config`: a keystore view and everything built on it,
+/// sharing the chains, auth settings and notifiers of `base`. The KMS keys
+/// and bridge wallets of `base` belong to the default tenant and are not
+/// carried over.
+fn tenant_state(
+    base: &AppState,
+    config: &TenantConfig,
+    encryption_key: Arc<str>,
+    redis: Option<RedisSharedState>,
+) -> Result<AppState> {
+    let keystore = Arc::new(
+        base.keystore
+            .for_tenant(&config.id)
+            .with_context(|| format!("tenant '{}'", config.id))?,
+    );
+    let (challenges, nonce_store, idempotency): (Challenges, Arc<dyn kc_storage::NonceStore>, _) = match redis {
+        Some(redis) => {
+            let redis = Arc::new(redis);
+            (
+                Challenges::new(redis.clone()),
+                redis.clone(),
+                redis as Arc<dyn kc_storage::IdempotencyStore>,
+            )
+        }
+        None => (
+            Challenges::new(keystore.clone()),
+            Arc::new(InMemoryNonceStore::default()),
+            Arc::new(InMemoryIdempotencyStore::default()) as Arc<dyn kc_storage::IdempotencyStore>,
+        ),
+    };
+    Ok(AppState {
+        wallet_core: custody_core(
+            &keystore,
+            &encryption_key,
+            &base.signing_domain,
+            Arc::new(auth::AuditLog {
+                keystore: keystore.clone(),
+                postgres_repo: None,
+                db_fallback_counters: Arc::clone(&base.db_fallback_counters),
+            }),
+        ),
+        nonces: Arc::new(NonceManager::new(nonce_store, keystore.clone())),
+        approvals: Arc::new(ApprovalQueue::new(keystore.clone())),
+        submissions: Arc::new(SubmissionTracker::new(keystore.clone(), submissions::policy_from_env())),
+        keystore,
+        postgres_repo: None,
+        db_fallback_counters: Arc::clone(&base.db_fallback_counters),
+        postgres_startup: Arc::clone(&base.postgres_startup),
+        encryption_key,
+        signing_domain: base.signing_domain.clone(),
+        sign_in_origin: Arc::clone(&base.sign_in_origin),
+        wallet_sessions: base.wallet_sessions.for_tenant(&config.id),
+        authbuddy_jwt_secret: Arc::clone(&base.authbuddy_jwt_secret),
+        authbuddy_jwks: Arc::clone(&base.authbuddy_jwks),
+        jwks_status: Arc::clone(&base.jwks_status),
+        authbuddy_expected_issuer: base.authbuddy_expected_issuer.clone(),
+        authbuddy_expected_audience: base.authbuddy_expected_audience.clone(),
+        challenges: Arc::new(challenges),
+        challenge_mirror: None,
+        submit_idempotency_cache: idempotency,
+        authbuddy_callback: base.authbuddy_callback.clone(),
+        chains: Arc::clone(&base.chains),
+        honeytoken_alert_url: base.honeytoken_alert_url.clone(),
+        approval_notifier: base.approval_notifier.clone(),
+        event_publisher: base.event_publisher.clone(),
+        webhook_notifier: base.webhook_notifier.clone(),
+        trusted_device_submit_threshold: base.trusted_device_submit_threshold,
+        min_passphrase_entropy_bits: base.min_passphrase_entropy_bits,
+        kms_keys: Arc::new(KmsKeyRegistry::default()),
+        bridge_wallets: HashMap::new(),
+        chaos: base.chaos.clone(),
+        health_history: Arc::clone(&base.health_history),
+        demo_ledger: None,
+        request_timeout: base.request_timeout,
+        wallet_undelete_grace: base.wallet_undelete_grace,
+        wallet_quota: config.max_wallets.map(|max_wallets| WalletQuota {
+            max_wallets,
+            lock: tokio::sync::Mutex::new(()),
+        }),
+    })
+}
+
+struct Dispatcher {
+    default: Router,
+    default_state: Arc<AppState>,
+    tenants: Tenants,
+}
+
+/// Serve each request from the router of the tenant it acts for, and the
+/// rest from `default`, built over `default_state`.
+pub(crate) fn route(default: Router, default_state: Arc<AppState>, tenants: Tenants) -> Router {
+    Router::new().fallback(dispatch).with_state(Arc::new(Dispatcher {
+        default,
+        default_state,
+        tenants,
+    }))
+}
+
+async fn dispatch(State(dispatcher): State<Arc<Dispatcher>>, request: Request) -> Response {
+    let router = match dispatcher
+        .tenants
+        .resolve(request.headers(), &dispatcher.default_state)
+    {
+        Ok(None) => dispatcher.default.clone(),
+        Ok(Some(tenant)) => {
+            if !tenant.admit(epoch_ms().unwrap_or_default()) {
+                return quota_exceeded("tenant request quota for this minute is used up").into_response();
+            }
+            tenant.router.clone()
+        }
+        Err(err) => return err.into_response(),
+    };
+    match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+/// A tenant's wallet limit.
+pub(crate) struct WalletQuota {
+    max_wallets: usize,
+    /// Held from counting the tenant's wallets until the new one is saved,
+    /// so concurrent creates cannot both take the last slot.
+    lock: tokio::sync::Mutex<()>,
+}
+
+/// Refuse a new custodied wallet once the tenant holds its wallet limit.
+/// Otherwise the returned guard holds the slot; keep it until the wallet
+/// is saved.
+pub(crate) async fn reserve_wallet_slot(
+    state: &AppState,
+) -> Result<Option<tokio::sync::MutexGuard<'_, ()>>, (StatusCode, Json<ApiError>)> {
+    let Some(quota) = &state.wallet_quota else {
+        return Ok(None);
+    };
+    let slot = quota.lock.lock().await;
+    let max_wallets = quota.max_wallets;
+    let full = match max_wallets.checked_sub(1) {
+        Some(last) => !state
+            .keystore
+            .list_wallet_addresses(last, 1)
+            .await
+            .map_err(internal_error)?
+            .is_empty(),
+        None => true,
+    };
+    if full {
+        return Err(quota_exceeded(&format!("tenant wallet limit of {max_wallets} is reached")));
+    }
+    Ok(Some(slot))
+}
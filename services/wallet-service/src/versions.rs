@@ -0,0 +1,74 @@
+//! Handlers for the `/v2` routes.
+//!
+//! Each shares its logic with the v1 handler of the same path, which builds
+//! the v2 shape and downgrades it through `kc_api_types::compat`. Only the
+//! response and the error body differ.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+};
+use kc_api_types::{Page, PageQuery, v2};
+use kc_storage::AuditEventRecord;
+use std::sync::Arc;
+
+use crate::ops::OpsAuditQuery;
+use crate::{AppState, ApiError, WalletBalanceQuery, WalletListQuery};
+
+pub(crate) type V2Result<T> = Result<Json<T>, (StatusCode, Json<v2::ApiError>)>;
+
+fn v2_error((status, Json(error)): (StatusCode, Json<ApiError>)) -> (StatusCode, Json<v2::ApiError>) {
+    (status, Json(error.into()))
+}
+
+/// GET /v2/wallet/balance — the balance with the decimals of its asset.
+pub(crate) async fn wallet_balance_v2(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<WalletBalanceQuery>,
+) -> V2Result<v2::WalletBalanceResponse> {
+    crate::fetch_balance(&state, &headers, query)
+        .await
+        .map(Json)
+        .map_err(v2_error)
+}
+
+/// GET /v2/wallet/list — wallets in address order, a page at a time.
+pub(crate) async fn wallet_list_v2(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<WalletListQuery>,
+    Query(page): Query<PageQuery>,
+) -> V2Result<v2::WalletListResponse> {
+    crate::wallet_list_page(&state, &headers, &query, &page)
+        .await
+        .map(Json)
+        .map_err(v2_error)
+}
+
+/// GET /v2/wallet/{address}/transactions — transfers newest first.
+pub(crate) async fn wallet_tx_history_v2(
+    State(state): State<Arc<AppState>>,
+    Path(wallet_address): Path<String>,
+    Query(page): Query<PageQuery>,
+) -> V2Result<v2::WalletTxHistoryResponse> {
+    crate::portfolio::tx_history_page(&state, &wallet_address, &page)
+        .await
+        .map(Json)
+        .map_err(v2_error)
+}
+
+/// GET /v2/ops/audit — audit events newest first, with the filters of
+/// `/ops/audit`.
+pub(crate) async fn ops_list_audit_v2(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<OpsAuditQuery>,
+    Query(page): Query<PageQuery>,
+) -> V2Result<Page<AuditEventRecord>> {
+    crate::ops::audit_page(&state, &headers, &query, &page)
+        .await
+        .map(Json)
+        .map_err(v2_error)
+}
@@ -0,0 +1,334 @@
+//! Wallet sessions: short-lived tokens proving a wallet was verified.
+//!
+//! A successful `/auth/verify` returns an HS256 JWT signed with
+//! `KEYCORTEX_WALLET_SESSION_SECRET`, naming the wallet and when it was
+//! verified, valid for `KEYCORTEX_WALLET_SESSION_TTL_SECONDS` (default 900).
+//! Clients send it back in `X-Wallet-Session`. [`accept`] checks the token
+//! on every request and attaches its [`WalletSession`]. Every wallet-scoped
+//! endpoint takes a [`WalletScope`], which refuses a request without a
+//! session when `KEYCORTEX_REQUIRE_WALLET_SESSION` is set, and calls
+//! [`WalletScope::authorize`] with the wallet it acts for, which refuses a
+//! session for another wallet. `/auth/session/refresh` trades a valid token
+//! for a fresh one until [`MAX_SESSION_AGE`] after verification.
+//!
+//! Each token carries a random `jti`, recorded in the session store when
+//! the token is issued, so it can be listed and revoked through
+//! `/auth/sessions` like an AuthBuddy session. [`accept`] refuses a token
+//! whose `jti` is revoked or was never issued, or that a different
+//! tenant issued.
+//!
+//! Requiring sessions without setting `KEYCORTEX_WALLET_SESSION_SECRET`
+//! fails startup. Otherwise a missing secret is replaced by a random one
+//! for the life of the process, so sessions only work on the replica that
+//! issued them and not across restarts.
+
+use anyhow::bail;
+use axum::{
+    Extension, Json,
+    extract::{FromRequestParts, Request, State},
+    http::{StatusCode, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use kc_api_types::{ApiError, WalletSessionToken};
+use kc_storage::{RocksDbKeystore, SessionRecord};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{AppState, ApiResult, epoch_ms, forbidden, internal_error, unauthorized};
+
+pub(crate) const WALLET_SESSION_HEADER: &str = "x-wallet-session";
+
+/// Default `KEYCORTEX_WALLET_SESSION_TTL_SECONDS`.
+const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// How long after verification a session can still be refreshed; past it
+/// the wallet must verify again.
+pub(crate) const MAX_SESSION_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How recently a decision on a held transfer must follow a verification
+/// of the decider's wallet.
+pub(crate) const REAUTH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// `iss` of every wallet session token, so AuthBuddy tokens are never
+/// mistaken for one.
+const ISSUER: &str = "keycortex-wallet-session";
+
+/// A verified wallet session, attached to the request by [`accept`].
+#[derive(Debug, Clone)]
+pub(crate) struct WalletSession {
+    pub(crate) wallet_address: String,
+    pub(crate) verified_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    iss: String,
+    sub: String,
+    jti: String,
+    iat: u64,
+    exp: u64,
+    verified_at_epoch_ms: u64,
+    /// The issuing tenant; absent for the default tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tenant: Option<String>,
+}
+
+/// Issues and checks wallet session tokens.
+#[derive(Clone)]
+pub(crate) struct WalletSessions {
+    secret: Arc<[u8]>,
+    ttl: Duration,
+    required: bool,
+    tenant: Option<Arc<str>>,
+}
+
+impl WalletSessions {
+    pub(crate) fn new(secret: &[u8], ttl: Duration) -> Self {
+        Self {
+            secret: Arc::from(secret),
+            ttl,
+            required: false,
+            tenant: None,
+        }
+    }
+
+    /// The same sessions, issued and accepted for `tenant_id` only.
+    pub(crate) fn for_tenant(&self, tenant_id: &str) -> Self {
+        Self {
+            tenant: Some(Arc::from(tenant_id)),
+            ..self.clone()
+        }
+    }
+
+    /// Refuse wallet-scoped requests that carry no session.
+    pub(crate) fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub(crate) fn from_env() -> anyhow::Result<Self> {
+        let required = matches!(
+            env::var("KEYCORTEX_REQUIRE_WALLET_SESSION")
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+                .as_str(),
+            "1" | "true" | "yes" | "on"
+        );
+        let secret = env::var("KEYCORTEX_WALLET_SESSION_SECRET")
+            .ok()
+            .filter(|value| !value.trim().is_empty());
+        let secret = match secret {
+            Some(secret) => secret.into_bytes(),
+            None if required => bail!(
+                "KEYCORTEX_REQUIRE_WALLET_SESSION is set but KEYCORTEX_WALLET_SESSION_SECRET is not"
+            ),
+            None => {
+                warn!(
+                    "KEYCORTEX_WALLET_SESSION_SECRET is not set; wallet sessions are signed with a random per-process secret"
+                );
+                rand::random::<[u8; 32]>().to_vec()
+            }
+        };
+        let ttl = env::var("KEYCORTEX_WALLET_SESSION_TTL_SECONDS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|seconds| *seconds > 0)
+            .map_or(DEFAULT_TTL, Duration::from_secs);
+        let sessions = Self::new(&secret, ttl);
+        Ok(if required { sessions.required() } else { sessions })
+    }
+
+    /// A token for `wallet_address`, verified at `verified_at_epoch_ms`,
+    /// valid for the TTL from `now`, recorded in `keystore`'s sessions
+    /// under the user the wallet is bound to, or the wallet itself.
+    pub(crate) fn issue(
+        &self,
+        keystore: &RocksDbKeystore,
+        wallet_address: &str,
+        verified_at_epoch_ms: u128,
+        now_epoch_ms: u128,
+    ) -> anyhow::Result<WalletSessionToken> {
+        let iat = (now_epoch_ms / 1_000) as u64;
+        let exp = iat + self.ttl.as_secs();
+        let session_id = Uuid::new_v4().to_string();
+        let claims = Claims {
+            iss: ISSUER.to_owned(),
+            sub: wallet_address.to_owned(),
+            jti: session_id.clone(),
+            iat,
+            exp,
+            verified_at_epoch_ms: verified_at_epoch_ms as u64,
+            tenant: self.tenant.as_deref().map(ToOwned::to_owned),
+        };
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(&self.secret),
+        )?;
+        let user_id = keystore
+            .load_wallet_binding(wallet_address)?
+            .map_or_else(|| wallet_address.to_owned(), |binding| binding.user_id);
+        keystore.save_session(&SessionRecord {
+            session_id: session_id.clone(),
+            user_id,
+            issued_at_epoch_ms: Some(u128::from(iat) * 1_000),
+            expires_at_epoch_ms: Some(u128::from(exp) * 1_000),
+            first_seen_epoch_ms: now_epoch_ms,
+            last_seen_epoch_ms: now_epoch_ms,
+            revoked: false,
+            revoked_at_epoch_ms: None,
+            revoked_by: None,
+        })?;
+        Ok(WalletSessionToken {
+            token,
+            session_id,
+            wallet_address: wallet_address.to_owned(),
+            verified_at_epoch_ms,
+            expires_at_epoch_ms: u128::from(exp) * 1_000,
+        })
+    }
+
+    fn check(&self, token: &str, now_epoch_ms: u128) -> Result<(String, WalletSession), String> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_exp = false;
+        validation.required_spec_claims.clear();
+        validation.set_issuer(&[ISSUER]);
+        let claims = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(&self.secret),
+            &validation,
+        )
+        .map_err(|_| "invalid wallet session".to_owned())?
+        .claims;
+        if claims.tenant.as_deref() != self.tenant.as_deref() {
+            return Err("wallet session is for another tenant".to_owned());
+        }
+        if u128::from(claims.exp) * 1_000 <= now_epoch_ms {
+            return Err("wallet session expired".to_owned());
+        }
+        Ok((
+            claims.jti,
+            WalletSession {
+                wallet_address: claims.sub,
+                verified_at_epoch_ms: u128::from(claims.verified_at_epoch_ms),
+            },
+        ))
+    }
+}
+
+/// Check the `X-Wallet-Session` token of any request carrying one and
+/// attach its [`WalletSession`]; an invalid, expired, unknown or revoked
+/// token fails the request with `401`.
+pub(crate) async fn accept(State(state): State<Arc<AppState>>, mut request: Request, next: Next) -> Response {
+    let Some(value) = request.headers().get(WALLET_SESSION_HEADER) else {
+        return next.run(request).await;
+    };
+    let session = value
+        .to_str()
+        .map_err(|_| "invalid wallet session".to_owned())
+        .and_then(|token| {
+            state
+                .wallet_sessions
+                .check(token.trim(), epoch_ms().unwrap_or_default())
+        })
+        .and_then(|(session_id, session)| {
+            crate::sessions::track_wallet_session(&state, &session_id)?;
+            Ok(session)
+        });
+    match session {
+        Ok(session) => {
+            request.extensions_mut().insert(session);
+            next.run(request).await
+        }
+        Err(message) => unauthorized(&message).into_response(),
+    }
+}
+
+/// The session of a wallet-scoped request, if it has one. Extracting it
+/// fails with `401` when sessions are required and the request has none.
+#[derive(Debug, Clone)]
+pub(crate) struct WalletScope(Option<WalletSession>);
+
+impl FromRequestParts<Arc<AppState>> for WalletScope {
+    type Rejection = (StatusCode, Json<ApiError>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let session = parts.extensions.get::<WalletSession>().cloned();
+        if session.is_none() && state.wallet_sessions.required {
+            return Err(unauthorized("wallet session required"));
+        }
+        Ok(Self(session))
+    }
+}
+
+impl WalletScope {
+    /// Let the request act for `wallet_address`.
+    pub(crate) fn authorize(&self, wallet_address: &str) -> Result<(), (StatusCode, Json<ApiError>)> {
+        match &self.0 {
+            Some(session) if session.wallet_address != wallet_address => {
+                Err(forbidden("wallet session is for another wallet"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the request carries a session for `wallet_address`.
+    pub(crate) fn is_for(&self, wallet_address: &str) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|session| session.wallet_address == wallet_address)
+    }
+}
+
+/// Refuse unless `session` is for a wallet bound to `user_id` that was
+/// verified within [`REAUTH_WINDOW`], so a stolen bearer token alone
+/// cannot make a sensitive decision.
+pub(crate) fn require_recent_verification(
+    state: &AppState,
+    session: Option<&WalletSession>,
+    user_id: &str,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    let session =
+        session.ok_or_else(|| unauthorized("verify your wallet again before deciding"))?;
+    let now = epoch_ms().map_err(internal_error)?;
+    if now.saturating_sub(session.verified_at_epoch_ms) > REAUTH_WINDOW.as_millis() {
+        return Err(unauthorized("wallet verification is too old; verify your wallet again"));
+    }
+    let bound_to_user = state
+        .keystore
+        .load_wallet_binding(&session.wallet_address)
+        .map_err(internal_error)?
+        .is_some_and(|binding| binding.user_id == user_id);
+    if !bound_to_user {
+        return Err(forbidden("wallet session is not for a wallet bound to the caller"));
+    }
+    Ok(())
+}
+
+/// POST /auth/session/refresh — a fresh token for the caller's session.
+pub(crate) async fn auth_session_refresh(
+    State(state): State<Arc<AppState>>,
+    session: Option<Extension<WalletSession>>,
+) -> ApiResult<WalletSessionToken> {
+    let Some(Extension(session)) = session else {
+        return Err(unauthorized("missing X-Wallet-Session header"));
+    };
+    let now = epoch_ms().map_err(internal_error)?;
+    if now.saturating_sub(session.verified_at_epoch_ms) > MAX_SESSION_AGE.as_millis() {
+        return Err(unauthorized("wallet session is too old to refresh; verify the wallet again"));
+    }
+    state
+        .wallet_sessions
+        .issue(&state.keystore, &session.wallet_address, session.verified_at_epoch_ms, now)
+        .map(Json)
+        .map_err(internal_error)
+}
@@ -0,0 +1,167 @@
+use axum::{Json, extract::State, http::StatusCode};
+use kc_api_types::{
+    WalletSummary, WalletWatchBatchRequest, WalletWatchBatchResponse, WalletWatchBatchResult,
+    WalletAddress, WalletWatchRequest,
+};
+use kc_chain_flowcortex::FLOWCORTEX_L1;
+use kc_storage::{KEY_SCHEME_WATCH_ONLY, Keystore, WalletMetadataRecord, WalletUsage, WatchOnlyWalletRecord};
+use std::sync::Arc;
+
+use crate::{AppState, ApiResult, ApiError, bad_request, epoch_ms, forbidden, internal_error, parse_field};
+
+const MAX_BATCH_ROWS: usize = 500;
+
+/// Reject sign/submit for addresses registered as watch-only.
+pub(crate) fn reject_watch_only(
+    state: &AppState,
+    wallet_address: &str,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    if state
+        .keystore
+        .load_watch_wallet(wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(forbidden(
+            "watch-only wallet has no custodied key; sign and submit are unavailable",
+        ));
+    }
+    Ok(())
+}
+
+/// POST /wallet/watch — add an address for balance/history only.
+pub(crate) async fn wallet_watch(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<WalletWatchRequest>,
+) -> ApiResult<WalletSummary> {
+    register_watch(&state, request).await.map(Json)
+}
+
+/// POST /wallet/watch/batch — register many watch-only wallets, e.g. from a
+/// CSV import. Rows are validated independently; one bad row does not fail
+/// the batch.
+pub(crate) async fn wallet_watch_batch(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<WalletWatchBatchRequest>,
+) -> ApiResult<WalletWatchBatchResponse> {
+    if request.wallets.is_empty() {
+        return Err(bad_request("wallets is required"));
+    }
+    if request.wallets.len() > MAX_BATCH_ROWS {
+        return Err(bad_request("at most 500 wallets per batch"));
+    }
+
+    let mut results = Vec::with_capacity(request.wallets.len());
+    for (row, mut wallet) in request.wallets.into_iter().enumerate() {
+        if wallet.device_id.is_none() {
+            wallet.device_id = request.device_id.clone();
+        }
+        let wallet_address = wallet.wallet_address.trim().to_owned();
+        let (ok, error) = match register_watch(&state, wallet).await {
+            Ok(_) => (true, None),
+            Err((_, Json(err))) => (false, Some(err.message)),
+        };
+        results.push(WalletWatchBatchResult {
+            row,
+            wallet_address,
+            ok,
+            error,
+        });
+    }
+    let registered = results.iter().filter(|r| r.ok).count();
+
+    Ok(Json(WalletWatchBatchResponse {
+        registered,
+        failed: results.len() - registered,
+        results,
+    }))
+}
+
+async fn register_watch(
+    state: &AppState,
+    request: WalletWatchRequest,
+) -> Result<WalletSummary, (StatusCode, Json<ApiError>)> {
+    let wallet_address =
+        parse_field::<WalletAddress>("wallet_address", request.wallet_address.trim())?.0;
+
+    let chain = request.chain.unwrap_or_else(|| FLOWCORTEX_L1.to_owned());
+    if chain != FLOWCORTEX_L1 {
+        return Err(bad_request(
+            "unsupported chain for MVP; only flowcortex-l1 is enabled",
+        ));
+    }
+
+    if state
+        .keystore
+        .load_encrypted_key(&wallet_address)
+        .await
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(bad_request("wallet is already custodied"));
+    }
+    if state
+        .keystore
+        .load_external_key(&wallet_address)
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(bad_request("wallet is already registered with an imported public key"));
+    }
+
+    let created_at_epoch_ms = epoch_ms().map_err(internal_error)?;
+    state
+        .keystore
+        .save_watch_wallet(&WatchOnlyWalletRecord {
+            wallet_address: wallet_address.clone(),
+            chain: chain.clone(),
+            created_at_epoch_ms,
+        })
+        .map_err(internal_error)?;
+
+    let label = request
+        .label
+        .map(|l| l.trim().to_owned())
+        .filter(|l| !l.is_empty());
+    state
+        .keystore
+        .save_wallet_metadata(&WalletMetadataRecord {
+            wallet_address: wallet_address.clone(),
+            label: label.clone(),
+            created_at_epoch_ms: Some(created_at_epoch_ms),
+            key_scheme: KEY_SCHEME_WATCH_ONLY.to_owned(),
+            usage: WalletUsage::default(),
+        })
+        .map_err(internal_error)?;
+    crate::mirror_wallet_metadata(state, &wallet_address).await;
+    let device_id = request
+        .device_id
+        .map(|d| d.trim().to_owned())
+        .filter(|d| !d.is_empty());
+    if let Some(device_id) = &device_id {
+        state
+            .keystore
+            .save_device_wallet(device_id, &wallet_address)
+            .map_err(internal_error)?;
+    }
+
+    Ok(WalletSummary {
+        wallet_address,
+        chain,
+        bound_user_id: None,
+        public_key: None,
+        key_fingerprint: None,
+        label,
+        device_id,
+        email: None,
+        phone: None,
+        bank_id: None,
+        custodied: false,
+        external_key: false,
+        created_at_epoch_ms: Some(created_at_epoch_ms),
+        key_scheme: Some(KEY_SCHEME_WATCH_ONLY.to_owned()),
+        signature_count: 0,
+        last_signed_at_epoch_ms: None,
+        last_submitted_at_epoch_ms: None,
+    })
+}
@@ -0,0 +1,144 @@
+//! Signed event webhooks.
+//!
+//! With `KEYCORTEX_WEBHOOK_URL` set, every event below is posted there as a
+//! [`WebhookEnvelope`] signed with `KEYCORTEX_WEBHOOK_SECRET`; consumers
+//! check it with [`WebhookEnvelope::verify`] and the same secret.
+//!
+//! - `tx_status_changed` and `wallet_bound` follow the keystore's change
+//!   notifications, so every path that records them is covered.
+//! - `wallet_created` is sent by `POST /wallet/create`.
+//! - `challenge_verified` is sent by `POST /auth/verify` when the signature
+//!   checks out.
+//!
+//! Delivery is best effort: one attempt per event, failures are logged, and
+//! events raised while the service is down are not replayed. Consumers that
+//! must see every tx status change read the event broker instead (see
+//! [`crate::publisher`]).
+
+use anyhow::{Context, Result, bail};
+use kc_api_types::webhook::{WebhookEnvelope, WebhookEvent};
+use kc_storage::StorageEvent;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{AppState, epoch_ms};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) struct WebhookNotifier {
+    url: String,
+    secret: Vec<u8>,
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    /// `None` unless `KEYCORTEX_WEBHOOK_URL` is set.
+    pub(crate) fn from_env() -> Result<Option<Self>> {
+        let Some(url) = env::var("KEYCORTEX_WEBHOOK_URL")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let secret = env::var("KEYCORTEX_WEBHOOK_SECRET")
+            .context("KEYCORTEX_WEBHOOK_SECRET is required with KEYCORTEX_WEBHOOK_URL")?;
+        Self::new(&url, &secret).map(Some)
+    }
+
+    pub(crate) fn new(url: &str, secret: &str) -> Result<Self> {
+        let url = url.trim();
+        if url.is_empty() {
+            bail!("webhook URL is empty");
+        }
+        if secret.len() < 16 {
+            bail!("webhook secret must be at least 16 bytes");
+        }
+        Ok(Self {
+            url: url.to_owned(),
+            secret: secret.as_bytes().to_vec(),
+            http: reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?,
+        })
+    }
+
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn send(&self, event: &WebhookEvent) -> Result<()> {
+        let envelope = WebhookEnvelope::sign(
+            format!("evt-{}", Uuid::new_v4()),
+            event,
+            epoch_ms()?,
+            &self.secret,
+        );
+        self.http
+            .post(&self.url)
+            .json(&envelope)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Post `event` in the background if webhooks are configured.
+pub(crate) fn emit(state: &AppState, event: WebhookEvent) {
+    let Some(notifier) = state.webhook_notifier.clone() else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(err) = notifier.send(&event).await {
+            warn!("webhook {} not delivered: {:#}", event.kind(), err);
+        }
+    });
+}
+
+/// The webhook for a keystore change, if it has one.
+fn storage_event(event: StorageEvent) -> Option<WebhookEvent> {
+    match event {
+        StorageEvent::TxStatusChanged {
+            tx_hash,
+            from,
+            previous_status,
+            status,
+        } => Some(WebhookEvent::TxStatusChanged {
+            tx_hash,
+            from,
+            previous_status,
+            status,
+        }),
+        StorageEvent::BindingUpdated(binding) => Some(WebhookEvent::WalletBound {
+            wallet_address: binding.wallet_address,
+            user_id: binding.user_id,
+            chain: binding.chain,
+        }),
+        StorageEvent::KeySaved { .. } | StorageEvent::AuditAppended(_) => None,
+    }
+}
+
+/// Forward keystore changes from `events` to the webhook until the
+/// keystore is dropped. Subscribe before spawning this, so writes made in
+/// the meantime are not missed.
+pub(crate) async fn run(notifier: Arc<WebhookNotifier>, mut events: Receiver<StorageEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                let Some(event) = storage_event(event) else {
+                    continue;
+                };
+                if let Err(err) = notifier.send(&event).await {
+                    warn!("webhook {} not delivered: {:#}", event.kind(), err);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("webhook notifier fell behind; {skipped} keystore events were not sent");
+            }
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
@@ -5,6 +5,8 @@
 //! Extend by adding new request helpers or auth header injection.
 
 use crate::dom;
+use kc_api_types::ApiError;
+use std::fmt;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
@@ -46,53 +48,136 @@ pub async fn request(
     method: &str,
     body: Option<String>,
 ) -> Result<serde_json::Value, String> {
+    request_with_headers(path, method, body, &[]).await
+}
+
+/// Like [`request`], with extra headers (e.g. `Authorization`, `X-Device-Id`).
+pub async fn request_with_headers(
+    path: &str,
+    method: &str,
+    body: Option<String>,
+    extra_headers: &[(&str, &str)],
+) -> Result<serde_json::Value, String> {
+    request_api(path, method, body, extra_headers)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Why a request failed.
+#[derive(Debug)]
+pub enum RequestError {
+    /// No usable response: a network, CORS or parse failure.
+    Transport(String),
+    /// The backend answered with an error status. `error` is `None` when the
+    /// body is not an error response, e.g. from a proxy in between.
+    Api {
+        status: u16,
+        status_text: String,
+        error: Option<ApiError>,
+        body: String,
+    },
+}
+
+impl RequestError {
+    /// The backend's error, for branching on its `code`.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            RequestError::Api { error, .. } => error.as_ref(),
+            RequestError::Transport(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Transport(msg) => f.write_str(msg),
+            RequestError::Api { status, status_text, error: Some(error), .. } => {
+                write!(f, "{} {}: {}", status, status_text, error.message)
+            }
+            RequestError::Api { status, status_text, error: None, body } => {
+                write!(f, "{} {}: {}", status, status_text, body)
+            }
+        }
+    }
+}
+
+/// Like [`request_with_headers`], keeping the backend's typed error.
+pub async fn request_api(
+    path: &str,
+    method: &str,
+    body: Option<String>,
+    extra_headers: &[(&str, &str)],
+) -> Result<serde_json::Value, RequestError> {
     let url = format!("{}{}", base_url(), path);
 
     let opts = RequestInit::new();
     opts.set_method(method);
     opts.set_mode(RequestMode::Cors);
 
-    let headers = Headers::new().map_err(|e| format!("{:?}", e))?;
+    let headers = Headers::new().map_err(|e| RequestError::Transport(format!("{:?}", e)))?;
 
     if let Some(ref b) = body {
         headers
             .set("Content-Type", "application/json")
-            .map_err(|e| format!("{:?}", e))?;
+            .map_err(|e| RequestError::Transport(format!("{:?}", e)))?;
         let js_body = JsValue::from_str(b);
         opts.set_body(&js_body);
     }
 
+    for (name, value) in extra_headers {
+        if !value.is_empty() {
+            headers.set(name, value).map_err(|e| RequestError::Transport(format!("{:?}", e)))?;
+        }
+    }
+
     opts.set_headers(&headers);
 
-    let request = Request::new_with_str_and_init(&url, &opts).map_err(|e| format!("{:?}", e))?;
+    let request = Request::new_with_str_and_init(&url, &opts).map_err(|e| RequestError::Transport(format!("{:?}", e)))?;
 
     let window = dom::window();
     let resp_value = match JsFuture::from(window.fetch_with_request(&request)).await {
         Ok(v) => v,
         Err(e) => {
             let api_host = base_url();
-            return Err(format!(
+            return Err(RequestError::Transport(format!(
                 "Network error: {:?}\n\nIf using a self-signed certificate, open {}/health in a new tab, accept the certificate, then retry.",
                 e, api_host
-            ));
+            )));
         }
     };
 
     let resp: Response = resp_value
         .dyn_into()
-        .map_err(|_| "response is not a Response".to_string())?;
+        .map_err(|_| RequestError::Transport("response is not a Response".to_string()))?;
 
-    let text = JsFuture::from(resp.text().map_err(|e| format!("{:?}", e))?)
+    let text = JsFuture::from(resp.text().map_err(|e| RequestError::Transport(format!("{:?}", e)))?)
         .await
-        .map_err(|e| format!("text error: {:?}", e))?;
+        .map_err(|e| RequestError::Transport(format!("text error: {:?}", e)))?;
 
     let text_str = text.as_string().unwrap_or_default();
 
     if !resp.ok() {
-        return Err(format!("{} {}: {}", resp.status(), resp.status_text(), text_str));
+        return Err(RequestError::Api {
+            status: resp.status(),
+            status_text: resp.status_text(),
+            error: serde_json::from_str(&text_str).ok(),
+            body: text_str,
+        });
     }
 
-    serde_json::from_str(&text_str).map_err(|e| format!("JSON parse error: {} — raw: {}", e, text_str))
+    serde_json::from_str(&text_str)
+        .map_err(|e| RequestError::Transport(format!("JSON parse error: {} — raw: {}", e, text_str)))
+}
+
+/// Format a bearer `Authorization` header value (empty when no token is set).
+pub fn bearer(token: &str) -> String {
+    let token = token.trim();
+    if token.is_empty() {
+        String::new()
+    } else {
+        format!("Bearer {}", token)
+    }
 }
 
 /// Fetch a URL and return the body as a plain string.
@@ -118,6 +203,93 @@ pub async fn fetch_text(url: &str) -> Result<String, String> {
     Ok(text.as_string().unwrap_or_default())
 }
 
+/// Fetch an export endpoint and hand the body to the browser as a file
+/// download. The filename comes from the response's `Content-Disposition`
+/// (exposed to CORS by the backend), or `fallback_filename` when absent.
+/// Returns the filename used.
+pub async fn download(
+    path: &str,
+    extra_headers: &[(&str, &str)],
+    fallback_filename: &str,
+) -> Result<String, String> {
+    let url = format!("{}{}", base_url(), path);
+
+    let opts = RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(RequestMode::Cors);
+    let headers = Headers::new().map_err(|e| format!("{:?}", e))?;
+    for (name, value) in extra_headers {
+        if !value.is_empty() {
+            headers.set(name, value).map_err(|e| format!("{:?}", e))?;
+        }
+    }
+    opts.set_headers(&headers);
+
+    let request = Request::new_with_str_and_init(&url, &opts).map_err(|e| format!("{:?}", e))?;
+    let resp: Response = JsFuture::from(dom::window().fetch_with_request(&request))
+        .await
+        .map_err(|e| format!("Network error: {:?}", e))?
+        .dyn_into()
+        .map_err(|_| "response is not a Response".to_string())?;
+
+    if !resp.ok() {
+        let text = JsFuture::from(resp.text().map_err(|e| format!("{:?}", e))?)
+            .await
+            .ok()
+            .and_then(|t| t.as_string())
+            .unwrap_or_default();
+        return Err(format!("{} {}: {}", resp.status(), resp.status_text(), text));
+    }
+
+    let filename = resp
+        .headers()
+        .get("Content-Disposition")
+        .ok()
+        .flatten()
+        .and_then(|value| disposition_filename(&value))
+        .unwrap_or_else(|| fallback_filename.to_string());
+    let blob: web_sys::Blob = JsFuture::from(resp.blob().map_err(|e| format!("{:?}", e))?)
+        .await
+        .map_err(|e| format!("blob error: {:?}", e))?
+        .dyn_into()
+        .map_err(|_| "body is not a Blob".to_string())?;
+
+    let object_url =
+        web_sys::Url::create_object_url_with_blob(&blob).map_err(|e| format!("{:?}", e))?;
+    let anchor: web_sys::HtmlAnchorElement = dom::document()
+        .create_element("a")
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into()
+        .map_err(|_| "not an anchor".to_string())?;
+    anchor.set_href(&object_url);
+    anchor.set_download(&filename);
+    anchor.click();
+    // Some browsers read the blob after `click()` returns; release it later.
+    gloo_timers::callback::Timeout::new(1_000, move || {
+        let _ = web_sys::Url::revoke_object_url(&object_url);
+    })
+    .forget();
+    Ok(filename)
+}
+
+/// Filename from a `Content-Disposition` value, preferring the RFC 5987
+/// `filename*` form over the plain `filename`.
+fn disposition_filename(value: &str) -> Option<String> {
+    let param = |name: &str| {
+        value.split(';').find_map(|part| {
+            let (key, val) = part.trim().split_once('=')?;
+            (key.eq_ignore_ascii_case(name)).then(|| val.trim().trim_matches('"').to_string())
+        })
+    };
+    param("filename*")
+        .and_then(|encoded| {
+            let encoded = encoded.strip_prefix("UTF-8''")?;
+            js_sys::decode_uri_component(encoded).ok()?.as_string()
+        })
+        .or_else(|| param("filename"))
+        .filter(|name| !name.is_empty())
+}
+
 /// Base64-encode a UTF-8 string (mirrors JS `btoa`).
 pub fn to_base64(input: &str) -> String {
     let window = dom::window();
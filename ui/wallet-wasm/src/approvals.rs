@@ -0,0 +1,613 @@
+//! Approval inbox for transfers waiting on the current user.
+//!
+//! Lists two kinds of pending transfer: escrows from `GET /wallet/escrow`
+//! whose approver is the wallet on the Connect tab, and transfers held by a
+//! wallet's signing policy from `GET /wallet/approvals` that the AuthBuddy
+//! user in the Connect token may decide, i.e. those someone else submitted.
+//! Selecting one shows the canonical payload that is signed if it is
+//! approved. Approve and Reject re-authenticate first: the Connect wallet
+//! signs a fresh challenge, and the decision is only sent once
+//! `/auth/verify` accepts it, with the wallet session it returns, which the
+//! service requires to be fresh. While the tab is open the list is polled, so
+//! transfers decided elsewhere drop out with a note of how they ended.
+
+use std::cell::RefCell;
+
+use gloo_timers::callback::Interval;
+use kc_api_types::{
+    ConditionalTransferResponse, TransferApprovalResponse, TxEnvelopeV1, WalletSubmitRequest,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+use crate::api;
+use crate::dom::{self, Elements};
+
+const POLL_MS: u32 = 5_000;
+
+/// A transfer awaiting the current user.
+#[derive(Clone)]
+enum Pending {
+    /// Released by the Connect wallet signing its digest.
+    Escrow(ConditionalTransferResponse),
+    /// Held by a signing policy until an ops principal decides it.
+    Held(TransferApprovalResponse),
+}
+
+impl Pending {
+    fn id(&self) -> &str {
+        match self {
+            Pending::Escrow(t) => &t.transfer_id,
+            Pending::Held(a) => &a.approval_id,
+        }
+    }
+
+    fn status(&self) -> &str {
+        match self {
+            Pending::Escrow(t) => &t.status,
+            Pending::Held(a) => &a.status,
+        }
+    }
+
+    /// `(from, to, amount, asset, chain)`
+    fn transfer(&self) -> (&str, &str, &str, &str, &str) {
+        match self {
+            Pending::Escrow(t) => (&t.from, &t.to, &t.amount, &t.asset, &t.chain),
+            Pending::Held(a) => (&a.from, &a.to, &a.amount, &a.asset, &a.chain),
+        }
+    }
+
+    fn since_epoch_ms(&self) -> u128 {
+        match self {
+            Pending::Escrow(t) => t.created_at_epoch_ms,
+            Pending::Held(a) => a.requested_at_epoch_ms,
+        }
+    }
+}
+
+thread_local! {
+    static POLL: RefCell<Option<Interval>> = const { RefCell::new(None) };
+    /// The pending transfers last rendered.
+    static PENDING: RefCell<Vec<Pending>> = const { RefCell::new(Vec::new()) };
+    static SELECTED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Poll the inbox while the Approvals tab is open, and stop once it is left.
+pub fn set_polling(els: &Elements, active: bool) {
+    let timer = active.then(|| {
+        let els = els.clone();
+        Interval::new(POLL_MS, move || {
+            let els = els.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                refresh(&els, false).await;
+            });
+        })
+    });
+    // Replacing the interval drops, and so cancels, the previous one.
+    POLL.with(|p| *p.borrow_mut() = timer);
+    if active {
+        let els = els.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            refresh(&els, true).await;
+        });
+    }
+}
+
+/// GET /wallet/escrow?approver_wallet=…&status=pending and
+/// GET /wallet/approvals?status=pending
+pub async fn on_refresh_approvals(els: &Elements) {
+    refresh(els, true).await;
+}
+
+/// Reload the inbox. A background poll stays quiet about failures that a
+/// manual refresh reports.
+async fn refresh(els: &Elements, manual: bool) {
+    let approver = dom::get_input_value(&els.connect_wallet_address);
+    let token = dom::get_input_value(&els.connect_token);
+    if approver.is_empty() && token.is_empty() {
+        if manual {
+            api::set_result_error(
+                &els.approvals_result,
+                "enter your wallet address or an AuthBuddy ops token on the Connect tab",
+            );
+        }
+        return;
+    }
+
+    let mut awaiting = Vec::new();
+    let mut errors = Vec::new();
+    if !approver.is_empty() {
+        match pending_escrows(&approver).await {
+            Ok(escrows) => awaiting.extend(escrows.into_iter().map(Pending::Escrow)),
+            Err(e) => errors.push(e),
+        }
+    }
+    if !token.is_empty() {
+        match pending_holds(&token).await {
+            Ok(holds) => awaiting.extend(holds.into_iter().map(Pending::Held)),
+            Err(e) => errors.push(e),
+        }
+    }
+    awaiting.sort_by_key(Pending::since_epoch_ms);
+
+    let gone: Vec<Pending> = PENDING.with(|p| {
+        p.borrow()
+            .iter()
+            .filter(|old| !awaiting.iter().any(|new| new.id() == old.id()))
+            .cloned()
+            .collect()
+    });
+    PENDING.with(|p| *p.borrow_mut() = awaiting.clone());
+    render_list(els, &awaiting);
+    render_detail(els).await;
+
+    if !gone.is_empty() {
+        let notes = decided_elsewhere(&gone, &token).await;
+        if !notes.is_empty() {
+            dom::remove_class(&els.approvals_result, "error");
+            dom::set_text(&els.approvals_result, &notes.join("\n"));
+            return;
+        }
+    }
+    if manual {
+        if errors.is_empty() {
+            dom::remove_class(&els.approvals_result, "error");
+            dom::set_text(
+                &els.approvals_result,
+                &format!("{} transfer(s) awaiting your approval", awaiting.len()),
+            );
+        } else {
+            api::set_result_error(&els.approvals_result, &errors.join("\n"));
+        }
+    }
+}
+
+/// Pending escrows whose approver is `approver`.
+async fn pending_escrows(approver: &str) -> Result<Vec<ConditionalTransferResponse>, String> {
+    let path = format!(
+        "/wallet/escrow?approver_wallet={}&status=pending",
+        js_sys::encode_uri_component(approver)
+    );
+    let result = api::request(&path, "GET", None).await?;
+    Ok(serde_json::from_value(result["transfers"].clone()).unwrap_or_default())
+}
+
+/// Pending holds that the user in `token` did not submit themselves.
+async fn pending_holds(token: &str) -> Result<Vec<TransferApprovalResponse>, String> {
+    let auth = api::bearer(token);
+    let headers = [("Authorization", auth.as_str())];
+    let result = api::request_with_headers(
+        "/wallet/approvals?status=pending",
+        "GET",
+        None,
+        &headers,
+    )
+    .await?;
+    let me = token_subject(token);
+    Ok(
+        serde_json::from_value::<Vec<TransferApprovalResponse>>(result["approvals"].clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|approval| me.is_none() || approval.requested_by != me)
+            .collect(),
+    )
+}
+
+/// One line per transfer in `gone` that has been decided, saying how it
+/// ended and, for holds, who decided it.
+async fn decided_elsewhere(gone: &[Pending], token: &str) -> Vec<String> {
+    let mut notes = Vec::new();
+    let mut held = Vec::new();
+    for pending in gone {
+        match pending {
+            Pending::Escrow(old) => {
+                let path = format!(
+                    "/wallet/escrow/{}",
+                    js_sys::encode_uri_component(&old.transfer_id)
+                );
+                let Ok(result) = api::request(&path, "GET", None).await else {
+                    continue;
+                };
+                let Ok(transfer) = serde_json::from_value::<ConditionalTransferResponse>(result)
+                else {
+                    continue;
+                };
+                notes.push(format!(
+                    "{} {} to {}: {}",
+                    transfer.amount,
+                    transfer.asset,
+                    shorten(&transfer.to),
+                    transfer.status,
+                ));
+            }
+            Pending::Held(old) => held.push(old.approval_id.clone()),
+        }
+    }
+    if held.is_empty() || token.is_empty() {
+        return notes;
+    }
+
+    let auth = api::bearer(token);
+    let headers = [("Authorization", auth.as_str())];
+    let Ok(result) = api::request_with_headers("/wallet/approvals", "GET", None, &headers).await
+    else {
+        return notes;
+    };
+    notes.extend(
+        serde_json::from_value::<Vec<TransferApprovalResponse>>(result["approvals"].clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|approval| held.contains(&approval.approval_id))
+            .map(|approval| {
+                format!(
+                    "{} {} to {}: {} by {}",
+                    approval.amount,
+                    approval.asset,
+                    shorten(&approval.to),
+                    approval.status,
+                    approval.decided_by.as_deref().unwrap_or("another approver"),
+                )
+            }),
+    );
+    notes
+}
+
+fn render_list(els: &Elements, pending: &[Pending]) {
+    if pending.is_empty() {
+        dom::set_inner_html(
+            &els.approvals_list,
+            r#"<div class="approval-empty">Nothing is waiting for your approval.</div>"#,
+        );
+        return;
+    }
+    let selected = SELECTED.with(|s| s.borrow().clone());
+    let html: String = pending
+        .iter()
+        .map(|item| {
+            let (from, to, amount, asset, _) = item.transfer();
+            let note = match item {
+                Pending::Escrow(t) => {
+                    format!("escrow until {}", format_time(t.expires_at_epoch_ms as f64))
+                }
+                Pending::Held(a) => a.requested_by.clone().unwrap_or_else(|| "—".to_string()),
+            };
+            format!(
+                r#"<div class="approval-row{}" data-id="{}"><span class="approval-time">{}</span><span>{} → {}</span><span class="approval-amount">{} {}</span><span class="approval-by">{}</span></div>"#,
+                if selected.as_deref() == Some(item.id()) {
+                    " approval-row--selected"
+                } else {
+                    ""
+                },
+                escape(item.id()),
+                format_time(item.since_epoch_ms() as f64),
+                escape(&shorten(from)),
+                escape(&shorten(to)),
+                escape(amount),
+                escape(asset),
+                escape(&note),
+            )
+        })
+        .collect();
+    dom::set_inner_html(&els.approvals_list, &html);
+
+    for row in dom::query_all_within(&els.approvals_list, ".approval-row") {
+        let id = row.get_attribute("data-id").unwrap_or_default();
+        let els2 = els.clone();
+        let cb = Closure::wrap(Box::new(move |_: web_sys::MouseEvent| {
+            SELECTED.with(|s| *s.borrow_mut() = Some(id.clone()));
+            let els3 = els2.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let pending = PENDING.with(|p| p.borrow().clone());
+                render_list(&els3, &pending);
+                render_detail(&els3).await;
+            });
+        }) as Box<dyn FnMut(_)>);
+        row.add_event_listener_with_callback("click", cb.as_ref().unchecked_ref())
+            .unwrap();
+        cb.forget();
+    }
+}
+
+fn find_pending(id: &str) -> Option<Pending> {
+    PENDING.with(|p| p.borrow().iter().find(|item| item.id() == id).cloned())
+}
+
+/// Show the selected transfer, or clear the detail once it is decided.
+async fn render_detail(els: &Elements) {
+    let selected = SELECTED.with(|s| s.borrow().clone());
+    let Some(item) = selected.as_deref().and_then(find_pending) else {
+        SELECTED.with(|s| *s.borrow_mut() = None);
+        dom::set_inner_html(&els.approvals_detail, "");
+        return;
+    };
+
+    let (from, to, amount, asset, chain) = item.transfer();
+    let mut facts = vec![
+        ("Chain", escape(chain)),
+        ("From", escape(from)),
+        ("To", escape(to)),
+        ("Amount", format!("{} {}", escape(amount), escape(asset))),
+    ];
+    let payload_html = match &item {
+        Pending::Escrow(transfer) => {
+            facts.push(("Approver", escape(&transfer.approver_wallet)));
+            facts.push(("Created", format_time(transfer.created_at_epoch_ms as f64)));
+            facts.push(("Expires", format_time(transfer.expires_at_epoch_ms as f64)));
+            format!(
+                r#"<p class="approval-hint">Approving signs this digest, the SHA-256 of the canonical payload below. Rejecting signs <code>cancel:</code> followed by it.</p><pre class="approval-payload">{}</pre><code class="tx-hash">{}</code>"#,
+                escape(&escrow_payload(transfer)),
+                escape(&transfer.digest),
+            )
+        }
+        Pending::Held(approval) => {
+            facts.push(("Held because", escape(&approval.reason)));
+            facts.push((
+                "Requested by",
+                escape(approval.requested_by.as_deref().unwrap_or("—")),
+            ));
+            facts.push(("Requested", format_time(approval.requested_at_epoch_ms as f64)));
+            match held_payload(approval).await {
+                Ok(payload) => format!(
+                    r#"<p class="approval-hint">Signed if approved now. The signature also binds the chain, this nonce and a fresh expiry.</p><pre class="approval-payload">{}</pre>"#,
+                    escape(&payload)
+                ),
+                Err(e) => format!(
+                    r#"<p class="approval-hint dash-error">Canonical payload unavailable: {}</p>"#,
+                    escape(&e)
+                ),
+            }
+        }
+    };
+    let facts_html: String = facts
+        .iter()
+        .map(|(label, value)| format!("<dt>{}</dt><dd>{}</dd>", label, value))
+        .collect();
+    let html = format!(
+        r#"<div class="tx-card">
+  <div class="tx-card-header">
+    <span class="tx-status">{status}</span>
+    <code class="tx-hash">{id}</code>
+  </div>
+  <dl class="tx-facts">{facts}</dl>
+  {payload}
+  <div class="button-row">
+    <button class="approval-approve-btn primary" data-id="{id}">Approve</button>
+    <button class="approval-reject-btn secondary" data-id="{id}">Reject</button>
+  </div>
+</div>"#,
+        status = escape(item.status()),
+        id = escape(item.id()),
+        facts = facts_html,
+        payload = payload_html,
+    );
+    dom::set_inner_html(&els.approvals_detail, &html);
+
+    for (selector, approve) in [(".approval-approve-btn", true), (".approval-reject-btn", false)] {
+        for btn in dom::query_all_within(&els.approvals_detail, selector) {
+            let id = btn.get_attribute("data-id").unwrap_or_default();
+            let els2 = els.clone();
+            let cb = Closure::wrap(Box::new(move |_: web_sys::MouseEvent| {
+                let els3 = els2.clone();
+                let id = id.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    decide(&els3, &id, approve).await;
+                });
+            }) as Box<dyn FnMut(_)>);
+            btn.add_event_listener_with_callback("click", cb.as_ref().unchecked_ref())
+                .unwrap();
+            cb.forget();
+        }
+    }
+}
+
+/// The string the service hashes into an escrow's `digest`.
+fn escrow_payload(transfer: &ConditionalTransferResponse) -> String {
+    format!(
+        "keycortex:escrow:v1:{}:{}:{}:{}:{}:{}:{}:{}",
+        transfer.transfer_id,
+        transfer.from,
+        transfer.to,
+        transfer.amount,
+        transfer.asset,
+        transfer.chain,
+        transfer.approver_wallet,
+        transfer.expires_at_epoch_ms,
+    )
+}
+
+/// [`TxEnvelopeV1::canonical`] of a held transfer with the source wallet's
+/// next nonce, which the service assigns when it is approved.
+async fn held_payload(approval: &TransferApprovalResponse) -> Result<String, String> {
+    let query = format!(
+        "wallet_address={}&chain={}",
+        js_sys::encode_uri_component(&approval.from),
+        js_sys::encode_uri_component(&approval.chain),
+    );
+    let nonce = api::request(&format!("/wallet/nonce?{}", query), "GET", None)
+        .await?
+        .get("next_nonce")
+        .and_then(|v| v.as_u64())
+        .ok_or("nonce response has no next_nonce")?;
+    let request = WalletSubmitRequest {
+        from: approval.from.clone(),
+        to: approval.to.clone(),
+        amount: approval.amount.clone(),
+        asset: approval.asset.clone(),
+        chain: approval.chain.clone(),
+        nonce,
+        signed_payload: None,
+        expires_at_epoch_ms: None,
+        memo: None,
+        dry_run: false,
+    };
+    TxEnvelopeV1::from_request(&request)
+        .map(|envelope| envelope.canonical())
+        .map_err(|e| e.to_string())
+}
+
+/// Re-authenticate, then send the decision for the transfer `id`.
+async fn decide(els: &Elements, id: &str, approve: bool) {
+    let verb = if approve { "Approve" } else { "Reject" };
+    let confirmed = dom::window()
+        .confirm_with_message(&format!(
+            "{} this transfer? You will sign in again with the Connect wallet first.",
+            verb
+        ))
+        .unwrap_or(false);
+    if !confirmed {
+        return;
+    }
+    let Some(item) = find_pending(id) else {
+        return;
+    };
+    dom::remove_class(&els.approvals_result, "error");
+    dom::set_text(&els.approvals_result, "Re-authenticating…");
+    let (wallet, session) = match reauthenticate(els).await {
+        Ok(verified) => verified,
+        Err(e) => {
+            api::set_result_error(
+                &els.approvals_result,
+                &format!("re-authentication failed; nothing was decided: {}", e),
+            );
+            return;
+        }
+    };
+
+    let decided = match &item {
+        Pending::Escrow(transfer) => decide_escrow(transfer, &wallet, &session, approve).await,
+        Pending::Held(approval) => {
+            let token = dom::get_input_value(&els.connect_token);
+            decide_held(&token, &session, approval, approve).await
+        }
+    };
+    match decided {
+        Ok(result) => {
+            PENDING.with(|p| p.borrow_mut().retain(|item| item.id() != id));
+            refresh(els, false).await;
+            api::set_result(&els.approvals_result, &result);
+        }
+        Err(e) => api::set_result_error(&els.approvals_result, &e),
+    }
+}
+
+/// Sign with the approver wallet → POST /wallet/escrow/:id/approve or /cancel
+async fn decide_escrow(
+    transfer: &ConditionalTransferResponse,
+    approver: &str,
+    session: &str,
+    approve: bool,
+) -> Result<serde_json::Value, String> {
+    let (payload, action) = if approve {
+        (transfer.digest.clone(), "approve")
+    } else {
+        (format!("cancel:{}", transfer.digest), "cancel")
+    };
+    let signature = sign(approver, &payload, "transaction", Some(session)).await?;
+    let body = if approve {
+        serde_json::json!({ "signature": signature })
+    } else {
+        serde_json::json!({ "wallet_address": approver, "signature": signature })
+    };
+    let path = format!(
+        "/wallet/escrow/{}/{}",
+        js_sys::encode_uri_component(&transfer.transfer_id),
+        action
+    );
+    let headers = [("X-Wallet-Session", session)];
+    api::request_with_headers(&path, "POST", Some(body.to_string()), &headers).await
+}
+
+/// POST /wallet/approvals/:id/decide
+async fn decide_held(
+    token: &str,
+    session: &str,
+    approval: &TransferApprovalResponse,
+    approve: bool,
+) -> Result<serde_json::Value, String> {
+    let auth = api::bearer(token);
+    let headers = [("Authorization", auth.as_str()), ("X-Wallet-Session", session)];
+    let path = format!(
+        "/wallet/approvals/{}/decide",
+        js_sys::encode_uri_component(&approval.approval_id)
+    );
+    let body = serde_json::json!({ "approve": approve });
+    api::request_with_headers(&path, "POST", Some(body.to_string()), &headers).await
+}
+
+/// Challenge → sign → verify with the Connect wallet, which is returned
+/// with the wallet session token `/auth/verify` issued for it.
+async fn reauthenticate(els: &Elements) -> Result<(String, String), String> {
+    let addr = dom::get_input_value(&els.connect_wallet_address);
+    if addr.is_empty() {
+        return Err("enter your wallet address on the Connect tab".to_string());
+    }
+    let challenge = api::request("/auth/challenge", "POST", None).await?;
+    let challenge = challenge["challenge"].as_str().unwrap_or_default().to_string();
+    let signature = sign(&addr, &challenge, "auth", None).await?;
+
+    let verify_body = serde_json::json!({
+        "wallet_address": addr,
+        "signature": signature,
+        "challenge": challenge,
+    });
+    let verified = api::request("/auth/verify", "POST", Some(verify_body.to_string())).await?;
+    if verified["valid"].as_bool() != Some(true) {
+        return Err("signature was not accepted".to_string());
+    }
+    let session = verified["session"]["token"]
+        .as_str()
+        .ok_or("verification returned no wallet session")?;
+    Ok((addr, session.to_string()))
+}
+
+/// Sign `payload` with `wallet_address` for `purpose`, under its wallet
+/// session if there is one.
+async fn sign(
+    wallet_address: &str,
+    payload: &str,
+    purpose: &str,
+    session: Option<&str>,
+) -> Result<String, String> {
+    let body = serde_json::json!({
+        "wallet_address": wallet_address,
+        "payload": api::to_base64(payload),
+        "purpose": purpose,
+    });
+    let headers: Vec<(&str, &str)> = session.map(|s| ("X-Wallet-Session", s)).into_iter().collect();
+    let result =
+        api::request_with_headers("/wallet/sign", "POST", Some(body.to_string()), &headers).await?;
+    Ok(result["signature"].as_str().unwrap_or_default().to_string())
+}
+
+/// The `sub` claim of a JWT. The signature is not checked here; the service
+/// checks the token on every request.
+fn token_subject(token: &str) -> Option<String> {
+    let payload = token.trim().split('.').nth(1)?;
+    let mut b64 = payload.replace('-', "+").replace('_', "/");
+    while b64.len() % 4 != 0 {
+        b64.push('=');
+    }
+    let json = dom::window().atob(&b64).ok()?;
+    let claims: serde_json::Value = serde_json::from_str(&json).ok()?;
+    claims["sub"].as_str().map(str::to_string)
+}
+
+fn shorten(s: &str) -> String {
+    if s.len() <= 15 {
+        s.to_string()
+    } else {
+        format!("{}\u{2026}{}", &s[..8], &s[s.len() - 6..])
+    }
+}
+
+fn format_time(epoch_ms: f64) -> String {
+    let at = js_sys::Date::new(&JsValue::from_f64(epoch_ms));
+    String::from(at.to_locale_string("default", &JsValue::UNDEFINED))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,231 @@
+//! CSV import of watch-only wallets.
+//!
+//! Reads a CSV of `address[,label]` rows, checks each row locally, registers
+//! the valid ones through `POST /wallet/watch/batch` and assigns them to the
+//! active profile. Every row gets a line of feedback, local or from the server.
+
+use std::collections::HashSet;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::api;
+use crate::dom::{self, Elements};
+use crate::profile;
+use crate::state;
+use crate::wallet_list;
+
+const MAX_ROWS: usize = 500;
+
+/// One data row of the CSV, with its 1-based line number for feedback.
+struct CsvRow {
+    line: usize,
+    address: String,
+    label: String,
+}
+
+/// Outcome shown for a row.
+struct RowFeedback {
+    line: usize,
+    address: String,
+    ok: bool,
+    message: String,
+}
+
+/// Import the selected CSV file.
+pub async fn on_csv_import(els: &Elements) {
+    let text = match read_selected_file(&els.csv_import_input).await {
+        Ok(text) => text,
+        Err(e) => {
+            api::set_result_error(&els.create_result, &e);
+            return;
+        }
+    };
+
+    let rows = parse_csv(&text);
+    if rows.is_empty() {
+        api::set_result_error(&els.create_result, "CSV has no address rows");
+        return;
+    }
+    if rows.len() > MAX_ROWS {
+        api::set_result_error(
+            &els.create_result,
+            &format!(
+                "CSV has {} rows; at most {} per import",
+                rows.len(),
+                MAX_ROWS
+            ),
+        );
+        return;
+    }
+
+    let (valid, mut feedback) = validate_rows(rows);
+    if !valid.is_empty() {
+        let wallets: Vec<serde_json::Value> = valid
+            .iter()
+            .map(|row| {
+                serde_json::json!({
+                    "wallet_address": row.address,
+                    "chain": "flowcortex-l1",
+                    "label": if row.label.is_empty() { None } else { Some(&row.label) },
+                })
+            })
+            .collect();
+        let body = serde_json::json!({
+            "wallets": wallets,
+            "device_id": state::get_device_id(),
+        });
+        match api::request("/wallet/watch/batch", "POST", Some(body.to_string())).await {
+            Ok(result) => {
+                let active_profile = state::active_profile();
+                for entry in result["results"].as_array().into_iter().flatten() {
+                    let Some(row) = entry["row"].as_u64().and_then(|i| valid.get(i as usize))
+                    else {
+                        continue;
+                    };
+                    let ok = entry["ok"].as_bool().unwrap_or(false);
+                    if ok {
+                        if let Some(profile_id) = &active_profile {
+                            profile::assign_wallet_to_profile(&row.address, profile_id);
+                        }
+                    }
+                    feedback.push(RowFeedback {
+                        line: row.line,
+                        address: row.address.clone(),
+                        ok,
+                        message: if ok {
+                            "watching".to_string()
+                        } else {
+                            entry["error"].as_str().unwrap_or("rejected").to_string()
+                        },
+                    });
+                }
+            }
+            Err(e) => {
+                api::set_result_error(&els.create_result, &e);
+                return;
+            }
+        }
+    }
+
+    feedback.sort_by_key(|f| f.line);
+    render_feedback(els, &feedback);
+    els.csv_import_input.set_value("");
+    wallet_list::load_wallet_list(els).await;
+}
+
+async fn read_selected_file(input: &web_sys::HtmlInputElement) -> Result<String, String> {
+    let file = input
+        .files()
+        .and_then(|files| files.get(0))
+        .ok_or_else(|| "choose a CSV file first".to_string())?;
+    let text = JsFuture::from(file.text())
+        .await
+        .map_err(|e| format!("could not read {}: {:?}", file.name(), e))?;
+    text.dyn_into::<js_sys::JsString>()
+        .map(String::from)
+        .map_err(|_| "file is not text".to_string())
+}
+
+/// Split CSV text into rows of `address[,label]`. Handles quoted fields and
+/// skips blank lines, `#` comments and a leading header row.
+fn parse_csv(text: &str) -> Vec<CsvRow> {
+    let mut rows = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = split_fields(line);
+        let address = fields
+            .first()
+            .map(|f| f.trim().to_string())
+            .unwrap_or_default();
+        if rows.is_empty()
+            && matches!(
+                address.to_ascii_lowercase().as_str(),
+                "address" | "wallet_address" | "wallet"
+            )
+        {
+            continue;
+        }
+        rows.push(CsvRow {
+            line: i + 1,
+            address,
+            label: fields
+                .get(1)
+                .map(|f| f.trim().to_string())
+                .unwrap_or_default(),
+        });
+    }
+    rows
+}
+
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Local checks before anything is sent: empty, malformed and duplicate
+/// addresses are reported without a server round trip.
+fn validate_rows(rows: Vec<CsvRow>) -> (Vec<CsvRow>, Vec<RowFeedback>) {
+    let mut seen = HashSet::new();
+    let mut valid = Vec::new();
+    let mut feedback = Vec::new();
+    for row in rows {
+        let problem = if row.address.is_empty() {
+            Some("address is empty")
+        } else if row.address.chars().any(char::is_whitespace) {
+            Some("address contains whitespace")
+        } else if !seen.insert(row.address.clone()) {
+            Some("duplicate of an earlier row")
+        } else {
+            None
+        };
+        match problem {
+            Some(message) => feedback.push(RowFeedback {
+                line: row.line,
+                address: row.address,
+                ok: false,
+                message: message.to_string(),
+            }),
+            None => valid.push(row),
+        }
+    }
+    (valid, feedback)
+}
+
+fn render_feedback(els: &Elements, feedback: &[RowFeedback]) {
+    let ok = feedback.iter().filter(|f| f.ok).count();
+    let mut text = format!("Imported {} of {} row(s)\n", ok, feedback.len());
+    for f in feedback {
+        text.push_str(&format!(
+            "{} line {}: {} — {}\n",
+            if f.ok { "✓" } else { "✗" },
+            f.line,
+            if f.address.is_empty() {
+                "(blank)"
+            } else {
+                &f.address
+            },
+            f.message,
+        ));
+    }
+    dom::toggle_class(&els.create_result, "error", ok == 0);
+    dom::set_text(&els.create_result, text.trim_end());
+}
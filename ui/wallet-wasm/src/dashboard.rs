@@ -0,0 +1,321 @@
+//! Multi-wallet dashboard.
+//!
+//! Summarises the wallets assigned to the active profile: per-asset totals
+//! and per-wallet balances from `POST /wallet/balances`, recent transfers
+//! from `POST /wallet/activity`, and a sparkline of each wallet's balance
+//! snapshots. Snapshots are recorded on every refresh and kept in
+//! localStorage, so the sparklines only cover what this browser has seen.
+//! The full transfer history downloads as CSV from
+//! `GET /wallet/activity/export`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api;
+use crate::dom::{self, Elements};
+use crate::profile;
+use crate::state;
+
+const SNAPSHOT_KEY: &str = "kc_balance_snapshots";
+const MAX_SNAPSHOTS: usize = 30;
+const ACTIVITY_LIMIT: usize = 20;
+
+const SPARK_WIDTH: f64 = 120.0;
+const SPARK_HEIGHT: f64 = 28.0;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Snapshot {
+    at: f64,
+    amount: f64,
+}
+
+/// Snapshot series keyed by `"{wallet_address}|{asset}"`.
+type SnapshotMap = HashMap<String, Vec<Snapshot>>;
+
+/// Refresh the dashboard for the active profile.
+pub async fn on_refresh_dashboard(els: &Elements) {
+    let active_profile = state::active_profile().unwrap_or_default();
+    let (assigned, _) = profile::get_wallets_for_profile(&active_profile);
+    if assigned.is_empty() {
+        dom::set_inner_html(&els.dashboard_totals, "");
+        dom::set_inner_html(&els.dashboard_wallets, "");
+        dom::set_inner_html(&els.dashboard_activity, "");
+        api::set_result_error(
+            &els.dashboard_result,
+            "no wallets assigned to this profile; assign some from the Wallets tab",
+        );
+        return;
+    }
+    dom::set_text(&els.dashboard_result, "Loading…");
+    dom::remove_class(&els.dashboard_result, "error");
+
+    let addresses: Vec<String> = assigned.iter().map(|w| w.wallet_address.clone()).collect();
+    let balances_body = serde_json::json!({ "wallet_addresses": addresses });
+    let activity_body = serde_json::json!({
+        "wallet_addresses": addresses,
+        "limit": ACTIVITY_LIMIT,
+    });
+
+    let balances =
+        match api::request("/wallet/balances", "POST", Some(balances_body.to_string())).await {
+            Ok(result) => result,
+            Err(e) => {
+                api::set_result_error(&els.dashboard_result, &e);
+                return;
+            }
+        };
+
+    let decimals = asset_decimals(&balances);
+    let snapshots = record_snapshots(&balances, &decimals);
+    render_totals(els, &balances);
+    render_wallets(els, &assigned, &balances, &decimals, &snapshots);
+
+    match api::request("/wallet/activity", "POST", Some(activity_body.to_string())).await {
+        Ok(result) => {
+            render_activity(els, &result, &decimals);
+            dom::set_text(
+                &els.dashboard_result,
+                &format!(
+                    "{} wallet(s) in {}",
+                    assigned.len(),
+                    profile::get_profile_name(&active_profile)
+                ),
+            );
+        }
+        Err(e) => api::set_result_error(&els.dashboard_result, &e),
+    }
+}
+
+/// Download the transfer history of the active profile's wallets as CSV.
+pub async fn on_export_activity(els: &Elements) {
+    let active_profile = state::active_profile().unwrap_or_default();
+    let (assigned, _) = profile::get_wallets_for_profile(&active_profile);
+    if assigned.is_empty() {
+        api::set_result_error(&els.dashboard_result, "no wallets to export");
+        return;
+    }
+    let addresses: Vec<String> = assigned.iter().map(|w| w.wallet_address.clone()).collect();
+    let path = format!(
+        "/wallet/activity/export?wallet_addresses={}",
+        js_sys::encode_uri_component(&addresses.join(","))
+    );
+
+    match api::download(&path, &[], "keycortex-activity.csv").await {
+        Ok(filename) => {
+            dom::remove_class(&els.dashboard_result, "error");
+            dom::set_text(&els.dashboard_result, &format!("Downloaded {}", filename));
+        }
+        Err(e) => api::set_result_error(&els.dashboard_result, &e),
+    }
+}
+
+fn asset_decimals(balances: &serde_json::Value) -> HashMap<String, u32> {
+    balances["totals"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| {
+            let asset = t["asset"].as_str()?;
+            let decimals = t["decimals"].as_u64()?;
+            Some((asset.to_string(), decimals as u32))
+        })
+        .collect()
+}
+
+/// Render a base-unit integer string with `decimals` fractional digits,
+/// trimming trailing zeros.
+fn format_units(amount: &str, decimals: u32) -> String {
+    let decimals = decimals as usize;
+    if decimals == 0 || !amount.bytes().all(|b| b.is_ascii_digit()) {
+        return amount.to_string();
+    }
+    let padded = format!("{:0>width$}", amount, width = decimals + 1);
+    let (whole, frac) = padded.split_at(padded.len() - decimals);
+    let frac = frac.trim_end_matches('0');
+    if frac.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, frac)
+    }
+}
+
+fn to_f64(amount: &str, decimals: u32) -> f64 {
+    format_units(amount, decimals).parse().unwrap_or(0.0)
+}
+
+fn load_snapshots() -> SnapshotMap {
+    state::local_get(SNAPSHOT_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Append this refresh's balances to the stored series and return them.
+fn record_snapshots(balances: &serde_json::Value, decimals: &HashMap<String, u32>) -> SnapshotMap {
+    let mut map = load_snapshots();
+    let now = js_sys::Date::now();
+    for entry in balances["balances"].as_array().into_iter().flatten() {
+        let (Some(addr), Some(asset), Some(amount)) = (
+            entry["wallet_address"].as_str(),
+            entry["asset"].as_str(),
+            entry["amount"].as_str(),
+        ) else {
+            continue;
+        };
+        let series = map.entry(format!("{}|{}", addr, asset)).or_default();
+        series.push(Snapshot {
+            at: now,
+            amount: to_f64(amount, decimals.get(asset).copied().unwrap_or(0)),
+        });
+        if series.len() > MAX_SNAPSHOTS {
+            series.drain(..series.len() - MAX_SNAPSHOTS);
+        }
+    }
+    if let Ok(raw) = serde_json::to_string(&map) {
+        state::local_set(SNAPSHOT_KEY, &raw);
+    }
+    map
+}
+
+/// Inline SVG polyline scaled to the series' own min/max.
+fn sparkline(series: &[Snapshot]) -> String {
+    if series.len() < 2 {
+        return r#"<span class="dash-spark dash-spark--empty">refresh again to chart</span>"#
+            .to_string();
+    }
+    let min = series
+        .iter()
+        .map(|s| s.amount)
+        .fold(f64::INFINITY, f64::min);
+    let max = series
+        .iter()
+        .map(|s| s.amount)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let span = if max > min { max - min } else { 1.0 };
+    let step = SPARK_WIDTH / (series.len() - 1) as f64;
+    let points: Vec<String> = series
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let x = i as f64 * step;
+            let y = SPARK_HEIGHT - 2.0 - (s.amount - min) / span * (SPARK_HEIGHT - 4.0);
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect();
+    format!(
+        r#"<svg class="dash-spark" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><polyline fill="none" stroke="currentColor" stroke-width="1.5" points="{}"/></svg>"#,
+        points.join(" "),
+        w = SPARK_WIDTH,
+        h = SPARK_HEIGHT,
+    )
+}
+
+fn render_totals(els: &Elements, balances: &serde_json::Value) {
+    let mut html = String::new();
+    for total in balances["totals"].as_array().into_iter().flatten() {
+        let asset = total["asset"].as_str().unwrap_or_default();
+        let amount = total["amount"].as_str().unwrap_or("0");
+        let decimals = total["decimals"].as_u64().unwrap_or(0) as u32;
+        html.push_str(&format!(
+            r#"<div class="dash-total"><span class="dash-total-asset">{}</span><span class="dash-total-amount">{}</span></div>"#,
+            asset,
+            format_units(amount, decimals),
+        ));
+    }
+    dom::set_inner_html(&els.dashboard_totals, &html);
+}
+
+fn render_wallets(
+    els: &Elements,
+    wallets: &[state::WalletInfo],
+    balances: &serde_json::Value,
+    decimals: &HashMap<String, u32>,
+    snapshots: &SnapshotMap,
+) {
+    let entries: Vec<&serde_json::Value> = balances["balances"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut html = String::new();
+    for w in wallets {
+        let name = match &w.label {
+            Some(l) if !l.is_empty() => l.clone(),
+            _ => shorten(&w.wallet_address),
+        };
+        let mut rows = String::new();
+        for entry in entries
+            .iter()
+            .filter(|e| e["wallet_address"].as_str() == Some(w.wallet_address.as_str()))
+        {
+            let asset = entry["asset"].as_str().unwrap_or_default();
+            let value = match (entry["amount"].as_str(), entry["error"].as_str()) {
+                (Some(amount), _) => {
+                    format_units(amount, decimals.get(asset).copied().unwrap_or(0))
+                }
+                (None, Some(err)) => format!(
+                    r#"<span class="dash-error" title="{}">unavailable</span>"#,
+                    err
+                ),
+                (None, None) => "—".to_string(),
+            };
+            let series = snapshots
+                .get(&format!("{}|{}", w.wallet_address, asset))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            rows.push_str(&format!(
+                r#"<div class="dash-asset-row"><span class="dash-asset">{}</span><span class="dash-amount">{}</span>{}</div>"#,
+                asset,
+                value,
+                sparkline(series),
+            ));
+        }
+        html.push_str(&format!(
+            r#"<div class="dash-wallet"><div class="dash-wallet-name" title="{}">{}</div>{}</div>"#,
+            w.wallet_address, name, rows,
+        ));
+    }
+    dom::set_inner_html(&els.dashboard_wallets, &html);
+}
+
+fn render_activity(els: &Elements, result: &serde_json::Value, decimals: &HashMap<String, u32>) {
+    let activity: Vec<&serde_json::Value> = result["activity"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .collect();
+    if activity.is_empty() {
+        dom::set_inner_html(
+            &els.dashboard_activity,
+            r#"<div class="dash-activity-empty">No transfers yet.</div>"#,
+        );
+        return;
+    }
+    let mut html = String::new();
+    for tx in activity {
+        let at = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(
+            tx["submitted_at_epoch_ms"].as_f64().unwrap_or(0.0),
+        ));
+        let asset = tx["asset"].as_str().unwrap_or_default();
+        let amount = tx["amount"].as_str().unwrap_or_default();
+        html.push_str(&format!(
+            r#"<div class="dash-activity-row" title="{}"><span class="dash-activity-time">{}</span><span>{} → {}</span><span>{} {}</span><span class="dash-status">{}</span></div>"#,
+            tx["tx_hash"].as_str().unwrap_or_default(),
+            String::from(at.to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)),
+            shorten(tx["from"].as_str().unwrap_or_default()),
+            shorten(tx["to"].as_str().unwrap_or_default()),
+            format_units(amount, decimals.get(asset).copied().unwrap_or(0)),
+            asset,
+            tx["status"].as_str().unwrap_or_default(),
+        ));
+    }
+    dom::set_inner_html(&els.dashboard_activity, &html);
+}
+
+fn shorten(s: &str) -> String {
+    if s.len() <= 15 {
+        s.to_string()
+    } else {
+        format!("{}\u{2026}{}", &s[..8], &s[s.len() - 6..])
+    }
+}
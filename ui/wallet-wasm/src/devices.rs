@@ -0,0 +1,128 @@
+//! Trusted-device management.
+//!
+//! Registers this browser's device ID and device key against the AuthBuddy
+//! user from the Connect token, and approves it by signing a fresh challenge
+//! with a bound wallet. Transfers above the trusted-device threshold carry
+//! [`proof_headers`].
+
+use crate::api;
+use crate::dom::{self, Elements};
+use crate::state;
+use kc_api_types::hex::to_hex;
+
+/// POST /auth/devices
+pub async fn on_register_device(els: &Elements) {
+    let auth = api::bearer(&dom::get_input_value(&els.connect_token));
+    let label = dom::get_input_value(&els.device_label);
+
+    let Some(device_key) = state::device_signing_key() else {
+        api::set_result_error(&els.devices_result, "could not create a device key");
+        return;
+    };
+    let body = serde_json::json!({
+        "device_id": state::get_device_id(),
+        "label": if label.is_empty() { None } else { Some(label) },
+        "public_key": to_hex(device_key.verifying_key().as_bytes()),
+    });
+
+    let headers = [("Authorization", auth.as_str())];
+    match api::request_with_headers("/auth/devices", "POST", Some(body.to_string()), &headers).await
+    {
+        Ok(result) => api::set_result(&els.devices_result, &result),
+        Err(e) => api::set_result_error(&els.devices_result, &e),
+    }
+}
+
+/// GET /auth/devices
+pub async fn on_list_devices(els: &Elements) {
+    let auth = api::bearer(&dom::get_input_value(&els.connect_token));
+    let headers = [("Authorization", auth.as_str())];
+    match api::request_with_headers("/auth/devices", "GET", None, &headers).await {
+        Ok(result) => api::set_result(&els.devices_result, &result),
+        Err(e) => api::set_result_error(&els.devices_result, &e),
+    }
+}
+
+/// Challenge → sign → POST /auth/devices/:id/approve
+pub async fn on_approve_device(els: &Elements) {
+    let addr = dom::get_input_value(&els.connect_wallet_address);
+    if addr.is_empty() {
+        api::set_result_error(&els.devices_result, "bound wallet address required");
+        return;
+    }
+    let auth = api::bearer(&dom::get_input_value(&els.connect_token));
+    let device_id = state::get_device_id();
+
+    let challenge = match api::request("/auth/challenge", "POST", None).await {
+        Ok(r) => r
+            .get("challenge")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        Err(e) => {
+            api::set_result_error(&els.devices_result, &e);
+            return;
+        }
+    };
+
+    let sign_body = serde_json::json!({
+        "wallet_address": addr,
+        "payload": api::to_base64(&format!("{}:device-approve:{}", challenge, device_id)),
+        "purpose": "auth",
+    });
+    let signature = match api::request("/wallet/sign", "POST", Some(sign_body.to_string())).await
+    {
+        Ok(r) => r
+            .get("signature")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        Err(e) => {
+            api::set_result_error(&els.devices_result, &e);
+            return;
+        }
+    };
+
+    let body = serde_json::json!({
+        "wallet_address": addr,
+        "challenge": challenge,
+        "signature": signature,
+    });
+    let path = format!(
+        "/auth/devices/{}/approve",
+        js_sys::encode_uri_component(&device_id)
+    );
+    let headers = [("Authorization", auth.as_str())];
+    match api::request_with_headers(&path, "POST", Some(body.to_string()), &headers).await {
+        Ok(result) => api::set_result(&els.devices_result, &result),
+        Err(e) => api::set_result_error(&els.devices_result, &e),
+    }
+}
+
+/// Headers vouching that a transfer of `amount` from `from` to `to` comes
+/// from this device: its ID, the Connect token, and the device key's
+/// signature (purpose `auth`) over
+/// `device-proof:{device_id}:{from}:{to}:{amount}:{timestamp}`.
+pub async fn proof_headers(els: &Elements, from: &str, to: &str, amount: &str) -> Vec<(&'static str, String)> {
+    let device_id = state::get_device_id();
+    let mut headers = vec![("X-Device-Id", device_id.clone())];
+    let auth = api::bearer(&dom::get_input_value(&els.connect_token));
+    if !auth.is_empty() {
+        headers.push(("Authorization", auth));
+    }
+    let Some(device_key) = state::device_signing_key() else {
+        return headers;
+    };
+    let auth_tag = api::request("/chain/config", "GET", None)
+        .await
+        .ok()
+        .and_then(|config| config["domains"]["auth_domain_tag"].as_str().map(str::to_owned))
+        .unwrap_or_else(|| "keycortex:v1:auth".to_owned());
+    let timestamp = js_sys::Date::now() as u64;
+    let signing_input =
+        format!("{auth_tag}:device-proof:{device_id}:{from}:{to}:{amount}:{timestamp}");
+    let signature = ed25519_dalek::Signer::sign(&device_key, signing_input.as_bytes());
+    headers.push(("X-Device-Timestamp", timestamp.to_string()));
+    headers.push(("X-Device-Signature", to_hex(&signature.to_bytes())));
+    headers
+}
@@ -0,0 +1,263 @@
+//! Startup self-test and the diagnostics view.
+//!
+//! `init()` runs [`run`] once the UI is up and renders the report into the
+//! Diagnostics panel, opened from the button in the settings header. A
+//! failing check opens the panel straight away, so "the wallet won't load"
+//! reports come with the reason instead of a blank widget. When the panel
+//! itself is missing from the page the report is shown in a plain box at
+//! the end of `<body>`. `keycortexSelfTest()` in the browser console
+//! returns the same report as JSON.
+//! Extend by adding a check function and calling it from [`run`].
+
+use crate::api;
+use crate::dom::{self, Elements};
+use crate::fold;
+use crate::state;
+use crate::theme;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Where the fallback report goes when the diagnostics panel is missing.
+const FALLBACK_ID: &str = "kcSelfTestFallback";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// Degraded but usable, e.g. default icons instead of the manifest's.
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        CheckResult {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SelfTestReport {
+    pub checks: Vec<CheckResult>,
+    pub api_base_url: String,
+    pub page_url: String,
+    pub user_agent: String,
+}
+
+impl SelfTestReport {
+    pub fn failed(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// One line for the settings header, e.g. `4/5 passed · 1 failed`.
+    pub fn summary(&self) -> String {
+        let count = |status| self.checks.iter().filter(|c| c.status == status).count();
+        let mut summary = format!("{}/{} passed", count(CheckStatus::Pass), self.checks.len());
+        for status in [CheckStatus::Warn, CheckStatus::Fail] {
+            let n = count(status);
+            if n > 0 {
+                let word = if status == CheckStatus::Warn { "warning" } else { "failed" };
+                summary.push_str(&format!(" · {} {}", n, word));
+            }
+        }
+        summary
+    }
+}
+
+/// Run every check. Only the backend check touches the network.
+pub async fn run() -> SelfTestReport {
+    let window = dom::window();
+    SelfTestReport {
+        checks: vec![
+            check_dom_bindings(),
+            check_backend().await,
+            check_themes().await,
+            check_icon_manifest(),
+            check_local_storage(),
+        ],
+        api_base_url: api::base_url(),
+        page_url: window.location().href().unwrap_or_default(),
+        user_agent: window.navigator().user_agent().unwrap_or_default(),
+    }
+}
+
+fn check_dom_bindings() -> CheckResult {
+    let missing = state::missing_bindings();
+    if missing.is_empty() {
+        CheckResult::new("DOM bindings", CheckStatus::Pass, "all elements found")
+    } else {
+        CheckResult::new(
+            "DOM bindings",
+            CheckStatus::Fail,
+            format!(
+                "{} missing from index.html (stale or partial deploy?): {}",
+                missing.len(),
+                missing.join(", ")
+            ),
+        )
+    }
+}
+
+async fn check_backend() -> CheckResult {
+    match api::request("/health", "GET", None).await {
+        Ok(body) => {
+            let status = body["status"].as_str().unwrap_or("ok");
+            CheckResult::new(
+                "Backend reachable",
+                CheckStatus::Pass,
+                format!("{}/health: {}", api::base_url(), status),
+            )
+        }
+        Err(e) => CheckResult::new("Backend reachable", CheckStatus::Fail, e),
+    }
+}
+
+async fn check_themes() -> CheckResult {
+    let themes = match state::themes() {
+        Some(t) => Some(t),
+        None => theme::load_themes().await,
+    };
+    match themes {
+        Some(t) if t.contains_key("classic") => {
+            CheckResult::new("Themes loaded", CheckStatus::Pass, format!("{} skins", t.len()))
+        }
+        Some(_) => CheckResult::new(
+            "Themes loaded",
+            CheckStatus::Warn,
+            "themes.json has no \"classic\" skin to fall back to",
+        ),
+        None => CheckResult::new(
+            "Themes loaded",
+            CheckStatus::Fail,
+            "wallet-baseline/themes.json could not be fetched or parsed; skins are unstyled",
+        ),
+    }
+}
+
+fn check_icon_manifest() -> CheckResult {
+    match state::manifest() {
+        Some(m) => CheckResult::new(
+            "Icon manifest loaded",
+            CheckStatus::Pass,
+            format!("{} networks, {} coins", m.networks.len(), m.coins.len()),
+        ),
+        None => CheckResult::new(
+            "Icon manifest loaded",
+            CheckStatus::Warn,
+            "config/icon-manifest.json could not be loaded; using default icon paths",
+        ),
+    }
+}
+
+fn check_local_storage() -> CheckResult {
+    match state::check_local_storage() {
+        Ok(()) => CheckResult::new("localStorage writable", CheckStatus::Pass, "probe written and read back"),
+        Err(e) => CheckResult::new(
+            "localStorage writable",
+            CheckStatus::Fail,
+            format!("{}; profiles, wallets and settings will not be remembered", e),
+        ),
+    }
+}
+
+/// Run the self-test and render it. Opens the panel when a check fails.
+pub async fn run_and_render(els: &Elements) {
+    dom::set_text(&els.self_test_summary, "running…");
+    let report = run().await;
+    render(els, &report);
+    if report.failed() {
+        show(els);
+    }
+}
+
+/// Unfold the wallet and switch to the diagnostics panel.
+pub fn show(els: &Elements) {
+    fold::set_wallet_state(els, fold::FoldState::Unfolded);
+    crate::events::set_active_tab(els, "diagnostics");
+}
+
+fn render(els: &Elements, report: &SelfTestReport) {
+    let summary = report.summary();
+    dom::set_text(&els.self_test_summary, &summary);
+    dom::toggle_class(&els.self_test_summary, "error", report.failed());
+
+    if !els.diagnostics_panel.is_connected() || !els.diagnostics_list.is_connected() {
+        render_fallback(report);
+        return;
+    }
+
+    dom::set_inner_html(&els.diagnostics_list, "");
+    for check in &report.checks {
+        let item = dom::create_element("li");
+        item.set_class_name(&format!("diagnostics-check diagnostics-{}", check.status.label().to_lowercase()));
+        let status = dom::create_element("span");
+        status.set_class_name("diagnostics-status");
+        dom::set_text(&status, check.status.label());
+        let name = dom::create_element("strong");
+        dom::set_text(&name, check.name);
+        let detail = dom::create_element("span");
+        detail.set_class_name("diagnostics-detail");
+        dom::set_text(&detail, &check.detail);
+        let _ = item.append_child(&status);
+        let _ = item.append_child(&name);
+        let _ = item.append_child(&detail);
+        let _ = els.diagnostics_list.append_child(&item);
+    }
+    dom::set_text(&els.diagnostics_report, &report_json(report));
+}
+
+/// Plain-text report at the end of `<body>`, for pages too broken to show
+/// the panel.
+fn render_fallback(report: &SelfTestReport) {
+    let doc = dom::document();
+    let Some(body) = doc.body() else {
+        return;
+    };
+    let pre = match dom::by_id(FALLBACK_ID) {
+        Some(el) => el,
+        None => {
+            let el = dom::create_element("pre");
+            el.set_id(FALLBACK_ID);
+            let _ = body.append_child(&el);
+            el
+        }
+    };
+    let mut text = format!("KeyCortex wallet self-test: {}\n\n", report.summary());
+    for check in &report.checks {
+        text.push_str(&format!("[{}] {}: {}\n", check.status.label(), check.name, check.detail));
+    }
+    text.push('\n');
+    text.push_str(&report_json(report));
+    dom::set_text(&pre, &text);
+}
+
+fn report_json(report: &SelfTestReport) -> String {
+    serde_json::to_string_pretty(report).unwrap_or_default()
+}
+
+/// `keycortexSelfTest()` — run the self-test from the browser console.
+#[wasm_bindgen(js_name = keycortexSelfTest)]
+pub async fn self_test() -> Result<JsValue, JsValue> {
+    let report = run().await;
+    serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -1,7 +1,7 @@
 //! DOM element bindings.
 //!
 //! Mirrors the JS `elements` object. All fields are resolved once at startup.
-//! To add new UI elements, add a field here and bind it in `Elements::bind()`.
+//! To add new UI elements, add a field here and bind it in `Elements::bind_lenient()`.
 
 use wasm_bindgen::prelude::*;
 use web_sys::{
@@ -162,6 +162,12 @@ pub struct Elements {
     pub restore_wallet_btn: HtmlElement,
     pub restore_hint: Element,
     pub refresh_wallets_btn: HtmlElement,
+    pub watch_address_input: HtmlInputElement,
+    pub watch_wallet_btn: HtmlElement,
+    pub csv_import_input: HtmlInputElement,
+    pub csv_import_btn: HtmlElement,
+    pub import_public_key_input: HtmlInputElement,
+    pub import_public_btn: HtmlElement,
 
     // Wallet list
     pub wallet_list_container: Element,
@@ -184,6 +190,13 @@ pub struct Elements {
     pub bind_wallet_btn: HtmlElement,
     pub connect_result: Element,
 
+    // Devices
+    pub device_label: HtmlInputElement,
+    pub register_device_btn: HtmlElement,
+    pub list_devices_btn: HtmlElement,
+    pub approve_device_btn: HtmlElement,
+    pub devices_result: Element,
+
     // Balance
     pub balance_wallet_address: HtmlInputElement,
     pub balance_chain: HtmlInputElement,
@@ -191,6 +204,7 @@ pub struct Elements {
     pub balance_network_icon: HtmlImageElement,
     pub balance_coin_icon: HtmlImageElement,
     pub balance_btn: HtmlElement,
+    pub portfolio_btn: HtmlElement,
     pub balance_result: Element,
 
     // Sign
@@ -207,14 +221,27 @@ pub struct Elements {
     pub submit_asset: HtmlSelectElement,
     pub submit_chain: HtmlInputElement,
     pub submit_nonce: HtmlInputElement,
-    pub nonce_btn: HtmlElement,
+    pub submit_memo: HtmlTextAreaElement,
     pub nonce_display: Element,
+    pub transfer_summary: Element,
     pub submit_tx_btn: HtmlElement,
     pub submit_result: Element,
 
+    // Escrow
+    pub escrow_approver: HtmlInputElement,
+    pub escrow_expires: HtmlInputElement,
+    pub create_escrow_btn: HtmlElement,
+    pub escrow_transfer_id: HtmlInputElement,
+    pub escrow_status_btn: HtmlElement,
+    pub approve_escrow_btn: HtmlElement,
+    pub cancel_escrow_btn: HtmlElement,
+    pub escrow_result: Element,
+
     // History
     pub tx_hash: HtmlInputElement,
     pub tx_status_btn: HtmlElement,
+    pub read_memo_btn: HtmlElement,
+    pub tx_detail: Element,
     pub history_result: Element,
 
     // Platform integration
@@ -232,135 +259,227 @@ pub struct Elements {
     pub readyz_btn: HtmlElement,
     pub startupz_btn: HtmlElement,
     pub ops_result: Element,
+
+    // Dashboard
+    pub dashboard_refresh_btn: HtmlElement,
+    pub dashboard_export_btn: HtmlElement,
+    pub dashboard_totals: Element,
+    pub dashboard_wallets: Element,
+    pub dashboard_activity: Element,
+    pub dashboard_result: Element,
+
+    // Approvals
+    pub approvals_refresh_btn: HtmlElement,
+    pub approvals_list: Element,
+    pub approvals_detail: Element,
+    pub approvals_result: Element,
+
+    // Diagnostics
+    pub self_test_summary: Element,
+    pub diagnostics_btn: HtmlElement,
+    pub diagnostics_panel: Element,
+    pub diagnostics_list: Element,
+    pub diagnostics_report: Element,
+    pub diagnostics_rerun_btn: HtmlElement,
+}
+
+/// Record a missing binding and stand in a detached element for it, so the
+/// rest of the UI still starts and the self-test can name what is missing.
+fn placeholder(missing: &mut Vec<String>, what: String, tag: &str) -> Element {
+    missing.push(what);
+    create_element(tag)
 }
 
 macro_rules! get_el {
-    ($id:expr) => {
-        by_id($id).ok_or_else(|| JsValue::from_str(&format!("missing element #{}", $id)))?
+    ($missing:ident, $id:expr) => {
+        by_id($id).unwrap_or_else(|| placeholder(&mut $missing, format!("element #{}", $id), "div"))
     };
 }
 
 macro_rules! get_input {
-    ($id:expr) => {
-        by_id_typed::<HtmlInputElement>($id)
-            .ok_or_else(|| JsValue::from_str(&format!("missing input #{}", $id)))?
+    ($missing:ident, $id:expr) => {
+        by_id_typed::<HtmlInputElement>($id).unwrap_or_else(|| {
+            placeholder(&mut $missing, format!("input #{}", $id), "input").unchecked_into()
+        })
     };
 }
 
 macro_rules! get_select {
-    ($id:expr) => {
-        by_id_typed::<HtmlSelectElement>($id)
-            .ok_or_else(|| JsValue::from_str(&format!("missing select #{}", $id)))?
+    ($missing:ident, $id:expr) => {
+        by_id_typed::<HtmlSelectElement>($id).unwrap_or_else(|| {
+            placeholder(&mut $missing, format!("select #{}", $id), "select").unchecked_into()
+        })
     };
 }
 
 macro_rules! get_textarea {
-    ($id:expr) => {
-        by_id_typed::<HtmlTextAreaElement>($id)
-            .ok_or_else(|| JsValue::from_str(&format!("missing textarea #{}", $id)))?
+    ($missing:ident, $id:expr) => {
+        by_id_typed::<HtmlTextAreaElement>($id).unwrap_or_else(|| {
+            placeholder(&mut $missing, format!("textarea #{}", $id), "textarea").unchecked_into()
+        })
     };
 }
 
 macro_rules! get_img {
-    ($id:expr) => {
-        by_id_typed::<HtmlImageElement>($id)
-            .ok_or_else(|| JsValue::from_str(&format!("missing img #{}", $id)))?
+    ($missing:ident, $id:expr) => {
+        by_id_typed::<HtmlImageElement>($id).unwrap_or_else(|| {
+            placeholder(&mut $missing, format!("img #{}", $id), "img").unchecked_into()
+        })
     };
 }
 
 macro_rules! get_html {
-    ($id:expr) => {
-        by_id_typed::<HtmlElement>($id)
-            .ok_or_else(|| JsValue::from_str(&format!("missing html element #{}", $id)))?
+    ($missing:ident, $id:expr) => {
+        by_id_typed::<HtmlElement>($id).unwrap_or_else(|| {
+            placeholder(&mut $missing, format!("html element #{}", $id), "button").unchecked_into()
+        })
     };
 }
 
 impl Elements {
     /// Resolve all DOM references. Call once after DOMContentLoaded.
+    /// Fails naming every missing element.
     pub fn bind() -> Result<Elements, JsValue> {
-        Ok(Elements {
+        let (els, missing) = Self::bind_lenient();
+        if missing.is_empty() {
+            Ok(els)
+        } else {
+            Err(JsValue::from_str(&format!("missing {}", missing.join(", "))))
+        }
+    }
+
+    /// Like [`Elements::bind`], standing in detached elements for missing
+    /// ones instead of failing. Also returns what was missing.
+    pub fn bind_lenient() -> (Elements, Vec<String>) {
+        let mut missing = Vec::new();
+        let els = Elements {
             wallet_window: query(".wallet-window")
-                .ok_or_else(|| JsValue::from_str("missing .wallet-window"))?,
-            wallet_folded: get_el!("walletFolded"),
-            wallet_fold_toggle: get_el!("walletFoldToggle"),
-            wallet_app: get_el!("walletApp"),
+                .unwrap_or_else(|| placeholder(&mut missing, ".wallet-window".to_string(), "main")),
+            wallet_folded: get_el!(missing, "walletFolded"),
+            wallet_fold_toggle: get_el!(missing, "walletFoldToggle"),
+            wallet_app: get_el!(missing, "walletApp"),
 
-            base_url: get_input!("baseUrl"),
-            skin_select: get_select!("skinSelect"),
-            form_select: get_select!("formSelect"),
-            skin_cycle_btn: get_html!("skinCycleBtn"),
+            base_url: get_input!(missing, "baseUrl"),
+            skin_select: get_select!(missing, "skinSelect"),
+            form_select: get_select!(missing, "formSelect"),
+            skin_cycle_btn: get_html!(missing, "skinCycleBtn"),
 
             tabs: query_all(".tab"),
             panels: query_all(".panel"),
 
-            create_wallet_btn: get_html!("createWalletBtn"),
-            create_result: get_el!("createResult"),
-            wallet_label_input: get_input!("walletLabelInput"),
-            wallet_passphrase_input: get_input!("walletPassphraseInput"),
-            restore_wallet_btn: get_html!("restoreWalletBtn"),
-            restore_hint: get_el!("restoreHint"),
-            refresh_wallets_btn: get_html!("refreshWalletsBtn"),
-
-            wallet_list_container: get_el!("walletListContainer"),
-
-            half_fold_wallet_name: get_el!("halfFoldWalletName"),
-            half_fold_chain: get_el!("halfFoldChain"),
-
-            profile_select: get_select!("profileSelect"),
-            add_profile_btn: get_html!("addProfileBtn"),
-            active_wallet_select: get_select!("activeWalletSelect"),
-
-            connect_wallet_address: get_input!("connectWalletAddress"),
-            connect_chain: get_input!("connectChain"),
-            connect_token: get_input!("connectToken"),
-            challenge_btn: get_html!("challengeBtn"),
-            verify_btn: get_html!("verifyBtn"),
-            bind_wallet_btn: get_html!("bindWalletBtn"),
-            connect_result: get_el!("connectResult"),
-
-            balance_wallet_address: get_input!("balanceWalletAddress"),
-            balance_chain: get_input!("balanceChain"),
-            balance_asset: get_select!("balanceAsset"),
-            balance_network_icon: get_img!("balanceNetworkIcon"),
-            balance_coin_icon: get_img!("balanceCoinIcon"),
-            balance_btn: get_html!("balanceBtn"),
-            balance_result: get_el!("balanceResult"),
-
-            sign_wallet_address: get_input!("signWalletAddress"),
-            sign_purpose: get_select!("signPurpose"),
-            sign_payload: get_textarea!("signPayload"),
-            sign_btn: get_html!("signBtn"),
-            sign_result: get_el!("signResult"),
-
-            submit_from: get_input!("submitFrom"),
-            submit_to: get_input!("submitTo"),
-            submit_amount: get_input!("submitAmount"),
-            submit_asset: get_select!("submitAsset"),
-            submit_chain: get_input!("submitChain"),
-            submit_nonce: get_input!("submitNonce"),
-            nonce_btn: get_html!("nonceBtn"),
-            nonce_display: get_el!("nonceDisplay"),
-            submit_tx_btn: get_html!("submitTxBtn"),
-            submit_result: get_el!("submitResult"),
-
-            tx_hash: get_input!("txHash"),
-            tx_status_btn: get_html!("txStatusBtn"),
-            history_result: get_el!("historyResult"),
-
-            chain_config_btn: get_html!("chainConfigBtn"),
-            chain_config_result: get_el!("chainConfigResult"),
-            fd_wallet_address: get_input!("fdWalletAddress"),
-            wallet_status_btn: get_html!("walletStatusBtn"),
-            wallet_status_result: get_el!("walletStatusResult"),
-            pc_wallet_address: get_input!("pcWalletAddress"),
-            pc_challenge: get_input!("pcChallenge"),
-            pc_tx_hash: get_input!("pcTxHash"),
-            commitment_btn: get_html!("commitmentBtn"),
-            commitment_result: get_el!("commitmentResult"),
-            health_btn: get_html!("healthBtn"),
-            readyz_btn: get_html!("readyzBtn"),
-            startupz_btn: get_html!("startupzBtn"),
-            ops_result: get_el!("opsResult"),
-        })
+            create_wallet_btn: get_html!(missing, "createWalletBtn"),
+            create_result: get_el!(missing, "createResult"),
+            wallet_label_input: get_input!(missing, "walletLabelInput"),
+            wallet_passphrase_input: get_input!(missing, "walletPassphraseInput"),
+            restore_wallet_btn: get_html!(missing, "restoreWalletBtn"),
+            restore_hint: get_el!(missing, "restoreHint"),
+            refresh_wallets_btn: get_html!(missing, "refreshWalletsBtn"),
+            watch_address_input: get_input!(missing, "watchAddressInput"),
+            watch_wallet_btn: get_html!(missing, "watchWalletBtn"),
+            csv_import_input: get_input!(missing, "csvImportInput"),
+            csv_import_btn: get_html!(missing, "csvImportBtn"),
+            import_public_key_input: get_input!(missing, "importPublicKeyInput"),
+            import_public_btn: get_html!(missing, "importPublicBtn"),
+
+            wallet_list_container: get_el!(missing, "walletListContainer"),
+
+            half_fold_wallet_name: get_el!(missing, "halfFoldWalletName"),
+            half_fold_chain: get_el!(missing, "halfFoldChain"),
+
+            profile_select: get_select!(missing, "profileSelect"),
+            add_profile_btn: get_html!(missing, "addProfileBtn"),
+            active_wallet_select: get_select!(missing, "activeWalletSelect"),
+
+            connect_wallet_address: get_input!(missing, "connectWalletAddress"),
+            connect_chain: get_input!(missing, "connectChain"),
+            connect_token: get_input!(missing, "connectToken"),
+            challenge_btn: get_html!(missing, "challengeBtn"),
+            verify_btn: get_html!(missing, "verifyBtn"),
+            bind_wallet_btn: get_html!(missing, "bindWalletBtn"),
+            connect_result: get_el!(missing, "connectResult"),
+
+            device_label: get_input!(missing, "deviceLabel"),
+            register_device_btn: get_html!(missing, "registerDeviceBtn"),
+            list_devices_btn: get_html!(missing, "listDevicesBtn"),
+            approve_device_btn: get_html!(missing, "approveDeviceBtn"),
+            devices_result: get_el!(missing, "devicesResult"),
+
+            balance_wallet_address: get_input!(missing, "balanceWalletAddress"),
+            balance_chain: get_input!(missing, "balanceChain"),
+            balance_asset: get_select!(missing, "balanceAsset"),
+            balance_network_icon: get_img!(missing, "balanceNetworkIcon"),
+            balance_coin_icon: get_img!(missing, "balanceCoinIcon"),
+            balance_btn: get_html!(missing, "balanceBtn"),
+            portfolio_btn: get_html!(missing, "portfolioBtn"),
+            balance_result: get_el!(missing, "balanceResult"),
+
+            sign_wallet_address: get_input!(missing, "signWalletAddress"),
+            sign_purpose: get_select!(missing, "signPurpose"),
+            sign_payload: get_textarea!(missing, "signPayload"),
+            sign_btn: get_html!(missing, "signBtn"),
+            sign_result: get_el!(missing, "signResult"),
+
+            submit_from: get_input!(missing, "submitFrom"),
+            submit_to: get_input!(missing, "submitTo"),
+            submit_amount: get_input!(missing, "submitAmount"),
+            submit_asset: get_select!(missing, "submitAsset"),
+            submit_chain: get_input!(missing, "submitChain"),
+            submit_nonce: get_input!(missing, "submitNonce"),
+            submit_memo: get_textarea!(missing, "submitMemo"),
+            nonce_display: get_el!(missing, "nonceDisplay"),
+            transfer_summary: get_el!(missing, "transferSummary"),
+            submit_tx_btn: get_html!(missing, "submitTxBtn"),
+            submit_result: get_el!(missing, "submitResult"),
+
+            escrow_approver: get_input!(missing, "escrowApprover"),
+            escrow_expires: get_input!(missing, "escrowExpires"),
+            create_escrow_btn: get_html!(missing, "createEscrowBtn"),
+            escrow_transfer_id: get_input!(missing, "escrowTransferId"),
+            escrow_status_btn: get_html!(missing, "escrowStatusBtn"),
+            approve_escrow_btn: get_html!(missing, "approveEscrowBtn"),
+            cancel_escrow_btn: get_html!(missing, "cancelEscrowBtn"),
+            escrow_result: get_el!(missing, "escrowResult"),
+
+            tx_hash: get_input!(missing, "txHash"),
+            tx_status_btn: get_html!(missing, "txStatusBtn"),
+            read_memo_btn: get_html!(missing, "readMemoBtn"),
+            tx_detail: get_el!(missing, "txDetail"),
+            history_result: get_el!(missing, "historyResult"),
+
+            chain_config_btn: get_html!(missing, "chainConfigBtn"),
+            chain_config_result: get_el!(missing, "chainConfigResult"),
+            fd_wallet_address: get_input!(missing, "fdWalletAddress"),
+            wallet_status_btn: get_html!(missing, "walletStatusBtn"),
+            wallet_status_result: get_el!(missing, "walletStatusResult"),
+            pc_wallet_address: get_input!(missing, "pcWalletAddress"),
+            pc_challenge: get_input!(missing, "pcChallenge"),
+            pc_tx_hash: get_input!(missing, "pcTxHash"),
+            commitment_btn: get_html!(missing, "commitmentBtn"),
+            commitment_result: get_el!(missing, "commitmentResult"),
+            health_btn: get_html!(missing, "healthBtn"),
+            readyz_btn: get_html!(missing, "readyzBtn"),
+            startupz_btn: get_html!(missing, "startupzBtn"),
+            ops_result: get_el!(missing, "opsResult"),
+
+            dashboard_refresh_btn: get_html!(missing, "dashboardRefreshBtn"),
+            dashboard_export_btn: get_html!(missing, "dashboardExportBtn"),
+            dashboard_totals: get_el!(missing, "dashboardTotals"),
+            dashboard_wallets: get_el!(missing, "dashboardWallets"),
+            dashboard_activity: get_el!(missing, "dashboardActivity"),
+            dashboard_result: get_el!(missing, "dashboardResult"),
+
+            approvals_refresh_btn: get_html!(missing, "approvalsRefreshBtn"),
+            approvals_list: get_el!(missing, "approvalsList"),
+            approvals_detail: get_el!(missing, "approvalsDetail"),
+            approvals_result: get_el!(missing, "approvalsResult"),
+
+            self_test_summary: get_el!(missing, "selfTestSummary"),
+            diagnostics_btn: get_html!(missing, "diagnosticsBtn"),
+            diagnostics_panel: get_el!(missing, "diagnostics"),
+            diagnostics_list: get_el!(missing, "diagnosticsList"),
+            diagnostics_report: get_el!(missing, "diagnosticsReport"),
+            diagnostics_rerun_btn: get_html!(missing, "diagnosticsRerunBtn"),
+        };
+        (els, missing)
     }
 }
@@ -0,0 +1,152 @@
+//! Escrow-style conditional transfers.
+//!
+//! Creates a held transfer from the Transfer form, then releases it by signing
+//! the server-issued digest with the approver wallet, or cancels it from the
+//! sender wallet.
+
+use crate::api;
+use crate::dom::{self, Elements};
+
+fn escrow_path(els: &Elements) -> Option<String> {
+    let transfer_id = dom::get_input_value(&els.escrow_transfer_id);
+    if transfer_id.is_empty() {
+        api::set_result_error(&els.escrow_result, "transfer id required");
+        return None;
+    }
+    Some(format!(
+        "/wallet/escrow/{}",
+        js_sys::encode_uri_component(&transfer_id)
+    ))
+}
+
+/// Sign `payload` with `wallet_address` (purpose `transaction`).
+async fn sign(wallet_address: &str, payload: &str) -> Result<String, String> {
+    let body = serde_json::json!({
+        "wallet_address": wallet_address,
+        "payload": api::to_base64(payload),
+        "purpose": "transaction",
+    });
+    let result = api::request("/wallet/sign", "POST", Some(body.to_string())).await?;
+    Ok(result
+        .get("signature")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Fetch the transfer digest for signing.
+async fn fetch_digest(path: &str) -> Result<String, String> {
+    let result = api::request(path, "GET", None).await?;
+    Ok(result
+        .get("digest")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// POST /wallet/escrow
+pub async fn on_create_escrow(els: &Elements) {
+    let chain_val = dom::get_input_value(&els.submit_chain);
+    let expires: Option<u64> = dom::get_input_value(&els.escrow_expires).parse().ok();
+    let body = serde_json::json!({
+        "from": dom::get_input_value(&els.submit_from),
+        "to": dom::get_input_value(&els.submit_to),
+        "amount": dom::get_input_value(&els.submit_amount),
+        "asset": dom::get_select_value(&els.submit_asset),
+        "chain": if chain_val.is_empty() { "flowcortex-l1".to_string() } else { chain_val },
+        "approver_wallet": dom::get_input_value(&els.escrow_approver),
+        "expires_in_seconds": expires,
+    });
+
+    let proof = crate::devices::proof_headers(
+        els,
+        body["from"].as_str().unwrap_or_default(),
+        body["to"].as_str().unwrap_or_default(),
+        body["amount"].as_str().unwrap_or_default(),
+    )
+    .await;
+    let headers: Vec<(&str, &str)> = proof.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    match api::request_with_headers("/wallet/escrow", "POST", Some(body.to_string()), &headers).await {
+        Ok(result) => {
+            api::set_result(&els.escrow_result, &result);
+            if let Some(id) = result.get("transfer_id").and_then(|v| v.as_str()) {
+                els.escrow_transfer_id.set_value(id);
+                els.tx_hash.set_value(id);
+            }
+        }
+        Err(e) => api::set_result_error(&els.escrow_result, &e),
+    }
+}
+
+/// GET /wallet/escrow/:id
+pub async fn on_escrow_status(els: &Elements) {
+    let Some(path) = escrow_path(els) else {
+        return;
+    };
+    match api::request(&path, "GET", None).await {
+        Ok(result) => api::set_result(&els.escrow_result, &result),
+        Err(e) => api::set_result_error(&els.escrow_result, &e),
+    }
+}
+
+/// Sign digest with approver → POST /wallet/escrow/:id/approve
+pub async fn on_approve_escrow(els: &Elements) {
+    let Some(status_path) = escrow_path(els) else {
+        return;
+    };
+    let approver = dom::get_input_value(&els.escrow_approver);
+    if approver.is_empty() {
+        api::set_result_error(&els.escrow_result, "approver wallet required");
+        return;
+    }
+
+    let signature = match fetch_digest(&status_path).await {
+        Ok(digest) => sign(&approver, &digest).await,
+        Err(e) => Err(e),
+    };
+    let signature = match signature {
+        Ok(s) => s,
+        Err(e) => {
+            api::set_result_error(&els.escrow_result, &e);
+            return;
+        }
+    };
+
+    let body = serde_json::json!({ "signature": signature });
+    let path = format!("{status_path}/approve");
+    match api::request(&path, "POST", Some(body.to_string())).await {
+        Ok(result) => api::set_result(&els.escrow_result, &result),
+        Err(e) => api::set_result_error(&els.escrow_result, &e),
+    }
+}
+
+/// Sign `cancel:{digest}` with sender → POST /wallet/escrow/:id/cancel
+pub async fn on_cancel_escrow(els: &Elements) {
+    let Some(status_path) = escrow_path(els) else {
+        return;
+    };
+    let sender = dom::get_input_value(&els.submit_from);
+    if sender.is_empty() {
+        api::set_result_error(&els.escrow_result, "from wallet required");
+        return;
+    }
+
+    let signature = match fetch_digest(&status_path).await {
+        Ok(digest) => sign(&sender, &format!("cancel:{digest}")).await,
+        Err(e) => Err(e),
+    };
+    let signature = match signature {
+        Ok(s) => s,
+        Err(e) => {
+            api::set_result_error(&els.escrow_result, &e);
+            return;
+        }
+    };
+
+    let body = serde_json::json!({ "wallet_address": sender, "signature": signature });
+    let path = format!("{status_path}/cancel");
+    match api::request(&path, "POST", Some(body.to_string())).await {
+        Ok(result) => api::set_result(&els.escrow_result, &result),
+        Err(e) => api::set_result_error(&els.escrow_result, &e),
+    }
+}
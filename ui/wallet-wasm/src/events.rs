@@ -4,13 +4,21 @@
 //! To add new events, add closures here and (if async) spawn via
 //! `wasm_bindgen_futures::spawn_local`.
 
+use crate::approvals;
+use crate::csv_import;
+use crate::dashboard;
+use crate::devices;
+use crate::diagnostics;
 use crate::dom::{self, Elements};
+use crate::escrow;
 use crate::fold;
 use crate::icons;
 use crate::platform;
 use crate::profile;
 use crate::state;
 use crate::theme;
+use crate::transfer_form;
+use crate::tx_detail;
 use crate::wallet_list;
 use crate::wallet_ops;
 use wasm_bindgen::prelude::*;
@@ -60,6 +68,9 @@ pub fn bind_events(els: &Elements) {
     on_click_async!(els.create_wallet_btn, els, wallet_ops::on_create_wallet);
     on_click_async!(els.refresh_wallets_btn, els, wallet_list::load_wallet_list);
     on_click_async!(els.restore_wallet_btn, els, wallet_ops::on_restore_wallet);
+    on_click_async!(els.watch_wallet_btn, els, wallet_ops::on_watch_wallet);
+    on_click_async!(els.csv_import_btn, els, csv_import::on_csv_import);
+    on_click_async!(els.import_public_btn, els, wallet_ops::on_import_public_key);
 
     // ── Profile ──
     {
@@ -102,18 +113,31 @@ pub fn bind_events(els: &Elements) {
     on_click_async!(els.verify_btn, els, wallet_ops::on_verify);
     on_click_async!(els.bind_wallet_btn, els, wallet_ops::on_bind_wallet);
 
+    // ── Devices ──
+    on_click_async!(els.register_device_btn, els, devices::on_register_device);
+    on_click_async!(els.list_devices_btn, els, devices::on_list_devices);
+    on_click_async!(els.approve_device_btn, els, devices::on_approve_device);
+
     // ── Balance ──
     on_click_async!(els.balance_btn, els, wallet_ops::on_fetch_balance);
+    on_click_async!(els.portfolio_btn, els, wallet_ops::on_fetch_portfolio);
 
     // ── Sign ──
     on_click_async!(els.sign_btn, els, wallet_ops::on_sign_payload);
 
     // ── Transfer ──
-    on_click_async!(els.nonce_btn, els, wallet_ops::on_fetch_nonce);
+    transfer_form::bind(els);
     on_click_async!(els.submit_tx_btn, els, wallet_ops::on_submit_tx);
 
+    // ── Escrow ──
+    on_click_async!(els.create_escrow_btn, els, escrow::on_create_escrow);
+    on_click_async!(els.escrow_status_btn, els, escrow::on_escrow_status);
+    on_click_async!(els.approve_escrow_btn, els, escrow::on_approve_escrow);
+    on_click_async!(els.cancel_escrow_btn, els, escrow::on_cancel_escrow);
+
     // ── History ──
-    on_click_async!(els.tx_status_btn, els, wallet_ops::on_fetch_tx_status);
+    on_click_async!(els.tx_status_btn, els, tx_detail::on_fetch_tx_status);
+    on_click_async!(els.read_memo_btn, els, tx_detail::on_read_memo);
 
     // ── Platform ──
     on_click_async!(els.chain_config_btn, els, platform::on_chain_config);
@@ -123,6 +147,22 @@ pub fn bind_events(els: &Elements) {
     on_click_async!(els.readyz_btn, els, platform::on_ops_readyz);
     on_click_async!(els.startupz_btn, els, platform::on_ops_startupz);
 
+    // ── Dashboard ──
+    on_click_async!(els.dashboard_refresh_btn, els, dashboard::on_refresh_dashboard);
+    on_click_async!(els.dashboard_export_btn, els, dashboard::on_export_activity);
+
+    // ── Approvals ──
+    on_click_async!(els.approvals_refresh_btn, els, approvals::on_refresh_approvals);
+
+    // ── Diagnostics ──
+    {
+        let els2 = els.clone();
+        on_click!(els.diagnostics_btn, move |_: web_sys::MouseEvent| {
+            diagnostics::show(&els2);
+        });
+    }
+    on_click_async!(els.diagnostics_rerun_btn, els, diagnostics::run_and_render);
+
     // ── Balance icons ──
     {
         let els2 = els.clone();
@@ -233,7 +273,7 @@ pub fn bind_events(els: &Elements) {
 }
 
 /// Switch active tab and panel.
-fn set_active_tab(els: &Elements, tab_name: &str) {
+pub(crate) fn set_active_tab(els: &Elements, tab_name: &str) {
     for tab in &els.tabs {
         dom::toggle_class(tab, "active", tab.get_attribute("data-tab").as_deref() == Some(tab_name));
     }
@@ -241,4 +281,11 @@ fn set_active_tab(els: &Elements, tab_name: &str) {
         let id = panel.id();
         dom::toggle_class(panel, "active", id == tab_name);
     }
+    if tab_name == "dashboard" {
+        let els2 = els.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            dashboard::on_refresh_dashboard(&els2).await;
+        });
+    }
+    approvals::set_polling(els, tab_name == "approvals");
 }
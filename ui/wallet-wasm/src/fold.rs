@@ -7,6 +7,10 @@
 //! **Must** match the JS `setWalletState()` exactly: classes on `.wallet-window`
 //! (`folded`, `half-folded`, `unfolded`) **and** on `#walletFolded` overlay
 //! (`closed`, `half`, `open`) **and** inline styles on `#walletApp`.
+//!
+//! Host pages read and drive the state through `kc_fold_state()` /
+//! `kc_set_fold_state(state)` and subscribe with `kc_on_fold_state_change(cb)`
+//! rather than observing those classes.
 
 use crate::dom::{self, Elements};
 use std::cell::RefCell;
@@ -22,11 +26,38 @@ pub enum FoldState {
     Unfolded,
 }
 
+impl FoldState {
+    /// Name used by the host page bindings (`FoldState` in the `.d.ts`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FoldState::Folded => "folded",
+            FoldState::Half => "half",
+            FoldState::Unfolded => "unfolded",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "folded" => Some(FoldState::Folded),
+            "half" => Some(FoldState::Half),
+            "unfolded" => Some(FoldState::Unfolded),
+            _ => None,
+        }
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const FOLD_STATE_TS: &str = r#"
+export type FoldState = "folded" | "half" | "unfolded";
+"#;
+
 thread_local! {
-    static FOLD_STATE: RefCell<FoldState> = RefCell::new(FoldState::Folded);
-    static AUTO_FOLD_TIMER: RefCell<Option<i32>> = RefCell::new(None);
-    static AUTO_CLOSE_TIMER: RefCell<Option<i32>> = RefCell::new(None);
-    static CLICK_TIMER: RefCell<Option<i32>> = RefCell::new(None);
+    static FOLD_STATE: RefCell<FoldState> = const { RefCell::new(FoldState::Folded) };
+    static AUTO_FOLD_TIMER: RefCell<Option<i32>> = const { RefCell::new(None) };
+    static AUTO_CLOSE_TIMER: RefCell<Option<i32>> = const { RefCell::new(None) };
+    static CLICK_TIMER: RefCell<Option<i32>> = const { RefCell::new(None) };
+    static FOLD_ELEMENTS: RefCell<Option<Elements>> = const { RefCell::new(None) };
+    static ON_CHANGE: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
 }
 
 pub fn current() -> FoldState {
@@ -115,7 +146,13 @@ pub fn reset_auto_fold_timer(els: &Elements) {
 /// Transition the wallet to a new fold state, updating DOM classes and inline
 /// styles to **exactly** match the JS `setWalletState()`.
 pub fn set_wallet_state(els: &Elements, target: FoldState) {
+    let previous = current();
     set_state(target);
+    FOLD_ELEMENTS.with(|e| {
+        if e.borrow().is_none() {
+            *e.borrow_mut() = Some(els.clone());
+        }
+    });
     let win = &els.wallet_window;
     let overlay = &els.wallet_folded;
     let app = &els.wallet_app;
@@ -169,6 +206,53 @@ pub fn set_wallet_state(els: &Elements, target: FoldState) {
         }
     }
     reset_auto_fold_timer(els);
+    if target != previous {
+        notify_change(target);
+    }
+}
+
+fn notify_change(state: FoldState) {
+    // Clone out of the cell so the callback may re-enter `kc_on_fold_state_change`.
+    let callback = ON_CHANGE.with(|c| c.borrow().clone());
+    if let Some(callback) = callback {
+        if let Err(err) = callback.call1(&JsValue::NULL, &JsValue::from_str(state.as_str())) {
+            web_sys::console::error_2(&"fold state callback failed:".into(), &err);
+        }
+    }
+}
+
+// ── Host page bindings ──
+
+/// Current fold state.
+#[wasm_bindgen(unchecked_return_type = "FoldState")]
+pub fn kc_fold_state() -> String {
+    current().as_str().to_owned()
+}
+
+/// Move the wallet to `state`, exactly as the fold toggle would.
+/// Fails on an unknown state or until the wallet UI has initialised.
+#[wasm_bindgen]
+pub fn kc_set_fold_state(
+    #[wasm_bindgen(unchecked_param_type = "FoldState")] state: &str,
+) -> Result<(), JsValue> {
+    let state = FoldState::parse(state)
+        .ok_or_else(|| JsValue::from_str(&format!("unknown fold state '{state}'")))?;
+    let els = FOLD_ELEMENTS
+        .with(|e| e.borrow().clone())
+        .ok_or_else(|| JsValue::from_str("wallet UI is not initialised yet"))?;
+    set_wallet_state(&els, state);
+    Ok(())
+}
+
+/// Register `callback(state)` to run after every fold state change, whether
+/// from the user, an auto-fold timer or `kc_set_fold_state`. Pass `null` or
+/// `undefined` to unsubscribe; a new callback replaces the previous one.
+#[wasm_bindgen]
+pub fn kc_on_fold_state_change(
+    #[wasm_bindgen(unchecked_param_type = "((state: FoldState) => void) | null | undefined")]
+    callback: Option<js_sys::Function>,
+) {
+    ON_CHANGE.with(|c| *c.borrow_mut() = callback);
 }
 
 /// Wire click / double-click behaviour on the fold toggle.
@@ -9,13 +9,11 @@ use crate::state;
 /// Load the icon manifest from `../../config/icon-manifest.json`.
 pub async fn load_manifest() {
     let url = "../../config/icon-manifest.json";
-    match api::fetch_text(url).await {
-        Ok(text) => {
-            if let Ok(m) = serde_json::from_str::<state::IconManifest>(&text) {
-                state::set_manifest(m);
-            }
+    // Non-critical, silently ignore fetch failures
+    if let Ok(text) = api::fetch_text(url).await {
+        if let Ok(m) = serde_json::from_str::<state::IconManifest>(&text) {
+            state::set_manifest(m);
         }
-        Err(_) => {} // Non-critical, silently ignore
     }
 }
 
@@ -4,7 +4,13 @@
 //! Modularised for extensibility: each concern lives in its own module.
 
 pub mod api;
+pub mod approvals;
+pub mod csv_import;
+pub mod dashboard;
+pub mod devices;
+pub mod diagnostics;
 pub mod dom;
+pub mod escrow;
 pub mod events;
 pub mod fold;
 pub mod icons;
@@ -12,6 +18,8 @@ pub mod platform;
 pub mod profile;
 pub mod state;
 pub mod theme;
+pub mod transfer_form;
+pub mod tx_detail;
 pub mod wallet_list;
 pub mod wallet_ops;
 
@@ -28,27 +36,29 @@ pub async fn start() -> Result<(), JsValue> {
 
 /// Main initialisation sequence (mirrors JS `main()`).
 async fn init() -> Result<(), JsValue> {
-    let els = dom::Elements::bind()?;
+    // Bind leniently so a partial page still gets a self-test report
+    // instead of a blank widget.
+    let (els, missing) = dom::Elements::bind_lenient();
+    if !missing.is_empty() {
+        web_sys::console::error_1(&format!("wallet UI: missing elements: {}", missing.join(", ")).into());
+    }
+    state::set_missing_bindings(missing);
 
     // Set initial fold state to folded (must be first, before anything else renders)
     fold::set_wallet_state(&els, fold::FoldState::Folded);
 
     // Restore skin
     let saved_skin = state::local_get("kc_wallet_skin").unwrap_or_default();
-    if !saved_skin.is_empty() {
-        if dom::has_option(&els.skin_select, &saved_skin) {
-            dom::set_select_value(&els.skin_select, &saved_skin);
-        }
+    if !saved_skin.is_empty() && dom::has_option(&els.skin_select, &saved_skin) {
+        dom::set_select_value(&els.skin_select, &saved_skin);
     }
     let skin = dom::get_select_value(&els.skin_select);
     theme::apply_skin(&els, &skin).await;
 
     // Restore form factor
     let saved_form = state::local_get("kc_wallet_form").unwrap_or_default();
-    if !saved_form.is_empty() {
-        if dom::has_option(&els.form_select, &saved_form) {
-            dom::set_select_value(&els.form_select, &saved_form);
-        }
+    if !saved_form.is_empty() && dom::has_option(&els.form_select, &saved_form) {
+        dom::set_select_value(&els.form_select, &saved_form);
     }
     let form = dom::get_select_value(&els.form_select);
     theme::apply_form(&els, &form);
@@ -72,5 +82,8 @@ async fn init() -> Result<(), JsValue> {
     // Load icon manifest
     icons::load_manifest().await;
 
+    // Startup self-test; opens the diagnostics panel if anything failed
+    diagnostics::run_and_render(&els).await;
+
     Ok(())
 }
@@ -3,6 +3,8 @@
 //! Uses `RefCell`-wrapped `thread_local!` storage (WASM is single-threaded).
 //! Extend `AppState` and the accessor helpers to add new state fields.
 
+use kc_api_types::KeyFingerprint;
+use kc_api_types::hex::to_hex;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 
@@ -17,8 +19,21 @@ pub struct WalletInfo {
     pub bound_user_id: Option<String>,
     #[serde(default)]
     pub public_key: Option<String>,
+    /// Word/digit fingerprint of `public_key` for out-of-band comparison.
+    #[serde(default)]
+    pub key_fingerprint: Option<KeyFingerprint>,
     #[serde(default)]
     pub label: Option<String>,
+    /// `false` for watch-only entries (no key held; sign/submit unavailable).
+    #[serde(default = "default_custodied")]
+    pub custodied: bool,
+    /// Key held outside KeyCortex; transfers must be signed on the device.
+    #[serde(default)]
+    pub external_key: bool,
+}
+
+fn default_custodied() -> bool {
+    true
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -75,6 +90,8 @@ pub struct AppState {
     pub manifest: Option<IconManifest>,
     pub themes: Option<std::collections::HashMap<String, ThemeTokens>>,
     pub last_challenge: Option<String>,
+    /// DOM elements `Elements::bind_lenient` could not find at startup.
+    pub missing_bindings: Vec<String>,
 }
 
 // ── Thread-local singleton ──
@@ -157,6 +174,14 @@ pub fn set_themes(t: std::collections::HashMap<String, ThemeTokens>) {
     with_mut(|s| s.themes = Some(t));
 }
 
+pub fn missing_bindings() -> Vec<String> {
+    with(|s| s.missing_bindings.clone())
+}
+
+pub fn set_missing_bindings(missing: Vec<String>) {
+    with_mut(|s| s.missing_bindings = missing);
+}
+
 // ── localStorage helpers ──
 
 fn storage() -> Option<web_sys::Storage> {
@@ -173,6 +198,22 @@ pub fn local_set(key: &str, value: &str) {
     }
 }
 
+/// Write, read back and remove a probe key. `local_set` ignores failures,
+/// so this is how to tell whether anything is being persisted.
+pub fn check_local_storage() -> Result<(), String> {
+    const PROBE_KEY: &str = "kc_selftest_probe";
+    let s = storage().ok_or("localStorage is unavailable (blocked or disabled by the browser)")?;
+    s.set_item(PROBE_KEY, "ok")
+        .map_err(|_| "localStorage rejected a write (storage full or private browsing)")?;
+    let read = s.get_item(PROBE_KEY).ok().flatten();
+    let _ = s.remove_item(PROBE_KEY);
+    if read.as_deref() == Some("ok") {
+        Ok(())
+    } else {
+        Err("localStorage did not return what was written".to_string())
+    }
+}
+
 /// Return a persistent device ID (generated once per browser, stored in localStorage).
 pub fn get_device_id() -> String {
     if let Some(id) = local_get("kc_device_id") {
@@ -188,3 +229,33 @@ pub fn get_device_id() -> String {
     local_set("kc_device_id", &id);
     id
 }
+
+/// This browser's device key, generated once from `crypto.getRandomValues`
+/// and stored next to the device ID. It signs the proofs that a transfer
+/// comes from this device.
+pub fn device_signing_key() -> Option<ed25519_dalek::SigningKey> {
+    let stored = local_get("kc_device_key").and_then(|hex| {
+        (hex.len() == 64)
+            .then(|| {
+                (0..32)
+                    .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+                    .collect::<Option<Vec<u8>>>()
+            })
+            .flatten()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+    });
+    let seed = match stored {
+        Some(seed) => seed,
+        None => {
+            let mut seed = [0u8; 32];
+            web_sys::window()?
+                .crypto()
+                .ok()?
+                .get_random_values_with_u8_array(&mut seed)
+                .ok()?;
+            local_set("kc_device_key", &to_hex(&seed));
+            seed
+        }
+    };
+    Some(ed25519_dalek::SigningKey::from_bytes(&seed))
+}
@@ -0,0 +1,175 @@
+//! Live nonce and fee display on the transfer form.
+//!
+//! Changing the From address refetches the nonce; changing the amount, asset,
+//! chain or recipient refetches the fee estimate from
+//! `POST /wallet/fee-estimate`. Both are debounced so typing does not fire a
+//! request per keystroke. The summary line shows amount, fee and the total
+//! debited from the sender.
+
+use std::cell::RefCell;
+
+use gloo_timers::callback::Timeout;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+use crate::api;
+use crate::dom::{self, Elements};
+
+const DEBOUNCE_MS: u32 = 400;
+
+thread_local! {
+    static NONCE_TIMER: RefCell<Option<Timeout>> = const { RefCell::new(None) };
+    static FEE_TIMER: RefCell<Option<Timeout>> = const { RefCell::new(None) };
+}
+
+/// Attach the debounced listeners. Call once after init.
+pub fn bind(els: &Elements) {
+    listen(
+        els.submit_from.as_ref(),
+        "input",
+        els,
+        schedule_nonce_refresh,
+    );
+    for field in [&els.submit_amount, &els.submit_to, &els.submit_chain] {
+        listen(field.as_ref(), "input", els, schedule_fee_refresh);
+    }
+    listen(
+        els.submit_asset.as_ref(),
+        "change",
+        els,
+        schedule_fee_refresh,
+    );
+}
+
+fn listen(target: &web_sys::EventTarget, event: &str, els: &Elements, handler: fn(&Elements)) {
+    let els = els.clone();
+    let cb = Closure::wrap(Box::new(move |_: web_sys::Event| handler(&els)) as Box<dyn FnMut(_)>);
+    target
+        .add_event_listener_with_callback(event, cb.as_ref().unchecked_ref())
+        .unwrap();
+    cb.forget();
+}
+
+/// Refetch the nonce once the From address has settled. Also called when
+/// the active wallet fills in the field programmatically.
+pub fn schedule_nonce_refresh(els: &Elements) {
+    let els = els.clone();
+    let timer = Timeout::new(DEBOUNCE_MS, move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            refresh_nonce(&els).await;
+            refresh_fee(&els).await;
+        });
+    });
+    // Replacing the pending timer drops, and so cancels, the previous one.
+    NONCE_TIMER.with(|t| *t.borrow_mut() = Some(timer));
+}
+
+pub fn schedule_fee_refresh(els: &Elements) {
+    let els = els.clone();
+    let timer = Timeout::new(DEBOUNCE_MS, move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            refresh_fee(&els).await;
+        });
+    });
+    FEE_TIMER.with(|t| *t.borrow_mut() = Some(timer));
+}
+
+fn chain(els: &Elements) -> String {
+    let chain = dom::get_input_value(&els.submit_chain);
+    if chain.is_empty() {
+        "flowcortex-l1".to_string()
+    } else {
+        chain
+    }
+}
+
+/// GET /wallet/nonce
+async fn refresh_nonce(els: &Elements) {
+    let addr = dom::get_input_value(&els.submit_from);
+    if addr.is_empty() {
+        dom::set_text(&els.nonce_display, "\u{2014}");
+        els.submit_nonce.set_value("");
+        return;
+    }
+    let query = format!(
+        "wallet_address={}&chain={}",
+        js_sys::encode_uri_component(&addr),
+        js_sys::encode_uri_component(&chain(els)),
+    );
+
+    match api::request(&format!("/wallet/nonce?{}", query), "GET", None).await {
+        Ok(result) => {
+            // A late response for an address the user has since changed is stale.
+            if dom::get_input_value(&els.submit_from) != addr {
+                return;
+            }
+            let last = result
+                .get("last_nonce")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            let next = result
+                .get("next_nonce")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            dom::set_text(
+                &els.nonce_display,
+                &format!("nonce last: {} · next: {}", last, next),
+            );
+            els.submit_nonce.set_value(&next.to_string());
+        }
+        Err(e) => dom::set_text(&els.nonce_display, &format!("nonce unavailable: {}", e)),
+    }
+}
+
+/// POST /wallet/fee-estimate
+async fn refresh_fee(els: &Elements) {
+    let amount = dom::get_input_value(&els.submit_amount);
+    let asset = dom::get_select_value(&els.submit_asset);
+    let Ok(amount_units) = amount.parse::<u128>() else {
+        dom::set_text(
+            &els.transfer_summary,
+            if amount.is_empty() {
+                "\u{2014}"
+            } else {
+                "amount must be a whole number of base units"
+            },
+        );
+        return;
+    };
+
+    let body = serde_json::json!({
+        "from": dom::get_input_value(&els.submit_from),
+        "to": dom::get_input_value(&els.submit_to),
+        "amount": amount,
+        "asset": asset,
+        "chain": chain(els),
+    });
+    let summary = match api::request("/wallet/fee-estimate", "POST", Some(body.to_string())).await {
+        Ok(result) => {
+            let fee = result["fee"].as_str().and_then(|f| f.parse::<u128>().ok());
+            let fee_asset = result["fee_asset"].as_str().unwrap_or(&asset).to_string();
+            match fee {
+                Some(fee) if fee_asset == asset => format!(
+                    "amount {} + fee {} = total debit {} {}",
+                    amount_units,
+                    fee,
+                    amount_units.saturating_add(fee),
+                    asset
+                ),
+                Some(fee) => format!(
+                    "total debit {} {} + fee {} {}",
+                    amount_units, asset, fee, fee_asset
+                ),
+                None => format!("total debit {} {} (fee unknown)", amount_units, asset),
+            }
+        }
+        Err(_) => format!(
+            "total debit {} {} (fee estimate unavailable)",
+            amount_units, asset
+        ),
+    };
+    // Skip a stale response if the amount changed while the request was in flight.
+    if dom::get_input_value(&els.submit_amount) == amount {
+        dom::set_text(&els.transfer_summary, &summary);
+    }
+}
@@ -0,0 +1,228 @@
+//! Transaction detail card for the Tx Lookup panel.
+//!
+//! Renders `GET /wallet/tx/{tx_hash}` as a card: status timeline, block
+//! height and confirmations, transfer details, a "view on explorer" link and
+//! the raw JSON behind an expander. The explorer link comes from the chain's
+//! `explorer_tx_url` template, configured server-side through `/ops/chains`.
+//! Addresses and the block height link out through the address and block
+//! templates advertised by `GET /chain/config`. "Read Memo" decrypts the
+//! note attached to the transfer with the recipient wallet's key.
+
+use wasm_bindgen::JsValue;
+
+use crate::api;
+use crate::dom::{self, Elements};
+
+/// GET /wallet/tx/:hash
+pub async fn on_fetch_tx_status(els: &Elements) {
+    let tx_hash = dom::get_input_value(&els.tx_hash);
+    if tx_hash.is_empty() {
+        api::set_result_error(&els.history_result, "tx hash required");
+        return;
+    }
+    let path = format!("/wallet/tx/{}", js_sys::encode_uri_component(&tx_hash));
+
+    match api::request(&path, "GET", None).await {
+        Ok(result) => {
+            let templates = chain_explorer(result["chain"].as_str().unwrap_or_default()).await;
+            render(els, &result, &templates);
+            dom::remove_class(&els.history_result, "error");
+            dom::set_text(
+                &els.history_result,
+                &format!("Fetched {}", format_time(js_sys::Date::now())),
+            );
+        }
+        Err(e) => {
+            dom::set_inner_html(&els.tx_detail, "");
+            api::set_result_error(&els.history_result, &e);
+        }
+    }
+}
+
+/// POST /wallet/{to}/decrypt — open the memo attached to the looked-up
+/// transfer with the recipient wallet's key.
+pub async fn on_read_memo(els: &Elements) {
+    let tx_hash = dom::get_input_value(&els.tx_hash);
+    if tx_hash.is_empty() {
+        api::set_result_error(&els.history_result, "tx hash required");
+        return;
+    }
+    let path = format!("/wallet/tx/{}", js_sys::encode_uri_component(&tx_hash));
+    let recipient = match api::request(&path, "GET", None).await {
+        Ok(tx) => tx["to"].as_str().unwrap_or_default().to_string(),
+        Err(e) => {
+            api::set_result_error(&els.history_result, &e);
+            return;
+        }
+    };
+
+    let body = serde_json::json!({ "tx_hash": tx_hash });
+    let path = format!("/wallet/{}/decrypt", js_sys::encode_uri_component(&recipient));
+    match api::request(&path, "POST", Some(body.to_string())).await {
+        Ok(result) => {
+            dom::remove_class(&els.history_result, "error");
+            dom::set_text(
+                &els.history_result,
+                &format!("Memo: {}", result["memo"].as_str().unwrap_or_default()),
+            );
+        }
+        Err(e) => api::set_result_error(&els.history_result, &e),
+    }
+}
+
+/// Explorer templates for `chain`, or `Null` when `/chain/config` describes
+/// another chain or cannot be fetched.
+async fn chain_explorer(chain: &str) -> serde_json::Value {
+    match api::request("/chain/config", "GET", None).await {
+        Ok(config) if config["chain_slug"].as_str() == Some(chain) => config["explorer"].clone(),
+        _ => serde_json::Value::Null,
+    }
+}
+
+/// `text` wrapped in a link built from `template`, or plain when unset.
+fn deep_link(template: Option<&str>, placeholder: &str, value: &str, text: &str) -> String {
+    match template {
+        Some(template) if !value.is_empty() => format!(
+            r#"<a href="{}" target="_blank" rel="noopener noreferrer">{}</a>"#,
+            escape(
+                &template.replace(
+                    placeholder,
+                    &js_sys::encode_uri_component(value)
+                        .as_string()
+                        .unwrap_or_default()
+                )
+            ),
+            text
+        ),
+        _ => text.to_string(),
+    }
+}
+
+fn render(els: &Elements, tx: &serde_json::Value, templates: &serde_json::Value) {
+    let text = |key: &str| escape(tx[key].as_str().unwrap_or("—"));
+    let status = tx["status"].as_str().unwrap_or("unknown");
+    let chain = tx["chain"].as_str().unwrap_or_default();
+
+    let explorer = match tx["explorer_url"].as_str() {
+        Some(url) => format!(
+            r#"<a class="tx-explorer-link" href="{}" target="_blank" rel="noopener noreferrer">View on explorer ↗</a>"#,
+            escape(url)
+        ),
+        None => format!(
+            r#"<span class="tx-explorer-missing">No explorer configured for {}</span>"#,
+            escape(chain)
+        ),
+    };
+
+    let optional_u64 = |key: &str| {
+        tx[key]
+            .as_u64()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "—".to_string())
+    };
+    let address = |key: &str| {
+        deep_link(
+            templates["address_url"].as_str(),
+            "{address}",
+            tx[key].as_str().unwrap_or_default(),
+            &text(key),
+        )
+    };
+    let block_height = deep_link(
+        templates["block_url"].as_str(),
+        "{height}",
+        &tx["block_height"]
+            .as_u64()
+            .map(|h| h.to_string())
+            .unwrap_or_default(),
+        &optional_u64("block_height"),
+    );
+    let facts = [
+        ("Chain", escape(chain)),
+        ("From", address("from")),
+        ("To", address("to")),
+        ("Amount", format!("{} {}", text("amount"), text("asset"))),
+        ("Block height", block_height),
+        ("Confirmations", optional_u64("confirmations")),
+        (
+            "Submitted",
+            format_time(tx["submitted_at_epoch_ms"].as_f64().unwrap_or(0.0)),
+        ),
+    ];
+    let facts_html: String = facts
+        .iter()
+        .map(|(label, value)| format!("<dt>{}</dt><dd>{}</dd>", label, value))
+        .collect();
+
+    let raw = serde_json::to_string_pretty(tx).unwrap_or_default();
+    let html = format!(
+        r#"<div class="tx-card">
+  <div class="tx-card-header">
+    <span class="tx-status tx-status--{status_class}">{status}</span>
+    <code class="tx-hash">{hash}</code>
+    {explorer}
+  </div>
+  <ol class="tx-timeline">{timeline}</ol>
+  <dl class="tx-facts">{facts}</dl>
+  <details class="tx-raw"><summary>Raw JSON</summary><pre>{raw}</pre></details>
+</div>"#,
+        status_class = status_class(status),
+        status = escape(status),
+        hash = text("tx_hash"),
+        explorer = explorer,
+        timeline = timeline(tx, status),
+        facts = facts_html,
+        raw = escape(&raw),
+    );
+    dom::set_inner_html(&els.tx_detail, &html);
+}
+
+/// One `<li>` per recorded status, oldest first. The last step is the
+/// current one.
+fn timeline(tx: &serde_json::Value, current: &str) -> String {
+    let steps: Vec<(&str, f64)> = tx["status_history"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|change| Some((change["status"].as_str()?, change["at_epoch_ms"].as_f64()?)))
+        .collect();
+    if steps.is_empty() {
+        return format!(
+            r#"<li class="tx-step tx-step--current"><span class="tx-step-status">{}</span></li>"#,
+            escape(current)
+        );
+    }
+    let last = steps.len() - 1;
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, (status, at))| {
+            format!(
+                r#"<li class="tx-step{}"><span class="tx-step-status">{}</span><span class="tx-step-time">{}</span></li>"#,
+                if i == last { " tx-step--current" } else { "" },
+                escape(status),
+                format_time(*at),
+            )
+        })
+        .collect()
+}
+
+fn status_class(status: &str) -> &'static str {
+    match status {
+        "confirmed" | "escrow_released" => "ok",
+        "rejected" | "failed" | "escrow_cancelled" | "escrow_expired" => "bad",
+        _ => "pending",
+    }
+}
+
+fn format_time(epoch_ms: f64) -> String {
+    let at = js_sys::Date::new(&JsValue::from_f64(epoch_ms));
+    String::from(at.to_locale_string("default", &JsValue::UNDEFINED))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
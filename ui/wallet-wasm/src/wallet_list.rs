@@ -79,6 +79,15 @@ pub fn render_wallet_list(els: &Elements) {
             Some(u) if !u.is_empty() => format!(r#"<span class="wc-user">{}</span>"#, u),
             _ => String::new(),
         };
+        let watch_label = if w.custodied {
+            String::new()
+        } else if w.external_key {
+            r#"<span class="wc-watch wc-external" title="Key held externally; submit needs a client-side signature">external key</span>"#
+                .to_string()
+        } else {
+            r#"<span class="wc-watch" title="No key held; sign and submit unavailable">watch-only</span>"#
+                .to_string()
+        };
         let profile_name = profile::get_profile_name(&active_profile);
         let profile_label = if is_assigned {
             format!(
@@ -98,6 +107,13 @@ pub fn render_wallet_list(els: &Elements) {
             }
             _ => String::new(),
         };
+        let fingerprint_html = match &w.key_fingerprint {
+            Some(fp) => format!(
+                r#"<div class="wc-fingerprint" title="Verification code: {}">{}</div>"#,
+                fp.code, fp.words
+            ),
+            None => String::new(),
+        };
         let assign_btn = if is_assigned {
             format!(
                 r#"<button class="wc-unassign-btn icon-btn" data-addr="{}" title="Remove from profile">&minus;</button>"#,
@@ -114,7 +130,8 @@ pub fn render_wallet_list(els: &Elements) {
             r#"
             {}
             <div class="wc-address" title="{}">{}</div>
-            <div class="wc-meta">{} {} {}</div>
+            <div class="wc-meta">{} {} {} {}</div>
+            {}
             {}
             <div class="wc-actions">
               <button class="wc-select-btn secondary" data-addr="{}">Use</button>
@@ -126,9 +143,11 @@ pub fn render_wallet_list(els: &Elements) {
             w.wallet_address,
             short_addr,
             w.chain,
+            watch_label,
             user_label,
             profile_label,
             pk_html,
+            fingerprint_html,
             w.wallet_address,
             w.wallet_address,
             assign_btn,
@@ -313,6 +332,7 @@ pub fn select_active_wallet(els: &Elements, addr: &str) {
     els.submit_from.set_value(addr);
     els.fd_wallet_address.set_value(addr);
     els.pc_wallet_address.set_value(addr);
+    crate::transfer_form::schedule_nonce_refresh(els);
 
     update_half_fold_info(els);
     render_wallet_list(els);
@@ -3,11 +3,13 @@
 //! Each function corresponds to a backend API call.
 //! Extend by adding new operations and wiring them in `events.rs`.
 
+use kc_api_types::ErrorCode;
 use wasm_bindgen::JsCast;
 
 use crate::api;
 use crate::dom::{self, Elements};
 use crate::state;
+use crate::transfer_form;
 use crate::wallet_list;
 
 /// POST /wallet/create
@@ -67,6 +69,67 @@ pub async fn on_restore_wallet(els: &Elements) {
     }
 }
 
+/// POST /wallet/watch
+pub async fn on_watch_wallet(els: &Elements) {
+    let addr = dom::get_input_value(&els.watch_address_input);
+    if addr.is_empty() {
+        api::set_result_error(&els.create_result, "address required to watch");
+        return;
+    }
+    let label = dom::get_input_value(&els.wallet_label_input);
+
+    let body = serde_json::json!({
+        "wallet_address": addr,
+        "chain": "flowcortex-l1",
+        "label": if label.is_empty() { None } else { Some(label) },
+        "device_id": state::get_device_id(),
+    });
+
+    match api::request("/wallet/watch", "POST", Some(body.to_string())).await {
+        Ok(result) => {
+            api::set_result(&els.create_result, &result);
+            if let Some(profile) = state::active_profile() {
+                crate::profile::assign_wallet_to_profile(&addr, &profile);
+            }
+            els.watch_address_input.set_value("");
+            wallet_list::load_wallet_list(els).await;
+        }
+        Err(e) => api::set_result_error(&els.create_result, &e),
+    }
+}
+
+/// POST /wallet/import-public
+pub async fn on_import_public_key(els: &Elements) {
+    let public_key = dom::get_input_value(&els.import_public_key_input);
+    if public_key.is_empty() {
+        api::set_result_error(&els.create_result, "public key (hex) required to import");
+        return;
+    }
+    let label = dom::get_input_value(&els.wallet_label_input);
+
+    let body = serde_json::json!({
+        "public_key": public_key,
+        "key_type": "ed25519",
+        "chain": "flowcortex-l1",
+        "label": if label.is_empty() { None } else { Some(label) },
+        "device_id": state::get_device_id(),
+    });
+
+    match api::request("/wallet/import-public", "POST", Some(body.to_string())).await {
+        Ok(result) => {
+            api::set_result(&els.create_result, &result);
+            if let (Some(addr), Some(profile)) =
+                (result["wallet_address"].as_str(), state::active_profile())
+            {
+                crate::profile::assign_wallet_to_profile(addr, &profile);
+            }
+            els.import_public_key_input.set_value("");
+            wallet_list::load_wallet_list(els).await;
+        }
+        Err(e) => api::set_result_error(&els.create_result, &e),
+    }
+}
+
 /// POST /wallet/rename (prompt for new name)
 pub async fn on_rename_wallet(els: &Elements, wallet_address: &str) {
     let new_name = dom::window()
@@ -128,6 +191,20 @@ pub async fn on_fetch_balance(els: &Elements) {
     }
 }
 
+/// GET /wallet/portfolio — every asset on every chain, into the balance result.
+pub async fn on_fetch_portfolio(els: &Elements) {
+    let addr = dom::get_input_value(&els.balance_wallet_address);
+    let path = format!(
+        "/wallet/portfolio?wallet_address={}",
+        js_sys::encode_uri_component(&addr)
+    );
+
+    match api::request(&path, "GET", None).await {
+        Ok(result) => api::set_result(&els.balance_result, &result),
+        Err(e) => api::set_result_error(&els.balance_result, &e),
+    }
+}
+
 /// POST /wallet/sign
 pub async fn on_sign_payload(els: &Elements) {
     let addr = dom::get_input_value(&els.sign_wallet_address);
@@ -146,51 +223,17 @@ pub async fn on_sign_payload(els: &Elements) {
     }
 }
 
-/// GET /wallet/nonce
-pub async fn on_fetch_nonce(els: &Elements) {
-    let addr = dom::get_input_value(&els.submit_from);
-    if addr.is_empty() {
-        els.nonce_display
-            .set_text_content(Some("set 'From' address first"));
-        return;
-    }
-    let query = format!(
-        "wallet_address={}",
-        js_sys::encode_uri_component(&addr)
-    );
-
-    match api::request(&format!("/wallet/nonce?{}", query), "GET", None).await {
-        Ok(result) => {
-            let last = result
-                .get("last_nonce")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let next = result
-                .get("next_nonce")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            els.nonce_display
-                .set_text_content(Some(&format!("last: {} · next: {}", last, next)));
-            els.submit_nonce.set_value(&next.to_string());
-        }
-        Err(e) => {
-            els.nonce_display.set_text_content(Some("\u{2014}"));
-            api::set_result_error(&els.submit_result, &e);
-        }
-    }
-}
-
 /// POST /wallet/submit
 pub async fn on_submit_tx(els: &Elements) {
     let nonce_str = dom::get_input_value(&els.submit_nonce);
     let nonce: i64 = nonce_str.parse().unwrap_or(0);
     if nonce < 1 {
-        api::set_result_error(&els.submit_result, "nonce required (use Get Nonce)");
+        api::set_result_error(&els.submit_result, "nonce required; enter the From address to fetch it");
         return;
     }
 
     let chain_val = dom::get_input_value(&els.submit_chain);
-    let body = serde_json::json!({
+    let mut body = serde_json::json!({
         "from": dom::get_input_value(&els.submit_from),
         "to": dom::get_input_value(&els.submit_to),
         "amount": dom::get_input_value(&els.submit_amount),
@@ -198,27 +241,36 @@ pub async fn on_submit_tx(els: &Elements) {
         "chain": if chain_val.is_empty() { "flowcortex-l1".to_string() } else { chain_val },
         "nonce": nonce,
     });
+    let memo = dom::get_textarea_value(&els.submit_memo);
+    if !memo.is_empty() {
+        body["memo"] = serde_json::Value::String(memo);
+    }
 
-    match api::request("/wallet/submit", "POST", Some(body.to_string())).await {
+    let proof = crate::devices::proof_headers(
+        els,
+        body["from"].as_str().unwrap_or_default(),
+        body["to"].as_str().unwrap_or_default(),
+        body["amount"].as_str().unwrap_or_default(),
+    )
+    .await;
+    let headers: Vec<(&str, &str)> = proof.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    match api::request_api("/wallet/submit", "POST", Some(body.to_string()), &headers).await {
         Ok(result) => {
             api::set_result(&els.submit_result, &result);
             // Populate tx hash for easy lookup
             if let Some(hash) = result.get("tx_hash").and_then(|v| v.as_str()) {
                 els.tx_hash.set_value(hash);
             }
+            els.submit_memo.set_value("");
         }
-        Err(e) => api::set_result_error(&els.submit_result, &e),
-    }
-}
-
-/// GET /wallet/tx/:hash
-pub async fn on_fetch_tx_status(els: &Elements) {
-    let tx_hash = dom::get_input_value(&els.tx_hash);
-    let path = format!("/wallet/tx/{}", js_sys::encode_uri_component(&tx_hash));
-
-    match api::request(&path, "GET", None).await {
-        Ok(result) => api::set_result(&els.history_result, &result),
-        Err(e) => api::set_result_error(&els.history_result, &e),
+        Err(e) if e.api_error().is_some_and(|err| err.code == ErrorCode::NonceConflict) => {
+            api::set_result_error(
+                &els.submit_result,
+                &format!("{}\n\nFetching the next nonce; submit again once it updates.", e),
+            );
+            transfer_form::schedule_nonce_refresh(els);
+        }
+        Err(e) => api::set_result_error(&els.submit_result, &e.to_string()),
     }
 }
 